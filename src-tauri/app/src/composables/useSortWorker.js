@@ -33,6 +33,10 @@ function getWorker() {
               aVal = a.size || 0;
               bVal = b.size || 0;
               return sortDirection === 'asc' ? aVal - bVal : bVal - aVal;
+            case 'items':
+              aVal = (a.fileCount || 0) + (a.dirCount || 0);
+              bVal = (b.fileCount || 0) + (b.dirCount || 0);
+              return sortDirection === 'asc' ? aVal - bVal : bVal - aVal;
             default:
               return 0;
           }
@@ -176,6 +180,10 @@ export function useSortWorker() {
           aVal = a.size || 0
           bVal = b.size || 0
           return sortDirection === 'asc' ? aVal - bVal : bVal - aVal
+        case 'items':
+          aVal = (a.fileCount || 0) + (a.dirCount || 0)
+          bVal = (b.fileCount || 0) + (b.dirCount || 0)
+          return sortDirection === 'asc' ? aVal - bVal : bVal - aVal
         default:
           return 0
       }
@@ -1,5 +1,14 @@
 import { ref } from 'vue'
 
+// 参数校验失败时后端返回 { field, code, message }（见 types/flashdir.d.ts 的 ValidationError）；
+// 保留结构化字段供表单定位，同时让 '失败: ' + error 这类拼接仍显示可读文本
+function toCommandError(error) {
+  if (error && typeof error === 'object' && 'field' in error && 'message' in error) {
+    return { ...error, toString: () => `${error.field}: ${error.message}` }
+  }
+  return error
+}
+
 export function useTauri() {
   const invoke = ref(window.__TAURI__?.core?.invoke)
 
@@ -7,7 +16,29 @@ export function useTauri() {
     if (!invoke.value) {
       throw new Error('Tauri invoke API not available')
     }
-    return await invoke.value(cmd, args)
+    try {
+      return await invoke.value(cmd, args)
+    } catch (error) {
+      throw toCommandError(error)
+    }
+  }
+
+  // 可取消的维护操作（clear_disk_cache / export_selected / save_snapshot）：
+  // 生成操作 ID 随参数传给后端，onProgress 接收该操作的 operation-progress 事件，
+  // 返回 { result, cancel }，result 在操作结束（含取消）后 settle
+  const invokeOperation = (cmd, args = {}, onProgress) => {
+    const operationId = crypto.randomUUID()
+    const listening = onProgress
+      ? window.__TAURI__?.event?.listen('operation-progress', ({ payload }) => {
+          if (payload.id === operationId) onProgress(payload)
+        })
+      : null
+    const result = safeInvoke(cmd, { ...args, operationId }).finally(async () => {
+      const unlisten = await listening
+      unlisten?.()
+    })
+    const cancel = () => safeInvoke('cancel_operation', { operationId })
+    return { result, cancel }
   }
 
   // Tauri v2 对话框 API
@@ -31,6 +62,7 @@ export function useTauri() {
 
   return {
     invoke: safeInvoke,
+    invokeOperation,
     openDialog
   }
 }
@@ -0,0 +1,1753 @@
+// 由 cargo run -p flashdir-core --example gen_schema 生成，请勿手动修改
+
+export interface AgeBucket {
+  fileCount: number;
+  label: string;
+  /** 上界（不含）；最后一段为 None */
+  maxDays?: number | null;
+  minDays: number;
+  percentOfSize: number;
+  totalSize: number;
+  totalSizeFormatted: string;
+}
+
+export interface AppDataFile {
+  /** 旧内容可归档到外部存储 */
+  archivable: boolean;
+  /** 应用内压缩 / VACUUM 可回收已删除内容占用的空间 */
+  compactable: boolean;
+  /** 处理建议 */
+  hint: string;
+  kind: AppDataKind;
+  modified: number;
+  path: string;
+  size: number;
+  sizeFormatted: string;
+}
+
+export interface AppDataGroup {
+  /** 应用名；无法推断时为 "其他" */
+  app: string;
+  /** 按大小降序 */
+  files: AppDataFile[];
+  totalSize: number;
+  totalSizeFormatted: string;
+}
+
+export type AppDataKind = "mbox" | "sqlite" | "levelDb" | "pst" | "ost";
+
+export interface AppDataReport {
+  /** 按总大小降序 */
+  groups: AppDataGroup[];
+  path: string;
+  totalSize: number;
+  totalSizeFormatted: string;
+}
+
+/** 生成报告时使用的外观（见 report_style） */
+export interface AppearanceSettings {
+  /** BCP 47 语言标记（如 "zh-CN"、"en-US"、"ar-EG"），决定日期 / 数字格式与书写方向； None 为跟随系统 */
+  locale?: string | null;
+  theme?: ThemePreference;
+}
+
+export interface AppliedMigration {
+  description: string;
+  version: number;
+}
+
+/** 压缩前的估计 */
+export interface ArchiveEstimate {
+  dirCount: number;
+  /** estimated_size / input_size（空输入为 1） */
+  estimatedRatio: number;
+  estimatedSize: number;
+  estimatedSizeFormatted: string;
+  fileCount: number;
+  inputSize: number;
+  inputSizeFormatted: string;
+}
+
+export type ArchiveFormat = "zip" | "tarZst";
+
+export interface ArchiveResult {
+  archiveSize: number;
+  archiveSizeFormatted: string;
+  dest: string;
+  /** 压缩前的估计（与实际结果对照） */
+  estimate: ArchiveEstimate;
+  /** 写入归档的文件数 */
+  fileCount: number;
+  format: ArchiveFormat;
+  inputSize: number;
+  /** 无法读取而跳过的条目 */
+  skipped: BulkFailure[];
+}
+
+/** 后端选择 */
+export type BackendKind = "auto" | "mft" | "native" | "std";
+
+/** 批量操作结果 */
+export interface BulkActionResult {
+  affectedBytes: number;
+  affectedBytesFormatted: string;
+  failed: BulkFailure[];
+  succeeded: number;
+}
+
+/** 批量操作中单项失败 */
+export interface BulkFailure {
+  error: string;
+  path: string;
+}
+
+/** 一条磁盘缓存的概要 */
+export interface CacheEntryInfo {
+  /** 距 scanned_at 的秒数 */
+  ageSecs: number;
+  /** 展示给用户的目录路径（保留原始大小写，不含修饰后缀） */
+  displayPath: string;
+  /** 作为新鲜缓存命中的次数 */
+  hitCount: number;
+  /** 从其他机器导入的结果：来源主机 */
+  importedFrom?: string | null;
+  itemCount: number;
+  /** 最近一次写入或命中的时间，Unix 秒 */
+  lastAccessed: number;
+  /** 缓存 key（规范化的目录路径，启用排除预设 / 包含模式时带 `|exclude=` / `|include=` 后缀； Windows 下为小写），删除 / 导出时原样传回 */
+  path: string;
+  /** 扫描（或增量确认未变化）的时间，Unix 秒 */
+  scannedAt: number;
+  /** 编码后的大小（字节） */
+  size: number;
+  sizeFormatted: string;
+}
+
+export interface CacheExportResult {
+  dest: string;
+  /** 导出的扫描结果数 */
+  entries: number;
+  fileSize: number;
+  fileSizeFormatted: string;
+}
+
+export interface CacheImportResult {
+  /** 导出时间，Unix 秒 */
+  exportedAt: number;
+  /** 来源主机 */
+  host: string;
+  /** 已导入的扫描根目录（可直接按路径打开） */
+  imported: string[];
+  /** 本机已有缓存而跳过的目录 */
+  skipped: string[];
+}
+
+/** 一个缓存位置 */
+export interface CacheLocation {
+  fileCount: number;
+  /** 说明，如 "registry 下载缓存"、"target 构建产物" */
+  label: string;
+  path: string;
+  /** cargo target 所属项目目录（run_safe_clean 的 project 参数） */
+  project?: string | null;
+  /** 清理命令可回收（否则仅供参考，如 cargo 的 git 索引） */
+  reclaimable: boolean;
+  size: number;
+  sizeFormatted: string;
+}
+
+/** 导出文件的清单 */
+export interface CacheManifest {
+  /** 导出时的 FlashDir 版本 */
+  appVersion: string;
+  entries: CacheManifestEntry[];
+  /** 导出时间，Unix 秒 */
+  exportedAt: number;
+  format: string;
+  /** 来源主机名 */
+  host: string;
+  version: number;
+}
+
+export interface CacheManifestEntry {
+  /** 包内的结果文件名 */
+  file: string;
+  itemCount: number;
+  /** 扫描根目录（展示形式） */
+  path: string;
+  /** 扫描时间，Unix 秒 */
+  scannedAt: number;
+  totalSize: number;
+}
+
+/** 扫描结果缓存的保留策略：内存中的会话与磁盘缓存。 修改后经 scan::apply_cache_policy 立即作用于已有的缓存 */
+export interface CacheSettings {
+  /** 空闲页（删除缓存留下的空间）达到此值（MB）时才自动压缩 */
+  compactMinFreeMb?: number;
+  /** 空闲时自动压缩磁盘缓存数据库（见 DiskCache::compact），空闲条件同 idle，但不要求开启空闲扫描 */
+  compactWhenIdle?: boolean;
+  /** 无论内存占用如何都保留的最近查看的会话数 */
+  keepSessions?: number;
+  /** 磁盘缓存中扫描结果的总大小上限（MB），超出时淘汰最久未访问的条目 */
+  maxDiskMb?: number;
+  /** 内存缓存保留的扫描结果数上限，超出时关闭最久未查看的会话（低内存的机器可调小） */
+  memoryMaxEntries?: number;
+  /** 内存缓存中扫描结果的估计大小上限（MB） */
+  memoryMaxMb?: number;
+  /** 从不缓存的目录（含其子目录）：扫描结果不写入磁盘缓存，再次扫描总是完整遍历； 本次打开的结果仍保留在内存中供浏览 */
+  neverCache?: string[];
+  /** 进程常驻内存（RSS）超过此值（MB）时，按最久未查看的顺序关闭内存中的会话， 再次打开时透明地从磁盘缓存读回。None 为物理内存的 1/4 */
+  rssLimitMb?: number | null;
+  /** 缓存的有效期（天）：超过此时间未被访问的磁盘缓存条目被删除， 超过此时间的内存缓存不再作为命中返回 */
+  ttlDays?: number;
+  /** 命中缓存前抽样检查的子目录数（见 dir_digest）：任一子目录在扫描后有增删即按未命中处理； 0 为只校验根目录 mtime */
+  validationSamples?: number;
+}
+
+/** 扫描结果的来源 */
+export type CacheSource = "memory" | "disk" | "partial" | "fresh";
+
+export interface CacheStats {
+  /** 命中时解码扫描结果的平均耗时（毫秒） */
+  avgDeserializeMs: number;
+  /** 命中时从缓存读出的编码数据累计字节数 */
+  bytesServed: number;
+  entryCount: number;
+  /** hits / (hits + misses)，尚无查询时为 0 */
+  hitRate: number;
+  /** 累计命中次数（跨会话） */
+  hits: number;
+  maxSizeMb: number;
+  /** 累计未命中次数：无缓存、目录已变化、已过期或解码失败 */
+  misses: number;
+  oldestEntryTimestamp?: number | null;
+  totalSizeBytes: number;
+  totalSizeMb: number;
+}
+
+/** 多级缓存在本次扫描中的使用情况，让用户判断结果是否最新 */
+export interface CacheUsage {
+  /** 失效的缓存条目数：增量更新中被删除 / 改动的条目，或完整遍历时丢弃的旧结果条目 */
+  invalidatedEntries: number;
+  /** 直接沿用的缓存条目数 */
+  reusedEntries: number;
+  source: CacheSource;
+}
+
+/** 单个类别的聚合统计（仅统计文件） */
+export interface CategoryStat {
+  category: FileCategory;
+  fileCount: number;
+  percentOfTotal: number;
+  totalSize: number;
+  totalSizeFormatted: string;
+}
+
+export interface ChildCounts {
+  dirs: number;
+  /** 直接子项中的文件数与目录数 */
+  files: number;
+  totalDirs?: number | null;
+  /** 子树中的文件数与目录数（递归，取自扫描缓存；未扫描时为 None） */
+  totalFiles?: number | null;
+}
+
+/** 清理命令的执行结果 */
+export interface CleanOutput {
+  command: string;
+  durationMs: number;
+  exitCode?: number | null;
+  /** 清理前后缓存位置的大小差 */
+  freedBytes: number;
+  freedBytesFormatted: string;
+  stderr: string;
+  stdout: string;
+  success: boolean;
+  tool: PackageTool;
+}
+
+/** 复制的内容 */
+export type ClipboardContent = "summary" | "selection";
+
+export type ClipboardFormat = "text" | "markdown" | "tsv";
+
+export interface ClipboardResult {
+  /** 写入的字符数 */
+  chars: number;
+  content: ClipboardContent;
+  format: ClipboardFormat;
+  /** 写入的条目行数（摘要为 0） */
+  rows: number;
+  /** 条目超过 MAX_CLIPBOARD_ROWS 被截断 */
+  truncated: boolean;
+}
+
+/** cold 文件 Top 项 */
+export interface ColdFile {
+  daysSinceUse: number;
+  /** 最后使用时间（Unix 秒） */
+  lastUsed: number;
+  path: string;
+  size: number;
+  sizeFormatted: string;
+}
+
+/** 一次压缩的结果 */
+export interface CompactResult {
+  afterBytes: number;
+  /** 压缩前数据库文件的大小（字节） */
+  beforeBytes: number;
+  /** 取消时为 false，已回收的部分保留 */
+  completed: boolean;
+  durationMs: number;
+  /** 旧数据库首次压缩时整体 VACUUM（同时切换为增量回收），之后只做增量回收 */
+  fullVacuum: boolean;
+  /** 归还文件系统的字节数 */
+  reclaimedBytes: number;
+}
+
+/** 目标位置已有同名条目时的处理方式 */
+export type ConflictPolicy = "skip" | "overwrite" | "rename";
+
+/** 只计数的扫描结果（不收集条目） */
+export interface CountResult {
+  dirCount: number;
+  /** 无法读取的目录数（其内容未计入） */
+  errorCount: number;
+  fileCount: number;
+  path: string;
+  scanTime: number;
+  totalSize: number;
+  totalSizeFormatted: string;
+  /** 超出时间预算而未遍历的目录数；非 0 时结果不完整 */
+  unscannedCount: number;
+}
+
+/** 附加数据流：NTFS 备用数据流（如 Zone.Identifier），Unix 为扩展属性 */
+export interface DataStream {
+  name: string;
+  size: number;
+}
+
+/** 永久删除的确认信息 */
+export interface DeleteConfirmation {
+  /** 令牌过期时间（Unix 秒） */
+  expiresAt: number;
+  /** 将被删除的路径（规范展示形式，已去掉位于其他已选目录之下的条目） */
+  paths: string[];
+  /** 无法删除的路径（不存在、卷根目录） */
+  rejected: BulkFailure[];
+  /** 传给 delete_permanently 的一次性令牌 */
+  token: string;
+  totalSize: number;
+  totalSizeFormatted: string;
+}
+
+/** 完整开发者分析结果 */
+export interface DevAnalysisResult {
+  /** 按总大小降序排列的类别统计 */
+  categories: DevCategoryStats[];
+  /** 被归类到开发者类别的项数 */
+  devItems: number;
+  /** 开发者类别占总空间百分比 */
+  devPercent: number;
+  /** 被归类到开发者类别的总字节数 */
+  devTotalSize: number;
+  /** 总扫描项数 */
+  totalItems: number;
+}
+
+/** 单个开发类别的聚合统计 */
+export interface DevCategoryStats {
+  /** 类别标识符 */
+  category: string;
+  /** 描述 */
+  description: string;
+  /** 匹配到的目录数 */
+  dirCount: number;
+  /** 匹配到的文件数（不含目录） */
+  fileCount: number;
+  /** 图标 emoji */
+  icon: string;
+  /** 匹配到的项目数 */
+  itemCount: number;
+  /** 显示名称 */
+  label: string;
+  /** 占总大小的百分比（相对所有开发者类别的合计） */
+  percentOfDev: number;
+  /** 占全部扫描结果的百分比 */
+  percentOfTotal: number;
+  /** 该类别中最大的 5 个项目 */
+  topItems: DevTopItem[];
+  /** 总占用字节数（文件 + 目录聚合后的大小） */
+  totalSize: number;
+  /** 格式化后的总大小 */
+  totalSizeFormatted: string;
+}
+
+/** 类别内 Top 项目 */
+export interface DevTopItem {
+  name: string;
+  size: number;
+  sizeFormatted: string;
+}
+
+/** 差异项 */
+export interface DiffItem {
+  id: number;
+  isDir: boolean;
+  name: string;
+  path: string;
+  size: number;
+  sizeFormatted: string;
+}
+
+/** 修改项 */
+export interface DiffModifyItem {
+  delta: number;
+  deltaFormatted: string;
+  id: number;
+  isDir: boolean;
+  name: string;
+  newSize: number;
+  newSizeFormatted: string;
+  oldSize: number;
+  oldSizeFormatted: string;
+  path: string;
+}
+
+/** 差异摘要 */
+export interface DiffSummary {
+  addedCount: number;
+  growthPercent: number;
+  modifiedCount: number;
+  newItemCount: number;
+  newTotalSize: number;
+  newTotalSizeFormatted: string;
+  oldItemCount: number;
+  oldTotalSize: number;
+  oldTotalSizeFormatted: string;
+  removedCount: number;
+  totalChanges: number;
+}
+
+export interface DirEntryInfo {
+  category: FileCategory;
+  created: number;
+  hidden: boolean;
+  isDir: boolean;
+  kind: ItemKind;
+  modified: number;
+  name: string;
+  path: string;
+  /** 文件大小；目录为 0 */
+  size: number;
+  sizeFormatted: string;
+}
+
+export interface DirListing {
+  dirCount: number;
+  elapsedMs: number;
+  entries: DirEntryInfo[];
+  fileCount: number;
+  /** 直接包含的文件大小之和（不含子目录内容） */
+  filesSize: number;
+  path: string;
+}
+
+export interface DirPermission {
+  /** 无法读取部分的大小估计（取自上次快照） */
+  estimatedBytes: number;
+  estimatedBytesFormatted: string;
+  /** 子树中无法读取的目录数（含自身） */
+  inaccessible: number;
+  path: string;
+  readability: Readability;
+}
+
+/** 单盘扫描摘要 */
+export interface DriveScanSummary {
+  dirCount: number;
+  error?: string | null;
+  fileCount: number;
+  fileSystem: string;
+  freeBytes: number;
+  label: string;
+  mftAvailable: boolean;
+  mountPoint: string;
+  scanTime: number;
+  /** 扫描到的文件总大小 */
+  scannedSize: number;
+  scannedSizeFormatted: string;
+  totalBytes: number;
+}
+
+export type DriveType = "ssd" | "hdd" | "unknown" | "network";
+
+export interface DuplicateGroup {
+  /** 内容哈希；未按内容确认时为 None */
+  hash?: string | null;
+  paths: string[];
+  /** 每个文件的大小 */
+  size: number;
+}
+
+export interface DuplicateSummary {
+  /** 重复组中除每组一份外的文件数 */
+  duplicateFiles: number;
+  groupCount: number;
+  /** 浪费空间最多的组（最多 MAX_DUPLICATE_GROUPS 组） */
+  groups: DuplicateGroup[];
+  minSize: number;
+  /** 是否按内容哈希确认（否则为按大小 + 文件名判断的疑似重复） */
+  verified: boolean;
+  /** 删除多余副本可释放的空间 */
+  wastedBytes: number;
+}
+
+/** 提权建议 */
+export interface ElevationAdvice {
+  /** 拒绝访问的一级子目录 */
+  denied: string[];
+  /** 探测的一级子目录数 */
+  probed: number;
+  /** 有一级子目录拒绝访问且当前未提权：提权可明显提高覆盖率 */
+  recommended: boolean;
+}
+
+/** 事件日志查询条件（全部可选） */
+export interface EventFilter {
+  /** 只返回这些类型的事件 */
+  kinds?: EventKind[] | null;
+  /** 最多返回条数（默认 500，上限 10000） */
+  limit?: number | null;
+  /** 路径前缀 */
+  path?: string | null;
+  /** 起止时间（Unix 毫秒，含边界） */
+  since?: number | null;
+  until?: number | null;
+}
+
+/** 事件类型 */
+export type EventKind = "scan_start" | "scan_finish" | "cache_hit" | "cache_miss" | "cache_invalidate" | "cache_clear" | "error" | "cache_rebuild" | "cache_compact";
+
+/** 事件日志中的一条记录 */
+export interface EventRecord {
+  detail: string;
+  id: number;
+  kind: string;
+  path: string;
+  /** Unix 毫秒时间戳 */
+  timestamp: number;
+}
+
+/** 排除预设（判别值为 Exclusions 位掩码中的位序号，只能追加） */
+export type ExclusionPreset = "winSxs" | "systemVolumeInformation" | "pageFile" | "recycleBin" | "proc" | "sys";
+
+/** 供前端列出可勾选的预设 */
+export interface ExclusionPresetInfo {
+  /** 预设针对当前平台（其他平台的预设仍可勾选，但通常不会命中） */
+  currentPlatform: boolean;
+  description: string;
+  id: ExclusionPreset;
+  label: string;
+}
+
+/** 导出列；表头与 NDJSON 的键为其 camelCase 名称 */
+export type ExportColumn = "path" | "name" | "extension" | "size" | "sizeFormatted" | "isDir" | "kind" | "category" | "modified" | "created" | "fileCount" | "dirCount" | "percentOfParent" | "percentOfTotal" | "id";
+
+/** 导出格式 */
+export type ExportFormat = "csv" | "json" | "txt";
+
+/** 单个扩展名的聚合统计（仅统计文件） */
+export interface ExtensionStat {
+  /** 小写扩展名，不含点；无扩展名时为 "no-ext" */
+  extension: string;
+  fileCount: number;
+  percentOfTotal: number;
+  totalSize: number;
+  totalSizeFormatted: string;
+}
+
+export interface FileAgeReport {
+  buckets: AgeBucket[];
+  path: string;
+  /** 超过 1 年未修改的数据量 */
+  staleSize: number;
+  staleSizeFormatted: string;
+  topStaleFiles: StaleFile[];
+  totalFiles: number;
+  totalSize: number;
+  /** 缺少修改时间的文件数（未计入任何年龄段） */
+  unknownCount: number;
+  unknownSize: number;
+}
+
+/** 文件类别（判别值即二进制协议中的编码，只能追加）。 生成 schema 时无文档注释的变体会被合并到最前面，新增变体须带文档注释以保持顺序 */
+export type FileCategory = "other" | "image" | "video" | "audio" | "document" | "code" | "archive" | "executable" | "diskImage";
+
+/** 单个文件的哈希结果；失败时 hash 为 None、error 为原因 */
+export interface FileHash {
+  error?: string | null;
+  hash?: string | null;
+  path: string;
+  size: number;
+}
+
+export interface FileOpResult {
+  /** 成功处理的条目总大小 */
+  affectedBytes: number;
+  affectedBytesFormatted: string;
+  failed: BulkFailure[];
+  parents: ParentSize[];
+  /** 成功处理的路径（规范展示形式） */
+  succeeded: string[];
+}
+
+/** 文件操作队列（见 jobs） */
+export interface FileOpsSettings {
+  /** 同时执行的作业数；1 为按入队顺序逐个执行 */
+  maxParallel?: number;
+}
+
+export interface FilePreview {
+  /** 文本：检测到的编码（WHATWG 名称，如 UTF-8、gb18030、UTF-16LE） */
+  encoding?: string | null;
+  height?: number | null;
+  /** 二进制：带偏移与 ASCII 列的十六进制转储 */
+  hexDump?: string | null;
+  kind: PreviewKind;
+  path: string;
+  size: number;
+  sizeFormatted: string;
+  text?: string | null;
+  /** 图片：缩略图或原图的 data URL；格式无法解码且文件过大时为 None */
+  thumbnail?: string | null;
+  /** 文本或转储只覆盖了文件开头的一部分 */
+  truncated: boolean;
+  /** 图片原始尺寸（从文件头读出，未知格式为 None） */
+  width?: number | null;
+}
+
+export interface FrecentPath {
+  lastVisit: number;
+  path: string;
+  score: number;
+  visitCount: number;
+}
+
+export interface GitRepo {
+  gitSize: number;
+  gitSizeFormatted: string;
+  /** 清理建议，如 "packfile 共 12.00 GB，可执行 git gc 或检查历史中的大文件" */
+  hints: string[];
+  /** lfs/ 下的 LFS 对象缓存 */
+  lfsSize: number;
+  /** .git 为文件（链接工作树或子模块），对象存放在别处 */
+  linked: boolean;
+  looseCount: number;
+  /** objects/xx/ 下的松散对象 */
+  looseSize: number;
+  packCount: number;
+  /** objects/pack 下的 .pack 文件 */
+  packSize: number;
+  /** 仓库根目录（.git 的父目录） */
+  path: string;
+  totalSize: number;
+  totalSizeFormatted: string;
+  workingTreeSize: number;
+  workingTreeSizeFormatted: string;
+}
+
+export interface GitRepoReport {
+  path: string;
+  /** 按仓库总大小降序 */
+  repos: GitRepo[];
+  totalGitSize: number;
+  totalGitSizeFormatted: string;
+  totalWorkingTreeSize: number;
+  totalWorkingTreeSizeFormatted: string;
+}
+
+export type HashAlgorithm = "blake3" | "sha256";
+
+export interface HashProgress {
+  processedBytes: number;
+  processedFiles: number;
+  totalBytes: number;
+  totalFiles: number;
+}
+
+export interface HistoryItem {
+  itemCount: number;
+  path: string;
+  scanTime: number;
+  sizeFormat: string;
+  totalSize: number;
+}
+
+export interface HistoryItemSummary {
+  itemCount: number;
+  path: string;
+  scanTime: number;
+  sizeFormat: string;
+  totalSize: number;
+}
+
+export interface IdleScanEvent {
+  durationMs: number;
+  error?: string | null;
+  path: string;
+  startedAt: number;
+  success: boolean;
+  totalSize: number;
+}
+
+/** 空闲时自动刷新收藏路径的扫描结果（见 idle_scan），默认关闭 */
+export interface IdleScanSettings {
+  enabled?: boolean;
+  /** 无键盘 / 鼠标输入超过此分钟数视为空闲 */
+  idleMinutes?: number;
+  /** 系统 CPU 使用率不高于此百分比（1–100）时才扫描 */
+  maxCpuPercent?: number;
+  /** 静默时段：期间不做空闲扫描（如夜间避免风扇与磁盘噪音） */
+  quietHours?: QuietHours | null;
+  /** 同一路径两次空闲刷新的最短间隔（分钟） */
+  refreshMinutes?: number;
+}
+
+export interface IdleStatus {
+  inQuietHours: boolean;
+  /** 路径 → 上次空闲刷新时间（Unix 秒） */
+  lastRefresh: Record<string, number>;
+  /** 下一次将刷新的收藏路径 */
+  nextPath?: string | null;
+  /** 按当前状态，下一次检查是否会扫描 */
+  ready: boolean;
+  settings: IdleScanSettings;
+  systemCpuPercent: number;
+  /** 当前输入空闲秒数；平台不支持时为 None */
+  userIdleSecs?: number | null;
+}
+
+/** 索引中的一项（绝对路径） */
+export interface IndexEntry {
+  isDir: boolean;
+  /** 文件修改时间（Windows FILETIME 转换而来的 Unix 时间戳，目录为 0） */
+  mtime: number;
+  name: string;
+  path: string;
+  size: number;
+}
+
+/** 索引状态（前端据 kind 判断） */
+export type IndexState = {
+  kind: "notLoaded";
+} | {
+  data: {
+    drive: string;
+    scanned: number;
+  };
+  kind: "loading";
+} | {
+  data: ReadyData;
+  kind: "ready";
+} | {
+  data: {
+    reason: string;
+  };
+  kind: "failed";
+};
+
+export interface Item {
+  /** 按扩展名的文件类别（目录与链接类条目为 other） */
+  category?: FileCategory;
+  /** 创建时间（Unix 秒，0 表示未知） */
+  created: number;
+  /** 目录子树中的子目录数（递归，不含自身；文件为 0） */
+  dirCount?: number;
+  /** 目录子树中的文件数（递归，不含目录；文件为 0，小文件合成条目为合并的文件数） */
+  fileCount?: number;
+  /** 稳定 ID：卷 ID + 卷内相对路径的哈希，重扫后不变，前端据此保留选中/展开/标签状态 */
+  id: number;
+  isDir: boolean;
+  /** 条目类型：联接 / 符号链接 / 挂载点 / 云占位符不跟随、按 0 字节计，前端据此说明原因 */
+  kind?: ItemKind;
+  /** 最后修改时间（Unix 秒，0 表示未知） */
+  modified: number;
+  name: string;
+  path: string;
+  /** 占父目录大小的百分比（0–100，扫描根的直接子项相对扫描总大小） */
+  percentOfParent?: number;
+  /** 占扫描总大小的百分比（0–100） */
+  percentOfTotal?: number;
+  size: number;
+  sizeFormatted: string;
+}
+
+export interface ItemDetails {
+  accessed?: number | null;
+  /** 最后访问时间是否可信（系统关闭了访问时间更新时为 false） */
+  accessedReliable: boolean;
+  /** 实际分配的磁盘空间（文件；目录为目录项本身的占用） */
+  allocatedSize?: number | null;
+  /** 属性名（readOnly、hidden、system、compressed、encrypted、sparse 等） */
+  attributes: string[];
+  /** 目录的子项统计；文件为 None */
+  children?: ChildCounts | null;
+  /** 透明压缩 / 稀疏文件压缩后的占用；未压缩时为 None */
+  compressedSize?: number | null;
+  /** 创建、修改、最后访问时间（Unix 秒）；文件系统不提供时为 None */
+  created?: number | null;
+  hardLinks?: number | null;
+  isDir: boolean;
+  kind: ItemKind;
+  /** 符号链接 / 联接的目标 */
+  linkTarget?: string | null;
+  modified?: number | null;
+  name: string;
+  owner?: string | null;
+  /** 规范展示形式 */
+  path: string;
+  /** Unix 权限位（rwxr-xr-x） */
+  permissions?: string | null;
+  /** 文件为逻辑大小；目录为扫描缓存中的子树大小，未扫描时为 None */
+  size?: number | null;
+  sizeFormatted?: string | null;
+  /** NTFS 备用数据流；Unix 为扩展属性 */
+  streams: DataStream[];
+}
+
+/** Item 可投影的字段 */
+export type ItemField = "path" | "name" | "size" | "sizeFormatted" | "isDir" | "modified" | "created" | "id" | "kind" | "fileCount" | "dirCount" | "category" | "percentOfParent" | "percentOfTotal";
+
+/** 条目类型（判别值即二进制协议中的编码，只能追加） */
+export type ItemKind = "file" | "dir" | "junction" | "symlink" | "mountPoint" | "cloudPlaceholder" | "smallFiles";
+
+export interface Job {
+  /** 入队时间（Unix 秒） */
+  createdAt: number;
+  /** 失败摘要（如 "2 项失败"、异常终止原因） */
+  error?: string | null;
+  finishedAt?: number | null;
+  id: string;
+  result?: JobOutcome | null;
+  spec: JobSpec;
+  startedAt?: number | null;
+  state: JobState;
+}
+
+/** 作业结果（结构与对应的单次命令相同） */
+export type JobOutcome = FileOpResult | TransferResult;
+
+/** 作业内容 */
+export type JobSpec = {
+  paths: string[];
+  type: "trash";
+} | {
+  paths: string[];
+  token: string;
+  type: "delete";
+} | {
+  conflict?: ConflictPolicy;
+  dest: string;
+  paths: string[];
+  type: "move";
+} | {
+  conflict?: ConflictPolicy;
+  dest: string;
+  paths: string[];
+  type: "copy";
+};
+
+export type JobState = "queued" | "running" | "cancelled" | "completed" | "failed";
+
+export interface LevelResult {
+  /** 直接子项，按大小降序；子目录的 size / fileCount / dirCount 为整棵子树 */
+  children: Item[];
+  /** false 表示遍历超出时间预算，部分子目录的大小只含已遍历部分 */
+  complete: boolean;
+  dirCount: number;
+  /** 子树文件总数 / 目录总数（不含 path 本身） */
+  fileCount: number;
+  path: string;
+  scanTime: number;
+  source: LevelSource;
+  totalSize: number;
+  totalSizeFormatted: string;
+}
+
+/** 子项大小的来源 */
+export type LevelSource = "cache" | "traversal";
+
+/** 内存缓存的容量上限与当前占用（大小为估计值） */
+export interface MemoryCacheStats {
+  currentEntries: number;
+  currentSizeMb: number;
+  maxEntries: number;
+  maxSizeMb: number;
+}
+
+/** 本次启动的迁移结果 */
+export interface MigrationStatus {
+  applied: AppliedMigration[];
+  backupDir?: string | null;
+  /** 当前数据版本 */
+  currentVersion: number;
+  dataDir: string;
+  error?: string | null;
+  /** 迁移前的数据版本（0 = 旧版本未记录版本号） */
+  fromVersion: number;
+  /** 迁移失败后是否已从备份恢复 */
+  restored: boolean;
+  /** 程序支持的最新版本 */
+  targetVersion: number;
+}
+
+/** 网络模式选择 */
+export type NetworkMode = "on" | "off" | "auto";
+
+/** "operation-progress" 事件负载 */
+export interface OperationProgress {
+  cancelled: boolean;
+  /** 操作已结束（成功、失败或取消） */
+  done: boolean;
+  id: string;
+  /** 操作名（与命令名一致，如 clear_disk_cache） */
+  operation: string;
+  /** 0–100 */
+  percent: number;
+  /** 当前阶段（如 delete / write / prune） */
+  stage: string;
+}
+
+/** 单个所有者的聚合统计（仅统计文件） */
+export interface OwnerStat {
+  fileCount: number;
+  owner: string;
+  percentOfTotal: number;
+  totalSize: number;
+  totalSizeFormatted: string;
+}
+
+export interface PackageCacheReport {
+  /** 展示用的清理命令，如 "pnpm store prune"；None 表示没有安全的自带命令 */
+  cleanCommand?: string | null;
+  label: string;
+  /** 只列出存在的位置 */
+  locations: CacheLocation[];
+  reclaimableBytes: number;
+  reclaimableBytesFormatted: string;
+  tool: PackageTool;
+  totalSize: number;
+  totalSizeFormatted: string;
+}
+
+export type PackageTool = "cargo" | "npm" | "pnpm" | "pip" | "gradle";
+
+/** 操作后父目录的大小 */
+export interface ParentSize {
+  path: string;
+  /** 新大小；父目录没有缓存的扫描结果时为 None（前端可重新 scan_level） */
+  size?: number | null;
+  sizeFormatted?: string | null;
+}
+
+export interface PathAnomaly {
+  isDir: boolean;
+  kinds: PathAnomalyKind[];
+  /** 路径长度（UTF-16 单元，与 MAX_PATH 的计法一致） */
+  length: number;
+  path: string;
+}
+
+export type PathAnomalyKind = "overMaxPath" | "nearMaxPath" | "trailingSpaceOrDot" | "reservedName" | "invalidCharacters";
+
+export interface PathAnomalyReport {
+  /** 有异常的条目，最长路径在前；超过 5000 条时截断 */
+  anomalies: PathAnomaly[];
+  checked: number;
+  invalidCharacters: number;
+  nearMaxPath: number;
+  overMaxPath: number;
+  path: string;
+  reservedNames: number;
+  trailingSpaceOrDot: number;
+  truncated: boolean;
+}
+
+/** 单条补全建议 */
+export interface PathSuggestion {
+  path: string;
+  score: number;
+  source: SuggestionSource;
+}
+
+export interface PerformanceSummary {
+  avgIoTimeMs: number;
+  avgScanDurationMs: number;
+  avgThroughputMbps: number;
+  cacheHitRate: number;
+  cacheHits: number;
+  maxScanDurationMs: number;
+  minScanDurationMs: number;
+  totalScans: number;
+}
+
+export interface PermissionReport {
+  /** 其中因拒绝访问而无法读取的 */
+  accessDenied: number;
+  denied: number;
+  /** 一级子目录，拒绝访问与部分可读的在前 */
+  dirs: DirPermission[];
+  /** 存在拒绝访问的目录且当前进程不是管理员：提权补扫可能覆盖这些目录 */
+  elevationMayHelp: boolean;
+  estimatedBytes: number;
+  estimatedBytesFormatted: string;
+  /** 无法读取的目录总数 */
+  inaccessible: number;
+  partial: number;
+  path: string;
+  readable: number;
+}
+
+export type PreviewKind = "text" | "image" | "binary";
+
+export interface PrewarmEvent {
+  durationMs: number;
+  /** 刷新失败的收藏路径 */
+  failed: string[];
+  /** 载入内存缓存的缓存 key */
+  loaded: string[];
+  /** 刷新成功的收藏路径（未开启 refreshPinned 时为空） */
+  refreshed: string[];
+  trigger: PrewarmTrigger;
+}
+
+/** 启动与空闲后把常用的扫描结果预先载入内存缓存（见 prewarm） */
+export interface PrewarmSettings {
+  enabled?: boolean;
+  /** 无键盘 / 鼠标输入超过此分钟数后再预热一次（内存中的结果可能已按内存上限关闭） */
+  idleMinutes?: number;
+  /** 每次预热最多载入的扫描结果数（按磁盘缓存命中次数从多到少） */
+  maxEntries?: number;
+  /** 预热时以后台模式依次重扫收藏的路径：未变化的目录只校验缓存，变化的目录更新缓存 */
+  refreshPinned?: boolean;
+}
+
+/** 预热的时机 */
+export type PrewarmTrigger = "startup" | "idle";
+
+export type ProjectedItems = {
+  /** 按扩展名的文件类别（目录与链接类条目为 other） */
+  category?: FileCategory;
+  /** 创建时间（Unix 秒，0 表示未知） */
+  created?: number;
+  /** 目录子树中的子目录数（递归，不含自身；文件为 0） */
+  dirCount?: number;
+  /** 目录子树中的文件数（递归，不含目录；文件为 0，小文件合成条目为合并的文件数） */
+  fileCount?: number;
+  /** 稳定 ID：卷 ID + 卷内相对路径的哈希，重扫后不变，前端据此保留选中/展开/标签状态 */
+  id?: number;
+  isDir?: boolean;
+  /** 条目类型：联接 / 符号链接 / 挂载点 / 云占位符不跟随、按 0 字节计，前端据此说明原因 */
+  kind?: ItemKind;
+  /** 最后修改时间（Unix 秒，0 表示未知） */
+  modified?: number;
+  name?: string;
+  path?: string;
+  /** 占父目录大小的百分比（0–100，扫描根的直接子项相对扫描总大小） */
+  percentOfParent?: number;
+  /** 占扫描总大小的百分比（0–100） */
+  percentOfTotal?: number;
+  size?: number;
+  sizeFormatted?: string;
+}[];
+
+/** 本地时间段，"HH:MM"；结束早于开始时跨越午夜（如 22:00–07:00） */
+export interface QuietHours {
+  end: string;
+  start: string;
+}
+
+export type Readability = "readable" | "partial" | "denied";
+
+/** 索引就绪时的元数据（独立 struct：enum 级 rename_all 在 serde 里只作用于 variant 名， 不保证 struct variant 字段被重命名，故抽出来确保字段序列化为 camelCase） */
+export interface ReadyData {
+  allDrives: string[];
+  dirCount: number;
+  driveCount: number;
+  /** MFT 扫描失败的盘（需管理员或非 NTFS），以及枚举到的全部 NTFS 盘符（诊断用） */
+  failedDrives: string[];
+  fileCount: number;
+}
+
+/** 路径最近访问失败，在有效期内未重试 */
+export interface RecentFailure {
+  /** 上次失败的错误描述 */
+  error: string;
+  /** 距上次失败的秒数 */
+  failedSecsAgo: number;
+  path: string;
+  /** 再过多少秒后自动重试；强制刷新可立即重试 */
+  retryAfterSecs: number;
+}
+
+/** 单个回收站目录（每卷一个 $Recycle.Bin / Trash 目录） */
+export interface RecycleBinLocation {
+  itemCount: number;
+  path: string;
+  size: number;
+}
+
+export interface RecycleBinReport {
+  /** 按大小降序 */
+  folders: RecycledFolder[];
+  itemCount: number;
+  /** 按大小降序 */
+  items: RecycledItem[];
+  locations: RecycleBinLocation[];
+  /** 最早的删除时间（没有条目或时间均未知时为 None） */
+  oldestDeletedAt?: number | null;
+  totalSize: number;
+  totalSizeFormatted: string;
+}
+
+/** 按删除前所在目录汇总 */
+export interface RecycledFolder {
+  itemCount: number;
+  latestDeletedAt: number;
+  /** 原所在目录；原路径未知的条目归入空字符串 */
+  originalDir: string;
+  size: number;
+  sizeFormatted: string;
+}
+
+/** 回收站中的一个条目 */
+export interface RecycledItem {
+  /** 删除时间（Unix 秒），未知为 0 */
+  deletedAt: number;
+  isDir: boolean;
+  /** 数据在回收站中的位置 */
+  location: string;
+  name: string;
+  /** 删除前的路径（macOS 废纸篓不记录时为 None） */
+  originalPath?: string | null;
+  size: number;
+  sizeFormatted: string;
+}
+
+/** 重命名结果 */
+export interface RenameResult {
+  /** 扫描缓存中修正后的条目（路径、名称、类别、稳定 ID 已更新）； 不在任何内存缓存会话中时为 None，前端需自行刷新 */
+  item?: Item | null;
+  oldPath: string;
+  path: string;
+}
+
+/** 报告配色（CSS 颜色值，深色与应用的 --fd-* 变量一致） */
+export interface ReportColors {
+  accent: string;
+  background: string;
+  border: string;
+  muted: string;
+  surface: string;
+  text: string;
+}
+
+export interface ReportDirectory {
+  fileCount: number;
+  path: string;
+  percentOfTotal: number;
+  size: number;
+}
+
+export type ReportFormat = "html" | "pdf";
+
+export interface ReportOptions {
+  format?: ReportFormat;
+  locale?: string | null;
+  /** 参与重复检测的最小文件大小（字节），缺省 DEFAULT_MIN_DUPLICATE_SIZE */
+  minDuplicateSize?: number | null;
+  /** 缺省取设置中的外观 */
+  theme?: ThemePreference | null;
+  /** 报告标题；缺省为“磁盘空间报告” */
+  title?: string | null;
+  /** 最大目录与扩展名列表的条数，缺省 DEFAULT_TOP_N */
+  topN?: number | null;
+  /** 按内容哈希确认重复文件（需读取候选文件，较慢） */
+  verifyDuplicates?: boolean;
+}
+
+export interface ReportResult {
+  dest: string;
+  fileSize: number;
+  fileSizeFormatted: string;
+  format: ReportFormat;
+  summary: ReportSummary;
+}
+
+/** 解析后的报告外观 */
+export interface ReportStyle {
+  colors: ReportColors;
+  direction: TextDirection;
+  /** BCP 47 语言标记，如 "zh-CN"、"de-DE"、"ar-EG" */
+  locale: string;
+  theme: Theme;
+}
+
+/** 报告的数据部分（与输出格式无关） */
+export interface ReportSummary {
+  dirCount: number;
+  duplicates: DuplicateSummary;
+  extensions: ExtensionStat[];
+  fileCount: number;
+  /** 生成时间（Unix 秒） */
+  generatedAt: number;
+  root: string;
+  title: string;
+  topDirectories: ReportDirectory[];
+  totalSize: number;
+}
+
+/** 单个采样点 */
+export interface ResourceSample {
+  /** 本进程占全部 CPU 的百分比（已按核数归一化，0-100） */
+  cpuSharePercent: number;
+  diskReadMbps: number;
+  /** 本进程读取量占所有进程读取量的百分比 */
+  diskSharePercent: number;
+  /** 距扫描开始的毫秒数 */
+  elapsedMs: number;
+  memoryMb: number;
+  /** 本进程内存占物理内存的百分比 */
+  memorySharePercent: number;
+  /** 系统整体 CPU 使用率 */
+  systemCpuPercent: number;
+}
+
+/** 时间序列汇总 */
+export interface ResourceUsageSummary {
+  avgCpuSharePercent: number;
+  avgDiskReadMbps: number;
+  avgDiskSharePercent: number;
+  avgSystemCpuPercent: number;
+  peakCpuSharePercent: number;
+  peakDiskReadMbps: number;
+  peakMemoryMb: number;
+  peakMemorySharePercent: number;
+  sampleCount: number;
+}
+
+export interface SavedProfile {
+  /** 内置配置（未被用户覆盖） */
+  builtin?: boolean;
+  description?: string;
+  name: string;
+  options: ScanOptions;
+  /** 最后保存时间（Unix 秒），内置配置为 0 */
+  updatedAt?: number;
+}
+
+/** 同一路径上一次扫描与当前扫描的比较结果 */
+export interface ScanComparison {
+  diff: SnapshotDiff;
+  path: string;
+  /** 上一次扫描结果写入缓存的时间（Unix 秒） */
+  previousCachedAt: number;
+}
+
+/** 扫描中无法读取的目录 */
+export interface ScanError {
+  /** 该目录的大小估计（上次快照中的大小，未知为 0） */
+  estimatedBytes: number;
+  kind: ScanErrorKind;
+  path: string;
+}
+
+export type ScanErrorKind = "accessDenied" | "notFound" | "other";
+
+export type ScanExportFormat = "csv" | "ndjson" | "xlsx";
+
+export interface ScanExportResult {
+  dest: string;
+  fileSize: number;
+  fileSizeFormatted: string;
+  format: ScanExportFormat;
+  rows: number;
+}
+
+/** 扫描性能指标 */
+export interface ScanMetrics {
+  bytesRead: number;
+  cacheHit: boolean;
+  cachePhaseMs: number;
+  cacheReadTimeMs: number;
+  computePhaseMs: number;
+  cpuUsagePercent: number;
+  dirsScanned: number;
+  durationMs: number;
+  endTime?: string | null;
+  errors: string[];
+  filesScanned: number;
+  ioOperations: number;
+  ioPhaseMs: number;
+  ioThroughputMbps: number;
+  memoryAllocatedMb: number;
+  memoryPeakMb: number;
+  path: string;
+  /** 扫描期间的资源占用时间序列（本进程占系统 CPU / 内存 / 磁盘读取的比例） */
+  resourceSamples?: ResourceSample[];
+  resourceUsage?: ResourceUsageSummary | null;
+  scanId: string;
+  serializePhaseMs: number;
+  startTime: string;
+  threadsUsed: number;
+}
+
+/** 扫描选项（默认值与旧行为一致） */
+export interface ScanOptions {
+  /** 遍历后端（默认按平台与路径自动选择，见 backend 模块） */
+  backend?: BackendKind;
+  /** 后台模式：遍历线程使用低 CPU / I/O 优先级并限速派发目录，定时扫描不拖慢前台程序 */
+  background?: boolean;
+  /** 启用的内置排除预设（WinSxS、页面文件、/proc 等），命中的条目在遍历中跳过 */
+  excludePresets?: ExclusionPreset[];
+  /** 附带按扩展名的统计，前端分页加载 items 时也能直接展示类型分布 */
+  extensionStats?: boolean;
+  /** items 字段投影：只序列化列出的字段（None / 空 = 全部字段）， 只需 path + size 的调用方（图表、导出）可大幅缩小 IPC 负载 */
+  fields?: ItemField[] | null;
+  /** 包含模式（glob，如 `*.mp4;*.mkv`）：只统计匹配的文件，目录大小只含匹配内容（见 include_filter 模块） */
+  includeGlobs?: string[];
+  /** 解析每个 item 的所有者（Windows SID → 账户名，Unix uid → 用户名），每项一次系统调用 */
+  includeOwner?: boolean;
+  /** 小于此字节数的文件不逐个列出：每个目录下的这些文件合并为一个合成条目（名称如 `12 个小文件`）， 目录大小与各项统计不变。源码树等小文件多的目录可大幅缩小结果（缓存仍保存完整结果） */
+  minFileSize?: number | null;
+  /** 网络模式：UNC 共享 / 网络驱动器使用低并发的遍历参数（默认自动识别） */
+  networkMode?: NetworkMode;
+  /** 扫描时间预算（毫秒）：超时后不再进入尚未遍历的子目录，立即返回已有结果， 未遍历的目录记录在 ScanResult::unscanned 中。仪表盘等需要 2 秒内给出答案的场景使用 */
+  timeBudgetMs?: number | null;
+}
+
+/** 扫描性能指标 */
+export interface ScanPerfMetrics {
+  cacheHit: boolean;
+  cacheReadTimeMs: number;
+  cacheSource?: string | null;
+  computePhaseMs: number;
+  dirsScanned: number;
+  /** 遍历时识别的存储介质；命中缓存 / USN 增量等未遍历目录时为 None */
+  driveType?: DriveType | null;
+  filesScanned: number;
+  ioPhaseMs: number;
+  ioThroughputMbps: number;
+  memoryPeakMb: number;
+  serializePhaseMs: number;
+  threadsUsed: number;
+}
+
+/** "scan-progress" 事件负载 */
+export interface ScanProgress {
+  dirsScanned: number;
+  elapsedSecs: number;
+  entriesScanned: number;
+  /** 预计剩余秒数；没有上次快照时为 None */
+  etaSecs?: number | null;
+  /** 上次快照中的条目数（无快照时为 None） */
+  expectedEntries?: number | null;
+  path: string;
+  /** 0–100；扫描结束前不超过 99 */
+  percent: number;
+  /** 是否按上次快照加权（false 为按目录数估计） */
+  weighted: boolean;
+}
+
+/** 命令返回的扫描结果：与 ScanResult 的 JSON 形状一致，items 按 ScanOptions::fields 投影。 单独成型而不是给 ScanResult 加掩码，是因为 ScanResult 还要用 bincode 写磁盘缓存 */
+export interface ScanResponse {
+  cacheUsage: CacheUsage;
+  categoryStats: CategoryStat[];
+  cloudLocalBytes: number;
+  cloudOnlyBytes: number;
+  /** 扫描受保护根目录前的提权建议（见 elevated::probe），未探测时省略 */
+  elevation?: ElevationAdvice | null;
+  errors: ScanError[];
+  extensionStats?: ExtensionStat[] | null;
+  items: ProjectedItems;
+  mftAvailable: boolean;
+  owners?: string[] | null;
+  path: string;
+  perfMetrics?: ScanPerfMetrics | null;
+  scanTime: number;
+  sparseFiles: SparseEntry[];
+  sparseSavingsBytes: number;
+  timing?: TimingInfo | null;
+  totalSize: number;
+  totalSizeFormatted: string;
+  unreadableBytes: number;
+  unscanned?: string[] | null;
+}
+
+export interface ScanResult {
+  /** 本次结果的来源与缓存条目的沿用 / 失效情况（命中缓存时重新填入） */
+  cacheUsage: CacheUsage;
+  /** 按文件类别聚合的大小/数量统计，返回前计算（缓存中为空） */
+  categoryStats: CategoryStat[];
+  /** 已下载到本地的云同步文件大小之和（已计入 total_size） */
+  cloudLocalBytes: number;
+  /** 仅在线的云占位符（OneDrive / Dropbox / iCloud）的逻辑大小之和，未计入 total_size； 扫描只读取目录元数据，不会触发下载 */
+  cloudOnlyBytes: number;
+  /** 无法读取的目录（拒绝访问等），非空时总大小可能偏小，可提权后重新扫描 */
+  errors: ScanError[];
+  /** 按扩展名聚合的大小/数量统计（仅 ScanOptions::extension_stats 开启时计算）。 不加 skip_serializing_if：磁盘缓存用 bincode 序列化，要求字段完整 */
+  extensionStats?: ExtensionStat[] | null;
+  items: Item[];
+  mftAvailable: boolean;
+  /** 各 item 的所有者账户名，与 items 按下标对齐，无法解析时为空串 （仅 ScanOptions::include_owner 开启时解析，较慢，不进缓存） */
+  owners?: string[] | null;
+  path: string;
+  perfMetrics?: ScanPerfMetrics | null;
+  scanTime: number;
+  /** 稀疏文件（VM 磁盘镜像、下载中的种子等），条目 size 按实际分配大小计 */
+  sparseFiles: SparseEntry[];
+  /** 稀疏文件逻辑大小与分配大小之差的总和（未计入 total_size） */
+  sparseSavingsBytes: number;
+  /** timing / perf_metrics 同样不加 skip_serializing_if：为 None 时跳过字段会使 bincode 数据无法解码 */
+  timing?: TimingInfo | null;
+  totalSize: number;
+  totalSizeFormatted: string;
+  /** 无法读取目录的大小估计（取自上次快照，无快照时为 0） */
+  unreadableBytes: number;
+  /** 超出 ScanOptions::time_budget_ms 时未遍历的目录：这些目录以大小 0 占位， 结果为尽力而为的部分结果（不进缓存）；None 表示完整扫描 */
+  unscanned?: string[] | null;
+}
+
+/** 目录遍历参数 */
+export interface ScanSettings {
+  /** 单线程确定性模式（调试用）：1 个 worker，按名称顺序读取条目、忽略上次快照的优先级提示， 同一目录树每次扫描的遍历顺序、事件顺序与结果顺序都相同 */
+  deterministic?: boolean;
+  /** 共享目录队列的容量上限；队列满时 worker 把新发现的子目录留在本线程内深度优先处理， 超大目录树的待遍历队列不再无限增长。None 为按介质默认（机械硬盘 64，其他不限） */
+  queueCapacity?: number | null;
+  /** 本地磁盘的遍历线程数；None 为按 CPU 自动（核数 × 2，限制在 8–32）。 网络路径仍使用低并发参数 */
+  threads?: number | null;
+}
+
+export interface Schedule {
+  createdAt: number;
+  id: string;
+  /** 上次运行失败的原因 */
+  lastError?: string | null;
+  lastRun?: number | null;
+  /** 下一次运行时间（Unix 秒） */
+  nextRun: number;
+  path: string;
+  /** 用户输入的触发规则（间隔或 cron） */
+  spec: string;
+}
+
+/** 定时扫描完成事件的负载 */
+export interface ScheduledScanEvent {
+  dirCount: number;
+  durationMs: number;
+  error?: string | null;
+  fileCount: number;
+  nextRun?: number | null;
+  path: string;
+  scheduleId: string;
+  snapshotId?: number | null;
+  startedAt: number;
+  success: boolean;
+  totalSize: number;
+}
+
+/** 选择操作方式 */
+export type SelectionMode = "add" | "remove" | "toggle" | "set";
+
+/** 选择集摘要 */
+export interface SelectionSummary {
+  count: number;
+  session: string;
+  /** 选中内容的总大小（已选目录下的子项不重复计算） */
+  totalSize: number;
+  totalSizeFormatted: string;
+}
+
+/** 选择目标 */
+export type SelectionTarget = {
+  ids: number[];
+  kind: "ids";
+} | {
+  end: number;
+  kind: "range";
+  start: number;
+} | {
+  kind: "all";
+};
+
+export interface Settings {
+  appearance?: AppearanceSettings;
+  cache?: CacheSettings;
+  fileOps?: FileOpsSettings;
+  idle?: IdleScanSettings;
+  prewarm?: PrewarmSettings;
+  scan?: ScanSettings;
+}
+
+export interface SizeEstimate {
+  /** 置信水平（0.95） */
+  confidence: number;
+  dirCount: number;
+  /** 无法读取的目录数（按空目录计入） */
+  errorCount: number;
+  /** true 为抽样估算（界面标注“估算”）；false 表示目录树已完整遍历，数值精确 */
+  estimated: boolean;
+  fileCount: number;
+  high: number;
+  /** 置信区间下限 / 上限（精确结果时与 total_size 相同） */
+  low: number;
+  path: string;
+  /** 随机探测次数 */
+  probes: number;
+  /** 实际读取的目录数 */
+  sampledDirs: number;
+  scanTime: number;
+  totalSize: number;
+  totalSizeFormatted: string;
+}
+
+/** 时间序列中的一个点 */
+export interface SizePoint {
+  size: number;
+  /** 扫描时间（Unix 秒） */
+  timestamp: number;
+}
+
+export interface SmallFileDir {
+  path: string;
+  slackBytes: number;
+  slackBytesFormatted: string;
+  /** 直接位于该目录下、小于一个簇的文件数 */
+  smallFiles: number;
+}
+
+export interface SmallFileReport {
+  /** 按簇向上取整后的估计分配大小 */
+  allocatedBytes: number;
+  allocatedBytesFormatted: string;
+  clusterSize: number;
+  /** 簇大小无法获取，按 DEFAULT_CLUSTER_SIZE 估算 */
+  clusterSizeEstimated: boolean;
+  /** 全部文件逻辑大小之和 */
+  logicalBytes: number;
+  path: string;
+  /** 分配大小与逻辑大小之差（所有文件的尾部空闲） */
+  slackBytes: number;
+  slackBytesFormatted: string;
+  /** slack 占分配大小的百分比 */
+  slackPercent: number;
+  /** 这些小文件的逻辑大小之和 */
+  subClusterBytes: number;
+  /** 大于 0 且小于一个簇的文件 */
+  subClusterFiles: number;
+  /** 其中小于一个簇的文件贡献的部分 */
+  subClusterSlackBytes: number;
+  topDirs: SmallFileDir[];
+  totalFiles: number;
+  zeroByteFiles: number;
+}
+
+/** 差异结果 */
+export interface SnapshotDiff {
+  /** 新增文件/目录 */
+  added: DiffItem[];
+  /** 新增总字节数 */
+  addedTotalSize: number;
+  /** 大小发生变化的文件/目录 */
+  modified: DiffModifyItem[];
+  /** 修改导致的净变化 (new - old) */
+  modifiedDelta: number;
+  /** 净变化 (added - removed + modified_delta) */
+  netChange: number;
+  /** 已删除的文件/目录 */
+  removed: DiffItem[];
+  /** 删除总字节数 */
+  removedTotalSize: number;
+  /** 变更统计摘要 */
+  summary: DiffSummary;
+  /** 未变更的文件数 */
+  unchangedCount: number;
+}
+
+/** 快照元数据（不含完整文件列表） */
+export interface SnapshotInfo {
+  dirCount: number;
+  fileCount: number;
+  id: number;
+  itemCount: number;
+  path: string;
+  scanTime: number;
+  totalSize: number;
+  totalSizeFormatted: string;
+}
+
+/** 稀疏文件的逻辑大小与实际分配大小 */
+export interface SparseEntry {
+  allocatedSize: number;
+  logicalSize: number;
+  path: string;
+}
+
+export interface StaleFile {
+  ageDays: number;
+  /** 最后修改时间（Unix 秒） */
+  modified: number;
+  path: string;
+  size: number;
+  sizeFormatted: string;
+}
+
+/** 候选来源 */
+export type SuggestionSource = "favorite" | "history" | "cacheIndex" | "filesystem";
+
+/** 全系统扫描结果（不含完整 items，单盘明细请按 mount_point 再次调用 scan_directory 命中缓存） */
+export interface SystemScanResult {
+  dirCount: number;
+  drives: DriveScanSummary[];
+  fileCount: number;
+  /** 总耗时（各盘并行，约等于最慢的一个盘） */
+  scanTime: number;
+  /** 跨所有盘的 Top 100 大目录（按 size 降序） */
+  topDirectories: Item[];
+  totalSize: number;
+  totalSizeFormatted: string;
+}
+
+export type TextDirection = "ltr" | "rtl";
+
+export type Theme = "light" | "dark";
+
+/** 主题偏好 */
+export type ThemePreference = "light" | "dark" | "system";
+
+export type Tier = "hot" | "warm" | "cold";
+
+/** 单级统计 */
+export interface TierStats {
+  fileCount: number;
+  percentOfTotal: number;
+  tier: Tier;
+  totalSize: number;
+  totalSizeFormatted: string;
+}
+
+/** 冷热分级报告 */
+export interface TieringReport {
+  /** 拿到了 atime 的文件数（为 0 时分级仅基于 mtime） */
+  accessTimeAvailable: number;
+  coldDays: number;
+  hotDays: number;
+  /** 可迁移到廉价存储的数据量（cold 级合计） */
+  movableSize: number;
+  movableSizeFormatted: string;
+  path: string;
+  /** 按 hot / warm / cold 顺序 */
+  tiers: TierStats[];
+  timeBasis: TimeBasis;
+  topColdFiles: ColdFile[];
+  totalFiles: number;
+  totalSize: number;
+  /** 无法读取时间信息的文件数（未计入任何级别） */
+  unknownCount: number;
+  /** 系统关闭了 atime 更新等导致分级可能失真时的提示 */
+  warning?: string | null;
+}
+
+/** 分级依据的时间 */
+export type TimeBasis = "lastUsed" | "modifiedOnly";
+
+export interface TimingInfo {
+  computePhase: number;
+  formatPhase: number;
+  scanPhase: number;
+  total: number;
+}
+
+export interface TransferResult {
+  /** 成功移动 / 复制的条目总大小 */
+  affectedBytes: number;
+  affectedBytesFormatted: string;
+  failed: BulkFailure[];
+  /** 目标目录与（移动时）各源父目录的新大小 */
+  parents: ParentSize[];
+  /** 因同名冲突跳过的源路径 */
+  skipped: string[];
+  transferred: Transferred[];
+}
+
+/** 一次成功的移动 / 复制 */
+export interface Transferred {
+  /** 实际写入的位置（Rename 时与源文件名不同） */
+  destination: string;
+  source: string;
+}
+
+export interface TreemapLayout {
+  height: number;
+  maxDepth: number;
+  /** 按层排列：父目录总在其子项之前，按顺序绘制即可 */
+  rects: TreemapRect[];
+  /** 超过矩形数上限，部分深层未展开 */
+  truncated: boolean;
+  width: number;
+}
+
+export interface TreemapRect {
+  category: FileCategory;
+  /** 1 为扫描根的直接子项 */
+  depth: number;
+  h: number;
+  id: number;
+  isDir: boolean;
+  kind: ItemKind;
+  name: string;
+  path: string;
+  size: number;
+  w: number;
+  x: number;
+  y: number;
+}
+
+/** 校验失败类型 */
+export type ValidationCode = "required" | "notAbsolute" | "notFound" | "notDirectory" | "outOfRange" | "invalidPattern";
+
+/** 参数校验失败 */
+export interface ValidationError {
+  code: ValidationCode;
+  /** 出错的参数名（与前端 invoke 的参数名一致，camelCase；数组元素为 `paths[2]`） */
+  field: string;
+  /** 面向用户的说明 */
+  message: string;
+}
+
+/** 单个已挂载卷的信息 */
+export interface VolumeInfo {
+  /** 分配单元（簇 / 块）大小（字节），文件按其整数倍占用空间；获取失败为 None */
+  clusterSize?: number | null;
+  /** 盘符（仅 Windows） */
+  driveLetter?: string | null;
+  /** 文件系统类型，如 NTFS / exFAT / ext4 */
+  fileSystem: string;
+  freeBytes: number;
+  isNetwork: boolean;
+  /** 卷是否就绪（读卡器无卡、光驱无盘时为 false，空间信息均为 0） */
+  isReady: boolean;
+  isRemovable: boolean;
+  kind: VolumeKind;
+  /** 卷标（可能为空） */
+  label: string;
+  /** 挂载点（Windows 为 `C:/`，其他平台为挂载目录），可直接作为扫描路径 */
+  mountPoint: string;
+  totalBytes: number;
+}
+
+/** 卷类型 */
+export type VolumeKind = "fixed" | "removable" | "network" | "cdRom" | "ramDisk" | "unknown";
@@ -0,0 +1,10 @@
+// 由 cargo run -p flashdir-core --example gen_schema 生成，请勿手动修改
+
+// 后端 scan::BINARY_PROTOCOL_VERSION，解码器支持的最高版本
+export const BINARY_PROTOCOL_VERSION = 7
+
+// 与后端 ItemKind 判别值一一对应
+export const ITEM_KINDS = ["file","dir","junction","symlink","mountPoint","cloudPlaceholder","smallFiles"]
+
+// 与后端 FileCategory 判别值一一对应
+export const FILE_CATEGORIES = ["other","image","video","audio","document","code","archive","executable","diskImage"]
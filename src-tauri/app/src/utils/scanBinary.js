@@ -6,11 +6,23 @@
 //   f64 io_ms | f64 compute_ms | f64 serialize_ms
 //   u32 path_len | path_utf8
 //   逐项: u32 path_len|path_utf8 | u32 name_len|name_utf8 | i64 size | u8 is_dir
+//         | i64 modified | i64 created（version >= 2，Unix 秒，0 表示未知）
+//         | u64 id（version >= 3，稳定 ID，仅低 53 位有效，可安全转为 Number）
+//         | u8 kind（version >= 4，条目类型，见 types/protocol.js 的 ITEM_KINDS）
+//         | u32 file_count | u32 dir_count（version >= 5，目录子树的文件数 / 子目录数）
+//         | u8 category（version >= 6，文件类别，见 types/protocol.js 的 FILE_CATEGORIES）
+//         | u16 percent_of_parent | u16 percent_of_total（version >= 7，万分比，0–10000）
+//   类别统计（version >= 6）: u8 count | 逐类: u8 category | i64 total_size | u32 file_count
 //
 // 用 DataView 顺序读 + 复用单个 TextDecoder，避免每项都新建对象；
 // sizeFormatted 不在后端传输，前端用 formatSize 计算（省体积）。
 
 import { formatSize } from './format.js'
+import {
+  BINARY_PROTOCOL_VERSION,
+  ITEM_KINDS as KIND_NAMES,
+  FILE_CATEGORIES as CATEGORY_NAMES
+} from '../types/protocol.js'
 
 const MAGIC = 0x4644
 
@@ -40,9 +52,17 @@ export function decodeScanResult(buffer) {
   if (magic !== MAGIC) {
     throw new Error(`二进制扫描结果格式错误: magic=0x${magic.toString(16)}`)
   }
-  // version / flags（当前未使用，跳过）
-  off += 1 // version
-  off += 1 // flags
+  const version = u8[off]; off += 1
+  if (version > BINARY_PROTOCOL_VERSION) {
+    throw new Error(`二进制扫描结果版本 ${version} 高于前端支持的 ${BINARY_PROTOCOL_VERSION}，请重新生成 app/src/types`)
+  }
+  off += 1 // flags（当前未使用）
+  const hasTimes = version >= 2
+  const hasId = version >= 3
+  const hasKind = version >= 4
+  const hasCounts = version >= 5
+  const hasCategory = version >= 6
+  const hasPercents = version >= 7
 
   const totalSize = Number(dv.getBigInt64(off, true)); off += 8
   const scanTime = dv.getFloat64(off, true); off += 8
@@ -68,11 +88,66 @@ export function decodeScanResult(buffer) {
     const name = readStr()
     const size = Number(dv.getBigInt64(off, true)); off += 8
     const isDir = u8[off] === 1; off += 1
-    items[i] = { path, name, size, sizeFormatted: formatSize(size), isDir }
+    let modified = 0
+    let created = 0
+    if (hasTimes) {
+      modified = Number(dv.getBigInt64(off, true)); off += 8
+      created = Number(dv.getBigInt64(off, true)); off += 8
+    }
+    let id = 0
+    if (hasId) {
+      id = Number(dv.getBigUint64(off, true)); off += 8
+    }
+    let kind = isDir ? 'dir' : 'file'
+    if (hasKind) {
+      kind = KIND_NAMES[u8[off]] ?? kind; off += 1
+    }
+    let itemFileCount = 0
+    let itemDirCount = 0
+    if (hasCounts) {
+      itemFileCount = dv.getUint32(off, true); off += 4
+      itemDirCount = dv.getUint32(off, true); off += 4
+    }
+    let category = 'other'
+    if (hasCategory) {
+      category = CATEGORY_NAMES[u8[off]] ?? category; off += 1
+    }
+    let percentOfParent = 0
+    let percentOfTotal = 0
+    if (hasPercents) {
+      percentOfParent = dv.getUint16(off, true) / 100; off += 2
+      percentOfTotal = dv.getUint16(off, true) / 100; off += 2
+    }
+    items[i] = {
+      path, name, size, sizeFormatted: formatSize(size), isDir, modified, created, id, kind,
+      fileCount: itemFileCount, dirCount: itemDirCount, category, percentOfParent, percentOfTotal
+    }
+  }
+
+  const categoryStats = []
+  if (hasCategory) {
+    const count = u8[off]; off += 1
+    for (let i = 0; i < count; i++) {
+      const category = CATEGORY_NAMES[u8[off]] ?? 'other'; off += 1
+      const size = Number(dv.getBigInt64(off, true)); off += 8
+      const files = dv.getUint32(off, true); off += 4
+      categoryStats.push({
+        category,
+        totalSize: size,
+        totalSizeFormatted: formatSize(size),
+        fileCount: files,
+        percentOfTotal: 0
+      })
+    }
+    const categorized = categoryStats.reduce((sum, s) => sum + s.totalSize, 0)
+    for (const s of categoryStats) {
+      s.percentOfTotal = categorized > 0 ? (s.totalSize / categorized) * 100 : 0
+    }
   }
 
   return {
     items,
+    categoryStats,
     totalSize,
     totalSizeFormatted: formatSize(totalSize),
     scanTime,
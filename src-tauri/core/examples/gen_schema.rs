@@ -0,0 +1,16 @@
+// 生成前端类型定义：app/src/types/ 下的 JSON Schema、TypeScript 声明与协议常量
+//
+//   cargo run -p flashdir-core --example gen_schema
+
+use flashdir_core::schema;
+use std::path::Path;
+
+fn main() -> std::io::Result<()> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join(schema::OUTPUT_DIR);
+    std::fs::create_dir_all(&dir)?;
+    for (name, content) in schema::generated_files() {
+        std::fs::write(dir.join(name), content)?;
+        println!("已生成 {}", dir.join(name).display());
+    }
+    Ok(())
+}
@@ -0,0 +1,250 @@
+// 邮件存档与应用数据库识别
+//
+// 用户目录里最大的往往不是文档，而是邮件客户端与浏览器 / Electron 应用的数据文件：
+// Outlook 的 PST（个人存档）/ OST（Exchange 脱机缓存）、Thunderbird 与 Apple Mail 的 mbox、
+// 浏览器和 Electron 应用的 SQLite 数据库与 LevelDB 目录。基于扫描结果按扩展名 / 文件名识别，
+// 由路径推断所属应用并按应用分组，标注可压缩（compactable：删除的邮件 / 记录仍占空间，
+// 应用内压缩或 VACUUM 可回收）与可归档（archivable：旧邮件可移到外部存储）。
+//
+// 只报告不小于 MIN_SIZE 的条目。LevelDB 以目录为单位，由应用自行压缩。
+
+use crate::scan::{format_size, Item};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// 小于此大小的数据文件不报告
+const MIN_SIZE: i64 = 50 * 1024 * 1024;
+
+/// 已知应用：路径片段（小写）→ 应用名。按顺序匹配，先具体后宽泛
+const KNOWN_APPS: &[(&str, &str)] = &[
+    ("/microsoft/outlook/", "Outlook"),
+    ("/outlook files/", "Outlook"),
+    ("/thunderbird/", "Thunderbird"),
+    ("/library/mail/", "Apple Mail"),
+    ("/mozilla/firefox/", "Firefox"),
+    ("/google/chrome/", "Chrome"),
+    ("/microsoft/edge/", "Edge"),
+    ("/bravesoftware/", "Brave"),
+    ("/microsoft/teams/", "Teams"),
+    ("/code/user/", "VS Code"),
+    ("/slack/", "Slack"),
+    ("/discord/", "Discord"),
+    ("/signal/", "Signal"),
+    ("/whatsapp/", "WhatsApp"),
+    ("/notion/", "Notion"),
+    ("/obsidian/", "Obsidian"),
+];
+
+/// 应用数据根目录（小写），其下第一级目录名即应用名（未知的 Electron 应用等）
+const APP_DATA_ROOTS: &[&str] = &["/appdata/roaming/", "/appdata/local/", "/library/application support/", "/.config/"];
+
+/// Chromium 内核（Chrome / Edge / Electron）配置目录中无扩展名的 SQLite 数据库
+const CHROMIUM_SQLITE_NAMES: &[&str] =
+    &["History", "Cookies", "Web Data", "Login Data", "Favicons", "Top Sites", "Shortcuts", "Network Action Predictor"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum AppDataKind {
+    /// Outlook 个人存档（.pst）
+    Pst,
+    /// Outlook Exchange / IMAP 脱机缓存（.ost），可由服务器重新同步
+    Ost,
+    Mbox,
+    Sqlite,
+    LevelDb,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AppDataFile {
+    pub path: String,
+    pub kind: AppDataKind,
+    pub size: i64,
+    pub size_formatted: String,
+    pub modified: i64,
+    /// 应用内压缩 / VACUUM 可回收已删除内容占用的空间
+    pub compactable: bool,
+    /// 旧内容可归档到外部存储
+    pub archivable: bool,
+    /// 处理建议
+    pub hint: String,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AppDataGroup {
+    /// 应用名；无法推断时为 "其他"
+    pub app: String,
+    pub total_size: i64,
+    pub total_size_formatted: String,
+    /// 按大小降序
+    pub files: Vec<AppDataFile>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AppDataReport {
+    pub path: String,
+    /// 按总大小降序
+    pub groups: Vec<AppDataGroup>,
+    pub total_size: i64,
+    pub total_size_formatted: String,
+}
+
+fn extension(name: &str) -> Option<String> {
+    name.rsplit_once('.').filter(|(stem, _)| !stem.is_empty()).map(|(_, ext)| ext.to_ascii_lowercase())
+}
+
+fn parent(path: &str) -> &str {
+    path.rsplit_once('/').map(|(p, _)| p).unwrap_or("")
+}
+
+/// 识别数据文件类型。msf 为 Thunderbird 的邮件摘要文件路径集合（mbox 旁的 <名称>.msf）
+fn classify(item: &Item, msf: &HashSet<&str>) -> Option<AppDataKind> {
+    let name = item.name.as_str();
+    if item.is_dir {
+        let lower = name.to_ascii_lowercase();
+        return if lower == "leveldb" || lower.ends_with(".leveldb") {
+            Some(AppDataKind::LevelDb)
+        } else if lower.ends_with(".mbox") {
+            // Apple Mail：.mbox 目录
+            Some(AppDataKind::Mbox)
+        } else {
+            None
+        };
+    }
+    match extension(name).as_deref() {
+        Some("pst") => Some(AppDataKind::Pst),
+        Some("ost") => Some(AppDataKind::Ost),
+        Some("mbox" | "mbx") => Some(AppDataKind::Mbox),
+        Some("sqlite" | "sqlite3" | "db" | "db3") => Some(AppDataKind::Sqlite),
+        None if msf.contains(format!("{}.msf", item.path).as_str()) => Some(AppDataKind::Mbox),
+        None if CHROMIUM_SQLITE_NAMES.contains(&name) => {
+            let profile = parent(&item.path).rsplit('/').next().unwrap_or("");
+            (profile == "Default" || profile.starts_with("Profile ")).then_some(AppDataKind::Sqlite)
+        }
+        _ => None,
+    }
+}
+
+/// 由路径推断所属应用
+fn app_name(path: &str) -> String {
+    let lower = path.to_ascii_lowercase();
+    if let Some((_, app)) = KNOWN_APPS.iter().find(|(segment, _)| lower.contains(segment)) {
+        return app.to_string();
+    }
+    for root in APP_DATA_ROOTS {
+        if let Some(pos) = lower.find(root) {
+            let rest = &path[pos + root.len()..];
+            if let Some((app, _)) = rest.split_once('/') {
+                return app.to_string();
+            }
+        }
+    }
+    if lower.ends_with(".pst") || lower.ends_with(".ost") {
+        return "Outlook".to_string();
+    }
+    "其他".to_string()
+}
+
+fn guidance(kind: AppDataKind) -> (bool, bool, &'static str) {
+    match kind {
+        AppDataKind::Pst => {
+            (true, true, "在 Outlook 数据文件设置中执行「立即压缩」；旧邮件可按年份归档到外部存储上的新 PST")
+        }
+        AppDataKind::Ost => (
+            true,
+            false,
+            "脱机缓存，可在 Outlook 中压缩或缩短「脱机保留邮件」时长；删除后 Outlook 会从服务器重新同步",
+        ),
+        AppDataKind::Mbox => (true, true, "在邮件客户端中压缩文件夹以清除已删除邮件；旧邮件可归档后移到外部存储"),
+        AppDataKind::Sqlite => (true, false, "关闭应用后执行 VACUUM 可回收空闲页；或在应用内清除历史 / 缓存"),
+        AppDataKind::LevelDb => (false, false, "由应用自行压缩；可在应用内清除缓存或站点数据"),
+    }
+}
+
+/// 由扫描结果识别邮件存档与应用数据库。path 为扫描根（不在 items 中）
+pub fn analyze(path: &str, items: &[Item]) -> AppDataReport {
+    let msf: HashSet<&str> = items
+        .iter()
+        .filter(|i| !i.is_dir && extension(&i.name).as_deref() == Some("msf"))
+        .map(|i| i.path.as_str())
+        .collect();
+
+    let mut groups: HashMap<String, Vec<AppDataFile>> = HashMap::new();
+    for item in items.iter().filter(|i| i.size >= MIN_SIZE) {
+        let Some(kind) = classify(item, &msf) else {
+            continue;
+        };
+        let (compactable, archivable, hint) = guidance(kind);
+        groups.entry(app_name(&item.path)).or_default().push(AppDataFile {
+            path: item.path.to_string(),
+            kind,
+            size: item.size,
+            size_formatted: format_size(item.size).to_string(),
+            modified: item.modified,
+            compactable,
+            archivable,
+            hint: hint.to_string(),
+        });
+    }
+
+    let mut groups: Vec<AppDataGroup> = groups
+        .into_iter()
+        .map(|(app, mut files)| {
+            files.sort_unstable_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+            let total_size = files.iter().map(|f| f.size).sum();
+            AppDataGroup { app, total_size, total_size_formatted: format_size(total_size).to_string(), files }
+        })
+        .collect();
+    groups.sort_unstable_by(|a, b| b.total_size.cmp(&a.total_size).then_with(|| a.app.cmp(&b.app)));
+    let total_size = groups.iter().map(|g| g.total_size).sum();
+    AppDataReport {
+        path: path.to_string(),
+        groups,
+        total_size,
+        total_size_formatted: format_size(total_size).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: i64 = 1024 * 1024;
+
+    #[test]
+    fn detects_mail_archives_and_app_databases() {
+        let u = "C:/Users/me";
+        let items = vec![
+            Item::test(&format!("{u}/Documents/Outlook Files/archive.pst"), 4000 * MB, false),
+            Item::test(&format!("{u}/AppData/Local/Microsoft/Outlook/me@corp.ost"), 2000 * MB, false),
+            Item::test(&format!("{u}/AppData/Roaming/Thunderbird/Profiles/x.default/Mail/pop/Inbox"), 300 * MB, false),
+            Item::test(&format!("{u}/AppData/Roaming/Thunderbird/Profiles/x.default/Mail/pop/Inbox.msf"), MB, false),
+            Item::test(&format!("{u}/AppData/Roaming/Thunderbird/Profiles/x.default/Mail/pop/Notes"), 300 * MB, false),
+            Item::test(&format!("{u}/AppData/Local/Google/Chrome/User Data/Default/History"), 120 * MB, false),
+            Item::test(&format!("{u}/AppData/Roaming/SomeElectronApp/Local Storage/leveldb"), 80 * MB, true),
+            Item::test(&format!("{u}/AppData/Roaming/SomeElectronApp/tiny.db"), MB, false),
+            Item::test(&format!("{u}/Videos/movie.mkv"), 5000 * MB, false),
+        ];
+        let report = analyze(u, &items);
+        let apps: Vec<_> = report.groups.iter().map(|g| g.app.as_str()).collect();
+        assert_eq!(apps, vec!["Outlook", "Thunderbird", "Chrome", "SomeElectronApp"]);
+
+        let outlook = &report.groups[0];
+        assert_eq!(outlook.total_size, 6000 * MB);
+        assert_eq!(outlook.files[0].kind, AppDataKind::Pst);
+        assert!(outlook.files[0].compactable && outlook.files[0].archivable);
+        assert!(!outlook.files[1].archivable);
+
+        // 无 .msf 的无扩展名文件不是 mbox
+        let thunderbird = &report.groups[1];
+        assert_eq!(thunderbird.files.len(), 1);
+        assert_eq!(thunderbird.files[0].kind, AppDataKind::Mbox);
+
+        assert_eq!(report.groups[2].files[0].kind, AppDataKind::Sqlite);
+        assert_eq!(report.groups[3].files[0].kind, AppDataKind::LevelDb);
+        assert_eq!(report.total_size, 6000 * MB + 300 * MB + 120 * MB + 80 * MB);
+    }
+}
@@ -0,0 +1,467 @@
+// 压缩为归档：zip 与 tar.zst
+//
+// 先遍历选中的文件 / 文件夹得到条目清单（不跟随符号链接，链接按链接本身存入），再流式写出：
+// 每个文件边读边压缩，不在内存中缓存整个文件。写入 "<dest>.part"，完成后改名为 dest，
+// 失败或取消时删除未写完的文件。读取失败（权限不足、文件被占用）的条目跳过并在结果中报告。
+//
+// 压缩后大小的估计按扩展名类别给出经验压缩率（estimated_ratio）：图片 / 音视频 / 压缩包几乎不可压缩，
+// 文本与代码约压到 1/4。zip 中几乎不可压缩的类别直接存储（Stored），省去无效的 deflate。
+// tar.zst 需要以 zstd 功能构建（与二进制协议的压缩相同）。
+
+use crate::categories::FileCategory;
+use crate::operation::{Cancelled, Operation};
+use crate::scan::{format_size, CompactString};
+use crate::selection::BulkFailure;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// tar.zst 的 zstd 压缩级别
+#[cfg(feature = "zstd")]
+const ZSTD_LEVEL: i32 = 3;
+
+/// 压缩率不低于此值的类别在 zip 中直接存储
+const STORE_RATIO: f64 = 0.95;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ArchiveFormat {
+    #[default]
+    Zip,
+    TarZst,
+}
+
+/// 压缩前的估计
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEstimate {
+    pub file_count: u64,
+    pub dir_count: u64,
+    pub input_size: i64,
+    #[schemars(with = "String")]
+    pub input_size_formatted: CompactString,
+    pub estimated_size: i64,
+    #[schemars(with = "String")]
+    pub estimated_size_formatted: CompactString,
+    /// estimated_size / input_size（空输入为 1）
+    pub estimated_ratio: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveResult {
+    pub dest: String,
+    pub format: ArchiveFormat,
+    /// 写入归档的文件数
+    pub file_count: u64,
+    pub input_size: i64,
+    pub archive_size: i64,
+    #[schemars(with = "String")]
+    pub archive_size_formatted: CompactString,
+    /// 压缩前的估计（与实际结果对照）
+    pub estimate: ArchiveEstimate,
+    /// 无法读取而跳过的条目
+    pub skipped: Vec<BulkFailure>,
+}
+
+/// 归档中的一个条目
+struct Entry {
+    path: PathBuf,
+    /// 归档内的相对路径（正斜杠）
+    name: String,
+    kind: EntryKind,
+    size: u64,
+    mode: u32,
+    mtime: i64,
+}
+
+enum EntryKind {
+    File,
+    Dir,
+    Symlink(PathBuf),
+}
+
+/// 类别的经验压缩率（deflate；zstd 约再小 10%）
+fn category_ratio(category: FileCategory) -> f64 {
+    match category {
+        FileCategory::Image | FileCategory::Audio => 0.97,
+        FileCategory::Video | FileCategory::Archive => 1.0,
+        FileCategory::Document => 0.45,
+        FileCategory::Code => 0.25,
+        FileCategory::Executable => 0.55,
+        FileCategory::DiskImage => 0.7,
+        FileCategory::Other => 0.6,
+    }
+}
+
+fn file_ratio(name: &str, format: ArchiveFormat) -> f64 {
+    let ratio = category_ratio(crate::categories::classify(name));
+    match format {
+        ArchiveFormat::TarZst if ratio < STORE_RATIO => ratio * 0.9,
+        _ => ratio,
+    }
+}
+
+#[cfg(unix)]
+fn mode_of(meta: &std::fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o7777
+}
+
+#[cfg(not(unix))]
+fn mode_of(meta: &std::fs::Metadata) -> u32 {
+    match (meta.is_dir(), meta.permissions().readonly()) {
+        (true, _) => 0o755,
+        (false, true) => 0o444,
+        (false, false) => 0o644,
+    }
+}
+
+/// 遍历 paths 得到条目清单；顶层重名时追加 " (2)"、" (3)"…
+fn collect(paths: &[String], skipped: &mut Vec<BulkFailure>) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    let mut top_names: Vec<String> = Vec::new();
+    for path in paths {
+        let display = crate::fs::display_path(path);
+        let base = display.trim_end_matches('/').rsplit('/').next().unwrap_or_default().to_string();
+        let mut name = if base.is_empty() || base.ends_with(':') { "root".to_string() } else { base };
+        if top_names.contains(&name) {
+            name = (2..)
+                .map(|n| format!("{} ({})", name, n))
+                .find(|candidate| !top_names.contains(candidate))
+                .unwrap_or_default();
+        }
+        top_names.push(name.clone());
+        walk(crate::fs::to_extended_length(path), name, &mut entries, skipped);
+    }
+    entries
+}
+
+fn walk(path: PathBuf, name: String, entries: &mut Vec<Entry>, skipped: &mut Vec<BulkFailure>) {
+    let fail = |skipped: &mut Vec<BulkFailure>, path: &Path, e: std::io::Error| {
+        skipped.push(BulkFailure { path: crate::fs::display_path(&path.to_string_lossy()), error: e.to_string() })
+    };
+    let meta = match std::fs::symlink_metadata(&path) {
+        Ok(meta) => meta,
+        Err(e) => return fail(skipped, &path, e),
+    };
+    let mtime = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs() as i64);
+    let kind = if meta.is_symlink() {
+        match std::fs::read_link(&path) {
+            Ok(target) => EntryKind::Symlink(target),
+            Err(e) => return fail(skipped, &path, e),
+        }
+    } else if meta.is_dir() {
+        EntryKind::Dir
+    } else {
+        EntryKind::File
+    };
+    let is_dir = matches!(kind, EntryKind::Dir);
+    let size = if matches!(kind, EntryKind::File) { meta.len() } else { 0 };
+    entries.push(Entry { path: path.clone(), name: name.clone(), kind, size, mode: mode_of(&meta), mtime });
+    if !is_dir {
+        return;
+    }
+
+    let children = match std::fs::read_dir(&path) {
+        Ok(children) => children,
+        Err(e) => return fail(skipped, &path, e),
+    };
+    let mut children: Vec<_> = children.flatten().collect();
+    children.sort_by_key(|c| c.file_name());
+    for child in children {
+        let child_name = format!("{}/{}", name, child.file_name().to_string_lossy());
+        walk(child.path(), child_name, entries, skipped);
+    }
+}
+
+fn estimate_entries(entries: &[Entry], format: ArchiveFormat) -> ArchiveEstimate {
+    let mut estimate = ArchiveEstimate {
+        file_count: 0,
+        dir_count: 0,
+        input_size: 0,
+        input_size_formatted: CompactString::new(),
+        estimated_size: 0,
+        estimated_size_formatted: CompactString::new(),
+        estimated_ratio: 1.0,
+    };
+    let mut estimated = 0.0;
+    for entry in entries {
+        match entry.kind {
+            EntryKind::Dir => estimate.dir_count += 1,
+            _ => {
+                estimate.file_count += 1;
+                estimate.input_size += entry.size as i64;
+                estimated += entry.size as f64 * file_ratio(&entry.name, format);
+            }
+        }
+    }
+    estimate.estimated_size = estimated.round() as i64;
+    estimate.estimated_ratio =
+        if estimate.input_size > 0 { estimate.estimated_size as f64 / estimate.input_size as f64 } else { 1.0 };
+    estimate.input_size_formatted = format_size(estimate.input_size);
+    estimate.estimated_size_formatted = format_size(estimate.estimated_size);
+    estimate
+}
+
+/// 压缩前估计：条目数、输入大小与按扩展名类别估计的压缩后大小
+pub fn estimate(paths: &[String], format: ArchiveFormat) -> ArchiveEstimate {
+    estimate_entries(&collect(paths, &mut Vec::new()), format)
+}
+
+/// 读取时上报字节数并检查取消
+struct ProgressReader<'a, R> {
+    inner: R,
+    on_read: &'a mut dyn FnMut(u64) -> Result<(), Cancelled>,
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        (self.on_read)(n as u64).map_err(std::io::Error::other)?;
+        Ok(n)
+    }
+}
+
+/// 把 paths 压缩为 dest，经 op 上报 "compress" 阶段的进度（按已读取字节）
+pub fn compress(paths: &[String], dest: &str, format: ArchiveFormat, op: &Operation) -> anyhow::Result<ArchiveResult> {
+    let mut skipped = Vec::new();
+    op.progress("collect", 0.0);
+    let entries = collect(paths, &mut skipped);
+    let estimate = estimate_entries(&entries, format);
+    op.check()?;
+
+    let dest_path = crate::fs::to_extended_length(dest);
+    let part = PathBuf::from(format!("{}.part", dest_path.to_string_lossy()));
+    let total = estimate.input_size.max(1) as f64;
+    let mut done = 0u64;
+    let mut on_read = |bytes: u64| {
+        done += bytes;
+        op.progress("compress", done as f64 * 100.0 / total);
+        op.check()
+    };
+
+    let file = std::io::BufWriter::new(std::fs::File::create(&part)?);
+    let written = match format {
+        ArchiveFormat::Zip => write_zip(file, &entries, &mut skipped, &mut on_read),
+        ArchiveFormat::TarZst => write_tar_zst(file, &entries, &mut skipped, &mut on_read),
+    };
+    let file_count = match written.and_then(|count| Ok((count, std::fs::rename(&part, &dest_path)?))) {
+        Ok((count, ())) => count,
+        Err(e) => {
+            std::fs::remove_file(&part).ok();
+            if op.is_cancelled() {
+                return Err(Cancelled.into());
+            }
+            return Err(e);
+        }
+    };
+
+    let dest = crate::fs::display_path(dest);
+    crate::scan::invalidate_caches_for(&dest);
+    let archive_size = std::fs::metadata(&dest_path).map_or(0, |m| m.len() as i64);
+    Ok(ArchiveResult {
+        dest,
+        format,
+        file_count,
+        input_size: estimate.input_size,
+        archive_size,
+        archive_size_formatted: format_size(archive_size),
+        estimate,
+        skipped,
+    })
+}
+
+/// 打开待压缩的文件；失败时记入 skipped 并返回 None
+fn open_entry(entry: &Entry, skipped: &mut Vec<BulkFailure>) -> Option<std::fs::File> {
+    std::fs::File::open(&entry.path)
+        .map_err(|e| {
+            skipped.push(BulkFailure { path: crate::fs::display_path(&entry.path.to_string_lossy()), error: e.to_string() })
+        })
+        .ok()
+}
+
+/// 写出 zip，返回写入的文件数
+fn write_zip<W: Write + std::io::Seek>(
+    out: W,
+    entries: &[Entry],
+    skipped: &mut Vec<BulkFailure>,
+    on_read: &mut dyn FnMut(u64) -> Result<(), Cancelled>,
+) -> anyhow::Result<u64> {
+    use zip::write::SimpleFileOptions;
+    use zip::CompressionMethod;
+
+    let mut zip = zip::ZipWriter::new(out);
+    let mut count = 0;
+    for entry in entries {
+        let mut options = SimpleFileOptions::default().unix_permissions(entry.mode);
+        if let Some(time) = zip_time(entry.mtime) {
+            options = options.last_modified_time(time);
+        }
+        match &entry.kind {
+            EntryKind::Dir => zip.add_directory(format!("{}/", entry.name), options)?,
+            EntryKind::Symlink(target) => {
+                zip.add_symlink(&entry.name, target.to_string_lossy().replace('\\', "/"), options)?
+            }
+            EntryKind::File => {
+                let Some(file) = open_entry(entry, skipped) else { continue };
+                let method = if file_ratio(&entry.name, ArchiveFormat::Zip) >= STORE_RATIO {
+                    CompressionMethod::Stored
+                } else {
+                    CompressionMethod::Deflated
+                };
+                let options = options.compression_method(method).large_file(entry.size >= u32::MAX as u64);
+                zip.start_file(&entry.name, options)?;
+                std::io::copy(&mut ProgressReader { inner: file, on_read: &mut *on_read }, &mut zip)?;
+                count += 1;
+            }
+        }
+    }
+    zip.finish()?.flush()?;
+    Ok(count)
+}
+
+/// Unix 秒 → zip 的本地时间（MS-DOS 时间只能表示 1980–2107 年）
+fn zip_time(mtime: i64) -> Option<zip::DateTime> {
+    use chrono::{Datelike, TimeZone, Timelike};
+
+    let local = chrono::Local.timestamp_opt(mtime, 0).single()?;
+    zip::DateTime::from_date_and_time(
+        u16::try_from(local.year()).ok()?,
+        local.month() as u8,
+        local.day() as u8,
+        local.hour() as u8,
+        local.minute() as u8,
+        local.second() as u8,
+    )
+    .ok()
+}
+
+#[cfg(feature = "zstd")]
+fn write_tar_zst<W: Write>(
+    out: W,
+    entries: &[Entry],
+    skipped: &mut Vec<BulkFailure>,
+    on_read: &mut dyn FnMut(u64) -> Result<(), Cancelled>,
+) -> anyhow::Result<u64> {
+    let mut tar = tar::Builder::new(zstd::Encoder::new(out, ZSTD_LEVEL)?);
+    let mut count = 0;
+    for entry in entries {
+        let mut header = tar::Header::new_gnu();
+        header.set_mode(entry.mode);
+        header.set_mtime(entry.mtime.max(0) as u64);
+        match &entry.kind {
+            EntryKind::Dir => {
+                header.set_entry_type(tar::EntryType::Directory);
+                header.set_size(0);
+                tar.append_data(&mut header, format!("{}/", entry.name), std::io::empty())?;
+            }
+            EntryKind::Symlink(target) => {
+                header.set_entry_type(tar::EntryType::Symlink);
+                header.set_size(0);
+                tar.append_link(&mut header, &entry.name, target)?;
+            }
+            EntryKind::File => {
+                let Some(file) = open_entry(entry, skipped) else { continue };
+                header.set_entry_type(tar::EntryType::Regular);
+                header.set_size(entry.size);
+                // tar 头中的长度已写定：文件在读取期间变大时截断、变小时补零，保证条目边界正确
+                let data = file.take(entry.size).chain(std::io::repeat(0)).take(entry.size);
+                let reader = ProgressReader { inner: data, on_read: &mut *on_read };
+                tar.append_data(&mut header, &entry.name, reader)?;
+                count += 1;
+            }
+        }
+    }
+    tar.into_inner()?.finish()?.flush()?;
+    Ok(count)
+}
+
+#[cfg(not(feature = "zstd"))]
+fn write_tar_zst<W: Write>(
+    _out: W,
+    _entries: &[Entry],
+    _skipped: &mut Vec<BulkFailure>,
+    _on_read: &mut dyn FnMut(u64) -> Result<(), Cancelled>,
+) -> anyhow::Result<u64> {
+    anyhow::bail!("此构建未启用 zstd 功能，无法生成 tar.zst")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zip_round_trips_tree_and_estimates_by_category() {
+        let dir = std::env::temp_dir().join(format!("flashdir-archive-{}", std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(dir.join("src/sub")).unwrap();
+        std::fs::write(dir.join("src/notes.txt"), "hello ".repeat(1000)).unwrap();
+        std::fs::write(dir.join("src/sub/photo.jpg"), vec![7u8; 1000]).unwrap();
+        let src = dir.join("src").to_string_lossy().to_string();
+
+        let estimate = estimate(std::slice::from_ref(&src), ArchiveFormat::Zip);
+        assert_eq!((estimate.file_count, estimate.dir_count, estimate.input_size), (2, 2, 7000));
+        assert_eq!(estimate.estimated_size, (6000.0 * 0.45 + 1000.0 * 0.97_f64).round() as i64);
+
+        let dest = dir.join("out.zip").to_string_lossy().to_string();
+        let op = Operation::start("compress_to_archive", None, None);
+        let result = compress(&[src.clone(), src], &dest, ArchiveFormat::Zip, &op).unwrap();
+        assert_eq!(result.file_count, 4);
+        assert!(result.skipped.is_empty());
+        assert!(result.archive_size > 0 && !Path::new(&format!("{}.part", dest)).exists());
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&dest).unwrap()).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        let expected = ["src/", "src/notes.txt", "src/sub/", "src/sub/photo.jpg"];
+        let mut expected: Vec<String> =
+            expected.iter().flat_map(|n| [n.to_string(), n.replacen("src", "src (2)", 1)]).collect();
+        expected.sort();
+        assert_eq!(names, expected);
+        let mut text = String::new();
+        archive.by_name("src/notes.txt").unwrap().read_to_string(&mut text).unwrap();
+        assert_eq!(text.len(), 6000);
+        assert_eq!(archive.by_name("src/sub/photo.jpg").unwrap().compression(), zip::CompressionMethod::Stored);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn tar_zst_round_trips_files_and_symlinks() {
+        let dir = std::env::temp_dir().join(format!("flashdir-archive-tar-{}", std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/a.log"), "line\n".repeat(500)).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("a.log", dir.join("src/link")).unwrap();
+        let src = dir.join("src").to_string_lossy().to_string();
+        let dest = dir.join("out.tar.zst").to_string_lossy().to_string();
+
+        let op = Operation::start("compress_to_archive", None, None);
+        let result = compress(&[src], &dest, ArchiveFormat::TarZst, &op).unwrap();
+        assert_eq!(result.file_count, 1);
+        assert!(result.archive_size < result.input_size);
+
+        let decoder = zstd::Decoder::new(std::fs::File::open(&dest).unwrap()).unwrap();
+        let mut entries: Vec<(String, tar::EntryType)> = tar::Archive::new(decoder)
+            .entries()
+            .unwrap()
+            .map(|e| {
+                let e = e.unwrap();
+                (e.path().unwrap().to_string_lossy().to_string(), e.header().entry_type())
+            })
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(entries[0], ("src/".to_string(), tar::EntryType::Directory));
+        assert_eq!(entries[1], ("src/a.log".to_string(), tar::EntryType::Regular));
+        #[cfg(unix)]
+        assert_eq!(entries[2], ("src/link".to_string(), tar::EntryType::Symlink));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,157 @@
+// 路径输入框自动补全
+//
+// 候选来源：收藏夹、扫描历史、已缓存的扫描结果、文件系统当前层目录列举。
+// 各来源按权重打分后合并去重，路径比较忽略分隔符差异（Windows 下同时忽略大小写）。
+// 只补全目录。
+
+use serde::Serialize;
+use schemars::JsonSchema;
+use std::collections::HashMap;
+
+/// 候选来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SuggestionSource {
+    Favorite,
+    History,
+    CacheIndex,
+    Filesystem,
+}
+
+impl SuggestionSource {
+    fn weight(self) -> i64 {
+        match self {
+            SuggestionSource::Favorite => 400,
+            SuggestionSource::History => 300,
+            SuggestionSource::CacheIndex => 200,
+            SuggestionSource::Filesystem => 100,
+        }
+    }
+}
+
+/// 单条补全建议
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PathSuggestion {
+    pub path: String,
+    pub source: SuggestionSource,
+    pub score: i64,
+}
+
+/// 候选（来源内的先后顺序作为次要排序依据，越靠前越相关）
+pub struct Candidate {
+    pub path: String,
+    pub source: SuggestionSource,
+}
+
+/// 比较用的规范化形式：统一为 `/`、去掉末尾分隔符，Windows 下小写
+pub fn normalize(path: &str) -> String {
+    let s = path.replace('\\', "/");
+    let s = if s.len() > 1 && s.ends_with('/') && !s.ends_with(":/") {
+        s.trim_end_matches('/').to_string()
+    } else {
+        s
+    };
+    if cfg!(target_os = "windows") {
+        s.to_lowercase()
+    } else {
+        s
+    }
+}
+
+/// 列举 prefix 所在目录下名称匹配的子目录
+///
+/// `C:\Us` → 在 `C:\` 下找以 `Us` 开头的目录；`C:\Users\` → 列出 `C:\Users` 的子目录
+pub fn list_dir_completions(prefix: &str, limit: usize) -> Vec<String> {
+    let Some(sep) = prefix.rfind(['/', '\\']) else {
+        return Vec::new();
+    };
+    let parent = &prefix[..=sep];
+    let partial = normalize(&prefix[sep + 1..]);
+
+    let Ok(entries) = std::fs::read_dir(parent) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .filter_map(|e| e.file_name().into_string().ok())
+        .filter(|name| normalize(name).starts_with(&partial))
+        .collect();
+    names.sort_unstable_by_key(|name| name.to_lowercase());
+    names.truncate(limit);
+
+    names.into_iter().map(|name| format!("{}{}", parent, name)).collect()
+}
+
+/// 对候选打分、按前缀过滤、去重（同一路径保留最高分来源）并截断
+pub fn rank(prefix: &str, candidates: Vec<Candidate>, limit: usize) -> Vec<PathSuggestion> {
+    let norm_prefix = normalize(prefix);
+    let prefix_depth = norm_prefix.matches('/').count();
+    let mut best: HashMap<String, PathSuggestion> = HashMap::new();
+    let mut rank_in_source: HashMap<SuggestionSource, i64> = HashMap::new();
+
+    for c in candidates {
+        let key = normalize(&c.path);
+        if !key.starts_with(&norm_prefix) {
+            continue;
+        }
+        let position = rank_in_source.entry(c.source).or_insert(0);
+        // 同来源内越靠前分越高（最多 50 分），越深的路径分越低
+        let score = c.source.weight() + (50 - *position).max(0)
+            - (key.matches('/').count().saturating_sub(prefix_depth) as i64) * 10
+            + if c.path.starts_with(prefix) { 5 } else { 0 };
+        *position += 1;
+
+        match best.get(&key) {
+            Some(existing) if existing.score >= score => {}
+            _ => {
+                best.insert(
+                    key,
+                    PathSuggestion {
+                        path: c.path,
+                        source: c.source,
+                        score,
+                    },
+                );
+            }
+        }
+    }
+
+    let mut suggestions: Vec<PathSuggestion> = best.into_values().collect();
+    suggestions.sort_unstable_by(|a, b| b.score.cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+    suggestions.truncate(limit);
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(path: &str, source: SuggestionSource) -> Candidate {
+        Candidate {
+            path: path.to_string(),
+            source,
+        }
+    }
+
+    #[test]
+    fn rank_dedups_and_prefers_weighted_sources() {
+        let candidates = vec![
+            c("/data/projects", SuggestionSource::Filesystem),
+            c("/data/photos", SuggestionSource::Filesystem),
+            c("/data/projects/", SuggestionSource::History),
+            c("/data/projects/rust", SuggestionSource::CacheIndex),
+            c("/home/user", SuggestionSource::Favorite),
+        ];
+        let result = rank("/data/p", candidates, 10);
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0].source, SuggestionSource::History);
+        assert_eq!(normalize(&result[0].path), "/data/projects");
+        assert!(result.iter().all(|s| s.path.starts_with("/data/p")));
+
+        assert_eq!(rank("/data/p", vec![c("/data/photos", SuggestionSource::Filesystem)], 0).len(), 0);
+    }
+}
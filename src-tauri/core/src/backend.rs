@@ -0,0 +1,175 @@
+// 扫描后端
+//
+// scan_directory 负责缓存命中、USN 增量、结果组装、缓存写入与性能指标，真正的遍历交给
+// ScannerBackend：MFT 直读、平台原生遍历器（FindFirstFileExW / getdents64 / getattrlistbulk）、
+// 标准库 read_dir。新增后端只需实现该 trait 并加入 candidates，不改动扫描主流程；
+// 显式指定后端（ScanOptions::backend）即可在同一目录上对比各实现。
+
+use crate::events::Events;
+use crate::fs::FastDirEntry;
+use crate::perf::PerformanceMonitor;
+use crate::scan::{ScanOutput, ScanProfile};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// 单层目录读取实现
+pub type ReadDirFn = fn(&Path) -> std::io::Result<Vec<FastDirEntry>>;
+
+/// 后端选择
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum BackendKind {
+    /// 先尝试 MFT 直读，不可用时回退到平台原生遍历器（网络路径、后台模式直接遍历）
+    #[default]
+    Auto,
+    /// 只用 MFT 直读（Windows + 管理员 + NTFS），不可用时报错
+    Mft,
+    /// 平台原生遍历器
+    Native,
+    /// 标准库 read_dir（各平台通用，作为对比基线）
+    Std,
+}
+
+/// 一次遍历的输入（缓存检查与 USN 增量已由 scan_directory 处理）
+pub struct ScanRequest {
+    /// 规范化后的根目录，可直接用于文件系统调用
+    pub canonical_path: PathBuf,
+    /// 缓存 key / 展示形式的根路径
+    pub root_dir: String,
+    pub perf_monitor: Arc<PerformanceMonitor>,
+    pub events: Option<Events>,
+    /// 时间预算截止时刻
+    pub deadline: Option<Instant>,
+    pub(crate) profile: ScanProfile,
+}
+
+impl ScanRequest {
+    pub fn is_network(&self) -> bool {
+        self.profile.is_network()
+    }
+
+    pub fn is_background(&self) -> bool {
+        self.profile.is_background()
+    }
+}
+
+/// 遍历后端
+pub trait ScannerBackend: Send + Sync {
+    /// 名称（事件日志、基准测试输出）
+    fn name(&self) -> &'static str;
+
+    /// 遍历 request 指定的目录。Ok(None) 表示该后端不适用于此路径（非 NTFS、无管理员权限等），
+    /// 由调度换下一个后端；Err 为遍历本身失败
+    fn scan(&self, request: &ScanRequest) -> anyhow::Result<Option<ScanOutput>>;
+}
+
+/// MFT 直读（Everything 式快速路径）
+pub struct MftBackend;
+
+impl ScannerBackend for MftBackend {
+    fn name(&self) -> &'static str {
+        "mft"
+    }
+
+    fn scan(&self, request: &ScanRequest) -> anyhow::Result<Option<ScanOutput>> {
+        Ok(crate::scan::try_mft_scan_path(
+            &request.canonical_path,
+            &request.root_dir,
+            &request.perf_monitor,
+            request.events.as_ref(),
+            request.profile.exclusions(),
+            request.profile.includes(),
+        ))
+    }
+}
+
+/// 并行目录遍历（scan v4），单层目录读取可替换
+pub struct WalkerBackend {
+    name: &'static str,
+    read_dir: ReadDirFn,
+}
+
+impl WalkerBackend {
+    /// 平台原生遍历器
+    pub fn native() -> Self {
+        Self { name: "native", read_dir: crate::fs::read_dir_entries }
+    }
+
+    /// 标准库 read_dir
+    pub fn std() -> Self {
+        Self { name: "std", read_dir: crate::fs::read_dir_entries_std }
+    }
+}
+
+impl ScannerBackend for WalkerBackend {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn scan(&self, request: &ScanRequest) -> anyhow::Result<Option<ScanOutput>> {
+        // 上次快照：目录遍历优先级提示 + 加权进度与剩余时间估计
+        let previous = crate::disk_cache::DiskCache::instance()
+            .get_previous(&crate::fs::cache_key(&request.root_dir))
+            .map(|(p, _)| p);
+        let size_hints = previous.as_ref().map(crate::scan::dir_size_hints).unwrap_or_default();
+        let progress = crate::progress::ProgressTracker::new(
+            &request.root_dir,
+            previous.iter().flat_map(|p| p.items.iter().map(|i| i.path.as_str())),
+        )
+        .with_previous_duration(previous.as_ref().map_or(0.0, |p| p.scan_time));
+        drop(previous);
+        crate::scan::scan_directory_optimized_v4(
+            &request.canonical_path,
+            &request.perf_monitor,
+            request.events.clone(),
+            request.deadline,
+            request.profile.clone(),
+            &size_hints,
+            &progress,
+            self.read_dir,
+        )
+        .map(Some)
+    }
+}
+
+/// 按选择与路径依次尝试的后端
+pub fn candidates(kind: BackendKind, network: bool, background: bool) -> Vec<Box<dyn ScannerBackend>> {
+    match kind {
+        // 网络路径没有可读取的 MFT；后台模式不做整卷 $MFT 的突发顺序读取，改为限速遍历
+        BackendKind::Auto if network || background => vec![Box::new(WalkerBackend::native())],
+        BackendKind::Auto => vec![Box::new(MftBackend), Box::new(WalkerBackend::native())],
+        BackendKind::Mft => vec![Box::new(MftBackend)],
+        BackendKind::Native => vec![Box::new(WalkerBackend::native())],
+        BackendKind::Std => vec![Box::new(WalkerBackend::std())],
+    }
+}
+
+/// 依次尝试候选后端，返回第一个适用后端的名称与结果（阻塞，需在 spawn_blocking 中调用）
+pub fn run(kind: BackendKind, request: &ScanRequest) -> anyhow::Result<(&'static str, ScanOutput)> {
+    for backend in candidates(kind, request.is_network(), request.is_background()) {
+        if let Some(output) = backend.scan(request)? {
+            return Ok((backend.name(), output));
+        }
+    }
+    Err(anyhow::anyhow!("没有适用于 {} 的扫描后端（{:?}）", request.root_dir, kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_falls_back_to_native_walker() {
+        let names = |kind, network, background| {
+            candidates(kind, network, background).iter().map(|b| b.name()).collect::<Vec<_>>()
+        };
+        assert_eq!(names(BackendKind::Auto, false, false), vec!["mft", "native"]);
+        assert_eq!(names(BackendKind::Auto, true, false), vec!["native"]);
+        assert_eq!(names(BackendKind::Auto, false, true), vec!["native"]);
+        assert_eq!(names(BackendKind::Std, true, false), vec!["std"]);
+        assert_eq!(names(BackendKind::Mft, false, true), vec!["mft"]);
+    }
+}
@@ -23,7 +23,7 @@ impl BinaryPayload {
         let original_size = serialized.len();
 
         #[cfg(feature = "zstd")]
-        if original_size > compress_threshold {
+        if original_size > _compress_threshold {
             use std::io::Cursor;
             if let Ok(compressed) = zstd::stream::encode_all(Cursor::new(&serialized), 3) {
                 if compressed.len() < original_size * 8 / 10 {
@@ -42,6 +42,43 @@ impl BinaryPayload {
             original_size,
         })
     }
+
+    /// 从字节解码负载并校验长度一致性（输入可能被截断或篡改，不能 panic）
+    pub fn decode(bytes: &[u8]) -> anyhow::Result<Self> {
+        let payload: Self = bincode::deserialize(bytes)?;
+        if !payload.compressed && payload.data.len() != payload.original_size {
+            anyhow::bail!(
+                "负载长度不一致: data={} original_size={}",
+                payload.data.len(),
+                payload.original_size
+            );
+        }
+        Ok(payload)
+    }
+
+    /// 取出未压缩的序列化数据
+    pub fn into_data(self) -> anyhow::Result<Vec<u8>> {
+        if !self.compressed {
+            return Ok(self.data);
+        }
+
+        #[cfg(feature = "zstd")]
+        {
+            use std::io::Read;
+            // 以声明的原始大小为上限解压，防止解压炸弹
+            let mut out = Vec::new();
+            zstd::stream::read::Decoder::new(std::io::Cursor::new(&self.data))?
+                .take(self.original_size as u64 + 1)
+                .read_to_end(&mut out)?;
+            if out.len() != self.original_size {
+                anyhow::bail!("解压后长度不一致: {} != {}", out.len(), self.original_size);
+            }
+            Ok(out)
+        }
+
+        #[cfg(not(feature = "zstd"))]
+        anyhow::bail!("负载已压缩，但未启用 zstd 特性")
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,6 +104,9 @@ pub struct OptimizedItem {
     pub size: i64,
     pub size_formatted: String,
     pub is_dir: bool,
+    pub modified: i64,
+    pub created: i64,
+    pub id: u64,
 }
 
 impl From<crate::scan::ScanResult> for OptimizedScanResult {
@@ -77,6 +117,9 @@ impl From<crate::scan::ScanResult> for OptimizedScanResult {
             size: item.size,
             size_formatted: item.size_formatted.to_string(),
             is_dir: item.is_dir,
+            modified: item.modified,
+            created: item.created,
+            id: item.id,
         }).collect();
 
         let items_data = BinarySerializer::serialize(&items).unwrap_or_default();
@@ -0,0 +1,293 @@
+// 缓存导出 / 导入：把磁盘缓存中的扫描结果打包为可移植文件，在另一台机器上导入后离线浏览
+// （如采集服务器的磁盘布局，带回本机分析）
+//
+// 文件为 zip（deflate）：
+// - manifest.json：格式标识与版本、导出时间、来源主机、各条目的路径 / 扫描时间 / 条目数 / 总大小
+// - entries/<序号>.json：扫描结果（JSON，不依赖磁盘缓存的 bincode 编码布局，不同版本之间也能读取）
+// 只导出完整结果（不含启用排除预设 / 包含模式的缓存）。写入 "<dest>.part"，完成后改名为 dest，
+// 失败或取消时删除未写完的文件。
+//
+// 导入的结果记下来源主机、mtime 记为 0：本机扫描同一路径时不会当作新鲜缓存命中，也不参与
+// USN 增量与子目录沿用，只供 scan::open_cached_result 按路径打开浏览。缓存 key 按本机规则
+// 由结果路径重新生成（Windows 上导出的 key 是小写的）。本机已有同一路径的缓存时默认跳过，
+// overwrite 时替换。
+
+use crate::disk_cache::DiskCache;
+use crate::operation::Operation;
+use crate::scan::{format_size, CompactString, ScanResult};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+/// manifest.json 中的格式标识
+pub const CACHE_FILE_FORMAT: &str = "flashdir-cache";
+/// 文件格式版本：manifest 或条目结构不兼容地变化时递增
+pub const CACHE_FILE_VERSION: u32 = 1;
+
+const MANIFEST: &str = "manifest.json";
+
+/// 导出文件的清单
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheManifest {
+    pub format: String,
+    pub version: u32,
+    /// 导出时间，Unix 秒
+    pub exported_at: i64,
+    /// 来源主机名
+    pub host: String,
+    /// 导出时的 FlashDir 版本
+    pub app_version: String,
+    pub entries: Vec<CacheManifestEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheManifestEntry {
+    /// 扫描根目录（展示形式）
+    pub path: String,
+    /// 包内的结果文件名
+    pub file: String,
+    /// 扫描时间，Unix 秒
+    pub scanned_at: i64,
+    pub item_count: usize,
+    pub total_size: i64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheExportResult {
+    pub dest: String,
+    /// 导出的扫描结果数
+    pub entries: usize,
+    pub file_size: i64,
+    #[schemars(with = "String")]
+    pub file_size_formatted: CompactString,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheImportResult {
+    /// 来源主机
+    pub host: String,
+    /// 导出时间，Unix 秒
+    pub exported_at: i64,
+    /// 已导入的扫描根目录（可直接按路径打开）
+    pub imported: Vec<String>,
+    /// 本机已有缓存而跳过的目录
+    pub skipped: Vec<String>,
+}
+
+/// 本机主机名（无法获取时为 "unknown"）
+fn host_name() -> String {
+    sysinfo::System::host_name().unwrap_or_else(|| "unknown".to_string())
+}
+
+/// 把 cache 中 paths 的扫描结果（None 为全部完整结果）导出到 dest，经 op 上报 "export" 阶段的进度
+pub fn export_cache(
+    cache: &DiskCache,
+    paths: Option<&[String]>,
+    dest: &str,
+    op: &Operation,
+) -> anyhow::Result<CacheExportResult> {
+    let entries = cache.list_entries()?;
+    let selected: Vec<(String, i64)> = match paths {
+        Some(paths) => paths
+            .iter()
+            .map(|path| {
+                let key = crate::scan::normalize_cache_key(path);
+                entries
+                    .iter()
+                    .find(|e| e.path == key)
+                    .map(|e| (key, e.scanned_at))
+                    .ok_or_else(|| anyhow::anyhow!("没有 {} 的扫描缓存", path))
+            })
+            .collect::<anyhow::Result<_>>()?,
+        None => entries.iter().filter(|e| !e.path.contains('|')).map(|e| (e.path.clone(), e.scanned_at)).collect(),
+    };
+    if selected.is_empty() {
+        anyhow::bail!("没有可导出的扫描缓存");
+    }
+
+    let dest_path = crate::fs::to_extended_length(dest);
+    let part = PathBuf::from(format!("{}.part", dest_path.to_string_lossy()));
+    let written = std::fs::File::create(&part)
+        .map_err(anyhow::Error::from)
+        .and_then(|file| write_package(cache, &selected, std::io::BufWriter::new(file), op));
+    if let Err(e) = written.and_then(|()| Ok(std::fs::rename(&part, &dest_path)?)) {
+        std::fs::remove_file(&part).ok();
+        return Err(e);
+    }
+    op.progress("export", 100.0);
+
+    let file_size = std::fs::metadata(&dest_path).map_or(0, |m| m.len() as i64);
+    Ok(CacheExportResult {
+        dest: crate::fs::display_path(dest),
+        entries: selected.len(),
+        file_size,
+        file_size_formatted: format_size(file_size),
+    })
+}
+
+fn write_package<W: Write + std::io::Seek>(
+    cache: &DiskCache,
+    selected: &[(String, i64)],
+    out: W,
+    op: &Operation,
+) -> anyhow::Result<()> {
+    use zip::write::SimpleFileOptions;
+    use zip::CompressionMethod;
+
+    let mut zip = zip::ZipWriter::new(out);
+    let mut manifest = CacheManifest {
+        format: CACHE_FILE_FORMAT.to_string(),
+        version: CACHE_FILE_VERSION,
+        exported_at: chrono::Utc::now().timestamp(),
+        host: host_name(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        entries: Vec::with_capacity(selected.len()),
+    };
+    for (i, (key, scanned_at)) in selected.iter().enumerate() {
+        op.check()?;
+        let result = cache
+            .get_stale(key)
+            .ok_or_else(|| anyhow::anyhow!("无法读取 {} 的扫描缓存", key))?;
+        let file = format!("entries/{:05}.json", i + 1);
+        let data = serde_json::to_vec(&result)?;
+        let options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .large_file(data.len() >= u32::MAX as usize);
+        zip.start_file(file.as_str(), options)?;
+        zip.write_all(&data)?;
+        manifest.entries.push(CacheManifestEntry {
+            path: result.path.to_string(),
+            file,
+            scanned_at: *scanned_at,
+            item_count: result.items.len(),
+            total_size: result.total_size,
+        });
+        op.progress("export", (i + 1) as f64 * 100.0 / selected.len() as f64);
+    }
+    zip.start_file(MANIFEST, SimpleFileOptions::default().compression_method(CompressionMethod::Deflated))?;
+    serde_json::to_writer_pretty(&mut zip, &manifest)?;
+    zip.finish()?.flush()?;
+    Ok(())
+}
+
+/// 读取 src 的清单（不导入）
+pub fn read_manifest(src: &str) -> anyhow::Result<CacheManifest> {
+    let file = std::fs::File::open(crate::fs::to_extended_length(src))?;
+    let mut zip = zip::ZipArchive::new(std::io::BufReader::new(file))
+        .map_err(|e| anyhow::anyhow!("不是 FlashDir 缓存文件: {}", e))?;
+    manifest_of(&mut zip)
+}
+
+fn manifest_of<R: Read + std::io::Seek>(zip: &mut zip::ZipArchive<R>) -> anyhow::Result<CacheManifest> {
+    let manifest: CacheManifest = serde_json::from_reader(
+        zip.by_name(MANIFEST).map_err(|_| anyhow::anyhow!("不是 FlashDir 缓存文件：缺少 {}", MANIFEST))?,
+    )?;
+    if manifest.format != CACHE_FILE_FORMAT {
+        anyhow::bail!("不是 FlashDir 缓存文件（格式 {}）", manifest.format);
+    }
+    if manifest.version > CACHE_FILE_VERSION {
+        anyhow::bail!("缓存文件由更新版本的 FlashDir 导出（格式版本 {}），请先升级", manifest.version);
+    }
+    Ok(manifest)
+}
+
+/// 把 src 中的扫描结果导入 cache，经 op 上报 "import" 阶段的进度
+pub fn import_cache(cache: &DiskCache, src: &str, overwrite: bool, op: &Operation) -> anyhow::Result<CacheImportResult> {
+    let file = std::fs::File::open(crate::fs::to_extended_length(src))?;
+    let mut zip = zip::ZipArchive::new(std::io::BufReader::new(file))
+        .map_err(|e| anyhow::anyhow!("不是 FlashDir 缓存文件: {}", e))?;
+    let manifest = manifest_of(&mut zip)?;
+
+    let mut imported = Vec::new();
+    let mut skipped = Vec::new();
+    let total = manifest.entries.len().max(1) as f64;
+    for (i, entry) in manifest.entries.iter().enumerate() {
+        op.check()?;
+        let key = crate::fs::cache_key(&entry.path);
+        if !overwrite && cache.contains(&key) {
+            skipped.push(entry.path.clone());
+            continue;
+        }
+        let reader = zip.by_name(&entry.file).map_err(|e| anyhow::anyhow!("{}: {}", entry.file, e))?;
+        let result: ScanResult = serde_json::from_reader(std::io::BufReader::new(reader))
+            .map_err(|e| anyhow::anyhow!("{}: {}", entry.file, e))?;
+        cache.insert_imported(&key, &result, entry.scanned_at, &manifest.host)?;
+        // 内存中同一路径的旧会话不再代表缓存内容
+        crate::scan::evict_memory_result(&key);
+        imported.push(entry.path.clone());
+        op.progress("import", (i + 1) as f64 * 100.0 / total);
+    }
+    op.progress("import", 100.0);
+
+    Ok(CacheImportResult { host: manifest.host, exported_at: manifest.exported_at, imported, skipped })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{CacheUsage, CacheSource};
+
+    fn temp_cache() -> (DiskCache, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("flashdir_transfer_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        (DiskCache::open(&dir.join("cache_v2.db")).unwrap(), dir)
+    }
+
+    fn result(path: &str, total_size: i64) -> ScanResult {
+        ScanResult {
+            items: Vec::new(),
+            total_size,
+            total_size_formatted: format_size(total_size),
+            scan_time: 0.0,
+            path: path.into(),
+            mft_available: false,
+            timing: None,
+            perf_metrics: None,
+            extension_stats: None,
+            category_stats: Vec::new(),
+            owners: None,
+            unscanned: None,
+            errors: Vec::new(),
+            unreadable_bytes: 0,
+            cloud_only_bytes: 0,
+            cloud_local_bytes: 0,
+            sparse_files: Vec::new(),
+            sparse_savings_bytes: 0,
+            cache_usage: CacheUsage { source: CacheSource::Fresh, reused_entries: 0, invalidated_entries: 0 },
+        }
+    }
+
+    #[test]
+    fn exports_and_imports_without_serving_as_fresh() {
+        let (server, server_dir) = temp_cache();
+        server.insert("/srv/data", &result("/srv/data", 1000), 50).unwrap();
+        server.insert("/srv/data|exclude=system", &result("/srv/data", 900), 50).unwrap();
+        let package = server_dir.join("server.fdcache").to_string_lossy().to_string();
+        let op = Operation::start("test", None, None);
+        let exported = export_cache(&server, None, &package, &op).unwrap();
+        assert_eq!(exported.entries, 1);
+        assert!(export_cache(&server, Some(&["/srv/missing".into()]), &package, &op).is_err());
+        assert_eq!(read_manifest(&package).unwrap().entries[0].total_size, 1000);
+
+        let (local, local_dir) = temp_cache();
+        let imported = import_cache(&local, &package, false, &op).unwrap();
+        assert_eq!(imported.imported, vec!["/srv/data".to_string()]);
+        assert_eq!(local.imported_from("/srv/data"), Some(imported.host.clone()));
+        // 只供浏览：任何 mtime 下都不是新鲜缓存
+        assert!(local.get("/srv/data", 1).is_none());
+        assert_eq!(local.get_stale("/srv/data").map(|r| r.total_size), Some(1000));
+
+        assert_eq!(import_cache(&local, &package, false, &op).unwrap().skipped, vec!["/srv/data".to_string()]);
+        assert_eq!(import_cache(&local, &package, true, &op).unwrap().imported.len(), 1);
+
+        std::fs::write(local_dir.join("bogus.fdcache"), b"not a zip").unwrap();
+        assert!(import_cache(&local, &local_dir.join("bogus.fdcache").to_string_lossy(), false, &op).is_err());
+        std::fs::remove_dir_all(server_dir).ok();
+        std::fs::remove_dir_all(local_dir).ok();
+    }
+}
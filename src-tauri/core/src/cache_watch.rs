@@ -0,0 +1,97 @@
+// 监视已打开的扫描根，树内有变更时立即把相关缓存标记为过期
+//
+// 缓存只按根目录 mtime 校验，而根目录 mtime 只反映直接子项的增删，子目录深处新增 / 修改的文件
+// 不会让缓存失效。扫描（或命中缓存）完成后监视该根目录的整棵树（见 fs::watch_tree），
+// 收到变更时把变更目录本身及其祖先目录的内存与磁盘缓存标记为过期：数据保留供浏览与对比，
+// 下次扫描不再直接命中，走增量 / 完整遍历。
+//
+// 同时监视的根至多 MAX_WATCHED 个，超出时停止最久未扫描的；同一目录的连续变更在 DEBOUNCE 内只处理一次。
+// 数据目录（~/.flashdir，缓存数据库所在）内的变更忽略，扫描主目录时写缓存不会让自身过期。
+// 不支持监视的平台只记录日志，缓存仍按根目录 mtime 校验。
+
+use crate::scan::Item;
+use lazy_static::lazy_static;
+use lru::LruCache;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 同时监视的扫描根数上限
+pub const MAX_WATCHED: usize = 8;
+
+/// 同一目录两次标记过期的最短间隔
+const DEBOUNCE: Duration = Duration::from_secs(1);
+
+lazy_static! {
+    /// 缓存 key → 监视句柄（drop 即停止）
+    static ref WATCHES: Mutex<LruCache<String, crate::fs::TreeWatch>> =
+        Mutex::new(LruCache::new(NonZeroUsize::new(MAX_WATCHED).unwrap()));
+    /// 目录 key → 上次标记过期的时间
+    static ref RECENT: Mutex<HashMap<String, Instant>> = Mutex::new(HashMap::new());
+}
+
+/// 开始监视扫描根 root（items 为其扫描结果，提供不递归监视的平台所需的子目录）；已在监视时只刷新顺序
+pub fn watch(root: &str, items: &[Item]) {
+    let key = crate::fs::cache_key(root);
+    let mut watches = WATCHES.lock();
+    if watches.get(&key).is_some() {
+        return;
+    }
+    let dirs: Vec<PathBuf> = items.iter().filter(|i| i.is_dir).map(|i| PathBuf::from(i.path.as_str())).collect();
+    match crate::fs::watch_tree(Path::new(root), dirs, on_change) {
+        Ok(watch) => {
+            watches.put(key, watch);
+        }
+        Err(e) => eprintln!("[CacheWatch] 无法监视 {}: {}", root, e),
+    }
+}
+
+/// 停止监视 root；返回之前是否在监视
+pub fn unwatch(root: &str) -> bool {
+    WATCHES.lock().pop(&crate::fs::cache_key(root)).is_some()
+}
+
+/// 正在监视的扫描根（缓存 key，最近扫描的在前）
+pub fn watched_roots() -> Vec<String> {
+    WATCHES.lock().iter().map(|(key, _)| key.clone()).collect()
+}
+
+fn on_change(dir: &Path) {
+    if crate::migration::data_dir().is_ok_and(|data| dir.starts_with(data)) {
+        return;
+    }
+    let key = crate::fs::cache_key(&dir.to_string_lossy());
+    if !debounce(&mut RECENT.lock(), &key, Instant::now()) {
+        return;
+    }
+    crate::scan::mark_caches_stale(&key);
+}
+
+/// 距上次处理 key 超过 DEBOUNCE 时记录本次并返回 true；顺带清理过时的记录
+fn debounce(recent: &mut HashMap<String, Instant>, key: &str, now: Instant) -> bool {
+    if recent.get(key).is_some_and(|last| now.duration_since(*last) < DEBOUNCE) {
+        return false;
+    }
+    if recent.len() >= 1024 {
+        recent.retain(|_, last| now.duration_since(*last) < DEBOUNCE);
+    }
+    recent.insert(key.to_string(), now);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debounces_repeated_changes_per_directory() {
+        let mut recent = HashMap::new();
+        let start = Instant::now();
+        assert!(debounce(&mut recent, "/a", start));
+        assert!(!debounce(&mut recent, "/a", start + Duration::from_millis(500)));
+        assert!(debounce(&mut recent, "/a/b", start + Duration::from_millis(500)));
+        assert!(debounce(&mut recent, "/a", start + DEBOUNCE));
+    }
+}
@@ -0,0 +1,190 @@
+// 文件类别：按扩展名把文件归入 媒体 / 文档 / 代码 / 压缩包 等类别
+//
+// 扩展名统计（compute_extension_stats）动辄上百个扩展名，不适合直接画饼图；
+// 这里把常见扩展名归入少数几个类别，扫描时为每个文件标注类别，
+// 返回结果时附带各类别的大小与数量，前端无需再遍历 items。
+// 未列出的扩展名与无扩展名文件归入 Other；目录、链接类条目与云占位符也标注为 Other，不计入统计。
+
+use crate::scan::{format_size, CompactString, Item, ItemKind};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// 文件类别（判别值即二进制协议中的编码，只能追加）。
+/// 生成 schema 时无文档注释的变体会被合并到最前面，新增变体须带文档注释以保持顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum FileCategory {
+    /// 未归类的文件，以及目录与链接类条目
+    #[default]
+    Other = 0,
+    /// 图片与相机原始格式
+    Image = 1,
+    /// 视频
+    Video = 2,
+    /// 音频
+    Audio = 3,
+    /// 文档、表格、演示文稿、电子书与纯文本
+    Document = 4,
+    /// 源代码、脚本与配置文件
+    Code = 5,
+    /// 压缩包
+    Archive = 6,
+    /// 可执行文件、动态库与安装包
+    Executable = 7,
+    /// 光盘镜像与虚拟磁盘
+    DiskImage = 8,
+}
+
+impl FileCategory {
+    pub const ALL: [FileCategory; 9] = [
+        FileCategory::Other,
+        FileCategory::Image,
+        FileCategory::Video,
+        FileCategory::Audio,
+        FileCategory::Document,
+        FileCategory::Code,
+        FileCategory::Archive,
+        FileCategory::Executable,
+        FileCategory::DiskImage,
+    ];
+}
+
+/// 扩展名长于此值的一律归入 Other（表中最长的扩展名为 7 个字符）
+const MAX_EXTENSION_LEN: usize = 8;
+
+/// 按文件名的扩展名分类（大小写不敏感）；目录请勿调用
+pub fn classify(name: &str) -> FileCategory {
+    let Some((stem, ext)) = name.rsplit_once('.') else {
+        return FileCategory::Other;
+    };
+    if stem.is_empty() || ext.is_empty() || ext.len() > MAX_EXTENSION_LEN {
+        return FileCategory::Other;
+    }
+    // 在栈上转小写，百万级文件不逐个分配 String
+    let mut buf = [0u8; MAX_EXTENSION_LEN];
+    let lower = &mut buf[..ext.len()];
+    lower.copy_from_slice(ext.as_bytes());
+    lower.make_ascii_lowercase();
+    let Ok(ext) = std::str::from_utf8(lower) else {
+        return FileCategory::Other;
+    };
+
+    match ext {
+        "jpg" | "jpeg" | "png" | "gif" | "bmp" | "webp" | "tif" | "tiff" | "heic" | "heif" | "avif" | "raw"
+        | "cr2" | "cr3" | "nef" | "arw" | "dng" | "svg" | "ico" | "psd" => FileCategory::Image,
+        "mp4" | "mkv" | "avi" | "mov" | "wmv" | "flv" | "webm" | "m4v" | "mpg" | "mpeg" | "m2ts" | "mts"
+        | "3gp" | "vob" | "rmvb" => FileCategory::Video,
+        "mp3" | "flac" | "wav" | "aac" | "ogg" | "m4a" | "wma" | "opus" | "aiff" | "ape" | "mid" | "midi" => {
+            FileCategory::Audio
+        }
+        "pdf" | "doc" | "docx" | "xls" | "xlsx" | "ppt" | "pptx" | "odt" | "ods" | "odp" | "rtf" | "txt"
+        | "md" | "epub" | "mobi" | "csv" | "pages" | "numbers" | "key" => FileCategory::Document,
+        // .ts 在开发目录中几乎都是 TypeScript，按代码计
+        "rs" | "c" | "h" | "cpp" | "hpp" | "cc" | "cs" | "java" | "kt" | "go" | "py" | "js" | "mjs" | "ts"
+        | "jsx" | "tsx" | "vue" | "html" | "htm" | "css" | "scss" | "json" | "xml" | "yaml" | "yml"
+        | "toml" | "ini" | "sh" | "ps1" | "bat" | "rb" | "php" | "swift" | "m" | "sql" | "lua" => {
+            FileCategory::Code
+        }
+        "zip" | "rar" | "7z" | "tar" | "gz" | "tgz" | "bz2" | "xz" | "zst" | "lz4" | "cab" => FileCategory::Archive,
+        "exe" | "dll" | "msi" | "msix" | "appx" | "so" | "dylib" | "sys" | "deb" | "rpm" | "apk" | "pkg" => {
+            FileCategory::Executable
+        }
+        "iso" | "img" | "vhd" | "vhdx" | "vmdk" | "vdi" | "qcow2" | "dmg" | "wim" | "esd" => FileCategory::DiskImage,
+        _ => FileCategory::Other,
+    }
+}
+
+/// 条目的类别：只有普通文件按扩展名分类，目录、链接类条目与云占位符为 Other
+#[inline]
+pub fn item_category(name: &str, kind: ItemKind) -> FileCategory {
+    if kind == ItemKind::File {
+        classify(name)
+    } else {
+        FileCategory::Other
+    }
+}
+
+/// 单个类别的聚合统计（仅统计文件）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryStat {
+    pub category: FileCategory,
+    pub total_size: i64,
+    #[schemars(with = "String")]
+    pub total_size_formatted: CompactString,
+    pub file_count: usize,
+    pub percent_of_total: f64,
+}
+
+/// 按类别聚合文件大小与数量，按总大小降序；没有文件的类别不列出
+pub fn compute_category_stats(items: &[Item]) -> Vec<CategoryStat> {
+    let mut totals = [(0i64, 0usize); FileCategory::ALL.len()];
+    for item in items.iter().filter(|i| i.kind == ItemKind::File) {
+        let entry = &mut totals[item.category as usize];
+        entry.0 += item.size;
+        entry.1 += 1;
+    }
+
+    let total_size: i64 = totals.iter().map(|(size, _)| size).sum();
+    let mut stats: Vec<CategoryStat> = FileCategory::ALL
+        .iter()
+        .zip(totals)
+        .filter(|(_, (_, count))| *count > 0)
+        .map(|(&category, (size, count))| CategoryStat {
+            category,
+            total_size: size,
+            total_size_formatted: format_size(size),
+            file_count: count,
+            percent_of_total: if total_size > 0 {
+                (size as f64 / total_size as f64) * 100.0
+            } else {
+                0.0
+            },
+        })
+        .collect();
+    stats.sort_by_key(|s| std::cmp::Reverse(s.total_size));
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, size: i64) -> Item {
+        Item { category: classify(name), ..Item::test_file(&format!("r/{}", name), size) }
+    }
+
+    #[test]
+    fn classifies_by_extension_and_aggregates() {
+        assert_eq!(classify("IMG_0001.JPG"), FileCategory::Image);
+        assert_eq!(classify("movie.final.mkv"), FileCategory::Video);
+        assert_eq!(classify("index.ts"), FileCategory::Code);
+        assert_eq!(classify("backup.tar.gz"), FileCategory::Archive);
+        assert_eq!(classify("Ubuntu.ISO"), FileCategory::DiskImage);
+        assert_eq!(classify(".bashrc"), FileCategory::Other);
+        assert_eq!(classify("Makefile"), FileCategory::Other);
+        assert_eq!(classify("data.averylongextension"), FileCategory::Other);
+        assert_eq!(classify("照片.档案"), FileCategory::Other);
+        assert_eq!(item_category("photos.jpg", ItemKind::Dir), FileCategory::Other);
+        assert_eq!(item_category("song.mp3", ItemKind::CloudPlaceholder), FileCategory::Other);
+
+        let mut items = vec![file("a.mp4", 700), file("b.mov", 100), file("c.pdf", 200), file("d", 0)];
+        let mut dir = file("dir.zip", 999);
+        dir.is_dir = true;
+        dir.kind = ItemKind::Dir;
+        dir.category = item_category(&dir.name, ItemKind::Dir);
+        items.push(dir);
+
+        let stats = compute_category_stats(&items);
+        let summary: Vec<_> = stats.iter().map(|s| (s.category, s.total_size, s.file_count)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (FileCategory::Video, 800, 2),
+                (FileCategory::Document, 200, 1),
+                (FileCategory::Other, 0, 1),
+            ]
+        );
+        assert_eq!(stats[0].percent_of_total, 80.0);
+    }
+}
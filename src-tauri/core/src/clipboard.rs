@@ -0,0 +1,264 @@
+// 复制到剪贴板：把扫描摘要或选中的条目格式化为文本 / Markdown / TSV，由后端直接写入系统剪贴板
+//
+// 选中数十万条时路径列表不经过 IPC 回传给前端再复制。
+// - 文本：摘要为逐行的“标签：值”；条目为每行一个路径（便于粘贴到终端或脚本）
+// - Markdown：摘要为标题 + 表格；条目为名称 / 路径 / 大小 / 修改时间表格（路径中的 | 转义）
+// - TSV：带表头，大小与时间为原始数值（字节、Unix 秒），粘贴到表格软件后可直接计算
+// 摘要取自 report::build_summary（不按内容确认重复文件），大小、数字与日期按 report_style 的区域格式。
+// 条目超过 MAX_CLIPBOARD_ROWS 时截断，结果中标明，完整列表应使用导出。
+
+use crate::report::ReportSummary;
+use crate::report_style::ReportStyle;
+use crate::scan::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+
+/// 一次复制的条目数上限
+pub const MAX_CLIPBOARD_ROWS: usize = 200_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ClipboardFormat {
+    #[default]
+    Text,
+    Markdown,
+    Tsv,
+}
+
+/// 复制的内容
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ClipboardContent {
+    /// 扫描摘要（总计、最大目录、扩展名分布、重复文件）
+    Summary,
+    /// 会话的选择集
+    Selection,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardResult {
+    pub content: ClipboardContent,
+    pub format: ClipboardFormat,
+    /// 写入的条目行数（摘要为 0）
+    pub rows: usize,
+    /// 写入的字符数
+    pub chars: usize,
+    /// 条目超过 MAX_CLIPBOARD_ROWS 被截断
+    pub truncated: bool,
+}
+
+/// 把扫描摘要格式化为 format
+pub fn format_summary(summary: &ReportSummary, style: &ReportStyle, format: ClipboardFormat) -> String {
+    let mut out = String::new();
+    let dup = &summary.duplicates;
+    // 写入 String 不会失败，以下忽略 writeln! 的返回值
+    match format {
+        ClipboardFormat::Text => {
+            let _ = writeln!(out, "{}", summary.title);
+            let _ = writeln!(out, "路径：{}", summary.root);
+            let _ = writeln!(out, "生成于：{}", style.format_date(summary.generated_at));
+            let _ = writeln!(out, "总大小：{}", style.format_size(summary.total_size));
+            let _ = writeln!(out, "文件数：{}", style.format_number(summary.file_count as i64));
+            let _ = writeln!(out, "目录数：{}", style.format_number(summary.dir_count as i64));
+            out.push_str("\n最大的目录：\n");
+            for d in &summary.top_directories {
+                let _ = writeln!(out, "  {}  {}", style.format_size(d.size), d.path);
+            }
+            out.push_str("\n扩展名分布：\n");
+            for e in &summary.extensions {
+                let _ = writeln!(
+                    out,
+                    "  {}  {}（{} 个文件）",
+                    e.extension,
+                    style.format_size(e.total_size),
+                    style.format_number(e.file_count as i64)
+                );
+            }
+            let _ = writeln!(
+                out,
+                "\n重复文件：{} 组，{} 个多余副本，共 {}",
+                style.format_number(dup.group_count as i64),
+                style.format_number(dup.duplicate_files as i64),
+                style.format_size(dup.wasted_bytes)
+            );
+        }
+        ClipboardFormat::Markdown => {
+            let _ = writeln!(out, "# {}\n", markdown_cell(&summary.title));
+            let _ = writeln!(out, "`{}` · 生成于 {}\n", summary.root.replace('`', "'"), style.format_date(summary.generated_at));
+            out.push_str("| 总大小 | 文件数 | 目录数 | 重复文件可释放 |\n|---:|---:|---:|---:|\n");
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} |\n",
+                style.format_size(summary.total_size),
+                style.format_number(summary.file_count as i64),
+                style.format_number(summary.dir_count as i64),
+                style.format_size(dup.wasted_bytes)
+            );
+            out.push_str("## 最大的目录\n\n| 路径 | 大小 | 文件数 |\n|---|---:|---:|\n");
+            for d in &summary.top_directories {
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} |",
+                    markdown_cell(&d.path),
+                    style.format_size(d.size),
+                    style.format_number(d.file_count as i64)
+                );
+            }
+            out.push_str("\n## 扩展名分布\n\n| 扩展名 | 大小 | 文件数 | 占比 |\n|---|---:|---:|---:|\n");
+            for e in &summary.extensions {
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} | {:.1}% |",
+                    markdown_cell(&e.extension),
+                    style.format_size(e.total_size),
+                    style.format_number(e.file_count as i64),
+                    e.percent_of_total
+                );
+            }
+            let _ = writeln!(
+                out,
+                "\n## 重复文件\n\n{} 组，{} 个多余副本，共 {}",
+                style.format_number(dup.group_count as i64),
+                style.format_number(dup.duplicate_files as i64),
+                style.format_size(dup.wasted_bytes)
+            );
+        }
+        ClipboardFormat::Tsv => {
+            out.push_str("section\tname\tsize\tcount\n");
+            let _ = writeln!(out, "total\t{}\t{}\t{}", tsv_cell(&summary.root), summary.total_size, summary.file_count);
+            for d in &summary.top_directories {
+                let _ = writeln!(out, "directory\t{}\t{}\t{}", tsv_cell(&d.path), d.size, d.file_count);
+            }
+            for e in &summary.extensions {
+                let _ = writeln!(out, "extension\t{}\t{}\t{}", tsv_cell(&e.extension), e.total_size, e.file_count);
+            }
+            let _ = writeln!(out, "duplicates\t\t{}\t{}", dup.wasted_bytes, dup.duplicate_files);
+        }
+    }
+    out
+}
+
+/// 把条目格式化为 format；返回文本与是否截断
+pub fn format_items(items: &[&Item], style: &ReportStyle, format: ClipboardFormat) -> (String, bool) {
+    let truncated = items.len() > MAX_CLIPBOARD_ROWS;
+    let items = &items[..items.len().min(MAX_CLIPBOARD_ROWS)];
+    let mut out = String::new();
+    match format {
+        ClipboardFormat::Text => {
+            for item in items {
+                let _ = writeln!(out, "{}", item.path);
+            }
+        }
+        ClipboardFormat::Markdown => {
+            out.push_str("| 名称 | 路径 | 大小 | 修改时间 |\n|---|---|---:|---|\n");
+            for item in items {
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} | {} |",
+                    markdown_cell(&item.name),
+                    markdown_cell(&item.path),
+                    style.format_size(item.size),
+                    style.format_date(item.modified)
+                );
+            }
+        }
+        ClipboardFormat::Tsv => {
+            out.push_str("path\tname\tsize\tisDir\tmodified\n");
+            for item in items {
+                let _ = writeln!(
+                    out,
+                    "{}\t{}\t{}\t{}\t{}",
+                    tsv_cell(&item.path),
+                    tsv_cell(&item.name),
+                    item.size,
+                    item.is_dir,
+                    item.modified
+                );
+            }
+        }
+    }
+    (out, truncated)
+}
+
+/// Markdown 表格单元格：转义竖线，换行换为空格
+fn markdown_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+/// TSV 单元格：制表符与换行换为空格（文件名中极少出现，不做引号转义）
+fn tsv_cell(s: &str) -> String {
+    s.replace(['\t', '\r', '\n'], " ")
+}
+
+/// 复制扫描摘要
+pub fn copy_summary(summary: &ReportSummary, style: &ReportStyle, format: ClipboardFormat) -> anyhow::Result<ClipboardResult> {
+    let text = format_summary(summary, style, format);
+    crate::fs::set_clipboard_text(&text)?;
+    Ok(ClipboardResult {
+        content: ClipboardContent::Summary,
+        format,
+        rows: 0,
+        chars: text.chars().count(),
+        truncated: false,
+    })
+}
+
+/// 复制会话 session 的选择集（全部选中项，不做祖先去重，顺序同扫描结果）
+pub fn copy_selection(
+    session: &str,
+    items: &[Item],
+    style: &ReportStyle,
+    format: ClipboardFormat,
+) -> anyhow::Result<ClipboardResult> {
+    let selected = crate::selection::SelectionStore::instance().ids(session);
+    if selected.is_empty() {
+        anyhow::bail!("没有选中的条目");
+    }
+    let chosen: Vec<&Item> = items.iter().filter(|i| selected.contains(&i.id)).collect();
+    let (text, truncated) = format_items(&chosen, style, format);
+    crate::fs::set_clipboard_text(&text)?;
+    Ok(ClipboardResult {
+        content: ClipboardContent::Selection,
+        format,
+        rows: chosen.len().min(MAX_CLIPBOARD_ROWS),
+        chars: text.chars().count(),
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{build_summary, ReportOptions};
+    use crate::report_style::Theme;
+    use crate::scan::format_size;
+
+    fn item(path: &str, size: i64, is_dir: bool) -> Item {
+        let item = Item::test(path, size, is_dir);
+        Item { size_formatted: format_size(size), file_count: 1, ..item }
+    }
+
+    #[test]
+    fn formats_summary_and_items() {
+        let items = vec![item("/r/a|b", 2048, true), item("/r/a|b/x\ty.txt", 2048, false)];
+        let style = ReportStyle::new(Theme::Light, "de-DE");
+        let op = crate::operation::Operation::start("test", None, None);
+        let summary = build_summary("/r", &items, &ReportOptions::default(), &op).unwrap();
+
+        let text = format_summary(&summary, &style, ClipboardFormat::Text);
+        assert!(text.contains("总大小：2,00 KB"));
+        let markdown = format_summary(&summary, &style, ClipboardFormat::Markdown);
+        assert!(markdown.contains("| /r/a\\|b | 2,00 KB | 1 |"));
+        let tsv = format_summary(&summary, &style, ClipboardFormat::Tsv);
+        assert!(tsv.lines().any(|l| l == "directory\t/r/a|b\t2048\t1"));
+
+        let refs: Vec<&Item> = items.iter().collect();
+        let (paths, truncated) = format_items(&refs, &style, ClipboardFormat::Text);
+        assert_eq!(paths, "/r/a|b\n/r/a|b/x\ty.txt\n");
+        assert!(!truncated);
+        let (tsv, _) = format_items(&refs, &style, ClipboardFormat::Tsv);
+        assert_eq!(tsv.lines().nth(2), Some("/r/a|b/x y.txt\tx y.txt\t2048\tfalse\t0"));
+    }
+}
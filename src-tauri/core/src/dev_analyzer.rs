@@ -9,6 +9,7 @@
 // - 每个文件/目录只归入一个最匹配的类别
 
 use serde::Serialize;
+use schemars::JsonSchema;
 use rayon::prelude::*;
 use std::collections::HashMap;
 use crate::scan::Item;
@@ -247,7 +248,7 @@ static KNOWN_PATTERNS: &[KnownPattern] = &[
 // ─── 输出结构 ────────────────────────────────────────────
 
 /// 单个开发类别的聚合统计
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DevCategoryStats {
     /// 类别标识符
@@ -277,7 +278,7 @@ pub struct DevCategoryStats {
 }
 
 /// 类别内 Top 项目
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DevTopItem {
     pub name: String,
@@ -286,7 +287,7 @@ pub struct DevTopItem {
 }
 
 /// 完整开发者分析结果
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DevAnalysisResult {
     /// 总扫描项数
@@ -363,7 +364,7 @@ pub fn analyze(items: &[Item], total_size: i64, total_items: usize) -> DevAnalys
         .collect();
 
     // 按总大小降序排序
-    categories.sort_unstable_by(|a, b| b.total_size.cmp(&a.total_size));
+    categories.sort_unstable_by_key(|i| std::cmp::Reverse(i.total_size));
 
     // 重新计算各分类占 dev 总体的百分比（排序后分配 percent_of_dev）
     for cat in &mut categories {
@@ -525,13 +526,8 @@ mod tests {
     use crate::scan::CompactString;
 
     fn item(path: &str, name: &str, size: i64, is_dir: bool) -> Item {
-        Item {
-            path: CompactString::from(path),
-            name: CompactString::from(name),
-            size,
-            size_formatted: CompactString::new(),
-            is_dir,
-        }
+        let item = Item::test(path, size, is_dir);
+        Item { name: CompactString::from(name), ..item }
     }
 
     #[test]
@@ -4,11 +4,12 @@
 // 用于磁盘空间变化追踪和增长趋势分析。
 
 use serde::Serialize;
+use schemars::JsonSchema;
 use std::collections::HashMap;
 use crate::scan::{Item, format_size};
 
 /// 差异结果
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SnapshotDiff {
     /// 新增文件/目录
@@ -31,10 +32,21 @@ pub struct SnapshotDiff {
     pub summary: DiffSummary,
 }
 
+/// 同一路径上一次扫描与当前扫描的比较结果
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanComparison {
+    pub path: String,
+    /// 上一次扫描结果写入缓存的时间（Unix 秒）
+    pub previous_cached_at: i64,
+    pub diff: SnapshotDiff,
+}
+
 /// 差异项
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DiffItem {
+    pub id: u64,
     pub path: String,
     pub name: String,
     pub size: i64,
@@ -43,9 +55,10 @@ pub struct DiffItem {
 }
 
 /// 修改项
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DiffModifyItem {
+    pub id: u64,
     pub path: String,
     pub name: String,
     pub old_size: i64,
@@ -58,7 +71,7 @@ pub struct DiffModifyItem {
 }
 
 /// 差异摘要
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DiffSummary {
     pub added_count: usize,
@@ -100,6 +113,7 @@ pub fn diff(old_items: &[Item], new_items: &[Item], old_total_size: i64) -> Snap
                 // 大小变了
                 let delta = new_item.size - old_item.size;
                 modified.push(DiffModifyItem {
+                    id: new_item.id,
                     path: new_item.path.to_string(),
                     name: new_item.name.to_string(),
                     old_size: old_item.size,
@@ -116,6 +130,7 @@ pub fn diff(old_items: &[Item], new_items: &[Item], old_total_size: i64) -> Snap
         } else {
             // 只在新结果中存在 → 新增
             added.push(DiffItem {
+                id: new_item.id,
                 path: new_item.path.to_string(),
                 name: new_item.name.to_string(),
                 size: new_item.size,
@@ -130,6 +145,7 @@ pub fn diff(old_items: &[Item], new_items: &[Item], old_total_size: i64) -> Snap
         if !new_map.contains_key(old_item.path.as_str()) {
             // 只在旧结果中存在 → 已删除
             removed.push(DiffItem {
+                id: old_item.id,
                 path: old_item.path.to_string(),
                 name: old_item.name.to_string(),
                 size: old_item.size,
@@ -154,9 +170,9 @@ pub fn diff(old_items: &[Item], new_items: &[Item], old_total_size: i64) -> Snap
     };
 
     // 按 size 降序排序
-    added.sort_unstable_by(|a, b| b.size.cmp(&a.size));
-    removed.sort_unstable_by(|a, b| b.size.cmp(&a.size));
-    modified.sort_unstable_by(|a, b| b.delta.abs().cmp(&a.delta.abs()));
+    added.sort_unstable_by_key(|i| std::cmp::Reverse(i.size));
+    removed.sort_unstable_by_key(|i| std::cmp::Reverse(i.size));
+    modified.sort_unstable_by_key(|i| std::cmp::Reverse(i.delta.abs()));
 
     SnapshotDiff {
         added_total_size,
@@ -0,0 +1,118 @@
+// 子目录 mtime 摘要：缓存命中前抽样校验深层目录
+//
+// 缓存按根目录 mtime 判断新鲜度，而根目录 mtime 只反映直接子项的增删，深层目录内新增 / 删除 / 重命名
+// 文件时缓存仍被当作新鲜结果。写入缓存时记录扫描结果中每个子目录的 mtime（路径相对扫描根），
+// 命中前抽样 stat 其中一部分：任一目录的当前 mtime 晚于记录值、或目录已不存在，即按未命中处理，
+// 走增量 / 完整遍历。抽样位置每次轮换（等距取样、起点递增），多次命中后覆盖全部目录。
+// 文件内容修改不改变目录 mtime，不在此检测（打开期间的修改见 cache_watch）。
+// 样本数由 settings.cache.validationSamples 控制，0 为关闭（只校验根目录 mtime）。
+
+use crate::scan::Item;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// 轮换的取样起点，各次校验依次递增
+static NEXT_OFFSET: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirDigest {
+    /// (相对扫描根的目录路径, 扫描时的 mtime)；mtime 未知的目录不记录
+    entries: Vec<(String, i64)>,
+}
+
+impl DirDigest {
+    /// 从扫描结果收集 root 下各子目录的 mtime
+    pub fn from_items(root: &str, items: &[Item]) -> Self {
+        let root = root.trim_end_matches(['/', '\\']);
+        let entries = items
+            .iter()
+            .filter(|item| item.is_dir && item.modified > 0)
+            .filter_map(|item| {
+                let relative = item.path.strip_prefix(root)?.trim_start_matches(['/', '\\']);
+                (!relative.is_empty()).then(|| (relative.to_string(), item.modified))
+            })
+            .collect();
+        Self { entries }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        bincode::serialize(self).unwrap_or_default()
+    }
+
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        bincode::deserialize(data).ok()
+    }
+
+    /// 等距抽取至多 samples 个目录（起点为 offset 在步长内的余数），返回相对路径与记录的 mtime
+    fn sample(&self, samples: usize, offset: usize) -> impl Iterator<Item = &(String, i64)> {
+        let step = self.entries.len().div_ceil(samples.max(1)).max(1);
+        self.entries.iter().skip(offset % step).step_by(step).take(samples)
+    }
+
+    /// 抽样 stat root 下的目录，返回第一个已变化（mtime 更晚或已不存在）的目录的绝对路径
+    pub fn find_changed(&self, root: &str, samples: usize) -> Option<String> {
+        if samples == 0 || self.entries.is_empty() {
+            return None;
+        }
+        let offset = NEXT_OFFSET.fetch_add(1, Ordering::Relaxed);
+        let root = root.split('|').next().unwrap_or(root).trim_end_matches(['/', '\\']);
+        self.sample(samples, offset).find_map(|(relative, recorded)| {
+            let path = format!("{}/{}", root, relative);
+            let current = std::fs::metadata(crate::fs::to_extended_length(&path))
+                .and_then(|m| m.modified())
+                .ok()
+                .map(|t| chrono::DateTime::<chrono::Local>::from(t).timestamp());
+            current.is_none_or(|mtime| mtime > *recorded).then_some(path)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dir(path: &str, modified: i64) -> Item {
+        Item { modified, ..Item::test_dir(path, 0) }
+    }
+
+    #[test]
+    fn samples_rotate_and_detect_nested_changes() {
+        let items: Vec<Item> = (0..10).map(|i| dir(&format!("/r/d{}", i), 100 + i)).collect();
+        let digest = DirDigest::from_items("/r/", &items);
+        assert_eq!(digest.len(), 10);
+        assert_eq!(DirDigest::decode(&digest.encode()), Some(digest.clone()));
+
+        // 步长 4：起点轮换 0..4，四次取样覆盖全部目录
+        let mut seen: Vec<&str> = (0..4).flat_map(|o| digest.sample(3, o).map(|(p, _)| p.as_str())).collect();
+        seen.sort();
+        seen.dedup();
+        assert_eq!(seen.len(), 10);
+
+        let root = std::env::temp_dir().join(format!("flashdir_digest_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("a/b/c")).unwrap();
+        let root_str = root.to_string_lossy().replace('\\', "/");
+        let mtime = |p: &std::path::Path| {
+            chrono::DateTime::<chrono::Local>::from(std::fs::metadata(p).unwrap().modified().unwrap()).timestamp()
+        };
+        let items = vec![
+            dir(&format!("{}/a", root_str), mtime(&root.join("a"))),
+            dir(&format!("{}/a/b", root_str), mtime(&root.join("a/b"))),
+            dir(&format!("{}/a/b/c", root_str), mtime(&root.join("a/b/c")) - 10),
+        ];
+        let digest = DirDigest::from_items(&root_str, &items);
+        assert_eq!(digest.find_changed(&root_str, 0), None);
+        assert_eq!(digest.find_changed(&root_str, 3), Some(format!("{}/a/b/c", root_str)));
+        std::fs::remove_dir_all(root.join("a/b/c")).unwrap();
+        let unchanged = DirDigest::from_items(&root_str, &items[..1]);
+        assert_eq!(unchanged.find_changed(&format!("{}|exclude=system", root_str), 8), None);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,148 @@
+// 非递归目录列举：文件浏览面板与路径补全
+//
+// 只读一次目录（平台快速遍历器，与扫描相同），不进入子目录、不读写缓存，
+// 通常几毫秒内返回。子目录的 size 为 0（不计算递归大小，需要时用 level_scan）。
+// 隐藏项按名称以 "." 开头判断。
+
+use crate::categories::{item_category, FileCategory};
+use crate::fs::ItemKind;
+use crate::scan::{format_size, CompactString};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// 排序方式；各方式下目录都排在文件之前
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ListSort {
+    /// 名称（不区分大小写）
+    #[default]
+    Name,
+    /// 大小降序
+    Size,
+    /// 修改时间，新的在前
+    Modified,
+    /// 扩展名，同扩展名按名称
+    Extension,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DirEntryInfo {
+    #[schemars(with = "String")]
+    pub path: CompactString,
+    #[schemars(with = "String")]
+    pub name: CompactString,
+    pub is_dir: bool,
+    /// 文件大小；目录为 0
+    pub size: i64,
+    #[schemars(with = "String")]
+    pub size_formatted: CompactString,
+    pub modified: i64,
+    pub created: i64,
+    pub kind: ItemKind,
+    pub category: FileCategory,
+    pub hidden: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DirListing {
+    #[schemars(with = "String")]
+    pub path: CompactString,
+    pub entries: Vec<DirEntryInfo>,
+    pub file_count: usize,
+    pub dir_count: usize,
+    /// 直接包含的文件大小之和（不含子目录内容）
+    pub files_size: i64,
+    pub elapsed_ms: f64,
+}
+
+fn extension(name: &str) -> &str {
+    name.rsplit_once('.').filter(|(stem, _)| !stem.is_empty()).map(|(_, ext)| ext).unwrap_or("")
+}
+
+fn sort_entries(entries: &mut [DirEntryInfo], sort: ListSort) {
+    entries.sort_by(|a, b| {
+        let by_name = || a.name.to_lowercase().cmp(&b.name.to_lowercase());
+        b.is_dir.cmp(&a.is_dir).then_with(|| match sort {
+            ListSort::Name => by_name(),
+            ListSort::Size => b.size.cmp(&a.size).then_with(by_name),
+            ListSort::Modified => b.modified.cmp(&a.modified).then_with(by_name),
+            ListSort::Extension => extension(&a.name)
+                .to_lowercase()
+                .cmp(&extension(&b.name).to_lowercase())
+                .then_with(by_name),
+        })
+    });
+}
+
+/// 列出 path 的直接子项
+pub fn list_directory(path: &str, sort: ListSort) -> anyhow::Result<DirListing> {
+    let start = std::time::Instant::now();
+    let canonical = std::fs::canonicalize(crate::fs::to_extended_length(path))?;
+    let raw = crate::fs::read_dir_entries(&canonical)?;
+
+    let mut entries: Vec<DirEntryInfo> = raw
+        .into_iter()
+        .map(|e| {
+            let size = if e.is_dir { 0 } else { e.size as i64 };
+            DirEntryInfo {
+                path: CompactString::from(crate::fs::display_path(&e.path.to_string_lossy()).as_str()),
+                category: item_category(&e.name, e.kind),
+                hidden: e.name.starts_with('.'),
+                name: CompactString::from(e.name),
+                is_dir: e.is_dir,
+                size,
+                size_formatted: format_size(size),
+                modified: e.modified,
+                created: e.created,
+                kind: e.kind,
+            }
+        })
+        .collect();
+    sort_entries(&mut entries, sort);
+
+    let dir_count = entries.iter().filter(|e| e.is_dir).count();
+    Ok(DirListing {
+        path: CompactString::from(crate::fs::display_path(&canonical.to_string_lossy()).as_str()),
+        file_count: entries.len() - dir_count,
+        dir_count,
+        files_size: entries.iter().map(|e| e.size).sum(),
+        entries,
+        elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_direct_entries_with_dirs_first() {
+        let root = std::env::temp_dir().join(format!("flashdir-listing-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("Zeta/inner")).unwrap();
+        std::fs::write(root.join("Zeta/inner/deep.bin"), vec![0u8; 4096]).unwrap();
+        std::fs::write(root.join("b.txt"), vec![0u8; 10]).unwrap();
+        std::fs::write(root.join("a.log"), vec![0u8; 300]).unwrap();
+        std::fs::write(root.join(".hidden"), b"x").unwrap();
+
+        let listing = list_directory(&root.to_string_lossy(), ListSort::Name).unwrap();
+        let names: Vec<_> = listing.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["Zeta", ".hidden", "a.log", "b.txt"]);
+        assert_eq!((listing.dir_count, listing.file_count, listing.files_size), (1, 3, 311));
+        // 不递归：子目录大小为 0
+        assert_eq!(listing.entries[0].size, 0);
+        assert!(listing.entries[1].hidden);
+
+        let by_size = list_directory(&root.to_string_lossy(), ListSort::Size).unwrap();
+        let names: Vec<_> = by_size.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["Zeta", "a.log", "b.txt", ".hidden"]);
+
+        let by_ext = list_directory(&root.to_string_lossy(), ListSort::Extension).unwrap();
+        let names: Vec<_> = by_ext.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["Zeta", ".hidden", "a.log", "b.txt"]);
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,2300 @@
+use anyhow::Result;
+use chrono;
+use parking_lot::{Condvar, Mutex};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use lazy_static::lazy_static;
+
+use crate::dir_digest::DirDigest;
+use crate::scan::ScanResult;
+use crate::global_search::IndexEntry;
+use crate::frecency::{PathVisit, VisitKind};
+use crate::size_history::SizePoint;
+use crate::operation::Operation;
+use crate::settings::{CacheSettings, Settings};
+
+/// 后台写入队列容量：每项是一次完整扫描结果的编码数据（可达数十 MB），队列满时 insert_async 等待
+const WRITE_QUEUE_CAPACITY: usize = 4;
+
+/// 排队等待写入的扫描结果（已在调用方线程编码）
+struct WriteJob {
+    path: String,
+    /// 结果的展示路径（path 为查找用的缓存 key，Windows 下为小写）
+    display_path: String,
+    data: Vec<u8>,
+    /// 编码后的子目录 mtime 摘要（见 dir_digest）
+    digest: Vec<u8>,
+    item_count: usize,
+    dir_mtime: i64,
+}
+
+impl WriteJob {
+    fn encode(path: &str, result: &ScanResult, dir_mtime: i64) -> Result<Self> {
+        Ok(Self {
+            path: path.to_string(),
+            display_path: result.path.to_string(),
+            data: bincode::serialize(result)?,
+            digest: DirDigest::from_items(&result.path, &result.items).encode(),
+            item_count: result.items.len(),
+            dir_mtime,
+        })
+    }
+}
+
+/// 磁盘缓存管理器
+pub struct DiskCache {
+    conn: Mutex<Connection>,
+    /// scan_cache 中扫描结果的总字节数（每次写入 / 删除后按表重新统计）
+    current_size: Mutex<u64>,
+    /// 后台写入线程的队列；未启动（DiskCache::open 直接打开的实例）时 insert_async 同步写入
+    writer: OnceLock<crossbeam::channel::Sender<WriteJob>>,
+    /// 已排队、尚未写完的路径 → 排队次数；读取 / 失效该路径前等待其写完，保证先写后读
+    pending: Mutex<HashMap<String, usize>>,
+    pending_done: Condvar,
+}
+
+lazy_static! {
+    static ref DISK_CACHE: Arc<DiskCache> = {
+        let cache = Arc::new(DiskCache::new().expect("Failed to initialize disk cache"));
+        cache.start_writer();
+        cache
+    };
+}
+
+impl DiskCache {
+    pub fn instance() -> Arc<DiskCache> {
+        DISK_CACHE.clone()
+    }
+
+    pub fn new() -> Result<Self> {
+        let cache_path = Self::get_cache_path()?;
+
+        if let Some(parent) = cache_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        Self::open(&cache_path)
+    }
+
+    /// 打开（必要时重建）path 处的缓存数据库：完整性检查失败的文件移到一旁重建，
+    /// 按版本表执行表结构迁移，扫描结果编码布局变化时清空扫描缓存
+    pub fn open(cache_path: &Path) -> Result<Self> {
+        let (conn, mut rebuilt) = open_checked(cache_path)?;
+        let conn = match migrate_schema(&conn) {
+            Ok(()) => conn,
+            Err(e) => {
+                // 迁移失败：保留旧文件，以当前结构重建
+                drop(conn);
+                let reason = format!("表结构迁移失败: {}", e);
+                move_aside(cache_path, &reason);
+                rebuilt = Some(reason);
+                let conn = connect(cache_path)?;
+                migrate_schema(&conn)?;
+                conn
+            }
+        };
+        Self::create_tables(&conn)?;
+        let cleared = check_result_format(&conn)?;
+
+        let current_size: i64 = conn.query_row(TOTAL_SIZE_SQL, [], |row| row.get(0)).unwrap_or(0);
+
+        let cache = Self {
+            conn: Mutex::new(conn),
+            current_size: Mutex::new(current_size.max(0) as u64),
+            writer: OnceLock::new(),
+            pending: Mutex::new(HashMap::new()),
+            pending_done: Condvar::new(),
+        };
+
+        cache.apply_policy(&Settings::current().cache)?;
+        for reason in rebuilt.iter().chain(cleared.iter()) {
+            eprintln!("[DiskCache] {}", reason);
+            cache.record_event(EventKind::CacheRebuild, "", reason).ok();
+        }
+
+        Ok(cache)
+    }
+
+    /// 按当前结构创建缺少的表与索引（已有的表由 migrate_schema 升级）
+    fn create_tables(conn: &Connection) -> Result<()> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS scan_cache (
+                path TEXT PRIMARY KEY,
+                data BLOB NOT NULL,
+                dir_mtime INTEGER NOT NULL,
+                created_at INTEGER NOT NULL,
+                size INTEGER NOT NULL,
+                item_count INTEGER NOT NULL,
+                hit_count INTEGER NOT NULL DEFAULT 0,
+                scanned_at INTEGER NOT NULL DEFAULT 0,
+                imported_from TEXT,
+                last_accessed INTEGER NOT NULL DEFAULT 0,
+                dir_digest BLOB,
+                display_path TEXT
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_created_at ON scan_cache(created_at)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_last_accessed ON scan_cache(last_accessed)",
+            [],
+        )?;
+
+        // ── 快照表：同一目录的多版本扫描历史 ──
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS snapshots (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                path TEXT NOT NULL,
+                scan_time INTEGER NOT NULL,
+                data BLOB NOT NULL,
+                total_size INTEGER NOT NULL,
+                total_size_formatted TEXT NOT NULL,
+                item_count INTEGER NOT NULL,
+                file_count INTEGER NOT NULL,
+                dir_count INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_snapshots_path_time ON snapshots(path, scan_time DESC)",
+            [],
+        )?;
+
+        // ── 全局搜索索引表：持久化全局索引条目 ──
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS global_index (
+                path TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                name_lower TEXT NOT NULL,
+                size INTEGER NOT NULL,
+                is_dir INTEGER NOT NULL,
+                drive TEXT NOT NULL,
+                mtime INTEGER NOT NULL,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_global_index_name_lower ON global_index(name_lower)",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_global_index_drive ON global_index(drive)",
+            [],
+        )?;
+
+        // ── 事件日志表：扫描/缓存操作/错误的时间线 ──
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp INTEGER NOT NULL,
+                kind TEXT NOT NULL,
+                path TEXT NOT NULL,
+                detail TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_events_timestamp ON events(timestamp)",
+            [],
+        )?;
+
+        // ── 上一次扫描结果表：新结果覆盖缓存前把旧结果移到这里，供 compare_scans 使用 ──
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS previous_scans (
+                path TEXT PRIMARY KEY,
+                data BLOB NOT NULL,
+                cached_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // ── 目录大小历史表：每次扫描记录的 (路径, 大小, 时间) ──
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS size_history (
+                path TEXT NOT NULL,
+                scan_time INTEGER NOT NULL,
+                size INTEGER NOT NULL,
+                PRIMARY KEY (path, scan_time)
+            )",
+            [],
+        )?;
+
+        // ── 路径访问统计表：frecency 排序 ──
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS path_visits (
+                path TEXT PRIMARY KEY,
+                scan_count INTEGER NOT NULL DEFAULT 0,
+                open_count INTEGER NOT NULL DEFAULT 0,
+                last_visit INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // ── 缓存命中统计：单行累计值，跨会话保留（清空缓存不重置） ──
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS cache_stats (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                hits INTEGER NOT NULL DEFAULT 0,
+                misses INTEGER NOT NULL DEFAULT 0,
+                bytes_served INTEGER NOT NULL DEFAULT 0,
+                decode_micros INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
+        conn.execute("INSERT OR IGNORE INTO cache_stats (id) VALUES (1)", [])?;
+
+        // ── 收藏夹表 ──
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS favorites (
+                path TEXT PRIMARY KEY,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    fn get_cache_path() -> Result<PathBuf> {
+        let home_dir = std::env::var("USERPROFILE")
+            .or_else(|_| std::env::var("HOME"))
+            .map_err(|_| anyhow::anyhow!("Cannot get home directory"))?;
+
+        let mut path = PathBuf::from(home_dir);
+        path.push(".flashdir");
+        path.push("cache_v2.db");
+        Ok(path)
+    }
+
+    pub fn get(&self, path: &str, dir_mtime: i64) -> Option<ScanResult> {
+        self.wait_pending(Some(path));
+        let policy = Settings::current().cache;
+
+        let row = self
+            .conn
+            .lock()
+            .query_row(
+                "SELECT data, dir_mtime, last_accessed, dir_digest FROM scan_cache WHERE path = ?1",
+                params![path],
+                |row| {
+                    Ok((
+                        row.get::<_, Vec<u8>>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, Option<Vec<u8>>>(3)?,
+                    ))
+                },
+            )
+            .optional()
+            .ok()
+            .flatten();
+
+        // 校验与解码时不持有连接锁（抽样 stat 在网络路径上可能较慢）
+        let hit = row.and_then(|(data, cached_mtime, accessed_at, digest)| {
+            // 过期条目留给下次清理删除，这里只是不再命中
+            if cached_mtime < dir_mtime || policy.is_expired(accessed_at) {
+                return None;
+            }
+            let changed = digest
+                .as_deref()
+                .and_then(DirDigest::decode)
+                .and_then(|digest| digest.find_changed(path, policy.validation_samples));
+            if let Some(changed) = changed {
+                eprintln!("[DiskCache] 子目录在缓存后有变化，按未命中处理: {}", changed);
+                return None;
+            }
+            let started = std::time::Instant::now();
+            let decoded = decode_scan_result(&data)?;
+            Some((decoded, data.len(), started.elapsed()))
+        });
+
+        let conn = self.conn.lock();
+        match hit {
+            Some((result, bytes, elapsed)) => {
+                let _ = conn.execute(
+                    "UPDATE scan_cache SET last_accessed = ?1, hit_count = hit_count + 1 WHERE path = ?2",
+                    params![chrono::Utc::now().timestamp(), path],
+                );
+                record_lookup(&conn, Some((bytes, elapsed)));
+                Some(result)
+            }
+            None => {
+                record_lookup(&conn, None);
+                None
+            }
+        }
+    }
+
+    /// 仅刷新缓存条目的 mtime 与访问时间（内容未变化时使用，不覆盖上一次扫描结果）
+    pub fn touch(&self, path: &str, dir_mtime: i64) -> Result<()> {
+        self.wait_pending(Some(path));
+        let conn = self.conn.lock();
+        conn.execute(
+            "UPDATE scan_cache SET dir_mtime = ?1, last_accessed = ?2, scanned_at = ?2 WHERE path = ?3",
+            params![dir_mtime, chrono::Utc::now().timestamp(), path],
+        )?;
+        Ok(())
+    }
+
+    /// 获取被最近一次扫描覆盖的上一次扫描结果及其缓存时间（Unix 秒）
+    pub fn get_previous(&self, path: &str) -> Option<(ScanResult, i64)> {
+        self.wait_pending(Some(path));
+        let conn = self.conn.lock();
+
+        let row: Option<(Vec<u8>, i64)> = conn
+            .query_row(
+                "SELECT data, cached_at FROM previous_scans WHERE path = ?1",
+                params![path],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .ok()
+            .flatten();
+
+        row.and_then(|(data, cached_at)| {
+            decode_scan_result(&data).map(|r| (r, cached_at))
+        })
+    }
+
+    /// 获取缓存的扫描结果，忽略 mtime 检查（用于 USN 增量更新）
+    /// 返回即使缓存已过期也能使用的数据
+    pub fn get_stale(&self, path: &str) -> Option<ScanResult> {
+        self.wait_pending(Some(path));
+        let conn = self.conn.lock();
+
+        let data: Option<Vec<u8>> = conn
+            .query_row(
+                "SELECT data FROM scan_cache WHERE path = ?1",
+                params![path],
+                |row| row.get(0),
+            )
+            .optional()
+            .ok()
+            .flatten();
+
+        data.and_then(|d| decode_scan_result(&d))
+    }
+
+    /// 同步写入（等待该路径排队中的写入完成，保持先后顺序）
+    pub fn insert(&self, path: &str, result: &ScanResult, dir_mtime: i64) -> Result<()> {
+        let job = WriteJob::encode(path, result, dir_mtime)?;
+        self.wait_pending(Some(path));
+        self.write_encoded(job)
+    }
+
+    /// 在调用方线程编码后交给后台线程写入 SQLite，扫描结果不必等待写盘即可返回；
+    /// 写入失败只记录日志（与同步写入时调用方忽略错误的处理一致）
+    pub fn insert_async(&self, path: &str, result: &ScanResult, dir_mtime: i64) {
+        if Settings::current().cache.never_caches(path) {
+            return;
+        }
+        let job = match WriteJob::encode(path, result, dir_mtime) {
+            Ok(job) => job,
+            Err(e) => {
+                eprintln!("[DiskCache] 编码扫描结果失败 {}: {}", path, e);
+                return;
+            }
+        };
+        let Some(writer) = self.writer.get() else {
+            self.run_job(job);
+            return;
+        };
+        *self.pending.lock().entry(job.path.clone()).or_default() += 1;
+        if let Err(e) = writer.send(job) {
+            // 写入线程已退出：退回同步写入
+            let job = e.into_inner();
+            let path = job.path.clone();
+            self.run_job(job);
+            self.finish_pending(&path);
+        }
+    }
+
+    /// 等待所有排队中的写入完成（退出前调用，避免丢失刚完成的扫描结果）
+    pub fn flush(&self) {
+        self.wait_pending(None);
+    }
+
+    fn start_writer(self: &Arc<Self>) {
+        let (tx, rx) = crossbeam::channel::bounded::<WriteJob>(WRITE_QUEUE_CAPACITY);
+        let cache = Arc::clone(self);
+        let spawned = std::thread::Builder::new()
+            .name("disk-cache-writer".into())
+            .spawn(move || {
+                for job in rx {
+                    let path = job.path.clone();
+                    cache.run_job(job);
+                    cache.finish_pending(&path);
+                }
+            });
+        match spawned {
+            Ok(_) => {
+                let _ = self.writer.set(tx);
+            }
+            Err(e) => eprintln!("[DiskCache] 无法启动后台写入线程，改为同步写入: {}", e),
+        }
+    }
+
+    fn run_job(&self, job: WriteJob) {
+        let path = job.path.clone();
+        if let Err(e) = self.write_encoded(job) {
+            eprintln!("[DiskCache] 写入缓存失败 {}: {}", path, e);
+        }
+    }
+
+    fn finish_pending(&self, path: &str) {
+        let mut pending = self.pending.lock();
+        if let Some(count) = pending.get_mut(path) {
+            *count -= 1;
+            if *count == 0 {
+                pending.remove(path);
+            }
+        }
+        self.pending_done.notify_all();
+    }
+
+    /// 等待 path（None 表示全部）排队中的写入完成；不得在写入线程中调用
+    fn wait_pending(&self, path: Option<&str>) {
+        let mut pending = self.pending.lock();
+        while path.map_or(!pending.is_empty(), |p| pending.contains_key(p)) {
+            self.pending_done.wait(&mut pending);
+        }
+    }
+
+    fn write_encoded(&self, job: WriteJob) -> Result<()> {
+        let WriteJob { path, display_path, data, digest, item_count, dir_mtime } = job;
+        let path = path.as_str();
+        let policy = Settings::current().cache;
+        if policy.never_caches(path) {
+            return Ok(());
+        }
+        let size = data.len();
+        self.maybe_cleanup(path, size, &policy)?;
+
+        {
+            let conn = self.conn.lock();
+            conn.execute(
+                "INSERT OR REPLACE INTO previous_scans (path, data, cached_at)
+                 SELECT path, data, created_at FROM scan_cache WHERE path = ?1",
+                params![path],
+            )?;
+            conn.execute(
+                "INSERT OR REPLACE INTO scan_cache
+                     (path, data, dir_mtime, created_at, size, item_count, scanned_at, last_accessed, hit_count, dir_digest, display_path)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?4, ?4, COALESCE((SELECT hit_count FROM scan_cache WHERE path = ?1), 0), ?7, ?8)",
+                params![
+                    path,
+                    data,
+                    dir_mtime,
+                    chrono::Utc::now().timestamp(),
+                    size,
+                    item_count,
+                    digest,
+                    display_path
+                ],
+            )?;
+        }
+        self.recompute_size();
+
+        Ok(())
+    }
+
+    /// 删除过期（超过有效期未被访问）的缓存、上一次扫描结果、历史与事件；返回删除的扫描结果条数
+    fn cleanup_old_entries(&self, policy: &CacheSettings) -> Result<usize> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::seconds(policy.ttl_secs());
+
+        let conn = self.conn.lock();
+        let removed = conn.execute(
+            "DELETE FROM scan_cache WHERE last_accessed < ?1",
+            params![cutoff.timestamp()],
+        )?;
+
+        // 上一次扫描结果：保留 30 天
+        let previous_cutoff = chrono::Utc::now() - chrono::Duration::days(30);
+        conn.execute(
+            "DELETE FROM previous_scans WHERE cached_at < ?1",
+            params![previous_cutoff.timestamp()],
+        )?;
+
+        // 目录大小历史：保留 1 年
+        let history_cutoff = chrono::Utc::now() - chrono::Duration::days(365);
+        conn.execute(
+            "DELETE FROM size_history WHERE scan_time < ?1",
+            params![history_cutoff.timestamp()],
+        )?;
+
+        // 事件日志：30 天 TTL，且最多保留最近 10000 条
+        let event_cutoff = chrono::Utc::now() - chrono::Duration::days(30);
+        conn.execute(
+            "DELETE FROM events WHERE timestamp < ?1",
+            params![event_cutoff.timestamp_millis()],
+        )?;
+        conn.execute(
+            "DELETE FROM events WHERE id NOT IN (
+                SELECT id FROM events ORDER BY id DESC LIMIT 10000
+            )",
+            [],
+        )?;
+
+        Ok(removed)
+    }
+
+    /// 按 policy 立即整理已有的缓存：删除过期条目与 never_cache 目录的条目，
+    /// 总大小超过上限时淘汰最久未访问的条目；返回删除的扫描结果条数
+    pub fn apply_policy(&self, policy: &CacheSettings) -> Result<usize> {
+        self.flush();
+        let mut removed = self.cleanup_old_entries(policy)?;
+        {
+            let conn = self.conn.lock();
+            if !policy.never_cache.is_empty() {
+                let mut stmt = conn.prepare("SELECT path FROM scan_cache")?;
+                let excluded: Vec<String> = stmt
+                    .query_map([], |row| row.get::<_, String>(0))?
+                    .filter_map(|r| r.ok())
+                    .filter(|path| policy.never_caches(path))
+                    .collect();
+                for path in &excluded {
+                    removed += conn.execute("DELETE FROM scan_cache WHERE path = ?1", params![path])?;
+                    conn.execute("DELETE FROM previous_scans WHERE path = ?1", params![path])?;
+                }
+            }
+
+            // 从最久未访问的条目开始淘汰，直到总大小不超过上限
+            let mut total: i64 = conn.query_row(TOTAL_SIZE_SQL, [], |row| row.get(0))?;
+            let max_bytes = policy.max_disk_bytes() as i64;
+            if total > max_bytes {
+                for (table, path, size) in lru_entries(&conn, None)? {
+                    if total <= max_bytes {
+                        break;
+                    }
+                    let n = conn.execute(&format!("DELETE FROM {} WHERE path = ?1", table), params![path])?;
+                    if table == "scan_cache" {
+                        removed += n;
+                    }
+                    total -= size;
+                }
+            }
+            *self.current_size.lock() = total.max(0) as u64;
+        }
+        if removed > 0 {
+            self.record_event(EventKind::CacheInvalidate, "", &format!("按缓存策略删除 {} 条缓存", removed))
+                .ok();
+        }
+        Ok(removed)
+    }
+
+    /// 写入 path（new_entry_size 字节）之前检查总大小：超过上限时按最久未访问的顺序淘汰其他条目
+    /// （含上一次扫描结果），一次腾到上限的 3/4，避免之后每次写入都触发淘汰。
+    /// 同一路径的旧条目会转存为上一次扫描结果，被它覆盖的旧的上一次扫描结果不计入
+    fn maybe_cleanup(&self, path: &str, new_entry_size: usize, policy: &CacheSettings) -> Result<()> {
+        let max_bytes = policy.max_disk_bytes();
+        let conn = self.conn.lock();
+        let replaced: i64 = conn
+            .query_row(
+                "SELECT length(data) FROM previous_scans
+                 WHERE path = ?1 AND EXISTS(SELECT 1 FROM scan_cache WHERE path = ?1)",
+                params![path],
+                |row| row.get(0),
+            )
+            .optional()?
+            .unwrap_or(0);
+        let mut total = self.current_size.lock().saturating_sub(replaced.max(0) as u64) + new_entry_size as u64;
+        if total <= max_bytes {
+            return Ok(());
+        }
+
+        let target = max_bytes - max_bytes / 4;
+        let mut evicted = 0;
+        for (table, victim, size) in lru_entries(&conn, Some(path))? {
+            if total <= target {
+                break;
+            }
+            let n = conn.execute(&format!("DELETE FROM {} WHERE path = ?1", table), params![victim])?;
+            if table == "scan_cache" {
+                evicted += n;
+            }
+            total = total.saturating_sub(size.max(0) as u64);
+        }
+        if evicted > 0 {
+            eprintln!("[DiskCache] 超过 {} 上限，淘汰 {} 条最久未访问的缓存", crate::scan::format_size(max_bytes as i64), evicted);
+        }
+        Ok(())
+    }
+
+    pub fn clear(&self) -> Result<()> {
+        self.clear_with(&Operation::start("clear_disk_cache", None, None))
+    }
+
+    /// 清空扫描缓存：分批删除（每批之间释放连接锁、上报进度、检查取消），
+    /// 取消时已删除的部分不恢复，缓存占用按剩余条目重新统计
+    pub fn clear_with(&self, op: &Operation) -> Result<()> {
+        const TABLES: [&str; 2] = ["scan_cache", "previous_scans"];
+        // 每条缓存是一次扫描的完整结果（可达数十 MB），批次不宜大
+        const BATCH: usize = 16;
+
+        self.flush();
+
+        let total: i64 = {
+            let conn = self.conn.lock();
+            TABLES
+                .iter()
+                .map(|t| conn.query_row(&format!("SELECT COUNT(*) FROM {}", t), [], |row| row.get::<_, i64>(0)))
+                .sum::<rusqlite::Result<i64>>()?
+        };
+
+        let mut deleted = 0usize;
+        let outcome: Result<()> = TABLES.iter().try_for_each(|table| loop {
+            op.check()?;
+            let n = self.conn.lock().execute(
+                &format!("DELETE FROM {0} WHERE rowid IN (SELECT rowid FROM {0} LIMIT {1})", table, BATCH),
+                [],
+            )?;
+            if n == 0 {
+                return Ok(());
+            }
+            deleted += n;
+            op.progress("delete", deleted as f64 * 100.0 / total.max(1) as f64);
+        });
+
+        self.recompute_size();
+        let detail = if outcome.is_err() { format!("中止，已删除 {} 条", deleted) } else { String::new() };
+        self.record_event(EventKind::CacheClear, "", &detail).ok();
+        outcome
+    }
+
+    /// 数据库文件中空闲页（删除缓存留下、尚未归还文件系统）的字节数
+    pub fn free_bytes(&self) -> u64 {
+        page_usage(&self.conn.lock()).map(|(_, free)| free).unwrap_or(0)
+    }
+
+    /// 压缩数据库文件，把删除缓存留下的空闲页归还文件系统。
+    /// 未启用增量回收的旧数据库先切换为 auto_vacuum=INCREMENTAL 并整体 VACUUM 一次（期间持有连接锁，不可取消）；
+    /// 之后分批 incremental_vacuum，每批之间释放连接锁、上报进度、检查取消，取消时已回收的部分保留
+    pub fn compact(&self, op: &Operation) -> Result<CompactResult> {
+        // 每批归还的页数（默认页大小 4 KB 时为 4 MB）
+        const BATCH_PAGES: u64 = 1024;
+
+        self.flush();
+        let start = std::time::Instant::now();
+        let (before_bytes, free_bytes, page_size, auto_vacuum) = {
+            let conn = self.conn.lock();
+            let (file, free) = page_usage(&conn)?;
+            (file, free, pragma_i64(&conn, "page_size")?.max(1) as u64, pragma_i64(&conn, "auto_vacuum")?)
+        };
+        op.check()?;
+
+        // auto_vacuum: 0 = NONE, 1 = FULL, 2 = INCREMENTAL
+        let full_vacuum = auto_vacuum != 2;
+        let mut completed = true;
+        if full_vacuum {
+            op.progress("vacuum", 0.0);
+            self.conn.lock().execute_batch("PRAGMA auto_vacuum = INCREMENTAL; VACUUM;")?;
+        } else {
+            // 只回收开始时已有的空闲页，期间新删除留下的空闲页留到下一次
+            let total_pages = free_bytes / page_size;
+            for _ in 0..total_pages.div_ceil(BATCH_PAGES) {
+                if op.is_cancelled() {
+                    completed = false;
+                    break;
+                }
+                let remaining = {
+                    let conn = self.conn.lock();
+                    // 每步归还一页，须逐行执行完
+                    let mut stmt = conn.prepare(&format!("PRAGMA incremental_vacuum({})", BATCH_PAGES))?;
+                    let mut rows = stmt.query([])?;
+                    while rows.next()?.is_some() {}
+                    pragma_i64(&conn, "freelist_count")?.max(0) as u64
+                };
+                if remaining == 0 {
+                    break;
+                }
+                let reclaimed_pages = total_pages.saturating_sub(remaining);
+                op.progress("vacuum", reclaimed_pages as f64 * 100.0 / total_pages as f64);
+            }
+        }
+
+        let (after_bytes, _) = page_usage(&self.conn.lock())?;
+        let result = CompactResult {
+            before_bytes,
+            after_bytes,
+            reclaimed_bytes: before_bytes.saturating_sub(after_bytes),
+            full_vacuum,
+            completed,
+            duration_ms: start.elapsed().as_millis() as u64,
+        };
+        let detail = format!(
+            "{}回收 {}{}",
+            if full_vacuum { "整体 VACUUM，" } else { "" },
+            crate::scan::format_size(result.reclaimed_bytes as i64),
+            if completed { "" } else { "（已取消）" }
+        );
+        self.record_event(EventKind::CacheCompact, "", &detail).ok();
+        Ok(result)
+    }
+
+    pub fn get_stats(&self) -> CacheStats {
+        let conn = self.conn.lock();
+
+        let entry_count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM scan_cache", [], |row| row.get(0))
+            .unwrap_or(0);
+        let total_size: i64 = conn.query_row(TOTAL_SIZE_SQL, [], |row| row.get(0)).unwrap_or(0);
+
+        let oldest_entry: Option<i64> = conn
+            .query_row(
+                "SELECT MIN(created_at) FROM scan_cache",
+                [],
+                |row| row.get(0),
+            )
+            .optional()
+            .unwrap_or(None);
+
+        let (hits, misses, bytes_served, decode_micros): (i64, i64, i64, i64) = conn
+            .query_row(
+                "SELECT hits, misses, bytes_served, decode_micros FROM cache_stats WHERE id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap_or((0, 0, 0, 0));
+        let lookups = hits + misses;
+
+        CacheStats {
+            entry_count: entry_count as usize,
+            total_size_bytes: total_size as usize,
+            total_size_mb: total_size as f64 / 1024.0 / 1024.0,
+            max_size_mb: Settings::current().cache.max_disk_mb as usize,
+            oldest_entry_timestamp: oldest_entry,
+            hits: hits as u64,
+            misses: misses as u64,
+            hit_rate: if lookups > 0 { hits as f64 / lookups as f64 } else { 0.0 },
+            bytes_served: bytes_served as u64,
+            avg_deserialize_ms: if hits > 0 { decode_micros as f64 / hits as f64 / 1000.0 } else { 0.0 },
+        }
+    }
+
+    /// 写入从其他机器导入的扫描结果：mtime 记为 0，本机扫描同一路径时不会作为新鲜缓存命中，
+    /// 只供按路径打开浏览；origin 为来源主机
+    pub fn insert_imported(&self, path: &str, result: &ScanResult, scanned_at: i64, origin: &str) -> Result<()> {
+        let data = bincode::serialize(result)?;
+        self.wait_pending(Some(path));
+        let size = data.len();
+        self.maybe_cleanup(path, size, &Settings::current().cache)?;
+        self.conn.lock().execute(
+            "INSERT OR REPLACE INTO scan_cache
+                 (path, data, dir_mtime, created_at, last_accessed, size, item_count, scanned_at, imported_from, display_path)
+             VALUES (?1, ?2, 0, ?3, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![path, data, chrono::Utc::now().timestamp(), size, result.items.len(), scanned_at, origin, result.path.as_str()],
+        )?;
+        self.recompute_size();
+        Ok(())
+    }
+
+    /// 缓存中是否有 path 这一条
+    pub fn contains(&self, path: &str) -> bool {
+        self.wait_pending(Some(path));
+        self.conn
+            .lock()
+            .query_row("SELECT 1 FROM scan_cache WHERE path = ?1", params![path], |_| Ok(()))
+            .optional()
+            .ok()
+            .flatten()
+            .is_some()
+    }
+
+    /// path 的缓存若是从其他机器导入的，返回来源主机
+    pub fn imported_from(&self, path: &str) -> Option<String> {
+        self.wait_pending(Some(path));
+        self.conn
+            .lock()
+            .query_row("SELECT imported_from FROM scan_cache WHERE path = ?1", params![path], |row| {
+                row.get::<_, Option<String>>(0)
+            })
+            .optional()
+            .ok()
+            .flatten()
+            .flatten()
+    }
+
+    /// 逐条列出缓存的扫描结果（按大小降序），不读取结果数据
+    pub fn list_entries(&self) -> Result<Vec<CacheEntryInfo>> {
+        self.flush();
+        let now = chrono::Utc::now().timestamp();
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT path, size, item_count, scanned_at, last_accessed, hit_count, imported_from, display_path
+             FROM scan_cache ORDER BY size DESC, path",
+        )?;
+        let entries = stmt
+            .query_map([], |row| {
+                let size: i64 = row.get(1)?;
+                let scanned_at: i64 = row.get(3)?;
+                let path: String = row.get(0)?;
+                Ok(CacheEntryInfo {
+                    display_path: row.get::<_, Option<String>>(7)?.unwrap_or_else(|| cache_key_root(&path).to_string()),
+                    path,
+                    size,
+                    size_formatted: crate::scan::format_size(size).to_string(),
+                    item_count: row.get::<_, i64>(2)? as usize,
+                    scanned_at,
+                    age_secs: (now - scanned_at).max(0),
+                    last_accessed: row.get(4)?,
+                    hit_count: row.get::<_, i64>(5)? as u64,
+                    imported_from: row.get(6)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(entries)
+    }
+
+    /// 命中次数最多的 limit 条本机扫描结果（不含导入的条目），同等时最近访问的优先：
+    /// (key, 缓存时的目录 mtime, 最近访问时间)，不读取结果数据
+    pub fn frequent_entries(&self, limit: usize) -> Result<Vec<(String, i64, i64)>> {
+        self.flush();
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT path, dir_mtime, last_accessed FROM scan_cache
+             WHERE imported_from IS NULL
+             ORDER BY hit_count DESC, last_accessed DESC LIMIT ?1",
+        )?;
+        let entries = stmt
+            .query_map(params![limit as i64], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(entries)
+    }
+
+    /// 删除 path 这一条缓存（精确匹配，不影响子目录与祖先目录的缓存）；返回是否存在
+    pub fn delete_entry(&self, path: &str) -> Result<bool> {
+        self.wait_pending(Some(path));
+        let removed = self.conn.lock().execute("DELETE FROM scan_cache WHERE path = ?1", params![path])?;
+        if removed > 0 {
+            self.recompute_size();
+            self.record_event(EventKind::CacheInvalidate, path, "手动删除").ok();
+        }
+        Ok(removed > 0)
+    }
+
+    /// 按剩余条目重新统计缓存占用
+    fn recompute_size(&self) {
+        let remaining: i64 = self.conn.lock().query_row(TOTAL_SIZE_SQL, [], |row| row.get(0)).unwrap_or(0);
+        *self.current_size.lock() = remaining.max(0) as u64;
+    }
+
+    pub fn invalidate(&self, path: &str) -> Result<()> {
+        // 按前缀失效：等待全部排队写入，避免失效后又被写回
+        self.flush();
+        let removed = {
+            let conn = self.conn.lock();
+            conn.execute(
+                "INSERT OR REPLACE INTO previous_scans (path, data, cached_at)
+                 SELECT path, data, created_at FROM scan_cache WHERE path = ?1 OR path LIKE ?2",
+                params![path, format!("{}%", path)],
+            )?;
+            conn.execute(
+                "DELETE FROM scan_cache WHERE path = ?1 OR path LIKE ?2",
+                params![path, format!("{}%", path)],
+            )?
+        };
+        if removed > 0 {
+            self.recompute_size();
+            self.record_event(EventKind::CacheInvalidate, path, &format!("{} 条缓存", removed))
+                .ok();
+        }
+        Ok(())
+    }
+
+    /// 目录 path 内有变更：把 path 本身及其祖先目录（含带排除 / 包含修饰的 key）的缓存标记为过期。
+    /// 数据保留供对比与按路径打开，只是不再作为新鲜结果命中，也不在扫描父目录时沿用；返回标记的条数
+    pub fn mark_stale(&self, path: &str) -> Result<usize> {
+        self.flush();
+        let marked = {
+            let conn = self.conn.lock();
+            let keys: Vec<String> = {
+                let mut stmt = conn.prepare("SELECT path FROM scan_cache WHERE dir_mtime != ?1")?;
+                let rows = stmt
+                    .query_map(params![STALE_MTIME], |row| row.get::<_, String>(0))?
+                    .filter_map(|r| r.ok())
+                    .filter(|key| {
+                        let root = key.split('|').next().unwrap_or(key);
+                        root == path || crate::fs::is_child_cache_key(root, path)
+                    })
+                    .collect();
+                rows
+            };
+            for key in &keys {
+                conn.execute("UPDATE scan_cache SET dir_mtime = ?1 WHERE path = ?2", params![STALE_MTIME, key])?;
+            }
+            keys.len()
+        };
+        if marked > 0 {
+            self.record_event(EventKind::CacheInvalidate, path, &format!("目录变更，{} 条缓存标记为过期", marked))
+                .ok();
+        }
+        Ok(marked)
+    }
+
+    /// 失效 path 本身、其子目录与祖先目录（均含带排除 / 包含修饰的 key）的缓存。
+    /// 按路径层级比较而不用 LIKE：`/data/photo` 不影响 `/data/photos2`，`_` / `%` 也不作通配
+    pub fn invalidate_related(&self, path: &str) -> Result<()> {
+        self.flush();
+        let removed = {
+            let conn = self.conn.lock();
+            let keys: Vec<String> = {
+                let mut stmt = conn.prepare("SELECT path FROM scan_cache")?;
+                let rows = stmt
+                    .query_map([], |row| row.get::<_, String>(0))?
+                    .filter_map(|r| r.ok())
+                    .filter(|key| {
+                        let root = key.split('|').next().unwrap_or(key);
+                        root == path
+                            || crate::fs::is_child_cache_key(root, path)
+                            || crate::fs::is_child_cache_key(path, root)
+                    })
+                    .collect();
+                rows
+            };
+            for key in &keys {
+                conn.execute(
+                    "INSERT OR REPLACE INTO previous_scans (path, data, cached_at)
+                     SELECT path, data, created_at FROM scan_cache WHERE path = ?1",
+                    params![key],
+                )?;
+                conn.execute("DELETE FROM scan_cache WHERE path = ?1", params![key])?;
+            }
+            keys.len()
+        };
+        if removed > 0 {
+            self.recompute_size();
+            self.record_event(EventKind::CacheInvalidate, path, &format!("{} 条缓存", removed))
+                .ok();
+        }
+        Ok(())
+    }
+
+    // ─── 事件日志 ──────────────────────────────────────────
+
+    /// 记录一条事件（时间戳为毫秒，便于还原同一秒内的先后顺序）
+    pub fn record_event(&self, kind: EventKind, path: &str, detail: &str) -> Result<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "INSERT INTO events (timestamp, kind, path, detail) VALUES (?1, ?2, ?3, ?4)",
+            params![chrono::Utc::now().timestamp_millis(), kind.as_str(), path, detail],
+        )?;
+        Ok(())
+    }
+
+    /// 按条件查询事件日志，按时间倒序（最新在前）
+    pub fn query_events(&self, filter: &EventFilter) -> Result<Vec<EventRecord>> {
+        // kinds 拼成 ",a,b," 后用 instr 匹配，避免动态拼接 SQL
+        let kinds = filter.kinds.as_ref().filter(|k| !k.is_empty()).map(|k| {
+            let joined: Vec<&str> = k.iter().map(|kind| kind.as_str()).collect();
+            format!(",{},", joined.join(","))
+        });
+        let path_pattern = filter.path.as_ref().map(|p| format!("{}%", p));
+        let limit = filter.limit.unwrap_or(500).min(10000) as i64;
+
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT id, timestamp, kind, path, detail FROM events
+             WHERE (?1 IS NULL OR timestamp >= ?1)
+               AND (?2 IS NULL OR timestamp <= ?2)
+               AND (?3 IS NULL OR instr(?3, ',' || kind || ',') > 0)
+               AND (?4 IS NULL OR path LIKE ?4)
+             ORDER BY id DESC LIMIT ?5",
+        )?;
+
+        let events = stmt
+            .query_map(
+                params![filter.since, filter.until, kinds, path_pattern, limit],
+                |row| {
+                    Ok(EventRecord {
+                        id: row.get(0)?,
+                        timestamp: row.get(1)?,
+                        kind: row.get(2)?,
+                        path: row.get(3)?,
+                        detail: row.get(4)?,
+                    })
+                },
+            )?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(events)
+    }
+
+    // ─── 快照操作 ──────────────────────────────────────────
+
+    /// 保存一次扫描结果作为快照
+    pub fn insert_snapshot(
+        &self,
+        path: &str,
+        result: &ScanResult,
+        file_count: usize,
+        dir_count: usize,
+    ) -> Result<i64> {
+        let op = Operation::start("save_snapshot", None, None);
+        self.insert_snapshot_with(path, result, file_count, dir_count, &op)
+    }
+
+    /// 保存快照并经 op 上报阶段（serialize → write → prune）；写入前可取消
+    pub fn insert_snapshot_with(
+        &self,
+        path: &str,
+        result: &ScanResult,
+        file_count: usize,
+        dir_count: usize,
+        op: &Operation,
+    ) -> Result<i64> {
+        op.progress("serialize", 0.0);
+        let data = bincode::serialize(result)?;
+        let now = chrono::Utc::now().timestamp();
+        op.check()?;
+
+        op.progress("write", 40.0);
+        let conn = self.conn.lock();
+        conn.execute(
+            "INSERT INTO snapshots (path, scan_time, data, total_size, total_size_formatted, item_count, file_count, dir_count)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                path,
+                now,
+                data,
+                result.total_size,
+                result.total_size_formatted.as_str(),
+                result.items.len(),
+                file_count,
+                dir_count,
+            ],
+        )?;
+
+        let id = conn.last_insert_rowid();
+
+        // 快照已写入，清理阶段不再响应取消
+        op.progress("prune", 90.0);
+        // 每个路径最多保留 50 个快照
+        conn.execute(
+            "DELETE FROM snapshots WHERE path = ?1 AND id NOT IN (
+                SELECT id FROM snapshots WHERE path = ?1 ORDER BY scan_time DESC LIMIT 50
+            )",
+            params![path],
+        )?;
+
+        // 30 天 TTL
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(30);
+        conn.execute(
+            "DELETE FROM snapshots WHERE scan_time < ?1",
+            params![cutoff.timestamp()],
+        )?;
+
+        Ok(id)
+    }
+
+    /// 列出某路径的所有快照（元数据，不含完整数据）
+    pub fn list_snapshots(&self, path: &str) -> Result<Vec<SnapshotInfo>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT id, path, scan_time, total_size, total_size_formatted, item_count, file_count, dir_count
+             FROM snapshots WHERE path = ?1 ORDER BY scan_time DESC LIMIT 50",
+        )?;
+
+        let snapshots = stmt
+            .query_map(params![path], |row| {
+                Ok(SnapshotInfo {
+                    id: row.get(0)?,
+                    path: row.get(1)?,
+                    scan_time: row.get(2)?,
+                    total_size: row.get(3)?,
+                    total_size_formatted: row.get(4)?,
+                    item_count: row.get(5)?,
+                    file_count: row.get(6)?,
+                    dir_count: row.get(7)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(snapshots)
+    }
+
+    /// 获取指定 ID 的快照完整数据
+    pub fn get_snapshot(&self, id: i64) -> Option<ScanResult> {
+        let conn = self.conn.lock();
+        let data: Option<Vec<u8>> = conn
+            .query_row(
+                "SELECT data FROM snapshots WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()
+            .ok()
+            .flatten();
+
+        data.and_then(|d| decode_scan_result(&d))
+    }
+
+    /// 删除指定快照
+    pub fn delete_snapshot(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock();
+        conn.execute("DELETE FROM snapshots WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    // ─── 收藏夹 ─────────────────────────────────────────────
+
+    pub fn add_favorite(&self, path: &str) -> Result<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "INSERT OR IGNORE INTO favorites (path, created_at) VALUES (?1, ?2)",
+            params![path, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    pub fn remove_favorite(&self, path: &str) -> Result<()> {
+        let conn = self.conn.lock();
+        conn.execute("DELETE FROM favorites WHERE path = ?1", params![path])?;
+        Ok(())
+    }
+
+    /// 收藏路径列表（按收藏时间升序）
+    pub fn list_favorites(&self) -> Result<Vec<String>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare("SELECT path FROM favorites ORDER BY created_at")?;
+        let paths = stmt
+            .query_map([], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(paths)
+    }
+
+    /// root 下各子目录的完整结果缓存路径及其目录 mtime
+    pub fn child_entries(&self, root: &str) -> Result<Vec<(String, i64)>> {
+        self.flush();
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare("SELECT path, dir_mtime FROM scan_cache WHERE path LIKE ?1")?;
+        let entries = stmt
+            .query_map(params![format!("{}/%", root.trim_end_matches('/'))], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .filter_map(|r| r.ok())
+            // LIKE 中的 _ 与 % 是通配符，这里再按前缀精确过滤
+            .filter(|(path, _): &(String, i64)| crate::fs::is_child_cache_key(root, path))
+            .collect();
+        Ok(entries)
+    }
+
+    /// 已缓存扫描结果的目录路径中以 prefix 开头的（路径补全用，大小写不敏感）；
+    /// 带排除预设 / 包含模式修饰的 key 不是目录路径，不返回
+    pub fn cached_paths_with_prefix(&self, prefix: &str, limit: usize) -> Result<Vec<String>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT COALESCE(display_path, path) FROM scan_cache WHERE path LIKE ?1 AND instr(path, '|') = 0
+             ORDER BY last_accessed DESC LIMIT ?2",
+        )?;
+        let paths = stmt
+            .query_map(params![format!("{}%", prefix), limit as i64], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(paths)
+    }
+
+    // ─── 目录大小历史 ───────────────────────────────────────
+
+    /// 批量写入一次扫描的目录大小
+    pub fn insert_size_samples(&self, samples: &[(String, i64)], scan_time: i64) -> Result<()> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO size_history (path, scan_time, size) VALUES (?1, ?2, ?3)",
+            )?;
+            for (path, size) in samples {
+                stmt.execute(params![path, scan_time, size])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 某目录的大小时间序列（按时间升序）
+    pub fn get_size_history(&self, path: &str) -> Result<Vec<SizePoint>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT scan_time, size FROM size_history WHERE path = ?1 ORDER BY scan_time",
+        )?;
+        let points = stmt
+            .query_map(params![path], |row| {
+                Ok(SizePoint {
+                    timestamp: row.get(0)?,
+                    size: row.get(1)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(points)
+    }
+
+    // ─── 路径访问统计 ───────────────────────────────────────
+
+    /// 记录一次扫描 / 打开
+    pub fn record_visit(&self, path: &str, kind: VisitKind) -> Result<()> {
+        let (scans, opens) = match kind {
+            VisitKind::Scan => (1, 0),
+            VisitKind::Open => (0, 1),
+        };
+        let conn = self.conn.lock();
+        conn.execute(
+            "INSERT INTO path_visits (path, scan_count, open_count, last_visit)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(path) DO UPDATE SET
+                scan_count = scan_count + excluded.scan_count,
+                open_count = open_count + excluded.open_count,
+                last_visit = excluded.last_visit",
+            params![path, scans, opens, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    pub fn list_path_visits(&self) -> Result<Vec<PathVisit>> {
+        let conn = self.conn.lock();
+        let mut stmt =
+            conn.prepare("SELECT path, scan_count, open_count, last_visit FROM path_visits")?;
+        let visits = stmt
+            .query_map([], |row| {
+                Ok(PathVisit {
+                    path: row.get(0)?,
+                    scan_count: row.get(1)?,
+                    open_count: row.get(2)?,
+                    last_visit: row.get(3)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(visits)
+    }
+
+    pub fn clear_path_visits(&self) -> Result<()> {
+        let conn = self.conn.lock();
+        conn.execute("DELETE FROM path_visits", [])?;
+        Ok(())
+    }
+
+    // ─── 全局搜索索引持久化 ─────────────────────────────────
+
+    /// 加载全部全局索引条目
+    pub fn load_global_index(&self) -> Result<Vec<IndexEntry>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT path, name, name_lower, size, is_dir, mtime FROM global_index",
+        )?;
+        let entries = stmt
+            .query_map([], |row| {
+                Ok(IndexEntry {
+                    path: row.get(0)?,
+                    name: row.get(1)?,
+                    name_lower: row.get(2)?,
+                    size: row.get(3)?,
+                    is_dir: row.get::<_, i64>(4)? != 0,
+                    mtime: row.get(5)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(entries)
+    }
+
+    /// 全量重建全局索引时批量写入（事务内先清空再插入）
+    pub fn save_global_index_batch(&self, entries: &[IndexEntry]) -> Result<()> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM global_index", [])?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO global_index
+                 (path, name, name_lower, size, is_dir, drive, mtime, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            for e in entries {
+                let drive = Self::extract_drive(&e.path).unwrap_or('?').to_string();
+                stmt.execute(params![
+                    e.path,
+                    e.name,
+                    e.name_lower,
+                    e.size,
+                    e.is_dir as i64,
+                    drive,
+                    e.mtime,
+                    chrono::Utc::now().timestamp(),
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 单条 upsert（USN 增量同步）
+    pub fn upsert_global_index_entry(&self, entry: &IndexEntry) -> Result<()> {
+        let conn = self.conn.lock();
+        let drive = Self::extract_drive(&entry.path).unwrap_or('?').to_string();
+        conn.execute(
+            "INSERT OR REPLACE INTO global_index
+             (path, name, name_lower, size, is_dir, drive, mtime, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                entry.path,
+                entry.name,
+                entry.name_lower,
+                entry.size,
+                entry.is_dir as i64,
+                drive,
+                entry.mtime,
+                chrono::Utc::now().timestamp(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 按绝对路径删除条目（USN 删除/重命名旧名称）
+    pub fn remove_global_index_by_path(&self, path: &str) -> Result<()> {
+        let conn = self.conn.lock();
+        conn.execute("DELETE FROM global_index WHERE path = ?1", params![path])?;
+        Ok(())
+    }
+
+    /// 按前缀删除条目（USN 增量失败时重建某路径）
+    pub fn remove_global_index_by_prefix(&self, prefix: &str) -> Result<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "DELETE FROM global_index WHERE path LIKE ?1",
+            params![format!("{}%", prefix)],
+        )?;
+        Ok(())
+    }
+
+    /// 清空全局索引
+    pub fn clear_global_index(&self) -> Result<()> {
+        let conn = self.conn.lock();
+        conn.execute("DELETE FROM global_index", [])?;
+        Ok(())
+    }
+
+    fn extract_drive(path: &str) -> Option<char> {
+        let bytes = path.as_bytes();
+        if bytes.len() >= 2 && bytes[1] == b':' {
+            Some(bytes[0] as char)
+        } else {
+            None
+        }
+    }
+}
+
+// ─── 表结构版本与完整性 ──────────────────────────────────
+//
+// cache_meta 记录两个版本：
+// - schema_version：表 / 列 / 索引的结构版本，旧库按 SCHEMA_MIGRATIONS 逐步升级；
+//   没有版本表的旧库视为版本 1（引入版本表之前的结构），新建的库直接为最新版本
+// - result_format：ScanResult 的 bincode 布局指纹（FORMAT_VERSION + 类型结构的哈希）。
+//   bincode 不带字段名，布局变化后旧数据无法解码，此时清空 scan_cache 与 previous_scans 重新扫描；
+//   快照不删除，读取时解码失败按不可用处理
+// 打开时先做 PRAGMA quick_check，数据库损坏或迁移失败时把文件移到一旁
+// （cache_v2.db.corrupt-<时间戳>）后新建，不再因缓存文件问题导致启动失败。
+
+/// 表结构版本：表 / 列 / 索引变化时递增，并在 SCHEMA_MIGRATIONS 末尾追加对应迁移
+pub const SCHEMA_VERSION: i64 = 7;
+
+/// 被目录监视标记为过期的条目的 dir_mtime：小于任何实际的目录 mtime，不再作为新鲜结果命中
+pub const STALE_MTIME: i64 = -1;
+/// 扫描结果编码版本：字段语义变化但类型结构不变（指纹发现不了）时手动递增
+pub const FORMAT_VERSION: i64 = 1;
+
+/// 表结构迁移步骤：把结构从 version - 1 升级到 version
+struct SchemaMigration {
+    version: i64,
+    description: &'static str,
+    run: fn(&Connection) -> rusqlite::Result<()>,
+}
+
+/// 按版本升序；新增结构变更时在末尾追加（create_tables 同步改为最新结构）
+const SCHEMA_MIGRATIONS: &[SchemaMigration] = &[
+    SchemaMigration {
+        version: 1,
+        description: "引入 cache_meta 版本表",
+        run: |_| Ok(()),
+    },
+    SchemaMigration {
+        version: 2,
+        description: "scan_cache 增加命中次数与扫描时间",
+        run: |conn| {
+            conn.execute_batch(
+                "ALTER TABLE scan_cache ADD COLUMN hit_count INTEGER NOT NULL DEFAULT 0;
+                 ALTER TABLE scan_cache ADD COLUMN scanned_at INTEGER NOT NULL DEFAULT 0;
+                 UPDATE scan_cache SET scanned_at = created_at;",
+            )
+        },
+    },
+    SchemaMigration {
+        version: 3,
+        description: "scan_cache 增加导入来源",
+        run: |conn| conn.execute_batch("ALTER TABLE scan_cache ADD COLUMN imported_from TEXT;"),
+    },
+    SchemaMigration {
+        version: 4,
+        description: "scan_cache 增加最近访问时间（按最近最少访问淘汰）",
+        run: |conn| {
+            // 之前命中时刷新的是 created_at，原值即最近访问时间
+            conn.execute_batch(
+                "ALTER TABLE scan_cache ADD COLUMN last_accessed INTEGER NOT NULL DEFAULT 0;
+                 UPDATE scan_cache SET last_accessed = created_at;",
+            )
+        },
+    },
+    SchemaMigration {
+        version: 5,
+        description: "scan_cache 增加子目录 mtime 摘要（命中前抽样校验）",
+        run: |conn| conn.execute_batch("ALTER TABLE scan_cache ADD COLUMN dir_digest BLOB;"),
+    },
+    SchemaMigration {
+        version: 6,
+        description: "扫描缓存、上一次扫描与大小历史改用 cache_key（Windows 下不区分大小写）",
+        run: |conn| rekey_cache_paths(conn, crate::fs::cache_key),
+    },
+    SchemaMigration {
+        version: 7,
+        description: "scan_cache 增加展示路径（key 只用于查找）",
+        run: |conn| {
+            conn.execute_batch("ALTER TABLE scan_cache ADD COLUMN display_path TEXT;")?;
+            backfill_display_paths(conn)
+        },
+    },
+];
+
+/// 缓存 key 中的目录部分（去掉排除预设 / 包含模式修饰）
+fn cache_key_root(key: &str) -> &str {
+    key.split_once('|').map_or(key, |(root, _)| root)
+}
+
+/// 缓存总占用：扫描结果加上一次扫描结果（后者是一份完整的旧结果，同样计入上限）
+const TOTAL_SIZE_SQL: &str = "SELECT (SELECT COALESCE(SUM(size), 0) FROM scan_cache)
+     + (SELECT COALESCE(SUM(length(data)), 0) FROM previous_scans)";
+
+/// 可淘汰的条目 (表, key, 字节数)，按最近访问时间升序；上一次扫描结果按转存时间排序。
+/// except 对应的两张表中的条目都不参与
+fn lru_entries(conn: &Connection, except: Option<&str>) -> rusqlite::Result<Vec<(String, String, i64)>> {
+    let mut stmt = conn.prepare(
+        "SELECT 'scan_cache', path, size, last_accessed FROM scan_cache WHERE ?1 IS NULL OR path != ?1
+         UNION ALL
+         SELECT 'previous_scans', path, length(data), cached_at FROM previous_scans WHERE ?1 IS NULL OR path != ?1
+         ORDER BY 4 ASC",
+    )?;
+    let rows = stmt.query_map(params![except], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+    rows.collect()
+}
+
+/// 为已有条目补上展示路径：key 已按平台折叠大小写，从文件系统取回实际写法；
+/// 目录已不存在时留空，读取时退回 key
+fn backfill_display_paths(conn: &Connection) -> rusqlite::Result<()> {
+    let keys: Vec<String> = conn
+        .prepare("SELECT path FROM scan_cache")?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    for key in keys {
+        if let Some(display) = crate::scan::canonical_display_path(cache_key_root(&key)) {
+            conn.execute("UPDATE scan_cache SET display_path = ?1 WHERE path = ?2", params![display, key])?;
+        }
+    }
+    Ok(())
+}
+
+/// 按 cache_key 形式改写以路径为 key 的表：排除预设 / 包含模式等 `|` 修饰原样保留；
+/// 多个旧 key 折叠为同一个时只保留最新的一行（大小历史按 (路径, 扫描时间) 去重）
+fn rekey_cache_paths(conn: &Connection, fold: fn(&str) -> String) -> rusqlite::Result<()> {
+    // (表, 去重时附加的列, 新旧判断列)
+    const TABLES: [(&str, &str, &str); 3] = [
+        ("scan_cache", "0", "scanned_at"),
+        ("previous_scans", "0", "cached_at"),
+        ("size_history", "scan_time", "rowid"),
+    ];
+    for (table, group, newest) in TABLES {
+        // 较早版本的库可能还没有这些表（由 create_tables 新建）
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1)",
+            params![table],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            continue;
+        }
+        let rows: Vec<(i64, String, i64)> = conn
+            .prepare(&format!("SELECT rowid, path, {} FROM {} ORDER BY {} DESC", group, table, newest))?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        let mut seen = std::collections::HashSet::new();
+        let mut renames = Vec::new();
+        for (rowid, path, group) in rows {
+            let key = match path.split_once('|') {
+                Some((root, modifiers)) => format!("{}|{}", fold(root), modifiers),
+                None => fold(&path),
+            };
+            if !seen.insert((key.clone(), group)) {
+                conn.execute(&format!("DELETE FROM {} WHERE rowid = ?1", table), params![rowid])?;
+            } else if key != path {
+                renames.push((rowid, key));
+            }
+        }
+        // 先删掉重复行再改名，改名后的 key 不会与剩余行冲突
+        for (rowid, key) in renames {
+            conn.execute(&format!("UPDATE {} SET path = ?1 WHERE rowid = ?2", table), params![key, rowid])?;
+        }
+    }
+    Ok(())
+}
+
+/// 打开数据库并做快速完整性检查；无法打开或检查未通过时移到一旁新建，并返回重建原因
+fn open_checked(path: &Path) -> Result<(Connection, Option<String>)> {
+    let problem = match connect(path) {
+        Ok(conn) => match quick_check(&conn) {
+            Ok(()) => return Ok((conn, None)),
+            Err(e) => e,
+        },
+        Err(e) => e.to_string(),
+    };
+    let reason = format!("缓存数据库损坏，已重建: {}", problem);
+    move_aside(path, &reason);
+    Ok((connect(path)?, Some(reason)))
+}
+
+/// 打开数据库连接；新建的数据库启用增量回收（auto_vacuum 只能在建表前设置，对已有数据库无效）
+fn connect(path: &Path) -> rusqlite::Result<Connection> {
+    let conn = Connection::open(path)?;
+    conn.execute_batch("PRAGMA auto_vacuum = INCREMENTAL;")?;
+    Ok(conn)
+}
+
+fn pragma_i64(conn: &Connection, name: &str) -> rusqlite::Result<i64> {
+    conn.query_row(&format!("PRAGMA {}", name), [], |row| row.get(0))
+}
+
+/// 数据库文件大小与其中空闲页的字节数
+fn page_usage(conn: &Connection) -> rusqlite::Result<(u64, u64)> {
+    let page_size = pragma_i64(conn, "page_size")?.max(0) as u64;
+    let pages = pragma_i64(conn, "page_count")?.max(0) as u64;
+    let free = pragma_i64(conn, "freelist_count")?.max(0) as u64;
+    Ok((pages * page_size, free * page_size))
+}
+
+/// PRAGMA quick_check：结构与页面一致性检查（不校验索引内容，比 integrity_check 快得多）
+fn quick_check(conn: &Connection) -> std::result::Result<(), String> {
+    let mut stmt = conn.prepare("PRAGMA quick_check").map_err(|e| e.to_string())?;
+    let rows: Vec<String> = stmt
+        .query_map([], |row| row.get(0))
+        .and_then(|rows| rows.collect())
+        .map_err(|e| e.to_string())?;
+    match rows.as_slice() {
+        [ok] if ok == "ok" => Ok(()),
+        _ => Err(rows.join("; ")),
+    }
+}
+
+/// 把无法使用的数据库文件（及 WAL / 共享内存文件）改名保留，移动失败时删除
+fn move_aside(path: &Path, reason: &str) {
+    let stamp = chrono::Utc::now().format("%Y%m%d%H%M%S");
+    let aside = path.with_file_name(format!(
+        "{}.corrupt-{}",
+        path.file_name().map_or_else(|| "cache.db".into(), |n| n.to_string_lossy()),
+        stamp
+    ));
+    eprintln!("[DiskCache] {}，原文件移至 {}", reason, aside.display());
+    if std::fs::rename(path, &aside).is_err() {
+        std::fs::remove_file(path).ok();
+    }
+    for suffix in ["-wal", "-shm", "-journal"] {
+        std::fs::remove_file(format!("{}{}", path.display(), suffix)).ok();
+    }
+}
+
+fn read_meta(conn: &Connection, key: &str) -> rusqlite::Result<Option<String>> {
+    conn.query_row("SELECT value FROM cache_meta WHERE key = ?1", params![key], |row| row.get(0))
+        .optional()
+}
+
+fn write_meta(conn: &Connection, key: &str, value: &str) -> rusqlite::Result<()> {
+    conn.execute("INSERT OR REPLACE INTO cache_meta (key, value) VALUES (?1, ?2)", params![key, value])?;
+    Ok(())
+}
+
+/// 升级表结构到 SCHEMA_VERSION；版本高于当前程序（降级安装）时不做修改
+fn migrate_schema(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS cache_meta (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        )",
+        [],
+    )?;
+    let stored = read_meta(conn, "schema_version")?.and_then(|v| v.parse::<i64>().ok());
+    let version = match stored {
+        Some(version) => version,
+        None => {
+            let legacy: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'scan_cache')",
+                [],
+                |row| row.get(0),
+            )?;
+            if !legacy {
+                // 新建的库：create_tables 直接建出最新结构
+                write_meta(conn, "schema_version", &SCHEMA_VERSION.to_string())?;
+                return Ok(());
+            }
+            0
+        }
+    };
+    if version >= SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    for migration in SCHEMA_MIGRATIONS.iter().filter(|m| m.version > version) {
+        eprintln!("[DiskCache] 表结构迁移到 v{}：{}", migration.version, migration.description);
+        (migration.run)(&tx)?;
+    }
+    write_meta(&tx, "schema_version", &SCHEMA_VERSION.to_string())?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// ScanResult 编码布局指纹：FORMAT_VERSION + 去掉文档注释后的类型结构哈希
+fn result_format() -> String {
+    fn strip_descriptions(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                map.remove("description");
+                map.values_mut().for_each(strip_descriptions);
+            }
+            serde_json::Value::Array(values) => values.iter_mut().for_each(strip_descriptions),
+            _ => {}
+        }
+    }
+
+    let mut schema = serde_json::to_value(schemars::schema_for!(ScanResult)).unwrap_or_default();
+    strip_descriptions(&mut schema);
+    format!("{}:{}", FORMAT_VERSION, blake3::hash(schema.to_string().as_bytes()).to_hex())
+}
+
+/// 编码布局与库中记录不一致时清空扫描缓存，返回原因
+fn check_result_format(conn: &Connection) -> Result<Option<String>> {
+    let current = result_format();
+    let stored = read_meta(conn, "result_format")?;
+    if stored.as_deref() == Some(current.as_str()) {
+        return Ok(None);
+    }
+
+    let cleared = if stored.is_some() {
+        let n = conn.execute("DELETE FROM scan_cache", [])?;
+        conn.execute("DELETE FROM previous_scans", [])?;
+        Some(format!("扫描结果格式已变化，清空 {} 条扫描缓存", n))
+    } else {
+        // 没有记录（新库或引入版本表之前的库）：旧库中无法解码的条目读取时按未命中处理
+        None
+    };
+    write_meta(conn, "result_format", &current)?;
+    Ok(cleared)
+}
+
+/// 累计一次 get 的结果：hit 为命中时读出的字节数与解码耗时，None 为未命中
+fn record_lookup(conn: &Connection, hit: Option<(usize, std::time::Duration)>) {
+    let (hits, misses, bytes, micros) = match hit {
+        Some((bytes, elapsed)) => (1, 0, bytes as i64, elapsed.as_micros() as i64),
+        None => (0, 1, 0, 0),
+    };
+    let _ = conn.execute(
+        "UPDATE cache_stats SET hits = hits + ?1, misses = misses + ?2,
+             bytes_served = bytes_served + ?3, decode_micros = decode_micros + ?4
+         WHERE id = 1",
+        params![hits, misses, bytes, micros],
+    );
+}
+
+/// 解码缓存 / 快照中的 bincode 扫描结果；数据损坏（截断、旧版本字段布局）时
+/// 记录原因并按缓存未命中处理
+pub fn decode_scan_result(data: &[u8]) -> Option<ScanResult> {
+    match bincode::deserialize(data) {
+        Ok(result) => Some(result),
+        Err(e) => {
+            eprintln!("[DiskCache] 扫描结果解码失败（按未命中处理）: {}", e);
+            None
+        }
+    }
+}
+
+/// 快照元数据（不含完整文件列表）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotInfo {
+    pub id: i64,
+    pub path: String,
+    pub scan_time: i64,
+    pub total_size: i64,
+    pub total_size_formatted: String,
+    pub item_count: usize,
+    pub file_count: usize,
+    pub dir_count: usize,
+}
+
+/// 事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    ScanStart,
+    ScanFinish,
+    CacheHit,
+    CacheMiss,
+    CacheInvalidate,
+    CacheClear,
+    /// 缓存数据库重建或因格式变化清空
+    CacheRebuild,
+    /// 压缩数据库文件，归还空闲页
+    CacheCompact,
+    Error,
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::ScanStart => "scan_start",
+            EventKind::ScanFinish => "scan_finish",
+            EventKind::CacheHit => "cache_hit",
+            EventKind::CacheMiss => "cache_miss",
+            EventKind::CacheInvalidate => "cache_invalidate",
+            EventKind::CacheClear => "cache_clear",
+            EventKind::CacheRebuild => "cache_rebuild",
+            EventKind::CacheCompact => "cache_compact",
+            EventKind::Error => "error",
+        }
+    }
+}
+
+/// 事件日志中的一条记录
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EventRecord {
+    pub id: i64,
+    /// Unix 毫秒时间戳
+    pub timestamp: i64,
+    pub kind: String,
+    pub path: String,
+    pub detail: String,
+}
+
+/// 事件日志查询条件（全部可选）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EventFilter {
+    /// 只返回这些类型的事件
+    pub kinds: Option<Vec<EventKind>>,
+    /// 路径前缀
+    pub path: Option<String>,
+    /// 起止时间（Unix 毫秒，含边界）
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    /// 最多返回条数（默认 500，上限 10000）
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    pub entry_count: usize,
+    pub total_size_bytes: usize,
+    pub total_size_mb: f64,
+    pub max_size_mb: usize,
+    pub oldest_entry_timestamp: Option<i64>,
+    /// 累计命中次数（跨会话）
+    pub hits: u64,
+    /// 累计未命中次数：无缓存、目录已变化、已过期或解码失败
+    pub misses: u64,
+    /// hits / (hits + misses)，尚无查询时为 0
+    pub hit_rate: f64,
+    /// 命中时从缓存读出的编码数据累计字节数
+    pub bytes_served: u64,
+    /// 命中时解码扫描结果的平均耗时（毫秒）
+    pub avg_deserialize_ms: f64,
+}
+
+/// 一次压缩的结果
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactResult {
+    /// 压缩前数据库文件的大小（字节）
+    pub before_bytes: u64,
+    pub after_bytes: u64,
+    /// 归还文件系统的字节数
+    pub reclaimed_bytes: u64,
+    /// 旧数据库首次压缩时整体 VACUUM（同时切换为增量回收），之后只做增量回收
+    pub full_vacuum: bool,
+    /// 取消时为 false，已回收的部分保留
+    pub completed: bool,
+    pub duration_ms: u64,
+}
+
+/// 一条磁盘缓存的概要
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntryInfo {
+    /// 缓存 key（规范化的目录路径，启用排除预设 / 包含模式时带 `|exclude=` / `|include=` 后缀；
+    /// Windows 下为小写），删除 / 导出时原样传回
+    pub path: String,
+    /// 展示给用户的目录路径（保留原始大小写，不含修饰后缀）
+    pub display_path: String,
+    /// 编码后的大小（字节）
+    pub size: i64,
+    pub size_formatted: String,
+    pub item_count: usize,
+    /// 扫描（或增量确认未变化）的时间，Unix 秒
+    pub scanned_at: i64,
+    /// 距 scanned_at 的秒数
+    pub age_secs: i64,
+    /// 最近一次写入或命中的时间，Unix 秒
+    pub last_accessed: i64,
+    /// 作为新鲜缓存命中的次数
+    pub hit_count: u64,
+    /// 从其他机器导入的结果：来源主机
+    pub imported_from: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("flashdir_cache_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("cache_v2.db")
+    }
+
+    fn empty_result(path: &str) -> ScanResult {
+        ScanResult {
+            items: Vec::new(),
+            total_size: 42,
+            total_size_formatted: Default::default(),
+            scan_time: 0.0,
+            path: path.into(),
+            mft_available: false,
+            timing: None,
+            perf_metrics: None,
+            extension_stats: None,
+            category_stats: Vec::new(),
+            owners: None,
+            unscanned: None,
+            errors: Vec::new(),
+            unreadable_bytes: 0,
+            cloud_only_bytes: 0,
+            cloud_local_bytes: 0,
+            sparse_files: Vec::new(),
+            sparse_savings_bytes: 0,
+            cache_usage: Default::default(),
+        }
+    }
+
+    fn meta(path: &Path, key: &str) -> Option<String> {
+        read_meta(&Connection::open(path).unwrap(), key).unwrap()
+    }
+
+    #[test]
+    fn versions_legacy_database_and_clears_on_format_change() {
+        let db = temp_db();
+        // 引入版本表之前的库：只有 scan_cache 等表
+        {
+            let conn = Connection::open(&db).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE scan_cache (path TEXT PRIMARY KEY, data BLOB NOT NULL, dir_mtime INTEGER NOT NULL,
+                     created_at INTEGER NOT NULL, size INTEGER NOT NULL, item_count INTEGER NOT NULL);
+                 INSERT INTO scan_cache VALUES ('/a', x'00', 1, strftime('%s','now'), 1, 0);",
+            )
+            .unwrap();
+        }
+        let cache = DiskCache::open(&db).unwrap();
+        assert_eq!(cache.get_stats().entry_count, 1);
+        // v2 迁移补上的列：扫描时间取原有的写入时间
+        let entry = &cache.list_entries().unwrap()[0];
+        assert!(entry.scanned_at > 0 && entry.hit_count == 0);
+        drop(cache);
+        assert_eq!(meta(&db, "schema_version"), Some(SCHEMA_VERSION.to_string()));
+        assert_eq!(meta(&db, "result_format"), Some(result_format()));
+
+        // 编码布局变化：扫描缓存清空，其他表保留
+        {
+            let conn = Connection::open(&db).unwrap();
+            write_meta(&conn, "result_format", "0:old").unwrap();
+            conn.execute("INSERT INTO favorites VALUES ('/fav', 0)", []).unwrap();
+        }
+        let cache = DiskCache::open(&db).unwrap();
+        assert_eq!(cache.get_stats().entry_count, 0);
+        assert_eq!(cache.list_favorites().unwrap(), vec!["/fav".to_string()]);
+        std::fs::remove_dir_all(db.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn rekeys_cache_paths_when_migrating_to_v6() {
+        let db = temp_db();
+        drop(DiskCache::open(&db).unwrap());
+        let paths = |conn: &Connection, sql: &str| -> Vec<(String, i64)> {
+            let mut stmt = conn.prepare(sql).unwrap();
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?))).unwrap();
+            rows.map(Result::unwrap).collect()
+        };
+        {
+            // 模拟 Windows：只差大小写的旧 key 合并，保留最新的一行
+            let conn = Connection::open(&db).unwrap();
+            conn.execute_batch(
+                "INSERT INTO scan_cache (path, data, dir_mtime, created_at, size, item_count, scanned_at) VALUES
+                     ('D:/Data', x'00', 1, 0, 1, 0, 200), ('d:/DATA', x'00', 1, 0, 1, 0, 100),
+                     ('D:/Data|exclude=node_modules', x'00', 1, 0, 1, 0, 50);
+                 INSERT INTO previous_scans VALUES ('D:/Data', x'00', 10), ('d:/data', x'00', 20);
+                 INSERT INTO size_history VALUES ('D:/Data', 1, 100), ('d:/data', 1, 90), ('D:/DATA', 2, 110);",
+            )
+            .unwrap();
+            rekey_cache_paths(&conn, |path| path.to_lowercase()).unwrap();
+            assert_eq!(
+                paths(&conn, "SELECT path, scanned_at FROM scan_cache ORDER BY path"),
+                [("d:/data".to_string(), 200), ("d:/data|exclude=node_modules".to_string(), 50)]
+            );
+            assert_eq!(paths(&conn, "SELECT path, cached_at FROM previous_scans"), [("d:/data".to_string(), 20)]);
+            assert_eq!(
+                paths(&conn, "SELECT path, size FROM size_history ORDER BY scan_time"),
+                [("d:/data".to_string(), 90), ("d:/data".to_string(), 110)]
+            );
+
+            // v5 的库打开时执行迁移：末尾分隔符在各平台都会去掉
+            conn.execute_batch("ALTER TABLE scan_cache DROP COLUMN display_path;").unwrap();
+            write_meta(&conn, "schema_version", "5").unwrap();
+            conn.execute("INSERT INTO previous_scans VALUES ('/home/a/', x'00', strftime('%s','now'))", []).unwrap();
+        }
+        drop(DiskCache::open(&db).unwrap());
+        let conn = Connection::open(&db).unwrap();
+        let rekeyed = paths(&conn, "SELECT path, 0 FROM previous_scans");
+        assert_eq!(rekeyed, [("/home/a".to_string(), 0)]);
+        assert_eq!(meta(&db, "schema_version"), Some(SCHEMA_VERSION.to_string()));
+        drop(conn);
+        std::fs::remove_dir_all(db.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn rebuilds_corrupt_database() {
+        let db = temp_db();
+        std::fs::write(&db, vec![0xAB; 8192]).unwrap();
+        let cache = DiskCache::open(&db).unwrap();
+        assert_eq!(cache.get_stats().entry_count, 0);
+        drop(cache);
+
+        let dir = db.parent().unwrap();
+        let aside = std::fs::read_dir(dir)
+            .unwrap()
+            .flatten()
+            .any(|e| e.file_name().to_string_lossy().starts_with("cache_v2.db.corrupt-"));
+        assert!(aside);
+        assert_eq!(meta(&db, "schema_version"), Some(SCHEMA_VERSION.to_string()));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn background_writes_are_visible_to_readers() {
+        let db = temp_db();
+        let cache = Arc::new(DiskCache::open(&db).unwrap());
+        cache.start_writer();
+
+        for n in 0..8 {
+            cache.insert_async(&format!("/w/{}", n), &empty_result("/w"), 1);
+        }
+        // 读取前等待该路径的排队写入
+        assert_eq!(cache.get_stale("/w/7").map(|r| r.total_size), Some(42));
+        cache.flush();
+        assert_eq!(cache.get_stats().entry_count, 8);
+
+        cache.insert_async("/w/0", &empty_result("/w"), 2);
+        cache.invalidate("/w/").unwrap();
+        assert_eq!(cache.get_stats().entry_count, 0);
+        std::fs::remove_dir_all(db.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn invalidates_related_keys_by_path_hierarchy() {
+        let db = temp_db();
+        let cache = DiskCache::open(&db).unwrap();
+        let keys = [
+            "/data",
+            "/data/photo",
+            "/data/photo|exclude=cache",
+            "/data/photo/2024",
+            "/data/photos2",
+            "/data/photo_x",
+            "/dat",
+        ];
+        for key in keys {
+            cache.insert(key, &empty_result(key), 1).unwrap();
+        }
+        cache.invalidate_related("/data/photo").unwrap();
+        let mut remaining: Vec<String> = cache.list_entries().unwrap().into_iter().map(|e| e.path).collect();
+        remaining.sort();
+        // 兄弟前缀与 `_` 不受影响；被失效的条目转存为上一次扫描结果
+        assert_eq!(remaining, ["/dat", "/data/photo_x", "/data/photos2"]);
+        assert!(cache.get_previous("/data/photo").is_some());
+        std::fs::remove_dir_all(db.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn lists_entries_with_hit_counts_and_deletes_one() {
+        let db = temp_db();
+        let cache = DiskCache::open(&db).unwrap();
+        cache.insert("/l/a", &empty_result("/l/a"), 10).unwrap();
+        cache.insert("/l/a/b", &empty_result("/l/a/b"), 10).unwrap();
+        assert!(cache.get("/l/a", 10).is_some());
+        assert!(cache.get("/l/a", 10).is_some());
+        // mtime 变化不算命中；重写条目保留命中次数
+        assert!(cache.get("/l/a", 11).is_none());
+        cache.insert("/l/a", &empty_result("/l/a"), 11).unwrap();
+
+        let entries = cache.list_entries().unwrap();
+        let a = entries.iter().find(|e| e.path == "/l/a").unwrap();
+        assert_eq!((a.hit_count, a.item_count), (2, 0));
+        assert!(a.age_secs <= 1 && a.size > 0);
+
+        assert!(cache.delete_entry("/l/a").unwrap());
+        assert!(!cache.delete_entry("/l/a").unwrap());
+        let remaining: Vec<String> = cache.list_entries().unwrap().into_iter().map(|e| e.path).collect();
+        assert_eq!(remaining, vec!["/l/a/b"]);
+        std::fs::remove_dir_all(db.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn prefix_completion_skips_decorated_keys() {
+        let db = temp_db();
+        let cache = DiskCache::open(&db).unwrap();
+        cache.insert("/ac/data", &empty_result("/ac/data"), 1).unwrap();
+        cache.insert("/ac/data|exclude=proc,sys", &empty_result("/ac/data"), 1).unwrap();
+        cache.insert("/ac/data|include=*.mp4", &empty_result("/ac/data"), 1).unwrap();
+        assert_eq!(cache.cached_paths_with_prefix("/ac/d", 10).unwrap(), vec!["/ac/data"]);
+        std::fs::remove_dir_all(db.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn lists_and_completes_display_paths_not_folded_keys() {
+        let db = temp_db();
+        let cache = DiskCache::open(&db).unwrap();
+        // Windows 下 key 为小写，展示仍用结果中的原始写法
+        cache.insert("d:/users/bob/videos", &empty_result("D:/Users/Bob/Videos"), 1).unwrap();
+        cache.insert("d:/users/bob/videos|exclude=temp", &empty_result("D:/Users/Bob/Videos"), 1).unwrap();
+        let mut entries: Vec<(String, String)> =
+            cache.list_entries().unwrap().into_iter().map(|e| (e.path, e.display_path)).collect();
+        entries.sort();
+        assert_eq!(
+            entries,
+            [
+                ("d:/users/bob/videos".to_string(), "D:/Users/Bob/Videos".to_string()),
+                ("d:/users/bob/videos|exclude=temp".to_string(), "D:/Users/Bob/Videos".to_string()),
+            ]
+        );
+        assert_eq!(cache.cached_paths_with_prefix("d:/users/b", 10).unwrap(), vec!["D:/Users/Bob/Videos"]);
+        drop(cache);
+
+        // v6 的库没有展示路径：迁移时从文件系统取回，目录已不存在的退回 key
+        let existing = db.parent().unwrap().to_string_lossy().to_string();
+        {
+            let conn = Connection::open(&db).unwrap();
+            conn.execute_batch("DELETE FROM scan_cache; ALTER TABLE scan_cache DROP COLUMN display_path;").unwrap();
+            for key in [existing.as_str(), "/flashdir-missing/dir"] {
+                conn.execute(
+                    "INSERT INTO scan_cache (path, data, dir_mtime, created_at, size, item_count, last_accessed)
+                     VALUES (?1, x'00', 1, strftime('%s','now'), 1, 0, strftime('%s','now'))",
+                    params![key],
+                )
+                .unwrap();
+            }
+            write_meta(&conn, "schema_version", "6").unwrap();
+        }
+        let cache = DiskCache::open(&db).unwrap();
+        let mut entries: Vec<(String, String)> =
+            cache.list_entries().unwrap().into_iter().map(|e| (e.path, e.display_path)).collect();
+        entries.sort();
+        let mut expected = vec![
+            (existing.clone(), crate::scan::canonical_display_path(&existing).unwrap()),
+            ("/flashdir-missing/dir".to_string(), "/flashdir-missing/dir".to_string()),
+        ];
+        expected.sort();
+        assert_eq!(entries, expected);
+        drop(cache);
+        std::fs::remove_dir_all(db.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn policy_removes_expired_excluded_and_oversized_entries() {
+        let db = temp_db();
+        let cache = DiskCache::open(&db).unwrap();
+        for (n, path) in ["/p/old", "/p/tmp/a", "/p/b", "/p/c", "/p/d"].iter().enumerate() {
+            cache.insert(path, &empty_result(path), 1).unwrap();
+            // 按插入顺序错开访问时间；/p/old 超过有效期
+            let accessed = if n == 0 { 0 } else { chrono::Utc::now().timestamp() - 100 + n as i64 };
+            cache.conn.lock().execute("UPDATE scan_cache SET last_accessed = ?1, size = 400000 WHERE path = ?2", params![accessed, path]).unwrap();
+        }
+
+        let policy = CacheSettings { ttl_days: 7, max_disk_mb: 1, never_cache: vec!["/p/tmp".into()], ..Default::default() };
+        // 过期 1 条、不缓存目录 1 条；剩余 3 × 400000 字节超过 1 MiB，淘汰最早的 /p/b
+        assert_eq!(cache.apply_policy(&policy).unwrap(), 3);
+        let remaining: Vec<String> = {
+            let conn = cache.conn.lock();
+            let mut stmt = conn.prepare("SELECT path FROM scan_cache ORDER BY path").unwrap();
+            let rows = stmt.query_map([], |row| row.get(0)).unwrap().map(|r| r.unwrap()).collect();
+            rows
+        };
+        assert_eq!(remaining, vec!["/p/c", "/p/d"]);
+        std::fs::remove_dir_all(db.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn evicts_least_recently_used_and_tracks_exact_size() {
+        let db = temp_db();
+        let cache = DiskCache::open(&db).unwrap();
+        for (n, path) in ["/l/a", "/l/b", "/l/c"].iter().enumerate() {
+            cache.insert(path, &empty_result(path), 1).unwrap();
+            // /l/a 写入最早，其余条目依次更晚
+            let at = chrono::Utc::now().timestamp() - 100 + n as i64;
+            cache.conn.lock().execute(
+                "UPDATE scan_cache SET created_at = ?1, last_accessed = ?1, size = 300000 WHERE path = ?2",
+                params![at, path],
+            ).unwrap();
+        }
+        cache.recompute_size();
+        assert_eq!(*cache.current_size.lock(), 900_000);
+        // 命中刷新访问时间，写入时间不变
+        assert!(cache.get("/l/a", 1).is_some());
+
+        // 1 MiB 上限下再写入 300000 字节：淘汰最久未访问的 /l/b 与 /l/c，常用的 /l/a 保留
+        let policy = CacheSettings { max_disk_mb: 1, ..Default::default() };
+        cache.maybe_cleanup("/l/d", 300_000, &policy).unwrap();
+        let remaining: Vec<String> = {
+            let conn = cache.conn.lock();
+            let mut stmt = conn.prepare("SELECT path FROM scan_cache ORDER BY path").unwrap();
+            let rows = stmt.query_map([], |row| row.get(0)).unwrap().map(|r| r.unwrap()).collect();
+            rows
+        };
+        assert_eq!(remaining, vec!["/l/a"]);
+
+        // 小条目按字节计入总大小，删除后同步减少
+        cache.insert("/l/small", &empty_result("/l/small"), 1).unwrap();
+        let small: i64 = cache.conn.lock()
+            .query_row("SELECT size FROM scan_cache WHERE path = '/l/small'", [], |row| row.get(0))
+            .unwrap();
+        assert!(small > 0);
+        assert_eq!(*cache.current_size.lock(), 300_000 + small as u64);
+        assert!(cache.delete_entry("/l/a").unwrap());
+        assert_eq!(*cache.current_size.lock(), small as u64);
+        std::fs::remove_dir_all(db.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn counts_and_evicts_previous_scans_within_the_size_limit() {
+        let db = temp_db();
+        let cache = DiskCache::open(&db).unwrap();
+        // 重新写入把旧结果转存为上一次扫描结果：两份都计入总大小
+        cache.insert("/v/a", &empty_result("/v/a"), 1).unwrap();
+        cache.insert("/v/a", &empty_result("/v/a"), 2).unwrap();
+        cache.insert("/v/b", &empty_result("/v/b"), 1).unwrap();
+        let now = chrono::Utc::now().timestamp();
+        {
+            let conn = cache.conn.lock();
+            conn.execute("UPDATE previous_scans SET data = zeroblob(400000), cached_at = ?1", params![now - 300])
+                .unwrap();
+            conn.execute("UPDATE scan_cache SET size = 300000, last_accessed = ?1 WHERE path = '/v/a'", params![now - 200])
+                .unwrap();
+            conn.execute("UPDATE scan_cache SET size = 300000, last_accessed = ?1 WHERE path = '/v/b'", params![now - 100])
+                .unwrap();
+        }
+        cache.recompute_size();
+        assert_eq!(*cache.current_size.lock(), 1_000_000);
+        assert_eq!(cache.get_stats().total_size_bytes, 1_000_000);
+
+        // 1 MiB 上限下再写入 300000 字节：先淘汰最早的上一次扫描结果，再淘汰 /v/a
+        let policy = CacheSettings { max_disk_mb: 1, ..Default::default() };
+        cache.maybe_cleanup("/v/c", 300_000, &policy).unwrap();
+        let count = |table: &str| -> i64 {
+            cache.conn.lock().query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0)).unwrap()
+        };
+        assert_eq!(count("previous_scans"), 0);
+        assert_eq!(count("scan_cache"), 1);
+
+        // 按策略整理时同样计入上一次扫描结果
+        cache.insert("/v/b", &empty_result("/v/b"), 2).unwrap();
+        cache.conn.lock()
+            .execute("UPDATE previous_scans SET data = zeroblob(1100000), cached_at = ?1", params![now - 300])
+            .unwrap();
+        cache.apply_policy(&policy).unwrap();
+        assert_eq!(count("previous_scans"), 0);
+        assert_eq!(count("scan_cache"), 1);
+        std::fs::remove_dir_all(db.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn compacts_freed_pages_and_converts_legacy_databases() {
+        let fill = |cache: &DiskCache, path: &str| {
+            cache.insert(path, &empty_result(path), 1).unwrap();
+            cache.conn.lock()
+                .execute("UPDATE scan_cache SET data = zeroblob(2000000) WHERE path = ?1", params![path])
+                .unwrap();
+        };
+
+        // 新建的数据库启用增量回收
+        let db = temp_db();
+        let cache = DiskCache::open(&db).unwrap();
+        fill(&cache, "/c/a");
+        assert!(cache.delete_entry("/c/a").unwrap());
+        assert!(cache.free_bytes() > 1_000_000);
+        let result = cache.compact(&Operation::start("compact_disk_cache", None, None)).unwrap();
+        assert!(!result.full_vacuum && result.completed);
+        assert!(result.reclaimed_bytes > 1_000_000);
+        assert_eq!(result.after_bytes, std::fs::metadata(&db).unwrap().len());
+        assert_eq!(cache.free_bytes(), 0);
+        std::fs::remove_dir_all(db.parent().unwrap()).unwrap();
+
+        // 旧版本创建的数据库（auto_vacuum=NONE）首次压缩时整体 VACUUM 并切换为增量回收
+        let db = temp_db();
+        Connection::open(&db).unwrap().execute_batch("CREATE TABLE legacy (a INTEGER);").unwrap();
+        let cache = DiskCache::open(&db).unwrap();
+        fill(&cache, "/c/b");
+        assert!(cache.delete_entry("/c/b").unwrap());
+        let result = cache.compact(&Operation::start("compact_disk_cache", None, None)).unwrap();
+        assert!(result.full_vacuum);
+        assert!(result.reclaimed_bytes > 1_000_000);
+        assert_eq!(pragma_i64(&cache.conn.lock(), "auto_vacuum").unwrap(), 2);
+        std::fs::remove_dir_all(db.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn persists_hit_and_miss_counters_across_reopen() {
+        let db = temp_db();
+        {
+            let cache = DiskCache::open(&db).unwrap();
+            cache.insert("/s/a", &empty_result("/s/a"), 5).unwrap();
+            assert!(cache.get("/s/a", 5).is_some());
+            assert!(cache.get("/s/a", 6).is_none()); // 目录已变化
+            assert!(cache.get("/s/missing", 1).is_none());
+        }
+        let cache = DiskCache::open(&db).unwrap();
+        assert!(cache.get("/s/a", 5).is_some());
+        let stats = cache.get_stats();
+        assert_eq!((stats.hits, stats.misses), (2, 2));
+        assert_eq!(stats.hit_rate, 0.5);
+        let size: i64 = cache.conn.lock()
+            .query_row("SELECT size FROM scan_cache WHERE path = '/s/a'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(stats.bytes_served, 2 * size as u64);
+        std::fs::remove_dir_all(db.parent().unwrap()).unwrap();
+    }
+}
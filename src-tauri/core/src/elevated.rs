@@ -0,0 +1,318 @@
+// 提权补扫：扫描中拒绝访问的目录以管理员权限重新遍历，合并回原扫描结果
+//
+// 当前进程已是管理员：启用 SeBackupPrivilege 后直接在进程内遍历；
+// 否则以 runas 启动自身的助手模式（只需确认一次 UAC），助手遍历全部路径后把子树
+// 写入临时 JSON 文件并退出，主进程读取后合并。
+// 其他平台没有 UAC，直接在进程内重试（例如权限已被手动修复）。
+//
+// 扫描常见的受保护根目录（卷根、Windows、Program Files、ProgramData、Users）前先做一次
+// 浅层探测：只尝试打开一级子目录，按权限报告判断提权能否明显提高覆盖率，
+// 建议随扫描结果返回，前端可在扫描前后主动提供提权流程。
+
+use crate::permission_report::{self, PermissionReport, Readability};
+use crate::scan::{self, CompactString, ScanError, ScanResult, SubtreeScan};
+use anyhow::{anyhow, Context};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// 助手模式命令行：`flashdir --rescan-elevated <请求文件> <输出文件>`
+pub const HELPER_FLAG: &str = "--rescan-elevated";
+
+/// 程序启动时调用：命令行为助手模式时执行补扫并返回进程退出码，否则返回 None
+pub fn run_helper_from_args() -> Option<i32> {
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) != Some(HELPER_FLAG) {
+        return None;
+    }
+    let (Some(request), Some(output)) = (args.get(2), args.get(3)) else {
+        eprintln!("用法: {} <请求文件> <输出文件>", HELPER_FLAG);
+        return Some(2);
+    };
+    match run_helper(request, output) {
+        Ok(()) => Some(0),
+        Err(e) => {
+            eprintln!("提权补扫失败: {}", e);
+            Some(1)
+        }
+    }
+}
+
+fn run_helper(request: &str, output: &str) -> anyhow::Result<()> {
+    let paths: Vec<String> = serde_json::from_slice(&std::fs::read(request)?)?;
+    let subtrees = scan_in_process(&paths);
+    std::fs::write(output, serde_json::to_vec(&subtrees)?)?;
+    Ok(())
+}
+
+fn scan_in_process(paths: &[String]) -> Vec<SubtreeScan> {
+    if !crate::fs::enable_backup_privilege() {
+        eprintln!("[Elevated] 未能启用备份特权，按普通权限遍历");
+    }
+    paths
+        .iter()
+        .filter_map(|path| match scan::scan_subtree(path) {
+            Ok(subtree) => Some(subtree),
+            Err(e) => {
+                eprintln!("[Elevated] 补扫失败 {}: {}", path, e);
+                None
+            }
+        })
+        .collect()
+}
+
+fn scan_via_helper(paths: &[String]) -> anyhow::Result<Vec<SubtreeScan>> {
+    let stamp = format!("{}-{}", std::process::id(), chrono::Utc::now().timestamp_millis());
+    let dir = std::env::temp_dir();
+    let request = dir.join(format!("flashdir-rescan-{}.json", stamp));
+    let output = dir.join(format!("flashdir-rescan-{}.out.json", stamp));
+    std::fs::write(&request, serde_json::to_vec(paths)?)?;
+
+    let exit_code = crate::fs::run_self_elevated(&[
+        HELPER_FLAG.to_string(),
+        request.to_string_lossy().into_owned(),
+        output.to_string_lossy().into_owned(),
+    ]);
+    let _ = std::fs::remove_file(&request);
+    let exit_code = exit_code.context("启动提权助手失败（UAC 被拒绝？）")?;
+
+    let data = std::fs::read(&output);
+    let _ = std::fs::remove_file(&output);
+    if exit_code != 0 {
+        return Err(anyhow!("提权助手异常退出（退出码 {}）", exit_code));
+    }
+    Ok(serde_json::from_slice(&data.context("读取提权助手输出失败")?)?)
+}
+
+/// 以管理员权限重新遍历 `paths`（须位于 `root` 之下），合并进 `root` 当前的扫描结果，
+/// 写回缓存并返回合并后的结果
+pub fn rescan_elevated(root: &str, paths: &[String]) -> anyhow::Result<ScanResult> {
+    let (key, mut result) = scan::current_scan_result(root)
+        .ok_or_else(|| anyhow!("没有可合并的扫描结果，请先扫描 {}", root))?;
+    let paths: Vec<String> = paths
+        .iter()
+        .map(|p| crate::fs::display_path(p))
+        .filter(|p| is_within(&result.path, p))
+        .collect();
+    if paths.is_empty() {
+        return Ok(result);
+    }
+
+    let subtrees = if crate::fs::is_admin() || cfg!(not(target_os = "windows")) {
+        scan_in_process(&paths)
+    } else {
+        scan_via_helper(&paths)?
+    };
+    for subtree in subtrees {
+        if is_within(&result.path, &subtree.root) {
+            merge_subtree(&mut result, subtree);
+        }
+    }
+    scan::store_scan_result(&key, &result);
+    Ok(result)
+}
+
+/// 用补扫得到的子树替换结果中的对应部分：子树内原有条目与错误整体替换，
+/// 子树根目录及其祖先目录、总大小按差值调整
+pub fn merge_subtree(result: &mut ScanResult, subtree: SubtreeScan) {
+    let root = subtree.root.as_str();
+    let old_size = result
+        .items
+        .iter()
+        .find(|i| i.path == root)
+        .map(|i| i.size)
+        .unwrap_or(0);
+    let delta = subtree.total_size - old_size;
+
+    result.items.retain(|i| !is_within(root, &i.path));
+    for item in result
+        .items
+        .iter_mut()
+        .filter(|i| i.is_dir && (i.path == root || is_within(&i.path, root)))
+    {
+        item.size += delta;
+        item.size_formatted = scan::format_size(item.size);
+    }
+    result.total_size += delta;
+    result.total_size_formatted = scan::format_size(result.total_size);
+
+    result.errors.retain(|e| e.path != root && !is_within(root, &e.path));
+    result.errors.extend(subtree.errors);
+    result.unreadable_bytes = result.errors.iter().map(|e| e.estimated_bytes).sum();
+    // 替换前的子树无法读取，原结果中不含其云文件统计
+    result.cloud_only_bytes += subtree.cloud_only_bytes;
+    result.cloud_local_bytes += subtree.cloud_local_bytes;
+    result.sparse_files.extend(subtree.sparse_files);
+    result.sparse_savings_bytes = scan::sparse_savings(&result.sparse_files);
+
+    result.items.extend(subtree.items);
+    scan::fill_percentages(&mut result.items, result.total_size);
+    result.items.sort_unstable_by_key(|i| std::cmp::Reverse(i.size));
+}
+
+/// 卷根之外需要探测的受保护目录（相对盘符）
+const PROTECTED_DIRS: [&str; 5] = ["/Windows", "/Program Files", "/Program Files (x86)", "/ProgramData", "/Users"];
+
+/// 提权建议
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ElevationAdvice {
+    /// 有一级子目录拒绝访问且当前未提权：提权可明显提高覆盖率
+    pub recommended: bool,
+    /// 探测的一级子目录数
+    pub probed: usize,
+    /// 拒绝访问的一级子目录
+    pub denied: Vec<String>,
+}
+
+/// 路径（展示形式）是否为常见的受保护根目录
+pub fn is_protected_root(path: &str) -> bool {
+    let path = path.trim_end_matches('/');
+    let bytes = path.as_bytes();
+    if bytes.len() < 2 || bytes[1] != b':' || !bytes[0].is_ascii_alphabetic() {
+        return false;
+    }
+    let rest = &path[2..];
+    rest.is_empty() || PROTECTED_DIRS.iter().any(|dir| rest.eq_ignore_ascii_case(dir))
+}
+
+/// 由浅层探测的权限报告得出建议
+pub fn advise(report: &PermissionReport) -> ElevationAdvice {
+    let denied: Vec<String> = report
+        .dirs
+        .iter()
+        .filter(|d| d.readability == Readability::Denied)
+        .map(|d| d.path.clone())
+        .collect();
+    ElevationAdvice {
+        recommended: report.elevation_may_help && !denied.is_empty(),
+        probed: report.dirs.len(),
+        denied,
+    }
+}
+
+/// 扫描前的浅层探测：只在 Windows、未提权且 path 为受保护根目录时执行（其他情况返回 None）。
+/// 每个一级子目录只尝试打开一次，不读取内容
+pub fn probe(path: &str) -> Option<ElevationAdvice> {
+    if !cfg!(target_os = "windows") || crate::fs::is_admin() {
+        return None;
+    }
+    let root = scan::canonical_display_path(path).unwrap_or_else(|| crate::fs::display_path(path));
+    if !is_protected_root(&root) {
+        return None;
+    }
+
+    let entries = crate::fs::read_dir_entries(&crate::fs::to_extended_length(&root)).ok()?;
+    let mut dirs = Vec::new();
+    let mut errors = Vec::new();
+    for entry in entries.into_iter().filter(|e| e.is_dir && e.kind.descends()) {
+        let dir = crate::fs::display_path(&entry.path.to_string_lossy());
+        if let Err(e) = std::fs::read_dir(&entry.path) {
+            errors.push(ScanError {
+                path: CompactString::from(dir.as_str()),
+                kind: e.kind().into(),
+                estimated_bytes: 0,
+            });
+        }
+        dirs.push(dir);
+    }
+    let report = permission_report::classify(&root, dirs.iter().map(String::as_str), &errors, false);
+    Some(advise(&report))
+}
+
+/// `path` 是否位于 `root` 之下（不含 root 本身）
+fn is_within(root: &str, path: &str) -> bool {
+    path.strip_prefix(root.trim_end_matches('/'))
+        .is_some_and(|rest| rest.len() > 1 && rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{CompactString, Item, ScanError, ScanErrorKind};
+
+    fn denied(path: &str, estimated_bytes: i64) -> ScanError {
+        ScanError {
+            path: CompactString::from(path),
+            kind: ScanErrorKind::AccessDenied,
+            estimated_bytes,
+        }
+    }
+
+    #[test]
+    fn merges_rescanned_subtree_and_adjusts_ancestors() {
+        let mut result = ScanResult {
+            items: vec![
+                Item::test("C:/r/a", 10, true),
+                Item::test("C:/r/a/locked", 0, true),
+                Item::test("C:/r/a/f", 10, false),
+                Item::test("C:/r/b", 5, false),
+            ],
+            total_size: 15,
+            total_size_formatted: CompactString::new(),
+            scan_time: 0.0,
+            path: CompactString::from("C:/r"),
+            mft_available: false,
+            timing: None,
+            perf_metrics: None,
+            extension_stats: None,
+            category_stats: Vec::new(),
+            owners: None,
+            unscanned: None,
+            errors: vec![denied("C:/r/a/locked", 40), denied("C:/r/b2", 7)],
+            unreadable_bytes: 47,
+            cloud_only_bytes: 0,
+            cloud_local_bytes: 0,
+            sparse_files: Vec::new(),
+            sparse_savings_bytes: 0,
+            cache_usage: Default::default(),
+        };
+
+        merge_subtree(
+            &mut result,
+            SubtreeScan {
+                root: CompactString::from("C:/r/a/locked"),
+                total_size: 100,
+                items: vec![Item::test("C:/r/a/locked/x", 60, false), Item::test("C:/r/a/locked/deep", 40, true)],
+                errors: vec![denied("C:/r/a/locked/deep/still", 0)],
+                cloud_only_bytes: 5,
+                cloud_local_bytes: 0,
+                sparse_files: Vec::new(),
+            },
+        );
+
+        let size = |p: &str| result.items.iter().find(|i| i.path == p).map(|i| i.size);
+        assert_eq!(result.total_size, 115);
+        assert_eq!(size("C:/r/a"), Some(110));
+        assert_eq!(size("C:/r/a/locked"), Some(100));
+        assert_eq!(size("C:/r/a/locked/x"), Some(60));
+        // 兄弟条目不受影响，结果按大小降序
+        assert_eq!(size("C:/r/b"), Some(5));
+        assert_eq!(result.items[0].path, "C:/r/a");
+        // 被补扫目录的错误被子树中的错误替换
+        let errors: Vec<&str> = result.errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(errors, ["C:/r/b2", "C:/r/a/locked/deep/still"]);
+        assert_eq!(result.unreadable_bytes, 7);
+        assert_eq!(result.cloud_only_bytes, 5);
+
+        assert!(is_within("C:/", "C:/r") && !is_within("C:/r", "C:/rb") && !is_within("C:/r", "C:/r"));
+    }
+
+    #[test]
+    fn recommends_elevation_for_denied_top_level_dirs() {
+        assert!(is_protected_root("C:/") && is_protected_root("D:") && is_protected_root("C:/program files/"));
+        assert!(!is_protected_root("C:/Users/me") && !is_protected_root("/home") && !is_protected_root("//srv/share"));
+
+        let dirs = ["C:/Windows", "C:/Users", "C:/System Volume Information"];
+        let errors = vec![ScanError {
+            path: CompactString::from("C:/System Volume Information"),
+            kind: ScanErrorKind::AccessDenied,
+            estimated_bytes: 0,
+        }];
+        let advice = advise(&permission_report::classify("C:/", dirs, &errors, false));
+        assert!(advice.recommended);
+        assert_eq!((advice.probed, advice.denied.as_slice()), (3, &["C:/System Volume Information".to_string()][..]));
+
+        assert!(!advise(&permission_report::classify("C:/", dirs, &errors, true)).recommended);
+        assert!(!advise(&permission_report::classify("C:/", dirs, &[], false)).recommended);
+    }
+}
@@ -0,0 +1,297 @@
+// 抽样估算目录总大小
+//
+// 超大目录树（整盘、NAS 共享）完整遍历需要数分钟，概览页只需要一个量级。
+// 估算分两步：
+// 1. 先广度优先精确列出前 EXACT_DIR_LIMIT 个目录（最多占一半时间预算）；
+//    目录树在此范围内遍历完时直接返回精确结果（estimated = false）
+// 2. 否则对尚未列出的“前沿”目录做随机探测（Knuth 树大小估计）：均匀选一个前沿目录，
+//    沿随机子目录一路向下直到叶子，每层的文件大小乘以沿途分支数之积，再乘以前沿目录数。
+//    每次探测都是前沿子树总大小的无偏估计，取均值并由样本方差给出 95% 置信区间
+//
+// 目录列表在探测间复用，上层目录只读一次。目录大小分布越不均匀（少数目录占绝大部分空间），
+// 区间越宽；结果带 estimated 标志，界面应标注为“估算”。
+// 不读写缓存、不发事件；遵循排除预设与时间预算（默认 DEFAULT_BUDGET_MS）。
+
+use crate::exclusions::Exclusions;
+use crate::scan::{format_size, CompactString, ScanOptions};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 默认时间预算（毫秒）
+pub const DEFAULT_BUDGET_MS: u64 = 800;
+
+/// 精确列出的目录数上限
+const EXACT_DIR_LIMIT: usize = 2000;
+
+/// 超出时间预算也至少完成的探测次数（否则无法计算方差）
+const MIN_PROBES: u32 = 16;
+
+/// 探测次数上限
+const MAX_PROBES: u32 = 20_000;
+
+/// 95% 置信区间的正态分位数
+const Z_95: f64 = 1.96;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeEstimate {
+    #[schemars(with = "String")]
+    pub path: CompactString,
+    /// true 为抽样估算（界面标注“估算”）；false 表示目录树已完整遍历，数值精确
+    pub estimated: bool,
+    pub total_size: i64,
+    #[schemars(with = "String")]
+    pub total_size_formatted: CompactString,
+    /// 置信区间下限 / 上限（精确结果时与 total_size 相同）
+    pub low: i64,
+    pub high: i64,
+    /// 置信水平（0.95）
+    pub confidence: f64,
+    pub file_count: u64,
+    pub dir_count: u64,
+    /// 随机探测次数
+    pub probes: u32,
+    /// 实际读取的目录数
+    pub sampled_dirs: u64,
+    /// 无法读取的目录数（按空目录计入）
+    pub error_count: u64,
+    pub scan_time: f64,
+}
+
+/// 一个目录的直接内容
+#[derive(Default)]
+struct Listing {
+    bytes: u64,
+    files: u64,
+    dirs: u64,
+    /// 可进入的子目录（已排序，探测结果只取决于随机种子）
+    subdirs: Vec<PathBuf>,
+}
+
+/// SplitMix64：估算只需要均匀的下标，不值得为此引入随机数依赖
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+struct Sampler {
+    exclusions: Exclusions,
+    listings: HashMap<PathBuf, Listing>,
+    errors: u64,
+}
+
+impl Sampler {
+    fn list(&mut self, dir: &Path) -> &Listing {
+        if !self.listings.contains_key(dir) {
+            let mut listing = Listing::default();
+            match crate::fs::read_dir_entries(dir) {
+                Ok(entries) => {
+                    for entry in entries {
+                        if !self.exclusions.is_empty()
+                            && self.exclusions.excludes(
+                                &crate::fs::display_path(&entry.path.to_string_lossy()),
+                                &entry.name,
+                                entry.is_dir,
+                            )
+                        {
+                            continue;
+                        }
+                        if entry.is_dir {
+                            listing.dirs += 1;
+                            // 符号链接 / 挂载点计为目录但不进入，与完整扫描一致
+                            if entry.kind.descends() {
+                                listing.subdirs.push(entry.path);
+                            }
+                        } else {
+                            listing.files += 1;
+                            listing.bytes += entry.size;
+                        }
+                    }
+                }
+                Err(_) => self.errors += 1,
+            }
+            listing.subdirs.sort_unstable();
+            self.listings.insert(dir.to_path_buf(), listing);
+        }
+        &self.listings[dir]
+    }
+}
+
+/// 一次探测对前沿子树（文件大小, 文件数, 目录数）之和的估计
+fn probe(sampler: &mut Sampler, frontier: &[PathBuf], rng: &mut SplitMix64) -> (f64, f64, f64) {
+    let mut weight = frontier.len() as f64;
+    let mut dir = frontier[rng.below(frontier.len())].clone();
+    let (mut bytes, mut files, mut dirs) = (0.0, 0.0, 0.0);
+    loop {
+        let listing = sampler.list(&dir);
+        bytes += weight * listing.bytes as f64;
+        files += weight * listing.files as f64;
+        dirs += weight * listing.dirs as f64;
+        if listing.subdirs.is_empty() {
+            return (bytes, files, dirs);
+        }
+        weight *= listing.subdirs.len() as f64;
+        dir = listing.subdirs[rng.below(listing.subdirs.len())].clone();
+    }
+}
+
+fn mean_and_half_width(samples: &[f64]) -> (f64, f64) {
+    let n = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / n;
+    if samples.len() < 2 {
+        return (mean, 0.0);
+    }
+    let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, Z_95 * (variance / n).sqrt())
+}
+
+fn estimate_with(
+    root: &Path,
+    exclusions: Exclusions,
+    budget: Duration,
+    exact_limit: usize,
+    seed: u64,
+) -> SizeEstimate {
+    let start = Instant::now();
+    let mut sampler = Sampler { exclusions, listings: HashMap::new(), errors: 0 };
+
+    // 1. 广度优先精确部分
+    let (mut bytes, mut files, mut dirs) = (0u64, 0u64, 0u64);
+    let mut queue = VecDeque::from([root.to_path_buf()]);
+    let mut listed = 0usize;
+    while listed < exact_limit && start.elapsed() < budget / 2 {
+        let Some(dir) = queue.pop_front() else { break };
+        let listing = sampler.list(&dir);
+        bytes += listing.bytes;
+        files += listing.files;
+        dirs += listing.dirs;
+        queue.extend(listing.subdirs.iter().cloned());
+        listed += 1;
+    }
+    let frontier: Vec<PathBuf> = queue.into();
+    // 精确部分的目录列表不会再被探测用到
+    sampler.listings.clear();
+    let sampled_exact = listed as u64;
+
+    let mut result = SizeEstimate {
+        path: CompactString::from(crate::fs::display_path(&root.to_string_lossy()).as_str()),
+        estimated: !frontier.is_empty(),
+        total_size: bytes as i64,
+        total_size_formatted: CompactString::new(),
+        low: bytes as i64,
+        high: bytes as i64,
+        confidence: 0.95,
+        file_count: files,
+        dir_count: dirs,
+        probes: 0,
+        sampled_dirs: sampled_exact,
+        error_count: 0,
+        scan_time: 0.0,
+    };
+
+    // 2. 随机探测前沿子树
+    if !frontier.is_empty() {
+        let mut rng = SplitMix64(seed);
+        let (mut size_samples, mut file_sum, mut dir_sum) = (Vec::new(), 0.0, 0.0);
+        while size_samples.len() < MAX_PROBES as usize
+            && (size_samples.len() < MIN_PROBES as usize || start.elapsed() < budget)
+        {
+            let (b, f, d) = probe(&mut sampler, &frontier, &mut rng);
+            size_samples.push(b);
+            file_sum += f;
+            dir_sum += d;
+        }
+        let n = size_samples.len() as f64;
+        let (mean, half_width) = mean_and_half_width(&size_samples);
+        let known = bytes as f64;
+        result.total_size = (known + mean).round() as i64;
+        // 已精确列出的部分是确定的下限
+        result.low = (known + mean - half_width).max(known).round() as i64;
+        result.high = (known + mean + half_width).round() as i64;
+        result.file_count = files + (file_sum / n).round() as u64;
+        result.dir_count = dirs + (dir_sum / n).round() as u64;
+        result.probes = size_samples.len() as u32;
+        result.sampled_dirs += sampler.listings.len() as u64;
+    }
+
+    result.total_size_formatted = format_size(result.total_size);
+    result.error_count = sampler.errors;
+    result.scan_time = start.elapsed().as_secs_f64();
+    result
+}
+
+/// 抽样估算目录总大小、文件数与目录数，带 95% 置信区间。
+/// 遵循 ScanOptions 的排除预设与时间预算（未设置时为 DEFAULT_BUDGET_MS），其余选项不适用
+pub fn estimate_directory(path: &str, options: &ScanOptions) -> anyhow::Result<SizeEstimate> {
+    let canonical = std::fs::canonicalize(crate::fs::to_extended_length(path))?;
+    if !canonical.is_dir() {
+        return Err(anyhow::anyhow!("不是目录"));
+    }
+    let budget = Duration::from_millis(options.time_budget_ms.unwrap_or(DEFAULT_BUDGET_MS));
+    let seed = uuid::Uuid::new_v4().as_u64_pair().0;
+    Ok(estimate_with(&canonical, Exclusions::new(&options.exclude_presets), budget, EXACT_DIR_LIMIT, seed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// width^depth 个叶子目录，每个目录 files 个 size 字节的文件
+    fn uniform_tree(root: &Path, width: usize, depth: usize, files: usize, size: usize) {
+        std::fs::create_dir_all(root).unwrap();
+        for i in 0..files {
+            std::fs::write(root.join(format!("f{i}")), vec![0u8; size]).unwrap();
+        }
+        if depth > 0 {
+            for i in 0..width {
+                uniform_tree(&root.join(format!("d{i}")), width, depth - 1, files, size);
+            }
+        }
+    }
+
+    #[test]
+    fn small_tree_is_exact_and_uniform_tree_estimate_has_no_spread() {
+        let root = std::env::temp_dir().join(format!("flashdir-estimate-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        // 1 + 3 + 9 + 27 = 40 个目录，各 2 个 100 字节文件
+        uniform_tree(&root, 3, 3, 2, 100);
+        let budget = Duration::from_secs(5);
+
+        let exact = estimate_with(&root, Exclusions::default(), budget, EXACT_DIR_LIMIT, 1);
+        assert!(!exact.estimated);
+        assert_eq!((exact.total_size, exact.file_count, exact.dir_count), (8000, 80, 39));
+        assert_eq!((exact.low, exact.high, exact.probes), (8000, 8000, 0));
+
+        // 只精确列出根目录，其余靠探测：每层分支数相同，每次探测都给出真实值
+        let sampled = estimate_with(&root, Exclusions::default(), budget, 1, 7);
+        assert!(sampled.estimated);
+        assert!(sampled.probes >= MIN_PROBES);
+        assert_eq!((sampled.total_size, sampled.file_count, sampled.dir_count), (8000, 80, 39));
+        assert_eq!((sampled.low, sampled.high), (8000, 8000));
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn interval_half_width_follows_sample_spread() {
+        let (mean, half) = mean_and_half_width(&[10.0, 10.0, 10.0]);
+        assert_eq!((mean, half), (10.0, 0.0));
+        let (mean, half) = mean_and_half_width(&[0.0, 20.0, 0.0, 20.0]);
+        assert_eq!(mean, 10.0);
+        assert!((half - Z_95 * (400.0f64 / 3.0 / 4.0).sqrt()).abs() < 1e-9);
+    }
+}
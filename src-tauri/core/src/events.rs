@@ -0,0 +1,34 @@
+// 事件通道
+//
+// 核心库不依赖 Tauri：扫描进度、流式结果等事件经 EventSink 发出。
+// GUI 把事件转发给前端，CLI / 第三方工具可自行实现，或传 None 不接收事件。
+
+use serde::Serialize;
+use std::sync::Arc;
+
+/// 事件接收方。扫描工作线程会直接调用，须线程安全且尽快返回
+pub trait EventSink: Send + Sync {
+    fn emit_json(&self, event: &str, payload: serde_json::Value);
+}
+
+/// 可在线程间共享的事件接收方
+pub type Events = Arc<dyn EventSink>;
+
+impl dyn EventSink + '_ {
+    /// 序列化负载后发出事件（序列化失败时丢弃）
+    pub fn emit<T: Serialize>(&self, event: &str, payload: T) {
+        if let Ok(value) = serde_json::to_value(payload) {
+            self.emit_json(event, value);
+        }
+    }
+}
+
+/// 闭包即事件接收方：`Arc::new(|event: &str, payload| println!("{event}: {payload}"))`
+impl<F> EventSink for F
+where
+    F: Fn(&str, serde_json::Value) + Send + Sync,
+{
+    fn emit_json(&self, event: &str, payload: serde_json::Value) {
+        self(event, payload)
+    }
+}
@@ -0,0 +1,185 @@
+// 内置排除预设
+//
+// 系统目录（WinSxS 的硬链接组件存储、卷影副本、页面文件、回收站、Linux 伪文件系统）
+// 体积大或大小无意义，用户又无法直接清理。扫描时可按需勾选预设，命中的条目在遍历中
+// 直接跳过：不产生 item、不进入、不计入总大小。
+//
+// 排除后的结果与完整结果不同，启用的预设记录在缓存 key 中（见 Exclusions::cache_key），
+// 两种结果互不覆盖。
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// 排除预设（判别值为 Exclusions 位掩码中的位序号，只能追加）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ExclusionPreset {
+    /// Windows\WinSxS 组件存储（大量硬链接，逻辑大小远大于实际占用）
+    WinSxs = 0,
+    /// System Volume Information（卷影副本、还原点）
+    SystemVolumeInformation = 1,
+    /// pagefile.sys / swapfile.sys
+    PageFile = 2,
+    /// $Recycle.Bin
+    RecycleBin = 3,
+    /// /proc
+    Proc = 4,
+    /// /sys
+    Sys = 5,
+}
+
+impl ExclusionPreset {
+    pub const ALL: [ExclusionPreset; 6] = [
+        ExclusionPreset::WinSxs,
+        ExclusionPreset::SystemVolumeInformation,
+        ExclusionPreset::PageFile,
+        ExclusionPreset::RecycleBin,
+        ExclusionPreset::Proc,
+        ExclusionPreset::Sys,
+    ];
+
+    /// 预设 ID（与 serde 名称一致，用于缓存 key）
+    pub fn id(self) -> &'static str {
+        match self {
+            ExclusionPreset::WinSxs => "winSxs",
+            ExclusionPreset::SystemVolumeInformation => "systemVolumeInformation",
+            ExclusionPreset::PageFile => "pageFile",
+            ExclusionPreset::RecycleBin => "recycleBin",
+            ExclusionPreset::Proc => "proc",
+            ExclusionPreset::Sys => "sys",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ExclusionPreset::WinSxs => "Windows\\WinSxS",
+            ExclusionPreset::SystemVolumeInformation => "System Volume Information",
+            ExclusionPreset::PageFile => "pagefile.sys / swapfile.sys",
+            ExclusionPreset::RecycleBin => "$Recycle.Bin",
+            ExclusionPreset::Proc => "/proc",
+            ExclusionPreset::Sys => "/sys",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            ExclusionPreset::WinSxs => "组件存储，文件多为系统目录的硬链接，按逻辑大小会重复计算",
+            ExclusionPreset::SystemVolumeInformation => "卷影副本与系统还原点，通常无权读取",
+            ExclusionPreset::PageFile => "虚拟内存页面文件，由系统管理",
+            ExclusionPreset::RecycleBin => "回收站，可在资源管理器中清空",
+            ExclusionPreset::Proc => "进程信息伪文件系统，大小无意义",
+            ExclusionPreset::Sys => "内核对象伪文件系统，大小无意义",
+        }
+    }
+
+    /// 预设针对的平台
+    fn windows(self) -> bool {
+        !matches!(self, ExclusionPreset::Proc | ExclusionPreset::Sys)
+    }
+
+    /// path 为规范化后的绝对路径（正斜杠）
+    fn matches(self, path: &str, name: &str, is_dir: bool) -> bool {
+        match self {
+            ExclusionPreset::WinSxs => is_dir && ends_with_ignore_case(path, "/windows/winsxs"),
+            ExclusionPreset::SystemVolumeInformation => {
+                is_dir && name.eq_ignore_ascii_case("System Volume Information")
+            }
+            ExclusionPreset::PageFile => {
+                !is_dir && (name.eq_ignore_ascii_case("pagefile.sys") || name.eq_ignore_ascii_case("swapfile.sys"))
+            }
+            ExclusionPreset::RecycleBin => is_dir && name.eq_ignore_ascii_case("$Recycle.Bin"),
+            ExclusionPreset::Proc => is_dir && path == "/proc",
+            ExclusionPreset::Sys => is_dir && path == "/sys",
+        }
+    }
+}
+
+fn ends_with_ignore_case(path: &str, suffix: &str) -> bool {
+    path.len() >= suffix.len()
+        && path.is_char_boundary(path.len() - suffix.len())
+        && path[path.len() - suffix.len()..].eq_ignore_ascii_case(suffix)
+}
+
+/// 供前端列出可勾选的预设
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExclusionPresetInfo {
+    pub id: ExclusionPreset,
+    pub label: String,
+    pub description: String,
+    /// 预设针对当前平台（其他平台的预设仍可勾选，但通常不会命中）
+    pub current_platform: bool,
+}
+
+pub fn presets() -> Vec<ExclusionPresetInfo> {
+    ExclusionPreset::ALL
+        .iter()
+        .map(|&preset| ExclusionPresetInfo {
+            id: preset,
+            label: preset.label().to_string(),
+            description: preset.description().to_string(),
+            current_platform: preset.windows() == cfg!(target_os = "windows"),
+        })
+        .collect()
+}
+
+/// 一次扫描启用的预设集合（位掩码，可随遍历参数按值传递）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Exclusions(u8);
+
+impl Exclusions {
+    pub fn new(presets: &[ExclusionPreset]) -> Self {
+        Self(presets.iter().fold(0, |mask, &p| mask | 1 << p as u8))
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    fn iter(self) -> impl Iterator<Item = ExclusionPreset> {
+        ExclusionPreset::ALL.into_iter().filter(move |&p| self.0 & (1 << p as u8) != 0)
+    }
+
+    /// 条目是否被排除（path 为规范化后的绝对路径）
+    #[inline]
+    pub fn excludes(self, path: &str, name: &str, is_dir: bool) -> bool {
+        !self.is_empty() && self.iter().any(|p| p.matches(path, name, is_dir))
+    }
+
+    /// 缓存 key：未启用预设时即根路径，否则附加按固定顺序排列的预设 ID。
+    /// `|` 不会出现在 Windows 路径中；前缀仍是根路径，按前缀失效缓存时一并失效
+    pub fn cache_key(self, root: &str) -> String {
+        if self.is_empty() {
+            return root.to_string();
+        }
+        let ids: Vec<&str> = self.iter().map(ExclusionPreset::id).collect();
+        format!("{}|exclude={}", root, ids.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_presets_and_keys_cache() {
+        let all = Exclusions::new(&ExclusionPreset::ALL);
+        assert!(all.excludes("C:/Windows/WinSxS", "WinSxS", true));
+        assert!(!all.excludes("C:/Windows/WinSxS", "WinSxS", false));
+        assert!(all.excludes("D:/System Volume Information", "System Volume Information", true));
+        assert!(all.excludes("C:/pagefile.sys", "pagefile.sys", false));
+        assert!(all.excludes("C:/$RECYCLE.BIN", "$RECYCLE.BIN", true));
+        assert!(all.excludes("/proc", "proc", true));
+        assert!(!all.excludes("/home/u/proc", "proc", true));
+        assert!(!all.excludes("C:/Windows/System32", "System32", true));
+        assert!(!Exclusions::default().excludes("/proc", "proc", true));
+
+        // 预设顺序不影响缓存 key
+        let a = Exclusions::new(&[ExclusionPreset::Sys, ExclusionPreset::Proc]);
+        let b = Exclusions::new(&[ExclusionPreset::Proc, ExclusionPreset::Sys, ExclusionPreset::Proc]);
+        assert_eq!(a.cache_key("/"), "/|exclude=proc,sys");
+        assert_eq!(a.cache_key("/"), b.cache_key("/"));
+        assert_eq!(Exclusions::default().cache_key("/data"), "/data");
+        assert_eq!(presets().len(), ExclusionPreset::ALL.len());
+    }
+}
@@ -0,0 +1,418 @@
+// 扫描结果导出：把会话的完整条目列表（不分页）写成 CSV、NDJSON 或 XLSX
+//
+// 在后端逐行写出，百万级条目不经过 IPC 传给前端。列可配置，缺省为 DEFAULT_COLUMNS。
+// 写入 "<dest>.part"，完成后改名为 dest，失败或取消时删除未写完的文件；
+// 每写出 CHUNK 行检查一次取消、上报一次进度。
+//
+// - CSV：带 UTF-8 BOM（Excel 据此识别编码，否则中文路径乱码），时间为本地时间文本
+// - NDJSON：每行一个 JSON 对象，键为列名，时间为 Unix 秒
+// - XLSX：最小的 SpreadsheetML 包（zip），单元格用内联字符串，不需要共享字符串表；
+//   超过单表行数上限时依次写入 Sheet2、Sheet3…
+
+use crate::operation::Operation;
+use crate::scan::{format_size, CompactString, Item};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// 每写出 CHUNK 行检查一次取消、上报一次进度
+const CHUNK: usize = 10_000;
+/// Excel 单个工作表的行数上限（含表头）
+const XLSX_MAX_ROWS: usize = 1_048_576;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ScanExportFormat {
+    #[default]
+    Csv,
+    Ndjson,
+    Xlsx,
+}
+
+/// 导出列；表头与 NDJSON 的键为其 camelCase 名称
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportColumn {
+    Path,
+    Name,
+    Extension,
+    Size,
+    SizeFormatted,
+    IsDir,
+    Kind,
+    Category,
+    Modified,
+    Created,
+    FileCount,
+    DirCount,
+    PercentOfParent,
+    PercentOfTotal,
+    Id,
+}
+
+/// 未指定列时导出的列
+pub const DEFAULT_COLUMNS: &[ExportColumn] = &[
+    ExportColumn::Path,
+    ExportColumn::Name,
+    ExportColumn::Size,
+    ExportColumn::IsDir,
+    ExportColumn::Modified,
+    ExportColumn::Created,
+    ExportColumn::FileCount,
+    ExportColumn::DirCount,
+];
+
+impl ExportColumn {
+    fn header(self) -> &'static str {
+        match self {
+            ExportColumn::Path => "path",
+            ExportColumn::Name => "name",
+            ExportColumn::Extension => "extension",
+            ExportColumn::Size => "size",
+            ExportColumn::SizeFormatted => "sizeFormatted",
+            ExportColumn::IsDir => "isDir",
+            ExportColumn::Kind => "kind",
+            ExportColumn::Category => "category",
+            ExportColumn::Modified => "modified",
+            ExportColumn::Created => "created",
+            ExportColumn::FileCount => "fileCount",
+            ExportColumn::DirCount => "dirCount",
+            ExportColumn::PercentOfParent => "percentOfParent",
+            ExportColumn::PercentOfTotal => "percentOfTotal",
+            ExportColumn::Id => "id",
+        }
+    }
+
+    fn cell(self, item: &Item) -> Cell<'_> {
+        match self {
+            ExportColumn::Path => Cell::Text(Cow::Borrowed(&item.path)),
+            ExportColumn::Name => Cell::Text(Cow::Borrowed(&item.name)),
+            ExportColumn::Extension => Cell::Text(Cow::Owned(
+                (!item.is_dir).then(|| crate::scan::extension_of(&item.name)).flatten().unwrap_or_default(),
+            )),
+            ExportColumn::Size => Cell::Int(item.size),
+            ExportColumn::SizeFormatted => Cell::Text(Cow::Borrowed(&item.size_formatted)),
+            ExportColumn::IsDir => Cell::Bool(item.is_dir),
+            ExportColumn::Kind => Cell::Text(Cow::Owned(enum_name(&item.kind))),
+            ExportColumn::Category => Cell::Text(Cow::Owned(enum_name(&item.category))),
+            ExportColumn::Modified => Cell::Time(item.modified),
+            ExportColumn::Created => Cell::Time(item.created),
+            ExportColumn::FileCount => Cell::Int(item.file_count as i64),
+            ExportColumn::DirCount => Cell::Int(item.dir_count as i64),
+            ExportColumn::PercentOfParent => Cell::Float(item.percent_of_parent as f64),
+            ExportColumn::PercentOfTotal => Cell::Float(item.percent_of_total as f64),
+            // JS Number 可无损表示（稳定 ID 只保留低 53 位）
+            ExportColumn::Id => Cell::Int(item.id as i64),
+        }
+    }
+}
+
+/// 单元格值
+enum Cell<'a> {
+    Text(Cow<'a, str>),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    /// Unix 秒，0 表示未知
+    Time(i64),
+}
+
+impl Cell<'_> {
+    /// CSV / XLSX 中的文本形式；时间为本地时间，未知时为空
+    fn display(&self) -> Cow<'_, str> {
+        match self {
+            Cell::Text(s) => Cow::Borrowed(s.as_ref()),
+            Cell::Int(n) => Cow::Owned(n.to_string()),
+            Cell::Float(f) => Cow::Owned(format!("{:.2}", f)),
+            Cell::Bool(b) => Cow::Borrowed(if *b { "true" } else { "false" }),
+            Cell::Time(0) => Cow::Borrowed(""),
+            Cell::Time(t) => Cow::Owned(
+                chrono::DateTime::from_timestamp(*t, 0)
+                    .map(|d| d.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or_default(),
+            ),
+        }
+    }
+
+    fn json(&self) -> serde_json::Value {
+        match self {
+            Cell::Text(s) => serde_json::Value::from(s.as_ref()),
+            Cell::Int(n) | Cell::Time(n) => serde_json::Value::from(*n),
+            Cell::Float(f) => serde_json::Value::from(*f),
+            Cell::Bool(b) => serde_json::Value::from(*b),
+        }
+    }
+}
+
+/// 序列化后的枚举名（camelCase，与前端一致）
+fn enum_name<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanExportResult {
+    pub dest: String,
+    pub format: ScanExportFormat,
+    pub rows: usize,
+    pub file_size: i64,
+    #[schemars(with = "String")]
+    pub file_size_formatted: CompactString,
+}
+
+/// 把 items 按 columns（为空时取 DEFAULT_COLUMNS）导出到 dest，经 op 上报 "write" 阶段的进度
+pub fn export_scan(
+    items: &[Item],
+    dest: &str,
+    format: ScanExportFormat,
+    columns: &[ExportColumn],
+    op: &Operation,
+) -> anyhow::Result<ScanExportResult> {
+    let columns = if columns.is_empty() { DEFAULT_COLUMNS } else { columns };
+    let dest_path = crate::fs::to_extended_length(dest);
+    let part = PathBuf::from(format!("{}.part", dest_path.to_string_lossy()));
+
+    let total = items.len().max(1) as f64;
+    let mut tick = |written: usize| -> anyhow::Result<()> {
+        if written.is_multiple_of(CHUNK) {
+            op.check()?;
+            op.progress("write", written as f64 * 100.0 / total);
+        }
+        Ok(())
+    };
+    let out = std::io::BufWriter::new(std::fs::File::create(&part)?);
+    let written = match format {
+        ScanExportFormat::Csv => write_csv(out, items, columns, &mut tick),
+        ScanExportFormat::Ndjson => write_ndjson(out, items, columns, &mut tick),
+        ScanExportFormat::Xlsx => write_xlsx(out, items, columns, &mut tick),
+    };
+    if let Err(e) = written.and_then(|()| Ok(std::fs::rename(&part, &dest_path)?)) {
+        std::fs::remove_file(&part).ok();
+        return Err(e);
+    }
+    op.progress("write", 100.0);
+
+    let file_size = std::fs::metadata(&dest_path).map_or(0, |m| m.len() as i64);
+    Ok(ScanExportResult {
+        dest: crate::fs::display_path(dest),
+        format,
+        rows: items.len(),
+        file_size,
+        file_size_formatted: format_size(file_size),
+    })
+}
+
+type Tick<'a> = dyn FnMut(usize) -> anyhow::Result<()> + 'a;
+
+fn write_csv(mut out: impl Write, items: &[Item], columns: &[ExportColumn], tick: &mut Tick) -> anyhow::Result<()> {
+    out.write_all("\u{feff}".as_bytes())?;
+    let headers: Vec<&str> = columns.iter().map(|c| c.header()).collect();
+    writeln!(out, "{}", headers.join(","))?;
+    for (n, item) in items.iter().enumerate() {
+        tick(n)?;
+        let row: Vec<String> = columns.iter().map(|c| csv_field(&c.cell(item).display())).collect();
+        writeln!(out, "{}", row.join(","))?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn write_ndjson(mut out: impl Write, items: &[Item], columns: &[ExportColumn], tick: &mut Tick) -> anyhow::Result<()> {
+    for (n, item) in items.iter().enumerate() {
+        tick(n)?;
+        let row: serde_json::Map<String, serde_json::Value> =
+            columns.iter().map(|c| (c.header().to_string(), c.cell(item).json())).collect();
+        serde_json::to_writer(&mut out, &row)?;
+        out.write_all(b"\n")?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn write_xlsx<W: Write + std::io::Seek>(
+    out: W,
+    items: &[Item],
+    columns: &[ExportColumn],
+    tick: &mut Tick,
+) -> anyhow::Result<()> {
+    use zip::write::SimpleFileOptions;
+
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut zip = zip::ZipWriter::new(out);
+
+    // 每个工作表一行表头，其余为数据行；没有条目时也写出只有表头的 Sheet1
+    let per_sheet = XLSX_MAX_ROWS - 1;
+    let sheets = items.len().div_ceil(per_sheet).max(1);
+    for sheet in 0..sheets {
+        let start = sheet * per_sheet;
+        let rows = &items[start.min(items.len())..(start + per_sheet).min(items.len())];
+        zip.start_file(format!("xl/worksheets/sheet{}.xml", sheet + 1), options)?;
+        zip.write_all(
+            b"<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+              <worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\"><sheetData>",
+        )?;
+        write_xlsx_row(&mut zip, 1, columns.iter().map(|c| Cell::Text(Cow::Borrowed(c.header()))))?;
+        for (n, item) in rows.iter().enumerate() {
+            tick(start + n)?;
+            write_xlsx_row(&mut zip, n + 2, columns.iter().map(|c| c.cell(item)))?;
+        }
+        zip.write_all(b"</sheetData></worksheet>")?;
+    }
+
+    let sheet_entries: String = (1..=sheets)
+        .map(|i| format!("<sheet name=\"Sheet{0}\" sheetId=\"{0}\" r:id=\"rId{0}\"/>", i))
+        .collect();
+    let sheet_rels: String = (1..=sheets)
+        .map(|i| {
+            format!(
+                "<Relationship Id=\"rId{0}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" Target=\"worksheets/sheet{0}.xml\"/>",
+                i
+            )
+        })
+        .collect();
+    let sheet_types: String = (1..=sheets)
+        .map(|i| {
+            format!(
+                "<Override PartName=\"/xl/worksheets/sheet{}.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>",
+                i
+            )
+        })
+        .collect();
+    let parts = [
+        (
+            "[Content_Types].xml",
+            format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+                 <Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+                 <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+                 <Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+                 <Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>\
+                 {}</Types>",
+                sheet_types
+            ),
+        ),
+        (
+            "_rels/.rels",
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+             <Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"xl/workbook.xml\"/>\
+             </Relationships>"
+                .to_string(),
+        ),
+        (
+            "xl/workbook.xml",
+            format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+                 <workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" \
+                 xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\
+                 <sheets>{}</sheets></workbook>",
+                sheet_entries
+            ),
+        ),
+        (
+            "xl/_rels/workbook.xml.rels",
+            format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+                 <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">{}</Relationships>",
+                sheet_rels
+            ),
+        ),
+    ];
+    for (name, contents) in parts {
+        zip.start_file(name, options)?;
+        zip.write_all(contents.as_bytes())?;
+    }
+    zip.finish()?.flush()?;
+    Ok(())
+}
+
+/// 写出一行：数字与布尔为数值单元格，其余为内联字符串
+fn write_xlsx_row<'a>(out: &mut impl Write, row: usize, cells: impl Iterator<Item = Cell<'a>>) -> anyhow::Result<()> {
+    write!(out, "<row r=\"{}\">", row)?;
+    for cell in cells {
+        match &cell {
+            Cell::Int(n) => write!(out, "<c><v>{}</v></c>", n)?,
+            Cell::Float(f) => write!(out, "<c><v>{}</v></c>", f)?,
+            Cell::Bool(b) => write!(out, "<c t=\"b\"><v>{}</v></c>", *b as u8)?,
+            _ => write!(out, "<c t=\"inlineStr\"><is><t xml:space=\"preserve\">{}</t></is></c>", xml_text(&cell.display()))?,
+        }
+    }
+    out.write_all(b"</row>")?;
+    Ok(())
+}
+
+/// 转义 XML 特殊字符；XML 1.0 不允许的控制字符（Unix 文件名中可能出现）替换为 U+FFFD
+fn xml_text(s: &str) -> Cow<'_, str> {
+    if !s.contains(|c: char| matches!(c, '&' | '<' | '>' | '"') || (c.is_control() && !matches!(c, '\t' | '\n' | '\r'))) {
+        return Cow::Borrowed(s);
+    }
+    let mut out = String::with_capacity(s.len() + 16);
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if (c as u32) < 0x20 => out.push('\u{fffd}'),
+            c => out.push(c),
+        }
+    }
+    Cow::Owned(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn item(path: &str, size: i64, is_dir: bool) -> Item {
+        let item = Item::test(path, size, is_dir);
+        Item { size_formatted: format_size(size), id: 7, ..item }
+    }
+
+    #[test]
+    fn exports_selected_columns_in_each_format() {
+        let dir = std::env::temp_dir().join(format!("flashdir-export-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let items = vec![item("/data/a,b.txt", 10, false), item("/data/<dir>", 20, true)];
+        let columns = [ExportColumn::Name, ExportColumn::Size, ExportColumn::IsDir];
+        let op = Operation::start("export_scan", None, None);
+
+        let csv = dir.join("out.csv").to_string_lossy().to_string();
+        let result = export_scan(&items, &csv, ScanExportFormat::Csv, &columns, &op).unwrap();
+        assert_eq!(result.rows, 2);
+        let text = std::fs::read_to_string(&csv).unwrap();
+        assert_eq!(text, "\u{feff}name,size,isDir\n\"a,b.txt\",10,false\n<dir>,20,true\n");
+
+        let ndjson = dir.join("out.ndjson").to_string_lossy().to_string();
+        export_scan(&items, &ndjson, ScanExportFormat::Ndjson, &[], &op).unwrap();
+        let first: serde_json::Value =
+            serde_json::from_str(std::fs::read_to_string(&ndjson).unwrap().lines().next().unwrap()).unwrap();
+        assert_eq!((first["path"].as_str(), first["size"].as_i64()), (Some("/data/a,b.txt"), Some(10)));
+
+        let xlsx = dir.join("out.xlsx").to_string_lossy().to_string();
+        export_scan(&items, &xlsx, ScanExportFormat::Xlsx, &columns, &op).unwrap();
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&xlsx).unwrap()).unwrap();
+        let mut sheet = String::new();
+        archive.by_name("xl/worksheets/sheet1.xml").unwrap().read_to_string(&mut sheet).unwrap();
+        assert!(sheet.contains("<row r=\"3\"><c t=\"inlineStr\"><is><t xml:space=\"preserve\">&lt;dir&gt;</t></is></c><c><v>20</v></c><c t=\"b\"><v>1</v></c></row>"));
+        assert!(archive.by_name("xl/workbook.xml").is_ok() && archive.by_name("[Content_Types].xml").is_ok());
+        assert!(!std::path::Path::new(&format!("{}.part", xlsx)).exists());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,171 @@
+// 文件年龄分析模块
+//
+// 按最后修改时间把文件分入固定年龄段（<30 天 / 30–180 天 / 180 天–1 年 / >1 年），
+// 统计每段的数量与空间占用，并列出最大的陈旧文件，帮助找出可归档的冷数据。
+//
+// 直接使用扫描时采集的 Item::modified，不再额外 stat 文件。
+
+use serde::Serialize;
+use schemars::JsonSchema;
+use crate::scan::{Item, format_size};
+
+const DAY: i64 = 86_400;
+
+/// 陈旧文件阈值：超过 1 年未修改
+const STALE_DAYS: i64 = 365;
+
+/// 陈旧文件 Top 列表长度
+const TOP_STALE_FILES: usize = 20;
+
+/// 年龄段：(标签, 下界天数含, 上界天数不含)
+const BUCKETS: [(&str, i64, i64); 4] = [
+    ("<30d", 0, 30),
+    ("30-180d", 30, 180),
+    ("180d-1y", 180, STALE_DAYS),
+    (">1y", STALE_DAYS, i64::MAX),
+];
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AgeBucket {
+    pub label: String,
+    pub min_days: i64,
+    /// 上界（不含）；最后一段为 None
+    pub max_days: Option<i64>,
+    pub file_count: usize,
+    pub total_size: i64,
+    pub total_size_formatted: String,
+    pub percent_of_size: f64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StaleFile {
+    pub path: String,
+    pub size: i64,
+    pub size_formatted: String,
+    /// 最后修改时间（Unix 秒）
+    pub modified: i64,
+    pub age_days: i64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileAgeReport {
+    pub path: String,
+    pub total_files: usize,
+    pub total_size: i64,
+    pub buckets: Vec<AgeBucket>,
+    /// 超过 1 年未修改的数据量
+    pub stale_size: i64,
+    pub stale_size_formatted: String,
+    /// 缺少修改时间的文件数（未计入任何年龄段）
+    pub unknown_count: usize,
+    pub unknown_size: i64,
+    pub top_stale_files: Vec<StaleFile>,
+}
+
+/// 按修改时间生成年龄报告（只统计文件，目录跳过）
+pub fn analyze(path: &str, items: &[Item], now: i64) -> FileAgeReport {
+    let mut counts = [0usize; BUCKETS.len()];
+    let mut sizes = [0i64; BUCKETS.len()];
+    let mut unknown_count = 0usize;
+    let mut unknown_size = 0i64;
+    let mut stale: Vec<&Item> = Vec::new();
+
+    for item in items.iter().filter(|i| !i.is_dir) {
+        if item.modified <= 0 {
+            unknown_count += 1;
+            unknown_size += item.size;
+            continue;
+        }
+        let age_days = (now - item.modified).max(0) / DAY;
+        let idx = BUCKETS
+            .iter()
+            .position(|&(_, min, max)| age_days >= min && age_days < max)
+            .unwrap_or(BUCKETS.len() - 1);
+        counts[idx] += 1;
+        sizes[idx] += item.size;
+        if age_days >= STALE_DAYS {
+            stale.push(item);
+        }
+    }
+
+    stale.sort_unstable_by_key(|i| std::cmp::Reverse(i.size));
+    let top_stale_files = stale
+        .into_iter()
+        .take(TOP_STALE_FILES)
+        .map(|i| StaleFile {
+            path: i.path.to_string(),
+            size: i.size,
+            size_formatted: format_size(i.size).to_string(),
+            modified: i.modified,
+            age_days: (now - i.modified).max(0) / DAY,
+        })
+        .collect();
+
+    let dated_size: i64 = sizes.iter().sum();
+    let buckets = BUCKETS
+        .iter()
+        .enumerate()
+        .map(|(idx, &(label, min, max))| AgeBucket {
+            label: label.to_string(),
+            min_days: min,
+            max_days: if max == i64::MAX { None } else { Some(max) },
+            file_count: counts[idx],
+            total_size: sizes[idx],
+            total_size_formatted: format_size(sizes[idx]).to_string(),
+            percent_of_size: if dated_size > 0 {
+                (sizes[idx] as f64 / dated_size as f64) * 100.0
+            } else {
+                0.0
+            },
+        })
+        .collect();
+
+    let stale_size = sizes[BUCKETS.len() - 1];
+
+    FileAgeReport {
+        path: path.to_string(),
+        total_files: counts.iter().sum::<usize>() + unknown_count,
+        total_size: dated_size + unknown_size,
+        buckets,
+        stale_size,
+        stale_size_formatted: format_size(stale_size).to_string(),
+        unknown_count,
+        unknown_size,
+        top_stale_files,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, size: i64, modified: i64) -> Item {
+        Item { modified, ..Item::test_file(path, size) }
+    }
+
+    #[test]
+    fn buckets_by_modified_age() {
+        let now = 2000 * DAY;
+        let items = vec![
+            file("d/new.txt", 1, now - 5 * DAY),
+            file("d/month.txt", 2, now - 30 * DAY),
+            file("d/half.txt", 4, now - 200 * DAY),
+            file("d/old.bin", 8, now - 400 * DAY),
+            file("d/older.bin", 16, now - 800 * DAY),
+            file("d/unknown", 32, 0),
+        ];
+        let report = analyze("d", &items, now);
+
+        let sizes: Vec<i64> = report.buckets.iter().map(|b| b.total_size).collect();
+        assert_eq!(sizes, vec![1, 2, 4, 24]);
+        assert_eq!(report.buckets[3].file_count, 2);
+        assert_eq!(report.stale_size, 24);
+        assert_eq!(report.unknown_count, 1);
+        assert_eq!(report.total_size, 63);
+        assert_eq!(report.top_stale_files[0].path, "d/older.bin");
+        assert_eq!(report.top_stale_files[0].age_days, 800);
+    }
+}
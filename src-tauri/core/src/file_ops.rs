@@ -0,0 +1,789 @@
+// 应用内文件操作：移入回收站、永久删除、移动 / 复制
+//
+// 操作前从内存缓存的扫描结果取出被操作条目与其父目录的大小，操作后失效相关缓存
+// （条目本身、其子目录与祖先目录的会话），并把父目录的新大小（原大小减去移走的部分）
+// 返回给前端直接更新界面，不必等待重新扫描。父目录不在任何缓存会话中、
+// 或其下有条目操作失败（可能只删除了一部分）时新大小为 None。
+//
+// 永久删除不可撤销：前端先用 issue_delete_token 取得确认令牌（同时得到待删除的条目数与大小，
+// 用于确认对话框），用户确认后带令牌调用 delete_permanently。令牌只对签发时的路径集合有效，
+// 一次性使用，DELETE_TOKEN_TTL 后过期。
+//
+// 移动 / 复制到目标目录：同名冲突按 ConflictPolicy 跳过、覆盖或改名。同卷移动直接重命名，
+// 跨卷移动先完整复制再删除源。复制按 COPY_CHUNK 分块写入，块之间上报进度并检查取消；
+// 某项复制失败或被取消时删除该项已写出的部分，源保持不变。源与目标的缓存子树都会失效。
+
+use crate::operation::{Cancelled, Operation};
+use crate::scan::{format_size, CompactString};
+use crate::selection::BulkFailure;
+use crate::validate::{ValidationCode, ValidationError, Validated};
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// 永久删除确认令牌的有效期
+pub const DELETE_TOKEN_TTL: Duration = Duration::from_secs(120);
+
+/// 复制文件时每次读写的块大小
+const COPY_CHUNK: usize = 1024 * 1024;
+
+lazy_static! {
+    /// 已签发的删除令牌：令牌 → (路径集合的规范 key，签发时刻)
+    static ref DELETE_TOKENS: Mutex<HashMap<String, (Vec<String>, Instant)>> = Mutex::new(HashMap::new());
+}
+
+/// 操作后父目录的大小
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ParentSize {
+    pub path: String,
+    /// 新大小；父目录没有缓存的扫描结果时为 None（前端可重新 scan_level）
+    pub size: Option<i64>,
+    #[schemars(with = "Option<String>")]
+    pub size_formatted: Option<CompactString>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileOpResult {
+    /// 成功处理的路径（规范展示形式）
+    pub succeeded: Vec<String>,
+    pub failed: Vec<BulkFailure>,
+    /// 成功处理的条目总大小
+    pub affected_bytes: i64,
+    #[schemars(with = "String")]
+    pub affected_bytes_formatted: CompactString,
+    pub parents: Vec<ParentSize>,
+}
+
+/// 永久删除的确认信息
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteConfirmation {
+    /// 传给 delete_permanently 的一次性令牌
+    pub token: String,
+    /// 将被删除的路径（规范展示形式，已去掉位于其他已选目录之下的条目）
+    pub paths: Vec<String>,
+    /// 无法删除的路径（不存在、卷根目录）
+    pub rejected: Vec<BulkFailure>,
+    pub total_size: i64,
+    #[schemars(with = "String")]
+    pub total_size_formatted: CompactString,
+    /// 令牌过期时间（Unix 秒）
+    pub expires_at: i64,
+}
+
+/// 目标位置已有同名条目时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictPolicy {
+    /// 保留目标，跳过该项
+    #[default]
+    Skip,
+    /// 删除目标后再移动 / 复制
+    Overwrite,
+    /// 改名为 "name (2).ext"、"name (3).ext"…
+    Rename,
+}
+
+/// 一次成功的移动 / 复制
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Transferred {
+    pub source: String,
+    /// 实际写入的位置（Rename 时与源文件名不同）
+    pub destination: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferResult {
+    pub transferred: Vec<Transferred>,
+    /// 因同名冲突跳过的源路径
+    pub skipped: Vec<String>,
+    pub failed: Vec<BulkFailure>,
+    /// 成功移动 / 复制的条目总大小
+    pub affected_bytes: i64,
+    #[schemars(with = "String")]
+    pub affected_bytes_formatted: CompactString,
+    /// 目标目录与（移动时）各源父目录的新大小
+    pub parents: Vec<ParentSize>,
+}
+
+/// 重命名结果
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameResult {
+    pub old_path: String,
+    pub path: String,
+    /// 扫描缓存中修正后的条目（路径、名称、类别、稳定 ID 已更新）；
+    /// 不在任何内存缓存会话中时为 None，前端需自行刷新
+    pub item: Option<crate::scan::Item>,
+}
+
+/// 待操作的条目
+struct Target {
+    display: String,
+    parent: String,
+    size: i64,
+}
+
+/// 缓存会话中目录的当前大小（直接子项大小之和）
+fn cached_dir_size(dir: &str) -> Option<i64> {
+    let (display, items) = crate::scan::cached_items_containing(dir)?;
+    Some(crate::level_scan::level_from_items(&display, &items, crate::level_scan::LevelSource::Cache, true).total_size)
+}
+
+/// 条目大小：优先取缓存的扫描结果，否则文件取长度、目录完整统计。
+/// path 为 entry_display_path 的结果，链接按链接本身的条目匹配
+fn entry_size(path: &str, is_dir: bool) -> i64 {
+    let cached = crate::scan::cached_items_containing(path)
+        .and_then(|(_, items)| items.iter().find(|i| i.path == path).map(|i| i.size));
+    match cached {
+        Some(size) => size,
+        None if is_dir => crate::scan::count_directory(path, &Default::default()).map_or(0, |c| c.total_size),
+        None => std::fs::symlink_metadata(crate::fs::to_extended_length(path)).map_or(0, |m| m.len() as i64),
+    }
+}
+
+/// `path` 是否位于 `dir` 之下（不含 dir 本身）
+fn is_within(dir: &str, path: &str) -> bool {
+    path.strip_prefix(dir.trim_end_matches('/'))
+        .is_some_and(|rest| rest.len() > 1 && rest.starts_with('/'))
+}
+
+/// 父目录（规范展示形式）；卷根 / 根目录没有父目录
+fn parent_of(display: &str) -> Option<String> {
+    let parent = std::path::Path::new(display).parent()?;
+    Some(crate::fs::display_path(&parent.to_string_lossy()))
+}
+
+/// 条目的规范展示形式：只规范化所在目录再接回名称，条目本身是符号链接 / 联接点时
+/// 指向链接而不是其目标（与 rename_item 一致）；条目不存在时为 None
+fn entry_display_path(path: &str) -> Option<String> {
+    let entry = Path::new(path);
+    let display = match (entry.parent(), entry.file_name()) {
+        (Some(parent), Some(name)) => {
+            let parent = if parent.as_os_str().is_empty() { Path::new(".") } else { parent };
+            let parent = crate::scan::canonical_display_path(&parent.to_string_lossy())?;
+            format!("{}/{}", parent.trim_end_matches('/'), name.to_string_lossy())
+        }
+        // 卷根、以 `..` 结尾等没有名称的路径整体规范化
+        _ => crate::scan::canonical_display_path(path)?,
+    };
+    std::fs::symlink_metadata(crate::fs::to_extended_length(&display)).ok()?;
+    Some(display)
+}
+
+/// 解析路径并取大小：不存在的路径与卷根目录记为失败，已选目录之下的条目与重复项被去掉
+fn resolve(paths: &[String]) -> (Vec<Target>, Vec<BulkFailure>) {
+    let mut failed = Vec::new();
+    let mut targets = Vec::new();
+    for path in paths {
+        let Some(display) = entry_display_path(path) else {
+            failed.push(BulkFailure { path: path.clone(), error: "路径不存在".to_string() });
+            continue;
+        };
+        let Some(parent) = parent_of(&display) else {
+            failed.push(BulkFailure { path: display, error: "不能操作卷根目录".to_string() });
+            continue;
+        };
+        // 链接按链接本身计（不进入目标目录统计大小）
+        let is_dir = std::fs::symlink_metadata(crate::fs::to_extended_length(&display)).is_ok_and(|m| m.is_dir());
+        targets.push(Target { size: entry_size(&display, is_dir), display, parent });
+    }
+    // 已选目录之下的条目随目录一起处理，不重复计算
+    let dirs: Vec<String> = targets.iter().map(|t| t.display.clone()).collect();
+    let mut seen = std::collections::HashSet::new();
+    targets.retain(|t| !dirs.iter().any(|d| is_within(d, &t.display)) && seen.insert(t.display.clone()));
+    (targets, failed)
+}
+
+/// 逐项执行 op（每项：规范路径 → 结果），汇总结果、失效缓存并计算父目录新大小
+fn run_each(
+    targets: Vec<Target>,
+    mut failed: Vec<BulkFailure>,
+    mut op: impl FnMut(&str) -> anyhow::Result<()>,
+) -> FileOpResult {
+    // 父目录大小在操作前读取（操作后缓存即被失效）
+    let mut parents: Vec<(String, Option<i64>)> = Vec::new();
+    for target in &targets {
+        if !parents.iter().any(|(p, _)| *p == target.parent) {
+            parents.push((target.parent.clone(), cached_dir_size(&target.parent)));
+        }
+    }
+
+    let mut succeeded = Vec::new();
+    let mut affected_bytes = 0;
+    for target in targets {
+        let outcome = op(&target.display);
+        crate::scan::invalidate_caches_for(&target.display);
+        // 直接父目录与同批次中更上层的父目录都受影响
+        let affected = parents.iter_mut().filter(|(p, _)| is_within(p, &target.display));
+        match outcome {
+            Ok(()) => {
+                for (_, size) in affected {
+                    if let Some(size) = size {
+                        *size = (*size - target.size).max(0);
+                    }
+                }
+                affected_bytes += target.size;
+                succeeded.push(target.display);
+            }
+            Err(e) => {
+                for (_, size) in affected {
+                    *size = None;
+                }
+                failed.push(BulkFailure { path: target.display, error: e.to_string() });
+            }
+        }
+    }
+
+    FileOpResult {
+        succeeded,
+        failed,
+        affected_bytes,
+        affected_bytes_formatted: format_size(affected_bytes),
+        parents: parents
+            .into_iter()
+            .map(|(path, size)| ParentSize { path, size, size_formatted: size.map(format_size) })
+            .collect(),
+    }
+}
+
+/// 把 paths 移入回收站（可还原），逐项报告失败
+pub fn delete_to_trash(paths: &[String]) -> FileOpResult {
+    delete_to_trash_with(paths, &Operation::start("delete_to_trash", None, None))
+}
+
+/// 移入回收站并经 op 上报 "trash" 阶段的进度（按条目数），取消后剩余条目记为失败
+pub fn delete_to_trash_with(paths: &[String], op: &Operation) -> FileOpResult {
+    let (targets, failed) = resolve(paths);
+    let total = targets.len().max(1) as f64;
+    let mut done = 0usize;
+    run_each(targets, failed, |path| {
+        op.check()?;
+        let moved = crate::fs::move_to_trash(&crate::fs::to_extended_length(path));
+        done += 1;
+        op.progress("trash", done as f64 * 100.0 / total);
+        moved
+    })
+}
+
+/// 路径集合的比较 key：规范化、排序、去重（与签发时的顺序、写法无关）
+fn paths_key(paths: &[String]) -> Vec<String> {
+    let mut key: Vec<String> = paths.iter().map(|p| crate::fs::cache_key(p)).collect();
+    key.sort_unstable();
+    key.dedup();
+    key
+}
+
+/// 为永久删除 paths 签发确认令牌，同时统计将被删除的条目与大小
+pub fn issue_delete_token(paths: &[String]) -> DeleteConfirmation {
+    let (targets, rejected) = resolve(paths);
+    let token = uuid::Uuid::new_v4().to_string();
+    {
+        let mut tokens = DELETE_TOKENS.lock();
+        tokens.retain(|_, (_, issued)| issued.elapsed() < DELETE_TOKEN_TTL);
+        tokens.insert(token.clone(), (paths_key(paths), Instant::now()));
+    }
+    let total_size: i64 = targets.iter().map(|t| t.size).sum();
+    DeleteConfirmation {
+        token,
+        paths: targets.into_iter().map(|t| t.display).collect(),
+        rejected,
+        total_size,
+        total_size_formatted: format_size(total_size),
+        expires_at: chrono::Utc::now().timestamp() + DELETE_TOKEN_TTL.as_secs() as i64,
+    }
+}
+
+/// 核销令牌：令牌一经使用即作废（无论路径是否匹配）
+pub fn take_delete_token(token: &str, paths: &[String]) -> Validated<()> {
+    let invalid = |message: &str| ValidationError::new("token", ValidationCode::NotFound, message);
+    let (key, issued) = DELETE_TOKENS.lock().remove(token).ok_or_else(|| invalid("确认令牌无效或已使用"))?;
+    if issued.elapsed() >= DELETE_TOKEN_TTL {
+        return Err(invalid("确认令牌已过期，请重新确认"));
+    }
+    if key != paths_key(paths) {
+        return Err(invalid("确认令牌与待删除的路径不一致，请重新确认"));
+    }
+    Ok(())
+}
+
+/// 永久删除 paths（不经过回收站）。令牌无效时不删除任何内容；
+/// 删除过程经 op 上报 "delete" 阶段的进度（按已删除字节），取消后剩余条目记为失败
+pub fn delete_permanently(paths: &[String], token: &str, op: &Operation) -> Validated<FileOpResult> {
+    take_delete_token(token, paths)?;
+    Ok(delete_confirmed(paths, op))
+}
+
+/// 永久删除已经用 take_delete_token 确认过的 paths
+pub fn delete_confirmed(paths: &[String], op: &Operation) -> FileOpResult {
+    let (targets, failed) = resolve(paths);
+    let total = targets.iter().map(|t| t.size).sum::<i64>().max(1) as f64;
+    let mut deleted = 0u64;
+    run_each(targets, failed, |path| {
+        op.check()?;
+        remove_tree(&crate::fs::to_extended_length(path), &mut |bytes| {
+            deleted += bytes;
+            op.progress("delete", deleted as f64 * 100.0 / total);
+            op.check()
+        })
+    })
+}
+
+/// 删除单个文件或符号链接；Windows 上只读文件先去掉只读属性再删
+fn remove_entry(path: &Path, meta: &std::fs::Metadata) -> std::io::Result<()> {
+    // Windows 的目录符号链接 / 联接点须用 remove_dir 删除（不会进入其目标）
+    let removed = std::fs::remove_file(path)
+        .or_else(|e| if meta.is_symlink() { std::fs::remove_dir(path) } else { Err(e) });
+    let readonly = cfg!(windows) && meta.permissions().readonly();
+    match removed {
+        Err(e) if readonly && e.kind() == std::io::ErrorKind::PermissionDenied => {
+            let mut permissions = meta.permissions();
+            #[allow(clippy::permissions_set_readonly_false)]
+            permissions.set_readonly(false);
+            std::fs::set_permissions(path, permissions)?;
+            std::fs::remove_file(path)
+        }
+        other => other,
+    }
+}
+
+/// 递归删除（不跟随符号链接），每删除一个文件回调一次其大小；
+/// 单个条目失败时继续删除其余条目，最后报告失败数与第一个错误
+fn remove_tree(path: &Path, on_removed: &mut dyn FnMut(u64) -> Result<(), Cancelled>) -> anyhow::Result<()> {
+    let meta = std::fs::symlink_metadata(path)?;
+    if !meta.is_dir() {
+        remove_entry(path, &meta)?;
+        on_removed(meta.len())?;
+        return Ok(());
+    }
+
+    let mut failures = 0usize;
+    let mut first_error: Option<anyhow::Error> = None;
+    let mut stack = vec![(path.to_path_buf(), false)];
+    // 深度优先：目录第一次出栈时压回自身（标记子项已展开）再压入子目录，第二次出栈时子项已清空
+    while let Some((dir, expanded)) = stack.pop() {
+        if expanded {
+            if let Err(e) = std::fs::remove_dir(&dir) {
+                failures += 1;
+                first_error.get_or_insert_with(|| anyhow::anyhow!("{}: {}", dir.display(), e));
+            }
+            continue;
+        }
+        stack.push((dir.clone(), true));
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                failures += 1;
+                first_error.get_or_insert_with(|| anyhow::anyhow!("{}: {}", dir.display(), e));
+                continue;
+            }
+        };
+        for entry in entries.flatten() {
+            let child = entry.path();
+            let Ok(meta) = std::fs::symlink_metadata(&child) else { continue };
+            if meta.is_dir() {
+                stack.push((child, false));
+                continue;
+            }
+            match remove_entry(&child, &meta) {
+                Ok(()) => on_removed(meta.len())?,
+                Err(e) => {
+                    failures += 1;
+                    first_error.get_or_insert_with(|| anyhow::anyhow!("{}: {}", child.display(), e));
+                }
+            }
+        }
+    }
+
+    match first_error {
+        None => Ok(()),
+        Some(e) if failures == 1 => Err(e),
+        Some(e) => Err(anyhow::anyhow!("{} 个条目删除失败，首个错误 {}", failures, e)),
+    }
+}
+
+/// 递归复制（不跟随符号链接，符号链接按原目标重建），每写出一块回调一次字节数
+fn copy_tree(src: &Path, dest: &Path, on_copied: &mut dyn FnMut(u64) -> Result<(), Cancelled>) -> anyhow::Result<()> {
+    let meta = std::fs::symlink_metadata(src)?;
+    if meta.is_symlink() {
+        return copy_symlink(src, dest);
+    }
+    if !meta.is_dir() {
+        return copy_file(src, dest, &meta, on_copied);
+    }
+    std::fs::create_dir(dest)?;
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        copy_tree(&entry.path(), &dest.join(entry.file_name()), on_copied)?;
+    }
+    Ok(())
+}
+
+/// 复制到新位置；失败或被取消时删除已写出的部分
+fn copy_new(src: &Path, dest: &Path, on_copied: &mut dyn FnMut(u64) -> Result<(), Cancelled>) -> anyhow::Result<()> {
+    copy_tree(src, dest, on_copied).inspect_err(|_| {
+        if std::fs::symlink_metadata(dest).is_ok() {
+            remove_tree(dest, &mut |_| Ok(())).ok();
+        }
+    })
+}
+
+/// 分块复制单个文件并保留修改时间
+fn copy_file(
+    src: &Path,
+    dest: &Path,
+    meta: &std::fs::Metadata,
+    on_copied: &mut dyn FnMut(u64) -> Result<(), Cancelled>,
+) -> anyhow::Result<()> {
+    use std::io::{Read, Write};
+
+    let mut reader = std::fs::File::open(src)?;
+    let mut writer = std::fs::OpenOptions::new().write(true).create_new(true).open(dest)?;
+    let mut buf = vec![0u8; COPY_CHUNK];
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        writer.write_all(&buf[..n])?;
+        on_copied(n as u64)?;
+    }
+    writer.set_permissions(meta.permissions())?;
+    if let Ok(modified) = meta.modified() {
+        writer.set_modified(modified).ok();
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(src: &Path, dest: &Path) -> anyhow::Result<()> {
+    std::os::unix::fs::symlink(std::fs::read_link(src)?, dest)?;
+    Ok(())
+}
+
+#[cfg(windows)]
+fn copy_symlink(src: &Path, dest: &Path) -> anyhow::Result<()> {
+    let target = std::fs::read_link(src)?;
+    // 目录链接与文件链接是两种对象；目标已不存在时按文件链接重建
+    if std::fs::metadata(src).is_ok_and(|m| m.is_dir()) {
+        std::os::windows::fs::symlink_dir(target, dest)?;
+    } else {
+        std::os::windows::fs::symlink_file(target, dest)?;
+    }
+    Ok(())
+}
+
+/// dir 下不冲突的名称："name (2).ext"、"name (3).ext"…（目录不拆分扩展名）
+fn unique_name(dir: &Path, name: &str, is_dir: bool) -> String {
+    let (stem, ext) = match name.rfind('.') {
+        Some(pos) if pos > 0 && !is_dir => name.split_at(pos),
+        _ => (name, ""),
+    };
+    (2..)
+        .map(|n| format!("{} ({}){}", stem, n, ext))
+        .find(|candidate| std::fs::symlink_metadata(dir.join(candidate)).is_err())
+        .unwrap_or_default()
+}
+
+/// 把 src_paths 移动（moving）或复制到 dest 目录下
+fn transfer(
+    src_paths: &[String],
+    dest: &str,
+    conflict: ConflictPolicy,
+    op: &Operation,
+    moving: bool,
+) -> TransferResult {
+    let stage = if moving { "move" } else { "copy" };
+    let (targets, mut failed) = resolve(src_paths);
+    let dest = crate::scan::canonical_display_path(dest).unwrap_or_else(|| crate::fs::display_path(dest));
+    let dest_dir = crate::fs::to_extended_length(&dest);
+
+    // 受影响目录（目标目录、移动时的各源父目录）在操作前的缓存大小
+    let mut parents: Vec<(String, Option<i64>)> = vec![(dest.clone(), cached_dir_size(&dest))];
+    for target in targets.iter().filter(|_| moving) {
+        if !parents.iter().any(|(p, _)| *p == target.parent) {
+            parents.push((target.parent.clone(), cached_dir_size(&target.parent)));
+        }
+    }
+    // path 位于 p 之下或就是 p 时 p 的大小变化 delta；delta 为 None 表示大小已无法推算
+    let adjust = |parents: &mut Vec<(String, Option<i64>)>, path: &str, delta: Option<i64>| {
+        for (_, size) in parents.iter_mut().filter(|(p, _)| p == path || is_within(p, path)) {
+            *size = size.zip(delta).map(|(size, delta)| (size + delta).max(0));
+        }
+    };
+
+    let total = targets.iter().map(|t| t.size).sum::<i64>().max(1) as f64;
+    let mut done = 0u64;
+    let mut transferred = Vec::new();
+    let mut skipped = Vec::new();
+    let mut affected_bytes = 0;
+    for target in targets {
+        let fail = |failed: &mut Vec<BulkFailure>, error: String| {
+            failed.push(BulkFailure { path: target.display.clone(), error })
+        };
+        if let Err(e) = op.check() {
+            fail(&mut failed, e.to_string());
+            continue;
+        }
+        if dest == target.display || is_within(&target.display, &dest) {
+            fail(&mut failed, "不能移动或复制到自身之下".to_string());
+            continue;
+        }
+        let src = crate::fs::to_extended_length(&target.display);
+        let is_dir = std::fs::symlink_metadata(&src).is_ok_and(|m| m.is_dir());
+        let mut name = src.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+        // 移动 / 复制到源所在目录也视为冲突
+        let mut replaced = 0;
+        if std::fs::symlink_metadata(dest_dir.join(&name)).is_ok() {
+            match conflict {
+                ConflictPolicy::Skip => {
+                    skipped.push(target.display);
+                    continue;
+                }
+                ConflictPolicy::Rename => name = unique_name(&dest_dir, &name, is_dir),
+                ConflictPolicy::Overwrite => {
+                    let existing = format!("{}/{}", dest.trim_end_matches('/'), name);
+                    if existing == target.display || is_within(&existing, &target.display) {
+                        fail(&mut failed, "不能用源覆盖其自身或上级目录".to_string());
+                        continue;
+                    }
+                    replaced = entry_size(&existing, std::fs::symlink_metadata(dest_dir.join(&name)).is_ok_and(|m| m.is_dir()));
+                    if let Err(e) = remove_tree(&dest_dir.join(&name), &mut |_| Ok(())) {
+                        crate::scan::invalidate_caches_for(&existing);
+                        adjust(&mut parents, &existing, None);
+                        fail(&mut failed, format!("无法删除已有的目标: {}", e));
+                        continue;
+                    }
+                }
+            }
+        }
+
+        let dest_path = dest_dir.join(&name);
+        let destination = format!("{}/{}", dest.trim_end_matches('/'), name);
+        let mut on_copied = |bytes: u64| {
+            done += bytes;
+            op.progress(stage, done as f64 * 100.0 / total);
+            op.check()
+        };
+        let outcome = if moving {
+            match std::fs::rename(&src, &dest_path) {
+                Ok(()) => on_copied(target.size.max(0) as u64).map_err(anyhow::Error::from),
+                // 跨卷：复制完成后再删除源
+                Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+                    copy_new(&src, &dest_path, &mut on_copied).and_then(|()| remove_tree(&src, &mut |_| Ok(())))
+                }
+                Err(e) => Err(e.into()),
+            }
+        } else {
+            copy_new(&src, &dest_path, &mut on_copied)
+        };
+
+        if moving {
+            crate::scan::invalidate_caches_for(&target.display);
+        }
+        crate::scan::invalidate_caches_for(&destination);
+        match outcome {
+            Ok(()) => {
+                if moving {
+                    adjust(&mut parents, &target.display, Some(-target.size));
+                }
+                adjust(&mut parents, &destination, Some(target.size - replaced));
+                affected_bytes += target.size;
+                transferred.push(Transferred { source: target.display, destination });
+            }
+            Err(e) => {
+                if moving {
+                    adjust(&mut parents, &target.display, None);
+                }
+                adjust(&mut parents, &destination, None);
+                failed.push(BulkFailure { path: target.display, error: e.to_string() });
+            }
+        }
+    }
+
+    TransferResult {
+        transferred,
+        skipped,
+        failed,
+        affected_bytes,
+        affected_bytes_formatted: format_size(affected_bytes),
+        parents: parents
+            .into_iter()
+            .map(|(path, size)| ParentSize { path, size, size_formatted: size.map(format_size) })
+            .collect(),
+    }
+}
+
+/// 把 src_paths 移动到 dest 目录下，经 op 上报 "move" 阶段的进度
+pub fn move_items(src_paths: &[String], dest: &str, conflict: ConflictPolicy, op: &Operation) -> TransferResult {
+    transfer(src_paths, dest, conflict, op, true)
+}
+
+/// 把 src_paths 复制到 dest 目录下，经 op 上报 "copy" 阶段的进度
+pub fn copy_items(src_paths: &[String], dest: &str, conflict: ConflictPolicy, op: &Operation) -> TransferResult {
+    transfer(src_paths, dest, conflict, op, false)
+}
+
+/// 在原目录内把 path 改名为 new_name（单个名称，由调用方校验），并就地修正扫描缓存。
+/// 链接类条目重命名链接本身；目标已存在时报错（大小写不敏感的平台上只改大小写除外）
+pub fn rename_item(path: &str, new_name: &str) -> anyhow::Result<RenameResult> {
+    let path = Path::new(path);
+    let (Some(parent), Some(old_name)) = (path.parent(), path.file_name()) else {
+        anyhow::bail!("不能重命名卷根目录");
+    };
+    // 只规范化所在目录：条目本身是符号链接时不解析到目标
+    let parent = crate::scan::canonical_display_path(&parent.to_string_lossy())
+        .ok_or_else(|| anyhow::anyhow!("路径不存在: {}", path.display()))?;
+    let join = |name: &str| format!("{}/{}", parent.trim_end_matches('/'), name);
+    let old_path = join(&old_name.to_string_lossy());
+    let new_path = join(new_name);
+
+    let src = crate::fs::to_extended_length(&old_path);
+    let dest = crate::fs::to_extended_length(&new_path);
+    if std::fs::symlink_metadata(&src).is_err() {
+        anyhow::bail!("路径不存在: {}", old_path);
+    }
+    if std::fs::symlink_metadata(&dest).is_ok() && crate::fs::cache_key(&old_path) != crate::fs::cache_key(&new_path) {
+        anyhow::bail!("已存在同名条目: {}", new_name);
+    }
+    std::fs::rename(&src, &dest).map_err(|e| anyhow::anyhow!("重命名失败: {}", e))?;
+
+    let item = crate::scan::patch_renamed(&old_path, &new_path);
+    Ok(RenameResult { old_path, path: new_path, item })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_missing_paths_and_volume_roots_without_touching_them() {
+        let dir = std::env::temp_dir().join(format!("flashdir-fileops-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.bin"), vec![0u8; 64]).unwrap();
+        let file = dir.join("a.bin").to_string_lossy().to_string();
+        let missing = dir.join("missing").to_string_lossy().to_string();
+
+        let (targets, failed) = resolve(&[file.clone(), missing, "/".to_string(), file.clone()]);
+        let result = run_each(targets, failed, |path| Ok(std::fs::remove_file(path)?));
+        assert_eq!(result.succeeded, vec![crate::fs::display_path(&file)]);
+        assert_eq!(result.affected_bytes, 64);
+        let errors: Vec<_> = result.failed.iter().map(|f| f.error.as_str()).collect();
+        assert_eq!(errors, vec!["路径不存在", "不能操作卷根目录"]);
+        // 父目录未被扫描缓存：新大小未知
+        assert_eq!(result.parents.len(), 1);
+        assert_eq!(result.parents[0].size, None);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn permanent_delete_requires_matching_single_use_token() {
+        let dir = std::env::temp_dir().join(format!("flashdir-fileops-perm-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("tree/sub")).unwrap();
+        std::fs::write(dir.join("tree/a.bin"), vec![0u8; 100]).unwrap();
+        std::fs::write(dir.join("tree/sub/b.bin"), vec![0u8; 50]).unwrap();
+        std::fs::write(dir.join("c.bin"), vec![0u8; 10]).unwrap();
+        let tree = dir.join("tree").to_string_lossy().to_string();
+        let file = dir.join("c.bin").to_string_lossy().to_string();
+        let op = Operation::start("delete_permanently", None, None);
+
+        assert!(delete_permanently(std::slice::from_ref(&tree), "bogus", &op).is_err());
+        let confirmation = issue_delete_token(&[tree.clone(), format!("{}/sub", tree)]);
+        assert_eq!((confirmation.paths.len(), confirmation.total_size), (1, 150));
+        // 路径集合不同：令牌作废，不删除任何内容
+        let mismatch = delete_permanently(&[tree.clone(), file.clone()], &confirmation.token, &op).unwrap_err();
+        assert_eq!(mismatch.code, ValidationCode::NotFound);
+        assert!(dir.join("tree/sub/b.bin").exists());
+        assert!(delete_permanently(std::slice::from_ref(&tree), &confirmation.token, &op).is_err());
+
+        let confirmation = issue_delete_token(&[file.clone(), tree.clone()]);
+        let result = delete_permanently(&[tree, file], &confirmation.token, &op).unwrap();
+        assert!(result.failed.is_empty(), "{:?}", result.failed);
+        assert_eq!(result.affected_bytes, 160);
+        assert!(!dir.join("tree").exists() && !dir.join("c.bin").exists());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn copy_and_move_apply_conflict_policies() {
+        let dir = std::env::temp_dir().join(format!("flashdir-fileops-transfer-{}", std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(dir.join("src/tree/sub")).unwrap();
+        std::fs::create_dir_all(dir.join("dest")).unwrap();
+        std::fs::write(dir.join("src/tree/sub/a.bin"), vec![1u8; COPY_CHUNK + 10]).unwrap();
+        std::fs::write(dir.join("src/note.txt"), b"new").unwrap();
+        std::fs::write(dir.join("dest/note.txt"), b"old").unwrap();
+        let path = |rel: &str| dir.join(rel).to_string_lossy().to_string();
+        let sources = vec![path("src/tree"), path("src/note.txt")];
+        let dest = path("dest");
+        let op = Operation::start("copy_items", None, None);
+
+        let copied = copy_items(&sources, &dest, ConflictPolicy::Skip, &op);
+        assert_eq!(copied.transferred.len(), 1);
+        assert_eq!(copied.skipped, vec![crate::fs::display_path(&path("src/note.txt"))]);
+        assert_eq!(std::fs::read(dir.join("dest/tree/sub/a.bin")).unwrap().len(), COPY_CHUNK + 10);
+        assert_eq!(std::fs::read(dir.join("dest/note.txt")).unwrap(), b"old");
+
+        let renamed = copy_items(&sources[1..], &dest, ConflictPolicy::Rename, &op);
+        assert!(renamed.transferred[0].destination.ends_with("/dest/note (2).txt"));
+        assert_eq!(unique_name(&dir.join("dest"), "note.txt", false), "note (3).txt");
+
+        let moved = move_items(&sources, &dest, ConflictPolicy::Overwrite, &op);
+        assert!(moved.failed.is_empty(), "{:?}", moved.failed);
+        assert_eq!(std::fs::read(dir.join("dest/note.txt")).unwrap(), b"new");
+        assert!(!dir.join("src/tree").exists() && dir.join("dest/tree/sub/a.bin").exists());
+
+        // 不能移动到自身之下
+        let nested = move_items(&[path("dest")], &path("dest/tree"), ConflictPolicy::Skip, &op);
+        assert_eq!(nested.failed.len(), 1);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn bulk_operations_act_on_links_not_their_targets() {
+        let dir = std::env::temp_dir().join(format!("flashdir-fileops-link-{}", std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(dir.join("data/important")).unwrap();
+        std::fs::create_dir_all(dir.join("scan")).unwrap();
+        std::fs::create_dir_all(dir.join("dest")).unwrap();
+        std::fs::write(dir.join("data/important/keep.bin"), vec![7u8; 100]).unwrap();
+        let path = |rel: &str| dir.join(rel).to_string_lossy().to_string();
+        let link = |name: &str| std::os::unix::fs::symlink(dir.join("data/important"), dir.join("scan").join(name)).unwrap();
+        let target_intact = || std::fs::read(dir.join("data/important/keep.bin")).is_ok_and(|b| b.len() == 100);
+        let op = Operation::start("delete_permanently", None, None);
+
+        link("deleted");
+        let confirmation = issue_delete_token(&[path("scan/deleted")]);
+        assert_eq!(confirmation.paths, vec![crate::fs::display_path(&path("scan/deleted"))]);
+        let result = delete_permanently(&[path("scan/deleted")], &confirmation.token, &op).unwrap();
+        assert!(result.failed.is_empty(), "{:?}", result.failed);
+        assert!(std::fs::symlink_metadata(dir.join("scan/deleted")).is_err());
+        assert!(target_intact());
+
+        link("moved");
+        let moved = move_items(&[path("scan/moved")], &path("dest"), ConflictPolicy::Skip, &op);
+        assert!(moved.failed.is_empty(), "{:?}", moved.failed);
+        assert!(std::fs::symlink_metadata(dir.join("dest/moved")).unwrap().is_symlink());
+        assert!(target_intact() && dir.join("data/important").is_dir());
+
+        link("copied");
+        let copied = copy_items(&[path("scan/copied")], &path("dest"), ConflictPolicy::Skip, &op);
+        assert!(copied.failed.is_empty(), "{:?}", copied.failed);
+        assert!(std::fs::symlink_metadata(dir.join("dest/copied")).unwrap().is_symlink());
+        assert!(std::fs::symlink_metadata(dir.join("scan/copied")).unwrap().is_symlink());
+        assert!(target_intact());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
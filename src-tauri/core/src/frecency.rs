@@ -0,0 +1,118 @@
+// 最近位置与 frecency（频率 × 新近度）排序
+//
+// 每次扫描 / 打开路径都在 SQLite 中累加访问次数并更新最后访问时间，
+// 排序分数 = 加权访问次数 × 新近度系数（与 zoxide 相同的分段：1 小时 / 1 天 / 1 周）。
+
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+const HOUR: i64 = 3600;
+const DAY: i64 = 24 * HOUR;
+const WEEK: i64 = 7 * DAY;
+
+/// 访问类型：主动打开比扫描更能代表用户关注的位置，权重更高
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VisitKind {
+    Scan,
+    Open,
+}
+
+impl VisitKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VisitKind::Scan => "scan",
+            VisitKind::Open => "open",
+        }
+    }
+}
+
+/// 路径访问统计（DiskCache 中 path_visits 表的一行）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathVisit {
+    pub path: String,
+    pub scan_count: i64,
+    pub open_count: i64,
+    /// 最后访问时间（Unix 秒）
+    pub last_visit: i64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FrecentPath {
+    pub path: String,
+    pub score: f64,
+    pub visit_count: i64,
+    pub last_visit: i64,
+}
+
+/// 新近度系数
+fn recency_multiplier(age_secs: i64) -> f64 {
+    if age_secs < HOUR {
+        4.0
+    } else if age_secs < DAY {
+        2.0
+    } else if age_secs < WEEK {
+        0.5
+    } else {
+        0.25
+    }
+}
+
+pub fn score(visit: &PathVisit, now: i64) -> f64 {
+    let weighted = visit.scan_count as f64 + visit.open_count as f64 * 2.0;
+    weighted * recency_multiplier((now - visit.last_visit).max(0))
+}
+
+/// 按 frecency 降序排列，分数相同时最近访问的在前
+pub fn rank(visits: Vec<PathVisit>, now: i64, limit: usize) -> Vec<FrecentPath> {
+    let mut ranked: Vec<FrecentPath> = visits
+        .into_iter()
+        .map(|v| FrecentPath {
+            score: score(&v, now),
+            visit_count: v.scan_count + v.open_count,
+            last_visit: v.last_visit,
+            path: v.path,
+        })
+        .collect();
+    ranked.sort_unstable_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| b.last_visit.cmp(&a.last_visit))
+    });
+    ranked.truncate(limit);
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn visit(path: &str, scans: i64, opens: i64, last_visit: i64) -> PathVisit {
+        PathVisit {
+            path: path.to_string(),
+            scan_count: scans,
+            open_count: opens,
+            last_visit,
+        }
+    }
+
+    #[test]
+    fn recent_beats_frequent_but_stale() {
+        let now = 100 * WEEK;
+        let ranked = rank(
+            vec![
+                visit("/old-but-frequent", 20, 0, now - 2 * WEEK), // 20 × 0.25 = 5
+                visit("/fresh", 2, 1, now - 60),                   // 4 × 4 = 16
+                visit("/yesterday", 3, 0, now - 2 * HOUR),         // 3 × 2 = 6
+            ],
+            now,
+            10,
+        );
+        let order: Vec<&str> = ranked.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(order, vec!["/fresh", "/yesterday", "/old-but-frequent"]);
+        assert_eq!(ranked[0].visit_count, 3);
+        assert_eq!(rank(vec![visit("/a", 1, 0, now)], now, 0).len(), 0);
+    }
+}
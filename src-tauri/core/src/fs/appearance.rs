@@ -0,0 +1,117 @@
+// 系统外观：深色 / 浅色主题与界面语言
+//
+// 生成报告时若设置为“跟随系统”，按此处读取的系统偏好决定配色与日期、数字格式。
+// Windows: HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize\AppsUseLightTheme，
+//          HKCU\Control Panel\International\LocaleName
+// macOS: `defaults read -g AppleInterfaceStyle`（深色时为 "Dark"，浅色时键不存在）
+// Linux: GTK_THEME 的 :dark 后缀或 gsettings color-scheme
+// 语言在 Unix 上取 LC_ALL / LC_MESSAGES / LANG。读取失败一律返回 None，由调用方取默认值
+
+#[cfg(target_os = "windows")]
+fn read_hkcu(subkey: &str, value: &str, flags: u32, data: &mut [u8]) -> Option<usize> {
+    use windows_sys::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER};
+
+    let subkey: Vec<u16> = subkey.encode_utf16().chain(std::iter::once(0)).collect();
+    let value: Vec<u16> = value.encode_utf16().chain(std::iter::once(0)).collect();
+    let mut size = data.len() as u32;
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            subkey.as_ptr(),
+            value.as_ptr(),
+            flags,
+            std::ptr::null_mut(),
+            data.as_mut_ptr() as *mut _,
+            &mut size,
+        )
+    };
+    (status == 0).then_some(size as usize)
+}
+
+/// 系统是否使用深色主题
+#[cfg(target_os = "windows")]
+pub fn system_prefers_dark() -> Option<bool> {
+    use windows_sys::Win32::System::Registry::RRF_RT_REG_DWORD;
+
+    let mut data = [0u8; 4];
+    read_hkcu(
+        "Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize",
+        "AppsUseLightTheme",
+        RRF_RT_REG_DWORD,
+        &mut data,
+    )?;
+    Some(u32::from_le_bytes(data) == 0)
+}
+
+/// 系统界面语言（BCP 47，如 "zh-CN"）
+#[cfg(target_os = "windows")]
+pub fn system_locale() -> Option<String> {
+    use windows_sys::Win32::System::Registry::RRF_RT_REG_SZ;
+
+    let mut data = [0u8; 170];
+    let size = read_hkcu("Control Panel\\International", "LocaleName", RRF_RT_REG_SZ, &mut data)?;
+    let wide: Vec<u16> = data[..size]
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|&c| c != 0)
+        .collect();
+    String::from_utf16(&wide).ok().filter(|s| !s.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+pub fn system_prefers_dark() -> Option<bool> {
+    let output = std::process::Command::new("defaults")
+        .args(["read", "-g", "AppleInterfaceStyle"])
+        .output()
+        .ok()?;
+    // 浅色模式下该键不存在，命令以非零状态退出
+    Some(output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "Dark")
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn system_prefers_dark() -> Option<bool> {
+    if let Ok(theme) = std::env::var("GTK_THEME") {
+        return Some(theme.to_ascii_lowercase().contains(":dark"));
+    }
+    let output = std::process::Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", "color-scheme"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).contains("prefer-dark"))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn system_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|v| !v.is_empty())
+        .and_then(|v| posix_to_bcp47(&v))
+}
+
+/// "zh_CN.UTF-8" / "de_DE@euro" → "zh-CN" / "de-DE"；"C" 与 "POSIX" 不是语言
+#[cfg_attr(target_os = "windows", allow(dead_code))]
+fn posix_to_bcp47(value: &str) -> Option<String> {
+    let tag = value.split(['.', '@']).next()?;
+    if tag.is_empty() || tag == "C" || tag == "POSIX" {
+        return None;
+    }
+    Some(tag.replace('_', "-"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_posix_locale_names() {
+        assert_eq!(posix_to_bcp47("zh_CN.UTF-8").as_deref(), Some("zh-CN"));
+        assert_eq!(posix_to_bcp47("de_DE@euro").as_deref(), Some("de-DE"));
+        assert_eq!(posix_to_bcp47("en").as_deref(), Some("en"));
+        assert_eq!(posix_to_bcp47("C.UTF-8"), None);
+        assert_eq!(posix_to_bcp47("POSIX"), None);
+    }
+}
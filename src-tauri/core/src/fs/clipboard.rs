@@ -0,0 +1,104 @@
+// 写入系统剪贴板（纯文本）
+//
+// Windows: OpenClipboard + SetClipboardData(CF_UNICODETEXT)
+// macOS: pbcopy
+// Linux: Wayland 下 wl-copy，X11 下 xclip / xsel（均为常见的桌面组件，缺失时返回错误）
+// 由后端直接写入，大段文本不经过 IPC 传给前端
+
+/// 把 text 放入系统剪贴板，替换原有内容
+#[cfg(target_os = "windows")]
+pub fn set_clipboard_text(text: &str) -> anyhow::Result<()> {
+    use windows_sys::Win32::Foundation::GlobalFree;
+    use windows_sys::Win32::System::DataExchange::{CloseClipboard, EmptyClipboard, OpenClipboard, SetClipboardData};
+    use windows_sys::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+
+    /// 标准剪贴板格式 CF_UNICODETEXT（UTF-16，以 0 结尾）
+    const CF_UNICODETEXT: u32 = 13;
+
+    let wide: Vec<u16> = text.encode_utf16().chain(std::iter::once(0)).collect();
+    let bytes = wide.len() * std::mem::size_of::<u16>();
+
+    // 其他程序正占用剪贴板时 OpenClipboard 失败，稍等重试
+    let mut opened = false;
+    for _ in 0..10 {
+        if unsafe { OpenClipboard(0) } != 0 {
+            opened = true;
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    if !opened {
+        anyhow::bail!("剪贴板被其他程序占用: {}", std::io::Error::last_os_error());
+    }
+
+    let result = unsafe {
+        EmptyClipboard();
+        let mem = GlobalAlloc(GMEM_MOVEABLE, bytes);
+        if mem.is_null() {
+            Err(anyhow::anyhow!("无法分配剪贴板内存"))
+        } else {
+            let dst = GlobalLock(mem) as *mut u16;
+            if dst.is_null() {
+                GlobalFree(mem);
+                Err(anyhow::anyhow!("无法锁定剪贴板内存"))
+            } else {
+                std::ptr::copy_nonoverlapping(wide.as_ptr(), dst, wide.len());
+                GlobalUnlock(mem);
+                // 成功后内存归系统所有，失败时须自行释放
+                if SetClipboardData(CF_UNICODETEXT, mem as isize) == 0 {
+                    let err = std::io::Error::last_os_error();
+                    GlobalFree(mem);
+                    Err(anyhow::anyhow!("写入剪贴板失败: {}", err))
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    };
+    unsafe { CloseClipboard() };
+    result
+}
+
+#[cfg(target_os = "macos")]
+pub fn set_clipboard_text(text: &str) -> anyhow::Result<()> {
+    pipe_to("pbcopy", &[], text)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn set_clipboard_text(text: &str) -> anyhow::Result<()> {
+    let tools: &[(&str, &[&str])] = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        &[("wl-copy", &[]), ("xclip", &["-selection", "clipboard"]), ("xsel", &["--clipboard", "--input"])]
+    } else {
+        &[("xclip", &["-selection", "clipboard"]), ("xsel", &["--clipboard", "--input"])]
+    };
+    let mut last_error = None;
+    for (program, args) in tools {
+        match pipe_to(program, args, text) {
+            Ok(()) => return Ok(()),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| anyhow::anyhow!("没有可用的剪贴板工具")))
+}
+
+/// 启动 program 并把 text 写入其标准输入
+#[cfg(not(target_os = "windows"))]
+fn pipe_to(program: &str, args: &[&str], text: &str) -> anyhow::Result<()> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("无法启动 {}: {}", program, e))?;
+    // 写完后关闭标准输入，工具读到 EOF 才会接管剪贴板
+    child.stdin.take().map(|mut stdin| stdin.write_all(text.as_bytes())).transpose()?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        anyhow::bail!("{}: {}", program, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
@@ -0,0 +1,114 @@
+// 云同步文件（OneDrive / Dropbox / iCloud）的本地状态
+//
+// 仅在线的占位符按 0 字节计入 size（内容不在本地），其逻辑大小以 CloudFile::OnlineOnly 上报；
+// 已下载到本地、仍由同步客户端管理的文件照常计入 size，以 CloudFile::Local 标记。
+// 扫描由 CloudTally 汇总两类字节数，分别报告“仅在线”与“已在本地”的云文件大小。
+// macOS 只能识别无数据占位项（SF_DATALESS），已下载的 iCloud 文件与普通文件无从区分。
+//
+// 判断只依据目录枚举 / MFT 记录中已有的属性与重解析标记，从不打开文件，
+// 因此扫描不会触发占位符下载（打开或读取 RECALL_ON_DATA_ACCESS 文件的数据才会）。
+
+use super::reparse::{
+    FILE_ATTRIBUTE_OFFLINE, FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS, FILE_ATTRIBUTE_RECALL_ON_OPEN,
+    FILE_ATTRIBUTE_REPARSE_POINT,
+};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// IO_REPARSE_TAG_CLOUD 及其 _1 … _F 变体（第 12–15 位为同步提供方编号）
+const IO_REPARSE_TAG_CLOUD: u32 = 0x9000_001A;
+const IO_REPARSE_TAG_CLOUD_MASK: u32 = 0xFFFF_0FFF;
+
+/// 云同步文件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloudFile {
+    /// 仅在线：内容不在本地，携带逻辑大小（未计入条目 size）
+    OnlineOnly(u64),
+    /// 已下载到本地，大小已计入条目 size
+    Local,
+}
+
+/// 是否为 Cloud Files API（OneDrive、Dropbox 等同步客户端）的重解析标记
+#[inline]
+pub fn is_cloud_tag(reparse_tag: u32) -> bool {
+    reparse_tag & IO_REPARSE_TAG_CLOUD_MASK == IO_REPARSE_TAG_CLOUD
+}
+
+/// 按 Win32 文件属性与重解析标记判断文件的云同步状态；目录与非云文件返回 None。
+/// size 为目录枚举 / MFT 记录中的逻辑大小
+pub fn cloud_file_windows(attributes: u32, reparse_tag: u32, is_dir: bool, size: u64) -> Option<CloudFile> {
+    if is_dir {
+        return None;
+    }
+    if attributes & (FILE_ATTRIBUTE_RECALL_ON_OPEN | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS | FILE_ATTRIBUTE_OFFLINE) != 0 {
+        return Some(CloudFile::OnlineOnly(size));
+    }
+    if attributes & FILE_ATTRIBUTE_REPARSE_POINT != 0 && is_cloud_tag(reparse_tag) {
+        return Some(CloudFile::Local);
+    }
+    None
+}
+
+/// 一次扫描内的云文件字节数汇总
+#[derive(Debug, Default)]
+pub struct CloudTally {
+    online_only: AtomicU64,
+    local: AtomicU64,
+}
+
+impl CloudTally {
+    /// 记录一个条目；size 为已计入条目的本地大小
+    pub fn record(&self, cloud: Option<CloudFile>, size: u64) {
+        match cloud {
+            Some(CloudFile::OnlineOnly(bytes)) => {
+                self.online_only.fetch_add(bytes, Ordering::Relaxed);
+            }
+            Some(CloudFile::Local) => {
+                self.local.fetch_add(size, Ordering::Relaxed);
+            }
+            None => {}
+        }
+    }
+
+    /// 并入已统计好的字节数（沿用缓存的子树）
+    pub fn add_totals(&self, online_only: i64, local: i64) {
+        self.online_only.fetch_add(online_only.max(0) as u64, Ordering::Relaxed);
+        self.local.fetch_add(local.max(0) as u64, Ordering::Relaxed);
+    }
+
+    /// (仅在线字节数, 已在本地字节数)
+    pub fn totals(&self) -> (i64, i64) {
+        (
+            self.online_only.load(Ordering::Relaxed) as i64,
+            self.local.load(Ordering::Relaxed) as i64,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn separates_online_only_and_local_bytes() {
+        let tally = CloudTally::default();
+        let onedrive_tag = 0x9000_601A;
+        assert!(is_cloud_tag(onedrive_tag) && is_cloud_tag(IO_REPARSE_TAG_CLOUD) && !is_cloud_tag(0xA000_000C));
+
+        let placeholder = cloud_file_windows(
+            FILE_ATTRIBUTE_REPARSE_POINT | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS,
+            onedrive_tag,
+            false,
+            1000,
+        );
+        assert_eq!(placeholder, Some(CloudFile::OnlineOnly(1000)));
+        let hydrated = cloud_file_windows(FILE_ATTRIBUTE_REPARSE_POINT, onedrive_tag, false, 300);
+        assert_eq!(hydrated, Some(CloudFile::Local));
+        assert_eq!(cloud_file_windows(FILE_ATTRIBUTE_REPARSE_POINT, onedrive_tag, true, 0), None);
+        assert_eq!(cloud_file_windows(0, 0, false, 50), None);
+
+        tally.record(placeholder, 0);
+        tally.record(hydrated, 300);
+        tally.record(None, 50);
+        assert_eq!(tally.totals(), (1000, 300));
+    }
+}
@@ -0,0 +1,150 @@
+// 存储介质识别：固态硬盘 / 机械硬盘 / 网络
+//
+// 遍历参数按介质选择：SSD 高并发把队列打满，HDD 上并发随机读只会让磁头来回寻道，
+// 改用少量线程并让 worker 深度优先处理自己发现的子目录（相邻目录的元数据在盘上也相邻）。
+//
+// Windows: 打开 \\.\C: 卷设备（无需读权限），IOCTL_STORAGE_QUERY_PROPERTY 查询
+//          StorageDeviceSeekPenaltyProperty，有寻道开销即为 HDD
+// 其他平台: sysinfo::Disks 的 DiskKind（Linux 读 /sys/block/*/queue/rotational）
+// 取不到（虚拟磁盘、RAID 卡、挂载文件夹）时为 Unknown，按 SSD 参数处理
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum DriveType {
+    Ssd,
+    Hdd,
+    /// UNC 共享、网络驱动器与 nfs / cifs 等网络挂载
+    Network,
+    #[default]
+    Unknown,
+}
+
+#[cfg(target_os = "windows")]
+pub fn drive_type(path: &str) -> DriveType {
+    use std::mem;
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::Storage::FileSystem::{CreateFileW, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING};
+    use windows_sys::Win32::System::IO::DeviceIoControl;
+
+    /// IOCTL_STORAGE_QUERY_PROPERTY = CTL_CODE(IOCTL_STORAGE_BASE, 0x500, METHOD_BUFFERED, FILE_ANY_ACCESS)
+    const IOCTL_STORAGE_QUERY_PROPERTY: u32 = 0x002D1400;
+    /// STORAGE_PROPERTY_ID::StorageDeviceSeekPenaltyProperty
+    const STORAGE_DEVICE_SEEK_PENALTY_PROPERTY: u32 = 7;
+    /// STORAGE_QUERY_TYPE::PropertyStandardQuery
+    const PROPERTY_STANDARD_QUERY: u32 = 0;
+
+    /// STORAGE_PROPERTY_QUERY
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct StoragePropertyQuery {
+        property_id: u32,
+        query_type: u32,
+        additional_parameters: [u8; 1],
+    }
+
+    /// DEVICE_SEEK_PENALTY_DESCRIPTOR
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct DeviceSeekPenaltyDescriptor {
+        version: u32,
+        size: u32,
+        incurs_seek_penalty: u8,
+    }
+
+    if super::is_network_path(path) {
+        return DriveType::Network;
+    }
+    let path = super::display_path(path);
+    let bytes = path.as_bytes();
+    if bytes.len() < 2 || bytes[1] != b':' {
+        return DriveType::Unknown;
+    }
+    let volume: Vec<u16> = format!(r"\\.\{}:", bytes[0] as char)
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+
+    unsafe {
+        // 访问权限 0：只查询设备属性，不需要管理员权限
+        let handle = CreateFileW(
+            volume.as_ptr(),
+            0,
+            FILE_SHARE_READ | FILE_SHARE_WRITE,
+            std::ptr::null_mut(),
+            OPEN_EXISTING,
+            0,
+            0,
+        );
+        if handle == INVALID_HANDLE_VALUE {
+            return DriveType::Unknown;
+        }
+        let query = StoragePropertyQuery {
+            property_id: STORAGE_DEVICE_SEEK_PENALTY_PROPERTY,
+            query_type: PROPERTY_STANDARD_QUERY,
+            additional_parameters: [0],
+        };
+        let mut descriptor: DeviceSeekPenaltyDescriptor = mem::zeroed();
+        let mut bytes_returned = 0u32;
+        let ok = DeviceIoControl(
+            handle,
+            IOCTL_STORAGE_QUERY_PROPERTY,
+            &query as *const _ as *const _,
+            mem::size_of::<StoragePropertyQuery>() as u32,
+            &mut descriptor as *mut _ as *mut _,
+            mem::size_of::<DeviceSeekPenaltyDescriptor>() as u32,
+            &mut bytes_returned,
+            std::ptr::null_mut(),
+        ) != 0;
+        CloseHandle(handle);
+
+        if !ok || (bytes_returned as usize) < mem::size_of::<DeviceSeekPenaltyDescriptor>() {
+            DriveType::Unknown
+        } else if descriptor.incurs_seek_penalty != 0 {
+            DriveType::Hdd
+        } else {
+            DriveType::Ssd
+        }
+    }
+}
+
+/// 取包含该路径的最长挂载点：网络文件系统为 Network，否则按其 DiskKind
+#[cfg(not(target_os = "windows"))]
+pub fn drive_type(path: &str) -> DriveType {
+    use sysinfo::{DiskKind, Disks};
+
+    let path = super::display_path(path);
+    if path.starts_with("//") {
+        return DriveType::Network;
+    }
+    let disks = Disks::new_with_refreshed_list();
+    let Some(disk) = disks
+        .list()
+        .iter()
+        .filter(|disk| std::path::Path::new(&path).starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+    else {
+        return DriveType::Unknown;
+    };
+    if super::volumes::is_network_fs(&disk.file_system().to_string_lossy()) {
+        return DriveType::Network;
+    }
+    match disk.kind() {
+        DiskKind::SSD => DriveType::Ssd,
+        DiskKind::HDD => DriveType::Hdd,
+        DiskKind::Unknown(_) => DriveType::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unc_paths_are_network_and_local_paths_are_not() {
+        assert_eq!(drive_type("//nas/share/media"), DriveType::Network);
+        assert_ne!(drive_type(&std::env::temp_dir().to_string_lossy()), DriveType::Network);
+    }
+}
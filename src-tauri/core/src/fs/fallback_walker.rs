@@ -0,0 +1,122 @@
+// 标准库目录遍历：非 Windows 平台的回退方案，也是各平台都可用的 std 扫描后端
+// 使用标准库 fs::read_dir（在 Linux/macOS 上也已足够高效，
+// getdents64 系统调用本身就会返回 d_type）
+
+use std::io;
+use std::path::Path;
+
+use super::ItemKind;
+
+/// Windows 上与原生遍历器共用同一条目类型
+#[cfg(target_os = "windows")]
+pub use super::windows_walker::FastDirEntry;
+
+/// 快速目录条目
+#[cfg(not(target_os = "windows"))]
+#[derive(Debug, Clone)]
+pub struct FastDirEntry {
+    pub path: std::path::PathBuf,
+    pub name: String,
+    pub size: u64,
+    pub is_dir: bool,
+    /// 条目类型（符号链接 / 挂载点等）
+    pub kind: ItemKind,
+    /// 最后修改时间（Unix 秒，0 表示未知）
+    pub modified: i64,
+    /// 创建时间（Unix 秒，0 表示未知；部分文件系统不记录）
+    pub created: i64,
+    /// 与其他文件共享、未计入 size 的数据块（仅 macOS APFS 克隆）
+    pub shared: Option<super::SharedExtent>,
+    /// 云同步文件的本地状态（仅 macOS 无数据占位符）
+    pub cloud: Option<super::CloudFile>,
+    /// 稀疏文件的逻辑 / 分配大小（size 为分配大小）
+    pub sparse: Option<super::SparseFile>,
+}
+
+/// 使用标准库遍历目录
+pub fn read_dir_entries(dir_path: &Path) -> io::Result<Vec<FastDirEntry>> {
+    let dir_iter = std::fs::read_dir(dir_path)?;
+
+    let mut entries = Vec::with_capacity(128);
+    // 子目录设备号与所在目录不同即为其他文件系统的挂载点
+    let parent_dev = dev_of(std::fs::metadata(dir_path).ok().as_ref());
+
+    for entry in dir_iter.filter_map(|e| e.ok()) {
+        let entry_path = entry.path();
+
+        let file_type = match entry.file_type() {
+            Ok(ft) => ft,
+            Err(_) => continue,
+        };
+
+        let is_dir = file_type.is_dir();
+        let is_symlink = file_type.is_symlink();
+
+        let name = entry_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("?")
+            .to_string();
+
+        // 时间信息需要 metadata，目录也要读取一次
+        let metadata = entry.metadata().ok();
+        // 符号链接不跟随，按 0 字节计；稀疏文件按分配大小计
+        let sparse = metadata.as_ref().filter(|_| !is_dir && !is_symlink).and_then(sparse_of);
+        let size = match (&metadata, is_dir || is_symlink) {
+            (Some(m), false) => sparse.map_or(m.len(), |s| s.allocated),
+            _ => 0,
+        };
+        let kind = if is_symlink {
+            ItemKind::Symlink
+        } else if is_dir && parent_dev.is_some() && dev_of(metadata.as_ref()) != parent_dev {
+            ItemKind::MountPoint
+        } else {
+            ItemKind::regular(is_dir)
+        };
+        let modified = metadata.as_ref().and_then(|m| m.modified().ok()).map(to_unix).unwrap_or(0);
+        let created = metadata.as_ref().and_then(|m| m.created().ok()).map(to_unix).unwrap_or(0);
+
+        entries.push(FastDirEntry {
+            path: entry_path,
+            name,
+            size,
+            is_dir,
+            kind,
+            modified,
+            created,
+            shared: None,
+            cloud: None,
+            sparse,
+        });
+    }
+
+    Ok(entries)
+}
+
+#[cfg(unix)]
+fn sparse_of(metadata: &std::fs::Metadata) -> Option<super::SparseFile> {
+    use std::os::unix::fs::MetadataExt;
+    super::SparseFile::from_sizes(metadata.len(), metadata.blocks() * 512)
+}
+
+#[cfg(not(unix))]
+fn sparse_of(_metadata: &std::fs::Metadata) -> Option<super::SparseFile> {
+    None
+}
+
+#[cfg(unix)]
+fn dev_of(metadata: Option<&std::fs::Metadata>) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    metadata.map(|m| m.dev())
+}
+
+#[cfg(not(unix))]
+fn dev_of(_metadata: Option<&std::fs::Metadata>) -> Option<u64> {
+    None
+}
+
+fn to_unix(t: std::time::SystemTime) -> i64 {
+    t.duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,333 @@
+// 单个条目的平台相关属性（属性对话框）
+//
+// Windows: Win32 文件属性、重解析标记（FindFirstFileW 的 dwReserved0）、
+//          GetFileInformationByHandleEx(FileStandardInfo) 取分配大小与硬链接数、
+//          GetCompressedFileSizeW 取压缩 / 稀疏文件实际占用、FindFirstStreamW 列出备用数据流
+// Unix:    lstat 的权限位与 st_blocks；扩展属性（xattr）作为附加数据流列出，
+//          macOS 另有 BSD 文件标志（隐藏、不可变、透明压缩）
+
+use super::ItemKind;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::path::Path;
+
+/// 附加数据流：NTFS 备用数据流（如 Zone.Identifier），Unix 为扩展属性
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DataStream {
+    pub name: String,
+    pub size: u64,
+}
+
+/// native_details 的结果
+#[derive(Debug, Clone, Default)]
+pub struct NativeDetails {
+    pub kind: ItemKind,
+    /// 属性名（camelCase，如 readOnly、hidden、compressed）
+    pub attributes: Vec<String>,
+    /// Unix 权限位（如 rwxr-xr-x）；Windows 为 None
+    pub permissions: Option<String>,
+    /// 实际分配的磁盘空间
+    pub allocated_size: Option<u64>,
+    /// 透明压缩 / 稀疏文件压缩后的占用；未压缩时为 None
+    pub compressed_size: Option<u64>,
+    pub hard_links: Option<u64>,
+    pub streams: Vec<DataStream>,
+}
+
+/// Win32 文件属性位与对应的属性名（winnt.h）
+#[cfg(target_os = "windows")]
+const ATTRIBUTE_NAMES: &[(u32, &str)] = &[
+    (0x0000_0001, "readOnly"),
+    (0x0000_0002, "hidden"),
+    (0x0000_0004, "system"),
+    (0x0000_0020, "archive"),
+    (0x0000_0100, "temporary"),
+    (0x0000_0200, "sparse"),
+    (0x0000_0400, "reparsePoint"),
+    (0x0000_0800, "compressed"),
+    (0x0000_1000, "offline"),
+    (0x0000_2000, "notContentIndexed"),
+    (0x0000_4000, "encrypted"),
+    (0x0000_8000, "integrityStream"),
+    (0x0004_0000, "recallOnOpen"),
+    (0x0008_0000, "pinned"),
+    (0x0010_0000, "unpinned"),
+    (0x0040_0000, "recallOnDataAccess"),
+];
+
+/// path 为扩展长度形式的原生路径，meta 为其 symlink_metadata（不跟随链接）
+#[cfg(target_os = "windows")]
+pub fn native_details(path: &Path, meta: &std::fs::Metadata) -> NativeDetails {
+    use std::os::windows::fs::MetadataExt;
+
+    let attributes = meta.file_attributes();
+    let is_dir = meta.is_dir();
+    let reparse_tag = if attributes & super::reparse::FILE_ATTRIBUTE_REPARSE_POINT != 0 { reparse_tag(path) } else { 0 };
+    let (allocated_size, hard_links) = standard_info(path).unzip();
+    // 压缩 / 稀疏文件的实际占用小于逻辑大小；其他文件 GetCompressedFileSizeW 只返回逻辑大小
+    let compressed_size = (!is_dir && attributes & (0x0800 | 0x0200) != 0)
+        .then(|| super::sparse_files::allocated_size(path))
+        .flatten();
+
+    NativeDetails {
+        kind: super::classify_windows(attributes, reparse_tag, is_dir, || super::is_volume_mount_point(path)),
+        attributes: ATTRIBUTE_NAMES
+            .iter()
+            .filter(|(bit, _)| attributes & bit != 0)
+            .map(|(_, name)| name.to_string())
+            .collect(),
+        permissions: None,
+        allocated_size,
+        compressed_size,
+        hard_links,
+        streams: alternate_streams(path),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn wide(path: &Path) -> Vec<u16> {
+    use std::os::windows::ffi::OsStrExt;
+    path.as_os_str().encode_wide().chain(std::iter::once(0)).collect()
+}
+
+/// 重解析标记；FindFirstFileW 在 dwReserved0 中返回
+#[cfg(target_os = "windows")]
+fn reparse_tag(path: &Path) -> u32 {
+    use windows_sys::Win32::Foundation::INVALID_HANDLE_VALUE;
+    use windows_sys::Win32::Storage::FileSystem::{FindClose, FindFirstFileW, WIN32_FIND_DATAW};
+
+    let name = wide(path);
+    let mut data: WIN32_FIND_DATAW = unsafe { std::mem::zeroed() };
+    let handle = unsafe { FindFirstFileW(name.as_ptr(), &mut data) };
+    if handle == INVALID_HANDLE_VALUE {
+        return 0;
+    }
+    unsafe { FindClose(handle) };
+    data.dwReserved0
+}
+
+/// (分配大小, 硬链接数)；不跟随重解析点，目录需 FILE_FLAG_BACKUP_SEMANTICS 才能打开
+#[cfg(target_os = "windows")]
+fn standard_info(path: &Path) -> Option<(u64, u64)> {
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::Storage::FileSystem::{
+        CreateFileW, FileStandardInfo, GetFileInformationByHandleEx, FILE_FLAG_BACKUP_SEMANTICS,
+        FILE_FLAG_OPEN_REPARSE_POINT, FILE_READ_ATTRIBUTES, FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE,
+        FILE_STANDARD_INFO, OPEN_EXISTING,
+    };
+
+    let name = wide(path);
+    let handle = unsafe {
+        CreateFileW(
+            name.as_ptr(),
+            FILE_READ_ATTRIBUTES,
+            FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+            std::ptr::null(),
+            OPEN_EXISTING,
+            FILE_FLAG_BACKUP_SEMANTICS | FILE_FLAG_OPEN_REPARSE_POINT,
+            0,
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        return None;
+    }
+    let mut info: FILE_STANDARD_INFO = unsafe { std::mem::zeroed() };
+    let ok = unsafe {
+        GetFileInformationByHandleEx(
+            handle,
+            FileStandardInfo,
+            &mut info as *mut _ as *mut _,
+            std::mem::size_of::<FILE_STANDARD_INFO>() as u32,
+        )
+    } != 0;
+    unsafe { CloseHandle(handle) };
+    ok.then_some((info.AllocationSize.max(0) as u64, info.NumberOfLinks as u64))
+}
+
+/// 备用数据流（不含无名主数据流 ::$DATA）；非 NTFS 卷上 FindFirstStreamW 失败，返回空
+#[cfg(target_os = "windows")]
+fn alternate_streams(path: &Path) -> Vec<DataStream> {
+    use windows_sys::Win32::Foundation::INVALID_HANDLE_VALUE;
+    use windows_sys::Win32::Storage::FileSystem::{
+        FindClose, FindFirstStreamW, FindNextStreamW, FindStreamInfoStandard, WIN32_FIND_STREAM_DATA,
+    };
+
+    let name = wide(path);
+    let mut data: WIN32_FIND_STREAM_DATA = unsafe { std::mem::zeroed() };
+    let handle = unsafe { FindFirstStreamW(name.as_ptr(), FindStreamInfoStandard, &mut data as *mut _ as *mut _, 0) };
+    if handle == INVALID_HANDLE_VALUE {
+        return Vec::new();
+    }
+    let mut streams = Vec::new();
+    loop {
+        // 流名形如 ":Zone.Identifier:$DATA"
+        let len = data.cStreamName.iter().position(|&c| c == 0).unwrap_or(data.cStreamName.len());
+        let raw = String::from_utf16_lossy(&data.cStreamName[..len]);
+        let stream = raw.strip_prefix(':').unwrap_or(&raw);
+        let stream = stream.strip_suffix(":$DATA").unwrap_or(stream);
+        if !stream.is_empty() {
+            streams.push(DataStream { name: stream.to_string(), size: data.StreamSize.max(0) as u64 });
+        }
+        if unsafe { FindNextStreamW(handle, &mut data as *mut _ as *mut _) } == 0 {
+            break;
+        }
+    }
+    unsafe { FindClose(handle) };
+    streams
+}
+
+/// path 为原生路径，meta 为其 symlink_metadata（不跟随链接）
+#[cfg(unix)]
+pub fn native_details(path: &Path, meta: &std::fs::Metadata) -> NativeDetails {
+    use std::os::unix::fs::MetadataExt;
+
+    let mode = meta.mode();
+    let mut attributes = Vec::new();
+    if mode & 0o222 == 0 {
+        attributes.push("readOnly");
+    }
+    if path.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.')) {
+        attributes.push("hidden");
+    }
+    if meta.is_file() && mode & 0o111 != 0 {
+        attributes.push("executable");
+    }
+    for (bit, name) in [(0o4000, "setuid"), (0o2000, "setgid"), (0o1000, "sticky")] {
+        if mode & bit != 0 {
+            attributes.push(name);
+        }
+    }
+    let allocated = meta.blocks() * 512;
+
+    #[cfg(target_os = "macos")]
+    let compressed_size = {
+        use std::os::macos::fs::MetadataExt as _;
+        // sys/stat.h：UF_IMMUTABLE、UF_COMPRESSED（HFS+ / APFS 透明压缩）、UF_HIDDEN
+        let flags = meta.st_flags();
+        for (bit, name) in [(0x0002, "immutable"), (0x0020, "compressed"), (0x8000, "hidden")] {
+            if flags & bit != 0 && !attributes.contains(&name) {
+                attributes.push(name);
+            }
+        }
+        (flags & 0x0020 != 0).then_some(allocated)
+    };
+    #[cfg(not(target_os = "macos"))]
+    let compressed_size = None;
+
+    NativeDetails {
+        kind: unix_kind(path, meta),
+        attributes: attributes.into_iter().map(str::to_string).collect(),
+        permissions: Some(permission_string(mode)),
+        allocated_size: Some(allocated),
+        compressed_size,
+        hard_links: Some(meta.nlink()),
+        streams: extended_attributes(path),
+    }
+}
+
+/// 符号链接；与父目录设备号不同的目录为挂载点
+#[cfg(unix)]
+fn unix_kind(path: &Path, meta: &std::fs::Metadata) -> ItemKind {
+    use std::os::unix::fs::MetadataExt;
+
+    if meta.file_type().is_symlink() {
+        return ItemKind::Symlink;
+    }
+    let mounted = meta.is_dir()
+        && path
+            .parent()
+            .and_then(|parent| std::fs::metadata(parent).ok())
+            .is_some_and(|parent| parent.dev() != meta.dev());
+    if mounted {
+        ItemKind::MountPoint
+    } else {
+        ItemKind::regular(meta.is_dir())
+    }
+}
+
+/// 权限位转 ls 风格的 rwxr-xr-x
+#[cfg(unix)]
+fn permission_string(mode: u32) -> String {
+    [6, 3, 0]
+        .iter()
+        .flat_map(|shift| {
+            let bits = (mode >> shift) & 0o7;
+            [(4, 'r'), (2, 'w'), (1, 'x')].map(|(bit, c)| if bits & bit != 0 { c } else { '-' })
+        })
+        .collect()
+}
+
+/// 扩展属性名与值大小（不跟随符号链接）；文件系统不支持或无权限时返回空
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn extended_attributes(path: &Path) -> Vec<DataStream> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let Ok(c_path) = CString::new(path.as_os_str().as_bytes()) else {
+        return Vec::new();
+    };
+    let len = unsafe { list_xattr(&c_path, std::ptr::null_mut(), 0) };
+    if len <= 0 {
+        return Vec::new();
+    }
+    let mut names = vec![0u8; len as usize];
+    let len = unsafe { list_xattr(&c_path, names.as_mut_ptr() as *mut libc::c_char, names.len()) };
+    names.truncate(len.max(0) as usize);
+
+    names
+        .split(|&b| b == 0)
+        .filter(|name| !name.is_empty())
+        .filter_map(|name| {
+            let c_name = CString::new(name).ok()?;
+            let size = unsafe { xattr_size(&c_path, &c_name) };
+            Some(DataStream { name: String::from_utf8_lossy(name).into_owned(), size: size.max(0) as u64 })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+unsafe fn list_xattr(path: &std::ffi::CStr, buf: *mut libc::c_char, size: usize) -> isize {
+    libc::llistxattr(path.as_ptr(), buf, size)
+}
+
+#[cfg(target_os = "linux")]
+unsafe fn xattr_size(path: &std::ffi::CStr, name: &std::ffi::CStr) -> isize {
+    libc::lgetxattr(path.as_ptr(), name.as_ptr(), std::ptr::null_mut(), 0)
+}
+
+#[cfg(target_os = "macos")]
+unsafe fn list_xattr(path: &std::ffi::CStr, buf: *mut libc::c_char, size: usize) -> isize {
+    libc::listxattr(path.as_ptr(), buf, size, libc::XATTR_NOFOLLOW)
+}
+
+#[cfg(target_os = "macos")]
+unsafe fn xattr_size(path: &std::ffi::CStr, name: &std::ffi::CStr) -> isize {
+    libc::getxattr(path.as_ptr(), name.as_ptr(), std::ptr::null_mut(), 0, 0, libc::XATTR_NOFOLLOW)
+}
+
+#[cfg(all(unix, not(any(target_os = "linux", target_os = "macos"))))]
+fn extended_attributes(_path: &Path) -> Vec<DataStream> {
+    Vec::new()
+}
+
+#[cfg(not(any(unix, target_os = "windows")))]
+pub fn native_details(_path: &Path, meta: &std::fs::Metadata) -> NativeDetails {
+    NativeDetails {
+        kind: ItemKind::regular(meta.is_dir()),
+        attributes: if meta.permissions().readonly() { vec!["readOnly".to_string()] } else { Vec::new() },
+        ..Default::default()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_permission_bits() {
+        assert_eq!(permission_string(0o755), "rwxr-xr-x");
+        assert_eq!(permission_string(0o640), "rw-r-----");
+        assert_eq!(permission_string(0o4000), "---------");
+    }
+}
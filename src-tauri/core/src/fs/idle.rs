@@ -0,0 +1,61 @@
+// 用户空闲时长：距最后一次键盘 / 鼠标输入的秒数
+//
+// 空闲扫描（idle_scan）据此判断用户是否离开。
+// Windows: GetLastInputInfo（本会话的最后输入时刻，与 GetTickCount 同一时基）
+// macOS: `ioreg -c IOHIDSystem` 的 HIDIdleTime（纳秒）
+// Linux: `xprintidle`（毫秒，X11；未安装或 Wayland 下不可用）
+// 无法获取时返回 None，由调用方决定是否只按 CPU 负载判断
+
+#[cfg(target_os = "windows")]
+pub fn user_idle_secs() -> Option<u64> {
+    use windows_sys::Win32::System::SystemInformation::GetTickCount;
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    let mut info = LASTINPUTINFO { cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32, dwTime: 0 };
+    if unsafe { GetLastInputInfo(&mut info) } == 0 {
+        return None;
+    }
+    // 两者都是 32 位毫秒计数，约 49.7 天回绕一次，用回绕减法
+    let now = unsafe { GetTickCount() };
+    Some(now.wrapping_sub(info.dwTime) as u64 / 1000)
+}
+
+#[cfg(target_os = "macos")]
+pub fn user_idle_secs() -> Option<u64> {
+    let output = std::process::Command::new("ioreg").args(["-c", "IOHIDSystem"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_hid_idle_time(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn user_idle_secs() -> Option<u64> {
+    let output = std::process::Command::new("xprintidle").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let ms: u64 = String::from_utf8_lossy(&output.stdout).trim().parse().ok()?;
+    Some(ms / 1000)
+}
+
+/// 从 ioreg 输出中取 `"HIDIdleTime" = 123456789`（纳秒）
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_hid_idle_time(ioreg: &str) -> Option<u64> {
+    let line = ioreg.lines().find(|l| l.contains("\"HIDIdleTime\""))?;
+    let ns: u64 = line.split('=').nth(1)?.trim().parse().ok()?;
+    Some(ns / 1_000_000_000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ioreg_idle_time() {
+        let out = "  | |   \"HIDIdleTime\" = 125000000000\n  | |   \"HIDParameters\" = {}";
+        assert_eq!(parse_hid_idle_time(out), Some(125));
+        assert_eq!(parse_hid_idle_time("\"HIDIdleTime\" = abc"), None);
+        assert_eq!(parse_hid_idle_time(""), None);
+    }
+}
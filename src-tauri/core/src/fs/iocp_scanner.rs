@@ -106,6 +106,8 @@ impl IocpScanner {
             }
         });
 
+        // FindFirstFileExW 只有在扩展长度形式下才能打开超过 MAX_PATH 的目录
+        let root = super::to_extended_length(&root.to_string_lossy());
         self.scan_with_iocp(root, tx).await?;
 
         drop(collector);
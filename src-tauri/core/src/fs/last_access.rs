@@ -0,0 +1,88 @@
+// 最后访问时间（atime）可用性检测
+//
+// NTFS 的最后访问时间更新经常被关闭（Windows 10 1803 起系统可自动管理，大容量卷默认关闭），
+// 此时 atime 基本等于创建/修改时间，基于访问时间的分析（冷热分级等）没有意义。
+//
+// Windows: 读取 HKLM\SYSTEM\CurrentControlSet\Control\FileSystem\NtfsDisableLastAccessUpdate
+//   0 / 0x80000000 = 开启（用户 / 系统管理）
+//   1 / 0x80000001 = 关闭（用户 / 系统管理）
+// Linux: 检查根文件系统挂载选项是否含 noatime
+// 其他平台: 未知
+
+use serde::Serialize;
+
+/// 最后访问时间更新状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LastAccessUpdates {
+    Enabled,
+    Disabled,
+    Unknown,
+}
+
+impl LastAccessUpdates {
+    /// atime 是否可信（仅在明确关闭时视为不可信）
+    pub fn is_reliable(&self) -> bool {
+        *self != LastAccessUpdates::Disabled
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn last_access_updates() -> LastAccessUpdates {
+    use windows_sys::Win32::System::Registry::{RegGetValueW, HKEY_LOCAL_MACHINE, RRF_RT_REG_DWORD};
+
+    let subkey: Vec<u16> = "SYSTEM\\CurrentControlSet\\Control\\FileSystem"
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    let value: Vec<u16> = "NtfsDisableLastAccessUpdate"
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+
+    let mut data: u32 = 0;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            subkey.as_ptr(),
+            value.as_ptr(),
+            RRF_RT_REG_DWORD,
+            std::ptr::null_mut(),
+            &mut data as *mut u32 as *mut _,
+            &mut size,
+        )
+    };
+
+    if status != 0 {
+        return LastAccessUpdates::Unknown;
+    }
+    // 最高位表示"系统管理"，最低位表示是否关闭
+    if data & 1 == 1 {
+        LastAccessUpdates::Disabled
+    } else {
+        LastAccessUpdates::Enabled
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn last_access_updates() -> LastAccessUpdates {
+    let Ok(mounts) = std::fs::read_to_string("/proc/mounts") else {
+        return LastAccessUpdates::Unknown;
+    };
+    // 格式: device mount_point fs_type options dump pass
+    match mounts
+        .lines()
+        .map(|l| l.split_whitespace().collect::<Vec<_>>())
+        .find(|f| f.len() >= 4 && f[1] == "/")
+    {
+        Some(f) if f[3].split(',').any(|o| o == "noatime") => LastAccessUpdates::Disabled,
+        Some(_) => LastAccessUpdates::Enabled,
+        None => LastAccessUpdates::Unknown,
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn last_access_updates() -> LastAccessUpdates {
+    LastAccessUpdates::Unknown
+}
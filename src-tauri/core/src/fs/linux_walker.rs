@@ -0,0 +1,219 @@
+// Linux 目录遍历：getdents64 + statx
+//
+// std::fs::read_dir 为每个条目分配 DirEntry / PathBuf，metadata() 还要按完整路径 lstat。
+// 这里直接用 getdents64 批量读取目录项（64KB 缓冲，一次系统调用返回数百项），
+// 再以目录 fd 为基准对条目名调用 statx（免去逐级路径解析），只请求需要的字段，
+// 并带 AT_STATX_DONT_SYNC，NFS / CIFS 上不强制向服务器同步属性。
+// statx 走裸系统调用，不依赖 glibc 2.28+ 的包装函数；内核不支持（< 4.11）时回退到标准库实现。
+
+use std::ffi::{CStr, CString, OsStr};
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+use super::ItemKind;
+pub use super::fallback_walker::FastDirEntry;
+
+/// getdents64 缓冲区大小
+const DIRENT_BUF_SIZE: usize = 64 * 1024;
+
+/// linux_dirent64 布局：u64 d_ino | i64 d_off | u16 d_reclen | u8 d_type | char d_name[]
+const DIRENT_RECLEN_OFFSET: usize = 16;
+const DIRENT_TYPE_OFFSET: usize = 18;
+const DIRENT_NAME_OFFSET: usize = 19;
+
+/// 遍历目录（getdents64 + statx）
+pub fn read_dir_entries(dir_path: &Path) -> io::Result<Vec<FastDirEntry>> {
+    let dir = DirFd::open(dir_path)?;
+    // 子目录设备号与所在目录不同即为其他文件系统的挂载点
+    let parent_dev = dir.dev();
+
+    let mut entries = Vec::with_capacity(128);
+    let mut buf = vec![0u8; DIRENT_BUF_SIZE];
+
+    loop {
+        let n = unsafe { libc::syscall(libc::SYS_getdents64, dir.0, buf.as_mut_ptr(), buf.len()) };
+        if n < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if n == 0 {
+            break;
+        }
+
+        let bytes = &buf[..n as usize];
+        let mut offset = 0;
+        while offset + DIRENT_NAME_OFFSET < bytes.len() {
+            let reclen = u16::from_ne_bytes([
+                bytes[offset + DIRENT_RECLEN_OFFSET],
+                bytes[offset + DIRENT_RECLEN_OFFSET + 1],
+            ]) as usize;
+            if reclen == 0 {
+                break;
+            }
+            let d_type = bytes[offset + DIRENT_TYPE_OFFSET];
+            let record = &bytes[offset + DIRENT_NAME_OFFSET..(offset + reclen).min(bytes.len())];
+            offset += reclen;
+
+            let Ok(name) = CStr::from_bytes_until_nul(record) else {
+                continue;
+            };
+            if matches!(name.to_bytes(), b"." | b"..") {
+                continue;
+            }
+
+            let stx = match statx_at(dir.0, name) {
+                Ok(stx) => Some(stx),
+                Err(e) if e.raw_os_error() == Some(libc::ENOSYS) => {
+                    return super::fallback_walker::read_dir_entries(dir_path);
+                }
+                // 遍历期间被删除等：有 d_type 时仍保留条目
+                Err(_) => None,
+            };
+            if let Some(entry) = to_entry(dir_path, name, d_type, stx.as_ref(), parent_dev) {
+                entries.push(entry);
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+fn to_entry(
+    dir_path: &Path,
+    name: &CStr,
+    d_type: u8,
+    stx: Option<&libc::statx>,
+    parent_dev: Option<u64>,
+) -> Option<FastDirEntry> {
+    // 部分文件系统（XFS 旧格式等）不填 d_type，取 statx 的类型位
+    let (is_dir, is_symlink) = match d_type {
+        libc::DT_DIR => (true, false),
+        libc::DT_LNK => (false, true),
+        libc::DT_UNKNOWN => {
+            let mode = stx?.stx_mode as libc::mode_t & libc::S_IFMT;
+            (mode == libc::S_IFDIR, mode == libc::S_IFLNK)
+        }
+        _ => (false, false),
+    };
+
+    // 符号链接不跟随，按 0 字节计；稀疏文件按分配大小计
+    let sparse = stx
+        .filter(|s| !is_dir && !is_symlink && s.stx_mask & libc::STATX_BLOCKS != 0)
+        .and_then(|s| super::SparseFile::from_sizes(s.stx_size, s.stx_blocks * 512));
+    let size = match stx {
+        Some(s) if !is_dir && !is_symlink => sparse.map_or(s.stx_size, |sp| sp.allocated),
+        _ => 0,
+    };
+    let kind = if is_symlink {
+        ItemKind::Symlink
+    } else if is_dir && parent_dev.is_some() && stx.map(dev_of) != parent_dev {
+        ItemKind::MountPoint
+    } else {
+        ItemKind::regular(is_dir)
+    };
+    let modified = stx
+        .filter(|s| s.stx_mask & libc::STATX_MTIME != 0)
+        .map(|s| s.stx_mtime.tv_sec)
+        .unwrap_or(0);
+    // 创建时间需要文件系统支持（ext4 / btrfs / xfs 支持，tmpfs 等不支持）
+    let created = stx
+        .filter(|s| s.stx_mask & libc::STATX_BTIME != 0)
+        .map(|s| s.stx_btime.tv_sec)
+        .unwrap_or(0);
+
+    Some(FastDirEntry {
+        path: dir_path.join(OsStr::from_bytes(name.to_bytes())),
+        name: name.to_string_lossy().into_owned(),
+        size,
+        is_dir,
+        kind,
+        modified,
+        created,
+        shared: None,
+        cloud: None,
+        sparse,
+    })
+}
+
+/// 相对目录 fd 对条目调用 statx（不跟随符号链接）
+fn statx_at(dirfd: libc::c_int, name: &CStr) -> io::Result<libc::statx> {
+    let mut stx: libc::statx = unsafe { std::mem::zeroed() };
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_statx,
+            dirfd,
+            name.as_ptr(),
+            libc::AT_SYMLINK_NOFOLLOW | libc::AT_STATX_DONT_SYNC,
+            libc::STATX_TYPE | libc::STATX_SIZE | libc::STATX_BLOCKS | libc::STATX_MTIME | libc::STATX_BTIME,
+            &mut stx as *mut libc::statx,
+        )
+    };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(stx)
+}
+
+#[inline]
+fn dev_of(stx: &libc::statx) -> u64 {
+    libc::makedev(stx.stx_dev_major, stx.stx_dev_minor)
+}
+
+/// 目录文件描述符，离开作用域时关闭
+struct DirFd(libc::c_int);
+
+impl DirFd {
+    fn open(path: &Path) -> io::Result<Self> {
+        let c_path = CString::new(path.as_os_str().as_bytes())
+            .map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+        let fd = unsafe { libc::open(c_path.as_ptr(), libc::O_RDONLY | libc::O_DIRECTORY | libc::O_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self(fd))
+    }
+
+    fn dev(&self) -> Option<u64> {
+        let mut st: libc::stat = unsafe { std::mem::zeroed() };
+        (unsafe { libc::fstat(self.0, &mut st) } == 0).then_some(st.st_dev)
+    }
+}
+
+impl Drop for DirFd {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_std_walker() {
+        let root = std::env::temp_dir().join(format!("flashdir-linux-walker-{}", std::process::id()));
+        std::fs::remove_dir_all(&root).ok();
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(root.join("a.bin"), vec![0u8; 1234]).unwrap();
+        std::fs::write(root.join("名字.txt"), b"hi").unwrap();
+        std::os::unix::fs::symlink(root.join("a.bin"), root.join("link")).unwrap();
+
+        let key = |e: &FastDirEntry| (e.name.clone(), e.size, e.is_dir, e.kind as u8, e.modified, e.path.clone());
+        let mut fast: Vec<_> = read_dir_entries(&root).unwrap().iter().map(key).collect();
+        let mut std_walk: Vec<_> = super::super::fallback_walker::read_dir_entries(&root)
+            .unwrap()
+            .iter()
+            .map(key)
+            .collect();
+        fast.sort();
+        std_walk.sort();
+        assert_eq!(fast, std_walk);
+        assert_eq!(fast.len(), 4);
+        assert!(fast.iter().any(|e| e.0 == "link" && e.3 == ItemKind::Symlink as u8 && e.1 == 0));
+
+        assert!(read_dir_entries(&root.join("missing")).is_err());
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,145 @@
+// Windows 长路径（超过 MAX_PATH = 260 字符）支持
+//
+// Win32 API 只有在扩展长度形式（\\?\C:\... / \\?\UNC\server\share\...）下才接受超长路径，
+// 否则超长目录会打开失败并被静默跳过。遍历一律使用扩展长度形式，
+// 展示给用户、写入结果 / 缓存 key / 历史的路径则去掉前缀。
+// 缓存 key 另按平台折叠大小写（Windows 路径不区分大小写）。
+
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+/// 把路径转换为 Windows 扩展长度形式（纯字符串变换，不访问文件系统）
+///
+/// - `C:\dir` / `C:/dir`      → `\\?\C:\dir`
+/// - `\\server\share\dir`     → `\\?\UNC\server\share\dir`
+/// - 已是 `\\?\` / `//?/` 形式 → 仅统一分隔符
+/// - 相对路径等无法转换的输入原样返回（扩展长度形式不做 `.` / `..` 解析）
+pub fn extended_length_str(path: &str) -> String {
+    let backslashed = path.replace('/', "\\");
+    if backslashed.starts_with("\\\\?\\") {
+        return backslashed;
+    }
+    if let Some(unc) = backslashed.strip_prefix("\\\\") {
+        return format!("\\\\?\\UNC\\{}", unc);
+    }
+    let bytes = backslashed.as_bytes();
+    if bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'\\' {
+        return format!("\\\\?\\{}", backslashed);
+    }
+    path.to_string()
+}
+
+/// 去掉扩展长度前缀，得到用户可读的路径（保留原有分隔符风格）
+///
+/// `\\?\C:\dir` → `C:\dir`，`\\?\UNC\server\share` → `\\server\share`，
+/// 正斜杠形式（`//?/C:/dir`、`//?/UNC/server/share`）同理
+pub fn display_path_str(path: &str) -> Cow<'_, str> {
+    for (verbatim_unc, unc) in [("\\\\?\\UNC\\", "\\\\"), ("//?/UNC/", "//")] {
+        if let Some(rest) = path.strip_prefix(verbatim_unc) {
+            return Cow::Owned(format!("{}{}", unc, rest));
+        }
+    }
+    for verbatim in ["\\\\?\\", "//?/"] {
+        if let Some(rest) = path.strip_prefix(verbatim) {
+            return Cow::Borrowed(rest);
+        }
+    }
+    Cow::Borrowed(path)
+}
+
+/// 结果、历史记录与事件日志统一使用的展示形式（缓存 key 见 cache_key）：
+/// 去掉扩展长度前缀、统一为正斜杠、盘符大写（`\\?\c:\Users` → `C:/Users`）
+pub fn display_path(path: &str) -> String {
+    let mut display = display_path_str(path).replace('\\', "/");
+    uppercase_drive_letter(&mut display);
+    display
+}
+
+/// `c:/...` → `C:/...`，同一目录不会因输入的盘符大小写不同而产生两份缓存 / 历史
+#[inline]
+pub fn uppercase_drive_letter(path: &mut String) {
+    let bytes = path.as_bytes();
+    if bytes.len() >= 2 && bytes[1] == b':' && bytes[0].is_ascii_lowercase() {
+        path[..1].make_ascii_uppercase();
+    }
+}
+
+/// 内存 / 磁盘缓存、大小历史与缓存失效统一使用的 key：在 display_path 基础上
+/// 去掉末尾分隔符（根目录 `C:/`、`/` 除外），Windows 下再整体转小写。
+/// `D:\Data\`、`d:\data`、`\\?\D:\DATA` 得到同一个 key；key 只用于查找，
+/// 展示给用户的路径仍用 display_path
+pub fn cache_key(path: &str) -> String {
+    fold_cache_key(path, cfg!(target_os = "windows"))
+}
+
+/// key 是否为 root 下某个子目录（不含 root 本身）的完整结果缓存 key；
+/// 两者均为 cache_key 形式，带排除预设 / 包含模式修饰的 key 不算
+pub fn is_child_cache_key(root: &str, key: &str) -> bool {
+    !key.contains('|')
+        && key
+            .strip_prefix(root.trim_end_matches('/'))
+            .is_some_and(|rest| rest.len() > 1 && rest.starts_with('/'))
+}
+
+fn fold_cache_key(path: &str, case_insensitive: bool) -> String {
+    let mut key = display_path(path);
+    while key.len() > 1 && key.ends_with('/') && !(key.len() == 3 && key.as_bytes()[1] == b':') {
+        key.pop();
+    }
+    if case_insensitive {
+        key = key.to_lowercase();
+    }
+    key
+}
+
+/// 遍历用路径：Windows 下为扩展长度形式，其他平台原样
+pub fn to_extended_length(path: &str) -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        PathBuf::from(extended_length_str(path))
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        PathBuf::from(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_path_round_trip() {
+        assert_eq!(extended_length_str("C:/Users/a"), "\\\\?\\C:\\Users\\a");
+        assert_eq!(extended_length_str("\\\\nas\\share\\x"), "\\\\?\\UNC\\nas\\share\\x");
+        assert_eq!(extended_length_str("//?/C:/a"), "\\\\?\\C:\\a");
+        assert_eq!(extended_length_str("relative\\dir"), "relative\\dir");
+
+        assert_eq!(display_path_str("\\\\?\\C:\\Users\\a"), "C:\\Users\\a");
+        assert_eq!(display_path_str("\\\\?\\UNC\\nas\\share\\x"), "\\\\nas\\share\\x");
+        assert_eq!(display_path_str("//?/UNC/nas/share"), "//nas/share");
+        assert_eq!(display_path_str("//?/C:/a"), "C:/a");
+        assert_eq!(display_path_str(&extended_length_str("\\\\nas\\s")), "\\\\nas\\s");
+
+        assert_eq!(display_path("\\\\?\\c:\\Users\\a"), "C:/Users/a");
+        assert_eq!(display_path("\\\\?\\UNC\\nas\\share"), "//nas/share");
+        assert_eq!(display_path("/home/user"), "/home/user");
+    }
+
+    #[test]
+    fn cache_key_folds_case_and_trailing_separators() {
+        for input in ["D:\\Data\\", "d:\\data", "\\\\?\\D:\\DATA", "d:/Data/"] {
+            assert_eq!(fold_cache_key(input, true), "d:/data");
+        }
+        assert_eq!(fold_cache_key("\\\\?\\UNC\\NAS\\Share\\", true), "//nas/share");
+        assert_eq!(fold_cache_key("C:\\", true), "c:/");
+        assert_eq!(fold_cache_key("/", false), "/");
+        assert!(is_child_cache_key("d:/data", "d:/data/app"));
+        assert!(is_child_cache_key("c:/", "c:/data"));
+        assert!(!is_child_cache_key("d:/data", "d:/data"));
+        assert!(!is_child_cache_key("d:/data", "d:/database"));
+        assert!(!is_child_cache_key("d:/data", "d:/data/app|exclude=system"));
+        // 区分大小写的平台只规范分隔符
+        assert_eq!(fold_cache_key("/home/User/", false), "/home/User");
+    }
+}
@@ -0,0 +1,245 @@
+// macOS 目录遍历：getattrlistbulk
+//
+// std::fs::read_dir 之后还要对每个条目 lstat 取大小与时间；getattrlistbulk 一次系统调用
+// 就返回一批条目的名称、类型、大小、时间与标志（64KB 缓冲，每批数百项）。
+//
+// APFS 克隆：额外请求私有大小（ATTR_CMNEXT_PRIVATESIZE）与克隆 ID（ATTR_CMNEXT_CLONEID），
+// 与其他文件共享数据块的文件只把私有部分记入 size，共享部分作为 SharedExtent 上报，
+// 由扫描按克隆组去重计一次（见 shared_extent.rs）。
+// iCloud 等按需下载的占位项（SF_DATALESS）不读取内容、不进入，按 0 字节计，
+// 文件的逻辑大小作为 CloudFile::OnlineOnly 上报。
+
+use std::ffi::{CStr, OsStr};
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+
+use super::{CloudFile, ItemKind, SharedExtent};
+pub use super::fallback_walker::FastDirEntry;
+
+/// getattrlistbulk 缓冲区大小
+const ATTR_BUF_SIZE: usize = 64 * 1024;
+
+// sys/attr.h / sys/vnode.h / sys/stat.h 中 libc 未导出的常量
+const ATTR_CMN_ERROR: libc::attrgroup_t = 0x2000_0000;
+const VDIR: u32 = 2;
+const VLNK: u32 = 5;
+/// 内容不在本地（按需下载的占位文件 / 目录）
+const SF_DATALESS: u32 = 0x4000_0000;
+
+/// 遍历目录（getattrlistbulk）
+pub fn read_dir_entries(dir_path: &Path) -> io::Result<Vec<FastDirEntry>> {
+    let dir = std::fs::File::open(dir_path)?;
+
+    let mut attrs = libc::attrlist {
+        bitmapcount: libc::ATTR_BIT_MAP_COUNT,
+        reserved: 0,
+        commonattr: libc::ATTR_CMN_RETURNED_ATTRS
+            | ATTR_CMN_ERROR
+            | libc::ATTR_CMN_NAME
+            | libc::ATTR_CMN_DEVID
+            | libc::ATTR_CMN_OBJTYPE
+            | libc::ATTR_CMN_CRTIME
+            | libc::ATTR_CMN_MODTIME
+            | libc::ATTR_CMN_FLAGS,
+        volattr: 0,
+        dirattr: libc::ATTR_DIR_MOUNTSTATUS,
+        fileattr: libc::ATTR_FILE_ALLOCSIZE | libc::ATTR_FILE_DATALENGTH,
+        // 带 FSOPT_ATTR_CMN_EXTENDED 时 forkattr 表示扩展通用属性
+        forkattr: libc::ATTR_CMNEXT_PRIVATESIZE | libc::ATTR_CMNEXT_CLONEID,
+    };
+
+    let mut entries = Vec::with_capacity(128);
+    let mut buf = vec![0u8; ATTR_BUF_SIZE];
+
+    loop {
+        let n = unsafe {
+            libc::getattrlistbulk(
+                dir.as_raw_fd(),
+                &mut attrs as *mut libc::attrlist as *mut libc::c_void,
+                buf.as_mut_ptr() as *mut libc::c_void,
+                buf.len(),
+                libc::FSOPT_ATTR_CMN_EXTENDED as u64,
+            )
+        };
+        if n < 0 {
+            let err = io::Error::last_os_error();
+            // 不支持批量属性的文件系统（部分第三方 FUSE 实现）回退到标准库实现
+            if entries.is_empty() && matches!(err.raw_os_error(), Some(libc::ENOTSUP) | Some(libc::EINVAL)) {
+                return super::fallback_walker::read_dir_entries(dir_path);
+            }
+            return Err(err);
+        }
+        if n == 0 {
+            break;
+        }
+
+        let mut offset = 0;
+        for _ in 0..n {
+            // 每条记录以 u32 记录长度开头
+            let Some(len) = read_u32(&buf, offset) else {
+                break;
+            };
+            let len = len as usize;
+            if len == 0 || offset + len > buf.len() {
+                break;
+            }
+            if let Some(entry) = parse_entry(dir_path, &buf[offset..offset + len]) {
+                entries.push(entry);
+            }
+            offset += len;
+        }
+    }
+
+    Ok(entries)
+}
+
+/// 解析一条记录。属性按请求分组（通用、目录、文件、扩展通用）与位序依次排列，
+/// 只有 ATTR_CMN_RETURNED_ATTRS 中置位的属性才实际存在
+fn parse_entry(dir_path: &Path, record: &[u8]) -> Option<FastDirEntry> {
+    let mut c = Cursor { buf: record, pos: 4 };
+    let common = c.u32()?;
+    let _vol = c.u32()?;
+    let dir_attrs = c.u32()?;
+    let file_attrs = c.u32()?;
+    let ext_attrs = c.u32()?;
+
+    if common & ATTR_CMN_ERROR != 0 && c.u32()? != 0 {
+        // 该条目的属性读取失败（遍历期间被删除等）
+        return None;
+    }
+
+    // 名称：attrreference_t，数据偏移相对该字段自身
+    if common & libc::ATTR_CMN_NAME == 0 {
+        return None;
+    }
+    let name_at = c.pos;
+    let name_offset = c.i32()?;
+    let name_len = c.u32()? as usize;
+    let name_start = usize::try_from(name_at as i64 + name_offset as i64).ok()?;
+    let name = CStr::from_bytes_until_nul(record.get(name_start..name_start + name_len)?).ok()?;
+
+    let dev = if common & libc::ATTR_CMN_DEVID != 0 { c.i32()? as u32 as u64 } else { 0 };
+    let obj_type = if common & libc::ATTR_CMN_OBJTYPE != 0 { c.u32()? } else { 0 };
+    let created = if common & libc::ATTR_CMN_CRTIME != 0 { c.timespec_sec()? } else { 0 };
+    let modified = if common & libc::ATTR_CMN_MODTIME != 0 { c.timespec_sec()? } else { 0 };
+    let flags = if common & libc::ATTR_CMN_FLAGS != 0 { c.u32()? } else { 0 };
+    let mount_status = if dir_attrs & libc::ATTR_DIR_MOUNTSTATUS != 0 { c.u32()? } else { 0 };
+    let alloc_size = if file_attrs & libc::ATTR_FILE_ALLOCSIZE != 0 { Some(c.u64()?) } else { None };
+    let data_length = if file_attrs & libc::ATTR_FILE_DATALENGTH != 0 { c.u64()? } else { 0 };
+    let private_size = if ext_attrs & libc::ATTR_CMNEXT_PRIVATESIZE != 0 { Some(c.u64()?) } else { None };
+    let clone_id = if ext_attrs & libc::ATTR_CMNEXT_CLONEID != 0 { c.u64()? } else { 0 };
+
+    let is_dir = obj_type == VDIR;
+    let is_symlink = obj_type == VLNK;
+    let dataless = flags & SF_DATALESS != 0;
+
+    let kind = if is_symlink {
+        ItemKind::Symlink
+    } else if dataless {
+        ItemKind::CloudPlaceholder
+    } else if is_dir && mount_status & libc::DIR_MNTSTATUS_MNTPOINT != 0 {
+        ItemKind::MountPoint
+    } else {
+        ItemKind::regular(is_dir)
+    };
+
+    // 符号链接 / 占位项按 0 字节计；与其他文件共享数据块的克隆只计私有部分；稀疏文件按分配大小计
+    let mut size = 0;
+    let mut shared = None;
+    let mut sparse = None;
+    if !is_dir && !is_symlink && !dataless {
+        size = data_length;
+        if let (Some(alloc), Some(private)) = (alloc_size, private_size) {
+            if clone_id != 0 && private < alloc {
+                size = private;
+                shared = Some(SharedExtent { volume: dev, clone_id, bytes: alloc - private });
+            }
+        }
+        if shared.is_none() {
+            sparse = alloc_size.and_then(|alloc| super::SparseFile::from_sizes(data_length, alloc));
+            size = sparse.map_or(size, |s| s.allocated);
+        }
+    }
+
+    Some(FastDirEntry {
+        path: dir_path.join(OsStr::from_bytes(name.to_bytes())),
+        name: name.to_string_lossy().into_owned(),
+        size,
+        is_dir,
+        kind,
+        modified,
+        created,
+        shared,
+        cloud: (dataless && !is_dir).then_some(CloudFile::OnlineOnly(data_length)),
+        sparse,
+    })
+}
+
+fn read_u32(buf: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_ne_bytes(buf.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+/// 记录内的顺序读取（属性按 4 字节对齐，8 字节值可能未按 8 对齐，逐字节读取）
+struct Cursor<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl Cursor<'_> {
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let bytes = self.buf.get(self.pos..self.pos + N)?.try_into().ok()?;
+        self.pos += N;
+        Some(bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take().map(u32::from_ne_bytes)
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        self.take().map(i32::from_ne_bytes)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take().map(u64::from_ne_bytes)
+    }
+
+    /// struct timespec（64 位：i64 秒 + i64 纳秒），只取秒
+    fn timespec_sec(&mut self) -> Option<i64> {
+        let sec = self.take().map(i64::from_ne_bytes)?;
+        self.take::<8>()?;
+        Some(sec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_std_walker() {
+        let root = std::env::temp_dir().join(format!("flashdir-macos-walker-{}", std::process::id()));
+        std::fs::remove_dir_all(&root).ok();
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(root.join("a.bin"), vec![0u8; 1234]).unwrap();
+        std::fs::write(root.join("名字.txt"), b"hi").unwrap();
+        std::os::unix::fs::symlink(root.join("a.bin"), root.join("link")).unwrap();
+
+        let key = |e: &FastDirEntry| (e.name.clone(), e.size, e.is_dir, e.kind as u8, e.modified, e.path.clone());
+        let mut fast: Vec<_> = read_dir_entries(&root).unwrap().iter().map(key).collect();
+        let mut std_walk: Vec<_> = super::super::fallback_walker::read_dir_entries(&root)
+            .unwrap()
+            .iter()
+            .map(key)
+            .collect();
+        fast.sort();
+        std_walk.sort();
+        assert_eq!(fast, std_walk);
+        assert_eq!(fast.len(), 4);
+
+        assert!(read_dir_entries(&root.join("missing")).is_err());
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
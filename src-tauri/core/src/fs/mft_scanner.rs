@@ -102,6 +102,11 @@ const FN_NAME_LENGTH: usize = 0x40;     // 文件名长度 (u8)
 const FN_NAME_NAMESPACE: usize = 0x41;  // 文件名命名空间 (u8)
 const FN_NAME_START: usize = 0x42;      // 文件名开始 (UTF-16LE)
 
+/// $STANDARD_INFORMATION 属性内的内容偏移
+/// （时间以此为准：$FILE_NAME 中的时间只在创建/重命名时更新）
+const SI_CREATION_TIME: usize = 0x00;
+const SI_MODIFY_TIME: usize = 0x08;
+
 /// NTFS 文件属性标志（与 Win32 FILE_ATTRIBUTE_* 一致）
 const FILE_ATTRIBUTE_DIRECTORY: u32 = 0x10;
 const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x400;
@@ -118,8 +123,14 @@ struct MftEntry {
     real_size: u64,
     /// 是否为目录
     is_dir: bool,
-    /// 是否为重解析点（符号链接等）
-    is_reparse: bool,
+    /// 文件属性（$FILE_NAME 中的副本）
+    attributes: u32,
+    /// 重解析标记（非重解析点为 0）
+    reparse_tag: u32,
+    /// 最后修改时间（Unix 秒，0 表示未知）
+    modified: i64,
+    /// 创建时间（Unix 秒，0 表示未知）
+    created: i64,
 }
 
 /// FRN → MftEntry 的索引（FRN 去掉序列号的高位作为 key）
@@ -154,6 +165,11 @@ pub struct MftFileInfo {
     pub name: String,
     pub size: u64,
     pub is_dir: bool,
+    pub modified: i64,
+    pub created: i64,
+    /// 文件属性与重解析标记，用于 ItemKind 分类
+    pub attributes: u32,
+    pub reparse_tag: u32,
 }
 
 /// 单条 MFT 记录解析结果（用于 FRN → 路径解析）
@@ -163,6 +179,8 @@ pub struct MftRecordInfo {
     pub parent_frn: u64,
     pub is_dir: bool,
     pub real_size: u64,
+    pub modified: i64,
+    pub created: i64,
 }
 
 impl MftScanner {
@@ -495,6 +513,10 @@ impl MftScanner {
                 name: entry.name.clone(),
                 size: entry.real_size,
                 is_dir: entry.is_dir,
+                modified: entry.modified,
+                created: entry.created,
+                attributes: entry.attributes,
+                reparse_tag: entry.reparse_tag,
             });
 
             // 只有目录才递归处理子节点，避免循环/栈溢出
@@ -639,6 +661,7 @@ fn parse_mft_record_info(data: &[u8], frn: u64) -> Option<MftRecordInfo> {
 
     let mut attr_offset = first_attr_offset;
     let mut best_info: Option<MftRecordInfo> = None;
+    let mut si_times: Option<(i64, i64)> = None;
     loop {
         if attr_offset + 16 > data.len() {
             break;
@@ -656,7 +679,9 @@ fn parse_mft_record_info(data: &[u8], frn: u64) -> Option<MftRecordInfo> {
 
         let non_resident = data[attr_offset + 8];
 
-        if attr_type == ATTR_FILE_NAME && non_resident == 0 {
+        if attr_type == ATTR_STANDARD_INFORMATION && non_resident == 0 {
+            si_times = parse_standard_times(data, attr_offset);
+        } else if attr_type == ATTR_FILE_NAME && non_resident == 0 {
             let content_size =
                 u32_from_le(&data[attr_offset + 0x10..attr_offset + 0x14]) as usize;
             let content_offset =
@@ -692,13 +717,17 @@ fn parse_mft_record_info(data: &[u8], frn: u64) -> Option<MftRecordInfo> {
             let name = read_utf16le(&fn_data[name_start..name_end]);
             let name_type = fn_data[FN_NAME_NAMESPACE];
 
+            let (created, modified) = si_times.unwrap_or_else(|| file_name_times(fn_data));
             let info = MftRecordInfo {
                 name,
                 parent_frn,
                 is_dir,
                 real_size,
+                modified,
+                created,
             };
 
+            // $STANDARD_INFORMATION (0x10) 总排在 $FILE_NAME (0x30) 之前，此时时间已确定
             if name_type == 1 || name_type == 3 {
                 return Some(info);
             }
@@ -720,11 +749,14 @@ fn parse_mft_record_info(data: &[u8], frn: u64) -> Option<MftRecordInfo> {
 
     // 有记录但未找到 $FILE_NAME —— 返回一个占位名称
     if flags & MFT_RECORD_IN_USE != 0 {
+        let (created, modified) = si_times.unwrap_or_default();
         return Some(MftRecordInfo {
             name: format!("<record_{}>", record_index),
             parent_frn: 5,
             is_dir,
             real_size: 0,
+            modified,
+            created,
         });
     }
 
@@ -762,6 +794,7 @@ fn parse_mft_record(data: &[u8], record_index: usize) -> Option<MftEntry> {
     let mut attr_offset = first_attr_offset;
     let mut best_entry: Option<MftEntry> = None;
     let mut data_size: u64 = 0;
+    let mut si_times: Option<(i64, i64)> = None;
 
     loop {
         if attr_offset + 16 > data.len() {
@@ -781,7 +814,9 @@ fn parse_mft_record(data: &[u8], record_index: usize) -> Option<MftEntry> {
         let non_resident = data[attr_offset + 8];
         let attr_name_len = data[attr_offset + 9] as usize;
 
-        if attr_type == ATTR_FILE_NAME && non_resident == 0 {
+        if attr_type == ATTR_STANDARD_INFORMATION && non_resident == 0 {
+            si_times = parse_standard_times(data, attr_offset);
+        } else if attr_type == ATTR_FILE_NAME && non_resident == 0 {
             // 解析 resident $FILE_NAME 属性
             let content_size = u32_from_le(&data[attr_offset + 0x10..attr_offset + 0x14]) as usize;
             let content_offset = u16_from_le(&data[attr_offset + 0x14..attr_offset + 0x16]) as usize;
@@ -800,7 +835,12 @@ fn parse_mft_record(data: &[u8], record_index: usize) -> Option<MftEntry> {
 
                 // 提取文件属性标志
                 let file_attrs = u32_from_le(&fn_data[FN_FLAGS..FN_FLAGS + 4]);
-                let is_reparse = (file_attrs & FILE_ATTRIBUTE_REPARSE_POINT) != 0;
+                // 重解析点的 $FILE_NAME 在 0x3C 处记录重解析标记（否则为扩展属性大小）
+                let reparse_tag = if (file_attrs & FILE_ATTRIBUTE_REPARSE_POINT) != 0 {
+                    u32_from_le(&fn_data[FN_REPARSE..FN_REPARSE + 4])
+                } else {
+                    0
+                };
 
                 // 提取文件名
                 let name_len = fn_data[FN_NAME_LENGTH] as usize;
@@ -811,12 +851,16 @@ fn parse_mft_record(data: &[u8], record_index: usize) -> Option<MftEntry> {
                     let name = read_utf16le(&fn_data[name_start..name_end]);
                     let name_type = fn_data[FN_NAME_NAMESPACE];
 
+                    let (created, modified) = file_name_times(fn_data);
                     let entry = MftEntry {
                         name,
                         parent_frn,
                         real_size,
                         is_dir,
-                        is_reparse,
+                        attributes: file_attrs,
+                        reparse_tag,
+                        modified,
+                        created,
                     };
 
                     // 1 = Win32, 3 = Win32 + DOS；这两个都是长名，优先使用
@@ -852,17 +896,25 @@ fn parse_mft_record(data: &[u8], record_index: usize) -> Option<MftEntry> {
         if data_size > entry.real_size {
             entry.real_size = data_size;
         }
+        if let Some((created, modified)) = si_times {
+            entry.created = created;
+            entry.modified = modified;
+        }
         return Some(entry);
     }
 
     // $FILE_NAME 未找到但有记录（可能是系统文件/元数据文件），返回最小信息
     if flags & MFT_RECORD_IN_USE != 0 {
+        let (created, modified) = si_times.unwrap_or_default();
         return Some(MftEntry {
             name: format!("<record_{}>", record_index),
             parent_frn: 5, // 挂到根目录
             real_size: data_size,
             is_dir,
-            is_reparse: false,
+            attributes: 0,
+            reparse_tag: 0,
+            modified,
+            created,
         });
     }
 
@@ -871,6 +923,32 @@ fn parse_mft_record(data: &[u8], record_index: usize) -> Option<MftEntry> {
 
 // ─── 辅助函数 ──────────────────────────────────────────────
 
+/// 读取 resident $STANDARD_INFORMATION 中的 (创建时间, 修改时间)，Unix 秒
+fn parse_standard_times(data: &[u8], attr_offset: usize) -> Option<(i64, i64)> {
+    if attr_offset + 0x16 > data.len() {
+        return None;
+    }
+    let content_size = u32_from_le(&data[attr_offset + 0x10..attr_offset + 0x14]) as usize;
+    let content_offset = u16_from_le(&data[attr_offset + 0x14..attr_offset + 0x16]) as usize;
+    let start = attr_offset + content_offset;
+    if content_size < SI_MODIFY_TIME + 8 || start + SI_MODIFY_TIME + 8 > data.len() {
+        return None;
+    }
+    let si = &data[start..];
+    Some((
+        super::filetime_to_unix(u64_from_le(&si[SI_CREATION_TIME..SI_CREATION_TIME + 8]) as i64),
+        super::filetime_to_unix(u64_from_le(&si[SI_MODIFY_TIME..SI_MODIFY_TIME + 8]) as i64),
+    ))
+}
+
+/// $FILE_NAME 中的 (创建时间, 修改时间)，仅在缺少 $STANDARD_INFORMATION 时作为回退
+fn file_name_times(fn_data: &[u8]) -> (i64, i64) {
+    (
+        super::filetime_to_unix(u64_from_le(&fn_data[FN_CREATION_TIME..FN_CREATION_TIME + 8]) as i64),
+        super::filetime_to_unix(u64_from_le(&fn_data[FN_MODIFY_TIME..FN_MODIFY_TIME + 8]) as i64),
+    )
+}
+
 #[inline]
 fn u16_from_le(data: &[u8]) -> u16 {
     u16::from_le_bytes([data[0], data[1]])
@@ -1148,13 +1226,8 @@ pub fn try_mft_scan(root_path: &str) -> Option<MftScanResult> {
 /// 从路径中提取盘符，如 "C:\\Users" -> 'C'，支持 canonicalize 产生的 \\?\ 前缀。
 pub(crate) fn extract_drive_letter(path: &str) -> Option<char> {
     let path = path.trim();
-    let normalized = path.replace('\\', "/");
-    // 处理 canonicalize 产生的 \\?\C:\ 前缀（标准化后为 //?/C:/）
-    let trimmed = if normalized.starts_with("//?/") {
-        &normalized[4..]
-    } else {
-        normalized.as_str()
-    };
+    let normalized = crate::fs::display_path(path);
+    let trimmed = normalized.as_str();
 
     if trimmed.len() >= 2 {
         let bytes = trimmed.as_bytes();
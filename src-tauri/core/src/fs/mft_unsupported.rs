@@ -0,0 +1,47 @@
+// 非 Windows 平台没有 NTFS $MFT：提供与 mft_scanner 同名的接口，
+// 可用性检查恒为 false、扫描恒为 None，调用方据此走目录遍历
+
+/// MFT 扫描结果（与 Windows 版结构一致）
+pub struct MftScanResult {
+    pub files: Vec<MftFileInfo>,
+    pub file_count: usize,
+    pub dir_count: usize,
+    pub data_read: u64,
+}
+
+/// 单个文件的 MFT 信息（与 Windows 版结构一致）
+#[derive(Debug, Clone)]
+pub struct MftFileInfo {
+    pub path: String,
+    pub name: String,
+    pub size: u64,
+    pub is_dir: bool,
+    pub modified: i64,
+    pub created: i64,
+    pub attributes: u32,
+    pub reparse_tag: u32,
+}
+
+/// 是否以 root 运行（有效用户 ID 为 0）
+#[cfg(unix)]
+pub fn is_admin() -> bool {
+    unsafe { libc::geteuid() == 0 }
+}
+
+#[cfg(not(unix))]
+pub fn is_admin() -> bool {
+    false
+}
+
+pub fn check_mft_available(_path: &str) -> bool {
+    false
+}
+
+/// 没有 UAC 式的自我提权，需要时请用 sudo 启动
+pub fn restart_as_admin() -> bool {
+    false
+}
+
+pub fn try_mft_scan(_root_path: &str) -> Option<MftScanResult> {
+    None
+}
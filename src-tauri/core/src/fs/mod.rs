@@ -0,0 +1,110 @@
+// 文件系统操作模块
+// 提供平台特定的快速目录遍历能力：各平台导出同名的 FastDirEntry / read_dir_entries，
+// 扫描引擎只依赖这组接口
+//   - Windows：FindFirstFileExW（另有 MFT 直读 / USN 增量）
+//   - Linux：getdents64 + statx
+//   - macOS：getattrlistbulk（APFS 克隆共享块按组去重计入）
+//   - 其他平台：std::fs::read_dir
+// 标准库实现在所有平台以 read_dir_entries_std 导出，供 std 扫描后端对比 / 回退
+
+#[cfg(target_os = "windows")]
+mod windows_walker;
+#[cfg(target_os = "windows")]
+pub use windows_walker::*;
+
+#[cfg(target_os = "windows")]
+mod mft_scanner;
+#[cfg(target_os = "windows")]
+pub use mft_scanner::*;
+
+#[cfg(target_os = "windows")]
+mod usn_journal;
+#[cfg(target_os = "windows")]
+pub use usn_journal::*;
+
+#[cfg(not(target_os = "windows"))]
+mod mft_unsupported;
+#[cfg(not(target_os = "windows"))]
+pub use mft_unsupported::*;
+
+mod fallback_walker;
+pub use fallback_walker::read_dir_entries as read_dir_entries_std;
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+pub use fallback_walker::*;
+
+#[cfg(target_os = "linux")]
+mod linux_walker;
+#[cfg(target_os = "linux")]
+pub use linux_walker::*;
+
+#[cfg(target_os = "macos")]
+mod macos_walker;
+#[cfg(target_os = "macos")]
+pub use macos_walker::*;
+
+mod shared_extent;
+pub use shared_extent::*;
+
+mod volumes;
+pub use volumes::*;
+
+mod drive_type;
+pub use drive_type::*;
+
+mod last_access;
+pub use last_access::*;
+
+mod appearance;
+pub use appearance::*;
+
+mod idle;
+pub use idle::*;
+
+mod watch;
+pub use watch::*;
+
+mod owner;
+pub use owner::*;
+
+mod long_path;
+pub use long_path::*;
+
+mod reparse;
+pub use reparse::*;
+
+mod cloud_files;
+pub use cloud_files::*;
+
+mod sparse_files;
+pub use sparse_files::*;
+
+mod privilege;
+pub use privilege::*;
+
+mod priority;
+pub use priority::*;
+
+mod trash;
+pub use trash::*;
+
+mod shell_open;
+pub use shell_open::*;
+
+mod file_details;
+pub use file_details::*;
+
+mod pdf_print;
+pub use pdf_print::*;
+
+mod clipboard;
+pub use clipboard::*;
+
+/// Windows FILETIME（自 1601-01-01 起的 100 纳秒间隔数）转 Unix 秒；0 / 无效值返回 0（未知）
+pub fn filetime_to_unix(ft: i64) -> i64 {
+    // 与 Unix 时间戳（1970-01-01）的差值为 11644473600 秒
+    const EPOCH_DIFF_100NS: i64 = 116_444_736_000_000_000;
+    if ft <= EPOCH_DIFF_100NS {
+        return 0;
+    }
+    (ft - EPOCH_DIFF_100NS) / 10_000_000
+}
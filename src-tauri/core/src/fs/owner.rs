@@ -0,0 +1,155 @@
+// 文件所有者解析
+//
+// Windows: GetNamedSecurityInfoW 取所有者 SID → LookupAccountSidW 解析为 DOMAIN\name
+// Unix:    metadata uid → /etc/passwd 中的用户名（找不到时回退为 uid 数字）
+//
+// 每个文件都要一次安全描述符查询，较慢，仅在显式请求时使用；
+// 同一 SID / uid 的账户名只解析一次。
+
+use std::collections::HashMap;
+
+/// 带缓存的所有者解析器（单线程使用；并行时每个线程各建一个）
+pub struct OwnerResolver {
+    #[cfg(target_os = "windows")]
+    names: HashMap<Vec<u8>, String>,
+    #[cfg(not(target_os = "windows"))]
+    names: HashMap<u32, String>,
+}
+
+impl Default for OwnerResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl OwnerResolver {
+    pub fn new() -> Self {
+        Self { names: HashMap::new() }
+    }
+
+    /// 解析路径的所有者账户名；无权限读取安全描述符时返回 None
+    pub fn owner_of(&mut self, path: &str) -> Option<String> {
+        use windows_sys::Win32::Foundation::LocalFree;
+        use windows_sys::Win32::Security::Authorization::{GetNamedSecurityInfoW, SE_FILE_OBJECT};
+        use windows_sys::Win32::Security::{GetLengthSid, OWNER_SECURITY_INFORMATION};
+
+        let wide: Vec<u16> = path
+            .replace('/', "\\")
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+
+        let mut owner_sid: *mut std::ffi::c_void = std::ptr::null_mut();
+        let mut descriptor: *mut std::ffi::c_void = std::ptr::null_mut();
+        let status = unsafe {
+            GetNamedSecurityInfoW(
+                wide.as_ptr(),
+                SE_FILE_OBJECT,
+                OWNER_SECURITY_INFORMATION,
+                &mut owner_sid as *mut _ as *mut _,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                &mut descriptor as *mut _ as *mut _,
+            )
+        };
+        if status != 0 || owner_sid.is_null() {
+            return None;
+        }
+
+        // SID 原始字节作为缓存 key
+        let sid_len = unsafe { GetLengthSid(owner_sid as _) } as usize;
+        let key = unsafe { std::slice::from_raw_parts(owner_sid as *const u8, sid_len) }.to_vec();
+
+        let name = match self.names.get(&key) {
+            Some(name) => Some(name.clone()),
+            None => {
+                let resolved = lookup_account_name(owner_sid);
+                if let Some(name) = &resolved {
+                    self.names.insert(key, name.clone());
+                }
+                resolved
+            }
+        };
+
+        unsafe {
+            LocalFree(descriptor as _);
+        }
+        name
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn lookup_account_name(sid: *mut std::ffi::c_void) -> Option<String> {
+    use windows_sys::Win32::Security::LookupAccountSidW;
+
+    let mut name = [0u16; 256];
+    let mut domain = [0u16; 256];
+    let mut name_len = name.len() as u32;
+    let mut domain_len = domain.len() as u32;
+    let mut sid_use = 0;
+    let ok = unsafe {
+        LookupAccountSidW(
+            std::ptr::null(),
+            sid as _,
+            name.as_mut_ptr(),
+            &mut name_len,
+            domain.as_mut_ptr(),
+            &mut domain_len,
+            &mut sid_use,
+        )
+    } != 0;
+    if !ok {
+        return None;
+    }
+
+    let name = String::from_utf16_lossy(&name[..name_len as usize]);
+    let domain = String::from_utf16_lossy(&domain[..domain_len as usize]);
+    Some(if domain.is_empty() { name } else { format!("{}\\{}", domain, name) })
+}
+
+#[cfg(not(target_os = "windows"))]
+impl OwnerResolver {
+    pub fn new() -> Self {
+        Self { names: load_passwd() }
+    }
+
+    /// 解析路径的所有者用户名；/etc/passwd 中没有对应条目时返回 uid 数字
+    pub fn owner_of(&mut self, path: &str) -> Option<String> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            let uid = std::fs::symlink_metadata(path).ok()?.uid();
+            Some(
+                self.names
+                    .entry(uid)
+                    .or_insert_with(|| uid.to_string())
+                    .clone(),
+            )
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = path;
+            None
+        }
+    }
+}
+
+/// 读取 /etc/passwd：name:x:uid:gid:...
+#[cfg(not(target_os = "windows"))]
+fn load_passwd() -> HashMap<u32, String> {
+    std::fs::read_to_string("/etc/passwd")
+        .map(|content| {
+            content
+                .lines()
+                .filter_map(|line| {
+                    let mut fields = line.split(':');
+                    let name = fields.next()?;
+                    let uid = fields.nth(1)?.parse().ok()?;
+                    Some((uid, name.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
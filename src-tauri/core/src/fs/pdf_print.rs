@@ -0,0 +1,124 @@
+// 把 HTML 打印为 PDF：调用本机已安装的 Chromium 内核浏览器（Edge / Chrome / Chromium）的无头模式
+//
+// Windows: Program Files 与 %LOCALAPPDATA% 下的 Edge / Chrome（Windows 10 起系统自带 Edge）
+// macOS: /Applications 下的 Chrome / Edge / Chromium
+// Linux: PATH 中的 google-chrome / chromium / microsoft-edge
+// 使用临时的用户数据目录，不与正在运行的浏览器实例共享配置；超时后结束子进程
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 浏览器打印的最长等待时间
+const PRINT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// 用无头浏览器把本地 HTML 文件 html 打印为 pdf；找不到浏览器、超时或未生成文件时返回错误
+pub fn print_html_to_pdf(html: &Path, pdf: &Path) -> anyhow::Result<()> {
+    let browser = find_pdf_browser()
+        .ok_or_else(|| anyhow::anyhow!("未找到可用于生成 PDF 的浏览器（Edge / Chrome / Chromium）"))?;
+    let profile = std::env::temp_dir().join(format!("flashdir_pdf_{}", uuid::Uuid::new_v4()));
+
+    let result = run_browser(&browser, html, pdf, &profile);
+    std::fs::remove_dir_all(&profile).ok();
+    result?;
+
+    if !std::fs::metadata(pdf).is_ok_and(|m| m.len() > 0) {
+        anyhow::bail!("浏览器未生成 PDF 文件");
+    }
+    Ok(())
+}
+
+fn run_browser(browser: &Path, html: &Path, pdf: &Path, profile: &Path) -> anyhow::Result<()> {
+    let mut child = std::process::Command::new(browser)
+        .args([
+            "--headless",
+            "--disable-gpu",
+            "--no-first-run",
+            "--no-default-browser-check",
+            "--no-pdf-header-footer",
+        ])
+        .arg(format!("--user-data-dir={}", profile.display()))
+        .arg(format!("--print-to-pdf={}", pdf.display()))
+        .arg(file_url(html))
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("无法启动 {}: {}", browser.display(), e))?;
+
+    let started = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            if !status.success() {
+                anyhow::bail!("浏览器打印失败（{}）", status);
+            }
+            return Ok(());
+        }
+        if started.elapsed() >= PRINT_TIMEOUT {
+            child.kill().ok();
+            child.wait().ok();
+            anyhow::bail!("浏览器打印超时");
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// 本地文件的 file:// URL（Windows 盘符路径写作 file:///C:/...）
+fn file_url(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let encoded: String = path
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' | b':' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+    if encoded.starts_with('/') {
+        format!("file://{}", encoded)
+    } else {
+        format!("file:///{}", encoded)
+    }
+}
+
+/// 查找可用于打印 PDF 的浏览器
+#[cfg(target_os = "windows")]
+pub fn find_pdf_browser() -> Option<PathBuf> {
+    let roots = ["ProgramFiles(x86)", "ProgramFiles", "LOCALAPPDATA"]
+        .iter()
+        .filter_map(|var| std::env::var_os(var).map(PathBuf::from));
+    let relative = [r"Microsoft\Edge\Application\msedge.exe", r"Google\Chrome\Application\chrome.exe"];
+    roots
+        .flat_map(|root| relative.iter().map(move |rel| root.join(rel)))
+        .find(|p| p.is_file())
+}
+
+#[cfg(target_os = "macos")]
+pub fn find_pdf_browser() -> Option<PathBuf> {
+    [
+        "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+        "/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge",
+        "/Applications/Chromium.app/Contents/MacOS/Chromium",
+    ]
+    .iter()
+    .map(PathBuf::from)
+    .find(|p| p.is_file())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn find_pdf_browser() -> Option<PathBuf> {
+    let names = ["google-chrome", "google-chrome-stable", "chromium", "chromium-browser", "microsoft-edge"];
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|p| p.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_file_urls() {
+        assert_eq!(file_url(Path::new("/tmp/a b/报告.html")), "file:///tmp/a%20b/%E6%8A%A5%E5%91%8A.html");
+        assert_eq!(file_url(Path::new(r"C:\Temp\r.html")), "file:///C:/Temp/r.html");
+    }
+}
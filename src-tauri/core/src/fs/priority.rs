@@ -0,0 +1,73 @@
+// 后台扫描的线程优先级
+//
+// 定时 / 后台扫描不应让机器变卡：遍历线程切换为低 CPU 优先级与低 I/O 优先级，
+// 磁盘调度器优先服务前台程序的请求。
+//   - Windows：THREAD_MODE_BACKGROUND_BEGIN（同时降低 CPU、I/O 与内存页优先级）
+//   - Linux：ioprio_set 设为 idle 类（ionice -c3）+ nice 19（Linux 上 nice 按线程生效）
+//   - macOS：QOS_CLASS_BACKGROUND（内核对该 QoS 的线程同时做 CPU 与磁盘 I/O 节流）
+
+/// 把当前线程切换为后台优先级，返回是否成功（失败时照常以原优先级扫描）
+///
+/// 非特权进程无法把 nice / I/O 类别改回去，只应在用完即销毁的专用工作线程上调用
+pub fn enter_background_priority() -> bool {
+    imp::enter()
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use windows_sys::Win32::System::Threading::{GetCurrentThread, SetThreadPriority, THREAD_MODE_BACKGROUND_BEGIN};
+
+    pub fn enter() -> bool {
+        unsafe { SetThreadPriority(GetCurrentThread(), THREAD_MODE_BACKGROUND_BEGIN) != 0 }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    // linux/ioprio.h
+    const IOPRIO_CLASS_IDLE: libc::c_int = 3;
+    const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+    const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+
+    pub fn enter() -> bool {
+        // who = 0 表示调用线程本身
+        let io = unsafe {
+            libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT)
+        } == 0;
+        let cpu = unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, 19) } == 0;
+        io && cpu
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    pub fn enter() -> bool {
+        unsafe { libc::pthread_set_qos_class_self_np(libc::qos_class_t::QOS_CLASS_BACKGROUND, 0) == 0 }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+mod imp {
+    pub fn enter() -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn lowers_only_the_calling_thread() {
+        let before = unsafe { libc::getpriority(libc::PRIO_PROCESS, 0) };
+        let nice = std::thread::spawn(|| {
+            assert!(enter_background_priority());
+            unsafe { libc::getpriority(libc::PRIO_PROCESS, 0) }
+        })
+        .join()
+        .unwrap();
+        assert_eq!(nice, 19);
+        assert_eq!(unsafe { libc::getpriority(libc::PRIO_PROCESS, 0) }, before);
+    }
+}
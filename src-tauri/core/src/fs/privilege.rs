@@ -0,0 +1,96 @@
+// 权限相关：备份特权、以管理员身份运行子进程
+//
+// 拒绝访问的目录有两种补扫方式：
+//   - 当前进程已提权：启用 SeBackupPrivilege。FindFirstFileExW 以备份意图打开目录，
+//     启用该特权后可绕过目录 ACL 读取条目
+//   - 未提权：以 runas 启动自身的助手模式（UAC 确认），等待其写出结果
+
+use std::io;
+
+/// 为当前进程启用 SeBackupPrivilege；进程令牌中没有该特权（未提权）时返回 false
+#[cfg(target_os = "windows")]
+pub fn enable_backup_privilege() -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, GetLastError, LUID};
+    use windows_sys::Win32::Security::{
+        AdjustTokenPrivileges, LookupPrivilegeValueW, LUID_AND_ATTRIBUTES, SE_PRIVILEGE_ENABLED,
+        TOKEN_ADJUST_PRIVILEGES, TOKEN_PRIVILEGES, TOKEN_QUERY,
+    };
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+    // AdjustTokenPrivileges 成功但未能分配全部特权
+    const ERROR_NOT_ALL_ASSIGNED: u32 = 1300;
+
+    let name: Vec<u16> = "SeBackupPrivilege".encode_utf16().chain(std::iter::once(0)).collect();
+    unsafe {
+        let mut token = 0;
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_ADJUST_PRIVILEGES | TOKEN_QUERY, &mut token) == 0 {
+            return false;
+        }
+        let mut luid: LUID = std::mem::zeroed();
+        if LookupPrivilegeValueW(std::ptr::null(), name.as_ptr(), &mut luid) == 0 {
+            CloseHandle(token);
+            return false;
+        }
+        let privileges = TOKEN_PRIVILEGES {
+            PrivilegeCount: 1,
+            Privileges: [LUID_AND_ATTRIBUTES { Luid: luid, Attributes: SE_PRIVILEGE_ENABLED }],
+        };
+        let ok = AdjustTokenPrivileges(token, 0, &privileges, 0, std::ptr::null_mut(), std::ptr::null_mut()) != 0
+            && GetLastError() != ERROR_NOT_ALL_ASSIGNED;
+        CloseHandle(token);
+        ok
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn enable_backup_privilege() -> bool {
+    false
+}
+
+/// 以管理员身份（UAC runas）运行当前程序并等待退出，返回退出码；
+/// 用户拒绝 UAC 时返回错误
+#[cfg(target_os = "windows")]
+pub fn run_self_elevated(args: &[String]) -> io::Result<u32> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{GetExitCodeProcess, WaitForSingleObject, INFINITE};
+    use windows_sys::Win32::UI::Shell::{ShellExecuteExW, SEE_MASK_NOCLOSEPROCESS, SHELLEXECUTEINFOW};
+    use windows_sys::Win32::UI::WindowsAndMessaging::SW_HIDE;
+
+    let wide = |s: &str| -> Vec<u16> { s.encode_utf16().chain(std::iter::once(0)).collect() };
+    let exe = std::env::current_exe()?;
+    let exe = wide(&exe.to_string_lossy());
+    let verb = wide("runas");
+    // 参数逐个加引号（路径中可能有空格）
+    let params = wide(
+        &args
+            .iter()
+            .map(|a| format!("\"{}\"", a))
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+
+    unsafe {
+        let mut info: SHELLEXECUTEINFOW = std::mem::zeroed();
+        info.cbSize = std::mem::size_of::<SHELLEXECUTEINFOW>() as u32;
+        info.fMask = SEE_MASK_NOCLOSEPROCESS;
+        info.lpVerb = verb.as_ptr();
+        info.lpFile = exe.as_ptr();
+        info.lpParameters = params.as_ptr();
+        info.nShow = SW_HIDE;
+        if ShellExecuteExW(&mut info) == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if info.hProcess == 0 {
+            return Err(io::Error::new(io::ErrorKind::Other, "未能获取助手进程句柄"));
+        }
+        WaitForSingleObject(info.hProcess, INFINITE);
+        let mut code = 0u32;
+        GetExitCodeProcess(info.hProcess, &mut code);
+        CloseHandle(info.hProcess);
+        Ok(code)
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn run_self_elevated(_args: &[String]) -> io::Result<u32> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "当前平台不支持以管理员身份启动助手"))
+}
@@ -0,0 +1,123 @@
+// 条目类型：重解析点 / 联接 / 挂载点 / 云占位符分类
+//
+// 链接类条目不跟随（避免重复计算与环），云占位符不读取（避免触发下载），
+// 但都作为 item 保留在结果中并标明类型，前端可据此解释为什么显示 0 字节。
+//
+// Windows 依据 Win32 文件属性 + 重解析标记（FindFirstFileExW 的 dwReserved0 /
+// MFT $FILE_NAME 的重解析值）分类；其他平台依据 lstat 的文件类型与设备号。
+
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+/// 条目类型（判别值即二进制协议中的编码，只能追加）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ItemKind {
+    #[default]
+    File = 0,
+    Dir = 1,
+    /// NTFS 目录联接（junction），不跟随
+    Junction = 2,
+    /// 符号链接（含 WSL 等其他名称代理类重解析点），不跟随
+    Symlink = 3,
+    /// 挂载点：Windows 卷挂载点（不跟随），Unix 其他文件系统的挂载目录
+    MountPoint = 4,
+    /// OneDrive 等云同步 / 分层存储的占位符，内容不在本地，按 0 字节计
+    CloudPlaceholder = 5,
+    /// 合成条目：目录下小于扫描选项 minFileSize 的文件合并为一项
+    SmallFiles = 6,
+}
+
+impl ItemKind {
+    #[inline]
+    pub fn regular(is_dir: bool) -> Self {
+        if is_dir { ItemKind::Dir } else { ItemKind::File }
+    }
+
+    /// 遍历时是否进入该目录。Unix 挂载点保持原有行为（进入），
+    /// Windows 卷挂载点与其余链接类条目一样不进入
+    #[inline]
+    pub fn descends(self) -> bool {
+        self == ItemKind::Dir || (self == ItemKind::MountPoint && cfg!(not(target_os = "windows")))
+    }
+}
+
+pub(super) const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x0000_0400;
+pub(super) const FILE_ATTRIBUTE_OFFLINE: u32 = 0x0000_1000;
+pub(super) const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x0004_0000;
+pub(super) const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x0040_0000;
+
+/// 目录联接与卷挂载点共用此标记
+const IO_REPARSE_TAG_MOUNT_POINT: u32 = 0xA000_0003;
+const IO_REPARSE_TAG_SYMLINK: u32 = 0xA000_000C;
+/// 名称代理位：重解析点指向另一个命名实体（链接类）
+const REPARSE_TAG_NAME_SURROGATE: u32 = 0x2000_0000;
+
+/// 按 Win32 文件属性与重解析标记分类；`is_volume_mount` 仅在标记为
+/// IO_REPARSE_TAG_MOUNT_POINT 时调用，用于区分卷挂载点与目录联接
+pub fn classify_windows(
+    attributes: u32,
+    reparse_tag: u32,
+    is_dir: bool,
+    is_volume_mount: impl FnOnce() -> bool,
+) -> ItemKind {
+    // 云文件已下载到本地后会清除 RECALL 属性，此时按普通条目统计
+    if attributes & (FILE_ATTRIBUTE_RECALL_ON_OPEN | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS | FILE_ATTRIBUTE_OFFLINE) != 0 {
+        return ItemKind::CloudPlaceholder;
+    }
+    if attributes & FILE_ATTRIBUTE_REPARSE_POINT == 0 {
+        return ItemKind::regular(is_dir);
+    }
+    match reparse_tag {
+        IO_REPARSE_TAG_MOUNT_POINT if is_volume_mount() => ItemKind::MountPoint,
+        IO_REPARSE_TAG_MOUNT_POINT => ItemKind::Junction,
+        IO_REPARSE_TAG_SYMLINK => ItemKind::Symlink,
+        tag if tag & REPARSE_TAG_NAME_SURROGATE != 0 => ItemKind::Symlink,
+        // 去重、已下载的云文件等：内容在本地，按普通条目处理
+        _ => ItemKind::regular(is_dir),
+    }
+}
+
+/// 目录是否为卷挂载点（GetVolumeNameForVolumeMountPointW 只对卷挂载点成功）
+#[cfg(target_os = "windows")]
+pub fn is_volume_mount_point(path: &std::path::Path) -> bool {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetVolumeNameForVolumeMountPointW;
+
+    // 参数必须以反斜杠结尾
+    let mut wide: Vec<u16> = path.as_os_str().encode_wide().collect();
+    if wide.last() != Some(&(b'\\' as u16)) {
+        wide.push(b'\\' as u16);
+    }
+    wide.push(0);
+    let mut volume = [0u16; 64];
+    unsafe { GetVolumeNameForVolumeMountPointW(wide.as_ptr(), volume.as_mut_ptr(), volume.len() as u32) != 0 }
+}
+
+/// 卷挂载点只存在于 NTFS（其他平台由遍历器按设备号识别挂载点）
+#[cfg(not(target_os = "windows"))]
+pub fn is_volume_mount_point(_path: &std::path::Path) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_reparse_points() {
+        let reparse_dir = FILE_ATTRIBUTE_REPARSE_POINT | 0x10;
+        assert_eq!(classify_windows(0x10, 0, true, || unreachable!()), ItemKind::Dir);
+        assert_eq!(classify_windows(reparse_dir, IO_REPARSE_TAG_MOUNT_POINT, true, || false), ItemKind::Junction);
+        assert_eq!(classify_windows(reparse_dir, IO_REPARSE_TAG_MOUNT_POINT, true, || true), ItemKind::MountPoint);
+        assert_eq!(classify_windows(FILE_ATTRIBUTE_REPARSE_POINT, IO_REPARSE_TAG_SYMLINK, false, || false), ItemKind::Symlink);
+        // OneDrive：未下载的占位符 vs 已下载到本地的文件
+        let cloud_tag = 0x9000_601A;
+        assert_eq!(
+            classify_windows(FILE_ATTRIBUTE_REPARSE_POINT | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS, cloud_tag, false, || false),
+            ItemKind::CloudPlaceholder
+        );
+        assert_eq!(classify_windows(FILE_ATTRIBUTE_REPARSE_POINT, cloud_tag, false, || false), ItemKind::File);
+        assert!(!ItemKind::Junction.descends() && ItemKind::Dir.descends());
+    }
+}
@@ -0,0 +1,51 @@
+// 共享数据块去重（APFS 克隆）
+//
+// APFS 上 cp -c / Finder 复制得到的克隆文件与原文件共享数据块，各自的逻辑大小相加会把
+// 共享部分重复计算。遍历器只把文件的私有部分记入 size，共享部分以 SharedExtent 上报，
+// 由扫描在一次遍历内按（卷, 克隆 ID）只计一次。其他平台的遍历器不产生 SharedExtent。
+
+use parking_lot::Mutex;
+use std::collections::HashSet;
+
+/// 与其他文件共享的数据块
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SharedExtent {
+    /// 所在卷（设备号），克隆 ID 只在卷内唯一
+    pub volume: u64,
+    pub clone_id: u64,
+    /// 共享部分的字节数
+    pub bytes: u64,
+}
+
+/// 一次扫描内的共享块去重表
+#[derive(Debug, Default)]
+pub struct SharedExtentTracker {
+    seen: Mutex<HashSet<(u64, u64)>>,
+}
+
+impl SharedExtentTracker {
+    /// 本条目应额外计入的共享字节数：同一克隆组首次出现时为共享大小，之后为 0
+    pub fn charge(&self, extent: Option<SharedExtent>) -> u64 {
+        match extent {
+            Some(e) if self.seen.lock().insert((e.volume, e.clone_id)) => e.bytes,
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn charges_each_clone_group_once() {
+        let tracker = SharedExtentTracker::default();
+        let extent = |volume, clone_id| Some(SharedExtent { volume, clone_id, bytes: 4096 });
+
+        assert_eq!(tracker.charge(extent(1, 7)), 4096);
+        assert_eq!(tracker.charge(extent(1, 7)), 0);
+        // 不同卷上的相同克隆 ID 是不同的组
+        assert_eq!(tracker.charge(extent(2, 7)), 4096);
+        assert_eq!(tracker.charge(None), 0);
+    }
+}
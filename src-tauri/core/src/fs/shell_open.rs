@@ -0,0 +1,103 @@
+// 在文件管理器中显示 / 用默认程序打开
+//
+// Windows: explorer.exe /select 定位条目；ShellExecuteW（默认动作）打开
+// macOS: `open -R` 在 Finder 中定位；`open` 打开
+// Linux: D-Bus org.freedesktop.FileManager1.ShowItems（Nautilus、Dolphin、Nemo 等均实现）定位条目，
+//        不可用时用 xdg-open 打开所在目录；xdg-open 打开
+// path 为规范化后的路径（正斜杠，可为扩展长度形式），传给系统前转换为平台原生形式
+
+/// 在系统文件管理器中显示 path（打开所在目录并选中该条目）
+#[cfg(target_os = "windows")]
+pub fn reveal_in_file_manager(path: &str) -> anyhow::Result<()> {
+    use std::os::windows::process::CommandExt;
+
+    let native = native_path(path);
+    // explorer 自行解析命令行，路径须整体加引号跟在 /select, 之后；其退出码不反映成败
+    std::process::Command::new("explorer.exe")
+        .raw_arg(format!("/select,\"{}\"", native))
+        .spawn()?;
+    Ok(())
+}
+
+/// 用系统为该类型关联的默认程序打开 path（目录在文件管理器中打开）
+#[cfg(target_os = "windows")]
+pub fn open_with_default_app(path: &str) -> anyhow::Result<()> {
+    use windows_sys::Win32::UI::Shell::ShellExecuteW;
+    use windows_sys::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+    let wide: Vec<u16> = native_path(path).encode_utf16().chain(std::iter::once(0)).collect();
+    // 动作为 NULL：使用该类型的默认动作（不一定是 "open"）
+    let result = unsafe {
+        ShellExecuteW(0, std::ptr::null(), wide.as_ptr(), std::ptr::null(), std::ptr::null(), SW_SHOWNORMAL)
+    };
+    // 返回值大于 32 表示成功，否则为错误码
+    if result <= 32 {
+        anyhow::bail!("ShellExecute 失败（错误码 {}）", result);
+    }
+    Ok(())
+}
+
+/// 去掉扩展长度前缀（Shell 不接受 \\?\ 形式）并换回反斜杠
+#[cfg(target_os = "windows")]
+fn native_path(path: &str) -> String {
+    super::display_path_str(path).replace('/', "\\")
+}
+
+#[cfg(target_os = "macos")]
+pub fn reveal_in_file_manager(path: &str) -> anyhow::Result<()> {
+    run_open(&["-R", path])
+}
+
+#[cfg(target_os = "macos")]
+pub fn open_with_default_app(path: &str) -> anyhow::Result<()> {
+    run_open(&[path])
+}
+
+#[cfg(target_os = "macos")]
+fn run_open(args: &[&str]) -> anyhow::Result<()> {
+    let output = std::process::Command::new("open").args(args).output()?;
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn reveal_in_file_manager(path: &str) -> anyhow::Result<()> {
+    let uri = format!("file://{}", super::trash::percent_encode(path));
+    let shown = std::process::Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.freedesktop.FileManager1",
+            "--type=method_call",
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1.ShowItems",
+            &format!("array:string:{}", uri),
+            "string:",
+        ])
+        .output()
+        .is_ok_and(|o| o.status.success());
+    if shown {
+        return Ok(());
+    }
+    // 没有实现 FileManager1 的文件管理器：退而打开所在目录
+    match std::path::Path::new(path).parent() {
+        Some(parent) => open_with_default_app(&parent.to_string_lossy()),
+        None => open_with_default_app(path),
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn open_with_default_app(path: &str) -> anyhow::Result<()> {
+    // 部分桌面环境下 xdg-open 会等待被打开的程序退出：不等待，由后台线程回收子进程
+    let mut child = std::process::Command::new("xdg-open")
+        .arg(path)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("无法启动 xdg-open: {}", e))?;
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
@@ -0,0 +1,441 @@
+// 回收站 / 废纸篓枚举与清空
+//
+// Windows: 各本地卷的 X:\$Recycle.Bin\<SID>\ 下，每个被删除的条目对应一对文件：
+//          $R<后缀> 为数据本身（文件或整个目录），$I<后缀> 记录原路径、大小与删除时间
+//          （版本 1 为定长 260 字符路径，版本 2 为变长）。其他用户的 SID 目录无权读取，跳过。
+//          清空使用 SHEmptyRecycleBinW（所有卷，不弹确认框）
+// Linux:   freedesktop.org Trash 规范：主目录回收站 $XDG_DATA_HOME/Trash 及各挂载点的
+//          .Trash/<uid>、.Trash-<uid>；info/<名称>.trashinfo 记录原路径（URL 编码）与删除时间，
+//          files/<名称> 为数据
+// macOS:   ~/.Trash（不记录原路径，删除时间取条目的修改时间；读取需要“完全磁盘访问权限”）
+//
+// 移入回收站：Windows 用 SHFileOperationW（FOF_ALLOWUNDO，可在资源管理器中还原）；
+// Linux 写 .trashinfo 后 rename 进主目录回收站，跨文件系统时改用所在挂载点的 .Trash-<uid>；
+// macOS rename 进 ~/.Trash（重名时追加序号，不支持跨卷）
+
+use std::path::{Path, PathBuf};
+
+/// 回收站中的一个条目
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrashedItem {
+    /// 删除前的完整路径（macOS 废纸篓不记录，为 None）
+    pub original_path: Option<String>,
+    /// 数据在回收站中的位置
+    pub location: PathBuf,
+    /// 所在回收站目录（Windows 为 X:\$Recycle.Bin，Linux 为 Trash 目录）
+    pub bin: PathBuf,
+    pub size: u64,
+    /// 删除时间（Unix 秒），未知为 0
+    pub deleted_at: i64,
+    pub is_dir: bool,
+}
+
+/// 递归累计大小（不跟随符号链接），读取失败的部分忽略
+#[cfg_attr(target_os = "windows", allow(dead_code))]
+fn tree_size(path: &Path) -> u64 {
+    let Ok(meta) = std::fs::symlink_metadata(path) else { return 0 };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| tree_size(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+/// 解析 Windows $I 文件：(原大小, 删除时间 FILETIME, 原路径)
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn parse_recycle_info(data: &[u8]) -> Option<(u64, i64, String)> {
+    let read_i64 = |at: usize| Some(i64::from_le_bytes(data.get(at..at + 8)?.try_into().ok()?));
+    let version = read_i64(0)?;
+    let size = read_i64(8)?.max(0) as u64;
+    let deleted = read_i64(16)?;
+    let path_bytes = match version {
+        1 => data.get(24..24 + 520)?,
+        2 => {
+            let chars = u32::from_le_bytes(data.get(24..28)?.try_into().ok()?) as usize;
+            data.get(28..28 + chars.checked_mul(2)?)?
+        }
+        _ => return None,
+    };
+    let wide: Vec<u16> = path_bytes.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+    let end = wide.iter().position(|&c| c == 0).unwrap_or(wide.len());
+    let path = String::from_utf16_lossy(&wide[..end]);
+    (!path.is_empty()).then_some((size, deleted, path))
+}
+
+/// 解析 .trashinfo：(原路径（已解码，可能为相对挂载点的路径）, 删除时间 Unix 秒)
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_trash_info(text: &str) -> Option<(String, i64)> {
+    let section = text.lines().map(str::trim).skip_while(|l| *l != "[Trash Info]").skip(1);
+    let (mut path, mut deleted) = (None, 0);
+    for line in section.take_while(|l| !l.starts_with('[')) {
+        if let Some(value) = line.strip_prefix("Path=") {
+            path = Some(percent_decode(value));
+        } else if let Some(value) = line.strip_prefix("DeletionDate=") {
+            deleted = chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S")
+                .ok()
+                .and_then(|t| t.and_local_timezone(chrono::Local).earliest())
+                .map_or(0, |t| t.timestamp());
+        }
+    }
+    Some((path?, deleted))
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = (bytes[i] == b'%')
+            .then(|| value.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match hex {
+            Some(b) => {
+                out.push(b);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(target_os = "windows")]
+pub fn trash_items() -> Vec<TrashedItem> {
+    let mut items = Vec::new();
+    for volume in super::list_volumes().into_iter().filter(|v| v.is_ready && !v.is_network) {
+        let bin = PathBuf::from(format!("{}$Recycle.Bin", volume.mount_point));
+        let Ok(users) = std::fs::read_dir(&bin) else { continue };
+        for user_dir in users.filter_map(|e| e.ok()).map(|e| e.path()) {
+            let Ok(entries) = std::fs::read_dir(&user_dir) else { continue };
+            for entry in entries.filter_map(|e| e.ok()) {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let Some(suffix) = name.strip_prefix("$I") else { continue };
+                let location = user_dir.join(format!("$R{}", suffix));
+                let Ok(meta) = std::fs::symlink_metadata(&location) else { continue };
+                let Some((size, deleted, original)) =
+                    std::fs::read(entry.path()).ok().and_then(|d| parse_recycle_info(&d))
+                else {
+                    continue;
+                };
+                items.push(TrashedItem {
+                    original_path: Some(original),
+                    location,
+                    bin: bin.clone(),
+                    size,
+                    deleted_at: super::filetime_to_unix(deleted),
+                    is_dir: meta.is_dir(),
+                });
+            }
+        }
+    }
+    items
+}
+
+#[cfg(target_os = "windows")]
+pub fn empty_trash() -> anyhow::Result<()> {
+    use windows_sys::Win32::UI::Shell::SHEmptyRecycleBinW;
+
+    const SHERB_NOCONFIRMATION: u32 = 0x1;
+    const SHERB_NOPROGRESSUI: u32 = 0x2;
+    const SHERB_NOSOUND: u32 = 0x4;
+
+    // 回收站已空时 SHEmptyRecycleBinW 返回失败，先判断
+    if trash_items().is_empty() {
+        return Ok(());
+    }
+    let hr = unsafe {
+        SHEmptyRecycleBinW(0, std::ptr::null(), SHERB_NOCONFIRMATION | SHERB_NOPROGRESSUI | SHERB_NOSOUND)
+    };
+    if hr < 0 {
+        return Err(anyhow::anyhow!("清空回收站失败（HRESULT 0x{:08X}）", hr as u32));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+pub fn move_to_trash(path: &Path) -> anyhow::Result<()> {
+    use windows_sys::Win32::UI::Shell::{SHFileOperationW, SHFILEOPSTRUCTW};
+
+    const FO_DELETE: u32 = 0x3;
+    const FOF_SILENT: u16 = 0x4;
+    const FOF_NOCONFIRMATION: u16 = 0x10;
+    const FOF_ALLOWUNDO: u16 = 0x40;
+    const FOF_NOERRORUI: u16 = 0x400;
+
+    // SHFileOperationW 不接受 \\?\ 前缀；路径列表以两个 NUL 结尾
+    let display = super::display_path(&path.to_string_lossy()).replace('/', "\\");
+    let from: Vec<u16> = display.encode_utf16().chain([0, 0]).collect();
+    let mut op = SHFILEOPSTRUCTW {
+        hwnd: 0,
+        wFunc: FO_DELETE,
+        pFrom: from.as_ptr(),
+        pTo: std::ptr::null(),
+        fFlags: FOF_ALLOWUNDO | FOF_NOCONFIRMATION | FOF_SILENT | FOF_NOERRORUI,
+        fAnyOperationsAborted: 0,
+        hNameMappings: std::ptr::null_mut(),
+        lpszProgressTitle: std::ptr::null(),
+    };
+    let code = unsafe { SHFileOperationW(&mut op) };
+    if code != 0 || op.fAnyOperationsAborted != 0 {
+        return Err(anyhow::anyhow!("移入回收站失败（错误码 0x{:X}）", code));
+    }
+    Ok(())
+}
+
+/// 主目录回收站 $XDG_DATA_HOME/Trash（默认 ~/.local/share/Trash）
+#[cfg(target_os = "linux")]
+fn home_trash() -> Option<PathBuf> {
+    std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/share")))
+        .map(|data_home| data_home.join("Trash"))
+}
+
+/// 本用户可用的 Trash 目录：(Trash 目录, 原路径为相对路径时的基准目录)
+#[cfg(target_os = "linux")]
+fn trash_dirs() -> Vec<(PathBuf, PathBuf)> {
+    let mut dirs = Vec::new();
+    if let Some(home_trash) = home_trash() {
+        dirs.push((home_trash, PathBuf::from("/")));
+    }
+    let uid = unsafe { libc::getuid() };
+    for volume in super::list_volumes() {
+        let top = PathBuf::from(&volume.mount_point);
+        dirs.push((top.join(".Trash").join(uid.to_string()), top.clone()));
+        dirs.push((top.join(format!(".Trash-{}", uid)), top));
+    }
+    let mut seen = std::collections::HashSet::new();
+    dirs.retain(|(dir, _)| dir.join("info").is_dir() && seen.insert(std::fs::canonicalize(dir).unwrap_or(dir.clone())));
+    dirs
+}
+
+#[cfg(target_os = "linux")]
+pub fn trash_items() -> Vec<TrashedItem> {
+    let mut items = Vec::new();
+    for (bin, top) in trash_dirs() {
+        let Ok(infos) = std::fs::read_dir(bin.join("info")) else { continue };
+        for info in infos.filter_map(|e| e.ok()) {
+            let name = info.file_name().to_string_lossy().to_string();
+            let Some(stem) = name.strip_suffix(".trashinfo") else { continue };
+            let location = bin.join("files").join(stem);
+            let Ok(meta) = std::fs::symlink_metadata(&location) else { continue };
+            let Some((original, deleted_at)) =
+                std::fs::read_to_string(info.path()).ok().and_then(|t| parse_trash_info(&t))
+            else {
+                continue;
+            };
+            items.push(TrashedItem {
+                original_path: Some(top.join(original).to_string_lossy().to_string()),
+                size: tree_size(&location),
+                location,
+                bin: bin.clone(),
+                deleted_at,
+                is_dir: meta.is_dir(),
+            });
+        }
+    }
+    items
+}
+
+#[cfg(target_os = "linux")]
+pub fn empty_trash() -> anyhow::Result<()> {
+    for (bin, _) in trash_dirs() {
+        for sub in ["files", "info"] {
+            remove_children(&bin.join(sub))?;
+        }
+        std::fs::remove_file(bin.join("directorysizes")).ok();
+    }
+    Ok(())
+}
+
+/// .trashinfo 的 Path 值（也用于 file:// URI）：保留 / 与非保留字符，其余按字节百分号编码
+#[cfg_attr(any(target_os = "windows", target_os = "macos"), allow(dead_code))]
+pub(super) fn percent_encode(path: &str) -> String {
+    path.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// 把 path 移入 bin（先以 create_new 占用 info 文件名，避免并发重名），重名时追加 .2、.3……
+#[cfg(target_os = "linux")]
+fn trash_into(bin: &Path, path: &Path) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::DirBuilderExt;
+
+    for sub in ["files", "info"] {
+        std::fs::DirBuilder::new().recursive(true).mode(0o700).create(bin.join(sub))?;
+    }
+    let name = path.file_name().ok_or_else(|| std::io::Error::other("无法移入回收站：路径没有名称"))?;
+    let name = name.to_string_lossy();
+    let content = format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        percent_encode(&path.to_string_lossy()),
+        chrono::Local::now().format("%Y-%m-%dT%H:%M:%S")
+    );
+    for n in 1u32.. {
+        let candidate = if n == 1 { name.to_string() } else { format!("{}.{}", name, n) };
+        let info = bin.join("info").join(format!("{}.trashinfo", candidate));
+        match std::fs::OpenOptions::new().write(true).create_new(true).open(&info) {
+            Ok(mut file) => {
+                file.write_all(content.as_bytes())?;
+                return std::fs::rename(path, bin.join("files").join(&candidate)).inspect_err(|_| {
+                    std::fs::remove_file(&info).ok();
+                });
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    unreachable!()
+}
+
+#[cfg(target_os = "linux")]
+pub fn move_to_trash(path: &Path) -> anyhow::Result<()> {
+    // 只规范化所在目录：path 是符号链接时移走链接本身，不解析到目标
+    let path = match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => std::fs::canonicalize(parent)?.join(name),
+        _ => std::fs::canonicalize(path)?,
+    };
+    let home_trash = home_trash().ok_or_else(|| anyhow::anyhow!("无法确定主目录回收站"))?;
+    match trash_into(&home_trash, &path) {
+        // EXDEV：不在主目录所在文件系统，改用该挂载点的 .Trash-<uid>
+        Err(e) if e.raw_os_error() == Some(libc::EXDEV) => {
+            let top = super::list_volumes()
+                .into_iter()
+                .map(|v| PathBuf::from(v.mount_point))
+                .filter(|mount| path.starts_with(mount))
+                .max_by_key(|mount| mount.as_os_str().len())
+                .ok_or_else(|| anyhow::anyhow!("找不到 {} 所在的挂载点", path.display()))?;
+            let uid = unsafe { libc::getuid() };
+            Ok(trash_into(&top.join(format!(".Trash-{}", uid)), &path)?)
+        }
+        other => Ok(other?),
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn user_trash() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".Trash"))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn trash_items() -> Vec<TrashedItem> {
+    let Some(bin) = user_trash() else { return Vec::new() };
+    let Ok(entries) = std::fs::read_dir(&bin) else { return Vec::new() };
+    entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name() != ".DS_Store")
+        .filter_map(|e| {
+            let meta = std::fs::symlink_metadata(e.path()).ok()?;
+            let deleted_at = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map_or(0, |d| d.as_secs() as i64);
+            Some(TrashedItem {
+                original_path: None,
+                size: tree_size(&e.path()),
+                location: e.path(),
+                bin: bin.clone(),
+                deleted_at,
+                is_dir: meta.is_dir(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn empty_trash() -> anyhow::Result<()> {
+    match user_trash() {
+        Some(bin) => remove_children(&bin),
+        None => Ok(()),
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn move_to_trash(path: &Path) -> anyhow::Result<()> {
+    let bin = user_trash().ok_or_else(|| anyhow::anyhow!("无法确定废纸篓位置"))?;
+    std::fs::create_dir_all(&bin)?;
+    let name = path.file_name().ok_or_else(|| anyhow::anyhow!("路径没有名称"))?.to_string_lossy().to_string();
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem.to_string(), format!(".{}", ext)),
+        _ => (name.clone(), String::new()),
+    };
+    // 与访达一致：重名时为 "name 2.ext"、"name 3.ext"……
+    let target = (1u32..)
+        .map(|n| if n == 1 { bin.join(&name) } else { bin.join(format!("{} {}{}", stem, n, ext)) })
+        .find(|candidate| std::fs::symlink_metadata(candidate).is_err())
+        .unwrap_or_else(|| bin.join(&name));
+    std::fs::rename(path, &target)?;
+    Ok(())
+}
+
+/// 删除目录下的全部条目（目录本身保留）
+#[cfg(not(target_os = "windows"))]
+fn remove_children(dir: &Path) -> anyhow::Result<()> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Ok(()) };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            std::fs::remove_dir_all(&path)?;
+        } else {
+            std::fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(s: &str) -> Vec<u8> {
+        s.encode_utf16().chain(std::iter::once(0)).flat_map(|c| c.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn parses_both_recycle_info_versions() {
+        let path = "C:\\Users\\me\\报告.docx";
+        let mut v2 = Vec::new();
+        v2.extend(2i64.to_le_bytes());
+        v2.extend(4096i64.to_le_bytes());
+        v2.extend(133_000_000_000_000_000i64.to_le_bytes());
+        v2.extend(((path.encode_utf16().count() + 1) as u32).to_le_bytes());
+        v2.extend(utf16(path));
+        assert_eq!(parse_recycle_info(&v2), Some((4096, 133_000_000_000_000_000, path.to_string())));
+
+        let mut v1 = Vec::new();
+        v1.extend(1i64.to_le_bytes());
+        v1.extend(10i64.to_le_bytes());
+        v1.extend(0i64.to_le_bytes());
+        let mut fixed = utf16("D:\\old.txt");
+        fixed.resize(520, 0);
+        v1.extend(fixed);
+        assert_eq!(parse_recycle_info(&v1).map(|r| r.2), Some("D:\\old.txt".to_string()));
+
+        assert_eq!(parse_recycle_info(&v2[..20]), None);
+        assert_eq!(parse_recycle_info(&[3u8; 40]), None);
+    }
+
+    #[test]
+    fn parses_trash_info_with_encoded_path() {
+        let text = "[Trash Info]\nPath=/home/me/My%20Docs/%E6%8A%A5%E5%91%8A.txt\nDeletionDate=2024-03-01T12:30:45\n";
+        let (path, deleted) = parse_trash_info(text).unwrap();
+        assert_eq!(path, "/home/me/My Docs/报告.txt");
+        assert!(deleted > 0);
+        assert_eq!(parse_trash_info("[Other]\nPath=/x"), None);
+        assert_eq!(percent_decode("100%"), "100%");
+        let original = "/home/me/My Docs/报告 #1.txt";
+        assert_eq!(percent_decode(&percent_encode(original)), original);
+        assert_eq!(percent_encode("/a b"), "/a%20b");
+    }
+}
@@ -0,0 +1,314 @@
+// 卷/驱动器枚举
+//
+// 列出所有已挂载的卷及其卷标、文件系统、总空间/可用空间、可移动/网络属性，
+// 供前端驱动器选择器和空间概览使用 —— 不需要任何扫描。
+//
+// Windows: GetLogicalDrives + GetDriveTypeW + GetVolumeInformationW + GetDiskFreeSpaceExW
+// 其他平台: sysinfo::Disks（按文件系统类型识别网络挂载）
+// 分配单元大小：Windows GetDiskFreeSpaceW（每簇扇区数 × 扇区大小），其他平台 statvfs
+
+use serde::Serialize;
+use schemars::JsonSchema;
+
+/// 卷类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum VolumeKind {
+    Fixed,
+    Removable,
+    Network,
+    CdRom,
+    RamDisk,
+    Unknown,
+}
+
+/// 单个已挂载卷的信息
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeInfo {
+    /// 挂载点（Windows 为 `C:/`，其他平台为挂载目录），可直接作为扫描路径
+    pub mount_point: String,
+    /// 盘符（仅 Windows）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drive_letter: Option<char>,
+    /// 卷标（可能为空）
+    pub label: String,
+    /// 文件系统类型，如 NTFS / exFAT / ext4
+    pub file_system: String,
+    pub kind: VolumeKind,
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+    pub is_removable: bool,
+    pub is_network: bool,
+    /// 卷是否就绪（读卡器无卡、光驱无盘时为 false，空间信息均为 0）
+    pub is_ready: bool,
+    /// 分配单元（簇 / 块）大小（字节），文件按其整数倍占用空间；获取失败为 None
+    pub cluster_size: Option<u64>,
+}
+
+#[cfg(target_os = "windows")]
+pub fn list_volumes() -> Vec<VolumeInfo> {
+    use windows_sys::Win32::Storage::FileSystem::{
+        GetDiskFreeSpaceExW, GetDriveTypeW, GetLogicalDrives, GetVolumeInformationW,
+    };
+
+    // GetDriveTypeW 返回值
+    const DRIVE_REMOVABLE: u32 = 2;
+    const DRIVE_FIXED: u32 = 3;
+    const DRIVE_REMOTE: u32 = 4;
+    const DRIVE_CDROM: u32 = 5;
+    const DRIVE_RAMDISK: u32 = 6;
+
+    let mut volumes = Vec::new();
+    let mask = unsafe { GetLogicalDrives() };
+    if mask == 0 {
+        return volumes;
+    }
+
+    for i in 0..26u32 {
+        if (mask & (1 << i)) == 0 {
+            continue;
+        }
+        let letter = (b'A' + i as u8) as char;
+        let root = format!("{}:\\", letter);
+        let root_wide: Vec<u16> = root.encode_utf16().chain(std::iter::once(0)).collect();
+
+        let kind = match unsafe { GetDriveTypeW(root_wide.as_ptr()) } {
+            DRIVE_FIXED => VolumeKind::Fixed,
+            DRIVE_REMOVABLE => VolumeKind::Removable,
+            DRIVE_REMOTE => VolumeKind::Network,
+            DRIVE_CDROM => VolumeKind::CdRom,
+            DRIVE_RAMDISK => VolumeKind::RamDisk,
+            _ => VolumeKind::Unknown,
+        };
+
+        let mut label_buf = [0u16; 261];
+        let mut fs_buf = [0u16; 261];
+        let info_ok = unsafe {
+            GetVolumeInformationW(
+                root_wide.as_ptr(),
+                label_buf.as_mut_ptr(),
+                label_buf.len() as u32,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                fs_buf.as_mut_ptr(),
+                fs_buf.len() as u32,
+            )
+        } != 0;
+
+        let mut free_to_caller = 0u64;
+        let mut total = 0u64;
+        let mut total_free = 0u64;
+        let space_ok = unsafe {
+            GetDiskFreeSpaceExW(
+                root_wide.as_ptr(),
+                &mut free_to_caller,
+                &mut total,
+                &mut total_free,
+            )
+        } != 0;
+
+        volumes.push(VolumeInfo {
+            mount_point: format!("{}:/", letter),
+            drive_letter: Some(letter),
+            label: if info_ok { wide_to_string(&label_buf) } else { String::new() },
+            file_system: if info_ok { wide_to_string(&fs_buf) } else { String::new() },
+            kind,
+            total_bytes: if space_ok { total } else { 0 },
+            free_bytes: if space_ok { free_to_caller } else { 0 },
+            is_removable: matches!(kind, VolumeKind::Removable | VolumeKind::CdRom),
+            is_network: kind == VolumeKind::Network,
+            is_ready: info_ok && space_ok,
+            cluster_size: if space_ok { disk_cluster_size(&root_wide) } else { None },
+        });
+    }
+
+    volumes
+}
+
+#[cfg(target_os = "windows")]
+fn wide_to_string(buf: &[u16]) -> String {
+    let end = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+    String::from_utf16_lossy(&buf[..end])
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn list_volumes() -> Vec<VolumeInfo> {
+    use sysinfo::Disks;
+
+    let disks = Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .map(|disk| {
+            let file_system = disk.file_system().to_string_lossy().to_string();
+            let is_network = is_network_fs(&file_system);
+            let is_removable = disk.is_removable();
+            let kind = if is_network {
+                VolumeKind::Network
+            } else if is_removable {
+                VolumeKind::Removable
+            } else {
+                VolumeKind::Fixed
+            };
+            let mount_point = disk.mount_point().to_string_lossy().to_string();
+            VolumeInfo {
+                cluster_size: cluster_size(&mount_point),
+                mount_point,
+                drive_letter: None,
+                label: disk.name().to_string_lossy().to_string(),
+                file_system,
+                kind,
+                total_bytes: disk.total_space(),
+                free_bytes: disk.available_space(),
+                is_removable,
+                is_network,
+                is_ready: true,
+            }
+        })
+        .collect()
+}
+
+/// 路径所在卷的标识（Windows 为卷序列号，Unix 为 st_dev），用于生成稳定 item ID；获取失败返回 0
+#[cfg(target_os = "windows")]
+pub fn volume_id(path: &str) -> u64 {
+    use windows_sys::Win32::Storage::FileSystem::GetVolumeInformationW;
+
+    let bytes = path.as_bytes();
+    if bytes.len() < 2 || bytes[1] != b':' {
+        return 0;
+    }
+    let root: Vec<u16> = format!("{}:\\", bytes[0] as char)
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+
+    let mut serial = 0u32;
+    let ok = unsafe {
+        GetVolumeInformationW(
+            root.as_ptr(),
+            std::ptr::null_mut(),
+            0,
+            &mut serial,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            0,
+        )
+    } != 0;
+    if ok { serial as u64 } else { 0 }
+}
+
+#[cfg(unix)]
+pub fn volume_id(path: &str) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(path).map(|m| m.dev()).unwrap_or(0)
+}
+
+#[cfg(not(any(target_os = "windows", unix)))]
+pub fn volume_id(_path: &str) -> u64 {
+    0
+}
+
+/// 路径所在卷的分配单元（簇 / 块）大小（字节）；获取失败返回 None
+#[cfg(target_os = "windows")]
+pub fn cluster_size(path: &str) -> Option<u64> {
+    use windows_sys::Win32::Storage::FileSystem::GetVolumePathNameW;
+
+    let wide: Vec<u16> = path
+        .replace('/', "\\")
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    // 卷根（盘符、挂载文件夹或 UNC 共享根）
+    let mut root = [0u16; 1024];
+    if unsafe { GetVolumePathNameW(wide.as_ptr(), root.as_mut_ptr(), root.len() as u32) } == 0 {
+        return None;
+    }
+    disk_cluster_size(&root)
+}
+
+/// 卷根（以 NUL 结尾的宽字符串）的簇大小：每簇扇区数 × 每扇区字节数
+#[cfg(target_os = "windows")]
+fn disk_cluster_size(root: &[u16]) -> Option<u64> {
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceW;
+
+    let mut sectors_per_cluster = 0u32;
+    let mut bytes_per_sector = 0u32;
+    let ok = unsafe {
+        GetDiskFreeSpaceW(
+            root.as_ptr(),
+            &mut sectors_per_cluster,
+            &mut bytes_per_sector,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    } != 0;
+    let size = sectors_per_cluster as u64 * bytes_per_sector as u64;
+    (ok && size > 0).then_some(size)
+}
+
+#[cfg(unix)]
+pub fn cluster_size(path: &str) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(std::path::Path::new(path).as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    // f_frsize 为分配单元；部分文件系统只填 f_bsize
+    let size = if stat.f_frsize > 0 { stat.f_frsize as u64 } else { stat.f_bsize as u64 };
+    (size > 0).then_some(size)
+}
+
+#[cfg(not(any(target_os = "windows", unix)))]
+pub fn cluster_size(_path: &str) -> Option<u64> {
+    None
+}
+
+/// 路径是否位于网络位置：UNC 共享（`//server/share`）、映射的网络驱动器或网络文件系统挂载
+pub fn is_network_path(path: &str) -> bool {
+    let path = super::display_path(path);
+    path.starts_with("//") || is_network_mount(&path)
+}
+
+#[cfg(target_os = "windows")]
+fn is_network_mount(path: &str) -> bool {
+    use windows_sys::Win32::Storage::FileSystem::GetDriveTypeW;
+    const DRIVE_REMOTE: u32 = 4;
+
+    let bytes = path.as_bytes();
+    if bytes.len() < 2 || bytes[1] != b':' {
+        return false;
+    }
+    let root: Vec<u16> = format!("{}:\\", bytes[0] as char)
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    unsafe { GetDriveTypeW(root.as_ptr()) == DRIVE_REMOTE }
+}
+
+/// 取包含该路径的最长挂载点，按其文件系统类型判断
+#[cfg(not(target_os = "windows"))]
+fn is_network_mount(path: &str) -> bool {
+    use sysinfo::Disks;
+
+    let disks = Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|disk| std::path::Path::new(path).starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .is_some_and(|disk| is_network_fs(&disk.file_system().to_string_lossy()))
+}
+
+/// 按文件系统类型判断是否为网络挂载（nfs / cifs / smb / sshfs 等）
+#[cfg(not(target_os = "windows"))]
+pub(super) fn is_network_fs(fs: &str) -> bool {
+    let fs = fs.to_lowercase();
+    ["nfs", "cifs", "smb", "sshfs", "afp", "webdav", "9p"]
+        .iter()
+        .any(|p| fs.starts_with(p) || fs.contains(&format!("fuse.{}", p)))
+}
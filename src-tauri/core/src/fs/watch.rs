@@ -0,0 +1,256 @@
+// 目录树变更监视（供缓存失效使用）
+//
+// Windows: ReadDirectoryChangesW，一个目录句柄递归监视整棵树，取消时 CancelIoEx 打断阻塞的读取
+// Linux: inotify，每个目录一个 watch（不递归）；新建的子目录随即加入，watch 数超过上限时只监视前面的目录
+// 其他平台：暂不支持，watch_tree 返回错误（缓存仍按根目录 mtime 校验）
+// 回调收到的是发生变更的目录（新增 / 删除 / 修改的条目的父目录），在监视线程中调用，应尽快返回；
+// 事件队列溢出时回调收到根目录本身
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// 单棵目录树的 inotify watch 上限（系统默认的 max_user_watches 通常为 8192–65536，为其他程序留出余量）
+#[cfg(target_os = "linux")]
+const MAX_TREE_WATCHES: usize = 4096;
+
+/// 正在进行的监视；drop 时停止（监视线程随后自行退出并释放句柄）
+pub struct TreeWatch {
+    stop: Arc<AtomicBool>,
+    #[cfg(target_os = "windows")]
+    handle: isize,
+}
+
+impl Drop for TreeWatch {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        #[cfg(target_os = "windows")]
+        unsafe {
+            windows_sys::Win32::System::IO::CancelIoEx(self.handle, std::ptr::null());
+        }
+    }
+}
+
+/// 监视 root 整棵目录树。dirs 为树中已知的子目录（取自扫描结果），
+/// 不递归的平台据此逐个添加监视，递归监视的平台忽略
+#[cfg(target_os = "linux")]
+pub fn watch_tree<F>(root: &Path, dirs: Vec<PathBuf>, on_change: F) -> anyhow::Result<TreeWatch>
+where
+    F: Fn(&Path) + Send + 'static,
+{
+    use std::collections::HashMap;
+    use std::os::unix::ffi::OsStrExt;
+
+    const MASK: u32 = libc::IN_CREATE
+        | libc::IN_DELETE
+        | libc::IN_CLOSE_WRITE
+        | libc::IN_MODIFY
+        | libc::IN_MOVED_FROM
+        | libc::IN_MOVED_TO
+        | libc::IN_DELETE_SELF
+        | libc::IN_ONLYDIR;
+
+    let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+    if fd < 0 {
+        anyhow::bail!("inotify 初始化失败: {}", std::io::Error::last_os_error());
+    }
+    let add = move |watches: &mut HashMap<i32, PathBuf>, dir: PathBuf| {
+        if watches.len() >= MAX_TREE_WATCHES {
+            return;
+        }
+        let Ok(c_path) = std::ffi::CString::new(dir.as_os_str().as_bytes()) else {
+            return;
+        };
+        let wd = unsafe { libc::inotify_add_watch(fd, c_path.as_ptr(), MASK) };
+        if wd >= 0 {
+            watches.insert(wd, dir);
+        }
+    };
+
+    let mut watches = HashMap::new();
+    add(&mut watches, root.to_path_buf());
+    if watches.is_empty() {
+        let err = std::io::Error::last_os_error();
+        unsafe { libc::close(fd) };
+        anyhow::bail!("无法监视 {}: {}", root.display(), err);
+    }
+    for dir in dirs {
+        add(&mut watches, dir);
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_flag = Arc::clone(&stop);
+    let root = root.to_path_buf();
+    std::thread::Builder::new().name("flashdir-watch".into()).spawn(move || {
+        let header = std::mem::size_of::<libc::inotify_event>();
+        let mut buffer = vec![0u8; 64 * 1024];
+        while !stop_flag.load(Ordering::Relaxed) {
+            let mut poll = libc::pollfd { fd, events: libc::POLLIN, revents: 0 };
+            // 定时醒来检查停止标志
+            if unsafe { libc::poll(&mut poll, 1, 500) } <= 0 {
+                continue;
+            }
+            let n = unsafe { libc::read(fd, buffer.as_mut_ptr() as *mut libc::c_void, buffer.len()) };
+            if n <= 0 {
+                continue;
+            }
+            let mut offset = 0usize;
+            while offset + header <= n as usize {
+                let event = unsafe { std::ptr::read_unaligned(buffer.as_ptr().add(offset) as *const libc::inotify_event) };
+                let name_bytes = &buffer[offset + header..offset + header + event.len as usize];
+                offset += header + event.len as usize;
+
+                if event.mask & libc::IN_Q_OVERFLOW != 0 {
+                    on_change(&root);
+                    continue;
+                }
+                let Some(dir) = watches.get(&event.wd).cloned() else {
+                    continue;
+                };
+                if event.mask & libc::IN_IGNORED != 0 {
+                    watches.remove(&event.wd);
+                    continue;
+                }
+                if event.mask & libc::IN_ISDIR != 0 && event.mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0 {
+                    let name_len = name_bytes.iter().position(|&b| b == 0).unwrap_or(name_bytes.len());
+                    let name = std::ffi::OsStr::from_bytes(&name_bytes[..name_len]);
+                    add(&mut watches, dir.join(name));
+                }
+                on_change(&dir);
+            }
+        }
+        unsafe { libc::close(fd) };
+    })?;
+
+    Ok(TreeWatch { stop })
+}
+
+#[cfg(target_os = "windows")]
+pub fn watch_tree<F>(root: &Path, _dirs: Vec<PathBuf>, on_change: F) -> anyhow::Result<TreeWatch>
+where
+    F: Fn(&Path) + Send + 'static,
+{
+    use windows_sys::Win32::Foundation::{CloseHandle, GetLastError, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::Storage::FileSystem::{
+        CreateFileW, ReadDirectoryChangesW, FILE_FLAG_BACKUP_SEMANTICS, FILE_LIST_DIRECTORY,
+        FILE_NOTIFY_CHANGE_DIR_NAME, FILE_NOTIFY_CHANGE_FILE_NAME, FILE_NOTIFY_CHANGE_LAST_WRITE,
+        FILE_NOTIFY_CHANGE_SIZE, FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
+    };
+
+    let wide: Vec<u16> = super::to_extended_length(&root.to_string_lossy())
+        .as_os_str()
+        .to_string_lossy()
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    let handle = unsafe {
+        CreateFileW(
+            wide.as_ptr(),
+            FILE_LIST_DIRECTORY,
+            FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+            std::ptr::null(),
+            OPEN_EXISTING,
+            FILE_FLAG_BACKUP_SEMANTICS,
+            0,
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        anyhow::bail!("无法监视 {}: {}", root.display(), std::io::Error::from_raw_os_error(unsafe { GetLastError() } as i32));
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_flag = Arc::clone(&stop);
+    let root = root.to_path_buf();
+    std::thread::Builder::new().name("flashdir-watch".into()).spawn(move || {
+        // FILE_NOTIFY_INFORMATION 要求 DWORD 对齐
+        let mut buffer = vec![0u32; 16 * 1024];
+        while !stop_flag.load(Ordering::Relaxed) {
+            let mut returned = 0u32;
+            let ok = unsafe {
+                ReadDirectoryChangesW(
+                    handle,
+                    buffer.as_mut_ptr() as *mut _,
+                    (buffer.len() * 4) as u32,
+                    1,
+                    FILE_NOTIFY_CHANGE_FILE_NAME
+                        | FILE_NOTIFY_CHANGE_DIR_NAME
+                        | FILE_NOTIFY_CHANGE_SIZE
+                        | FILE_NOTIFY_CHANGE_LAST_WRITE,
+                    &mut returned,
+                    std::ptr::null_mut(),
+                    None,
+                )
+            };
+            if ok == 0 {
+                // 被 CancelIoEx 取消，或根目录已被删除
+                break;
+            }
+            if returned == 0 {
+                // 缓冲区溢出，变更已丢失
+                on_change(&root);
+                continue;
+            }
+            let bytes = unsafe { std::slice::from_raw_parts(buffer.as_ptr() as *const u8, returned as usize) };
+            let mut offset = 0usize;
+            while offset + 12 <= bytes.len() {
+                let field = |at: usize| u32::from_le_bytes(bytes[offset + at..offset + at + 4].try_into().unwrap());
+                let (next, name_len) = (field(0) as usize, field(8) as usize);
+                let name: Vec<u16> = bytes[offset + 12..(offset + 12 + name_len).min(bytes.len())]
+                    .chunks_exact(2)
+                    .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                    .collect();
+                let changed = root.join(String::from_utf16_lossy(&name));
+                on_change(changed.parent().unwrap_or(&root));
+                if next == 0 {
+                    break;
+                }
+                offset += next;
+            }
+        }
+        unsafe { CloseHandle(handle) };
+    })?;
+
+    Ok(TreeWatch { stop, handle })
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn watch_tree<F>(_root: &Path, _dirs: Vec<PathBuf>, _on_change: F) -> anyhow::Result<TreeWatch>
+where
+    F: Fn(&Path) + Send + 'static,
+{
+    anyhow::bail!("当前平台不支持目录监视")
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_changes_in_nested_directories() {
+        let root = std::env::temp_dir().join(format!("flashdir_watch_{}", uuid::Uuid::new_v4()));
+        let nested = root.join("a").join("b");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let watch = watch_tree(&root, vec![root.join("a"), nested.clone()], move |dir| {
+            tx.send(dir.to_path_buf()).ok();
+        })
+        .unwrap();
+        // 一次写入产生多个事件（创建、修改、关闭），等到期望的目录出现为止
+        let wait_for = |dir: &Path| {
+            let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+            while rx.recv_timeout(deadline.saturating_duration_since(std::time::Instant::now())).unwrap() != dir {}
+        };
+        std::fs::write(nested.join("f.txt"), b"x").unwrap();
+        wait_for(&nested);
+
+        // 监视开始后新建的子目录同样被监视
+        std::fs::create_dir(root.join("c")).unwrap();
+        wait_for(&root);
+        std::fs::write(root.join("c").join("g.txt"), b"y").unwrap();
+        wait_for(&root.join("c"));
+
+        drop(watch);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,6 +1,6 @@
 // Windows 快速目录遍历器
 // 直接使用 FindFirstFileExW / FindNextFileW，从 WIN32_FIND_DATAW 中一次性获取
-// 文件名、大小、是否为目录 —— 无需额外的 metadata() / file_type() 系统调用
+// 文件名、大小、是否为目录、修改/创建时间 —— 无需额外的 metadata() / file_type() 系统调用
 //
 // 对比 Rust 标准库 fs::read_dir：
 //   - fs::read_dir 内部调用 FindFirstFileExW，但不暴露 WIN32_FIND_DATAW 中的 size
@@ -14,10 +14,10 @@ use std::path::{Path, PathBuf};
 use std::ffi::OsString;
 use std::os::windows::ffi::{OsStrExt, OsStringExt};
 
-use windows_sys::Win32::Foundation::{GetLastError, INVALID_HANDLE_VALUE};
+use windows_sys::Win32::Foundation::{GetLastError, FILETIME, INVALID_HANDLE_VALUE};
 use windows_sys::Win32::Storage::FileSystem::{
     FindFirstFileExW, FindNextFileW, FindClose,
-    FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_REPARSE_POINT,
+    FILE_ATTRIBUTE_DIRECTORY,
     FIND_FIRST_EX_CASE_SENSITIVE, FIND_FIRST_EX_LARGE_FETCH,
     WIN32_FIND_DATAW,
 };
@@ -33,8 +33,18 @@ pub struct FastDirEntry {
     pub size: u64,
     /// 是否为目录
     pub is_dir: bool,
-    /// 是否为符号链接 / 重解析点
-    pub is_symlink: bool,
+    /// 条目类型（联接 / 符号链接 / 卷挂载点 / 云占位符等）
+    pub kind: super::ItemKind,
+    /// 最后修改时间（Unix 秒，0 表示未知）
+    pub modified: i64,
+    /// 创建时间（Unix 秒，0 表示未知）
+    pub created: i64,
+    /// 与其他文件共享、未计入 size 的数据块（Windows 上恒为 None）
+    pub shared: Option<super::SharedExtent>,
+    /// 云同步文件的本地状态（仅在线 / 已在本地）
+    pub cloud: Option<super::CloudFile>,
+    /// 稀疏文件的逻辑 / 分配大小（size 为分配大小）
+    pub sparse: Option<super::SparseFile>,
 }
 
 /// 使用 Windows 原生 API 快速遍历目录
@@ -87,22 +97,41 @@ pub fn read_dir_entries(dir_path: &Path) -> io::Result<Vec<FastDirEntry>> {
             // 跳过 "." 和 ".."
             if name != "." && name != ".." {
                 let is_dir = (find_data.dwFileAttributes & FILE_ATTRIBUTE_DIRECTORY) != 0;
-                let is_symlink = (find_data.dwFileAttributes & FILE_ATTRIBUTE_REPARSE_POINT) != 0;
+                let full_path = dir_path.join(&name);
+                // 设置了 FILE_ATTRIBUTE_REPARSE_POINT 时 dwReserved0 为重解析标记
+                let kind = super::classify_windows(
+                    find_data.dwFileAttributes,
+                    find_data.dwReserved0,
+                    is_dir,
+                    || super::is_volume_mount_point(&full_path),
+                );
 
-                let size = if is_dir {
+                let logical_size = ((find_data.nFileSizeHigh as u64) << 32) | (find_data.nFileSizeLow as u64);
+                let cloud = super::cloud_file_windows(
+                    find_data.dwFileAttributes,
+                    find_data.dwReserved0,
+                    is_dir,
+                    logical_size,
+                );
+                let sparse = super::sparse_file_windows(&full_path, find_data.dwFileAttributes, is_dir, logical_size);
+                // 云占位符的内容不在本地，按 0 字节计（逻辑大小随 cloud 上报）；稀疏文件按分配大小计
+                let size = if is_dir || kind == super::ItemKind::CloudPlaceholder {
                     0
                 } else {
-                    ((find_data.nFileSizeHigh as u64) << 32) | (find_data.nFileSizeLow as u64)
+                    sparse.map_or(logical_size, |s| s.allocated)
                 };
 
-                let full_path = dir_path.join(&name);
-
                 entries.push(FastDirEntry {
                     path: full_path,
                     name,
                     size,
                     is_dir,
-                    is_symlink,
+                    kind,
+                    modified: super::filetime_to_unix(filetime_to_i64(&find_data.ftLastWriteTime)),
+                    created: super::filetime_to_unix(filetime_to_i64(&find_data.ftCreationTime)),
+                    shared: None,
+                    cloud,
+                    sparse,
                 });
             }
 
@@ -122,6 +151,11 @@ pub fn read_dir_entries(dir_path: &Path) -> io::Result<Vec<FastDirEntry>> {
     }
 }
 
+#[inline]
+fn filetime_to_i64(ft: &FILETIME) -> i64 {
+    (((ft.dwHighDateTime as u64) << 32) | ft.dwLowDateTime as u64) as i64
+}
+
 /// 从 WIN32_FIND_DATAW 提取文件名
 unsafe fn win32_find_data_to_name(find_data: &WIN32_FIND_DATAW) -> String {
     let name_len = find_data.cFileName
@@ -0,0 +1,224 @@
+// Git 仓库识别与空间归属
+//
+// 开发者的磁盘里往往散落着几十上百个仓库，.git 本身（历史对象、packfile、LFS 缓存）
+// 可能比工作区还大。基于扫描结果中的 .git 条目识别仓库（其父目录即仓库根），
+// 把仓库大小拆分为 .git 与工作区两部分，并按 .git 内部结构细分：
+// objects/pack 下的 packfile、objects 下的松散对象、lfs 缓存与其余部分（索引、日志、hooks 等）。
+// 按仓库总大小降序列出，并给出 git gc / git lfs prune 等清理建议。
+//
+// .git 为文件时是链接工作树或子模块（gitdir 指向别处），只统计工作区。
+// 嵌套仓库的大小同时计入外层仓库的工作区。
+
+use crate::scan::{format_size, Item, ItemKind};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::HashMap;
+
+const GB: i64 = 1024 * 1024 * 1024;
+const MB: i64 = 1024 * 1024;
+
+/// packfile 总大小超过此值时建议 gc / 检查历史中的大文件
+const LARGE_PACK_BYTES: i64 = GB;
+/// packfile 个数超过此值时建议 gc 合并
+const MANY_PACKS: usize = 50;
+/// 松散对象个数或大小超过此值时建议 gc 打包
+const MANY_LOOSE_OBJECTS: usize = 1000;
+const LARGE_LOOSE_BYTES: i64 = 100 * MB;
+/// LFS 缓存超过此值时建议 prune
+const LARGE_LFS_BYTES: i64 = GB;
+
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRepo {
+    /// 仓库根目录（.git 的父目录）
+    pub path: String,
+    pub total_size: i64,
+    pub total_size_formatted: String,
+    pub git_size: i64,
+    pub git_size_formatted: String,
+    pub working_tree_size: i64,
+    pub working_tree_size_formatted: String,
+    /// objects/pack 下的 .pack 文件
+    pub pack_size: i64,
+    pub pack_count: usize,
+    /// objects/xx/ 下的松散对象
+    pub loose_size: i64,
+    pub loose_count: usize,
+    /// lfs/ 下的 LFS 对象缓存
+    pub lfs_size: i64,
+    /// .git 为文件（链接工作树或子模块），对象存放在别处
+    pub linked: bool,
+    /// 清理建议，如 "packfile 共 12.00 GB，可执行 git gc 或检查历史中的大文件"
+    pub hints: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRepoReport {
+    pub path: String,
+    /// 按仓库总大小降序
+    pub repos: Vec<GitRepo>,
+    pub total_git_size: i64,
+    pub total_git_size_formatted: String,
+    pub total_working_tree_size: i64,
+    pub total_working_tree_size_formatted: String,
+}
+
+/// .git 内部的相对路径归类
+fn attribute(repo: &mut GitRepo, inner: &str, size: i64) {
+    if let Some(rest) = inner.strip_prefix("objects/") {
+        if let Some(pack) = rest.strip_prefix("pack/") {
+            if pack.ends_with(".pack") {
+                repo.pack_size += size;
+                repo.pack_count += 1;
+            }
+        } else if rest.len() == 2 + 1 + 38 || rest.len() == 2 + 1 + 62 {
+            // xx/ + 38 位（SHA-1）或 62 位（SHA-256）十六进制
+            repo.loose_size += size;
+            repo.loose_count += 1;
+        }
+    } else if inner.starts_with("lfs/") {
+        repo.lfs_size += size;
+    }
+}
+
+fn hints(repo: &GitRepo) -> Vec<String> {
+    let mut hints = Vec::new();
+    if repo.pack_size >= LARGE_PACK_BYTES {
+        hints.push(format!("packfile 共 {}，可执行 git gc 或检查历史中的大文件", format_size(repo.pack_size)));
+    }
+    if repo.pack_count > MANY_PACKS {
+        hints.push(format!("packfile 多达 {} 个，git gc 可合并为一个", repo.pack_count));
+    }
+    if repo.loose_count > MANY_LOOSE_OBJECTS || repo.loose_size >= LARGE_LOOSE_BYTES {
+        hints.push(format!(
+            "松散对象 {} 个（{}），git gc 可打包压缩",
+            repo.loose_count,
+            format_size(repo.loose_size)
+        ));
+    }
+    if repo.lfs_size >= LARGE_LFS_BYTES {
+        hints.push(format!("LFS 缓存 {}，可执行 git lfs prune 清理旧版本", format_size(repo.lfs_size)));
+    }
+    if !repo.linked && repo.git_size > repo.working_tree_size && repo.git_size >= 100 * MB {
+        hints.push(".git 比工作区还大，历史中可能提交过大文件".to_string());
+    }
+    hints
+}
+
+/// 由扫描结果识别仓库并统计 .git / 工作区大小。path 为扫描根（不在 items 中）
+pub fn analyze(path: &str, items: &[Item]) -> GitRepoReport {
+    let root = path.trim_end_matches(['/', '\\']);
+    let mut repos: HashMap<&str, GitRepo> = HashMap::new();
+    for item in items.iter().filter(|i| i.name == ".git") {
+        let Some((parent, _)) = item.path.rsplit_once('/') else {
+            continue;
+        };
+        repos.insert(
+            parent,
+            GitRepo {
+                path: parent.to_string(),
+                git_size: if item.is_dir { item.size } else { 0 },
+                linked: item.kind == ItemKind::File,
+                ..Default::default()
+            },
+        );
+    }
+    if repos.is_empty() {
+        return report(path, Vec::new());
+    }
+
+    // 仓库根的大小：根目录条目自身，或扫描根下直接子项之和
+    let mut scan_root_size = 0i64;
+    for item in items {
+        if let Some(repo) = repos.get_mut(item.path.as_str()) {
+            repo.total_size = item.size;
+        }
+        if item.path.rsplit_once('/').is_some_and(|(p, _)| p == root) {
+            scan_root_size += item.size;
+        }
+        if item.kind != ItemKind::File {
+            continue;
+        }
+        // 第一个 /.git/ 之前即仓库根（子模块对象位于外层 .git/modules 中，归外层）
+        let Some(pos) = item.path.find("/.git/") else {
+            continue;
+        };
+        if let Some(repo) = repos.get_mut(&item.path[..pos]) {
+            attribute(repo, &item.path[pos + "/.git/".len()..], item.size);
+        }
+    }
+    if let Some(repo) = repos.get_mut(root) {
+        repo.total_size = scan_root_size;
+    }
+
+    let mut list: Vec<GitRepo> = repos
+        .into_values()
+        .map(|mut repo| {
+            repo.working_tree_size = (repo.total_size - repo.git_size).max(0);
+            repo.total_size_formatted = format_size(repo.total_size).to_string();
+            repo.git_size_formatted = format_size(repo.git_size).to_string();
+            repo.working_tree_size_formatted = format_size(repo.working_tree_size).to_string();
+            repo.hints = hints(&repo);
+            repo
+        })
+        .collect();
+    list.sort_unstable_by(|a, b| b.total_size.cmp(&a.total_size).then_with(|| a.path.cmp(&b.path)));
+    report(path, list)
+}
+
+fn report(path: &str, repos: Vec<GitRepo>) -> GitRepoReport {
+    let total_git_size = repos.iter().map(|r| r.git_size).sum();
+    let total_working_tree_size = repos.iter().map(|r| r.working_tree_size).sum();
+    GitRepoReport {
+        path: path.to_string(),
+        repos,
+        total_git_size,
+        total_git_size_formatted: format_size(total_git_size).to_string(),
+        total_working_tree_size,
+        total_working_tree_size_formatted: format_size(total_working_tree_size).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attributes_git_and_working_tree_sizes() {
+        let loose = format!("r/app/.git/objects/ab/{}", "c".repeat(38));
+        let items = vec![
+            Item::test("r/.git", 100, true),
+            Item::test("r/.git/HEAD", 100, false),
+            Item::test("r/app", 3 * GB + 50, true),
+            Item::test("r/app/main.rs", 50, false),
+            Item::test("r/app/.git", 3 * GB, true),
+            Item::test("r/app/.git/objects/pack/pack-1.pack", 2 * GB, false),
+            Item::test("r/app/.git/objects/pack/pack-1.idx", GB / 2, false),
+            Item::test(&loose, GB / 2, false),
+            Item::test("r/wt", 10, true),
+            Item::test("r/wt/.git", 0, false),
+            Item::test("r/wt/a.txt", 10, false),
+        ];
+        let report = analyze("r", &items);
+        let paths: Vec<_> = report.repos.iter().map(|r| r.path.as_str()).collect();
+        // 外层仓库的工作区包含嵌套仓库
+        assert_eq!(paths, vec!["r", "r/app", "r/wt"]);
+
+        let app = &report.repos[1];
+        assert_eq!((app.git_size, app.working_tree_size), (3 * GB, 50));
+        assert_eq!((app.pack_size, app.pack_count), (2 * GB, 1));
+        assert_eq!((app.loose_size, app.loose_count), (GB / 2, 1));
+        assert!(app.hints.iter().any(|h| h.contains("git gc")));
+
+        // 扫描根本身是仓库：总大小取其直接子项之和
+        let outer = &report.repos[0];
+        assert_eq!(outer.total_size, 100 + 3 * GB + 50 + 10);
+        assert_eq!(outer.working_tree_size, 3 * GB + 60);
+
+        let wt = &report.repos[2];
+        assert!(wt.linked && wt.hints.is_empty());
+        assert_eq!((wt.git_size, wt.working_tree_size), (0, 10));
+        assert_eq!(report.total_git_size, 3 * GB + 100);
+    }
+}
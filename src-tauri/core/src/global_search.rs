@@ -8,10 +8,11 @@ use std::collections::{HashMap, HashSet};
 use parking_lot::RwLock;
 use rayon::prelude::*;
 use serde::Serialize;
-use tauri::Emitter;
+use schemars::JsonSchema;
+use crate::events::EventSink;
 
 /// 索引中的一项（绝对路径）
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct IndexEntry {
     pub path: String,
@@ -27,7 +28,7 @@ pub struct IndexEntry {
 
 /// 索引就绪时的元数据（独立 struct：enum 级 rename_all 在 serde 里只作用于 variant 名，
 /// 不保证 struct variant 字段被重命名，故抽出来确保字段序列化为 camelCase）
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ReadyData {
     pub file_count: usize,
@@ -39,7 +40,7 @@ pub struct ReadyData {
 }
 
 /// 索引状态（前端据 kind 判断）
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase", tag = "kind", content = "data")]
 pub enum IndexState {
     NotLoaded,
@@ -141,7 +142,7 @@ impl GlobalIndex {
         }
         name_index
             .entry(first_char)
-            .or_insert_with(HashSet::new)
+            .or_default()
             .insert(entry.path.clone());
     }
 
@@ -257,7 +258,7 @@ impl GlobalIndex {
                 name_lower: name.to_lowercase(),
                 size: f.size as i64,
                 is_dir: f.is_dir,
-                mtime: 0,
+                mtime: if f.is_dir { 0 } else { f.modified },
             });
         }
     }
@@ -272,7 +273,7 @@ impl GlobalIndex {
                 name_lower: name.to_lowercase(),
                 size: item.size,
                 is_dir: item.is_dir,
-                mtime: 0,
+                mtime: if item.is_dir { 0 } else { item.modified },
             });
         }
 
@@ -325,7 +326,7 @@ impl GlobalIndex {
                 name_lower: name.to_lowercase(),
                 size: item.size,
                 is_dir: item.is_dir,
-                mtime: 0,
+                mtime: if item.is_dir { 0 } else { item.modified },
             });
         }
 
@@ -369,7 +370,7 @@ impl GlobalIndex {
     }
 
     /// 构建全盘索引（同步、耗时较长，调用者须在 spawn_blocking 中运行）
-    pub fn build_index(&self, app: &tauri::AppHandle) {
+    pub fn build_index(&self, events: &dyn EventSink) {
         *self.state.write() = IndexState::Loading { drive: String::new(), scanned: 0 };
         self.clear_internal();
 
@@ -390,7 +391,7 @@ impl GlobalIndex {
                 drive: drive.to_string(),
                 scanned: total_scanned,
             };
-            let _ = app.emit(
+            events.emit(
                 "global-search-progress",
                 ProgressPayload {
                     drive: drive.to_string(),
@@ -408,7 +409,7 @@ impl GlobalIndex {
                 }
                 None => {
                     failed_drives.push(drive.to_string());
-                    let _ = app.emit(
+                    events.emit(
                         "global-search-progress",
                         ProgressPayload {
                             drive: drive.to_string(),
@@ -435,7 +436,7 @@ impl GlobalIndex {
         }
         self.update_ready_state();
 
-        let _ = app.emit(
+        events.emit(
             "global-search-progress",
             ProgressPayload {
                 drive: String::new(),
@@ -548,24 +549,20 @@ fn parse_op(s: &str) -> Option<FilterOp> {
     }
 }
 
+/// 拆分比较运算符前缀（`>=100MB` → (">=", "100MB")），无前缀时使用 default
+fn split_op<'a>(s: &'a str, default: &'static str) -> (&'static str, &'a str) {
+    for op in [">=", "<=", "!=", "=", ">", "<"] {
+        if let Some(rest) = s.strip_prefix(op) {
+            return (op, rest);
+        }
+    }
+    (default, s)
+}
+
 fn parse_size(value: &str) -> Option<(FilterOp, i64)> {
     let s = value.trim();
     if s.is_empty() { return None; }
-    let (op_str, rest) = if s.starts_with(">=") {
-        (">=", &s[2..])
-    } else if s.starts_with("<=") {
-        ("<=", &s[2..])
-    } else if s.starts_with("!=") {
-        ("!=", &s[2..])
-    } else if s.starts_with('=') {
-        ("=", &s[1..])
-    } else if s.starts_with('>') {
-        (">", &s[1..])
-    } else if s.starts_with('<') {
-        ("<", &s[1..])
-    } else {
-        (">=", s)
-    };
+    let (op_str, rest) = split_op(s, ">=");
     let op = parse_op(op_str)?;
     let rest = rest.trim();
 
@@ -594,21 +591,7 @@ fn parse_size(value: &str) -> Option<(FilterOp, i64)> {
 fn parse_mtime(value: &str) -> Option<(FilterOp, i64)> {
     let s = value.trim();
     if s.is_empty() { return None; }
-    let (op_str, rest) = if s.starts_with(">=") {
-        (">=", &s[2..])
-    } else if s.starts_with("<=") {
-        ("<=", &s[2..])
-    } else if s.starts_with("!=") {
-        ("!=", &s[2..])
-    } else if s.starts_with('=') {
-        ("=", &s[1..])
-    } else if s.starts_with('>') {
-        (">", &s[1..])
-    } else if s.starts_with('<') {
-        ("<", &s[1..])
-    } else {
-        ("<=", s)
-    };
+    let (op_str, rest) = split_op(s, "<=");
     let op = parse_op(op_str)?;
     let rest = rest.trim();
 
@@ -657,6 +640,27 @@ fn split_filter_tokens(input: &str) -> Vec<String> {
     tokens
 }
 
+/// 检查搜索语法：引号配对、size: / mtime: 的值可解析。
+/// parse_search_filter 会静默丢弃无法解析的条件，命令入口先用本函数给出明确的错误
+pub fn check_search_filter(input: &str) -> Result<(), String> {
+    if input.chars().filter(|&c| c == '"').count() % 2 != 0 {
+        return Err("引号未配对".to_string());
+    }
+    for token in split_filter_tokens(input) {
+        if let Some((key, value)) = token.split_once(':') {
+            let parsed = match key.to_lowercase().as_str() {
+                "size" => parse_size(value).is_some(),
+                "mtime" => parse_mtime(value).is_some(),
+                _ => true,
+            };
+            if !parsed {
+                return Err(format!("无法解析条件 {}", token));
+            }
+        }
+    }
+    Ok(())
+}
+
 pub fn parse_search_filter(input: &str) -> Vec<SearchFilter> {
     let mut filters = Vec::new();
     let mut text_parts = Vec::new();
@@ -0,0 +1,114 @@
+// 命令防重入与节流
+//
+// 前端循环出错（effect 依赖写错、定时器未清理）时可能每帧调用同一个命令：
+// 昂贵或破坏性的命令（清空缓存、全盘扫描、重建索引）同一时间只允许一个在执行，
+// 轮询类命令（性能指标、系统信息）在最小间隔内直接返回上次结果，不重复采集。
+
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+lazy_static! {
+    static ref GUARD: CommandGuard = CommandGuard::default();
+}
+
+/// 同名命令正在执行
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Busy {
+    pub command: &'static str,
+}
+
+impl fmt::Display for Busy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} 正在执行，请稍候", self.command)
+    }
+}
+
+impl std::error::Error for Busy {}
+
+type Cached = (Instant, Arc<dyn Any + Send + Sync>);
+
+#[derive(Default)]
+pub struct CommandGuard {
+    running: Mutex<HashSet<&'static str>>,
+    throttled: Mutex<HashMap<&'static str, Cached>>,
+}
+
+/// 独占执行守卫，离开作用域时释放
+pub struct Exclusive<'a> {
+    guard: &'a CommandGuard,
+    command: &'static str,
+}
+
+impl Drop for Exclusive<'_> {
+    fn drop(&mut self) {
+        self.guard.running.lock().remove(self.command);
+    }
+}
+
+impl CommandGuard {
+    pub fn instance() -> &'static CommandGuard {
+        &GUARD
+    }
+
+    /// 独占执行：同名命令尚未结束时返回 Busy，而不是排队再执行一遍
+    pub fn exclusive(&self, command: &'static str) -> Result<Exclusive<'_>, Busy> {
+        if self.running.lock().insert(command) {
+            Ok(Exclusive { guard: self, command })
+        } else {
+            Err(Busy { command })
+        }
+    }
+
+    /// 节流：距上次实际执行不足 interval 时返回上次的结果
+    pub fn throttle<T, F>(&self, command: &'static str, interval: Duration, f: F) -> T
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce() -> T,
+    {
+        if let Some((at, value)) = self.throttled.lock().get(command) {
+            if at.elapsed() < interval {
+                if let Some(value) = value.downcast_ref::<T>() {
+                    return value.clone();
+                }
+            }
+        }
+        // 执行期间不持锁，其他节流命令不受影响
+        let value = f();
+        self.throttled
+            .lock()
+            .insert(command, (Instant::now(), Arc::new(value.clone())));
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_reentry_and_reuses_recent_results() {
+        let guard = CommandGuard::default();
+
+        let held = guard.exclusive("clear_disk_cache").unwrap();
+        assert_eq!(guard.exclusive("clear_disk_cache").err(), Some(Busy { command: "clear_disk_cache" }));
+        assert!(guard.exclusive("clear_history").is_ok());
+        drop(held);
+        assert!(guard.exclusive("clear_disk_cache").is_ok());
+
+        let mut calls = 0;
+        let mut poll = |interval| {
+            guard.throttle("metrics", interval, || {
+                calls += 1;
+                calls
+            })
+        };
+        assert_eq!(poll(Duration::from_secs(60)), 1);
+        assert_eq!(poll(Duration::from_secs(60)), 1);
+        assert_eq!(poll(Duration::ZERO), 2);
+    }
+}
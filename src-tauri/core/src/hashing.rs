@@ -0,0 +1,194 @@
+// 文件内容哈希
+//
+// 分块读取（1MB）计算 BLAKE3 / SHA-256，rayon 并行处理多个文件，
+// 按字节数节流上报进度，用于文件校验或交给外部去重流程。
+
+use parking_lot::Mutex;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use std::io::Read;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+const CHUNK_SIZE: usize = 1024 * 1024;
+/// 进度上报最小间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum HashAlgorithm {
+    Blake3,
+    Sha256,
+}
+
+/// 单个文件的哈希结果；失败时 hash 为 None、error 为原因
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileHash {
+    pub path: String,
+    pub size: u64,
+    pub hash: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HashProgress {
+    pub processed_files: u64,
+    pub total_files: u64,
+    pub processed_bytes: u64,
+    pub total_bytes: u64,
+}
+
+enum Hasher {
+    Blake3(Box<blake3::Hasher>),
+    Sha256(sha2::Sha256),
+}
+
+impl Hasher {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Blake3 => Hasher::Blake3(Box::new(blake3::Hasher::new())),
+            HashAlgorithm::Sha256 => Hasher::Sha256(<sha2::Sha256 as sha2::Digest>::new()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Blake3(h) => {
+                h.update(data);
+            }
+            Hasher::Sha256(h) => sha2::Digest::update(h, data),
+        }
+    }
+
+    fn finalize_hex(self) -> String {
+        match self {
+            Hasher::Blake3(h) => h.finalize().to_hex().to_string(),
+            Hasher::Sha256(h) => sha2::Digest::finalize(h)
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect(),
+        }
+    }
+}
+
+/// 分块计算单个文件的哈希；每读完一块调用 `on_chunk(字节数)`
+pub fn hash_file(
+    path: &str,
+    algorithm: HashAlgorithm,
+    mut on_chunk: impl FnMut(u64),
+) -> std::io::Result<(String, u64)> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Hasher::new(algorithm);
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut total = 0u64;
+
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        total += n as u64;
+        on_chunk(n as u64);
+    }
+
+    Ok((hasher.finalize_hex(), total))
+}
+
+/// 并行计算多个文件的哈希，结果与 `paths` 顺序一致；
+/// `on_progress` 最多每 200ms 调用一次，结束时保证调用一次
+pub fn hash_files(
+    paths: &[String],
+    algorithm: HashAlgorithm,
+    on_progress: impl Fn(HashProgress) + Sync,
+) -> Vec<FileHash> {
+    let total_bytes: u64 = paths
+        .iter()
+        .filter_map(|p| std::fs::metadata(p).ok())
+        .map(|m| m.len())
+        .sum();
+    let total_files = paths.len() as u64;
+    let processed_files = AtomicU64::new(0);
+    let processed_bytes = AtomicU64::new(0);
+    let last_report = Mutex::new(Instant::now());
+
+    let progress = || HashProgress {
+        processed_files: processed_files.load(Ordering::Relaxed),
+        total_files,
+        processed_bytes: processed_bytes.load(Ordering::Relaxed),
+        total_bytes,
+    };
+    let maybe_report = || {
+        if let Some(mut last) = last_report.try_lock() {
+            if last.elapsed() >= PROGRESS_INTERVAL {
+                *last = Instant::now();
+                on_progress(progress());
+            }
+        }
+    };
+
+    let results = paths
+        .par_iter()
+        .map(|path| {
+            let outcome = hash_file(path, algorithm, |n| {
+                processed_bytes.fetch_add(n, Ordering::Relaxed);
+                maybe_report();
+            });
+            processed_files.fetch_add(1, Ordering::Relaxed);
+            maybe_report();
+
+            match outcome {
+                Ok((hash, size)) => FileHash {
+                    path: path.clone(),
+                    size,
+                    hash: Some(hash),
+                    error: None,
+                },
+                Err(e) => FileHash {
+                    path: path.clone(),
+                    size: 0,
+                    hash: None,
+                    error: Some(e.to_string()),
+                },
+            }
+        })
+        .collect();
+
+    on_progress(progress());
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_match_known_digests() {
+        let dir = std::env::temp_dir().join(format!("flashdir-hash-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("abc.txt");
+        std::fs::write(&file, b"abc").unwrap();
+        let path = file.to_string_lossy().to_string();
+        let missing = dir.join("missing").to_string_lossy().to_string();
+
+        let calls = AtomicU64::new(0);
+        let results = hash_files(&[path.clone(), missing], HashAlgorithm::Sha256, |_| {
+            calls.fetch_add(1, Ordering::Relaxed);
+        });
+        assert_eq!(
+            results[0].hash.as_deref(),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert!(results[1].hash.is_none() && results[1].error.is_some());
+        assert!(calls.load(Ordering::Relaxed) >= 1);
+
+        let (blake, size) = hash_file(&path, HashAlgorithm::Blake3, |_| {}).unwrap();
+        assert_eq!(size, 3);
+        assert_eq!(blake, blake3::hash(b"abc").to_hex().to_string());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,250 @@
+// 空闲时自动刷新收藏路径
+//
+// 用户离开电脑（无键盘 / 鼠标输入超过 idleMinutes）且系统 CPU 不高于 maxCpuPercent 时，
+// 以后台模式（低 CPU / I/O 优先级）强制重扫收藏的路径，写入内存 / 磁盘缓存，
+// 用户回来打开应用时即可直接命中缓存。
+//
+// 每次检查最多扫描一个路径（优先最久未刷新的），扫描期间用户回来也只影响这一次；
+// 同一路径两次刷新至少间隔 refreshMinutes；静默时段内不扫描。
+// 取不到输入空闲时长（如 Linux 未安装 xprintidle）时只按 CPU 负载判断。
+// 配置见 settings.idle，默认关闭。
+//
+// 同样的空闲条件下（不要求开启空闲扫描），磁盘缓存数据库的空闲页达到 cache.compactMinFreeMb 时
+// 先压缩数据库（见 DiskCache::compact），与手动的 compact_disk_cache 互斥。由 cache.compactWhenIdle 控制，默认开启。
+
+use crate::events::Events;
+use crate::settings::{IdleScanSettings, Settings};
+use chrono::Timelike;
+use lazy_static::lazy_static;
+use parking_lot::RwLock;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// 空闲扫描完成事件
+pub const IDLE_SCAN_EVENT: &str = "idle-scan-complete";
+
+/// 空闲时压缩磁盘缓存完成事件
+pub const IDLE_COMPACT_EVENT: &str = "idle-compact-complete";
+
+/// 空闲检查间隔
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+lazy_static! {
+    /// 路径 → 上次空闲刷新时间（Unix 秒），仅本进程内
+    static ref LAST_REFRESH: RwLock<HashMap<String, i64>> = RwLock::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IdleScanEvent {
+    pub path: String,
+    pub started_at: i64,
+    pub duration_ms: u64,
+    pub success: bool,
+    pub total_size: i64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IdleStatus {
+    pub settings: IdleScanSettings,
+    /// 当前输入空闲秒数；平台不支持时为 None
+    pub user_idle_secs: Option<u64>,
+    pub system_cpu_percent: f64,
+    pub in_quiet_hours: bool,
+    /// 按当前状态，下一次检查是否会扫描
+    pub ready: bool,
+    /// 下一次将刷新的收藏路径
+    pub next_path: Option<String>,
+    /// 路径 → 上次空闲刷新时间（Unix 秒）
+    pub last_refresh: HashMap<String, i64>,
+}
+
+/// 是否满足空闲扫描条件。minute_of_day 为本地时间的一天中第几分钟
+pub fn idle_ready(settings: &IdleScanSettings, idle_secs: Option<u64>, cpu_percent: f64, minute_of_day: u32) -> bool {
+    if !settings.enabled {
+        return false;
+    }
+    if settings.quiet_hours.as_ref().is_some_and(|q| q.contains(minute_of_day)) {
+        return false;
+    }
+    if idle_secs.is_some_and(|secs| secs < settings.idle_minutes * 60) {
+        return false;
+    }
+    cpu_percent <= settings.max_cpu_percent as f64
+}
+
+/// 选出已到刷新间隔、且最久未刷新的收藏路径（从未刷新的优先，同等时按收藏顺序）
+pub fn next_path<'a>(favorites: &'a [String], last: &HashMap<String, i64>, now: i64, refresh_secs: i64) -> Option<&'a str> {
+    favorites
+        .iter()
+        .map(|p| (p, last.get(p).copied()))
+        .filter(|(_, t)| t.is_none_or(|t| now - t >= refresh_secs))
+        .min_by_key(|(_, t)| t.unwrap_or(i64::MIN))
+        .map(|(p, _)| p.as_str())
+}
+
+fn minute_of_day() -> u32 {
+    let now = chrono::Local::now();
+    now.hour() * 60 + now.minute()
+}
+
+fn favorites() -> Vec<String> {
+    crate::disk_cache::DiskCache::instance().list_favorites().unwrap_or_else(|e| {
+        eprintln!("[IdleScan] 读取收藏列表失败: {}", e);
+        Vec::new()
+    })
+}
+
+/// 当前空闲状态（采样 CPU 需约 200ms，阻塞调用）
+pub fn status() -> IdleStatus {
+    let settings = Settings::current().idle;
+    let user_idle_secs = crate::fs::user_idle_secs();
+    let system_cpu_percent = crate::perf::system_cpu_percent();
+    let minute = minute_of_day();
+    let last_refresh = LAST_REFRESH.read().clone();
+    let favorites = favorites();
+    IdleStatus {
+        in_quiet_hours: settings.quiet_hours.as_ref().is_some_and(|q| q.contains(minute)),
+        ready: idle_ready(&settings, user_idle_secs, system_cpu_percent, minute),
+        next_path: next_path(&favorites, &last_refresh, chrono::Utc::now().timestamp(), settings.refresh_minutes as i64 * 60)
+            .map(str::to_string),
+        settings,
+        user_idle_secs,
+        system_cpu_percent,
+        last_refresh,
+    }
+}
+
+/// 条件满足时刷新一个收藏路径；未扫描时返回 None
+pub async fn run_once(events: Option<&Events>) -> Option<IdleScanEvent> {
+    let settings = Settings::current().idle;
+    if !settings.enabled {
+        return None;
+    }
+    let (idle_secs, cpu) = tokio::task::spawn_blocking(|| (crate::fs::user_idle_secs(), crate::perf::system_cpu_percent()))
+        .await
+        .ok()?;
+    if !idle_ready(&settings, idle_secs, cpu, minute_of_day()) {
+        return None;
+    }
+    let favorites = tokio::task::spawn_blocking(favorites).await.ok()?;
+    let started_at = chrono::Utc::now().timestamp();
+    let path = next_path(&favorites, &LAST_REFRESH.read(), started_at, settings.refresh_minutes as i64 * 60)?.to_string();
+
+    let start = std::time::Instant::now();
+    let options = crate::scan::ScanOptions { background: true, ..Default::default() };
+    let outcome = crate::scan::scan_directory_with_options(
+        &path,
+        true,
+        &options,
+        crate::perf::PerformanceMonitor::instance(),
+        None,
+    )
+    .await;
+    // 失败也记录时间，避免不可访问的路径每分钟重试
+    LAST_REFRESH.write().insert(path.clone(), chrono::Utc::now().timestamp());
+
+    let event = IdleScanEvent {
+        path,
+        started_at,
+        duration_ms: start.elapsed().as_millis() as u64,
+        success: outcome.is_ok(),
+        total_size: outcome.as_ref().map(|r| r.total_size).unwrap_or(0),
+        error: outcome.err().map(|e| e.to_string()),
+    };
+    if let Some(error) = &event.error {
+        eprintln!("[IdleScan] 扫描 {} 失败: {}", event.path, error);
+    }
+    if let Some(events) = events {
+        events.emit(IDLE_SCAN_EVENT, &event);
+    }
+    Some(event)
+}
+
+/// 条件满足时压缩磁盘缓存；未压缩时返回 None
+pub async fn compact_once(events: Option<&Events>) -> Option<crate::disk_cache::CompactResult> {
+    let settings = Settings::current();
+    if !settings.cache.compact_when_idle {
+        return None;
+    }
+    let (idle_secs, cpu) = tokio::task::spawn_blocking(|| (crate::fs::user_idle_secs(), crate::perf::system_cpu_percent()))
+        .await
+        .ok()?;
+    let conditions = IdleScanSettings { enabled: true, ..settings.idle };
+    if !idle_ready(&conditions, idle_secs, cpu, minute_of_day()) {
+        return None;
+    }
+    let min_free = settings.cache.compact_min_free_mb * 1024 * 1024;
+    let outcome = tokio::task::spawn_blocking(move || {
+        // 手动压缩进行中时跳过
+        let _running = crate::guard::CommandGuard::instance().exclusive("compact_disk_cache").ok()?;
+        let cache = crate::disk_cache::DiskCache::instance();
+        if cache.free_bytes() < min_free {
+            return None;
+        }
+        Some(cache.compact(&crate::operation::Operation::start("compact_disk_cache", None, None)))
+    })
+    .await
+    .ok()??;
+
+    match outcome {
+        Ok(result) => {
+            eprintln!(
+                "[IdleScan] 压缩磁盘缓存，回收 {}（{} ms）",
+                crate::scan::format_size(result.reclaimed_bytes as i64),
+                result.duration_ms
+            );
+            if let Some(events) = events {
+                events.emit(IDLE_COMPACT_EVENT, &result);
+            }
+            Some(result)
+        }
+        Err(e) => {
+            eprintln!("[IdleScan] 压缩磁盘缓存失败: {}", e);
+            None
+        }
+    }
+}
+
+/// 定时检查空闲状态（常驻，随进程退出）
+pub async fn run_loop(events: Option<Events>) {
+    let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        ticker.tick().await;
+        compact_once(events.as_ref()).await;
+        run_once(events.as_ref()).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::QuietHours;
+
+    #[test]
+    fn checks_idle_conditions_and_picks_stalest_favorite() {
+        let mut settings = IdleScanSettings { enabled: true, ..Default::default() };
+        assert!(idle_ready(&settings, Some(600), 10.0, 12 * 60));
+        assert!(!idle_ready(&settings, Some(599), 10.0, 12 * 60));
+        assert!(!idle_ready(&settings, Some(600), 30.0, 12 * 60));
+        // 取不到输入空闲时长时只看 CPU
+        assert!(idle_ready(&settings, None, 10.0, 12 * 60));
+
+        settings.quiet_hours = Some(QuietHours { start: "22:00".into(), end: "07:00".into() });
+        assert!(!idle_ready(&settings, Some(3600), 0.0, 23 * 60));
+        assert!(idle_ready(&settings, Some(3600), 0.0, 7 * 60));
+        settings.enabled = false;
+        assert!(!idle_ready(&settings, Some(3600), 0.0, 12 * 60));
+
+        let favorites = vec!["/a".to_string(), "/b".to_string(), "/c".to_string()];
+        let mut last = HashMap::from([("/a".to_string(), 1000), ("/b".to_string(), 500)]);
+        assert_eq!(next_path(&favorites, &last, 4000, 3600), Some("/c"));
+        last.insert("/c".to_string(), 3900);
+        assert_eq!(next_path(&favorites, &last, 4200, 3600), Some("/b"));
+        assert_eq!(next_path(&favorites, &last, 4000, 3600), None);
+    }
+}
@@ -0,0 +1,150 @@
+// 包含过滤（glob）：只统计匹配的文件
+//
+// 与排除预设互补：例如 `*.mp4;*.mkv` 只扫描整盘的视频文件。过滤只作用于文件，
+// 目录照常遍历并保留为条目，但其大小与文件数只累计匹配的文件。
+//
+// 模式语法：`*` 匹配任意字符序列（含 `/`），`?` 匹配单个字符，不区分 ASCII 大小写。
+// 不含 `/` 的模式匹配文件名，含 `/` 的模式匹配规范化后的绝对路径（正斜杠）。
+// 一个选项值内可用 `;` 分隔多个模式，任一模式匹配即包含。
+//
+// 过滤后的结果与完整结果不同，规范化后的模式记录在缓存 key 中（见 IncludeFilter::cache_key）。
+
+use std::sync::Arc;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Pattern {
+    /// 小写后的模式字节
+    glob: Box<[u8]>,
+    /// 含 `/`：匹配完整路径，否则匹配文件名
+    full_path: bool,
+}
+
+/// 一次扫描的包含模式集合（空 = 不过滤），克隆开销为一次引用计数
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IncludeFilter {
+    patterns: Arc<[Pattern]>,
+}
+
+impl IncludeFilter {
+    /// 解析选项中的模式：按 `;` 拆分、去空白、统一分隔符与大小写，去重后排序
+    pub fn new(globs: &[String]) -> Self {
+        let mut normalized: Vec<String> = globs
+            .iter()
+            .flat_map(|g| g.split(';'))
+            .map(|g| g.trim().replace('\\', "/").to_ascii_lowercase())
+            .filter(|g| !g.is_empty())
+            .collect();
+        normalized.sort_unstable();
+        normalized.dedup();
+        Self {
+            patterns: normalized
+                .into_iter()
+                .map(|g| Pattern { full_path: g.contains('/'), glob: g.into_bytes().into_boxed_slice() })
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// 文件是否计入结果（path 为规范化后的绝对路径）；未设置模式时恒为 true
+    #[inline]
+    pub fn includes_file(&self, path: &str, name: &str) -> bool {
+        self.is_empty()
+            || self.patterns.iter().any(|p| {
+                let text = if p.full_path { path } else { name };
+                glob_match(&p.glob, text.as_bytes())
+            })
+    }
+
+    /// 在缓存 key 后附加规范化的模式（顺序、大小写、重复不影响 key）；前缀仍是根路径
+    pub fn cache_key(&self, key: String) -> String {
+        if self.is_empty() {
+            return key;
+        }
+        let globs: Vec<&str> =
+            self.patterns.iter().map(|p| std::str::from_utf8(&p.glob).unwrap_or_default()).collect();
+        format!("{}|include={}", key, globs.join(";"))
+    }
+}
+
+/// UTF-8 首字节对应的字符字节数（`?` 与 `*` 回溯按字符前进）
+#[inline]
+fn char_width(lead: u8) -> usize {
+    match lead {
+        0xF0.. => 4,
+        0xE0.. => 3,
+        0xC0.. => 2,
+        _ => 1,
+    }
+}
+
+/// 通配符匹配（glob 已小写，text 按 ASCII 折叠大小写比较），`*` 回溯为线性贪心
+fn glob_match(glob: &[u8], text: &[u8]) -> bool {
+    let (mut g, mut t) = (0, 0);
+    // 最近一个 `*` 之后的模式位置，以及它当前吞到的文本位置
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match glob.get(g) {
+            Some(b'*') => {
+                star = Some((g + 1, t));
+                g += 1;
+                continue;
+            }
+            Some(b'?') => {
+                g += 1;
+                t += char_width(text[t]);
+                continue;
+            }
+            Some(&c) if c == text[t].to_ascii_lowercase() => {
+                g += 1;
+                t += 1;
+                continue;
+            }
+            _ => {}
+        }
+        let Some((star_g, star_t)) = star else { return false };
+        let next = star_t + char_width(text[star_t]);
+        star = Some((star_g, next));
+        g = star_g;
+        t = next;
+    }
+    glob[g.min(glob.len())..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(globs: &[&str]) -> IncludeFilter {
+        IncludeFilter::new(&globs.iter().map(|g| g.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn matches_names_and_paths_case_insensitively() {
+        let media = filter(&["*.mp4; *.MKV", ""]);
+        assert!(media.includes_file("D:/v/Movie.MP4", "Movie.MP4"));
+        assert!(media.includes_file("D:/v/a.mkv", "a.mkv"));
+        assert!(!media.includes_file("D:/v/a.mp4.part", "a.mp4.part"));
+        assert!(!media.includes_file("D:/v/notes.txt", "notes.txt"));
+
+        let by_path = filter(&["*/photos/*.jp?g", "img_????.png"]);
+        assert!(by_path.includes_file("C:/Users/me/Photos/2024/a.jpeg", "a.jpeg"));
+        assert!(!by_path.includes_file("C:/Users/me/Docs/a.jpeg", "a.jpeg"));
+        assert!(by_path.includes_file("C:/x/IMG_0042.png", "IMG_0042.png"));
+        assert!(by_path.includes_file("C:/x/img_照片一张.png", "img_照片一张.png"));
+        assert!(!by_path.includes_file("C:/x/img_42.png", "img_42.png"));
+
+        assert!(IncludeFilter::default().includes_file("/a/b", "b"));
+    }
+
+    #[test]
+    fn cache_key_ignores_order_case_and_duplicates() {
+        let a = filter(&["*.mkv;*.mp4"]).cache_key("d:/media".to_string());
+        let b = filter(&["*.MP4", "*.mkv", "*.mp4"]).cache_key("d:/media".to_string());
+        assert_eq!(a, "d:/media|include=*.mkv;*.mp4");
+        assert_eq!(a, b);
+        assert_eq!(IncludeFilter::default().cache_key("d:/media".to_string()), "d:/media");
+    }
+}
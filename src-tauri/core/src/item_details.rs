@@ -0,0 +1,176 @@
+// 单个条目的详细属性：前端“属性”对话框所需的全部元数据
+//
+// 时间戳、属性、所有者、链接目标、附加数据流、分配 / 压缩后大小直接读取文件系统；
+// 目录的直接子项数读取一层目录得到，子树大小与递归文件数 / 目录数取自扫描缓存
+// （路径所在的会话或以它为根的会话），不触发重新扫描，未扫描过时为 None。
+// 链接类条目不跟随，描述的是链接本身。
+
+use crate::fs::{DataStream, ItemKind};
+use crate::scan::format_size;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemDetails {
+    /// 规范展示形式
+    pub path: String,
+    pub name: String,
+    pub kind: ItemKind,
+    pub is_dir: bool,
+    /// 文件为逻辑大小；目录为扫描缓存中的子树大小，未扫描时为 None
+    pub size: Option<i64>,
+    pub size_formatted: Option<String>,
+    /// 实际分配的磁盘空间（文件；目录为目录项本身的占用）
+    pub allocated_size: Option<u64>,
+    /// 透明压缩 / 稀疏文件压缩后的占用；未压缩时为 None
+    pub compressed_size: Option<u64>,
+    /// 创建、修改、最后访问时间（Unix 秒）；文件系统不提供时为 None
+    pub created: Option<i64>,
+    pub modified: Option<i64>,
+    pub accessed: Option<i64>,
+    /// 最后访问时间是否可信（系统关闭了访问时间更新时为 false）
+    pub accessed_reliable: bool,
+    /// 属性名（readOnly、hidden、system、compressed、encrypted、sparse 等）
+    pub attributes: Vec<String>,
+    /// Unix 权限位（rwxr-xr-x）
+    pub permissions: Option<String>,
+    pub owner: Option<String>,
+    /// 符号链接 / 联接的目标
+    pub link_target: Option<String>,
+    pub hard_links: Option<u64>,
+    /// NTFS 备用数据流；Unix 为扩展属性
+    pub streams: Vec<DataStream>,
+    /// 目录的子项统计；文件为 None
+    pub children: Option<ChildCounts>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChildCounts {
+    /// 直接子项中的文件数与目录数
+    pub files: u64,
+    pub dirs: u64,
+    /// 子树中的文件数与目录数（递归，取自扫描缓存；未扫描时为 None）
+    pub total_files: Option<u64>,
+    pub total_dirs: Option<u64>,
+}
+
+/// 读取 path 的详细属性；path 不存在或无法读取元数据时返回错误
+pub fn get_item_details(path: &str) -> anyhow::Result<ItemDetails> {
+    let native_path = crate::fs::to_extended_length(path);
+    let meta = std::fs::symlink_metadata(&native_path)
+        .map_err(|e| anyhow::anyhow!("无法读取属性 {}: {}", path, e))?;
+    let display = crate::scan::canonical_display_path(path).unwrap_or_else(|| crate::fs::display_path(path));
+    let native = crate::fs::native_details(&native_path, &meta);
+    let is_dir = meta.is_dir();
+
+    let cached = if is_dir { cached_totals(&display) } else { None };
+    let size = if is_dir { cached.map(|(size, _, _)| size) } else { Some(meta.len() as i64) };
+    let children = is_dir.then(|| {
+        let (files, dirs) = direct_children(&native_path);
+        ChildCounts {
+            files,
+            dirs,
+            total_files: cached.map(|(_, files, _)| files),
+            total_dirs: cached.map(|(_, _, dirs)| dirs),
+        }
+    });
+    let link_target = (meta.file_type().is_symlink() || native.kind == ItemKind::Junction)
+        .then(|| std::fs::read_link(&native_path).ok())
+        .flatten()
+        .map(|target| crate::fs::display_path(&target.to_string_lossy()));
+
+    Ok(ItemDetails {
+        name: Path::new(&display)
+            .file_name()
+            .map_or_else(|| display.clone(), |n| n.to_string_lossy().into_owned()),
+        kind: native.kind,
+        is_dir,
+        size,
+        size_formatted: size.map(|s| format_size(s).to_string()),
+        allocated_size: native.allocated_size,
+        compressed_size: native.compressed_size,
+        created: meta.created().ok().map(unix_secs),
+        modified: meta.modified().ok().map(unix_secs),
+        accessed: meta.accessed().ok().map(unix_secs),
+        accessed_reliable: crate::fs::last_access_updates().is_reliable(),
+        attributes: native.attributes,
+        permissions: native.permissions,
+        owner: crate::fs::OwnerResolver::new().owner_of(&native_path.to_string_lossy()),
+        link_target,
+        hard_links: native.hard_links,
+        streams: native.streams,
+        children,
+        path: display,
+    })
+}
+
+fn unix_secs(t: std::time::SystemTime) -> i64 {
+    match t.duration_since(std::time::UNIX_EPOCH) {
+        Ok(d) => d.as_secs() as i64,
+        Err(e) => -(e.duration().as_secs() as i64),
+    }
+}
+
+/// 直接子项中的 (文件数, 目录数)；链接类条目按文件计。无法读取目录时为 (0, 0)
+fn direct_children(dir: &Path) -> (u64, u64) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return (0, 0);
+    };
+    entries.flatten().fold((0, 0), |(files, dirs), entry| {
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            (files, dirs + 1)
+        } else {
+            (files + 1, dirs)
+        }
+    })
+}
+
+/// 目录在扫描缓存中的 (子树大小, 文件数, 子目录数)：先在包含它的会话中查找条目，
+/// 它本身是扫描根时由会话 items 汇总
+fn cached_totals(display: &str) -> Option<(i64, u64, u64)> {
+    if let Some((display, items)) = crate::scan::cached_items_containing(display) {
+        if let Some(item) = items.iter().find(|i| i.path == display.as_str()) {
+            return Some((item.size, item.file_count, item.dir_count));
+        }
+    }
+    let items = crate::scan::get_cached_items(display)?;
+    let (size, files, dirs) = items.iter().fold((0i64, 0u64, 0u64), |(size, files, dirs), item| {
+        if item.is_dir {
+            (size, files, dirs + 1)
+        } else {
+            (size + item.size, files + 1, dirs)
+        }
+    });
+    Some((size, files, dirs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_file_and_directory_details() {
+        let dir = std::env::temp_dir().join(format!("flashdir_details_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        std::fs::write(dir.join("a.txt"), b"hello").unwrap();
+        std::fs::write(dir.join("sub/b.txt"), b"x").unwrap();
+
+        let file = get_item_details(&dir.join("a.txt").to_string_lossy()).unwrap();
+        assert_eq!(file.name, "a.txt");
+        assert_eq!(file.kind, ItemKind::File);
+        assert_eq!(file.size, Some(5));
+        assert!(file.modified.is_some());
+        assert!(file.children.is_none());
+
+        let folder = get_item_details(&dir.to_string_lossy()).unwrap();
+        assert!(folder.is_dir);
+        let children = folder.children.unwrap();
+        assert_eq!((children.files, children.dirs), (1, 1));
+
+        assert!(get_item_details(&dir.join("missing").to_string_lossy()).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,373 @@
+// 文件操作队列：移入回收站 / 永久删除 / 移动 / 复制 作业
+//
+// enqueue 校验参数后立即返回作业，作业按入队顺序执行；同时执行的作业数由设置 fileOps.maxParallel
+// 决定（默认 1，即逐个执行）。每个作业以自己的 ID 登记为可取消的 Operation：执行中发出
+// "operation-progress"（id 为作业 ID），状态变化（入队 / 开始 / 结束 / 取消）时发出 JOB_EVENT，负载为 Job。
+//
+// 队列保存在 ~/.flashdir/jobs.json。应用重启后，排队中与执行到一半的作业重新排队，
+// 调用 resume 后继续执行：已删除 / 已移走的路径记为"路径不存在"，移动 / 复制按原冲突策略处理
+// 已经写到目标的条目。已结束的作业保留最近 MAX_FINISHED 个供查询。
+//
+// 永久删除作业在入队时核销 issue_delete_token 签发的令牌，令牌不写入队列文件。
+
+use crate::events::Events;
+use crate::file_ops::{ConflictPolicy, FileOpResult, TransferResult};
+use crate::operation::Operation;
+use crate::validate::{self, Validated};
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const JOBS_FILE: &str = "jobs.json";
+
+/// 作业状态变化事件
+pub const JOB_EVENT: &str = "job-updated";
+
+/// 保留的已结束作业数
+const MAX_FINISHED: usize = 100;
+
+lazy_static! {
+    static ref QUEUE: Mutex<Queue> = Mutex::new(Queue {
+        jobs: jobs_path().map(|p| restore(load_from(&p))).unwrap_or_default(),
+        running: 0,
+        events: None,
+    });
+}
+
+struct Queue {
+    jobs: Vec<Job>,
+    /// 执行中的作业数
+    running: usize,
+    /// 最近一次 enqueue / resume 传入的事件接收方
+    events: Option<Events>,
+}
+
+/// 作业内容
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum JobSpec {
+    /// 移入回收站
+    Trash { paths: Vec<String> },
+    /// 永久删除；token 为 issue_delete_token 签发的确认令牌，只在入队时使用
+    Delete {
+        paths: Vec<String>,
+        #[serde(default, skip_serializing)]
+        token: String,
+    },
+    Move {
+        paths: Vec<String>,
+        dest: String,
+        #[serde(default)]
+        conflict: ConflictPolicy,
+    },
+    Copy {
+        paths: Vec<String>,
+        dest: String,
+        #[serde(default)]
+        conflict: ConflictPolicy,
+    },
+}
+
+impl JobSpec {
+    /// 对应的命令名（operation-progress 事件的 operation 字段）
+    pub fn operation_name(&self) -> &'static str {
+        match self {
+            JobSpec::Trash { .. } => "delete_to_trash",
+            JobSpec::Delete { .. } => "delete_permanently",
+            JobSpec::Move { .. } => "move_items",
+            JobSpec::Copy { .. } => "copy_items",
+        }
+    }
+
+    /// 校验路径并规范化；永久删除作业在此核销确认令牌
+    fn validated(self) -> Validated<JobSpec> {
+        Ok(match self {
+            JobSpec::Trash { paths } => JobSpec::Trash { paths: validate::absolute_paths("paths", &paths)? },
+            JobSpec::Delete { paths, token } => {
+                let paths = validate::absolute_paths("paths", &paths)?;
+                crate::file_ops::take_delete_token(&token, &paths)?;
+                JobSpec::Delete { paths, token: String::new() }
+            }
+            JobSpec::Move { paths, dest, conflict } => JobSpec::Move {
+                paths: validate::absolute_paths("paths", &paths)?,
+                dest: validate::existing_dir("dest", &dest)?,
+                conflict,
+            },
+            JobSpec::Copy { paths, dest, conflict } => JobSpec::Copy {
+                paths: validate::absolute_paths("paths", &paths)?,
+                dest: validate::existing_dir("dest", &dest)?,
+                conflict,
+            },
+        })
+    }
+
+    fn run(&self, op: &Operation) -> JobOutcome {
+        match self {
+            JobSpec::Trash { paths } => JobOutcome::Removed(crate::file_ops::delete_to_trash_with(paths, op)),
+            JobSpec::Delete { paths, .. } => JobOutcome::Removed(crate::file_ops::delete_confirmed(paths, op)),
+            JobSpec::Move { paths, dest, conflict } => {
+                JobOutcome::Transferred(crate::file_ops::move_items(paths, dest, *conflict, op))
+            }
+            JobSpec::Copy { paths, dest, conflict } => {
+                JobOutcome::Transferred(crate::file_ops::copy_items(paths, dest, *conflict, op))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum JobState {
+    Queued,
+    Running,
+    /// 全部条目成功（或因冲突跳过）
+    Completed,
+    /// 有条目失败，或作业异常终止
+    Failed,
+    Cancelled,
+}
+
+impl JobState {
+    pub fn is_finished(self) -> bool {
+        matches!(self, JobState::Completed | JobState::Failed | JobState::Cancelled)
+    }
+}
+
+/// 作业结果（结构与对应的单次命令相同）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum JobOutcome {
+    Removed(FileOpResult),
+    Transferred(TransferResult),
+}
+
+impl JobOutcome {
+    fn failures(&self) -> usize {
+        match self {
+            JobOutcome::Removed(result) => result.failed.len(),
+            JobOutcome::Transferred(result) => result.failed.len(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Job {
+    pub id: String,
+    pub spec: JobSpec,
+    pub state: JobState,
+    /// 入队时间（Unix 秒）
+    pub created_at: i64,
+    pub started_at: Option<i64>,
+    pub finished_at: Option<i64>,
+    pub result: Option<JobOutcome>,
+    /// 失败摘要（如 "2 项失败"、异常终止原因）
+    pub error: Option<String>,
+}
+
+/// 读入的队列：执行到一半的作业重新排队
+fn restore(mut jobs: Vec<Job>) -> Vec<Job> {
+    for job in jobs.iter_mut().filter(|j| j.state == JobState::Running) {
+        job.state = JobState::Queued;
+        job.started_at = None;
+    }
+    jobs
+}
+
+/// 只保留最近 MAX_FINISHED 个已结束的作业
+fn prune(jobs: &mut Vec<Job>) {
+    let finished = jobs.iter().filter(|j| j.state.is_finished()).count();
+    let mut excess = finished.saturating_sub(MAX_FINISHED);
+    jobs.retain(|j| {
+        if excess > 0 && j.state.is_finished() {
+            excess -= 1;
+            return false;
+        }
+        true
+    });
+}
+
+fn jobs_path() -> Option<PathBuf> {
+    crate::migration::data_dir().ok().map(|dir| dir.join(JOBS_FILE))
+}
+
+fn load_from(path: &Path) -> Vec<Job> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save(jobs: &[Job]) {
+    let Some(path) = jobs_path() else { return };
+    let written = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(&path, serde_json::to_string_pretty(jobs).unwrap_or_default()));
+    if let Err(e) = written {
+        eprintln!("[Jobs] 保存作业队列失败: {}", e);
+    }
+}
+
+fn emit(events: &Option<Events>, job: &Job) {
+    if let Some(sink) = events {
+        sink.emit(JOB_EVENT, job);
+    }
+}
+
+/// 校验并加入队列，返回排队中的作业
+pub fn enqueue(spec: JobSpec, events: Option<Events>) -> Validated<Job> {
+    let spec = spec.validated()?;
+    let job = Job {
+        id: uuid::Uuid::new_v4().to_string(),
+        spec,
+        state: JobState::Queued,
+        created_at: chrono::Utc::now().timestamp(),
+        started_at: None,
+        finished_at: None,
+        result: None,
+        error: None,
+    };
+    {
+        let mut queue = QUEUE.lock();
+        if events.is_some() {
+            queue.events = events;
+        }
+        queue.jobs.push(job.clone());
+        save(&queue.jobs);
+        emit(&queue.events, &job);
+    }
+    pump();
+    Ok(job)
+}
+
+/// 继续执行上次未完成的作业（应用启动时调用）
+pub fn resume(events: Option<Events>) {
+    QUEUE.lock().events = events;
+    pump();
+}
+
+pub fn get(id: &str) -> Option<Job> {
+    QUEUE.lock().jobs.iter().find(|j| j.id == id).cloned()
+}
+
+/// 全部作业（入队顺序）
+pub fn list() -> Vec<Job> {
+    QUEUE.lock().jobs.clone()
+}
+
+/// 取消作业：排队中的直接标记为已取消，执行中的在下一个条目 / 数据块之前停止；
+/// 作业不存在或已结束时返回 false
+pub fn cancel(id: &str) -> bool {
+    let mut queue = QUEUE.lock();
+    let Some(job) = queue.jobs.iter_mut().find(|j| j.id == id) else {
+        return false;
+    };
+    match job.state {
+        JobState::Queued => {
+            job.state = JobState::Cancelled;
+            job.finished_at = Some(chrono::Utc::now().timestamp());
+            let job = job.clone();
+            save(&queue.jobs);
+            emit(&queue.events, &job);
+            true
+        }
+        JobState::Running => crate::operation::cancel(id),
+        _ => false,
+    }
+}
+
+/// 在并发上限内启动排队中的作业
+fn pump() {
+    let mut queue = QUEUE.lock();
+    let limit = crate::settings::Settings::current().file_ops.max_parallel.max(1);
+    while queue.running < limit {
+        let Some(job) = queue.jobs.iter_mut().find(|j| j.state == JobState::Queued) else { break };
+        job.state = JobState::Running;
+        job.started_at = Some(chrono::Utc::now().timestamp());
+        let job = job.clone();
+        queue.running += 1;
+        save(&queue.jobs);
+        emit(&queue.events, &job);
+
+        let events = queue.events.clone();
+        std::thread::spawn(move || run(job, events));
+    }
+}
+
+fn run(job: Job, events: Option<Events>) {
+    let op = Operation::start(job.spec.operation_name(), Some(job.id.clone()), events);
+    let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| job.spec.run(&op)));
+    let cancelled = op.is_cancelled();
+    // 先发出 operation-progress 的结束事件，再发出作业状态
+    drop(op);
+
+    {
+        let mut queue = QUEUE.lock();
+        queue.running -= 1;
+        if let Some(done) = queue.jobs.iter_mut().find(|j| j.id == job.id) {
+            done.finished_at = Some(chrono::Utc::now().timestamp());
+            match outcome {
+                Ok(result) => {
+                    let failures = result.failures();
+                    done.state = match (cancelled, failures) {
+                        (true, _) => JobState::Cancelled,
+                        (false, 0) => JobState::Completed,
+                        _ => JobState::Failed,
+                    };
+                    done.error = (failures > 0 && !cancelled).then(|| format!("{} 项失败", failures));
+                    done.result = Some(result);
+                }
+                Err(_) => {
+                    done.state = JobState::Failed;
+                    done.error = Some("作业异常终止".to_string());
+                }
+            }
+            let done = done.clone();
+            prune(&mut queue.jobs);
+            save(&queue.jobs);
+            emit(&queue.events, &done);
+        }
+    }
+    pump();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(id: &str, state: JobState) -> Job {
+        Job {
+            id: id.to_string(),
+            spec: JobSpec::Trash { paths: vec![format!("/tmp/{}", id)] },
+            state,
+            created_at: 0,
+            started_at: Some(1),
+            finished_at: None,
+            result: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn restores_interrupted_jobs_and_prunes_oldest_finished() {
+        let jobs = restore(vec![job("a", JobState::Running), job("b", JobState::Completed)]);
+        assert_eq!(jobs[0].state, JobState::Queued);
+        assert_eq!(jobs[0].started_at, None);
+        assert_eq!(jobs[1].state, JobState::Completed);
+
+        let mut jobs: Vec<Job> = (0..MAX_FINISHED + 2).map(|i| job(&i.to_string(), JobState::Completed)).collect();
+        jobs.insert(1, job("queued", JobState::Queued));
+        prune(&mut jobs);
+        assert_eq!(jobs.len(), MAX_FINISHED + 1);
+        assert_eq!(jobs[0].id, "queued");
+        assert_eq!(jobs[1].id, "2");
+
+        // 令牌不写入队列文件
+        let spec = JobSpec::Delete { paths: vec!["/x".into()], token: "secret".into() };
+        let json = serde_json::to_string(&spec).unwrap();
+        assert_eq!(json, r#"{"type":"delete","paths":["/x"]}"#);
+    }
+}
@@ -0,0 +1,153 @@
+// 逐层浏览：只返回一层子项（带递归大小），按需展开
+//
+// 浏览时界面一次只显示一层，没必要把百万级的完整结果传给前端。scan_level 只返回
+// path 的直接子项，每个子目录带整棵子树的大小与文件 / 目录数：
+// - 内存缓存中已有 path 或其祖先目录的扫描结果时直接取出（下钻不再遍历）
+// - 否则在时间预算内完整扫描（默认 LEVEL_BUDGET_MS），完整结果留在后端缓存，
+//   之后展开其下任意子目录都命中缓存；超出预算时子目录大小为已遍历部分（complete = false）
+//
+// expand_directory 用于下钻：沿用同一逻辑，通常命中上一层扫描留下的缓存。
+
+use crate::events::Events;
+use crate::scan::{format_size, CacheSource, CompactString, Item, ScanOptions};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// 未指定时间预算时的遍历上限（毫秒）
+pub const LEVEL_BUDGET_MS: u64 = 3000;
+
+/// 子项大小的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum LevelSource {
+    /// 内存 / 磁盘缓存中的扫描结果（可能来自祖先目录的扫描）
+    Cache,
+    /// 本次遍历
+    Traversal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelResult {
+    #[schemars(with = "String")]
+    pub path: CompactString,
+    pub total_size: i64,
+    #[schemars(with = "String")]
+    pub total_size_formatted: CompactString,
+    /// 子树文件总数 / 目录总数（不含 path 本身）
+    pub file_count: u64,
+    pub dir_count: u64,
+    /// 直接子项，按大小降序；子目录的 size / fileCount / dirCount 为整棵子树
+    pub children: Vec<Item>,
+    pub source: LevelSource,
+    /// false 表示遍历超出时间预算，部分子目录的大小只含已遍历部分
+    pub complete: bool,
+    pub scan_time: f64,
+}
+
+/// item_path 是否为 parent 的直接子项（路径均为正斜杠的规范形式）
+fn is_direct_child(parent: &str, item_path: &str) -> bool {
+    let Some(rest) = item_path.strip_prefix(parent) else {
+        return false;
+    };
+    let rest = if parent.ends_with('/') { rest } else { rest.strip_prefix('/').unwrap_or("") };
+    !rest.is_empty() && !rest.contains('/')
+}
+
+/// 从扫描结果中取出 parent 的直接子项，组装一层结果
+pub fn level_from_items(parent: &str, items: &[Item], source: LevelSource, complete: bool) -> LevelResult {
+    let mut children: Vec<Item> = items.iter().filter(|i| is_direct_child(parent, &i.path)).cloned().collect();
+    children.sort_unstable_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    let total_size: i64 = children.iter().map(|c| c.size).sum();
+    // 子项的占比以本层为父目录重新计算（祖先会话中的 percent_of_total 相对的是会话根）
+    crate::scan::fill_percentages(&mut children, total_size);
+    let (file_count, dir_count) = children.iter().fold((0u64, 0u64), |(f, d), c| {
+        if c.is_dir {
+            (f + c.file_count, d + 1 + c.dir_count)
+        } else {
+            (f + 1, d)
+        }
+    });
+    LevelResult {
+        path: CompactString::from(parent),
+        total_size,
+        total_size_formatted: format_size(total_size),
+        file_count,
+        dir_count,
+        children,
+        source,
+        complete,
+        scan_time: 0.0,
+    }
+}
+
+/// 返回 path 的直接子项及其递归大小：优先取缓存（含祖先目录的会话），否则限时遍历
+pub async fn scan_level(path: &str, options: &ScanOptions, events: Option<Events>) -> anyhow::Result<LevelResult> {
+    let start = std::time::Instant::now();
+    let cached = {
+        let path = path.to_string();
+        tokio::task::spawn_blocking(move || crate::scan::cached_items_containing(&path)).await?
+    };
+    if let Some((key, items)) = cached {
+        let mut level = level_from_items(&key, &items, LevelSource::Cache, true);
+        level.scan_time = start.elapsed().as_secs_f64();
+        return Ok(level);
+    }
+
+    let options = ScanOptions {
+        time_budget_ms: Some(options.time_budget_ms.unwrap_or(LEVEL_BUDGET_MS)),
+        ..options.clone()
+    };
+    let result = crate::scan::scan_directory_with_options(
+        path,
+        false,
+        &options,
+        crate::perf::PerformanceMonitor::instance(),
+        events,
+    )
+    .await?;
+    let source = match result.cache_usage.source {
+        CacheSource::Fresh => LevelSource::Traversal,
+        _ => LevelSource::Cache,
+    };
+    let mut level = level_from_items(&result.path, &result.items, source, result.unscanned.is_none());
+    level.scan_time = start.elapsed().as_secs_f64();
+    Ok(level)
+}
+
+/// 按需展开子目录（下钻）：通常命中上一层扫描留在缓存中的结果，否则同 scan_level 限时遍历
+pub async fn expand_directory(path: &str, events: Option<Events>) -> anyhow::Result<LevelResult> {
+    scan_level(path, &ScanOptions::default(), events).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(path: &str, size: i64, is_dir: bool, file_count: u64, dir_count: u64) -> Item {
+        let item = Item::test(path, size, is_dir);
+        Item { file_count, dir_count, ..item }
+    }
+
+    #[test]
+    fn picks_direct_children_with_subtree_totals() {
+        let items = vec![
+            item("C:/data/a", 300, true, 2, 1),
+            item("C:/data/a/b", 200, true, 1, 0),
+            item("C:/data/a/b/x.bin", 200, false, 0, 0),
+            item("C:/data/a/y.bin", 100, false, 0, 0),
+            item("C:/data/z.txt", 700, false, 0, 0),
+            item("C:/database/other", 5, false, 0, 0),
+        ];
+        let level = level_from_items("C:/data", &items, LevelSource::Cache, true);
+        let names: Vec<_> = level.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["z.txt", "a"]);
+        assert_eq!(level.total_size, 1000);
+        assert_eq!((level.file_count, level.dir_count), (3, 2));
+        assert!((level.children[1].percent_of_parent - 30.0).abs() < 1e-4);
+
+        let nested = level_from_items("C:/data/a", &items, LevelSource::Cache, true);
+        assert_eq!(nested.children.len(), 2);
+        assert_eq!(level_from_items("C:/", &[item("C:/data", 1, true, 0, 0)], LevelSource::Cache, true).children.len(), 1);
+    }
+}
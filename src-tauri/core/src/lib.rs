@@ -0,0 +1,140 @@
+//! FlashDir 核心库：磁盘扫描、两级缓存、聚合分析与导出，不依赖 Tauri。
+//!
+//! Tauri GUI、命令行工具与第三方 Rust 工具共用本库。主要模块：
+//! - [`scan`]：扫描引擎（MFT / USN 增量 / 目录遍历 / 内存与磁盘缓存 / 流式结果）
+//! - [`estimate`]：抽样估算超大目录树的总大小（带 95% 置信区间，1 秒内返回）
+//! - [`level_scan`]：逐层浏览，只返回一层子项（递归大小取自缓存或限时遍历），按需展开
+//! - [`dir_listing`]：非递归目录列举（文件浏览面板、路径补全），不读写缓存
+//! - [`backend`]：遍历后端（MFT 直读 / 平台原生遍历器 / 标准库），按选项或平台自动选择
+//! - [`exclusions`]：内置排除预设（WinSxS、页面文件、回收站、/proc 等），按次扫描启用
+//! - [`include_filter`]：包含模式（glob，如 `*.mp4;*.mkv`），只统计匹配的文件
+//! - [`categories`]：按扩展名的文件类别（图片 / 视频 / 文档 / 代码 / 压缩包等）与各类别大小
+//! - [`treemap`]：基于缓存扫描结果的 squarified 矩形树图布局
+//! - [`events`]：扫描进度与流式结果的事件接收方（[`events::EventSink`]）
+//! - [`disk_cache`]：SQLite 磁盘缓存、事件日志、快照与大小历史
+//! - [`cache_transfer`]：把缓存的扫描结果导出为可移植文件，在另一台机器上导入后离线浏览
+//! - [`diff_engine`] / [`size_history`]：扫描对比与增长趋势
+//! - [`dev_analyzer`] / [`tiering`] / [`file_age`] / [`owner_report`] / [`small_files`]：聚合分析
+//! - [`git_repos`]：Git 仓库识别，.git 与工作区的大小拆分及 git gc 等清理建议
+//! - [`app_data`]：Outlook PST / OST、mbox 邮件存档与浏览器 / Electron 应用的 SQLite、LevelDB 数据，按应用分组
+//! - [`pkg_caches`]：cargo / npm / pnpm / pip / Gradle 缓存大小与工具自带的清理命令
+//! - [`recycle_bin`]：回收站 / 废纸篓中的条目（原路径、大小、删除时间）与清空
+//! - [`file_ops`]：应用内文件操作（移入回收站、凭确认令牌永久删除、移动 / 复制），返回父目录新大小
+//! - [`archive`]：把选中的文件 / 文件夹压缩为 zip 或 tar.zst，压缩前估计压缩后大小
+//! - [`export`]：把扫描结果的完整条目列表按可配置的列导出为 CSV、NDJSON 或 XLSX
+//! - [`report`]：独立的 HTML / PDF 扫描报告（总计、最大目录、扩展名分布、重复文件摘要）
+//! - [`clipboard`]：把扫描摘要或选中条目格式化为文本 / Markdown / TSV，由后端写入系统剪贴板
+//! - [`preview`]：结果条目的内容预览（文本按编码检测解码、图片缩略图、二进制十六进制转储）
+//! - [`item_details`]：单个条目的详细属性（时间戳、属性、所有者、链接目标、数据流、子项统计）
+//! - [`jobs`]：文件操作作业队列（顺序或限量并行执行、跨重启保存、逐作业进度与取消）
+//! - [`path_anomalies`]：超长路径、保留设备名、非法字符等会让备份 / 同步失败的条目
+//! - [`permission_report`]：一级子目录的可读性（完全可读 / 部分可读 / 拒绝访问）
+//! - [`selection`]：多选、批量删除与导出（CSV / JSON）
+//! - [`operation`]：维护操作（清空缓存 / 导出 / 保存快照）的进度事件与取消
+//! - [`scan_profiles`]：命名的扫描选项配置（内置快速概览 / 完整审计 / 网络共享），保存在 scan_profiles.json
+//! - [`schedule`]：定时扫描计划（间隔 / cron），后台模式重扫并保存快照
+//! - [`idle_scan`]：用户空闲且 CPU 负载低时刷新收藏路径的缓存，支持静默时段
+//! - [`cache_watch`]：监视已打开的扫描根，子目录内有变更时立即把相关缓存标记为过期
+//! - [`dir_digest`]：子目录 mtime 摘要，缓存命中前抽样校验深层目录是否有增删
+//! - [`prewarm`]：启动与空闲后把常用的扫描结果预先载入内存缓存，可选刷新收藏路径
+//! - [`scan_failures`]：无法访问的扫描路径的负缓存，有效期内直接返回上次的错误
+//! - [`settings`]：用户设置（遍历线程数、队列容量、确定性模式、内存保留、外观），保存在 settings.json
+//! - [`report_style`]：报告的主题配色与区域格式（日期、数字、RTL），取自设置或系统偏好
+//! - [`guard`]：命令防重入与轮询节流
+//! - [`validate`]：命令参数校验（返回带出错字段的 ValidationError）
+//! - [`schema`]：由载荷类型生成前端使用的 JSON Schema / TypeScript 声明
+//! - [`fs`]：平台文件系统抽象（Windows 快速遍历器 / MFT 读取 / USN Journal / 卷信息）
+//!
+//! # 示例
+//!
+//! ```no_run
+//! use flashdir_core::perf::PerformanceMonitor;
+//! use flashdir_core::scan::{self, ScanOptions};
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! let result = scan::scan_directory_with_options(
+//!     "/home/user",
+//!     false,
+//!     &ScanOptions::default(),
+//!     PerformanceMonitor::instance(),
+//!     None,
+//! )
+//! .await?;
+//! for item in result.items.iter().take(10) {
+//!     println!("{}\t{}", item.size_formatted, item.path);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! 需要进度或流式结果时传入事件接收方，闭包即可：
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use flashdir_core::events::Events;
+//!
+//! let events: Events = Arc::new(|event: &str, payload: serde_json::Value| {
+//!     if event == "scan-progress" {
+//!         eprintln!("{}", payload["percent"]);
+//!     }
+//! });
+//! ```
+
+pub mod scan;
+pub mod estimate;
+pub mod level_scan;
+pub mod dir_listing;
+pub mod backend;
+pub mod exclusions;
+pub mod include_filter;
+pub mod categories;
+pub mod treemap;
+pub mod perf;
+pub mod disk_cache;
+pub mod cache_transfer;
+pub mod binary_protocol;
+pub mod fs;
+pub mod dev_analyzer;
+pub mod diff_engine;
+pub mod global_search;
+pub mod tiering;
+pub mod system_scan;
+pub mod file_age;
+pub mod owner_report;
+pub mod small_files;
+pub mod git_repos;
+pub mod app_data;
+pub mod pkg_caches;
+pub mod recycle_bin;
+pub mod file_ops;
+pub mod jobs;
+pub mod archive;
+pub mod item_details;
+pub mod preview;
+pub mod export;
+pub mod report;
+pub mod clipboard;
+pub mod path_anomalies;
+pub mod permission_report;
+pub mod selection;
+pub mod autocomplete;
+pub mod hashing;
+pub mod frecency;
+pub mod migration;
+pub mod size_history;
+pub mod progress;
+pub mod elevated;
+pub mod events;
+pub mod schema;
+pub mod validate;
+pub mod guard;
+pub mod operation;
+pub mod settings;
+pub mod scan_profiles;
+pub mod schedule;
+pub mod idle_scan;
+pub mod prewarm;
+pub mod cache_watch;
+pub mod dir_digest;
+pub mod scan_failures;
+pub mod report_style;
@@ -0,0 +1,363 @@
+// 应用数据迁移
+//
+// ~/.flashdir 下的数据（历史、磁盘缓存、快照、USN 检查点等）带版本号，记录在 version.json。
+// 启动时（打开数据库之前）按版本顺序执行待执行的迁移：
+//   1. 把数据目录顶层文件备份到 backups/v{旧版本}-{时间戳}/，并用 BLAKE3 校验备份完整性
+//   2. 依次执行迁移，全部成功后写入新版本号
+//   3. 任一迁移失败则从备份恢复，数据保持迁移前的状态
+//
+// 数据版本高于当前程序支持的版本时（降级安装）不做任何修改。
+
+use anyhow::{anyhow, Context, Result};
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use std::path::{Path, PathBuf};
+
+const MANIFEST_FILE: &str = "version.json";
+const BACKUP_DIR: &str = "backups";
+/// 保留的备份数量
+const MAX_BACKUPS: usize = 3;
+
+/// 单个迁移步骤
+pub struct Migration {
+    /// 执行后的数据版本
+    pub version: u32,
+    pub description: &'static str,
+    pub run: fn(&Path) -> Result<()>,
+}
+
+/// 迁移列表，按版本升序；新增数据格式变更时在末尾追加
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "建立数据目录版本记录",
+        run: |_| Ok(()),
+    },
+    Migration {
+        version: 2,
+        description: "历史记录路径统一为展示形式（去掉 \\\\?\\ 前缀、正斜杠、盘符大写）",
+        run: normalize_history_paths,
+    },
+];
+
+/// v2：旧版本按用户输入原样记录历史路径（`c:\Users`、`\\?\C:\Users` 等），
+/// 统一改写为与缓存 key 一致的展示形式
+fn normalize_history_paths(dir: &Path) -> Result<()> {
+    let file = dir.join("history.json");
+    let Ok(content) = std::fs::read_to_string(&file) else {
+        return Ok(());
+    };
+    let mut history: serde_json::Value = serde_json::from_str(&content).context("history.json 格式无效")?;
+    if let Some(entries) = history.as_array_mut() {
+        for entry in entries {
+            if let Some(serde_json::Value::String(path)) = entry.get_mut("path") {
+                *path = crate::fs::display_path(path);
+            }
+        }
+    }
+    std::fs::write(&file, serde_json::to_vec(&history)?)?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    version: u32,
+    app_version: String,
+    migrated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedMigration {
+    pub version: u32,
+    pub description: String,
+}
+
+/// 本次启动的迁移结果
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationStatus {
+    pub data_dir: String,
+    /// 迁移前的数据版本（0 = 旧版本未记录版本号）
+    pub from_version: u32,
+    /// 当前数据版本
+    pub current_version: u32,
+    /// 程序支持的最新版本
+    pub target_version: u32,
+    pub applied: Vec<AppliedMigration>,
+    pub backup_dir: Option<String>,
+    pub error: Option<String>,
+    /// 迁移失败后是否已从备份恢复
+    pub restored: bool,
+}
+
+lazy_static! {
+    static ref STATUS: Mutex<Option<MigrationStatus>> = Mutex::new(None);
+}
+
+/// ~/.flashdir
+pub fn data_dir() -> Result<PathBuf> {
+    let home = std::env::var("USERPROFILE")
+        .or_else(|_| std::env::var("HOME"))
+        .map_err(|_| anyhow!("Cannot get home directory"))?;
+    Ok(PathBuf::from(home).join(".flashdir"))
+}
+
+/// 启动时调用：执行待执行的迁移并记录结果供 `status()` 查询
+pub fn run_startup_migrations() -> MigrationStatus {
+    let status = match data_dir() {
+        Ok(dir) => run_pending_in(&dir, MIGRATIONS),
+        Err(e) => MigrationStatus {
+            data_dir: String::new(),
+            from_version: 0,
+            current_version: 0,
+            target_version: target_version(MIGRATIONS),
+            applied: Vec::new(),
+            backup_dir: None,
+            error: Some(e.to_string()),
+            restored: false,
+        },
+    };
+    *STATUS.lock() = Some(status.clone());
+    status
+}
+
+/// 本次启动的迁移结果；尚未执行迁移时返回 None
+pub fn status() -> Option<MigrationStatus> {
+    STATUS.lock().clone()
+}
+
+fn target_version(migrations: &[Migration]) -> u32 {
+    migrations.last().map(|m| m.version).unwrap_or(0)
+}
+
+fn read_version(dir: &Path) -> u32 {
+    std::fs::read_to_string(dir.join(MANIFEST_FILE))
+        .ok()
+        .and_then(|s| serde_json::from_str::<Manifest>(&s).ok())
+        .map(|m| m.version)
+        .unwrap_or(0)
+}
+
+fn write_version(dir: &Path, version: u32) -> Result<()> {
+    let manifest = Manifest {
+        version,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        migrated_at: chrono::Utc::now().timestamp(),
+    };
+    // 先写临时文件再重命名，避免写到一半中断留下损坏的版本记录
+    let tmp = dir.join(format!("{}.tmp", MANIFEST_FILE));
+    std::fs::write(&tmp, serde_json::to_vec_pretty(&manifest)?)?;
+    std::fs::rename(&tmp, dir.join(MANIFEST_FILE))?;
+    Ok(())
+}
+
+/// 对指定数据目录执行迁移
+pub fn run_pending_in(dir: &Path, migrations: &[Migration]) -> MigrationStatus {
+    let from_version = read_version(dir);
+    let target = target_version(migrations);
+    let mut status = MigrationStatus {
+        data_dir: dir.to_string_lossy().to_string(),
+        from_version,
+        current_version: from_version,
+        target_version: target,
+        applied: Vec::new(),
+        backup_dir: None,
+        error: None,
+        restored: false,
+    };
+
+    if from_version > target {
+        status.error = Some(format!(
+            "数据版本 {} 高于当前程序支持的版本 {}，未做修改",
+            from_version, target
+        ));
+        return status;
+    }
+    let pending: Vec<&Migration> = migrations.iter().filter(|m| m.version > from_version).collect();
+    if pending.is_empty() {
+        return status;
+    }
+
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        status.error = Some(format!("创建数据目录失败: {}", e));
+        return status;
+    }
+
+    let backup = match backup_data_dir(dir, from_version) {
+        Ok(backup) => backup,
+        Err(e) => {
+            status.error = Some(format!("迁移前备份失败，已跳过迁移: {}", e));
+            return status;
+        }
+    };
+    status.backup_dir = backup.as_ref().map(|p| p.to_string_lossy().to_string());
+
+    for migration in pending {
+        if let Err(e) = (migration.run)(dir) {
+            status.error = Some(format!("迁移到版本 {} 失败: {}", migration.version, e));
+            if let Some(backup) = &backup {
+                status.restored = restore_backup(dir, backup).is_ok();
+            }
+            status.applied.clear();
+            return status;
+        }
+        status.applied.push(AppliedMigration {
+            version: migration.version,
+            description: migration.description.to_string(),
+        });
+    }
+
+    match write_version(dir, target) {
+        Ok(()) => status.current_version = target,
+        Err(e) => status.error = Some(format!("写入数据版本失败: {}", e)),
+    }
+    prune_backups(dir);
+    status
+}
+
+/// 数据目录顶层的数据文件（不含备份目录与版本记录）
+fn data_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() && entry.file_name() != MANIFEST_FILE {
+            files.push(entry.path());
+        }
+    }
+    Ok(files)
+}
+
+fn file_hash(path: &Path) -> Result<blake3::Hash> {
+    let mut hasher = blake3::Hasher::new();
+    let mut file = std::fs::File::open(path)?;
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(hasher.finalize())
+}
+
+/// 备份并逐文件校验；数据目录为空时不创建备份
+fn backup_data_dir(dir: &Path, from_version: u32) -> Result<Option<PathBuf>> {
+    let files = data_files(dir)?;
+    if files.is_empty() {
+        return Ok(None);
+    }
+
+    let backup = dir.join(BACKUP_DIR).join(format!(
+        "v{}-{}",
+        from_version,
+        chrono::Utc::now().format("%Y%m%d%H%M%S")
+    ));
+    std::fs::create_dir_all(&backup)?;
+
+    for file in files {
+        let name = file.file_name().context("无效的文件名")?;
+        let dest = backup.join(name);
+        std::fs::copy(&file, &dest)?;
+        if file_hash(&file)? != file_hash(&dest)? {
+            return Err(anyhow!("备份校验不一致: {}", file.display()));
+        }
+    }
+    Ok(Some(backup))
+}
+
+/// 用备份覆盖数据目录：迁移过程中新建的文件删除，备份中的文件复制回去
+fn restore_backup(dir: &Path, backup: &Path) -> Result<()> {
+    for file in data_files(dir)? {
+        if !backup.join(file.file_name().context("无效的文件名")?).exists() {
+            std::fs::remove_file(&file)?;
+        }
+    }
+    for entry in std::fs::read_dir(backup)? {
+        let entry = entry?;
+        std::fs::copy(entry.path(), dir.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+/// 只保留最近的几个备份（目录名含时间戳，按名称排序即按时间排序）
+fn prune_backups(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir.join(BACKUP_DIR)) else {
+        return;
+    };
+    let mut backups: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect();
+    backups.sort_by_key(|p| p.file_name().map(|n| n.to_os_string()));
+    let excess = backups.len().saturating_sub(MAX_BACKUPS);
+    for old in backups.into_iter().take(excess) {
+        std::fs::remove_dir_all(old).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_with_backup_and_restores_on_failure() {
+        let dir = std::env::temp_dir().join(format!("flashdir-migrate-{}", std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("history.json"), b"[]").unwrap();
+
+        let ok: &[Migration] = &[
+            Migration { version: 1, description: "v1", run: |_| Ok(()) },
+            Migration {
+                version: 2,
+                description: "v2",
+                run: |d| Ok(std::fs::write(d.join("history.json"), b"[1]")?),
+            },
+        ];
+        let status = run_pending_in(&dir, ok);
+        assert!(status.error.is_none());
+        assert_eq!((status.from_version, status.current_version), (0, 2));
+        assert_eq!(status.applied.len(), 2);
+        let backup = PathBuf::from(status.backup_dir.unwrap());
+        assert_eq!(std::fs::read(backup.join("history.json")).unwrap(), b"[]");
+
+        // 已是最新版本：不再执行
+        assert!(run_pending_in(&dir, ok).applied.is_empty());
+
+        let failing: &[Migration] = &[
+            Migration { version: 2, description: "v2", run: |_| Ok(()) },
+            Migration {
+                version: 3,
+                description: "broken",
+                run: |d| {
+                    std::fs::write(d.join("history.json"), b"garbage")?;
+                    Err(anyhow!("boom"))
+                },
+            },
+        ];
+        let status = run_pending_in(&dir, failing);
+        assert!(status.error.is_some() && status.restored);
+        assert_eq!(status.current_version, 2);
+        assert_eq!(std::fs::read(dir.join("history.json")).unwrap(), b"[1]");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn normalizes_legacy_history_paths() {
+        let dir = std::env::temp_dir().join(format!("flashdir-migrate-history-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("history.json"),
+            r#"[{"path":"\\\\?\\c:\\Users","scanTime":0},{"path":"/home/u","scanTime":0}]"#,
+        )
+        .unwrap();
+
+        normalize_history_paths(&dir).unwrap();
+        let history: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(dir.join("history.json")).unwrap()).unwrap();
+        assert_eq!(history[0]["path"], "C:/Users");
+        assert_eq!(history[1]["path"], "/home/u");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
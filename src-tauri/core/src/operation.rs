@@ -0,0 +1,180 @@
+// 维护操作的进度与取消
+//
+// 清空磁盘缓存（数百 MB 的数据库）、导出数百万行、保存大快照等操作可能持续数十秒：
+// 执行过程中按阶段发出 "operation-progress" 事件，并在批次之间检查取消标记。
+// 操作 ID 由调用方提供，前端先生成 ID 再发起命令，随后可用同一 ID 调用 cancel 中止。
+
+use crate::events::Events;
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// 同一阶段内进度事件的最小间隔
+const EMIT_INTERVAL: Duration = Duration::from_millis(200);
+
+lazy_static! {
+    /// 执行中的操作：ID → 取消标记
+    static ref RUNNING: Mutex<HashMap<String, Arc<AtomicBool>>> = Mutex::new(HashMap::new());
+}
+
+/// "operation-progress" 事件负载
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationProgress {
+    pub id: String,
+    /// 操作名（与命令名一致，如 clear_disk_cache）
+    pub operation: String,
+    /// 当前阶段（如 delete / write / prune）
+    pub stage: String,
+    /// 0–100
+    pub percent: f64,
+    /// 操作已结束（成功、失败或取消）
+    pub done: bool,
+    pub cancelled: bool,
+}
+
+/// 操作被取消
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("操作已取消")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// 执行中的操作，离开作用域时注销并发出结束事件
+pub struct Operation {
+    id: String,
+    name: &'static str,
+    events: Option<Events>,
+    cancelled: Arc<AtomicBool>,
+    /// (当前阶段, 当前进度, 上次发出事件的时刻)
+    state: Mutex<(&'static str, f64, Option<Instant>)>,
+}
+
+impl Operation {
+    /// 登记一个可取消的操作；id 为 None 时自动生成（调用方无法取消，仍可收到进度事件）
+    pub fn start(name: &'static str, id: Option<String>, events: Option<Events>) -> Self {
+        let id = id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let cancelled = Arc::new(AtomicBool::new(false));
+        RUNNING.lock().insert(id.clone(), Arc::clone(&cancelled));
+        Self {
+            id,
+            name,
+            events,
+            cancelled,
+            state: Mutex::new(("start", 0.0, None)),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// 已取消时返回 Err(Cancelled)，在批次之间用 `?` 提前结束
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 上报进度：阶段变化时立即发出，同一阶段内最多每 200ms 一次
+    pub fn progress(&self, stage: &'static str, percent: f64) {
+        let percent = percent.clamp(0.0, 100.0);
+        let mut state = self.state.lock();
+        let due = state.0 != stage || state.2.is_none_or(|at| at.elapsed() >= EMIT_INTERVAL);
+        *state = (stage, percent, state.2);
+        if due {
+            state.2 = Some(Instant::now());
+            drop(state);
+            self.emit(stage, percent, false);
+        }
+    }
+
+    fn emit(&self, stage: &str, percent: f64, done: bool) {
+        if let Some(sink) = self.events.as_ref() {
+            sink.emit(
+                "operation-progress",
+                OperationProgress {
+                    id: self.id.clone(),
+                    operation: self.name.to_string(),
+                    stage: stage.to_string(),
+                    percent,
+                    done,
+                    cancelled: self.is_cancelled(),
+                },
+            );
+        }
+    }
+}
+
+impl Drop for Operation {
+    fn drop(&mut self) {
+        {
+            let mut running = RUNNING.lock();
+            // 同一 ID 可能已被新的操作重新登记
+            if running.get(&self.id).is_some_and(|flag| Arc::ptr_eq(flag, &self.cancelled)) {
+                running.remove(&self.id);
+            }
+        }
+        let (stage, percent, _) = *self.state.lock();
+        let percent = if self.is_cancelled() { percent } else { 100.0 };
+        self.emit(stage, percent, true);
+    }
+}
+
+/// 请求取消操作；ID 不存在（已结束或从未开始）时返回 false
+pub fn cancel(id: &str) -> bool {
+    match RUNNING.lock().get(id) {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_stages_and_honours_cancel() {
+        let seen: Arc<Mutex<Vec<serde_json::Value>>> = Arc::default();
+        let sink = Arc::clone(&seen);
+        let events: Events = Arc::new(move |_: &str, payload| sink.lock().push(payload));
+
+        let op = Operation::start("export_selected", Some("op-test".into()), Some(events));
+        op.progress("write", 10.0);
+        op.progress("write", 20.0); // 节流，不发出
+        op.progress("flush", 99.0);
+        assert!(op.check().is_ok());
+
+        assert!(cancel("op-test"));
+        assert_eq!(op.check(), Err(Cancelled));
+        drop(op);
+        assert!(!cancel("op-test"));
+
+        let seen = seen.lock();
+        let stages: Vec<_> = seen.iter().map(|p| p["stage"].as_str().unwrap().to_string()).collect();
+        assert_eq!(stages, vec!["write", "flush", "flush"]);
+        let last = seen.last().unwrap();
+        assert_eq!((last["done"].as_bool(), last["cancelled"].as_bool()), (Some(true), Some(true)));
+        assert_eq!(last["percent"], 99.0);
+    }
+}
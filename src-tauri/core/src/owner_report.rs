@@ -0,0 +1,98 @@
+// 文件所有者统计模块
+//
+// 解析每个文件的所有者并按所有者聚合空间占用，用于共享文件服务器上
+// "谁占了多少空间" 的分析。所有者解析每个文件一次系统调用，较慢，
+// 因此只在显式请求时执行，且不写入扫描缓存。
+
+use rayon::prelude::*;
+use serde::Serialize;
+use schemars::JsonSchema;
+use std::collections::HashMap;
+use crate::fs::OwnerResolver;
+use crate::scan::{CompactString, Item, format_size};
+
+/// 无法解析所有者时的统计 key
+pub const UNKNOWN_OWNER: &str = "<unknown>";
+
+/// 单个所有者的聚合统计（仅统计文件）
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnerStat {
+    pub owner: String,
+    pub file_count: usize,
+    pub total_size: i64,
+    pub total_size_formatted: String,
+    pub percent_of_total: f64,
+}
+
+/// 并行解析 items 的所有者，结果与 items 按下标对齐，失败为空串
+pub fn resolve_owners(items: &[Item]) -> Vec<CompactString> {
+    items
+        .par_iter()
+        .map_init(OwnerResolver::new, |resolver, item| {
+            resolver
+                .owner_of(item.path.as_str())
+                .map(CompactString::from)
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// 按所有者聚合文件大小与数量（`owners` 与 `items` 按下标对齐），按总大小降序
+pub fn aggregate(items: &[Item], owners: &[CompactString]) -> Vec<OwnerStat> {
+    let mut totals: HashMap<&str, (i64, usize)> = HashMap::new();
+    for (item, owner) in items.iter().zip(owners) {
+        if item.is_dir {
+            continue;
+        }
+        let key = if owner.is_empty() { UNKNOWN_OWNER } else { owner.as_str() };
+        let e = totals.entry(key).or_insert((0, 0));
+        e.0 += item.size;
+        e.1 += 1;
+    }
+
+    let total_size: i64 = totals.values().map(|(size, _)| size).sum();
+    let mut stats: Vec<OwnerStat> = totals
+        .into_iter()
+        .map(|(owner, (size, count))| OwnerStat {
+            owner: owner.to_string(),
+            file_count: count,
+            total_size: size,
+            total_size_formatted: format_size(size).to_string(),
+            percent_of_total: if total_size > 0 {
+                (size as f64 / total_size as f64) * 100.0
+            } else {
+                0.0
+            },
+        })
+        .collect();
+
+    stats.sort_unstable_by(|a, b| b.total_size.cmp(&a.total_size).then_with(|| a.owner.cmp(&b.owner)));
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregate_by_owner_skips_dirs() {
+        let items = vec![
+            Item::test("s/a", 10, false),
+            Item::test("s/b", 30, false),
+            Item::test("s/c", 5, false),
+            Item::test("s", 45, true),
+        ];
+        let owners: Vec<CompactString> = ["alice", "bob", "", "root"]
+            .iter()
+            .map(|s| CompactString::from(*s))
+            .collect();
+
+        let stats = aggregate(&items, &owners);
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats[0].owner, "bob");
+        assert_eq!(stats[0].total_size, 30);
+        assert_eq!(stats[2].owner, UNKNOWN_OWNER);
+        assert!(stats.iter().all(|s| s.owner != "root"));
+    }
+}
@@ -0,0 +1,167 @@
+// 路径长度与名称异常
+//
+// 备份软件、压缩工具与跨平台同步客户端（OneDrive、Dropbox、Syncthing）常在以下条目上失败：
+// 超过或接近 Windows MAX_PATH（260）的路径、以空格或点结尾的名称（Win32 API 会静默去掉）、
+// 保留设备名（CON、NUL、COM1…，带扩展名同样保留）、在 Windows 上非法的字符
+// （在 Linux / macOS 上创建的 `a:b`、`what?` 同步到 Windows 时无法落盘）。
+// 基于内存缓存中的扫描结果逐项检查，不访问文件系统。
+
+use crate::scan::Item;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Windows 传统路径长度上限（含结尾 NUL，可用 259 个 UTF-16 单元）
+pub const MAX_PATH: usize = 260;
+
+/// 距 MAX_PATH 不足此长度即报告为“接近上限”（复制到更深的目录就会超限）
+const NEAR_MAX_PATH_MARGIN: usize = 20;
+
+/// 报告中列出的条目上限，计数不受影响
+const MAX_LISTED: usize = 5000;
+
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// 在 Windows 上不能出现在名称中的字符（另有 0x00–0x1F 控制字符）
+const WINDOWS_INVALID_CHARS: [char; 8] = ['<', '>', ':', '"', '\\', '|', '?', '*'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum PathAnomalyKind {
+    /// 路径长度达到 MAX_PATH
+    OverMaxPath,
+    /// 路径长度距 MAX_PATH 不足 20
+    NearMaxPath,
+    /// 名称以空格或点结尾
+    TrailingSpaceOrDot,
+    /// 名称（去掉扩展名后）为 Windows 保留设备名
+    ReservedName,
+    /// 名称含 Windows 非法字符或控制字符
+    InvalidCharacters,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PathAnomaly {
+    pub path: String,
+    pub is_dir: bool,
+    /// 路径长度（UTF-16 单元，与 MAX_PATH 的计法一致）
+    pub length: usize,
+    pub kinds: Vec<PathAnomalyKind>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PathAnomalyReport {
+    pub path: String,
+    pub checked: usize,
+    pub over_max_path: usize,
+    pub near_max_path: usize,
+    pub trailing_space_or_dot: usize,
+    pub reserved_names: usize,
+    pub invalid_characters: usize,
+    /// 有异常的条目，最长路径在前；超过 5000 条时截断
+    pub anomalies: Vec<PathAnomaly>,
+    pub truncated: bool,
+}
+
+/// 单个条目的异常（path 为展示形式的绝对路径）
+pub fn check(path: &str, name: &str) -> Vec<PathAnomalyKind> {
+    let mut kinds = Vec::new();
+    let length = path.encode_utf16().count();
+    if length >= MAX_PATH {
+        kinds.push(PathAnomalyKind::OverMaxPath);
+    } else if length >= MAX_PATH - NEAR_MAX_PATH_MARGIN {
+        kinds.push(PathAnomalyKind::NearMaxPath);
+    }
+    if name != "." && name != ".." && (name.ends_with(' ') || name.ends_with('.')) {
+        kinds.push(PathAnomalyKind::TrailingSpaceOrDot);
+    }
+    if is_reserved_name(name) {
+        kinds.push(PathAnomalyKind::ReservedName);
+    }
+    if name.chars().any(|c| (c.is_ascii_control() && c != '\u{7f}') || WINDOWS_INVALID_CHARS.contains(&c)) {
+        kinds.push(PathAnomalyKind::InvalidCharacters);
+    }
+    kinds
+}
+
+/// `CON`、`nul.txt`、`Com1.tar.gz` 均为保留名：按第一个点之前的部分（去掉尾部空格）判断
+fn is_reserved_name(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or(name).trim_end_matches(' ');
+    RESERVED_NAMES.iter().any(|r| stem.eq_ignore_ascii_case(r))
+}
+
+/// 检查 items 中的全部条目（扫描根本身不在 items 中）
+pub fn analyze(path: &str, items: &[Item]) -> PathAnomalyReport {
+    let mut report = PathAnomalyReport {
+        path: path.to_string(),
+        checked: items.len(),
+        over_max_path: 0,
+        near_max_path: 0,
+        trailing_space_or_dot: 0,
+        reserved_names: 0,
+        invalid_characters: 0,
+        anomalies: Vec::new(),
+        truncated: false,
+    };
+
+    for item in items {
+        let kinds = check(&item.path, &item.name);
+        if kinds.is_empty() {
+            continue;
+        }
+        for kind in &kinds {
+            match kind {
+                PathAnomalyKind::OverMaxPath => report.over_max_path += 1,
+                PathAnomalyKind::NearMaxPath => report.near_max_path += 1,
+                PathAnomalyKind::TrailingSpaceOrDot => report.trailing_space_or_dot += 1,
+                PathAnomalyKind::ReservedName => report.reserved_names += 1,
+                PathAnomalyKind::InvalidCharacters => report.invalid_characters += 1,
+            }
+        }
+        report.anomalies.push(PathAnomaly {
+            path: item.path.to_string(),
+            is_dir: item.is_dir,
+            length: item.path.encode_utf16().count(),
+            kinds,
+        });
+    }
+
+    report
+        .anomalies
+        .sort_unstable_by(|a, b| b.length.cmp(&a.length).then_with(|| a.path.cmp(&b.path)));
+    if report.anomalies.len() > MAX_LISTED {
+        report.anomalies.truncate(MAX_LISTED);
+        report.truncated = true;
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_length_and_name_anomalies() {
+        use PathAnomalyKind::*;
+
+        assert!(check("C:/data/report.txt", "report.txt").is_empty());
+        assert_eq!(check("C:/data/notes. ", "notes. "), vec![TrailingSpaceOrDot]);
+        assert_eq!(check("/srv/nul.txt", "nul.txt"), vec![ReservedName]);
+        assert_eq!(check("/srv/COM1", "COM1"), vec![ReservedName]);
+        assert!(check("/srv/console", "console").is_empty());
+        assert_eq!(check("/srv/a:b?", "a:b?"), vec![InvalidCharacters]);
+        assert_eq!(check("/srv/tab\there", "tab\there"), vec![InvalidCharacters]);
+
+        let deep = format!("C:/{}", "x".repeat(MAX_PATH));
+        assert_eq!(check(&deep, "x"), vec![OverMaxPath]);
+        let near = format!("C:/{}", "x".repeat(MAX_PATH - 10));
+        assert_eq!(check(&near, "x"), vec![NearMaxPath]);
+        // 按 UTF-16 单元计：中文名称每字 1 个单元
+        let cjk = format!("C:/{}", "文".repeat(200));
+        assert!(check(&cjk, "文").is_empty());
+    }
+}
@@ -1,12 +1,16 @@
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use std::collections::VecDeque;
 use std::sync::Arc;
 use std::time::Instant;
 use lazy_static::lazy_static;
 
+mod sampler;
+pub use sampler::*;
+
 /// 扫描性能指标
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ScanMetrics {
     pub scan_id: String,
@@ -30,6 +34,11 @@ pub struct ScanMetrics {
     pub cache_hit: bool,
     pub cache_read_time_ms: u64,
     pub errors: Vec<String>,
+    /// 扫描期间的资源占用时间序列（本进程占系统 CPU / 内存 / 磁盘读取的比例）
+    #[serde(default)]
+    pub resource_samples: Vec<ResourceSample>,
+    #[serde(default)]
+    pub resource_usage: Option<ResourceUsageSummary>,
 }
 
 impl Default for ScanMetrics {
@@ -56,6 +65,8 @@ impl Default for ScanMetrics {
             cache_hit: false,
             cache_read_time_ms: 0,
             errors: Vec::new(),
+            resource_samples: Vec::new(),
+            resource_usage: None,
         }
     }
 }
@@ -71,6 +82,7 @@ struct ScanSession {
     io_timer: Instant,
     compute_timer: Instant,
     start_instant: Instant,
+    sampler: Option<ResourceSampler>,
 }
 
 lazy_static! {
@@ -104,6 +116,7 @@ impl PerformanceMonitor {
             io_timer: now,
             compute_timer: now,
             start_instant: now,
+            sampler: Some(ResourceSampler::start()),
         };
 
         *self.current_scan.lock() = Some(session);
@@ -181,6 +194,10 @@ impl PerformanceMonitor {
             let mut metrics = session.metrics;
             metrics.end_time = Some(chrono::Utc::now());
             metrics.duration_ms = session.start_instant.elapsed().as_millis() as u64;
+            if let Some(sampler) = session.sampler {
+                metrics.resource_samples = sampler.stop();
+                metrics.resource_usage = summarize(&metrics.resource_samples);
+            }
 
             let mut history = self.history.lock();
             if history.len() >= self.max_history {
@@ -237,7 +254,7 @@ impl PerformanceMonitor {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PerformanceSummary {
     pub total_scans: usize,
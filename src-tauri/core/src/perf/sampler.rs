@@ -0,0 +1,224 @@
+// 扫描期间的资源占用采样
+//
+// 扫描开始时启动后台线程，定期采样本进程占整个系统的 CPU / 内存 / 磁盘读取比例，
+// 扫描结束时生成时间序列与汇总，附加到 ScanMetrics，用于验证扫描是否"吃满"系统。
+//
+// 磁盘带宽占比 = 本进程读取字节 / 所有进程读取字节之和（sysinfo 不提供系统级磁盘 I/O 计数）
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// 初始采样间隔
+const SAMPLE_INTERVAL_MS: u64 = 500;
+/// 时间序列最大长度：超出后隔点抽稀并把间隔加倍，长时间扫描也只保留有限个点
+const MAX_SAMPLES: usize = 240;
+
+/// 单个采样点
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceSample {
+    /// 距扫描开始的毫秒数
+    pub elapsed_ms: u64,
+    /// 本进程占全部 CPU 的百分比（已按核数归一化，0-100）
+    pub cpu_share_percent: f64,
+    /// 系统整体 CPU 使用率
+    pub system_cpu_percent: f64,
+    pub memory_mb: f64,
+    /// 本进程内存占物理内存的百分比
+    pub memory_share_percent: f64,
+    pub disk_read_mbps: f64,
+    /// 本进程读取量占所有进程读取量的百分比
+    pub disk_share_percent: f64,
+}
+
+/// 时间序列汇总
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceUsageSummary {
+    pub sample_count: usize,
+    pub avg_cpu_share_percent: f64,
+    pub peak_cpu_share_percent: f64,
+    pub avg_system_cpu_percent: f64,
+    pub peak_memory_mb: f64,
+    pub peak_memory_share_percent: f64,
+    pub avg_disk_read_mbps: f64,
+    pub peak_disk_read_mbps: f64,
+    pub avg_disk_share_percent: f64,
+}
+
+/// 汇总采样点；无采样（如缓存命中的瞬时扫描）时返回 None
+pub fn summarize(samples: &[ResourceSample]) -> Option<ResourceUsageSummary> {
+    if samples.is_empty() {
+        return None;
+    }
+    let n = samples.len() as f64;
+    let avg = |f: fn(&ResourceSample) -> f64| samples.iter().map(f).sum::<f64>() / n;
+    let peak = |f: fn(&ResourceSample) -> f64| samples.iter().map(f).fold(0.0, f64::max);
+
+    Some(ResourceUsageSummary {
+        sample_count: samples.len(),
+        avg_cpu_share_percent: avg(|s| s.cpu_share_percent),
+        peak_cpu_share_percent: peak(|s| s.cpu_share_percent),
+        avg_system_cpu_percent: avg(|s| s.system_cpu_percent),
+        peak_memory_mb: peak(|s| s.memory_mb),
+        peak_memory_share_percent: peak(|s| s.memory_share_percent),
+        avg_disk_read_mbps: avg(|s| s.disk_read_mbps),
+        peak_disk_read_mbps: peak(|s| s.disk_read_mbps),
+        avg_disk_share_percent: avg(|s| s.disk_share_percent),
+    })
+}
+
+/// 后台采样器：drop 或 stop 时通知线程退出（不等待线程结束）
+pub struct ResourceSampler {
+    stop_tx: Option<Sender<()>>,
+    samples: Arc<Mutex<Vec<ResourceSample>>>,
+}
+
+impl ResourceSampler {
+    pub fn start() -> Self {
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let samples = Arc::new(Mutex::new(Vec::new()));
+        let samples_for_thread = Arc::clone(&samples);
+
+        std::thread::Builder::new()
+            .name("flashdir-resource-sampler".to_string())
+            .spawn(move || {
+                use sysinfo::System;
+
+                let Ok(pid) = sysinfo::get_current_pid() else {
+                    return;
+                };
+                let start = Instant::now();
+                let mut system = System::new();
+                // 基线刷新：CPU 占用需要两次刷新之间的差值
+                system.refresh_cpu();
+                system.refresh_memory();
+                system.refresh_processes();
+                let cpu_count = system.cpus().len().max(1) as f64;
+                let total_memory = system.total_memory().max(1) as f64;
+
+                let mut interval = Duration::from_millis(SAMPLE_INTERVAL_MS);
+                let mut last = Instant::now();
+
+                // 超时即到下一个采样点；收到停止信号或发送端断开时退出
+                while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(interval) {
+                    system.refresh_cpu();
+                    system.refresh_memory();
+                    system.refresh_processes();
+                    let secs = last.elapsed().as_secs_f64().max(0.001);
+                    last = Instant::now();
+
+                    let Some(process) = system.process(pid) else {
+                        break;
+                    };
+                    let read_bytes = process.disk_usage().read_bytes;
+                    let system_read_bytes: u64 = system
+                        .processes()
+                        .values()
+                        .map(|p| p.disk_usage().read_bytes)
+                        .sum();
+
+                    let sample = ResourceSample {
+                        elapsed_ms: start.elapsed().as_millis() as u64,
+                        cpu_share_percent: (process.cpu_usage() as f64 / cpu_count).min(100.0),
+                        system_cpu_percent: system.global_cpu_info().cpu_usage() as f64,
+                        memory_mb: process.memory() as f64 / 1024.0 / 1024.0,
+                        memory_share_percent: process.memory() as f64 / total_memory * 100.0,
+                        disk_read_mbps: read_bytes as f64 / 1024.0 / 1024.0 / secs,
+                        disk_share_percent: if system_read_bytes > 0 {
+                            read_bytes as f64 / system_read_bytes as f64 * 100.0
+                        } else {
+                            0.0
+                        },
+                    };
+
+                    let mut samples = samples_for_thread.lock();
+                    samples.push(sample);
+                    if samples.len() >= MAX_SAMPLES {
+                        let mut i = 0;
+                        samples.retain(|_| {
+                            i += 1;
+                            i % 2 == 0
+                        });
+                        interval *= 2;
+                    }
+                }
+            })
+            .ok();
+
+        Self {
+            stop_tx: Some(stop_tx),
+            samples,
+        }
+    }
+
+    /// 停止采样并取出时间序列
+    pub fn stop(mut self) -> Vec<ResourceSample> {
+        self.stop_tx.take();
+        std::mem::take(&mut *self.samples.lock())
+    }
+}
+
+impl Drop for ResourceSampler {
+    fn drop(&mut self) {
+        // 丢弃 Sender 即通知采样线程退出
+        self.stop_tx.take();
+    }
+}
+
+/// 本进程常驻内存与系统物理内存（字节），无法获取时返回 None
+pub fn process_memory() -> Option<(u64, u64)> {
+    use sysinfo::System;
+
+    let pid = sysinfo::get_current_pid().ok()?;
+    let mut system = System::new();
+    system.refresh_memory();
+    system.refresh_process(pid);
+    Some((system.process(pid)?.memory(), system.total_memory()))
+}
+
+/// 系统整体 CPU 使用率（0-100）。两次采样相隔 sysinfo 的最小刷新间隔，调用会阻塞约 200ms
+pub fn system_cpu_percent() -> f64 {
+    use sysinfo::System;
+
+    let mut system = System::new();
+    system.refresh_cpu();
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    system.refresh_cpu();
+    system.global_cpu_info().cpu_usage() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(cpu: f64, mem: f64, disk: f64) -> ResourceSample {
+        ResourceSample {
+            elapsed_ms: 0,
+            cpu_share_percent: cpu,
+            system_cpu_percent: cpu * 2.0,
+            memory_mb: mem,
+            memory_share_percent: mem / 100.0,
+            disk_read_mbps: disk,
+            disk_share_percent: 50.0,
+        }
+    }
+
+    #[test]
+    fn summarize_averages_and_peaks() {
+        assert!(summarize(&[]).is_none());
+
+        let s = summarize(&[sample(10.0, 100.0, 20.0), sample(30.0, 300.0, 40.0)]).unwrap();
+        assert_eq!(s.sample_count, 2);
+        assert_eq!(s.avg_cpu_share_percent, 20.0);
+        assert_eq!(s.peak_cpu_share_percent, 30.0);
+        assert_eq!(s.avg_system_cpu_percent, 40.0);
+        assert_eq!(s.peak_memory_mb, 300.0);
+        assert_eq!(s.avg_disk_read_mbps, 30.0);
+        assert_eq!(s.peak_disk_read_mbps, 40.0);
+    }
+}
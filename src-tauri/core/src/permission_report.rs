@@ -0,0 +1,194 @@
+// 权限覆盖报告
+//
+// 扫描中无法读取的目录记录在 ScanResult::errors 里，列表很长时难以看出影响范围。
+// 按扫描根下的一级子目录归类：完全可读、部分可读（子树中有无法读取的目录）、
+// 拒绝访问（子目录本身无法读取），并给出无法读取的目录数与大小估计，
+// 用户据此判断是否值得以管理员权限补扫（rescan_elevated）。
+
+use crate::scan::{format_size, ScanError, ScanErrorKind, ScanResult};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum Readability {
+    Readable,
+    /// 子树中有无法读取的目录
+    Partial,
+    /// 目录本身无法读取
+    Denied,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DirPermission {
+    pub path: String,
+    pub readability: Readability,
+    /// 子树中无法读取的目录数（含自身）
+    pub inaccessible: usize,
+    /// 无法读取部分的大小估计（取自上次快照）
+    pub estimated_bytes: i64,
+    pub estimated_bytes_formatted: String,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionReport {
+    pub path: String,
+    pub readable: usize,
+    pub partial: usize,
+    pub denied: usize,
+    /// 无法读取的目录总数
+    pub inaccessible: usize,
+    /// 其中因拒绝访问而无法读取的
+    pub access_denied: usize,
+    pub estimated_bytes: i64,
+    pub estimated_bytes_formatted: String,
+    /// 存在拒绝访问的目录且当前进程不是管理员：提权补扫可能覆盖这些目录
+    pub elevation_may_help: bool,
+    /// 一级子目录，拒绝访问与部分可读的在前
+    pub dirs: Vec<DirPermission>,
+}
+
+/// 按一级子目录归类 result 中无法读取的目录
+pub fn analyze(result: &ScanResult, is_admin: bool) -> PermissionReport {
+    let top_dirs = result.items.iter().filter(|i| i.is_dir).map(|i| i.path.as_str());
+    classify(&result.path, top_dirs, &result.errors, is_admin)
+}
+
+/// 由扫描根、其下的目录（只取一级子目录，其余忽略）与无法读取的目录生成报告
+pub fn classify<'a>(
+    root_path: &str,
+    dirs: impl IntoIterator<Item = &'a str>,
+    errors: &[ScanError],
+    is_admin: bool,
+) -> PermissionReport {
+    let root = root_path.trim_end_matches('/');
+    let top_level = |path: &str| -> Option<usize> {
+        let rest = path.strip_prefix(root)?.strip_prefix('/')?;
+        Some(root.len() + 1 + rest.find('/').unwrap_or(rest.len()))
+    };
+
+    let mut dirs: Vec<DirPermission> = dirs
+        .into_iter()
+        .filter(|path| top_level(path) == Some(path.len()))
+        .map(|path| DirPermission {
+            path: path.to_string(),
+            readability: Readability::Readable,
+            inaccessible: 0,
+            estimated_bytes: 0,
+            estimated_bytes_formatted: String::new(),
+        })
+        .collect();
+    dirs.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+    for error in errors {
+        let Some(end) = top_level(&error.path) else {
+            continue;
+        };
+        let top = &error.path[..end];
+        let Ok(idx) = dirs.binary_search_by(|d| d.path.as_str().cmp(top)) else {
+            continue;
+        };
+        let dir = &mut dirs[idx];
+        dir.inaccessible += 1;
+        dir.estimated_bytes += error.estimated_bytes;
+        dir.readability = if error.path.len() == end {
+            Readability::Denied
+        } else if dir.readability == Readability::Readable {
+            Readability::Partial
+        } else {
+            dir.readability
+        };
+    }
+
+    for dir in &mut dirs {
+        dir.estimated_bytes_formatted = format_size(dir.estimated_bytes).to_string();
+    }
+    dirs.sort_by_key(|d| match d.readability {
+        Readability::Denied => 0,
+        Readability::Partial => 1,
+        Readability::Readable => 2,
+    });
+
+    let count = |r: Readability| dirs.iter().filter(|d| d.readability == r).count();
+    let access_denied = errors.iter().filter(|e| e.kind == ScanErrorKind::AccessDenied).count();
+    let estimated_bytes = errors.iter().map(|e| e.estimated_bytes).sum();
+    PermissionReport {
+        path: root_path.to_string(),
+        readable: count(Readability::Readable),
+        partial: count(Readability::Partial),
+        denied: count(Readability::Denied),
+        inaccessible: errors.len(),
+        access_denied,
+        estimated_bytes,
+        estimated_bytes_formatted: format_size(estimated_bytes).to_string(),
+        elevation_may_help: access_denied > 0 && !is_admin,
+        dirs,
+    }
+}
+
+/// 基于 path 当前的扫描结果（内存或磁盘缓存）生成报告，没有扫描结果时返回 None
+pub fn permission_report(path: &str) -> Option<PermissionReport> {
+    let (_, result) = crate::scan::current_scan_result(path)?;
+    Some(analyze(&result, crate::fs::is_admin()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{CompactString, Item};
+
+    fn dir(path: &str) -> Item {
+        Item::test_dir(path, 0)
+    }
+
+    fn error(path: &str, kind: ScanErrorKind, estimated_bytes: i64) -> ScanError {
+        ScanError { path: CompactString::from(path), kind, estimated_bytes }
+    }
+
+    #[test]
+    fn classifies_top_level_dirs() {
+        let errors = vec![
+            error("/r/locked", ScanErrorKind::AccessDenied, 100),
+            error("/r/mixed/a/b", ScanErrorKind::AccessDenied, 10),
+            error("/r/mixed/c", ScanErrorKind::NotFound, 0),
+        ];
+        let result = ScanResult {
+            items: vec![dir("/r/open"), dir("/r/open/sub"), dir("/r/locked"), dir("/r/mixed"), dir("/r/mixed/a")],
+            total_size: 0,
+            total_size_formatted: CompactString::new(),
+            scan_time: 0.0,
+            path: CompactString::from("/r"),
+            mft_available: false,
+            timing: None,
+            perf_metrics: None,
+            extension_stats: None,
+            category_stats: Vec::new(),
+            owners: None,
+            unscanned: None,
+            unreadable_bytes: errors.iter().map(|e| e.estimated_bytes).sum(),
+            errors,
+            cloud_only_bytes: 0,
+            cloud_local_bytes: 0,
+            sparse_files: Vec::new(),
+            sparse_savings_bytes: 0,
+            cache_usage: Default::default(),
+        };
+
+        let report = analyze(&result, false);
+        let status: Vec<_> = report.dirs.iter().map(|d| (d.path.as_str(), d.readability, d.inaccessible)).collect();
+        assert_eq!(
+            status,
+            vec![
+                ("/r/locked", Readability::Denied, 1),
+                ("/r/mixed", Readability::Partial, 2),
+                ("/r/open", Readability::Readable, 0),
+            ]
+        );
+        assert_eq!((report.readable, report.partial, report.denied), (1, 1, 1));
+        assert_eq!((report.inaccessible, report.access_denied, report.estimated_bytes), (3, 2, 110));
+        assert!(report.elevation_may_help);
+        assert!(!analyze(&result, true).elevation_may_help);
+    }
+}
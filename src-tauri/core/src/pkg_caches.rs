@@ -0,0 +1,381 @@
+// 包管理器缓存分析与安全清理
+//
+// cargo / npm / pnpm / pip / Gradle 的全局缓存常年只增不减，动辄几十 GB，
+// 但直接删除缓存目录可能破坏工具的内部状态（pnpm 内容寻址存储的硬链接、cargo 的索引锁等）。
+// 这里按各工具的约定位置（尊重 CARGO_HOME / npm_config_cache / PIP_CACHE_DIR / GRADLE_USER_HOME 等）
+// 统计缓存大小，并只提供工具自带的清理命令：
+//   cargo clean（按项目清理 target）、npm cache clean --force、pnpm store prune、pip cache purge
+// Gradle 没有清理缓存的自带命令（8.0 起自动清理过期条目），只报告大小。
+//
+// Rust 项目的 target 目录来自扫描结果：与 Cargo.toml 同级的 target 目录。
+
+use crate::scan::{format_size, Item, ItemKind};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// 清理命令输出保留的最大字节数（超出部分截掉开头，保留结尾的汇总信息）
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum PackageTool {
+    Cargo,
+    Npm,
+    Pnpm,
+    Pip,
+    Gradle,
+}
+
+impl PackageTool {
+    pub const ALL: [PackageTool; 5] =
+        [PackageTool::Cargo, PackageTool::Npm, PackageTool::Pnpm, PackageTool::Pip, PackageTool::Gradle];
+
+    fn label(self) -> &'static str {
+        match self {
+            PackageTool::Cargo => "Cargo",
+            PackageTool::Npm => "npm",
+            PackageTool::Pnpm => "pnpm",
+            PackageTool::Pip => "pip",
+            PackageTool::Gradle => "Gradle",
+        }
+    }
+
+    /// 工具自带的清理命令（程序名 + 参数）；Gradle 没有
+    fn clean_command(self) -> Option<(&'static str, &'static [&'static str])> {
+        match self {
+            PackageTool::Cargo => Some(("cargo", &["clean"])),
+            PackageTool::Npm => Some(("npm", &["cache", "clean", "--force"])),
+            PackageTool::Pnpm => Some(("pnpm", &["store", "prune"])),
+            PackageTool::Pip => Some(("pip", &["cache", "purge"])),
+            PackageTool::Gradle => None,
+        }
+    }
+}
+
+/// 一个缓存位置
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheLocation {
+    pub path: String,
+    /// 说明，如 "registry 下载缓存"、"target 构建产物"
+    pub label: String,
+    pub size: i64,
+    pub size_formatted: String,
+    pub file_count: u64,
+    /// 清理命令可回收（否则仅供参考，如 cargo 的 git 索引）
+    pub reclaimable: bool,
+    /// cargo target 所属项目目录（run_safe_clean 的 project 参数）
+    pub project: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageCacheReport {
+    pub tool: PackageTool,
+    pub label: String,
+    /// 只列出存在的位置
+    pub locations: Vec<CacheLocation>,
+    pub total_size: i64,
+    pub total_size_formatted: String,
+    pub reclaimable_bytes: i64,
+    pub reclaimable_bytes_formatted: String,
+    /// 展示用的清理命令，如 "pnpm store prune"；None 表示没有安全的自带命令
+    pub clean_command: Option<String>,
+}
+
+/// 清理命令的执行结果
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanOutput {
+    pub tool: PackageTool,
+    pub command: String,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    /// 清理前后缓存位置的大小差
+    pub freed_bytes: i64,
+    pub freed_bytes_formatted: String,
+    pub duration_ms: u64,
+}
+
+fn home() -> Option<PathBuf> {
+    std::env::var_os("USERPROFILE").or_else(|| std::env::var_os("HOME")).map(PathBuf::from)
+}
+
+fn env_path(var: &str) -> Option<PathBuf> {
+    std::env::var_os(var).filter(|v| !v.is_empty()).map(PathBuf::from)
+}
+
+/// 各平台的用户缓存根目录（Windows %LOCALAPPDATA%，macOS ~/Library/Caches，Linux $XDG_CACHE_HOME 或 ~/.cache）
+fn cache_root() -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        env_path("LOCALAPPDATA")
+    } else if cfg!(target_os = "macos") {
+        home().map(|h| h.join("Library/Caches"))
+    } else {
+        env_path("XDG_CACHE_HOME").or_else(|| home().map(|h| h.join(".cache")))
+    }
+}
+
+/// 工具的全局缓存位置：(路径, 说明, 可回收)
+fn global_locations(tool: PackageTool) -> Vec<(PathBuf, &'static str, bool)> {
+    let home = home();
+    match tool {
+        PackageTool::Cargo => {
+            let Some(cargo_home) = env_path("CARGO_HOME").or_else(|| home.map(|h| h.join(".cargo"))) else {
+                return Vec::new();
+            };
+            // cargo clean 只清理项目 target；注册表缓存由 cargo 1.78+ 按使用时间自动回收
+            vec![
+                (cargo_home.join("registry/cache"), "registry 下载缓存", false),
+                (cargo_home.join("registry/src"), "registry 解压源码", false),
+                (cargo_home.join("git/checkouts"), "git 依赖检出", false),
+            ]
+        }
+        PackageTool::Npm => {
+            let dir = env_path("npm_config_cache").or_else(|| {
+                if cfg!(target_os = "windows") {
+                    env_path("LOCALAPPDATA").map(|d| d.join("npm-cache"))
+                } else {
+                    home.map(|h| h.join(".npm"))
+                }
+            });
+            dir.map(|d| vec![(d.join("_cacache"), "内容缓存", true)]).unwrap_or_default()
+        }
+        PackageTool::Pnpm => {
+            let dir = env_path("PNPM_HOME").map(|d| d.join("store")).or_else(|| {
+                if cfg!(target_os = "windows") {
+                    env_path("LOCALAPPDATA").map(|d| d.join("pnpm/store"))
+                } else if cfg!(target_os = "macos") {
+                    home.map(|h| h.join("Library/pnpm/store"))
+                } else {
+                    env_path("XDG_DATA_HOME")
+                        .or_else(|| home.map(|h| h.join(".local/share")))
+                        .map(|d| d.join("pnpm/store"))
+                }
+            });
+            // prune 只删除未被任何项目引用的包，实际回收量以执行结果为准
+            dir.map(|d| vec![(d, "内容寻址存储", true)]).unwrap_or_default()
+        }
+        PackageTool::Pip => {
+            let dir = env_path("PIP_CACHE_DIR").or_else(|| {
+                let root = cache_root()?;
+                Some(if cfg!(target_os = "windows") { root.join("pip/Cache") } else { root.join("pip") })
+            });
+            dir.map(|d| vec![(d, "下载与构建缓存", true)]).unwrap_or_default()
+        }
+        PackageTool::Gradle => {
+            let Some(gradle_home) = env_path("GRADLE_USER_HOME").or_else(|| home.map(|h| h.join(".gradle"))) else {
+                return Vec::new();
+            };
+            vec![
+                (gradle_home.join("caches"), "依赖与构建缓存", false),
+                (gradle_home.join("wrapper/dists"), "Gradle 发行版", false),
+            ]
+        }
+    }
+}
+
+/// 目录大小与文件数（不跟随链接类条目），目录不存在时返回 None
+fn dir_size(path: &Path) -> Option<(i64, u64)> {
+    if !path.is_dir() {
+        return None;
+    }
+    let mut size = 0i64;
+    let mut files = 0u64;
+    let mut stack = vec![path.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = crate::fs::read_dir_entries(&dir) else {
+            continue;
+        };
+        for entry in entries {
+            if entry.is_dir {
+                if entry.kind.descends() {
+                    stack.push(entry.path);
+                }
+            } else {
+                size += entry.size as i64;
+                files += 1;
+            }
+        }
+    }
+    Some((size, files))
+}
+
+fn location(path: &Path, label: &str, reclaimable: bool, project: Option<String>) -> Option<CacheLocation> {
+    let (size, file_count) = dir_size(path)?;
+    Some(CacheLocation {
+        path: crate::fs::display_path(&path.to_string_lossy()),
+        label: label.to_string(),
+        size,
+        size_formatted: format_size(size).to_string(),
+        file_count,
+        reclaimable,
+        project,
+    })
+}
+
+/// 扫描结果中的 Rust 项目：与 Cargo.toml 同级的 target 目录，返回 (项目目录, target 目录)
+pub fn cargo_targets(items: &[Item]) -> Vec<(String, String)> {
+    let manifests: std::collections::HashSet<&str> = items
+        .iter()
+        .filter(|i| i.kind == ItemKind::File && i.name == "Cargo.toml")
+        .filter_map(|i| i.path.rsplit_once('/').map(|(parent, _)| parent))
+        .collect();
+    let mut targets: Vec<(String, String)> = items
+        .iter()
+        .filter(|i| i.is_dir && i.name == "target")
+        .filter_map(|i| {
+            let (parent, _) = i.path.rsplit_once('/')?;
+            manifests.contains(parent).then(|| (parent.to_string(), i.path.to_string()))
+        })
+        .collect();
+    targets.sort();
+    targets
+}
+
+fn report(tool: PackageTool, locations: Vec<CacheLocation>) -> PackageCacheReport {
+    let total_size = locations.iter().map(|l| l.size).sum();
+    let reclaimable_bytes = locations.iter().filter(|l| l.reclaimable).map(|l| l.size).sum();
+    PackageCacheReport {
+        tool,
+        label: tool.label().to_string(),
+        locations,
+        total_size,
+        total_size_formatted: format_size(total_size).to_string(),
+        reclaimable_bytes,
+        reclaimable_bytes_formatted: format_size(reclaimable_bytes).to_string(),
+        clean_command: tool.clean_command().map(|(program, args)| {
+            let command = std::iter::once(program).chain(args.iter().copied()).collect::<Vec<_>>().join(" ");
+            if tool == PackageTool::Cargo {
+                format!("{}（在各项目目录执行）", command)
+            } else {
+                command
+            }
+        }),
+    }
+}
+
+/// 统计各工具的缓存大小，按总大小降序；items 为扫描结果（用于查找 cargo target），可为空
+pub fn analyze(items: &[Item]) -> Vec<PackageCacheReport> {
+    use rayon::prelude::*;
+
+    let mut reports: Vec<PackageCacheReport> = PackageTool::ALL
+        .par_iter()
+        .map(|&tool| {
+            let mut locations: Vec<CacheLocation> = global_locations(tool)
+                .iter()
+                .filter_map(|(path, label, reclaimable)| location(path, label, *reclaimable, None))
+                .collect();
+            if tool == PackageTool::Cargo {
+                locations.extend(cargo_targets(items).into_iter().filter_map(|(project, target)| {
+                    location(Path::new(&target), "target 构建产物", true, Some(project))
+                }));
+            }
+            report(tool, locations)
+        })
+        .filter(|r| !r.locations.is_empty())
+        .collect();
+    reports.sort_by_key(|r| std::cmp::Reverse(r.total_size));
+    reports
+}
+
+/// 输出转为字符串，过长时只保留结尾
+fn capture(bytes: &[u8]) -> String {
+    let start = bytes.len().saturating_sub(MAX_OUTPUT_BYTES);
+    String::from_utf8_lossy(&bytes[start..]).into_owned()
+}
+
+/// 执行工具自带的清理命令并捕获输出。cargo 需要 project（含 Cargo.toml 的项目目录）
+pub fn run_safe_clean(tool: PackageTool, project: Option<&str>) -> anyhow::Result<CleanOutput> {
+    let Some((program, args)) = tool.clean_command() else {
+        anyhow::bail!("{} 没有安全的自带清理命令", tool.label());
+    };
+
+    let (measured, workdir): (Vec<PathBuf>, Option<PathBuf>) = if tool == PackageTool::Cargo {
+        let Some(project) = project else {
+            anyhow::bail!("cargo clean 需要指定项目目录");
+        };
+        let project = PathBuf::from(project);
+        if !project.join("Cargo.toml").is_file() {
+            anyhow::bail!("不是 Rust 项目（缺少 Cargo.toml）: {}", project.display());
+        }
+        (vec![project.join("target")], Some(project))
+    } else {
+        (global_locations(tool).into_iter().map(|(path, _, _)| path).collect(), None)
+    };
+    let measure = || measured.iter().filter_map(|p| dir_size(p)).map(|(size, _)| size).sum::<i64>();
+
+    // Windows 上 npm / pnpm 是 .cmd 脚本，须经 cmd 启动
+    let mut command = if cfg!(target_os = "windows") && matches!(tool, PackageTool::Npm | PackageTool::Pnpm) {
+        let mut c = std::process::Command::new("cmd");
+        c.arg("/C").arg(program);
+        c
+    } else {
+        std::process::Command::new(program)
+    };
+    command.args(args).stdin(std::process::Stdio::null());
+    if let Some(dir) = &workdir {
+        command.current_dir(dir);
+    }
+
+    let before = measure();
+    let start = Instant::now();
+    let output = command.output().map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            anyhow::anyhow!("未找到 {}，请确认已安装并在 PATH 中", program)
+        } else {
+            anyhow::anyhow!("启动 {} 失败: {}", program, e)
+        }
+    })?;
+    let duration_ms = start.elapsed().as_millis() as u64;
+    let freed_bytes = (before - measure()).max(0);
+
+    Ok(CleanOutput {
+        tool,
+        command: std::iter::once(program).chain(args.iter().copied()).collect::<Vec<_>>().join(" "),
+        success: output.status.success(),
+        exit_code: output.status.code(),
+        stdout: capture(&output.stdout),
+        stderr: capture(&output.stderr),
+        freed_bytes,
+        freed_bytes_formatted: format_size(freed_bytes).to_string(),
+        duration_ms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_cargo_targets_and_refuses_unsafe_clean() {
+        let items = vec![
+            Item::test("r/app", 0, true),
+            Item::test("r/app/Cargo.toml", 0, false),
+            Item::test("r/app/target", 0, true),
+            // 没有 Cargo.toml 的 target（如 Maven）不算
+            Item::test("r/java/target", 0, true),
+            Item::test("r/app/crates/x/Cargo.toml", 0, false),
+            Item::test("r/app/crates/x/target", 0, true),
+        ];
+        assert_eq!(
+            cargo_targets(&items),
+            vec![
+                ("r/app".to_string(), "r/app/target".to_string()),
+                ("r/app/crates/x".to_string(), "r/app/crates/x/target".to_string()),
+            ]
+        );
+
+        assert!(run_safe_clean(PackageTool::Gradle, None).is_err());
+        assert!(run_safe_clean(PackageTool::Cargo, None).is_err());
+        let not_rust = std::env::temp_dir();
+        assert!(run_safe_clean(PackageTool::Cargo, Some(not_rust.to_str().unwrap())).is_err());
+
+        let report = report(PackageTool::Pnpm, Vec::new());
+        assert_eq!(report.clean_command.as_deref(), Some("pnpm store prune"));
+    }
+}
@@ -0,0 +1,445 @@
+// 结果条目的内容预览：删除来历不明的大文件前先看一眼里面是什么
+//
+// 按文件头判断类型：
+// - 图片：PNG / JPEG 解码后按盒式滤波缩小为不超过 THUMBNAIL_SIZE 的缩略图（重新编码为 PNG，
+//   JPEG 先用 DCT 缩放按 1/2 ~ 1/8 解码）；GIF / BMP / WebP / ICO 没有内置解码器，
+//   不超过 MAX_INLINE_IMAGE 时原样内嵌，由前端 WebView 解码缩放，更大的只返回从文件头读出的尺寸
+// - 文本：读取前 max_bytes 字节，依次按 BOM、无 BOM 的 UTF-16、UTF-8、GB18030 判断编码，
+//   都不符合时按 windows-1252 解码
+// - 其他：前 HEX_DUMP_BYTES 字节的十六进制转储
+// 图片与缩略图以 data URL 返回，前端可直接作为 <img> 的 src。
+
+use crate::scan::{format_size, CompactString};
+use base64::Engine;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::io::Read;
+
+/// 文本预览缺省读取的字节数
+pub const DEFAULT_PREVIEW_BYTES: usize = 64 * 1024;
+/// 文本预览读取字节数上限
+pub const MAX_PREVIEW_BYTES: usize = 1024 * 1024;
+/// 缩略图最长边（像素）
+pub const THUMBNAIL_SIZE: u32 = 256;
+/// 无法解码的图片格式不超过此大小时原样内嵌
+const MAX_INLINE_IMAGE: u64 = 4 * 1024 * 1024;
+/// PNG / JPEG 解码允许分配的内存上限（约 6400 万像素 RGBA）
+const MAX_DECODE_BYTES: usize = 256 * 1024 * 1024;
+/// 二进制文件转储的字节数
+const HEX_DUMP_BYTES: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum PreviewKind {
+    Text,
+    Image,
+    Binary,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FilePreview {
+    pub path: String,
+    pub size: i64,
+    #[schemars(with = "String")]
+    pub size_formatted: CompactString,
+    pub kind: PreviewKind,
+    /// 文本：检测到的编码（WHATWG 名称，如 UTF-8、gb18030、UTF-16LE）
+    pub encoding: Option<String>,
+    pub text: Option<String>,
+    /// 图片：缩略图或原图的 data URL；格式无法解码且文件过大时为 None
+    pub thumbnail: Option<String>,
+    /// 图片原始尺寸（从文件头读出，未知格式为 None）
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// 二进制：带偏移与 ASCII 列的十六进制转储
+    pub hex_dump: Option<String>,
+    /// 文本或转储只覆盖了文件开头的一部分
+    pub truncated: bool,
+}
+
+/// 文件头识别的图片格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+    Bmp,
+    WebP,
+    Ico,
+}
+
+impl ImageFormat {
+    fn sniff(head: &[u8]) -> Option<ImageFormat> {
+        match head {
+            [0x89, b'P', b'N', b'G', ..] => Some(ImageFormat::Png),
+            [0xFF, 0xD8, 0xFF, ..] => Some(ImageFormat::Jpeg),
+            [b'G', b'I', b'F', b'8', ..] => Some(ImageFormat::Gif),
+            [b'B', b'M', ..] if head.len() >= 26 => Some(ImageFormat::Bmp),
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some(ImageFormat::WebP),
+            [0, 0, 1, 0, ..] => Some(ImageFormat::Ico),
+            _ => None,
+        }
+    }
+
+    fn mime(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "image/png",
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Gif => "image/gif",
+            ImageFormat::Bmp => "image/bmp",
+            ImageFormat::WebP => "image/webp",
+            ImageFormat::Ico => "image/x-icon",
+        }
+    }
+
+    /// 从文件头读取尺寸
+    fn dimensions(self, head: &[u8]) -> Option<(u32, u32)> {
+        let be32 = |at: usize| Some(u32::from_be_bytes(head.get(at..at + 4)?.try_into().ok()?));
+        let le16 = |at: usize| Some(u16::from_le_bytes(head.get(at..at + 2)?.try_into().ok()?) as u32);
+        let le32 = |at: usize| Some(i32::from_le_bytes(head.get(at..at + 4)?.try_into().ok()?).unsigned_abs());
+        match self {
+            // IHDR 紧跟在 8 字节签名与 8 字节块头之后
+            ImageFormat::Png => Some((be32(16)?, be32(20)?)),
+            ImageFormat::Gif => Some((le16(6)?, le16(8)?)),
+            // BITMAPINFOHEADER；高度为负表示自上而下存储
+            ImageFormat::Bmp => Some((le32(18)?, le32(22)?)),
+            ImageFormat::Jpeg => jpeg_dimensions(head),
+            ImageFormat::WebP | ImageFormat::Ico => None,
+        }
+    }
+}
+
+/// 在 JPEG 段中查找 SOF 标记（0xC0–0xCF，除 DHT / JPG / DAC）读取尺寸
+fn jpeg_dimensions(head: &[u8]) -> Option<(u32, u32)> {
+    let mut at = 2;
+    while at + 9 < head.len() {
+        if head[at] != 0xFF {
+            return None;
+        }
+        let marker = head[at + 1];
+        let len = u16::from_be_bytes([head[at + 2], head[at + 3]]) as usize;
+        if (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+            let height = u16::from_be_bytes([head[at + 5], head[at + 6]]) as u32;
+            let width = u16::from_be_bytes([head[at + 7], head[at + 8]]) as u32;
+            return Some((width, height));
+        }
+        at += 2 + len;
+    }
+    None
+}
+
+/// 预览 path：文本读取前 max_bytes 字节，图片生成缩略图，其他文件给出十六进制转储
+pub fn preview_file(path: &str, max_bytes: usize) -> anyhow::Result<FilePreview> {
+    let native = crate::fs::to_extended_length(path);
+    let meta = std::fs::metadata(&native).map_err(|e| anyhow::anyhow!("无法读取 {}: {}", path, e))?;
+    if meta.is_dir() {
+        anyhow::bail!("不能预览目录: {}", path);
+    }
+    let size = meta.len();
+    let mut head = Vec::with_capacity(max_bytes.min(size as usize));
+    std::fs::File::open(&native)
+        .and_then(|file| file.take(max_bytes as u64).read_to_end(&mut head))
+        .map_err(|e| anyhow::anyhow!("无法读取 {}: {}", path, e))?;
+    let truncated = (head.len() as u64) < size;
+
+    let mut preview = FilePreview {
+        path: crate::fs::display_path(path),
+        size: size as i64,
+        size_formatted: format_size(size as i64),
+        kind: PreviewKind::Binary,
+        encoding: None,
+        text: None,
+        thumbnail: None,
+        width: None,
+        height: None,
+        hex_dump: None,
+        truncated,
+    };
+
+    if let Some(format) = ImageFormat::sniff(&head) {
+        preview.kind = PreviewKind::Image;
+        preview.truncated = false;
+        (preview.width, preview.height) = format.dimensions(&head).unzip();
+        preview.thumbnail = match format {
+            ImageFormat::Png => std::fs::File::open(&native)
+                .ok()
+                .and_then(|file| png_thumbnail(std::io::BufReader::new(file)).ok()),
+            ImageFormat::Jpeg => std::fs::File::open(&native)
+                .ok()
+                .and_then(|file| jpeg_thumbnail(std::io::BufReader::new(file)).ok()),
+            _ if size <= MAX_INLINE_IMAGE => std::fs::read(&native).ok().map(|bytes| data_url(format.mime(), &bytes)),
+            _ => None,
+        };
+    } else if let Some((encoding, text)) = decode_text(&head, truncated) {
+        preview.kind = PreviewKind::Text;
+        preview.encoding = Some(encoding.to_string());
+        preview.text = Some(text);
+    } else {
+        preview.truncated = size > HEX_DUMP_BYTES as u64;
+        preview.hex_dump = Some(hex_dump(&head[..head.len().min(HEX_DUMP_BYTES)]));
+    }
+    Ok(preview)
+}
+
+fn data_url(mime: &str, bytes: &[u8]) -> String {
+    format!("data:{};base64,{}", mime, base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+/// 按 BOM、无 BOM 的 UTF-16、UTF-8、GB18030、windows-1252 的顺序判断编码；
+/// 像二进制数据（含 NUL 或大量控制字符）时返回 None。truncated 时末尾可能截断在多字节字符中间
+fn decode_text(bytes: &[u8], truncated: bool) -> Option<(&'static str, String)> {
+    if let Some((encoding, bom_len)) = encoding_rs::Encoding::for_bom(bytes) {
+        let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+        return Some((encoding.name(), text.into_owned()));
+    }
+    if let Some(encoding) = utf16_without_bom(bytes) {
+        let (text, _) = encoding.decode_without_bom_handling(bytes);
+        return Some((encoding.name(), text.into_owned()));
+    }
+    if looks_binary(bytes) {
+        return None;
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => return Some(("UTF-8", text.to_string())),
+        // 只是末尾的字符不完整
+        Err(e) if truncated && e.error_len().is_none() => {
+            return Some(("UTF-8", String::from_utf8_lossy(&bytes[..e.valid_up_to()]).into_owned()));
+        }
+        Err(_) => {}
+    }
+    // 截断时末尾最多 3 字节属于不完整的 GB18030 字符
+    let trailing = if truncated { 0..=3.min(bytes.len()) } else { 0..=0 };
+    for cut in trailing {
+        let encoding = encoding_rs::GB18030;
+        if let Some(text) = encoding.decode_without_bom_handling_and_without_replacement(&bytes[..bytes.len() - cut]) {
+            return Some((encoding.name(), text.into_owned()));
+        }
+    }
+    let (text, _) = encoding_rs::WINDOWS_1252.decode_without_bom_handling(bytes);
+    Some((encoding_rs::WINDOWS_1252.name(), text.into_owned()))
+}
+
+/// 无 BOM 的 UTF-16：ASCII 为主的文本每隔一个字节为 0
+fn utf16_without_bom(bytes: &[u8]) -> Option<&'static encoding_rs::Encoding> {
+    if bytes.len() < 4 {
+        return None;
+    }
+    let pairs = bytes.len() / 2;
+    let zeros_at = |offset: usize| bytes.iter().skip(offset).step_by(2).take(pairs).filter(|&&b| b == 0).count();
+    let (even, odd) = (zeros_at(0), zeros_at(1));
+    if odd * 10 >= pairs * 7 && even * 10 < pairs {
+        Some(encoding_rs::UTF_16LE)
+    } else if even * 10 >= pairs * 7 && odd * 10 < pairs {
+        Some(encoding_rs::UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// 含 NUL，或制表 / 换行以外的控制字符超过 10%
+fn looks_binary(bytes: &[u8]) -> bool {
+    let controls = bytes
+        .iter()
+        .filter(|&&b| b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0C | 0x1B))
+        .count();
+    bytes.contains(&0) || controls * 10 > bytes.len()
+}
+
+/// 每行 16 字节：偏移、十六进制、可打印 ASCII
+fn hex_dump(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for (row, chunk) in bytes.chunks(16).enumerate() {
+        let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+        let ascii: String = chunk
+            .iter()
+            .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+            .collect();
+        out.push_str(&format!("{:08x}  {:<47}  |{}|\n", row * 16, hex.join(" "), ascii));
+    }
+    out
+}
+
+/// 解码 PNG（调色板 / 灰度 / 16 位统一转为 8 位），缩小到 THUMBNAIL_SIZE 以内后重新编码为 RGBA PNG
+fn png_thumbnail(reader: impl Read) -> anyhow::Result<String> {
+    let mut decoder = png::Decoder::new_with_limits(reader, png::Limits { bytes: MAX_DECODE_BYTES });
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info()?;
+    let (width, height) = reader.info().size();
+    if width as usize * height as usize * 4 > MAX_DECODE_BYTES {
+        anyhow::bail!("图片过大: {}×{}", width, height);
+    }
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buf)?;
+    let channels = match frame.color_type {
+        png::ColorType::Grayscale => 1,
+        png::ColorType::GrayscaleAlpha => 2,
+        png::ColorType::Rgb => 3,
+        png::ColorType::Rgba => 4,
+        png::ColorType::Indexed => anyhow::bail!("调色板未展开"),
+    };
+    let rgba = to_rgba(&buf, frame.width, frame.height, frame.line_size, channels);
+    encode_thumbnail(&rgba, frame.width, frame.height)
+}
+
+/// 解码 JPEG（DCT 缩放到不小于 THUMBNAIL_SIZE 的最小尺寸），缩小后编码为 RGBA PNG；
+/// CMYK 不支持，按无法解码处理
+fn jpeg_thumbnail(reader: impl Read) -> anyhow::Result<String> {
+    let mut decoder = jpeg_decoder::Decoder::new(reader);
+    decoder.set_max_decoding_buffer_size(MAX_DECODE_BYTES);
+    let (width, height) = decoder.scale(THUMBNAIL_SIZE as u16, THUMBNAIL_SIZE as u16)?;
+    let pixels = decoder.decode()?;
+    let info = decoder.info().ok_or_else(|| anyhow::anyhow!("缺少 JPEG 帧信息"))?;
+    let rgba = match info.pixel_format {
+        jpeg_decoder::PixelFormat::L8 => to_rgba(&pixels, width as u32, height as u32, width as usize, 1),
+        jpeg_decoder::PixelFormat::RGB24 => to_rgba(&pixels, width as u32, height as u32, width as usize * 3, 3),
+        // 16 位灰度为大端，取高字节
+        jpeg_decoder::PixelFormat::L16 => {
+            let gray: Vec<u8> = pixels.chunks(2).map(|px| px[0]).collect();
+            to_rgba(&gray, width as u32, height as u32, width as usize, 1)
+        }
+        jpeg_decoder::PixelFormat::CMYK32 => anyhow::bail!("不支持 CMYK JPEG"),
+    };
+    encode_thumbnail(&rgba, width as u32, height as u32)
+}
+
+/// 缩小到 THUMBNAIL_SIZE 以内并编码为 RGBA PNG 的 data URL
+fn encode_thumbnail(rgba: &[u8], width: u32, height: u32) -> anyhow::Result<String> {
+    let (width, height, pixels) = downscale(rgba, width, height, THUMBNAIL_SIZE);
+
+    let mut encoded = Vec::new();
+    let mut encoder = png::Encoder::new(&mut encoded, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&pixels)?;
+    writer.finish()?;
+    Ok(data_url("image/png", &encoded))
+}
+
+fn to_rgba(buf: &[u8], width: u32, height: u32, line_size: usize, channels: usize) -> Vec<u8> {
+    let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+    for row in buf.chunks(line_size).take(height as usize) {
+        for px in row.chunks(channels).take(width as usize) {
+            rgba.extend_from_slice(&match *px {
+                [g] => [g, g, g, 255],
+                [g, a] => [g, g, g, a],
+                [r, g, b] => [r, g, b, 255],
+                [r, g, b, a] => [r, g, b, a],
+                _ => [0, 0, 0, 0],
+            });
+        }
+    }
+    rgba
+}
+
+/// 盒式滤波缩小到最长边不超过 max_side（不放大），返回 (宽, 高, RGBA 像素)
+fn downscale(rgba: &[u8], width: u32, height: u32, max_side: u32) -> (u32, u32, Vec<u8>) {
+    let longest = width.max(height);
+    if longest <= max_side {
+        return (width, height, rgba.to_vec());
+    }
+    let scale = longest as f64 / max_side as f64;
+    let out_w = ((width as f64 / scale).round() as u32).max(1);
+    let out_h = ((height as f64 / scale).round() as u32).max(1);
+    let mut out = Vec::with_capacity(out_w as usize * out_h as usize * 4);
+    for y in 0..out_h {
+        let (y0, y1) = span(y, out_h, height);
+        for x in 0..out_w {
+            let (x0, x1) = span(x, out_w, width);
+            let mut sum = [0u64; 4];
+            for sy in y0..y1 {
+                let row = sy as usize * width as usize;
+                for sx in x0..x1 {
+                    let px = &rgba[(row + sx as usize) * 4..][..4];
+                    for (acc, &v) in sum.iter_mut().zip(px) {
+                        *acc += v as u64;
+                    }
+                }
+            }
+            let count = ((y1 - y0) * (x1 - x0)) as u64;
+            out.extend(sum.iter().map(|&v| (v / count) as u8));
+        }
+    }
+    (out_w, out_h, out)
+}
+
+/// 目标第 i 个像素覆盖的源像素区间 [start, end)（至少 1 个）
+fn span(i: u32, out: u32, src: u32) -> (u32, u32) {
+    let start = (i as u64 * src as u64 / out as u64) as u32;
+    let end = ((i as u64 + 1) * src as u64 / out as u64) as u32;
+    (start, end.max(start + 1).min(src))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("flashdir-preview-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn detects_text_encodings_and_binary() {
+        // “中文”的 GBK 编码
+        assert_eq!(decode_text(&[0xD6, 0xD0, 0xCE, 0xC4], false).unwrap(), ("gb18030", "中文".to_string()));
+        // 截断在多字节 UTF-8 字符中间
+        assert_eq!(decode_text("ab中".as_bytes()[..4].as_ref(), true).unwrap(), ("UTF-8", "ab".to_string()));
+        assert_eq!(decode_text(b"h\0i\0!\0", false).unwrap(), ("UTF-16LE", "hi!".to_string()));
+        assert!(decode_text(&[0x7F, 0x45, 0x4C, 0x46, 0x02, 0x01, 0x01, 0x00], false).is_none());
+
+        let path = temp_file("data.bin", &[0u8, 1, 2, 0x41]);
+        let preview = preview_file(&path.to_string_lossy(), DEFAULT_PREVIEW_BYTES).unwrap();
+        assert_eq!(preview.kind, PreviewKind::Binary);
+        assert!(preview.hex_dump.unwrap().starts_with("00000000  00 01 02 41"));
+
+        let path = temp_file("notes.txt", "第一行\nline two\n".repeat(100).as_bytes());
+        let preview = preview_file(&path.to_string_lossy(), 100).unwrap();
+        assert_eq!((preview.kind, preview.encoding.as_deref(), preview.truncated), (PreviewKind::Text, Some("UTF-8"), true));
+    }
+
+    #[test]
+    fn downscales_png_to_thumbnail() {
+        let (width, height) = (600u32, 300u32);
+        let mut encoded = Vec::new();
+        let mut encoder = png::Encoder::new(&mut encoded, width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&vec![200u8; (width * height * 3) as usize]).unwrap();
+        writer.finish().unwrap();
+        let path = temp_file("photo.png", &encoded);
+
+        let preview = preview_file(&path.to_string_lossy(), DEFAULT_PREVIEW_BYTES).unwrap();
+        assert_eq!((preview.kind, preview.width, preview.height), (PreviewKind::Image, Some(600), Some(300)));
+        let thumbnail = preview.thumbnail.unwrap();
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(thumbnail.strip_prefix("data:image/png;base64,").unwrap())
+            .unwrap();
+        let info = png::Decoder::new(bytes.as_slice()).read_info().unwrap().info().size();
+        assert_eq!(info, (THUMBNAIL_SIZE, 128));
+    }
+
+    #[test]
+    fn downscales_jpeg_to_png_thumbnail() {
+        let (width, height) = (3000u16, 1500u16);
+        let mut encoded = Vec::new();
+        jpeg_encoder::Encoder::new(&mut encoded, 80)
+            .encode(&vec![120u8; width as usize * height as usize * 3], width, height, jpeg_encoder::ColorType::Rgb)
+            .unwrap();
+        let path = temp_file("photo.jpg", &encoded);
+
+        let preview = preview_file(&path.to_string_lossy(), DEFAULT_PREVIEW_BYTES).unwrap();
+        assert_eq!((preview.kind, preview.width, preview.height), (PreviewKind::Image, Some(3000), Some(1500)));
+        let thumbnail = preview.thumbnail.unwrap();
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(thumbnail.strip_prefix("data:image/png;base64,").unwrap())
+            .unwrap();
+        let info = png::Decoder::new(bytes.as_slice()).read_info().unwrap().info().size();
+        assert_eq!(info, (THUMBNAIL_SIZE, 128));
+    }
+}
@@ -0,0 +1,148 @@
+// 启动与空闲后预热缓存
+//
+// 启动时，以及用户离开电脑（无键盘 / 鼠标输入超过 idleMinutes）后，把磁盘缓存中命中次数最多的
+// 扫描结果载入内存缓存，打开这些目录时直接命中内存，不必再从 SQLite 读出并解码。
+// 预热只占用内存缓存的空余容量，不挤出用户打开过的会话；目录已变化的结果不载入。
+//
+// 可选地（refreshPinned）随后以后台模式依次扫描收藏的路径：未变化的目录只校验缓存，
+// 变化的目录增量更新缓存。每段空闲只预热一次；取不到输入空闲时长时只在启动时预热。
+// 配置见 settings.prewarm，默认开启预热、不刷新收藏。
+
+use crate::events::Events;
+use crate::settings::Settings;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// 预热完成事件
+pub const PREWARM_EVENT: &str = "cache-prewarm-complete";
+
+/// 空闲检查间隔
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// 预热的时机
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum PrewarmTrigger {
+    Startup,
+    Idle,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PrewarmEvent {
+    pub trigger: PrewarmTrigger,
+    /// 载入内存缓存的缓存 key
+    pub loaded: Vec<String>,
+    /// 刷新成功的收藏路径（未开启 refreshPinned 时为空）
+    pub refreshed: Vec<String>,
+    /// 刷新失败的收藏路径
+    pub failed: Vec<String>,
+    pub duration_ms: u64,
+}
+
+/// 按本次检查的输入空闲秒数判断是否应在空闲后预热：进入一段新的空闲时返回 true，
+/// prewarmed 记录本段空闲是否已预热，用户回来（空闲时长低于阈值）后复位
+pub fn idle_trigger(idle_minutes: u64, idle_secs: Option<u64>, prewarmed: &mut bool) -> bool {
+    let Some(secs) = idle_secs else {
+        return false;
+    };
+    if secs < idle_minutes * 60 {
+        *prewarmed = false;
+        return false;
+    }
+    !std::mem::replace(prewarmed, true)
+}
+
+/// 预热一次；未开启时返回 None
+pub async fn run_once(trigger: PrewarmTrigger, events: Option<&Events>) -> Option<PrewarmEvent> {
+    let settings = Settings::current().prewarm;
+    if !settings.enabled {
+        return None;
+    }
+    let start = std::time::Instant::now();
+    let limit = settings.max_entries;
+    let loaded = tokio::task::spawn_blocking(move || crate::scan::prewarm_memory_cache(limit))
+        .await
+        .ok()?;
+
+    let mut refreshed = Vec::new();
+    let mut failed = Vec::new();
+    if settings.refresh_pinned {
+        let favorites = tokio::task::spawn_blocking(|| crate::disk_cache::DiskCache::instance().list_favorites())
+            .await
+            .ok()?
+            .unwrap_or_else(|e| {
+                eprintln!("[Prewarm] 读取收藏列表失败: {}", e);
+                Vec::new()
+            });
+        let options = crate::scan::ScanOptions { background: true, ..Default::default() };
+        for path in favorites {
+            let outcome = crate::scan::scan_directory_with_options(
+                &path,
+                false,
+                &options,
+                crate::perf::PerformanceMonitor::instance(),
+                None,
+            )
+            .await;
+            match outcome {
+                Ok(_) => refreshed.push(path),
+                Err(e) => {
+                    eprintln!("[Prewarm] 刷新 {} 失败: {}", path, e);
+                    failed.push(path);
+                }
+            }
+        }
+    }
+
+    let event = PrewarmEvent { trigger, loaded, refreshed, failed, duration_ms: start.elapsed().as_millis() as u64 };
+    if !event.loaded.is_empty() || !event.refreshed.is_empty() {
+        eprintln!(
+            "[Prewarm] 载入 {} 条扫描结果，刷新 {} 个收藏路径（{} ms）",
+            event.loaded.len(),
+            event.refreshed.len(),
+            event.duration_ms
+        );
+    }
+    if let Some(events) = events {
+        events.emit(PREWARM_EVENT, &event);
+    }
+    Some(event)
+}
+
+/// 启动时预热一次，之后定时检查空闲状态（常驻，随进程退出）
+pub async fn run_loop(events: Option<Events>) {
+    run_once(PrewarmTrigger::Startup, events.as_ref()).await;
+
+    let mut prewarmed = false;
+    let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        let Ok(idle_secs) = tokio::task::spawn_blocking(crate::fs::user_idle_secs).await else {
+            continue;
+        };
+        if idle_trigger(Settings::current().prewarm.idle_minutes, idle_secs, &mut prewarmed) {
+            run_once(PrewarmTrigger::Idle, events.as_ref()).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prewarms_once_per_idle_period() {
+        let mut prewarmed = false;
+        assert!(!idle_trigger(10, Some(599), &mut prewarmed));
+        assert!(idle_trigger(10, Some(600), &mut prewarmed));
+        assert!(!idle_trigger(10, Some(1200), &mut prewarmed));
+        // 用户回来后复位，下一段空闲再预热
+        assert!(!idle_trigger(10, Some(5), &mut prewarmed));
+        assert!(idle_trigger(10, Some(700), &mut prewarmed));
+        // 取不到空闲时长时不触发
+        assert!(!idle_trigger(10, None, &mut false));
+    }
+}
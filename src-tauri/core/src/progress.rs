@@ -0,0 +1,222 @@
+// 扫描进度估计
+//
+// 有上次快照时，按各顶层目录（扫描根下第一层）上次的条目数加权计算完成度：
+// 每个顶层目录的完成度 = min(本次已扫描条目, 上次条目数)，总进度 = Σ完成度 / Σ上次条目数。
+// 某个子树占绝大部分数据时，进度条仍与耗时大致成线性，而不是在"目录数"上先冲到 90%。
+// 没有上次快照时退化为 已完成目录 / 已发现目录。
+//
+// 剩余时间：加权进度达到 5% 后按本次的实际速度外推（已用时 × 剩余比例 / 完成比例）；
+// 此前速度还不稳定，按上次扫描的耗时 × 剩余比例估计。没有上次快照时不给出估计。
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use schemars::JsonSchema;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// 进度事件的最小间隔
+const EMIT_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 完成比例达到此值后改用本次速度外推剩余时间
+const RATE_ESTIMATE_MIN_FRACTION: f64 = 0.05;
+
+/// "scan-progress" 事件负载
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanProgress {
+    pub path: String,
+    pub entries_scanned: u64,
+    pub dirs_scanned: u64,
+    /// 0–100；扫描结束前不超过 99
+    pub percent: f64,
+    /// 是否按上次快照加权（false 为按目录数估计）
+    pub weighted: bool,
+    /// 上次快照中的条目数（无快照时为 None）
+    pub expected_entries: Option<u64>,
+    pub elapsed_secs: f64,
+    /// 预计剩余秒数；没有上次快照时为 None
+    pub eta_secs: Option<f64>,
+}
+
+pub struct ProgressTracker {
+    root: String,
+    /// 顶层目录名 → (上次条目数, 本次已扫描条目数)
+    top_level: HashMap<String, (u64, AtomicU64)>,
+    total_expected: u64,
+    entries: AtomicU64,
+    dirs_done: AtomicU64,
+    dirs_queued: AtomicU64,
+    last_emit: Mutex<Instant>,
+    started: Instant,
+    /// 上次扫描的耗时（秒）
+    previous_secs: Option<f64>,
+}
+
+impl ProgressTracker {
+    /// `root` 为扫描根的展示路径，`previous_paths` 为上次快照中所有条目的路径
+    pub fn new<'a>(root: &str, previous_paths: impl IntoIterator<Item = &'a str>) -> Self {
+        let root = root.trim_end_matches('/').to_string();
+        let mut counts: HashMap<String, u64> = HashMap::new();
+        for path in previous_paths {
+            if let Some(top) = top_level_of(&root, path) {
+                *counts.entry(top.to_string()).or_default() += 1;
+            }
+        }
+        let total_expected = counts.values().sum();
+        Self {
+            root,
+            top_level: counts
+                .into_iter()
+                .map(|(k, expected)| (k, (expected, AtomicU64::new(0))))
+                .collect(),
+            total_expected,
+            entries: AtomicU64::new(0),
+            dirs_done: AtomicU64::new(0),
+            dirs_queued: AtomicU64::new(0),
+            last_emit: Mutex::new(Instant::now()),
+            started: Instant::now(),
+            previous_secs: None,
+        }
+    }
+
+    /// 上次扫描的耗时，用于扫描初期的剩余时间估计
+    pub fn with_previous_duration(mut self, secs: f64) -> Self {
+        self.previous_secs = (secs > 0.0).then_some(secs);
+        self
+    }
+
+    #[inline]
+    pub fn record_entry(&self, abs_path: &str) {
+        self.entries.fetch_add(1, Ordering::Relaxed);
+        if self.total_expected == 0 {
+            return;
+        }
+        if let Some((_, done)) = top_level_of(&self.root, abs_path).and_then(|top| self.top_level.get(top)) {
+            done.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[inline]
+    pub fn record_dir_queued(&self) {
+        self.dirs_queued.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn record_dir_done(&self) {
+        self.dirs_done.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 距上次事件超过间隔时返回 true（多线程下只有一个线程会拿到）
+    pub fn should_emit(&self) -> bool {
+        let Some(mut last) = self.last_emit.try_lock() else {
+            return false;
+        };
+        if last.elapsed() < EMIT_INTERVAL {
+            return false;
+        }
+        *last = Instant::now();
+        true
+    }
+
+    pub fn snapshot(&self, finished: bool) -> ScanProgress {
+        let dirs_done = self.dirs_done.load(Ordering::Relaxed);
+        let weighted = self.total_expected > 0;
+        let percent = if finished {
+            100.0
+        } else if weighted {
+            let done: u64 = self
+                .top_level
+                .values()
+                .map(|(expected, done)| done.load(Ordering::Relaxed).min(*expected))
+                .sum();
+            (done as f64 / self.total_expected as f64 * 100.0).min(99.0)
+        } else {
+            let queued = self.dirs_queued.load(Ordering::Relaxed).max(1);
+            (dirs_done as f64 / queued as f64 * 100.0).min(99.0)
+        };
+        let elapsed_secs = self.started.elapsed().as_secs_f64();
+        ScanProgress {
+            path: self.root.clone(),
+            entries_scanned: self.entries.load(Ordering::Relaxed),
+            dirs_scanned: dirs_done,
+            percent,
+            weighted,
+            expected_entries: weighted.then_some(self.total_expected),
+            elapsed_secs,
+            eta_secs: if finished {
+                Some(0.0)
+            } else if weighted {
+                self.estimate_remaining(percent / 100.0, elapsed_secs)
+            } else {
+                None
+            },
+        }
+    }
+
+    fn estimate_remaining(&self, fraction: f64, elapsed_secs: f64) -> Option<f64> {
+        if fraction >= RATE_ESTIMATE_MIN_FRACTION {
+            Some(elapsed_secs * (1.0 - fraction) / fraction)
+        } else {
+            self.previous_secs.map(|secs| secs * (1.0 - fraction))
+        }
+    }
+}
+
+/// 扫描根下的第一层名称（`root/a/b/c` → `a`）；不在根下时返回 None
+#[inline]
+fn top_level_of<'a>(root: &str, path: &'a str) -> Option<&'a str> {
+    let rel = path.strip_prefix(root)?.strip_prefix('/')?;
+    Some(rel.split('/').next().unwrap_or(rel))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weights_by_previous_entry_counts() {
+        // 上次：big 下 8 个条目，small 下 2 个
+        let mut previous = vec!["/r/big".to_string(), "/r/small".to_string(), "/r/small/x".to_string()];
+        previous.extend((0..7).map(|i| format!("/r/big/f{}", i)));
+        let tracker = ProgressTracker::new("/r/", previous.iter().map(|s| s.as_str()));
+
+        // small 全部完成只占 20%
+        tracker.record_entry("/r/small");
+        tracker.record_entry("/r/small/x");
+        tracker.record_entry("/r/small/new-file");
+        let p = tracker.snapshot(false);
+        assert!(p.weighted);
+        assert!((p.percent - 20.0).abs() < 1e-9);
+        assert_eq!(p.entries_scanned, 3);
+
+        for i in 0..8 {
+            tracker.record_entry(&format!("/r/big/f{}", i));
+        }
+        assert_eq!(tracker.snapshot(false).percent, 99.0);
+        assert_eq!(tracker.snapshot(true).percent, 100.0);
+
+        let fresh = ProgressTracker::new("/r", std::iter::empty());
+        fresh.record_dir_queued();
+        fresh.record_dir_queued();
+        fresh.record_dir_done();
+        let p = fresh.snapshot(false);
+        assert!(!p.weighted);
+        assert_eq!(p.percent, 50.0);
+        assert_eq!((p.expected_entries, p.eta_secs), (None, None));
+    }
+
+    #[test]
+    fn estimates_remaining_time() {
+        let previous: Vec<String> = (0..100).map(|i| format!("/r/d/f{}", i)).collect();
+        let tracker = ProgressTracker::new("/r", previous.iter().map(|s| s.as_str())).with_previous_duration(40.0);
+
+        // 刚开始：按上次耗时
+        let p = tracker.snapshot(false);
+        assert_eq!(p.expected_entries, Some(100));
+        assert_eq!(p.eta_secs, Some(40.0));
+        // 完成 50% 后按本次速度：剩余时间 = 已用时
+        assert_eq!(tracker.estimate_remaining(0.5, 3.0), Some(3.0));
+        assert_eq!(tracker.snapshot(true).eta_secs, Some(0.0));
+    }
+}
@@ -0,0 +1,194 @@
+// 回收站分析：回收站中的可回收空间
+//
+// 枚举各卷回收站（平台实现见 fs::trash），按条目、原所在目录与回收站位置汇总大小，
+// 条目带删除时间。清空后失效包含回收站目录的扫描缓存（回收站通常位于已扫描的卷根之下）。
+
+use crate::fs::TrashedItem;
+use crate::scan::{format_size, CompactString};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 回收站中的一个条目
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecycledItem {
+    /// 删除前的路径（macOS 废纸篓不记录时为 None）
+    pub original_path: Option<String>,
+    #[schemars(with = "String")]
+    pub name: CompactString,
+    /// 数据在回收站中的位置
+    pub location: String,
+    pub size: i64,
+    #[schemars(with = "String")]
+    pub size_formatted: CompactString,
+    /// 删除时间（Unix 秒），未知为 0
+    pub deleted_at: i64,
+    pub is_dir: bool,
+}
+
+/// 按删除前所在目录汇总
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecycledFolder {
+    /// 原所在目录；原路径未知的条目归入空字符串
+    pub original_dir: String,
+    pub size: i64,
+    #[schemars(with = "String")]
+    pub size_formatted: CompactString,
+    pub item_count: usize,
+    pub latest_deleted_at: i64,
+}
+
+/// 单个回收站目录（每卷一个 $Recycle.Bin / Trash 目录）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecycleBinLocation {
+    pub path: String,
+    pub size: i64,
+    pub item_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecycleBinReport {
+    pub total_size: i64,
+    #[schemars(with = "String")]
+    pub total_size_formatted: CompactString,
+    pub item_count: usize,
+    /// 最早的删除时间（没有条目或时间均未知时为 None）
+    pub oldest_deleted_at: Option<i64>,
+    /// 按大小降序
+    pub items: Vec<RecycledItem>,
+    /// 按大小降序
+    pub folders: Vec<RecycledFolder>,
+    pub locations: Vec<RecycleBinLocation>,
+}
+
+/// 原路径的父目录（统一为正斜杠）
+fn parent_dir(original: &str) -> String {
+    let path = crate::fs::display_path(original);
+    match path.trim_end_matches('/').rfind('/') {
+        Some(0) => "/".to_string(),
+        Some(pos) if pos == 2 && path.as_bytes()[1] == b':' => path[..3].to_string(),
+        Some(pos) => path[..pos].to_string(),
+        None => String::new(),
+    }
+}
+
+pub fn build_report(trashed: Vec<TrashedItem>) -> RecycleBinReport {
+    let mut items: Vec<RecycledItem> = trashed
+        .iter()
+        .map(|t| {
+            let name = t
+                .original_path
+                .as_deref()
+                .map(|p| crate::fs::display_path(p).rsplit('/').next().unwrap_or_default().to_string())
+                .unwrap_or_else(|| t.location.file_name().unwrap_or_default().to_string_lossy().to_string());
+            RecycledItem {
+                original_path: t.original_path.as_deref().map(crate::fs::display_path),
+                name: CompactString::from(name),
+                location: crate::fs::display_path(&t.location.to_string_lossy()),
+                size: t.size as i64,
+                size_formatted: format_size(t.size as i64),
+                deleted_at: t.deleted_at,
+                is_dir: t.is_dir,
+            }
+        })
+        .collect();
+    items.sort_unstable_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+
+    let mut folders: HashMap<String, RecycledFolder> = HashMap::new();
+    for item in &items {
+        let dir = item.original_path.as_deref().map(parent_dir).unwrap_or_default();
+        let folder = folders.entry(dir.clone()).or_insert_with(|| RecycledFolder {
+            original_dir: dir,
+            size: 0,
+            size_formatted: CompactString::new(),
+            item_count: 0,
+            latest_deleted_at: 0,
+        });
+        folder.size += item.size;
+        folder.item_count += 1;
+        folder.latest_deleted_at = folder.latest_deleted_at.max(item.deleted_at);
+    }
+    let mut folders: Vec<RecycledFolder> = folders.into_values().collect();
+    for folder in &mut folders {
+        folder.size_formatted = format_size(folder.size);
+    }
+    folders.sort_unstable_by(|a, b| b.size.cmp(&a.size).then_with(|| a.original_dir.cmp(&b.original_dir)));
+
+    let mut locations: Vec<RecycleBinLocation> = Vec::new();
+    for t in &trashed {
+        let path = crate::fs::display_path(&t.bin.to_string_lossy());
+        match locations.iter_mut().find(|l| l.path == path) {
+            Some(location) => {
+                location.size += t.size as i64;
+                location.item_count += 1;
+            }
+            None => locations.push(RecycleBinLocation { path, size: t.size as i64, item_count: 1 }),
+        }
+    }
+
+    let total_size: i64 = items.iter().map(|i| i.size).sum();
+    RecycleBinReport {
+        total_size,
+        total_size_formatted: format_size(total_size),
+        item_count: items.len(),
+        oldest_deleted_at: items.iter().map(|i| i.deleted_at).filter(|&t| t > 0).min(),
+        items,
+        folders,
+        locations,
+    }
+}
+
+/// 枚举当前用户可读的全部回收站
+pub fn analyze() -> RecycleBinReport {
+    build_report(crate::fs::trash_items())
+}
+
+/// 清空回收站，返回释放的字节数（清空前统计）
+pub fn empty() -> anyhow::Result<i64> {
+    let before = analyze();
+    crate::fs::empty_trash()?;
+    for location in &before.locations {
+        crate::scan::invalidate_caches_for(&location.path);
+    }
+    Ok(before.total_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn trashed(original: Option<&str>, bin: &str, size: u64, deleted_at: i64) -> TrashedItem {
+        TrashedItem {
+            original_path: original.map(str::to_string),
+            location: PathBuf::from(format!("{}/$R{}", bin, size)),
+            bin: PathBuf::from(bin),
+            size,
+            deleted_at,
+            is_dir: false,
+        }
+    }
+
+    #[test]
+    fn groups_by_original_folder_and_bin() {
+        let report = build_report(vec![
+            trashed(Some("C:\\Users\\me\\a.iso"), "C:/$Recycle.Bin", 700, 100),
+            trashed(Some("C:\\Users\\me\\b.txt"), "C:/$Recycle.Bin", 50, 300),
+            trashed(Some("D:\\c.zip"), "D:/$Recycle.Bin", 200, 0),
+            trashed(None, "/home/me/.Trash", 5, 50),
+        ]);
+        assert_eq!((report.total_size, report.item_count, report.oldest_deleted_at), (955, 4, Some(50)));
+        assert_eq!(report.items[0].name, "a.iso");
+        assert_eq!(report.items[0].original_path.as_deref(), Some("C:/Users/me/a.iso"));
+        let folders: Vec<_> = report.folders.iter().map(|f| (f.original_dir.as_str(), f.size, f.item_count)).collect();
+        assert_eq!(folders, vec![("C:/Users/me", 750, 2), ("D:/", 200, 1), ("", 5, 1)]);
+        assert_eq!(report.folders[0].latest_deleted_at, 300);
+        let locations: Vec<_> = report.locations.iter().map(|l| (l.path.as_str(), l.size)).collect();
+        assert_eq!(locations, vec![("C:/$Recycle.Bin", 750), ("D:/$Recycle.Bin", 200), ("/home/me/.Trash", 5)]);
+        assert_eq!(parent_dir("/x.txt"), "/");
+    }
+}
@@ -0,0 +1,474 @@
+// 扫描报告：把一次扫描的概况生成为独立的 HTML（可再打印为 PDF），便于发给管理层或客户
+//
+// 内容：总大小与文件 / 目录数、最大的目录、扩展名分布、重复文件摘要。
+// HTML 不引用任何外部资源（样式内联、条形图用 CSS 宽度绘制），单个文件即可打开或转发；
+// 配色、日期与数字格式、书写方向取自 report_style。PDF 由本机 Chromium 内核浏览器打印
+// （见 fs::print_html_to_pdf）。
+//
+// 重复文件只在不小于 min_duplicate_size 的文件中查找：先按大小分组，
+// 默认再按文件名（不区分大小写）细分，结果为“疑似重复”；verify_duplicates 时改为按 BLAKE3 内容哈希细分，
+// 只哈希同大小的候选文件。
+// 写入 "<dest>.part"，完成后改名为 dest，失败或取消时删除未写完的文件。
+
+use crate::hashing::{hash_file, HashAlgorithm};
+use crate::operation::Operation;
+use crate::report_style::{ReportStyle, TextDirection};
+use crate::scan::{compute_extension_stats, format_size, CompactString, ExtensionStat, Item};
+use crate::settings::ThemePreference;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+/// 各列表缺省列出的条数
+pub const DEFAULT_TOP_N: usize = 20;
+pub const MAX_TOP_N: usize = 500;
+/// 参与重复检测的最小文件大小缺省值（更小的文件浪费的空间可忽略）
+pub const DEFAULT_MIN_DUPLICATE_SIZE: u64 = 1024 * 1024;
+/// 报告中列出的重复组数上限（摘要中的组数与浪费空间仍按全部组统计）
+const MAX_DUPLICATE_GROUPS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ReportFormat {
+    #[default]
+    Html,
+    Pdf,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReportOptions {
+    /// 报告标题；缺省为“磁盘空间报告”
+    pub title: Option<String>,
+    pub format: ReportFormat,
+    /// 最大目录与扩展名列表的条数，缺省 DEFAULT_TOP_N
+    pub top_n: Option<usize>,
+    /// 缺省取设置中的外观
+    pub theme: Option<ThemePreference>,
+    pub locale: Option<String>,
+    /// 按内容哈希确认重复文件（需读取候选文件，较慢）
+    pub verify_duplicates: bool,
+    /// 参与重复检测的最小文件大小（字节），缺省 DEFAULT_MIN_DUPLICATE_SIZE
+    pub min_duplicate_size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportDirectory {
+    #[schemars(with = "String")]
+    pub path: CompactString,
+    pub size: i64,
+    pub file_count: u64,
+    pub percent_of_total: f64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    /// 每个文件的大小
+    pub size: i64,
+    /// 内容哈希；未按内容确认时为 None
+    pub hash: Option<String>,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateSummary {
+    /// 是否按内容哈希确认（否则为按大小 + 文件名判断的疑似重复）
+    pub verified: bool,
+    pub min_size: u64,
+    pub group_count: usize,
+    /// 重复组中除每组一份外的文件数
+    pub duplicate_files: usize,
+    /// 删除多余副本可释放的空间
+    pub wasted_bytes: i64,
+    /// 浪费空间最多的组（最多 MAX_DUPLICATE_GROUPS 组）
+    pub groups: Vec<DuplicateGroup>,
+}
+
+/// 报告的数据部分（与输出格式无关）
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportSummary {
+    pub title: String,
+    pub root: String,
+    /// 生成时间（Unix 秒）
+    pub generated_at: i64,
+    pub total_size: i64,
+    pub file_count: usize,
+    pub dir_count: usize,
+    pub top_directories: Vec<ReportDirectory>,
+    pub extensions: Vec<ExtensionStat>,
+    pub duplicates: DuplicateSummary,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportResult {
+    pub dest: String,
+    pub format: ReportFormat,
+    pub file_size: i64,
+    #[schemars(with = "String")]
+    pub file_size_formatted: CompactString,
+    pub summary: ReportSummary,
+}
+
+/// 由扫描根 root 的条目汇总报告数据；verify_duplicates 时经 op 上报 "hash" 阶段的进度
+pub fn build_summary(root: &str, all: &[Item], options: &ReportOptions, op: &Operation) -> anyhow::Result<ReportSummary> {
+    let top_n = options.top_n.unwrap_or(DEFAULT_TOP_N).clamp(1, MAX_TOP_N);
+    let root = crate::fs::display_path(root);
+    let items: Vec<&Item> = all.iter().filter(|i| i.path != root.as_str()).collect();
+
+    let total_size: i64 = items.iter().filter(|i| !i.is_dir).map(|i| i.size).sum();
+    let dir_count = items.iter().filter(|i| i.is_dir).count();
+
+    let mut dirs: Vec<&Item> = items.iter().copied().filter(|i| i.is_dir).collect();
+    dirs.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+    let top_directories = dirs
+        .iter()
+        .take(top_n)
+        .map(|d| ReportDirectory {
+            path: d.path.clone(),
+            size: d.size,
+            file_count: d.file_count,
+            percent_of_total: percent(d.size, total_size),
+        })
+        .collect();
+
+    // 扩展名统计只计文件，扫描根本身不影响结果
+    let mut extensions = compute_extension_stats(all);
+    extensions.truncate(top_n);
+
+    Ok(ReportSummary {
+        title: options.title.clone().filter(|t| !t.trim().is_empty()).unwrap_or_else(|| "磁盘空间报告".to_string()),
+        generated_at: chrono::Utc::now().timestamp(),
+        total_size,
+        file_count: items.len() - dir_count,
+        dir_count,
+        top_directories,
+        extensions,
+        duplicates: find_duplicates(&items, options, op)?,
+        root,
+    })
+}
+
+fn percent(part: i64, total: i64) -> f64 {
+    if total > 0 {
+        part as f64 * 100.0 / total as f64
+    } else {
+        0.0
+    }
+}
+
+fn find_duplicates(items: &[&Item], options: &ReportOptions, op: &Operation) -> anyhow::Result<DuplicateSummary> {
+    let min_size = options.min_duplicate_size.unwrap_or(DEFAULT_MIN_DUPLICATE_SIZE).max(1);
+    let mut by_size: HashMap<i64, Vec<&Item>> = HashMap::new();
+    for item in items.iter().filter(|i| i.kind == crate::fs::ItemKind::File && i.size as u64 >= min_size) {
+        by_size.entry(item.size).or_default().push(item);
+    }
+    let candidates: Vec<Vec<&Item>> = by_size.into_values().filter(|g| g.len() > 1).collect();
+
+    let mut groups = Vec::new();
+    if options.verify_duplicates {
+        let total_bytes: u64 = candidates.iter().flatten().map(|i| i.size as u64).sum();
+        let mut hashed = 0u64;
+        for candidate in &candidates {
+            let mut by_hash: HashMap<String, Vec<String>> = HashMap::new();
+            for item in candidate {
+                op.check()?;
+                let native = crate::fs::to_extended_length(&item.path);
+                // 读取失败（已删除、无权限）的文件不计入
+                if let Ok((hash, _)) = hash_file(&native.to_string_lossy(), HashAlgorithm::Blake3, |_| {}) {
+                    by_hash.entry(hash).or_default().push(item.path.to_string());
+                }
+                hashed += item.size as u64;
+                op.progress("hash", hashed as f64 * 100.0 / total_bytes.max(1) as f64);
+            }
+            let size = candidate[0].size;
+            groups.extend(
+                by_hash
+                    .into_iter()
+                    .filter(|(_, paths)| paths.len() > 1)
+                    .map(|(hash, paths)| DuplicateGroup { size, hash: Some(hash), paths }),
+            );
+        }
+    } else {
+        for candidate in &candidates {
+            let mut by_name: HashMap<String, Vec<String>> = HashMap::new();
+            for item in candidate {
+                by_name.entry(item.name.to_lowercase()).or_default().push(item.path.to_string());
+            }
+            let size = candidate[0].size;
+            groups.extend(
+                by_name
+                    .into_values()
+                    .filter(|paths| paths.len() > 1)
+                    .map(|paths| DuplicateGroup { size, hash: None, paths }),
+            );
+        }
+    }
+
+    let wasted = |g: &DuplicateGroup| g.size * (g.paths.len() as i64 - 1);
+    for group in &mut groups {
+        group.paths.sort();
+    }
+    groups.sort_by(|a, b| wasted(b).cmp(&wasted(a)).then_with(|| a.paths.cmp(&b.paths)));
+
+    Ok(DuplicateSummary {
+        verified: options.verify_duplicates,
+        min_size,
+        group_count: groups.len(),
+        duplicate_files: groups.iter().map(|g| g.paths.len() - 1).sum(),
+        wasted_bytes: groups.iter().map(wasted).sum(),
+        groups: groups.into_iter().take(MAX_DUPLICATE_GROUPS).collect(),
+    })
+}
+
+/// 渲染为独立的 HTML 文档
+pub fn render_html(summary: &ReportSummary, style: &ReportStyle) -> String {
+    let c = &style.colors;
+    let dir = match style.direction {
+        TextDirection::Ltr => "ltr",
+        TextDirection::Rtl => "rtl",
+    };
+    let mut html = String::new();
+    // 写入 String 不会失败，以下忽略 write! 的返回值
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"{lang}\" dir=\"{dir}\"><head><meta charset=\"utf-8\">\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+         <title>{title}</title><style>\
+         body{{margin:0;padding:32px;background:{bg};color:{text};font:14px/1.5 system-ui,-apple-system,'Segoe UI','Microsoft YaHei',sans-serif}}\
+         h1{{margin:0 0 4px;font-size:24px}}h2{{margin:32px 0 12px;font-size:17px}}\
+         .muted{{color:{muted}}}.cards{{display:flex;flex-wrap:wrap;gap:12px;margin-top:20px}}\
+         .card{{flex:1 1 160px;padding:12px 16px;background:{surface};border:1px solid {border};border-radius:6px}}\
+         .card b{{display:block;font-size:20px}}\
+         table{{width:100%;border-collapse:collapse}}th,td{{padding:6px 8px;border-bottom:1px solid {border};text-align:start;vertical-align:top}}\
+         th{{color:{muted};font-weight:600}}td.num,th.num{{text-align:end;white-space:nowrap}}td.path{{word-break:break-all}}\
+         .bar{{height:8px;min-width:2px;background:{accent};border-radius:4px}}.barcell{{width:25%}}\
+         @media print{{body{{padding:0}}tr{{break-inside:avoid}}}}\
+         </style></head><body>",
+        lang = escape(&style.locale),
+        dir = dir,
+        title = escape(&summary.title),
+        bg = c.background,
+        text = c.text,
+        muted = c.muted,
+        surface = c.surface,
+        border = c.border,
+        accent = c.accent,
+    );
+
+    let _ = write!(
+        html,
+        "<h1>{}</h1><div class=\"muted\">{} · 生成于 {}</div><div class=\"cards\">",
+        escape(&summary.title),
+        escape(&summary.root),
+        style.format_date(summary.generated_at)
+    );
+    for (label, value) in [
+        ("总大小", style.format_size(summary.total_size)),
+        ("文件数", style.format_number(summary.file_count as i64)),
+        ("目录数", style.format_number(summary.dir_count as i64)),
+        ("重复文件可释放", style.format_size(summary.duplicates.wasted_bytes)),
+    ] {
+        let _ = write!(html, "<div class=\"card\"><span class=\"muted\">{}</span><b>{}</b></div>", label, escape(&value));
+    }
+    html.push_str("</div>");
+
+    html.push_str("<h2>最大的目录</h2>");
+    if summary.top_directories.is_empty() {
+        html.push_str("<p class=\"muted\">没有子目录</p>");
+    } else {
+        html.push_str("<table><tr><th>路径</th><th class=\"num\">大小</th><th class=\"num\">文件数</th><th class=\"barcell\"></th></tr>");
+        for d in &summary.top_directories {
+            push_bar_row(&mut html, style, &d.path, d.size, d.file_count as i64, d.percent_of_total);
+        }
+        html.push_str("</table>");
+    }
+
+    html.push_str("<h2>扩展名分布</h2>");
+    if summary.extensions.is_empty() {
+        html.push_str("<p class=\"muted\">没有文件</p>");
+    } else {
+        html.push_str("<table><tr><th>扩展名</th><th class=\"num\">大小</th><th class=\"num\">文件数</th><th class=\"barcell\"></th></tr>");
+        for e in &summary.extensions {
+            push_bar_row(&mut html, style, &e.extension, e.total_size, e.file_count as i64, e.percent_of_total);
+        }
+        html.push_str("</table>");
+    }
+
+    let dup = &summary.duplicates;
+    let _ = write!(
+        html,
+        "<h2>重复文件</h2><p class=\"muted\">{}（不小于 {} 的文件）：{} 组，{} 个多余副本，共 {}</p>",
+        if dup.verified { "按内容哈希确认" } else { "按大小与文件名判断的疑似重复" },
+        style.format_size(dup.min_size as i64),
+        style.format_number(dup.group_count as i64),
+        style.format_number(dup.duplicate_files as i64),
+        style.format_size(dup.wasted_bytes)
+    );
+    if !dup.groups.is_empty() {
+        html.push_str("<table><tr><th>文件</th><th class=\"num\">单个大小</th><th class=\"num\">可释放</th></tr>");
+        for g in &dup.groups {
+            let paths: Vec<String> = g.paths.iter().map(|p| escape(p)).collect();
+            let _ = write!(
+                html,
+                "<tr><td class=\"path\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+                paths.join("<br>"),
+                style.format_size(g.size),
+                style.format_size(g.size * (g.paths.len() as i64 - 1))
+            );
+        }
+        html.push_str("</table>");
+    }
+
+    html.push_str("</body></html>\n");
+    html
+}
+
+fn push_bar_row(html: &mut String, style: &ReportStyle, label: &str, size: i64, count: i64, percent: f64) {
+    let _ = write!(
+        html,
+        "<tr><td class=\"path\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td>\
+         <td class=\"barcell\"><div class=\"bar\" style=\"width:{:.1}%\"></div></td></tr>",
+        escape(label),
+        style.format_size(size),
+        style.format_number(count),
+        percent.clamp(0.0, 100.0)
+    );
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// 生成扫描根 root 的报告写入 dest；经 op 上报 "hash"（按内容确认重复时）、"render" 与 "write" 阶段
+pub fn generate_report(
+    root: &str,
+    items: &[Item],
+    dest: &str,
+    options: &ReportOptions,
+    op: &Operation,
+) -> anyhow::Result<ReportResult> {
+    let summary = build_summary(root, items, options, op)?;
+    op.check()?;
+    op.progress("render", 0.0);
+    let style = ReportStyle::resolve(options.theme, options.locale.as_deref());
+    let html = render_html(&summary, &style);
+
+    let dest_path = crate::fs::to_extended_length(dest);
+    let part = PathBuf::from(format!("{}.part", dest_path.to_string_lossy()));
+    op.progress("write", 0.0);
+    let written = match options.format {
+        ReportFormat::Html => std::fs::write(&part, html).map_err(anyhow::Error::from),
+        ReportFormat::Pdf => {
+            // 浏览器只能打印文件：HTML 先写到临时目录，打印完即删除
+            let source = std::env::temp_dir().join(format!("flashdir_report_{}.html", uuid::Uuid::new_v4()));
+            let printed = std::fs::write(&source, html)
+                .map_err(anyhow::Error::from)
+                .and_then(|()| crate::fs::print_html_to_pdf(&source, &part));
+            std::fs::remove_file(&source).ok();
+            printed
+        }
+    };
+    if let Err(e) = written.and_then(|()| Ok(std::fs::rename(&part, &dest_path)?)) {
+        std::fs::remove_file(&part).ok();
+        return Err(e);
+    }
+    op.progress("write", 100.0);
+
+    let file_size = std::fs::metadata(&dest_path).map_or(0, |m| m.len() as i64);
+    Ok(ReportResult {
+        dest: crate::fs::display_path(dest),
+        format: options.format,
+        file_size,
+        file_size_formatted: format_size(file_size),
+        summary,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report_style::Theme;
+
+    fn item(path: &str, size: i64, is_dir: bool) -> Item {
+        let item = Item::test(path, size, is_dir);
+        Item { size_formatted: format_size(size), file_count: if is_dir { 2 } else { 0 }, ..item }
+    }
+
+    #[test]
+    fn summarizes_and_renders_escaped_html() {
+        let mb = 1024 * 1024;
+        let items = vec![
+            item("/r/a", 4 * mb, true),
+            item("/r/a/movie.mp4", 2 * mb, false),
+            item("/r/a/<b>.txt", 10, false),
+            item("/r/b", 2 * mb, true),
+            item("/r/b/Movie.MP4", 2 * mb, false),
+            item("/r/b/other.mp4", 2 * mb, false),
+        ];
+        let options = ReportOptions { top_n: Some(1), ..Default::default() };
+        let summary = build_summary("/r", &items, &options, &Operation::start("test", None, None)).unwrap();
+
+        assert_eq!((summary.file_count, summary.dir_count), (4, 2));
+        assert_eq!(summary.total_size, 6 * mb + 10);
+        assert_eq!(summary.top_directories.len(), 1);
+        assert_eq!(summary.top_directories[0].path.as_str(), "/r/a");
+        assert_eq!(summary.extensions[0].extension.as_str(), "mp4");
+        // 同大小且同名（不区分大小写）的两个 mp4 为疑似重复，other.mp4 不算
+        assert_eq!(summary.duplicates.group_count, 1);
+        assert_eq!(summary.duplicates.wasted_bytes, 2 * mb);
+        assert!(!summary.duplicates.verified);
+
+        let html = render_html(&summary, &ReportStyle::new(Theme::Dark, "ar-EG"));
+        assert!(html.contains("dir=\"rtl\""));
+        assert!(html.contains("#1e1e1e"));
+        assert!(!html.contains("<b>.txt"));
+    }
+
+    #[test]
+    fn verifies_duplicates_by_content_and_writes_html() {
+        let dir = std::env::temp_dir().join(format!("flashdir_report_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = |name: &str| dir.join(name).to_string_lossy().replace('\\', "/");
+        std::fs::write(path("x.bin"), b"same").unwrap();
+        std::fs::write(path("y.bin"), b"same").unwrap();
+        std::fs::write(path("z.bin"), b"diff").unwrap();
+        let items: Vec<Item> = ["x.bin", "y.bin", "z.bin"].iter().map(|n| item(&path(n), 4, false)).collect();
+
+        let dest = path("report.html");
+        let options = ReportOptions {
+            verify_duplicates: true,
+            min_duplicate_size: Some(1),
+            theme: Some(ThemePreference::Light),
+            locale: Some("en-US".to_string()),
+            ..Default::default()
+        };
+        let root = dir.to_string_lossy();
+        let result = generate_report(&root, &items, &dest, &options, &Operation::start("test", None, None)).unwrap();
+
+        let dup = &result.summary.duplicates;
+        assert!(dup.verified);
+        assert_eq!((dup.group_count, dup.duplicate_files), (1, 1));
+        assert!(dup.groups[0].hash.is_some());
+        assert!(std::fs::read_to_string(&dest).unwrap().starts_with("<!DOCTYPE html>"));
+        assert!(!std::path::Path::new(&format!("{}.part", dest)).exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
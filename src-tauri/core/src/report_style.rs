@@ -0,0 +1,227 @@
+// 报告外观：主题与区域格式
+//
+// 生成的报告 / 导出文件应与应用内看到的一致：深色或浅色配色、按语言习惯的日期与数字格式、
+// 阿拉伯语 / 希伯来语等从右到左书写的语言使用 RTL 排版。
+// 调用方传入的 theme / locale 优先，其次取 settings.appearance，“跟随系统”时读取系统偏好
+// （fs::system_prefers_dark / fs::system_locale），都取不到时为浅色 + zh-CN。
+//
+// 格式表只覆盖常见语言的日期顺序、分隔符与数字分组，不追求完整的 CLDR 数据。
+
+use crate::settings::{Settings, ThemePreference};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// 无法确定语言时使用的区域（与应用界面语言一致）
+pub const DEFAULT_LOCALE: &str = "zh-CN";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum TextDirection {
+    Ltr,
+    Rtl,
+}
+
+/// 报告配色（CSS 颜色值，深色与应用的 --fd-* 变量一致）
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportColors {
+    pub background: String,
+    pub surface: String,
+    pub border: String,
+    pub text: String,
+    pub muted: String,
+    pub accent: String,
+}
+
+impl ReportColors {
+    fn for_theme(theme: Theme) -> Self {
+        let [background, surface, border, text, muted, accent] = match theme {
+            Theme::Dark => ["#1e1e1e", "#252526", "#3e3e42", "#cccccc", "#858585", "#007acc"],
+            Theme::Light => ["#ffffff", "#f3f3f3", "#e5e5e5", "#1e1e1e", "#6e6e6e", "#005fb8"],
+        };
+        Self {
+            background: background.to_string(),
+            surface: surface.to_string(),
+            border: border.to_string(),
+            text: text.to_string(),
+            muted: muted.to_string(),
+            accent: accent.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DateOrder {
+    Ymd,
+    Mdy,
+    Dmy,
+}
+
+/// 一种语言的日期与数字格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LocaleFormat {
+    date_order: DateOrder,
+    date_separator: char,
+    group_separator: char,
+    decimal_separator: char,
+}
+
+impl LocaleFormat {
+    fn for_locale(locale: &str) -> Self {
+        let mut parts = locale.split(['-', '_']);
+        let language = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let region = parts.find(|p| p.len() == 2).map(|p| p.to_ascii_uppercase());
+
+        let (date_order, date_separator) = match language.as_str() {
+            "zh" | "ja" => (DateOrder::Ymd, '/'),
+            "ko" | "hu" => (DateOrder::Ymd, '.'),
+            "sv" | "lt" => (DateOrder::Ymd, '-'),
+            "en" if matches!(region.as_deref(), None | Some("US") | Some("PH")) => (DateOrder::Mdy, '/'),
+            "de" | "ru" | "uk" | "pl" | "cs" | "sk" | "fi" | "nb" | "no" | "da" | "tr" | "ro" => (DateOrder::Dmy, '.'),
+            "nl" => (DateOrder::Dmy, '-'),
+            _ => (DateOrder::Dmy, '/'),
+        };
+        let (group_separator, decimal_separator) = match language.as_str() {
+            "de" | "es" | "it" | "nl" | "pt" | "id" | "tr" | "da" | "ro" | "el" => ('.', ','),
+            // 法语等以空格分组（用不换行空格，避免数字在表格中折行）
+            "fr" | "ru" | "uk" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "no" | "hu" | "lt" => ('\u{a0}', ','),
+            _ => (',', '.'),
+        };
+        Self { date_order, date_separator, group_separator, decimal_separator }
+    }
+}
+
+/// 从右到左书写的语言
+fn is_rtl(locale: &str) -> bool {
+    let language = locale.split(['-', '_']).next().unwrap_or_default();
+    ["ar", "he", "fa", "ur", "yi", "ps"].iter().any(|l| language.eq_ignore_ascii_case(l))
+}
+
+/// 解析后的报告外观
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportStyle {
+    pub theme: Theme,
+    /// BCP 47 语言标记，如 "zh-CN"、"de-DE"、"ar-EG"
+    pub locale: String,
+    pub direction: TextDirection,
+    pub colors: ReportColors,
+    #[serde(skip)]
+    #[schemars(skip)]
+    format: LocaleFormat,
+}
+
+impl ReportStyle {
+    /// 由显式参数、设置与系统偏好解析（参数为 None 时取设置，设置为跟随系统时读取系统）
+    pub fn resolve(theme: Option<ThemePreference>, locale: Option<&str>) -> Self {
+        let appearance = Settings::current().appearance;
+        let dark = match theme.unwrap_or(appearance.theme) {
+            ThemePreference::Light => false,
+            ThemePreference::Dark => true,
+            ThemePreference::System => crate::fs::system_prefers_dark().unwrap_or(false),
+        };
+        let locale = locale
+            .map(str::to_string)
+            .or(appearance.locale)
+            .or_else(crate::fs::system_locale)
+            .unwrap_or_else(|| DEFAULT_LOCALE.to_string());
+        Self::new(if dark { Theme::Dark } else { Theme::Light }, &locale)
+    }
+
+    pub fn new(theme: Theme, locale: &str) -> Self {
+        Self {
+            theme,
+            locale: locale.to_string(),
+            direction: if is_rtl(locale) { TextDirection::Rtl } else { TextDirection::Ltr },
+            colors: ReportColors::for_theme(theme),
+            format: LocaleFormat::for_locale(locale),
+        }
+    }
+
+    /// 本地时间的日期 + 时分；0 表示未知，返回空串
+    pub fn format_date(&self, unix_secs: i64) -> String {
+        use chrono::TimeZone;
+
+        let Some(time) = chrono::Local.timestamp_opt(unix_secs, 0).single().filter(|_| unix_secs != 0) else {
+            return String::new();
+        };
+        let (y, m, d) = (time.format("%Y"), time.format("%m"), time.format("%d"));
+        let sep = self.format.date_separator;
+        let date = match self.format.date_order {
+            DateOrder::Ymd => format!("{y}{sep}{m}{sep}{d}"),
+            DateOrder::Mdy => format!("{m}{sep}{d}{sep}{y}"),
+            DateOrder::Dmy => format!("{d}{sep}{m}{sep}{y}"),
+        };
+        format!("{} {}", date, time.format("%H:%M"))
+    }
+
+    /// 整数按千位分组
+    pub fn format_number(&self, n: i64) -> String {
+        let digits = n.unsigned_abs().to_string();
+        let mut out = String::with_capacity(digits.len() + digits.len() / 3 + 1);
+        if n < 0 {
+            out.push('-');
+        }
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                out.push(self.format.group_separator);
+            }
+            out.push(c);
+        }
+        out
+    }
+
+    /// 字节数的可读形式（单位同 scan::format_size，小数点按区域）
+    pub fn format_size(&self, bytes: i64) -> String {
+        let formatted = crate::scan::format_size(bytes).to_string();
+        if self.format.decimal_separator == '.' {
+            formatted
+        } else {
+            formatted.replace('.', &self.format.decimal_separator.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_numbers_dates_and_direction_by_locale() {
+        let zh = ReportStyle::new(Theme::Light, "zh-CN");
+        let de = ReportStyle::new(Theme::Dark, "de-DE");
+        let fr = ReportStyle::new(Theme::Light, "fr-FR");
+        let ar = ReportStyle::new(Theme::Light, "ar-EG");
+
+        assert_eq!(zh.format_number(1234567), "1,234,567");
+        assert_eq!(de.format_number(-1234567), "-1.234.567");
+        assert_eq!(fr.format_number(999), "999");
+        assert_eq!(fr.format_number(1000), "1\u{a0}000");
+        assert_eq!(de.format_size(1536), "1,50 KB");
+        assert_eq!(zh.format_size(1536), "1.50 KB");
+
+        assert_eq!((zh.direction, ar.direction), (TextDirection::Ltr, TextDirection::Rtl));
+        assert_eq!(de.colors.background, "#1e1e1e");
+        assert_eq!(zh.format_date(0), "");
+
+        // 日期顺序与分隔符（取本地时区，只比较日期部分的形状）
+        let ts = chrono::NaiveDate::from_ymd_opt(2026, 3, 9)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+            .and_local_timezone(chrono::Local)
+            .unwrap()
+            .timestamp();
+        assert!(zh.format_date(ts).starts_with("2026/03/09"));
+        assert!(de.format_date(ts).starts_with("09.03.2026"));
+        assert!(ReportStyle::new(Theme::Light, "en-US").format_date(ts).starts_with("03/09/2026"));
+        assert!(ReportStyle::new(Theme::Light, "en-GB").format_date(ts).starts_with("09/03/2026"));
+    }
+}
@@ -0,0 +1,3867 @@
+// 扫描核心模块 - 优化版
+// 集成：性能监控、磁盘缓存、bincode 序列化、Windows 原生 I/O
+
+use anyhow;
+use crossbeam::channel::{unbounded, Sender, Receiver};
+use lru::LruCache;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use smartstring::SmartString;
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::events::Events;
+use crate::backend::BackendKind;
+use crate::exclusions::{ExclusionPreset, Exclusions};
+use crate::include_filter::IncludeFilter;
+use crate::categories::{compute_category_stats, item_category, CategoryStat, FileCategory};
+use tokio::fs;
+
+use crate::perf::PerformanceMonitor;
+use crate::disk_cache::{DiskCache, EventKind};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+pub type CompactString = SmartString<smartstring::Compact>;
+
+pub use crate::fs::ItemKind;
+
+/// 测试/诊断开关：强制禁用 MFT 快速路径，回退到目录遍历。
+static DISABLE_MFT: AtomicBool = AtomicBool::new(false);
+
+pub fn set_disable_mft(disable: bool) {
+    DISABLE_MFT.store(disable, Ordering::Relaxed);
+}
+
+fn is_mft_disabled() -> bool {
+    DISABLE_MFT.load(Ordering::Relaxed)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TimingInfo {
+    pub scan_phase: f64,
+    pub compute_phase: f64,
+    pub format_phase: f64,
+    pub total: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Item {
+    #[schemars(with = "String")]
+    pub path: CompactString,
+    #[schemars(with = "String")]
+    pub name: CompactString,
+    pub size: i64,
+    #[serde(rename = "sizeFormatted")]
+    #[schemars(with = "String")]
+    pub size_formatted: CompactString,
+    #[serde(rename = "isDir")]
+    pub is_dir: bool,
+    /// 最后修改时间（Unix 秒，0 表示未知）
+    pub modified: i64,
+    /// 创建时间（Unix 秒，0 表示未知）
+    pub created: i64,
+    /// 稳定 ID：卷 ID + 卷内相对路径的哈希，重扫后不变，前端据此保留选中/展开/标签状态
+    pub id: u64,
+    /// 条目类型：联接 / 符号链接 / 挂载点 / 云占位符不跟随、按 0 字节计，前端据此说明原因
+    #[serde(default)]
+    pub kind: ItemKind,
+    /// 目录子树中的文件数（递归，不含目录；文件为 0，小文件合成条目为合并的文件数）
+    #[serde(default)]
+    pub file_count: u64,
+    /// 目录子树中的子目录数（递归，不含自身；文件为 0）
+    #[serde(default)]
+    pub dir_count: u64,
+    /// 按扩展名的文件类别（目录与链接类条目为 other）
+    #[serde(default)]
+    pub category: FileCategory,
+    /// 占父目录大小的百分比（0–100，扫描根的直接子项相对扫描总大小）
+    #[serde(default)]
+    pub percent_of_parent: f32,
+    /// 占扫描总大小的百分比（0–100）
+    #[serde(default)]
+    pub percent_of_total: f32,
+}
+
+#[cfg(test)]
+impl Item {
+    /// 测试用文件条目：名称取路径最后一段，其余字段为缺省值，
+    /// 需要时用 `Item { modified, ..Item::test_file(path, size) }` 覆盖
+    pub(crate) fn test_file(path: &str, size: i64) -> Item {
+        Item {
+            path: CompactString::from(path),
+            name: CompactString::from(path.rsplit('/').next().unwrap_or(path)),
+            size,
+            size_formatted: CompactString::new(),
+            is_dir: false,
+            modified: 0,
+            created: 0,
+            id: 0,
+            kind: ItemKind::File,
+            file_count: 0,
+            dir_count: 0,
+            category: Default::default(),
+            percent_of_parent: 0.0,
+            percent_of_total: 0.0,
+        }
+    }
+
+    /// 测试用目录条目
+    pub(crate) fn test_dir(path: &str, size: i64) -> Item {
+        Item { is_dir: true, kind: ItemKind::Dir, ..Item::test_file(path, size) }
+    }
+
+    /// 按 is_dir 构造测试用目录或文件条目
+    pub(crate) fn test(path: &str, size: i64, is_dir: bool) -> Item {
+        if is_dir { Item::test_dir(path, size) } else { Item::test_file(path, size) }
+    }
+}
+
+/// 稳定 ID 只保留低 53 位，保证在 JS Number 中无精度损失
+const ITEM_ID_MASK: u64 = (1 << 53) - 1;
+
+/// 计算 item 的稳定 ID（FNV-1a，跨进程/版本稳定，不依赖 std 的随机哈希种子）。
+/// 路径去掉盘符后参与哈希，Windows 下忽略大小写，同一卷换盘符挂载 ID 不变。
+pub fn stable_item_id(volume_id: u64, abs_path: &str) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = FNV_OFFSET;
+    let mut feed = |b: u8| {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    };
+    for b in volume_id.to_le_bytes() {
+        feed(b);
+    }
+
+    let bytes = abs_path.as_bytes();
+    let relative = if bytes.len() >= 2 && bytes[1] == b':' { &abs_path[2..] } else { abs_path };
+    if cfg!(target_os = "windows") {
+        let mut buf = [0u8; 4];
+        for c in relative.chars().flat_map(char::to_lowercase) {
+            for &b in c.encode_utf8(&mut buf).as_bytes() {
+                feed(b);
+            }
+        }
+    } else {
+        for &b in relative.as_bytes() {
+            feed(b);
+        }
+    }
+
+    hash & ITEM_ID_MASK
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanResult {
+    pub items: Vec<Item>,
+    pub total_size: i64,
+    #[schemars(with = "String")]
+    pub total_size_formatted: CompactString,
+    pub scan_time: f64,
+    #[schemars(with = "String")]
+    pub path: CompactString,
+    pub mft_available: bool,
+    /// timing / perf_metrics 同样不加 skip_serializing_if：为 None 时跳过字段会使 bincode 数据无法解码
+    pub timing: Option<TimingInfo>,
+    pub perf_metrics: Option<ScanPerfMetrics>,
+    /// 按扩展名聚合的大小/数量统计（仅 ScanOptions::extension_stats 开启时计算）。
+    /// 不加 skip_serializing_if：磁盘缓存用 bincode 序列化，要求字段完整
+    pub extension_stats: Option<Vec<ExtensionStat>>,
+    /// 按文件类别聚合的大小/数量统计，返回前计算（缓存中为空）
+    pub category_stats: Vec<CategoryStat>,
+    /// 各 item 的所有者账户名，与 items 按下标对齐，无法解析时为空串
+    /// （仅 ScanOptions::include_owner 开启时解析，较慢，不进缓存）
+    #[schemars(with = "Option<Vec<String>>")]
+    pub owners: Option<Vec<CompactString>>,
+    /// 超出 ScanOptions::time_budget_ms 时未遍历的目录：这些目录以大小 0 占位，
+    /// 结果为尽力而为的部分结果（不进缓存）；None 表示完整扫描
+    #[schemars(with = "Option<Vec<String>>")]
+    pub unscanned: Option<Vec<CompactString>>,
+    /// 无法读取的目录（拒绝访问等），非空时总大小可能偏小，可提权后重新扫描
+    pub errors: Vec<ScanError>,
+    /// 无法读取目录的大小估计（取自上次快照，无快照时为 0）
+    pub unreadable_bytes: i64,
+    /// 仅在线的云占位符（OneDrive / Dropbox / iCloud）的逻辑大小之和，未计入 total_size；
+    /// 扫描只读取目录元数据，不会触发下载
+    pub cloud_only_bytes: i64,
+    /// 已下载到本地的云同步文件大小之和（已计入 total_size）
+    pub cloud_local_bytes: i64,
+    /// 稀疏文件（VM 磁盘镜像、下载中的种子等），条目 size 按实际分配大小计
+    pub sparse_files: Vec<SparseEntry>,
+    /// 稀疏文件逻辑大小与分配大小之差的总和（未计入 total_size）
+    pub sparse_savings_bytes: i64,
+    /// 本次结果的来源与缓存条目的沿用 / 失效情况（命中缓存时重新填入）
+    pub cache_usage: CacheUsage,
+}
+
+/// 扫描结果的来源
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum CacheSource {
+    /// 内存缓存命中
+    Memory,
+    /// 磁盘缓存命中（含 USN Journal 确认无变更）
+    Disk,
+    /// 沿用磁盘缓存并按 USN Journal 变更增量更新
+    Partial,
+    /// 完整遍历
+    #[default]
+    Fresh,
+}
+
+/// 多级缓存在本次扫描中的使用情况，让用户判断结果是否最新
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheUsage {
+    pub source: CacheSource,
+    /// 直接沿用的缓存条目数
+    pub reused_entries: usize,
+    /// 失效的缓存条目数：增量更新中被删除 / 改动的条目，或完整遍历时丢弃的旧结果条目
+    pub invalidated_entries: usize,
+}
+
+impl CacheUsage {
+    pub fn hit(source: CacheSource, entries: usize) -> Self {
+        Self { source, reused_entries: entries, invalidated_entries: 0 }
+    }
+
+    /// 一行摘要，如 "增量更新：沿用 12034 项缓存，12 项失效"
+    pub fn summary(&self) -> String {
+        match self.source {
+            CacheSource::Memory => format!("内存缓存：沿用 {} 项", self.reused_entries),
+            CacheSource::Disk => format!("磁盘缓存：沿用 {} 项", self.reused_entries),
+            CacheSource::Partial => format!(
+                "增量更新：沿用 {} 项缓存，{} 项失效",
+                self.reused_entries, self.invalidated_entries
+            ),
+            CacheSource::Fresh if self.reused_entries > 0 => {
+                format!("完整扫描：沿用 {} 项子目录缓存", self.reused_entries)
+            }
+            CacheSource::Fresh if self.invalidated_entries > 0 => {
+                format!("完整扫描：丢弃 {} 项过期缓存", self.invalidated_entries)
+            }
+            CacheSource::Fresh => "完整扫描".to_string(),
+        }
+    }
+}
+
+/// 稀疏文件的逻辑大小与实际分配大小
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SparseEntry {
+    #[schemars(with = "String")]
+    pub path: CompactString,
+    pub logical_size: i64,
+    pub allocated_size: i64,
+}
+
+/// 稀疏文件节省的字节数之和
+pub fn sparse_savings(entries: &[SparseEntry]) -> i64 {
+    entries.iter().map(|e| e.logical_size - e.allocated_size).sum()
+}
+
+/// 扫描中无法读取的目录
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanError {
+    #[schemars(with = "String")]
+    pub path: CompactString,
+    pub kind: ScanErrorKind,
+    /// 该目录的大小估计（上次快照中的大小，未知为 0）
+    pub estimated_bytes: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ScanErrorKind {
+    AccessDenied,
+    NotFound,
+    Other,
+}
+
+impl From<std::io::ErrorKind> for ScanErrorKind {
+    fn from(kind: std::io::ErrorKind) -> Self {
+        match kind {
+            std::io::ErrorKind::PermissionDenied => ScanErrorKind::AccessDenied,
+            std::io::ErrorKind::NotFound => ScanErrorKind::NotFound,
+            _ => ScanErrorKind::Other,
+        }
+    }
+}
+
+/// 扫描选项（默认值与旧行为一致）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScanOptions {
+    /// 附带按扩展名的统计，前端分页加载 items 时也能直接展示类型分布
+    pub extension_stats: bool,
+    /// items 字段投影：只序列化列出的字段（None / 空 = 全部字段），
+    /// 只需 path + size 的调用方（图表、导出）可大幅缩小 IPC 负载
+    pub fields: Option<Vec<ItemField>>,
+    /// 小于此字节数的文件不逐个列出：每个目录下的这些文件合并为一个合成条目（名称如 `12 个小文件`），
+    /// 目录大小与各项统计不变。源码树等小文件多的目录可大幅缩小结果（缓存仍保存完整结果）
+    pub min_file_size: Option<u64>,
+    /// 解析每个 item 的所有者（Windows SID → 账户名，Unix uid → 用户名），每项一次系统调用
+    pub include_owner: bool,
+    /// 扫描时间预算（毫秒）：超时后不再进入尚未遍历的子目录，立即返回已有结果，
+    /// 未遍历的目录记录在 ScanResult::unscanned 中。仪表盘等需要 2 秒内给出答案的场景使用
+    pub time_budget_ms: Option<u64>,
+    /// 网络模式：UNC 共享 / 网络驱动器使用低并发的遍历参数（默认自动识别）
+    pub network_mode: NetworkMode,
+    /// 遍历后端（默认按平台与路径自动选择，见 backend 模块）
+    pub backend: BackendKind,
+    /// 后台模式：遍历线程使用低 CPU / I/O 优先级并限速派发目录，定时扫描不拖慢前台程序
+    pub background: bool,
+    /// 启用的内置排除预设（WinSxS、页面文件、/proc 等），命中的条目在遍历中跳过
+    pub exclude_presets: Vec<ExclusionPreset>,
+    /// 包含模式（glob，如 `*.mp4;*.mkv`）：只统计匹配的文件，目录大小只含匹配内容（见 include_filter 模块）
+    pub include_globs: Vec<String>,
+}
+
+/// 网络模式选择
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum NetworkMode {
+    /// 按路径自动识别（UNC 路径、网络驱动器、nfs / cifs 等挂载）
+    #[default]
+    Auto,
+    On,
+    Off,
+}
+
+/// 目录遍历参数
+///
+/// 固态硬盘用高并发把 I/O 队列打满；网络共享上 32 个并发请求只会压垮服务器、反而更慢，
+/// 网络模式降低并发、增大流式批次（减少事件数）；机械硬盘并发随机读会来回寻道，
+/// 少量线程 + 小容量共享队列，子目录多由发现它的 worker 深度优先处理
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ScanProfile {
+    threads: usize,
+    /// 识别出的存储介质（ScanPerfMetrics::drive_type）
+    drive: crate::fs::DriveType,
+    /// 每个 scan-batch 事件携带的 item 数
+    stream_batch: usize,
+    network: bool,
+    /// 后台模式：每读完一个目录的暂停时间（限速），同时表示 worker 以低优先级运行
+    dispatch_pause: Option<std::time::Duration>,
+    /// 共享目录队列容量上限，满时子目录留在发现它的 worker 内处理
+    queue_capacity: Option<usize>,
+    /// 单线程确定性遍历：条目按名称排序，不使用大小提示
+    deterministic: bool,
+    /// 遍历中跳过的系统目录 / 文件
+    exclusions: Exclusions,
+    /// 只计入匹配的文件
+    includes: IncludeFilter,
+    /// 沿用缓存的子目录：遍历到时不再进入，直接并入缓存的子树
+    reuse: ReusedSubtrees,
+}
+
+impl ScanProfile {
+    pub(crate) fn local() -> Self {
+        Self {
+            threads: (num_cpus::get() * 2).clamp(8, 32),
+            drive: crate::fs::DriveType::Unknown,
+            stream_batch: 200,
+            network: false,
+            dispatch_pause: None,
+            queue_capacity: None,
+            deterministic: false,
+            exclusions: Exclusions::default(),
+            includes: IncludeFilter::default(),
+            reuse: ReusedSubtrees::default(),
+        }
+    }
+
+    pub(crate) fn network() -> Self {
+        Self {
+            threads: 4,
+            drive: crate::fs::DriveType::Network,
+            stream_batch: 1000,
+            network: true,
+            dispatch_pause: None,
+            queue_capacity: None,
+            deterministic: false,
+            exclusions: Exclusions::default(),
+            includes: IncludeFilter::default(),
+            reuse: ReusedSubtrees::default(),
+        }
+    }
+
+    pub(crate) fn hdd() -> Self {
+        Self {
+            threads: 4,
+            drive: crate::fs::DriveType::Hdd,
+            queue_capacity: Some(64),
+            ..Self::local()
+        }
+    }
+
+    /// 按路径所在介质选择参数；NetworkMode::On / Off 覆盖网络识别（Off 时网络路径按本地参数）
+    pub(crate) fn resolve(root_dir: &str, mode: NetworkMode) -> Self {
+        let drive = match mode {
+            NetworkMode::On => crate::fs::DriveType::Network,
+            _ => crate::fs::drive_type(root_dir),
+        };
+        match drive {
+            crate::fs::DriveType::Network if mode != NetworkMode::Off => Self::network(),
+            crate::fs::DriveType::Hdd => Self::hdd(),
+            _ => Self { drive, ..Self::local() },
+        }
+    }
+
+    pub(crate) fn drive(&self) -> crate::fs::DriveType {
+        self.drive
+    }
+
+    pub(crate) fn is_network(&self) -> bool {
+        self.network
+    }
+
+    /// 应用用户设置：线程数只覆盖本地磁盘参数（网络路径保持低并发），确定性模式固定单线程
+    pub(crate) fn with_settings(self, settings: &crate::settings::ScanSettings) -> Self {
+        let threads = match settings.threads {
+            _ if settings.deterministic => 1,
+            Some(n) if !self.is_network() => n,
+            _ => self.threads,
+        };
+        Self {
+            threads,
+            queue_capacity: settings.queue_capacity.or(self.queue_capacity),
+            deterministic: settings.deterministic,
+            ..self
+        }
+    }
+
+    /// 后台模式：最多 2 个低优先级 worker，每个目录之间暂停 2ms
+    pub(crate) fn background(self) -> Self {
+        Self {
+            threads: self.threads.min(2),
+            dispatch_pause: Some(std::time::Duration::from_millis(2)),
+            ..self
+        }
+    }
+
+    pub(crate) fn is_background(&self) -> bool {
+        self.dispatch_pause.is_some()
+    }
+
+    pub(crate) fn with_exclusions(self, exclusions: Exclusions) -> Self {
+        Self { exclusions, ..self }
+    }
+
+    pub(crate) fn exclusions(&self) -> Exclusions {
+        self.exclusions
+    }
+
+    pub(crate) fn with_includes(self, includes: IncludeFilter) -> Self {
+        Self { includes, ..self }
+    }
+
+    pub(crate) fn includes(&self) -> &IncludeFilter {
+        &self.includes
+    }
+
+    pub(crate) fn with_reused_subtrees(self, reuse: ReusedSubtrees) -> Self {
+        Self { reuse, ..self }
+    }
+}
+
+/// 一个可沿用的已缓存子树（子目录自身的完整扫描结果）
+#[derive(Debug)]
+pub(crate) struct ReusedSubtree {
+    items: Arc<Vec<Item>>,
+    errors: Arc<Vec<ScanError>>,
+    sparse_files: Arc<Vec<SparseEntry>>,
+    cloud_only_bytes: i64,
+    cloud_local_bytes: i64,
+}
+
+impl From<&ArcScanResult> for ReusedSubtree {
+    fn from(result: &ArcScanResult) -> Self {
+        Self {
+            items: Arc::clone(&result.items),
+            errors: Arc::clone(&result.errors),
+            sparse_files: Arc::clone(&result.sparse_files),
+            cloud_only_bytes: result.cloud_only_bytes,
+            cloud_local_bytes: result.cloud_local_bytes,
+        }
+    }
+}
+
+impl From<ScanResult> for ReusedSubtree {
+    fn from(result: ScanResult) -> Self {
+        Self {
+            items: Arc::new(result.items),
+            errors: Arc::new(result.errors),
+            sparse_files: Arc::new(result.sparse_files),
+            cloud_only_bytes: result.cloud_only_bytes,
+            cloud_local_bytes: result.cloud_local_bytes,
+        }
+    }
+}
+
+/// 扫描根目录下仍然新鲜的已缓存子树，按子目录的缓存 key 索引（互不嵌套，只保留最外层）
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ReusedSubtrees(Arc<HashMap<String, ReusedSubtree>>);
+
+impl ReusedSubtrees {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// 目录 abs_path 的缓存子树；缓存中的路径与本次遍历的路径形式不一致时不沿用
+    fn get(&self, abs_path: &str) -> Option<&ReusedSubtree> {
+        if self.0.is_empty() {
+            return None;
+        }
+        let subtree = self.0.get(&crate::fs::cache_key(abs_path))?;
+        let consistent = subtree.items.first().is_none_or(|first| {
+            first.path.strip_prefix(abs_path).is_some_and(|rest| rest.starts_with('/'))
+        });
+        consistent.then_some(subtree)
+    }
+}
+
+impl PartialEq for ReusedSubtrees {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || (self.is_empty() && other.is_empty())
+    }
+}
+
+impl Eq for ReusedSubtrees {}
+
+/// Item 可投影的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ItemField {
+    Path,
+    Name,
+    Size,
+    SizeFormatted,
+    IsDir,
+    Modified,
+    Created,
+    Id,
+    Kind,
+    FileCount,
+    DirCount,
+    Category,
+    PercentOfParent,
+    PercentOfTotal,
+}
+
+impl ItemField {
+    #[inline]
+    fn bit(self) -> u16 {
+        1 << (self as u16)
+    }
+}
+
+/// 字段集合（位掩码）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldMask(u16);
+
+impl FieldMask {
+    pub const ALL: FieldMask = FieldMask(0xFFFF);
+
+    /// 由字段列表构造；None 或空列表表示全部字段
+    pub fn from_fields(fields: Option<&[ItemField]>) -> Self {
+        match fields {
+            Some(fields) if !fields.is_empty() => {
+                FieldMask(fields.iter().fold(0, |acc, f| acc | f.bit()))
+            }
+            _ => Self::ALL,
+        }
+    }
+
+    #[inline]
+    pub fn contains(&self, field: ItemField) -> bool {
+        self.0 & field.bit() != 0
+    }
+}
+
+/// 按字段掩码序列化的 items（仅在序列化时裁剪，不复制 Item）
+#[derive(Debug, Clone)]
+pub struct ProjectedItems {
+    pub items: Vec<Item>,
+    pub mask: FieldMask,
+}
+
+impl Serialize for ProjectedItems {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{SerializeMap, SerializeSeq};
+
+        struct Projected<'a>(&'a Item, FieldMask);
+
+        impl Serialize for Projected<'_> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let (item, mask) = (self.0, self.1);
+                let mut map = serializer.serialize_map(None)?;
+                if mask.contains(ItemField::Path) {
+                    map.serialize_entry("path", item.path.as_str())?;
+                }
+                if mask.contains(ItemField::Name) {
+                    map.serialize_entry("name", item.name.as_str())?;
+                }
+                if mask.contains(ItemField::Size) {
+                    map.serialize_entry("size", &item.size)?;
+                }
+                if mask.contains(ItemField::SizeFormatted) {
+                    map.serialize_entry("sizeFormatted", item.size_formatted.as_str())?;
+                }
+                if mask.contains(ItemField::IsDir) {
+                    map.serialize_entry("isDir", &item.is_dir)?;
+                }
+                if mask.contains(ItemField::Modified) {
+                    map.serialize_entry("modified", &item.modified)?;
+                }
+                if mask.contains(ItemField::Created) {
+                    map.serialize_entry("created", &item.created)?;
+                }
+                if mask.contains(ItemField::Id) {
+                    map.serialize_entry("id", &item.id)?;
+                }
+                if mask.contains(ItemField::Kind) {
+                    map.serialize_entry("kind", &item.kind)?;
+                }
+                if mask.contains(ItemField::FileCount) {
+                    map.serialize_entry("fileCount", &item.file_count)?;
+                }
+                if mask.contains(ItemField::DirCount) {
+                    map.serialize_entry("dirCount", &item.dir_count)?;
+                }
+                if mask.contains(ItemField::Category) {
+                    map.serialize_entry("category", &item.category)?;
+                }
+                if mask.contains(ItemField::PercentOfParent) {
+                    map.serialize_entry("percentOfParent", &item.percent_of_parent)?;
+                }
+                if mask.contains(ItemField::PercentOfTotal) {
+                    map.serialize_entry("percentOfTotal", &item.percent_of_total)?;
+                }
+                map.end()
+            }
+        }
+
+        let mut seq = serializer.serialize_seq(Some(self.items.len()))?;
+        for item in &self.items {
+            seq.serialize_element(&Projected(item, self.mask))?;
+        }
+        seq.end()
+    }
+}
+
+impl JsonSchema for ProjectedItems {
+    fn schema_name() -> String {
+        "ProjectedItems".to_string()
+    }
+
+    /// 按 ScanOptions::fields 投影后的 Item：每个字段都可能缺省
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        use schemars::schema::{ArrayValidation, InstanceType, Schema, SchemaObject};
+
+        let mut item = Item::json_schema(gen).into_object();
+        item.object().required.clear();
+        Schema::Object(SchemaObject {
+            instance_type: Some(InstanceType::Array.into()),
+            array: Some(Box::new(ArrayValidation {
+                items: Some(Schema::Object(item).into()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        })
+    }
+}
+
+/// 命令返回的扫描结果：与 ScanResult 的 JSON 形状一致，items 按 ScanOptions::fields 投影。
+/// 单独成型而不是给 ScanResult 加掩码，是因为 ScanResult 还要用 bincode 写磁盘缓存
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanResponse {
+    pub items: ProjectedItems,
+    pub total_size: i64,
+    #[schemars(with = "String")]
+    pub total_size_formatted: CompactString,
+    pub scan_time: f64,
+    #[schemars(with = "String")]
+    pub path: CompactString,
+    pub mft_available: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<TimingInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub perf_metrics: Option<ScanPerfMetrics>,
+    pub extension_stats: Option<Vec<ExtensionStat>>,
+    pub category_stats: Vec<CategoryStat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<Vec<String>>")]
+    pub owners: Option<Vec<CompactString>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<Vec<String>>")]
+    pub unscanned: Option<Vec<CompactString>>,
+    pub errors: Vec<ScanError>,
+    pub unreadable_bytes: i64,
+    pub cloud_only_bytes: i64,
+    pub cloud_local_bytes: i64,
+    pub sparse_files: Vec<SparseEntry>,
+    pub sparse_savings_bytes: i64,
+    pub cache_usage: CacheUsage,
+    /// 扫描受保护根目录前的提权建议（见 elevated::probe），未探测时省略
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elevation: Option<crate::elevated::ElevationAdvice>,
+}
+
+impl ScanResponse {
+    pub fn new(result: ScanResult, fields: Option<&[ItemField]>) -> Self {
+        Self {
+            items: ProjectedItems {
+                items: result.items,
+                mask: FieldMask::from_fields(fields),
+            },
+            total_size: result.total_size,
+            total_size_formatted: result.total_size_formatted,
+            scan_time: result.scan_time,
+            path: result.path,
+            mft_available: result.mft_available,
+            timing: result.timing,
+            perf_metrics: result.perf_metrics,
+            extension_stats: result.extension_stats,
+            category_stats: result.category_stats,
+            owners: result.owners,
+            unscanned: result.unscanned,
+            errors: result.errors,
+            unreadable_bytes: result.unreadable_bytes,
+            cloud_only_bytes: result.cloud_only_bytes,
+            cloud_local_bytes: result.cloud_local_bytes,
+            sparse_files: result.sparse_files,
+            sparse_savings_bytes: result.sparse_savings_bytes,
+            cache_usage: result.cache_usage,
+            elevation: None,
+        }
+    }
+}
+
+/// 单个扩展名的聚合统计（仅统计文件）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionStat {
+    /// 小写扩展名，不含点；无扩展名时为 "no-ext"
+    #[schemars(with = "String")]
+    pub extension: CompactString,
+    pub total_size: i64,
+    #[schemars(with = "String")]
+    pub total_size_formatted: CompactString,
+    pub file_count: usize,
+    pub percent_of_total: f64,
+}
+
+/// 扫描性能指标
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanPerfMetrics {
+    pub io_phase_ms: u64,
+    pub compute_phase_ms: u64,
+    pub serialize_phase_ms: u64,
+    pub cache_read_time_ms: u64,
+    pub files_scanned: usize,
+    pub dirs_scanned: usize,
+    pub io_throughput_mbps: f64,
+    pub memory_peak_mb: f64,
+    pub threads_used: usize,
+    pub cache_hit: bool,
+    pub cache_source: Option<String>, // "memory" | "disk" | None
+    /// 遍历时识别的存储介质；命中缓存 / USN 增量等未遍历目录时为 None
+    pub drive_type: Option<crate::fs::DriveType>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArcScanResult {
+    pub items: Arc<Vec<Item>>,
+    pub total_size: i64,
+    pub total_size_formatted: Arc<str>,
+    pub scan_time: f64,
+    pub path: Arc<str>,
+    pub mft_available: bool,
+    pub timing: Option<TimingInfo>,
+    pub errors: Arc<Vec<ScanError>>,
+    pub unreadable_bytes: i64,
+    pub cloud_only_bytes: i64,
+    pub cloud_local_bytes: i64,
+    pub sparse_files: Arc<Vec<SparseEntry>>,
+    pub sparse_savings_bytes: i64,
+}
+
+impl From<ArcScanResult> for ScanResult {
+    fn from(result: ArcScanResult) -> Self {
+        Self {
+            items: Arc::unwrap_or_clone(result.items),
+            total_size: result.total_size,
+            total_size_formatted: CompactString::from(result.total_size_formatted.as_ref()),
+            scan_time: result.scan_time,
+            path: CompactString::from(result.path.as_ref()),
+            mft_available: result.mft_available,
+            timing: result.timing,
+            perf_metrics: None,
+            extension_stats: None,
+            category_stats: Vec::new(),
+            owners: None,
+            unscanned: None,
+            errors: Arc::unwrap_or_clone(result.errors),
+            unreadable_bytes: result.unreadable_bytes,
+            cloud_only_bytes: result.cloud_only_bytes,
+            cloud_local_bytes: result.cloud_local_bytes,
+            sparse_files: Arc::unwrap_or_clone(result.sparse_files),
+            sparse_savings_bytes: result.sparse_savings_bytes,
+            cache_usage: CacheUsage::default(),
+        }
+    }
+}
+
+impl From<&ArcScanResult> for ScanResult {
+    fn from(result: &ArcScanResult) -> Self {
+        Self {
+            items: result.items.as_ref().clone(),
+            total_size: result.total_size,
+            total_size_formatted: CompactString::from(result.total_size_formatted.as_ref()),
+            scan_time: result.scan_time,
+            path: CompactString::from(result.path.as_ref()),
+            mft_available: result.mft_available,
+            timing: result.timing.clone(),
+            perf_metrics: None,
+            extension_stats: None,
+            category_stats: Vec::new(),
+            owners: None,
+            unscanned: None,
+            errors: result.errors.as_ref().clone(),
+            unreadable_bytes: result.unreadable_bytes,
+            cloud_only_bytes: result.cloud_only_bytes,
+            cloud_local_bytes: result.cloud_local_bytes,
+            sparse_files: result.sparse_files.as_ref().clone(),
+            sparse_savings_bytes: result.sparse_savings_bytes,
+            cache_usage: CacheUsage::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryItem {
+    #[schemars(with = "String")]
+    pub path: CompactString,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[schemars(with = "i64")]
+    pub scan_time: chrono::DateTime<chrono::Utc>,
+    pub total_size: i64,
+    #[schemars(with = "String")]
+    pub size_format: CompactString,
+    pub item_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryItemSummary {
+    pub path: String,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[schemars(with = "i64")]
+    pub scan_time: chrono::DateTime<chrono::Utc>,
+    pub total_size: i64,
+    pub size_format: String,
+    pub item_count: usize,
+}
+
+impl From<&HistoryItem> for HistoryItemSummary {
+    fn from(item: &HistoryItem) -> Self {
+        Self {
+            path: item.path.to_string(),
+            scan_time: item.scan_time,
+            total_size: item.total_size,
+            size_format: item.size_format.to_string(),
+            item_count: item.item_count,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub result: ArcScanResult,
+    pub dir_mtime: chrono::DateTime<chrono::Local>,
+    pub size: usize,
+    /// 目录监视报告树内有变更，不再作为新鲜结果命中（会话仍可浏览）
+    pub stale: bool,
+    /// 子目录 mtime 摘要，命中前抽样校验
+    pub digest: Arc<crate::dir_digest::DirDigest>,
+}
+
+/// 内存缓存的容量上限与当前占用（大小为估计值）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryCacheStats {
+    pub max_entries: usize,
+    pub max_size_mb: usize,
+    pub current_entries: usize,
+    pub current_size_mb: f64,
+}
+
+pub struct ScanCache {
+    cache: Mutex<LruCache<String, CacheEntry>>,
+    /// 估计大小上限（字节），可经 set_limits 在运行时调整
+    max_size_bytes: AtomicUsize,
+    /// 插入后按进程 RSS 与 settings.cache 关闭最久未查看的会话
+    rss_retention: bool,
+}
+
+impl ScanCache {
+    pub fn new(max_entries: usize, max_size_mb: usize) -> Self {
+        ScanCache {
+            cache: Mutex::new(LruCache::new(NonZeroUsize::new(max_entries).unwrap())),
+            max_size_bytes: AtomicUsize::new(max_size_mb * 1024 * 1024),
+            rss_retention: false,
+        }
+    }
+
+    /// 启用按内存占用的会话回收（见 CacheSettings）
+    pub fn with_rss_retention(mut self) -> Self {
+        self.rss_retention = true;
+        self
+    }
+
+    pub fn get(&self, path: &str) -> Option<CacheEntry> {
+        let mut cache = self.cache.lock();
+        cache.get(path).cloned()
+    }
+
+    pub fn insert(&self, path: String, result: ScanResult) {
+        let digest = Arc::new(crate::dir_digest::DirDigest::from_items(&result.path, &result.items));
+        let arc_result = Self::to_arc(result);
+        let entry_size = Self::estimate_size(&arc_result);
+        let max_size_bytes = self.max_size_bytes.load(Ordering::Relaxed);
+        let mut cache = self.cache.lock();
+
+        let current_total: usize = cache.iter().map(|(_, e)| e.size).sum();
+        if current_total + entry_size > max_size_bytes {
+            while cache.iter().map(|(_, e)| e.size).sum::<usize>() + entry_size > max_size_bytes
+                && !cache.is_empty()
+            {
+                cache.pop_lru();
+            }
+        }
+
+        cache.put(
+            path,
+            CacheEntry {
+                result: arc_result,
+                dir_mtime: chrono::Local::now(),
+                size: entry_size,
+                stale: false,
+                digest,
+            },
+        );
+        drop(cache);
+
+        if self.rss_retention {
+            self.enforce_rss_limit();
+        }
+    }
+
+    /// 预热载入：只占用空余的容量（条数、估计大小与 RSS 上限），不挤出已有的会话，
+    /// 并排到最久未查看的一端，内存紧张时先于用户打开过的会话被关闭；返回是否载入
+    pub fn insert_prewarmed(&self, path: String, result: ScanResult) -> bool {
+        if self.rss_retention {
+            if let Some((rss, total_memory)) = crate::perf::process_memory() {
+                if rss >= crate::settings::Settings::current().cache.rss_limit_bytes(total_memory) {
+                    return false;
+                }
+            }
+        }
+        let digest = Arc::new(crate::dir_digest::DirDigest::from_items(&result.path, &result.items));
+        let arc_result = Self::to_arc(result);
+        let entry_size = Self::estimate_size(&arc_result);
+        let mut cache = self.cache.lock();
+        let current_total: usize = cache.iter().map(|(_, e)| e.size).sum();
+        if cache.len() >= cache.cap().get() || current_total + entry_size > self.max_size_bytes.load(Ordering::Relaxed) {
+            return false;
+        }
+        cache.put(
+            path.clone(),
+            CacheEntry {
+                result: arc_result,
+                dir_mtime: chrono::Local::now(),
+                size: entry_size,
+                stale: false,
+                digest,
+            },
+        );
+        cache.demote(&path);
+        true
+    }
+
+    /// 调整容量上限（条数、估计大小），超出新上限的部分按最久未查看的顺序移除，返回被移除的 key
+    pub fn set_limits(&self, max_entries: usize, max_size_mb: usize) -> Vec<String> {
+        let max_entries = max_entries.max(1);
+        let max_size_bytes = max_size_mb * 1024 * 1024;
+        self.max_size_bytes.store(max_size_bytes, Ordering::Relaxed);
+        let mut cache = self.cache.lock();
+        let mut total: usize = cache.iter().map(|(_, e)| e.size).sum();
+        let mut evicted = Vec::new();
+        while cache.len() > max_entries || total > max_size_bytes {
+            let Some((key, entry)) = cache.pop_lru() else {
+                break;
+            };
+            total -= entry.size;
+            evicted.push(key);
+        }
+        cache.resize(NonZeroUsize::new(max_entries).unwrap());
+        evicted
+    }
+
+    pub fn stats(&self) -> MemoryCacheStats {
+        let cache = self.cache.lock();
+        let size: usize = cache.iter().map(|(_, e)| e.size).sum();
+        MemoryCacheStats {
+            max_entries: cache.cap().get(),
+            max_size_mb: self.max_size_bytes.load(Ordering::Relaxed) / 1024 / 1024,
+            current_entries: cache.len(),
+            current_size_mb: size as f64 / 1024.0 / 1024.0,
+        }
+    }
+
+    /// 是否已有 path 的结果（不改变最近查看顺序）
+    pub fn contains(&self, path: &str) -> bool {
+        self.cache.lock().contains(path)
+    }
+
+    fn to_arc(result: ScanResult) -> ArcScanResult {
+        ArcScanResult {
+            items: Arc::new(result.items),
+            total_size: result.total_size,
+            total_size_formatted: Arc::from(result.total_size_formatted.as_str()),
+            scan_time: result.scan_time,
+            path: Arc::from(result.path.as_str()),
+            mft_available: result.mft_available,
+            timing: result.timing,
+            errors: Arc::new(result.errors),
+            unreadable_bytes: result.unreadable_bytes,
+            cloud_only_bytes: result.cloud_only_bytes,
+            cloud_local_bytes: result.cloud_local_bytes,
+            sparse_files: Arc::new(result.sparse_files),
+            sparse_savings_bytes: result.sparse_savings_bytes,
+        }
+    }
+
+    fn enforce_rss_limit(&self) {
+        let Some((rss, total_memory)) = crate::perf::process_memory() else {
+            return;
+        };
+        let settings = crate::settings::Settings::current().cache;
+        let limit = settings.rss_limit_bytes(total_memory);
+        for key in self.evict_for_rss(rss, limit, settings.keep_sessions) {
+            eprintln!(
+                "[Cache] 内存占用 {} 超过上限 {}，关闭最久未查看的会话（下次访问从磁盘缓存读回）: {}",
+                format_size(rss as i64),
+                format_size(limit as i64),
+                key
+            );
+        }
+    }
+
+    /// RSS 超过 limit 时按最久未查看的顺序移除会话（至少保留 keep 个），返回被移除的 key。
+    /// 释放的内存未必立即归还操作系统，这里按各会话的估计大小递减 RSS，而不是重新采样
+    pub fn evict_for_rss(&self, rss: u64, limit: u64, keep: usize) -> Vec<String> {
+        let mut cache = self.cache.lock();
+        let mut rss = rss;
+        let mut evicted = Vec::new();
+        while rss > limit && cache.len() > keep {
+            let Some((key, entry)) = cache.pop_lru() else {
+                break;
+            };
+            rss = rss.saturating_sub(entry.size as u64);
+            evicted.push(key);
+        }
+        evicted
+    }
+
+    fn estimate_size(result: &ArcScanResult) -> usize {
+        result.items.iter().map(|item| {
+            std::mem::size_of::<Item>()
+                + item.path.len()
+                + item.name.len()
+                + item.size_formatted.len()
+        }).sum::<usize>()
+            + std::mem::size_of::<Arc<Vec<Item>>>()
+    }
+
+    pub fn invalidate(&self, path: &str) {
+        let mut cache = self.cache.lock();
+        let keys_to_remove: Vec<String> = cache
+            .iter()
+            .filter(|(k, _)| k.starts_with(path))
+            .map(|(k, _)| k.clone())
+            .collect();
+        for key in keys_to_remove {
+            cache.pop(&key);
+        }
+    }
+
+    /// 移除 key 这一条结果（精确匹配）
+    pub fn remove(&self, key: &str) -> bool {
+        self.cache.lock().pop(key).is_some()
+    }
+
+    /// 移除超过有效期的结果（never_cache 目录的结果是当前会话，不在此移除）
+    pub fn apply_policy(&self, policy: &crate::settings::CacheSettings) -> usize {
+        let mut cache = self.cache.lock();
+        let expired: Vec<String> = cache
+            .iter()
+            .filter(|(_, e)| policy.is_expired(e.dir_mtime.timestamp()))
+            .map(|(k, _)| k.clone())
+            .collect();
+        for key in &expired {
+            cache.pop(key);
+        }
+        expired.len()
+    }
+
+    /// root 下各子目录的完整结果缓存条目，不改变 LRU 顺序
+    pub fn child_entries(&self, root: &str) -> Vec<(String, CacheEntry)> {
+        let cache = self.cache.lock();
+        cache
+            .iter()
+            .filter(|(k, _)| crate::fs::is_child_cache_key(root, k))
+            .map(|(k, e)| (k.clone(), e.clone()))
+            .collect()
+    }
+
+    /// 包含 path 的会话 key（path 本身或最近的祖先目录），不改变 LRU 顺序
+    fn containing_key(&self, path: &str) -> Option<String> {
+        let cache = self.cache.lock();
+        cache
+            .iter()
+            .map(|(k, _)| k)
+            .filter(|k| {
+                path.strip_prefix(k.as_str())
+                    .is_some_and(|rest| rest.is_empty() || k.ends_with('/') || rest.starts_with('/'))
+            })
+            .max_by_key(|k| k.len())
+            .cloned()
+    }
+
+    /// 失效 path 本身、其子目录以及包含它的祖先目录的缓存
+    /// （深层文件被删除时祖先目录的 mtime 不变，mtime 校验发现不了）
+    /// 目录 path 内有变更：path 本身及其祖先目录的结果不再作为新鲜结果命中（会话保留，仍可浏览）
+    pub fn mark_stale(&self, path: &str) -> usize {
+        let mut cache = self.cache.lock();
+        let mut marked = 0;
+        for (key, entry) in cache.iter_mut() {
+            let root = key.split('|').next().unwrap_or(key);
+            if !entry.stale && (root == path || crate::fs::is_child_cache_key(root, path)) {
+                entry.stale = true;
+                marked += 1;
+            }
+        }
+        marked
+    }
+
+    /// 移除 path 本身、其子目录与祖先目录的结果（按路径层级比较，与磁盘缓存一致）
+    pub fn invalidate_related(&self, path: &str) {
+        let mut cache = self.cache.lock();
+        let keys_to_remove: Vec<String> = cache
+            .iter()
+            .filter(|(k, _)| {
+                let root = k.split('|').next().unwrap_or(k);
+                root == path || crate::fs::is_child_cache_key(root, path) || crate::fs::is_child_cache_key(path, root)
+            })
+            .map(|(k, _)| k.clone())
+            .collect();
+        for key in keys_to_remove {
+            cache.pop(&key);
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref SCAN_CACHE: ScanCache = {
+        let policy = crate::settings::Settings::current().cache;
+        ScanCache::new(policy.memory_max_entries, policy.memory_max_mb as usize).with_rss_retention()
+    };
+    static ref SIZE_UNITS: [&'static str; 5] = ["B", "KB", "MB", "GB", "TB"];
+}
+
+/// 任意路径的规范展示形式（canonical + 正斜杠），路径不存在时为 None
+pub fn canonical_display_path(path: &str) -> Option<String> {
+    let canonical = std::fs::canonicalize(crate::fs::to_extended_length(path)).ok()?;
+    Some(normalize_path_separator(canonical.as_os_str()))
+}
+
+/// 将任意路径规范化为内存/磁盘缓存使用的 key（canonical 后按平台折叠大小写）
+fn cache_key_for(path: &str) -> Option<String> {
+    canonical_display_path(path).map(|p| crate::fs::cache_key(&p))
+}
+
+/// 获取内存缓存中的扫描结果 items（供 dev_analyzer 等模块复用，
+/// 避免把百万级 items 再次跨 IPC 传回后端）。
+/// 会话因内存上限被关闭时从磁盘缓存读回，并重新放入内存缓存
+pub fn get_cached_items(path: &str) -> Option<Arc<Vec<Item>>> {
+    // 路径在本机不存在时（导入的缓存）按字面规范化
+    let key = normalize_cache_key(path);
+    if let Some(entry) = SCAN_CACHE.get(&key) {
+        return Some(Arc::clone(&entry.result.items));
+    }
+    let result = DiskCache::instance().get_stale(&key)?;
+    SCAN_CACHE.insert(key.clone(), result);
+    SCAN_CACHE.get(&key).map(|e| Arc::clone(&e.result.items))
+}
+
+/// 内存缓存中包含 path 的扫描结果（path 本身或祖先目录的会话），返回 (path 的规范展示形式, 会话 items)。
+/// 从已扫描的根目录逐层下钻时不必再遍历子目录
+pub fn cached_items_containing(path: &str) -> Option<(String, Arc<Vec<Item>>)> {
+    let display = canonical_display_path(path)?;
+    let root = SCAN_CACHE.containing_key(&crate::fs::cache_key(&display))?;
+    let entry = SCAN_CACHE.get(&root)?;
+    Some((display, Arc::clone(&entry.result.items)))
+}
+
+/// 比较同一路径上一次扫描与当前扫描（当前结果优先取内存缓存），
+/// 没有上一次结果或当前结果时返回 None
+pub fn compare_with_previous_scan(path: &str) -> Option<crate::diff_engine::ScanComparison> {
+    let display = canonical_display_path(path)?;
+    let key = crate::fs::cache_key(&display);
+    let disk_cache = DiskCache::instance();
+    let (previous, previous_cached_at) = disk_cache.get_previous(&key)?;
+    let current = match SCAN_CACHE.get(&key) {
+        Some(entry) => Arc::clone(&entry.result.items),
+        None => Arc::new(disk_cache.get_stale(&key)?.items),
+    };
+
+    Some(crate::diff_engine::ScanComparison {
+        diff: crate::diff_engine::diff(&previous.items, &current, previous.total_size),
+        path: display,
+        previous_cached_at,
+    })
+}
+
+/// 前端传入路径对应的缓存 key；路径已不存在时只做字符串规范化
+pub fn normalize_cache_key(path: &str) -> String {
+    cache_key_for(path).unwrap_or_else(|| crate::fs::cache_key(path))
+}
+
+/// 打开缓存中 path 的扫描结果，不扫描、不校验新鲜度（浏览导入的缓存或已不存在的目录），
+/// 并放入内存缓存作为会话，之后可按 path 调用会话类命令
+pub fn open_cached_result(path: &str) -> Option<ScanResult> {
+    let key = normalize_cache_key(path);
+    if let Some(entry) = SCAN_CACHE.get(&key) {
+        let mut result = ScanResult::from(&entry.result);
+        result.cache_usage = CacheUsage::hit(CacheSource::Memory, result.items.len());
+        return Some(result);
+    }
+    let mut result = DiskCache::instance().get_stale(&key)?;
+    SCAN_CACHE.insert(key, result.clone());
+    result.cache_usage = CacheUsage::hit(CacheSource::Disk, result.items.len());
+    Some(result)
+}
+
+/// 内存缓存的容量上限与当前占用
+pub fn memory_cache_stats() -> MemoryCacheStats {
+    SCAN_CACHE.stats()
+}
+
+/// 从内存缓存移除 key 这一条结果（磁盘缓存不变）；返回是否存在
+pub fn evict_memory_result(key: &str) -> bool {
+    SCAN_CACHE.remove(key)
+}
+
+/// 把磁盘缓存中命中最多的至多 limit 条结果预先载入内存缓存，返回载入的 key。
+/// 已在内存中、目录已变化（或已不存在）、已过期的跳过；内存缓存没有空余容量时停止
+pub fn prewarm_memory_cache(limit: usize) -> Vec<String> {
+    let policy = crate::settings::Settings::current().cache;
+    let candidates = match DiskCache::instance().frequent_entries(limit) {
+        Ok(candidates) => candidates,
+        Err(e) => {
+            eprintln!("[Prewarm] 读取磁盘缓存失败: {}", e);
+            return Vec::new();
+        }
+    };
+    let mut loaded = Vec::new();
+    for (key, cached_mtime, last_accessed) in candidates {
+        if SCAN_CACHE.contains(&key) || policy.is_expired(last_accessed) || policy.never_caches(&key) {
+            continue;
+        }
+        let root = key.split('|').next().unwrap_or(&key);
+        let Ok(mtime) = std::fs::metadata(crate::fs::to_extended_length(root)).and_then(|m| m.modified()) else {
+            continue;
+        };
+        if cached_mtime < chrono::DateTime::<chrono::Local>::from(mtime).timestamp() {
+            continue;
+        }
+        let Some(result) = DiskCache::instance().get_stale(&key) else {
+            continue;
+        };
+        if !SCAN_CACHE.insert_prewarmed(key.clone(), result) {
+            break;
+        }
+        loaded.push(key);
+    }
+    loaded
+}
+
+/// 删除一条扫描缓存（内存与磁盘），下次扫描该目录时完整遍历；子目录与祖先目录的缓存不受影响。
+/// path 可以是目录路径，也可以是 list_entries 列出的 key（含排除 / 包含后缀）；返回是否存在
+pub fn delete_cache_entry(path: &str) -> anyhow::Result<bool> {
+    let key = if path.contains('|') { path.to_string() } else { normalize_cache_key(path) };
+    let in_memory = evict_memory_result(&key);
+    let on_disk = DiskCache::instance().delete_entry(&key)?;
+    Ok(in_memory || on_disk)
+}
+
+/// 记录扫描根及其下层目录的大小，供 get_size_history 绘制趋势
+fn record_size_history(root_dir: &str, result: &ScanResult) {
+    // 与 get_size_history 查询时一样按缓存 key 规范化
+    let samples: Vec<(String, i64)> = crate::size_history::sample_dirs(root_dir, result.total_size, &result.items)
+        .into_iter()
+        .map(|(path, size)| (crate::fs::cache_key(&path), size))
+        .collect();
+    DiskCache::instance()
+        .insert_size_samples(&samples, chrono::Utc::now().timestamp())
+        .ok();
+}
+
+/// 文件被应用自身修改（删除/移动等）后，失效所有受影响的内存与磁盘缓存
+pub fn invalidate_caches_for(path: &str) {
+    let key = normalize_cache_key(path);
+    SCAN_CACHE.invalidate_related(&key);
+    DiskCache::instance().invalidate_related(&key).ok();
+}
+
+/// 目录 key 内有变更（见 cache_watch）：内存与磁盘中 key 本身及其祖先目录的缓存标记为过期，
+/// 下次扫描这些目录时不再直接命中缓存
+pub fn mark_caches_stale(key: &str) {
+    // 先磁盘后内存：两步之间的扫描即使内存未命中，也不会从磁盘读回旧结果
+    if let Err(e) = DiskCache::instance().mark_stale(key) {
+        eprintln!("[Cache] 标记过期失败 {}: {}", key, e);
+    }
+    SCAN_CACHE.mark_stale(key);
+}
+
+/// 条目被应用自身重命名后就地修正缓存（均为规范展示形式）：包含它的内存会话中，
+/// 该条目及其子树的路径与稳定 ID、条目的名称与类别改为新值，写回内存与磁盘缓存；
+/// 其余相关会话（以旧路径或其子目录为根、其他祖先目录）失效。
+/// 返回修正后的条目，不在任何内存会话中（或本身是扫描根）时返回 None
+pub fn patch_renamed(old_path: &str, new_path: &str) -> Option<Item> {
+    let old_key = crate::fs::cache_key(old_path);
+    let patched = SCAN_CACHE
+        .containing_key(&old_key)
+        .filter(|root| *root != old_key)
+        .and_then(|root| {
+            let mut result = ScanResult::from(&SCAN_CACHE.get(&root)?.result);
+            let item = rename_in_result(&mut result, old_path, new_path)?;
+            Some((root, result, item))
+        });
+    // 同时移除了上面取出的会话，修正后的结果随后写回
+    invalidate_caches_for(old_path);
+    invalidate_caches_for(new_path);
+    let (root, result, item) = patched?;
+    store_scan_result(&root, &result);
+    Some(item)
+}
+
+/// 把结果中 old_path 及其子树改到 new_path 下，返回改名后的条目
+fn rename_in_result(result: &mut ScanResult, old_path: &str, new_path: &str) -> Option<Item> {
+    let index = result.items.iter().position(|i| i.path == old_path)?;
+    let moved = |path: &str| -> Option<CompactString> {
+        let rest = path.strip_prefix(old_path)?;
+        (rest.is_empty() || rest.starts_with('/')).then(|| CompactString::from(format!("{}{}", new_path, rest)))
+    };
+
+    let volume_id = crate::fs::volume_id(&result.path);
+    for item in result.items.iter_mut() {
+        if let Some(path) = moved(&item.path) {
+            item.id = stable_item_id(volume_id, &path);
+            item.path = path;
+        }
+    }
+    for entry in result.sparse_files.iter_mut() {
+        if let Some(path) = moved(&entry.path) {
+            entry.path = path;
+        }
+    }
+    for error in result.errors.iter_mut() {
+        if let Some(path) = moved(&error.path) {
+            error.path = path;
+        }
+    }
+
+    let item = &mut result.items[index];
+    let name = new_path.rsplit('/').next().unwrap_or(new_path);
+    item.name = CompactString::from(name);
+    item.category = item_category(name, item.kind);
+    let item = item.clone();
+    // 扩展名可能改变
+    if result.extension_stats.is_some() {
+        result.extension_stats = Some(compute_extension_stats(&result.items));
+    }
+    Some(item)
+}
+
+/// 单独遍历的子树（提权补扫时由助手进程写出，JSON 传回主进程）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtreeScan {
+    pub root: CompactString,
+    pub total_size: i64,
+    pub items: Vec<Item>,
+    pub errors: Vec<ScanError>,
+    pub cloud_only_bytes: i64,
+    pub cloud_local_bytes: i64,
+    #[serde(default)]
+    pub sparse_files: Vec<SparseEntry>,
+}
+
+/// 只遍历 path 子树：不读写缓存、不走 MFT、不发事件，供提权补扫使用
+pub fn scan_subtree(path: &str) -> anyhow::Result<SubtreeScan> {
+    let canonical = std::fs::canonicalize(crate::fs::to_extended_length(path))?;
+    let root = normalize_path_separator(canonical.as_os_str());
+    // 独立的监控实例，不干扰当前扫描的性能指标
+    let perf_monitor = Arc::new(PerformanceMonitor::new(1));
+    let progress = crate::progress::ProgressTracker::new(&root, std::iter::empty());
+    let output = scan_directory_optimized_v4(
+        &canonical,
+        &perf_monitor,
+        None,
+        None,
+        ScanProfile::local().with_settings(&crate::settings::Settings::current().scan),
+        &HashMap::new(),
+        &progress,
+        crate::fs::read_dir_entries,
+    )?;
+    Ok(SubtreeScan {
+        root: CompactString::from(root.as_str()),
+        total_size: output.total_size,
+        items: output.items,
+        errors: output.errors,
+        cloud_only_bytes: output.cloud_only_bytes,
+        cloud_local_bytes: output.cloud_local_bytes,
+        sparse_files: output.sparse_files,
+    })
+}
+
+/// 只计数的扫描结果（不收集条目）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CountResult {
+    #[schemars(with = "String")]
+    pub path: CompactString,
+    pub file_count: u64,
+    pub dir_count: u64,
+    pub total_size: i64,
+    #[schemars(with = "String")]
+    pub total_size_formatted: CompactString,
+    pub scan_time: f64,
+    /// 无法读取的目录数（其内容未计入）
+    pub error_count: u64,
+    /// 超出时间预算而未遍历的目录数；非 0 时结果不完整
+    pub unscanned_count: u64,
+}
+
+/// 只计数模式：并行遍历但不创建任何 item，只累计文件数、目录数与总大小。
+/// 不读写缓存、不发事件，内存占用只有目录队列；用于驱动器概览与进度估计。
+/// 遵循 ScanOptions 的网络模式、后台模式、排除预设与时间预算，其余选项不适用
+pub fn count_directory(path: &str, options: &ScanOptions) -> anyhow::Result<CountResult> {
+    use std::sync::atomic::AtomicU64;
+
+    let start = std::time::Instant::now();
+    let canonical = std::fs::canonicalize(crate::fs::to_extended_length(path))?;
+    if !canonical.is_dir() {
+        return Err(anyhow::anyhow!("不是目录"));
+    }
+    let root = normalize_path_separator(canonical.as_os_str());
+    let mut profile = ScanProfile::resolve(&root, options.network_mode)
+        .with_settings(&crate::settings::Settings::current().scan);
+    if options.background {
+        profile = profile.background();
+    }
+    let profile = profile
+        .with_exclusions(Exclusions::new(&options.exclude_presets))
+        .with_includes(IncludeFilter::new(&options.include_globs));
+    let deadline = options
+        .time_budget_ms
+        .map(|ms| start + std::time::Duration::from_millis(ms));
+
+    let files = AtomicU64::new(0);
+    let dirs = AtomicU64::new(0);
+    let bytes = AtomicU64::new(0);
+    let errors = AtomicU64::new(0);
+    let unscanned = AtomicU64::new(0);
+    let shared_extents = crate::fs::SharedExtentTracker::default();
+    let dir_queue = DirQueue::new();
+    dir_queue.push(canonical, 0);
+
+    let mut pool_builder = rayon::ThreadPoolBuilder::new().num_threads(profile.threads);
+    if profile.is_background() {
+        pool_builder = pool_builder.start_handler(|_| {
+            crate::fs::enter_background_priority();
+        });
+    }
+    let pool = pool_builder.build()?;
+    pool.scope(|s| {
+        for _ in 0..profile.threads {
+            let (files, dirs, bytes, errors, unscanned) = (&files, &dirs, &bytes, &errors, &unscanned);
+            let (shared_extents, dir_queue, profile) = (&shared_extents, &dir_queue, &profile);
+            s.spawn(move |_| {
+                while let Some((dir_path, _)) = dir_queue.pop() {
+                    let _done = dir_queue.completion();
+                    if deadline.is_some_and(|d| std::time::Instant::now() >= d) {
+                        unscanned.fetch_add(1, Ordering::Relaxed);
+                        continue;
+                    }
+                    match crate::fs::read_dir_entries(&dir_path) {
+                        Ok(entries) => {
+                            for entry in entries {
+                                if !profile.exclusions.is_empty()
+                                    && profile.exclusions.excludes(
+                                        &normalize_path_separator(entry.path.as_os_str()),
+                                        &entry.name,
+                                        entry.is_dir,
+                                    )
+                                {
+                                    continue;
+                                }
+                                if entry.is_dir {
+                                    dirs.fetch_add(1, Ordering::Relaxed);
+                                    if entry.kind.descends() {
+                                        dir_queue.push(entry.path, 0);
+                                    }
+                                } else if profile.includes.is_empty()
+                                    || profile.includes.includes_file(
+                                        &normalize_path_separator(entry.path.as_os_str()),
+                                        &entry.name,
+                                    )
+                                {
+                                    files.fetch_add(1, Ordering::Relaxed);
+                                    bytes.fetch_add(entry.size + shared_extents.charge(entry.shared), Ordering::Relaxed);
+                                }
+                            }
+                        }
+                        Err(_) => {
+                            errors.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                    if let Some(pause) = profile.dispatch_pause {
+                        std::thread::sleep(pause);
+                    }
+                }
+            });
+        }
+    });
+
+    let total_size = bytes.into_inner() as i64;
+    Ok(CountResult {
+        path: CompactString::from(root.as_str()),
+        file_count: files.into_inner(),
+        dir_count: dirs.into_inner(),
+        total_size,
+        total_size_formatted: format_size(total_size),
+        scan_time: start.elapsed().as_secs_f64(),
+        error_count: errors.into_inner(),
+        unscanned_count: unscanned.into_inner(),
+    })
+}
+
+/// 路径当前的扫描结果（内存缓存优先，其次磁盘缓存，不校验 mtime），返回 (缓存 key, 结果)
+pub fn current_scan_result(path: &str) -> Option<(String, ScanResult)> {
+    let key = cache_key_for(path)?;
+    let result = match SCAN_CACHE.get(&key) {
+        Some(entry) => ScanResult::from(&entry.result),
+        None => DiskCache::instance().get_stale(&key)?,
+    };
+    Some((key, result))
+}
+
+/// 写回修改后的扫描结果（内存 + 磁盘缓存），缓存时间取根目录当前 mtime
+pub fn store_scan_result(key: &str, result: &ScanResult) {
+    let mtime = std::fs::metadata(crate::fs::to_extended_length(key))
+        .and_then(|m| m.modified())
+        .map(|m| chrono::DateTime::<chrono::Local>::from(m).timestamp())
+        .unwrap_or(0);
+    SCAN_CACHE.insert(key.to_string(), result.clone());
+    DiskCache::instance().insert_async(key, result, mtime);
+}
+
+/// 二进制扫描结果协议版本（2: 每项追加 modified / created；3: 追加稳定 id；4: 追加 kind；
+/// 5: 追加 file_count / dir_count；6: 追加 category；7: 追加百分比）
+pub const BINARY_PROTOCOL_VERSION: u8 = 7;
+
+/// 自定义紧凑二进制编码扫描结果，供前端经 Tauri 原始字节通道接收，
+/// 避免 serde_json 序列化百万级 items 的开销（无 key 名/引号/转义，size 用定宽整数）。
+/// 前端用 DataView + TextDecoder 顺序解析。布局（小端）:
+///   u32 magic=0x4644 | u8 version | u8 flags
+///   i64 total_size | f64 scan_time | u32 item_count | u32 file_count | u32 dir_count
+///   f64 io_ms | f64 compute_ms | f64 serialize_ms
+///   u32 path_len | path_utf8                      （被扫描路径）
+///   逐项: u32 path_len|path_utf8 | u32 name_len|name_utf8 | i64 size | u8 is_dir
+///         | i64 modified | i64 created                （version >= 2）
+///         | u64 id                                    （version >= 3）
+///         | u8 kind（ItemKind 判别值）                （version >= 4）
+///         | u32 file_count | u32 dir_count（子树计数）（version >= 5）
+///         | u8 category（FileCategory 判别值）        （version >= 6）
+///         | u16 percent_of_parent | u16 percent_of_total（万分比，0–10000）（version >= 7）
+///   类别统计（version >= 6）: u8 count | 逐类: u8 category | i64 total_size | u32 file_count
+pub fn encode_scan_result(result: &ScanResult) -> Vec<u8> {
+    let item_count = result.items.len();
+    let (file_count, dir_count) = result.perf_metrics.as_ref().map(|m| (m.files_scanned, m.dirs_scanned)).unwrap_or_else(|| {
+        let f = result.items.iter().filter(|i| !i.is_dir).count();
+        (f, item_count.saturating_sub(f))
+    });
+
+    let path_str = result.path.as_str();
+    let est = result.items.iter().map(|i| i.path.len() + i.name.len() + 4 + 4 + 8 + 1 + 8 + 8 + 8 + 1 + 4 + 4 + 1 + 2 + 2).sum::<usize>()
+        + path_str.len() + 64;
+    let mut buf = Vec::with_capacity(est);
+
+    // header
+    buf.extend_from_slice(&0x4644u32.to_le_bytes());
+    buf.push(BINARY_PROTOCOL_VERSION);
+    buf.push(0u8); // flags
+
+    // metadata
+    buf.extend_from_slice(&result.total_size.to_le_bytes());
+    buf.extend_from_slice(&result.scan_time.to_le_bytes());
+    buf.extend_from_slice(&(item_count as u32).to_le_bytes());
+    buf.extend_from_slice(&(file_count as u32).to_le_bytes());
+    buf.extend_from_slice(&(dir_count as u32).to_le_bytes());
+
+    let m = result.perf_metrics.as_ref();
+    buf.extend_from_slice(&(m.map(|m| m.io_phase_ms).unwrap_or(0) as f64).to_le_bytes());
+    buf.extend_from_slice(&(m.map(|m| m.compute_phase_ms).unwrap_or(0) as f64).to_le_bytes());
+    buf.extend_from_slice(&(m.map(|m| m.serialize_phase_ms).unwrap_or(0) as f64).to_le_bytes());
+
+    // 被扫描路径
+    write_bin_str(&mut buf, path_str);
+
+    // items（不传 sizeFormatted，由前端 formatSize 计算）
+    for item in &result.items {
+        write_bin_str(&mut buf, item.path.as_str());
+        write_bin_str(&mut buf, item.name.as_str());
+        buf.extend_from_slice(&item.size.to_le_bytes());
+        buf.push(if item.is_dir { 1u8 } else { 0u8 });
+        buf.extend_from_slice(&item.modified.to_le_bytes());
+        buf.extend_from_slice(&item.created.to_le_bytes());
+        buf.extend_from_slice(&item.id.to_le_bytes());
+        buf.push(item.kind as u8);
+        buf.extend_from_slice(&(item.file_count.min(u32::MAX as u64) as u32).to_le_bytes());
+        buf.extend_from_slice(&(item.dir_count.min(u32::MAX as u64) as u32).to_le_bytes());
+        buf.push(item.category as u8);
+        buf.extend_from_slice(&quantize_percent(item.percent_of_parent).to_le_bytes());
+        buf.extend_from_slice(&quantize_percent(item.percent_of_total).to_le_bytes());
+    }
+
+    buf.push(result.category_stats.len() as u8);
+    for stat in &result.category_stats {
+        buf.push(stat.category as u8);
+        buf.extend_from_slice(&stat.total_size.to_le_bytes());
+        buf.extend_from_slice(&(stat.file_count as u32).to_le_bytes());
+    }
+
+    buf
+}
+
+/// 百分比量化为万分比（0–10000）
+#[inline]
+fn quantize_percent(percent: f32) -> u16 {
+    (percent.clamp(0.0, 100.0) * 100.0).round() as u16
+}
+
+#[inline]
+fn write_bin_str(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    buf.extend_from_slice(s.as_bytes());
+}
+
+/// 无扩展名文件的统计 key（与前端 WASM get_extension_stats 保持一致）
+pub const NO_EXTENSION: &str = "no-ext";
+
+/// 提取小写扩展名；无扩展名或以点开头的隐藏文件（如 .gitignore）返回 None
+pub fn extension_of(name: &str) -> Option<String> {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() && !ext.is_empty() => Some(ext.to_lowercase()),
+        _ => None,
+    }
+}
+
+/// 按扩展名聚合文件大小与数量，按总大小降序
+pub fn compute_extension_stats(items: &[Item]) -> Vec<ExtensionStat> {
+    use rayon::prelude::*;
+
+    let totals: HashMap<String, (i64, usize)> = items
+        .par_iter()
+        .filter(|i| !i.is_dir)
+        .fold(HashMap::new, |mut acc: HashMap<String, (i64, usize)>, item| {
+            let ext = extension_of(&item.name).unwrap_or_else(|| NO_EXTENSION.to_string());
+            let e = acc.entry(ext).or_insert((0, 0));
+            e.0 += item.size;
+            e.1 += 1;
+            acc
+        })
+        .reduce(HashMap::new, |mut a, b| {
+            for (ext, (size, count)) in b {
+                let e = a.entry(ext).or_insert((0, 0));
+                e.0 += size;
+                e.1 += count;
+            }
+            a
+        });
+
+    let total_size: i64 = totals.values().map(|(size, _)| size).sum();
+
+    let mut stats: Vec<ExtensionStat> = totals
+        .into_iter()
+        .map(|(ext, (size, count))| ExtensionStat {
+            extension: CompactString::from(ext),
+            total_size: size,
+            total_size_formatted: format_size(size),
+            file_count: count,
+            percent_of_total: if total_size > 0 {
+                (size as f64 / total_size as f64) * 100.0
+            } else {
+                0.0
+            },
+        })
+        .collect();
+
+    stats.sort_unstable_by(|a, b| {
+        b.total_size
+            .cmp(&a.total_size)
+            .then_with(|| a.extension.cmp(&b.extension))
+    });
+    stats
+}
+
+/// 按选项附加可选统计（缓存里始终存放不带统计的结果，返回前按需计算）
+fn apply_result_options(result: &mut ScanResult, options: &ScanOptions) {
+    result.category_stats = compute_category_stats(&result.items);
+    result.extension_stats = if options.extension_stats {
+        Some(compute_extension_stats(&result.items))
+    } else {
+        None
+    };
+    // 统计按完整条目计算后再合并小文件
+    if let Some(min_size) = options.min_file_size.filter(|&size| size > 0) {
+        let volume_id = crate::fs::volume_id(&result.path);
+        fold_small_files(&mut result.items, min_size as i64, result.total_size, volume_id);
+    }
+    // 所有者与 items 按下标对齐，须在合并小文件之后解析
+    result.owners = if options.include_owner {
+        Some(crate::owner_report::resolve_owners(&result.items))
+    } else {
+        None
+    };
+}
+
+/// 小文件合成条目路径的最后一段（路径为所在目录 + 此名称，重扫后 ID 不变）
+pub const SMALL_FILES_NAME: &str = "<小文件>";
+
+/// 把每个目录下小于 min_size 的普通文件合并为一个合成条目（链接、占位符等照常列出），
+/// 合并后按大小降序重排并重新计算百分比；volume_id 为扫描根所在卷，用于合成条目的稳定 ID
+pub fn fold_small_files(items: &mut Vec<Item>, min_size: i64, total_size: i64, volume_id: u64) {
+    let mut folded: HashMap<String, (i64, usize, i64)> = HashMap::new();
+    items.retain(|item| {
+        if item.is_dir || item.kind != ItemKind::File || item.size >= min_size {
+            return true;
+        }
+        let parent = item.path.rsplit_once('/').map_or("", |(parent, _)| parent);
+        let (size, count, modified) = folded.entry(parent.to_string()).or_default();
+        *size += item.size;
+        *count += 1;
+        *modified = (*modified).max(item.modified);
+        false
+    });
+    if folded.is_empty() {
+        return;
+    }
+    items.extend(folded.into_iter().map(|(parent, (size, count, modified))| {
+        let path = format!("{}/{}", parent, SMALL_FILES_NAME);
+        Item {
+            id: stable_item_id(volume_id, &path),
+            path: CompactString::from(path.as_str()),
+            name: CompactString::from(format!("{} 个小文件", count)),
+            size,
+            size_formatted: format_size(size),
+            is_dir: false,
+            modified,
+            created: 0,
+            kind: ItemKind::SmallFiles,
+            file_count: count as u64,
+            dir_count: 0,
+            category: Default::default(),
+            percent_of_parent: 0.0,
+            percent_of_total: 0.0,
+        }
+    }));
+    items.sort_by_key(|item| std::cmp::Reverse(item.size));
+    fill_percentages(items, total_size);
+}
+
+#[inline]
+pub fn format_size(bytes: i64) -> CompactString {
+    if bytes < 1024 {
+        return CompactString::from(format!("{} B", bytes));
+    }
+
+    let mut size = bytes as f64;
+    let mut unit_index = 0;
+
+    while size >= 1024.0 && unit_index < 4 {
+        size /= 1024.0;
+        unit_index += 1;
+    }
+
+    if size < 10.0 {
+        CompactString::from(format!("{:.2} {}", size, SIZE_UNITS[unit_index]))
+    } else if size < 100.0 {
+        CompactString::from(format!("{:.1} {}", size, SIZE_UNITS[unit_index]))
+    } else {
+        CompactString::from(format!("{:.0} {}", size, SIZE_UNITS[unit_index]))
+    }
+}
+
+/// 百分比（0–100），分母不大于 0 时为 0
+#[inline]
+fn percent(part: i64, whole: i64) -> f32 {
+    if whole > 0 {
+        (part.max(0) as f64 / whole as f64 * 100.0) as f32
+    } else {
+        0.0
+    }
+}
+
+/// 格式化阶段填充 percent_of_parent / percent_of_total（目录大小须已聚合）。
+/// 父目录不在 items 中的条目（扫描根的直接子项）以 total_size 为分母
+pub fn fill_percentages(items: &mut [Item], total_size: i64) {
+    use rayon::prelude::*;
+
+    let dir_sizes: HashMap<&str, i64> = items
+        .iter()
+        .filter(|i| i.is_dir)
+        .map(|i| (i.path.as_str(), i.size))
+        .collect();
+    let percents: Vec<(f32, f32)> = items
+        .par_iter()
+        .map(|item| {
+            let parent_size = item
+                .path
+                .rsplit_once('/')
+                .and_then(|(parent, _)| dir_sizes.get(parent).copied())
+                .unwrap_or(total_size);
+            (percent(item.size, parent_size), percent(item.size, total_size))
+        })
+        .collect();
+    drop(dir_sizes);
+
+    for (item, (of_parent, of_total)) in items.iter_mut().zip(percents) {
+        item.percent_of_parent = of_parent;
+        item.percent_of_total = of_total;
+    }
+}
+
+/// 写入事件日志（失败不影响扫描）
+fn log_event(kind: EventKind, path: &str, detail: &str) {
+    DiskCache::instance().record_event(kind, path, detail).ok();
+}
+
+/// 主扫描函数 - 优化版
+/// 支持可选的渐进式流式传输：通过 events 分批发送扫描结果
+pub async fn scan_directory(
+    path: &str,
+    force_refresh: bool,
+    perf_monitor: Arc<PerformanceMonitor>,
+    events: Option<Events>,
+) -> Result<ScanResult, anyhow::Error> {
+    scan_directory_with_options(path, force_refresh, &ScanOptions::default(), perf_monitor, events)
+        .await
+}
+
+/// 带扫描选项的主扫描函数
+pub async fn scan_directory_with_options(
+    path: &str,
+    force_refresh: bool,
+    options: &ScanOptions,
+    perf_monitor: Arc<PerformanceMonitor>,
+    events: Option<Events>,
+) -> Result<ScanResult, anyhow::Error> {
+    let _scan_id = perf_monitor.start_scan(path);
+    let start_time = std::time::Instant::now();
+    log_event(
+        EventKind::ScanStart,
+        path,
+        if force_refresh { "force_refresh" } else { "" },
+    );
+
+    if path.trim().is_empty() {
+        perf_monitor.add_error("路径不能为空".to_string());
+        perf_monitor.end_scan();
+        log_event(EventKind::Error, path, "路径不能为空");
+        return Err(anyhow::anyhow!("路径不能为空"));
+    }
+
+    // 最近无法访问的路径（如断开的网络驱动器）直接返回上次的错误，强制刷新时重试。
+    // 失败记录按 cache_key 归一，`D:\Share\` 与 `d:/share` 视为同一路径
+    let failure_key = crate::fs::cache_key(path);
+    if force_refresh {
+        crate::scan_failures::clear(&failure_key);
+    } else if let Some(mut failure) = crate::scan_failures::recent(&failure_key) {
+        failure.path = path.to_string();
+        perf_monitor.end_scan();
+        log_event(EventKind::Error, path, &failure.to_string());
+        return Err(failure.into());
+    }
+
+    // 遍历使用扩展长度形式，超过 MAX_PATH 的目录也能打开
+    let path_buf = crate::fs::to_extended_length(path);
+
+    let metadata = match fs::metadata(&path_buf).await {
+        Ok(m) => m,
+        Err(e) => {
+            perf_monitor.add_error(format!("无法访问路径: {}", e));
+            perf_monitor.end_scan();
+            log_event(EventKind::Error, path, &format!("无法访问路径: {}", e));
+            crate::scan_failures::record(&failure_key, &format!("无法访问路径: {}", e));
+            return Err(anyhow::anyhow!("无法访问路径: {}", e));
+        }
+    };
+
+    if !metadata.is_dir() {
+        perf_monitor.add_error("不是目录".to_string());
+        perf_monitor.end_scan();
+        log_event(EventKind::Error, path, "不是目录");
+        return Err(anyhow::anyhow!("不是目录"));
+    }
+
+    let canonical_path = match fs::canonicalize(&path_buf).await {
+        Ok(p) => p,
+        Err(e) => {
+            perf_monitor.add_error(format!("路径规范化失败: {}", e));
+            perf_monitor.end_scan();
+            log_event(EventKind::Error, path, &format!("路径规范化失败: {}", e));
+            crate::scan_failures::record(&failure_key, &format!("路径规范化失败: {}", e));
+            return Err(anyhow::anyhow!("路径规范化失败: {}", e));
+        }
+    };
+
+    let root_dir = normalize_path_separator(canonical_path.as_os_str());
+    // 启用排除预设 / 包含模式的结果与完整结果分开缓存
+    let exclusions = crate::exclusions::Exclusions::new(&options.exclude_presets);
+    let includes = IncludeFilter::new(&options.include_globs);
+    let cache_key = includes.cache_key(exclusions.cache_key(&crate::fs::cache_key(&root_dir)));
+    // never_cache 目录不命中也不沿用缓存，总是完整遍历（结果不写入磁盘缓存）
+    let cache_policy = crate::settings::Settings::current().cache;
+    let force_refresh = force_refresh || cache_policy.never_caches(&cache_key);
+
+    let mtime = match metadata.modified() {
+        Ok(m) => m,
+        Err(_) => std::time::SystemTime::UNIX_EPOCH,
+    };
+    let mtime_datetime: chrono::DateTime<chrono::Local> = mtime.into();
+    let mtime_timestamp = mtime_datetime.timestamp();
+
+    // 1. 检查内存缓存
+    if !force_refresh {
+        let cache_check_start = std::time::Instant::now();
+        if let Some(cached) = SCAN_CACHE.get(&cache_key) {
+            // 如果缓存来自目录遍历，但当前进程是管理员且 MFT 可用，
+            // 则放弃缓存并重新扫描，以升级到 MFT 快速路径。
+            let can_upgrade_to_mft = !cached.result.mft_available
+                && cfg!(target_os = "windows")
+                && crate::fs::is_admin()
+                && crate::fs::check_mft_available(&root_dir);
+
+            let fresh = !cached.stale
+                && cached.dir_mtime >= mtime_datetime
+                && !cache_policy.is_expired(cached.dir_mtime.timestamp())
+                && cached.digest.find_changed(&root_dir, cache_policy.validation_samples).is_none();
+            if fresh && !can_upgrade_to_mft {
+                let cache_read_time = cache_check_start.elapsed().as_millis() as u64;
+                perf_monitor.record_cache_hit(cache_read_time);
+
+                let mut result = ScanResult::from(&cached.result);
+                result.path = CompactString::from(root_dir.as_str());
+                result.scan_time = 0.0;
+                result.perf_metrics = Some(ScanPerfMetrics {
+                    io_phase_ms: 0,
+                    compute_phase_ms: 0,
+                    serialize_phase_ms: 0,
+                    cache_read_time_ms: cache_read_time,
+                    files_scanned: result.items.len(),
+                    dirs_scanned: result.items.iter().filter(|i| i.is_dir).count(),
+                    io_throughput_mbps: 0.0,
+                    memory_peak_mb: 0.0,
+                    threads_used: 0,
+                    cache_hit: true,
+                    cache_source: Some("memory".to_string()),
+                    drive_type: None,
+                });
+                result.cache_usage = CacheUsage::hit(CacheSource::Memory, result.items.len());
+                apply_result_options(&mut result, options);
+
+                perf_monitor.end_scan();
+                log_event(EventKind::CacheHit, &root_dir, "memory");
+                return Ok(result);
+            } else if can_upgrade_to_mft {
+                eprintln!(
+                    "[Scan] 管理员+MFT 可用，放弃旧缓存并重新扫描以启用 MFT: {}",
+                    root_dir
+                );
+            }
+        }
+
+        // 2. 检查磁盘缓存
+        let disk_cache = DiskCache::instance();
+        if let Some(cached_result) = disk_cache.get(&cache_key, mtime_timestamp) {
+            let can_upgrade_to_mft = !cached_result.mft_available
+                && cfg!(target_os = "windows")
+                && crate::fs::is_admin()
+                && crate::fs::check_mft_available(&root_dir);
+
+            if !can_upgrade_to_mft {
+                let cache_read_time = cache_check_start.elapsed().as_millis() as u64;
+                perf_monitor.record_cache_hit(cache_read_time);
+
+                // 同时写入内存缓存
+                SCAN_CACHE.insert(cache_key.clone(), cached_result.clone());
+
+                let mut result = cached_result;
+                result.path = CompactString::from(root_dir.as_str());
+                result.scan_time = 0.0;
+                result.perf_metrics = Some(ScanPerfMetrics {
+                    io_phase_ms: 0,
+                    compute_phase_ms: 0,
+                    serialize_phase_ms: 0,
+                    cache_read_time_ms: cache_read_time,
+                    files_scanned: result.items.len(),
+                    dirs_scanned: result.items.iter().filter(|i| i.is_dir).count(),
+                    io_throughput_mbps: 0.0,
+                    memory_peak_mb: 0.0,
+                    threads_used: 0,
+                    cache_hit: true,
+                    cache_source: Some("disk".to_string()),
+                    drive_type: None,
+                });
+                result.cache_usage = CacheUsage::hit(CacheSource::Disk, result.items.len());
+                apply_result_options(&mut result, options);
+
+                perf_monitor.end_scan();
+                log_event(EventKind::CacheHit, &root_dir, "disk");
+                return Ok(result);
+            } else {
+                eprintln!(
+                    "[Scan] 管理员+MFT 可用，放弃磁盘缓存并重新扫描以启用 MFT: {}",
+                    root_dir
+                );
+            }
+        }
+
+        log_event(EventKind::CacheMiss, &root_dir, "");
+    }
+
+    // 完整遍历时丢弃的旧结果条目数（内存缓存中仍有过期结果时）
+    let stale_entries = SCAN_CACHE.get(&cache_key).map_or(0, |cached| cached.result.items.len());
+    // 子目录的缓存是完整结果，启用排除预设或包含模式时不沿用；
+    // 下方按前缀失效缓存会一并移除子目录的缓存，须先取出
+    let reuse_subtrees = !force_refresh && exclusions.is_empty() && includes.is_empty();
+    let memory_subtrees = if reuse_subtrees { SCAN_CACHE.child_entries(&cache_key) } else { Vec::new() };
+    SCAN_CACHE.invalidate(&cache_key);
+
+    // ── P2 优化：USN Journal 增量更新 ──
+    // 在失效缓存之前，先尝试用 USN Journal 增量更新过期的缓存数据
+    // 这样即使 mtime 不匹配，也能秒级刷新（非 Windows 平台恒为 None）；
+    // 变更记录不区分排除的目录与包含模式，启用排除预设或包含模式时直接完整遍历；
+    // 从其他机器导入的结果与本机目录无关，同样完整遍历
+    if !force_refresh
+        && exclusions.is_empty()
+        && includes.is_empty()
+        && DiskCache::instance().imported_from(&cache_key).is_none()
+    {
+        if let Some(mut updated_result) = try_usn_incremental_update(
+            &root_dir,
+            &cache_key,
+            &canonical_path,
+            mtime_timestamp,
+            &perf_monitor,
+        ) {
+            apply_result_options(&mut updated_result, options);
+            perf_monitor.end_scan();
+            log_event(EventKind::CacheHit, &root_dir, "usn");
+            return Ok(updated_result);
+        }
+    }
+
+    // USN 增量失败，失效磁盘缓存并执行全量扫描（仍然新鲜的子目录缓存并入结果，不再遍历）
+    let reused = if reuse_subtrees {
+        find_reusable_subtrees(&cache_key, memory_subtrees)
+    } else {
+        ReusedSubtrees::default()
+    };
+    DiskCache::instance().invalidate(&cache_key).ok();
+
+    // 遍历交给扫描后端：默认先尝试 MFT 直读（Everything 式快速路径），不可用时回退到目录遍历
+    let deadline = options
+        .time_budget_ms
+        .map(|ms| start_time + std::time::Duration::from_millis(ms));
+    let mut profile = ScanProfile::resolve(&root_dir, options.network_mode);
+    if profile.is_network() {
+        eprintln!("[Scan] 网络模式: {} 线程, 批次 {}: {}", profile.threads, profile.stream_batch, root_dir);
+    } else if profile.drive() == crate::fs::DriveType::Hdd {
+        eprintln!("[Scan] 机械硬盘: {} 线程, 深度优先: {}", profile.threads, root_dir);
+    }
+    let drive_type = profile.drive();
+    profile = profile.with_settings(&crate::settings::Settings::current().scan);
+    if options.background {
+        profile = profile.background();
+    }
+    profile = profile
+        .with_exclusions(exclusions)
+        .with_includes(includes.clone())
+        .with_reused_subtrees(reused);
+
+    let request = crate::backend::ScanRequest {
+        canonical_path,
+        root_dir: root_dir.clone(),
+        perf_monitor: Arc::clone(&perf_monitor),
+        events,
+        deadline,
+        profile,
+    };
+    let backend_kind = options.backend;
+    let joined = tokio::task::spawn_blocking(move || crate::backend::run(backend_kind, &request)).await;
+    let (backend_name, output) = match joined.map_err(anyhow::Error::from).and_then(|r| r) {
+        Ok(done) => done,
+        Err(e) => {
+            perf_monitor.add_error(format!("扫描失败: {}", e));
+            perf_monitor.end_scan();
+            log_event(EventKind::Error, &root_dir, &format!("扫描失败: {}", e));
+            return Err(e);
+        }
+    };
+
+    let scan_time = start_time.elapsed().as_secs_f64();
+    let unscanned = output.unscanned;
+
+    let mut result = ScanResult {
+        items: output.items,
+        total_size: output.total_size,
+        total_size_formatted: format_size(output.total_size),
+        scan_time,
+        path: CompactString::from(root_dir.as_str()),
+        mft_available: output.mft_available,
+        timing: Some(output.timing.clone()),
+        perf_metrics: Some(ScanPerfMetrics {
+            io_phase_ms: (output.timing.scan_phase * 1000.0) as u64,
+            compute_phase_ms: (output.timing.compute_phase * 1000.0) as u64,
+            serialize_phase_ms: (output.timing.format_phase * 1000.0) as u64,
+            cache_read_time_ms: 0,
+            files_scanned: output.file_count,
+            dirs_scanned: output.dir_count,
+            io_throughput_mbps: output.throughput_mbps,
+            memory_peak_mb: output.memory_peak_mb,
+            threads_used: output.threads_used,
+            cache_hit: false,
+            cache_source: None,
+            drive_type: Some(drive_type),
+        }),
+        extension_stats: None,
+        category_stats: Vec::new(),
+        owners: None,
+        unscanned: None,
+        unreadable_bytes: output.errors.iter().map(|e| e.estimated_bytes).sum(),
+        errors: output.errors,
+        cloud_only_bytes: output.cloud_only_bytes,
+        cloud_local_bytes: output.cloud_local_bytes,
+        sparse_savings_bytes: sparse_savings(&output.sparse_files),
+        sparse_files: output.sparse_files,
+        cache_usage: CacheUsage {
+            source: CacheSource::Fresh,
+            reused_entries: output.reused_entries,
+            invalidated_entries: stale_entries,
+        },
+    };
+
+    let unscanned_note =
+        if unscanned.is_empty() { String::new() } else { format!("，超出时间预算，{} 个目录未遍历", unscanned.len()) };
+    if unscanned.is_empty() {
+        // 写入两级缓存
+        SCAN_CACHE.insert(cache_key.clone(), result.clone());
+        DiskCache::instance().insert_async(&cache_key, &result, mtime_timestamp);
+        // 大小历史只记录完整结果，排除部分目录或只含匹配文件的总大小不进入趋势
+        if exclusions.is_empty() && includes.is_empty() {
+            record_size_history(&root_dir, &result);
+        }
+    } else {
+        // 超出时间预算的部分结果不写缓存 / 历史，下次扫描仍完整遍历
+        result.unscanned = Some(unscanned);
+    }
+    apply_result_options(&mut result, options);
+
+    perf_monitor.end_scan();
+    log_event(
+        EventKind::ScanFinish,
+        &root_dir,
+        &format!(
+            "{} 项, {}, {:.2}s, {}{}",
+            result.items.len(),
+            result.total_size_formatted,
+            scan_time,
+            backend_name,
+            unscanned_note
+        ),
+    );
+    Ok(result)
+}
+
+/// 扫描后端一次遍历的产出，由 scan_directory 组装为 ScanResult 并写入缓存
+pub struct ScanOutput {
+    /// 已聚合目录大小、按大小降序排列的条目
+    pub items: Vec<Item>,
+    pub total_size: i64,
+    pub timing: TimingInfo,
+    pub file_count: usize,
+    pub dir_count: usize,
+    pub throughput_mbps: f64,
+    pub memory_peak_mb: f64,
+    pub threads_used: usize,
+    pub mft_available: bool,
+    /// 因超出时间预算而未遍历的目录
+    pub unscanned: Vec<CompactString>,
+    /// 无法读取的目录
+    pub errors: Vec<ScanError>,
+    /// 仅在线的云占位符逻辑大小之和
+    pub cloud_only_bytes: i64,
+    /// 已下载到本地的云同步文件大小之和
+    pub cloud_local_bytes: i64,
+    /// 稀疏文件（按节省字节数降序）
+    pub sparse_files: Vec<SparseEntry>,
+    /// 从缓存子树直接并入、未重新遍历的条目数
+    pub reused_entries: usize,
+}
+
+/// 从绝对路径中提取盘符和 MFT volume-relative 前缀。
+/// MFT 返回的路径不带盘符（如 `Users/xxx/Documents/file.txt`），而 canonical path
+/// 是完整路径（如 `C:/Users/xxx` 或 `//?/C:/Users/xxx`）。本函数返回盘符与
+/// volume-relative 前缀，例如 `C:/Users/xxx` -> `('C', "users/xxx/")`，`C:/` -> `('C', "")`。
+fn drive_and_vol_prefix(abs_path: &str) -> Option<(char, String)> {
+    let normalized = crate::fs::display_path(abs_path);
+    let trimmed = normalized.as_str();
+
+    if trimmed.len() >= 2 && trimmed.as_bytes().get(1) == Some(&b':') {
+        let drive = trimmed.as_bytes()[0] as char;
+        let rest = trimmed[2..].trim_start_matches('/');
+        let prefix = if rest.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", rest.to_lowercase())
+        };
+        Some((drive, prefix))
+    } else {
+        None
+    }
+}
+
+/// 把 MFT 返回的 volume-relative 路径转换为绝对路径。
+/// 如果路径已以盘符开头，则直接规范化；否则补全盘符前缀。
+fn mft_path_to_abs(drive: char, vol_relative_path: &str) -> CompactString {
+    let p = vol_relative_path.replace('\\', "/");
+    let vol_prefix = format!("{}:/", drive);
+    let vol_alt = format!("{}:", drive);
+    if p.starts_with(&vol_prefix) || p.starts_with(&vol_alt) {
+        CompactString::from(p)
+    } else if p.is_empty() {
+        CompactString::from(vol_prefix)
+    } else {
+        CompactString::from(format!("{}{}", vol_prefix, p))
+    }
+}
+
+/// MFT 文件记录 → Item（size_formatted 留空，由调用方按需格式化）
+fn mft_file_to_item(drive: char, volume_id: u64, f: crate::fs::MftFileInfo) -> Item {
+    let path = mft_path_to_abs(drive, &f.path);
+    let kind = crate::fs::classify_windows(f.attributes, f.reparse_tag, f.is_dir, || {
+        crate::fs::is_volume_mount_point(Path::new(path.as_str()))
+    });
+    // 云占位符的内容不在本地，按 0 字节计
+    let size = if kind == ItemKind::CloudPlaceholder { 0 } else { f.size as i64 };
+    Item {
+        id: stable_item_id(volume_id, &path),
+        path,
+        category: item_category(&f.name, kind),
+        percent_of_parent: 0.0,
+        percent_of_total: 0.0,
+        name: CompactString::from(f.name),
+        size,
+        size_formatted: CompactString::new(),
+        is_dir: f.is_dir,
+        modified: f.modified,
+        created: f.created,
+        kind,
+        // 子树计数在聚合阶段填入
+        file_count: 0,
+        dir_count: 0,
+    }
+}
+
+/// 轻量扫描：只做 MFT 读取 + 文件名提取（不聚合目录大小、不排序、不格式化）。
+/// 供全局搜索索引构建使用。与 try_mft_scan_path 使用相同的 canonicalize 预处理，
+/// 但跳过聚合/format/sort，失败返回 None，调用者应回退到完整 scan_directory。
+pub fn scan_lite(path: &str) -> Option<Vec<Item>> {
+    if is_mft_disabled() {
+        return None;
+    }
+
+    let canonical = std::fs::canonicalize(crate::fs::to_extended_length(path)).ok()?;
+    let root_path_str = normalize_path_separator(canonical.as_os_str());
+    let mft_result = crate::fs::try_mft_scan(&root_path_str)?;
+    let (drive, vol_prefix) = drive_and_vol_prefix(&root_path_str)?;
+    let volume_id = crate::fs::volume_id(&root_path_str);
+
+    let items: Vec<Item> = mft_result
+        .files
+        .into_iter()
+        .filter(|f| {
+            let p = f.path.to_lowercase();
+            vol_prefix.is_empty() || p.starts_with(&vol_prefix)
+        })
+        .map(|f| mft_file_to_item(drive, volume_id, f))
+        .collect();
+
+    Some(items)
+}
+
+/// 尝试使用 MFT 直接读取扫描（Everything 式快速路径）
+/// 仅在 Windows + 管理员权限 + NTFS 卷上生效
+/// 返回 None 表示不可用，调用者应回退到目录遍历
+pub(crate) fn try_mft_scan_path(
+    canonical_path: &Path,
+    _root_dir: &str,
+    perf_monitor: &Arc<PerformanceMonitor>,
+    events: Option<&Events>,
+    exclusions: Exclusions,
+    includes: &IncludeFilter,
+) -> Option<ScanOutput> {
+    if is_mft_disabled() {
+        return None;
+    }
+
+    let root_path_str = canonical_path.to_string_lossy().to_string();
+    let (drive, vol_prefix) = drive_and_vol_prefix(&root_path_str)?;
+    let volume_id = crate::fs::volume_id(&root_path_str);
+
+    // 尝试 MFT 全卷扫描
+    let mft_result = crate::fs::try_mft_scan(&root_path_str)?;
+
+    let total_start = std::time::Instant::now();
+
+    perf_monitor.start_io_phase();
+    let scan_start = std::time::Instant::now();
+
+    // 过滤：只保留目标目录下的文件
+    // MFT 返回的路径是 volume-relative（不带盘符），需用 volume-relative 前缀匹配
+    let normalized_root = vol_prefix;
+
+    // 排除预设命中的条目（小写 volume-relative 路径），其子树一并过滤
+    let excluded: Vec<String> = if exclusions.is_empty() {
+        Vec::new()
+    } else {
+        mft_result
+            .files
+            .iter()
+            .filter(|f| exclusions.excludes(&mft_path_to_abs(drive, &f.path), &f.name, f.is_dir))
+            .map(|f| f.path.to_lowercase())
+            .collect()
+    };
+
+    // 云同步文件按 $STANDARD_INFORMATION 属性与重解析标记统计，不读取文件数据；
+    // 稀疏文件只对带稀疏属性的条目查询分配大小
+    let cloud = crate::fs::CloudTally::default();
+    let mut sparse_files: Vec<SparseEntry> = Vec::new();
+    let mut items: Vec<Item> = mft_result
+        .files
+        .into_iter()
+        .filter(|f| {
+            let p = f.path.to_lowercase();
+            (normalized_root.is_empty() || p.starts_with(&normalized_root))
+                && !excluded.iter().any(|root| {
+                    p.strip_prefix(root.as_str())
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with(['/', '\\']))
+                })
+                && (f.is_dir || includes.is_empty() || includes.includes_file(&mft_path_to_abs(drive, &f.path), &f.name))
+        })
+        .map(|f| {
+            cloud.record(crate::fs::cloud_file_windows(f.attributes, f.reparse_tag, f.is_dir, f.size), f.size);
+            let (attributes, is_dir, logical) = (f.attributes, f.is_dir, f.size);
+            let mut item = mft_file_to_item(drive, volume_id, f); // size_formatted 下面统一格式化
+            if item.kind != ItemKind::CloudPlaceholder {
+                if let Some(sparse) = crate::fs::sparse_file_windows(Path::new(item.path.as_str()), attributes, is_dir, logical) {
+                    item.size = sparse.allocated as i64;
+                    sparse_files.push(SparseEntry {
+                        path: item.path.clone(),
+                        logical_size: sparse.logical as i64,
+                        allocated_size: sparse.allocated as i64,
+                    });
+                }
+            }
+            item
+        })
+        .collect();
+    let (cloud_only_bytes, cloud_local_bytes) = cloud.totals();
+    sparse_files.sort_unstable_by_key(|e| std::cmp::Reverse(e.logical_size - e.allocated_size));
+
+    let file_count = items.iter().filter(|i| !i.is_dir).count();
+    let dir_count = items.iter().filter(|i| i.is_dir).count();
+
+    let scan_phase = scan_start.elapsed();
+    perf_monitor.end_io_phase();
+
+    // 计算目录大小（聚合子文件大小到父目录）
+    perf_monitor.start_compute_phase();
+    let compute_start = std::time::Instant::now();
+
+    use std::collections::HashMap;
+
+    // 目录大小聚合：path → 下标索引 + 按下标累加，避免每层祖先都分配 CompactString
+    let dir_index: HashMap<&str, usize> = items
+        .iter()
+        .enumerate()
+        .filter(|(_, it)| it.is_dir)
+        .map(|(i, it)| (it.path.as_str(), i))
+        .collect();
+
+    let mut dir_sizes: Vec<i64> = vec![0; items.len()];
+    // 同一遍中统计每个目录子树的文件数 / 子目录数
+    let mut child_counts: Vec<(u64, u64)> = vec![(0, 0); items.len()];
+
+    for item in items.iter() {
+        let file_path = item.path.as_str();
+        let mut pos = 0;
+        while let Some(slash_pos) = file_path[pos..].find('/') {
+            let abs_pos = pos + slash_pos;
+            let parent = &file_path[..abs_pos];
+            if let Some(&idx) = dir_index.get(parent) {
+                if item.is_dir {
+                    child_counts[idx].1 += 1;
+                } else {
+                    child_counts[idx].0 += 1;
+                    dir_sizes[idx] += item.size.max(0);
+                }
+            }
+            pos = abs_pos + 1;
+        }
+    }
+
+    // 释放对 items 的借用，以便下方 iter_mut 可变借用
+    drop(dir_index);
+
+    let compute_phase = compute_start.elapsed();
+
+    // 更新目录条目的 size 和 size_formatted
+    for (i, item) in items.iter_mut().enumerate() {
+        if item.is_dir {
+            item.size = dir_sizes[i];
+            (item.file_count, item.dir_count) = child_counts[i];
+        }
+        item.size_formatted = format_size(item.size);
+    }
+
+    let actual_total_size: i64 = items
+        .iter()
+        .filter(|i| !i.is_dir)
+        .map(|i| i.size)
+        .sum();
+    fill_percentages(&mut items, actual_total_size);
+
+    // 按大小降序排序
+    items.sort_unstable_by_key(|i| std::cmp::Reverse(i.size));
+
+    let format_phase = compute_start.elapsed(); // approximate
+    let total = total_start.elapsed();
+    perf_monitor.end_compute_phase();
+
+    let throughput_mbps = if scan_phase.as_secs_f64() > 0.0 {
+        (actual_total_size as f64 / 1024.0 / 1024.0) / scan_phase.as_secs_f64()
+    } else {
+        0.0
+    };
+
+    let memory_peak_mb = (items.capacity() * std::mem::size_of::<Item>()) as f64 / 1024.0 / 1024.0;
+
+    perf_monitor.update_memory_stats(memory_peak_mb, memory_peak_mb);
+    perf_monitor.update_io_stats(file_count, dir_count, actual_total_size as u64, file_count + dir_count);
+
+    // 流式传输（与目录遍历保持一致的行为）
+    if let Some(sink) = events {
+        for chunk in items.chunks(500) {
+            sink.emit("scan-batch", chunk.to_vec());
+        }
+    }
+
+    eprintln!(
+        "[MFT] 扫描完成: {} 文件, {} 目录, {:.2}s (filtered from {} total)",
+        file_count,
+        dir_count,
+        total.as_secs_f64(),
+        mft_result.file_count + mft_result.dir_count
+    );
+
+    // 保存 USN 检查点，供下次增量更新使用
+    save_usn_checkpoint(&root_path_str);
+
+    Some(ScanOutput {
+        items,
+        total_size: actual_total_size,
+        timing: TimingInfo {
+            scan_phase: scan_phase.as_secs_f64(),
+            compute_phase: compute_phase.as_secs_f64(),
+            format_phase: format_phase.as_secs_f64(),
+            total: total.as_secs_f64(),
+        },
+        file_count,
+        dir_count,
+        throughput_mbps,
+        memory_peak_mb,
+        threads_used: 1, // MFT 扫描是单线程顺序读取
+        mft_available: true,
+        unscanned: Vec::new(),
+        errors: Vec::new(),
+        cloud_only_bytes,
+        cloud_local_bytes,
+        sparse_files,
+        reused_entries: 0,
+    })
+}
+
+// ─── USN Journal 增量更新 ───────────────────────────────────
+
+/// 保存 USN 检查点
+#[cfg(target_os = "windows")]
+fn save_usn_checkpoint(path: &str) {
+    if let Some(drive) = crate::fs::extract_drive_letter(path) {
+        if let Some(checkpoint) = crate::fs::get_checkpoint(drive) {
+            let checkpoint_path = usn_checkpoint_path(drive);
+            if let Some(parent) = checkpoint_path.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            if let Ok(json) = serde_json::to_string(&checkpoint) {
+                let _ = std::fs::write(&checkpoint_path, json);
+                eprintln!(
+                    "[USN] 检查点已保存: {}.{} (USN={})",
+                    drive,
+                    checkpoint.journal_id,
+                    checkpoint.max_usn
+                );
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn save_usn_checkpoint(_path: &str) {}
+
+/// USN 检查点文件路径
+#[cfg(target_os = "windows")]
+fn usn_checkpoint_path(drive: char) -> std::path::PathBuf {
+    let home = std::env::var("USERPROFILE")
+        .or_else(|_| std::env::var("HOME"))
+        .unwrap_or_default();
+    let mut p = std::path::PathBuf::from(home);
+    p.push(".flashdir");
+    p.push(format!("usn_checkpoint_{}.json", drive));
+    p
+}
+
+/// 尝试使用 USN Journal 增量更新缓存
+/// 成功返回更新后的 ScanResult，失败返回 None（回退到全量扫描）。
+/// root_dir 用于路径匹配与结果展示，缓存读写用 cache_key
+#[cfg(target_os = "windows")]
+fn try_usn_incremental_update(
+    root_dir: &str,
+    cache_key: &str,
+    _canonical_path: &std::path::Path,
+    _mtime_timestamp: i64,
+    _perf_monitor: &Arc<PerformanceMonitor>,
+) -> Option<ScanResult> {
+    let drive = crate::fs::extract_drive_letter(root_dir)?;
+    let volume_id = crate::fs::volume_id(root_dir);
+
+    // 加载之前的检查点
+    let cp_path = usn_checkpoint_path(drive);
+    let checkpoint: crate::fs::UsnCheckpoint = {
+        let data = std::fs::read_to_string(&cp_path).ok()?;
+        serde_json::from_str(&data).ok()?
+    };
+
+    // 检查点过期检查（超过 1 小时的检查点不使用增量更新）
+    let now = chrono::Utc::now().timestamp();
+    if now - checkpoint.created_at > 3600 {
+        return None;
+    }
+
+    // 读取增量变更
+    let (changes, new_checkpoint) =
+        crate::fs::read_incremental_changes(drive, &checkpoint).ok()?;
+
+    // 如果变更太多（>5000），不如全量扫描
+    if changes.len() > 5000 {
+        eprintln!(
+            "[USN] 变更过多 ({} 条)，回退到全量扫描",
+            changes.len()
+        );
+        return None;
+    }
+
+    if changes.is_empty() {
+        eprintln!("[USN] 无变更，直接返回缓存结果");
+        // 更新检查点时间戳
+        let updated_cp = crate::fs::UsnCheckpoint {
+            created_at: now,
+            ..new_checkpoint
+        };
+        if let Ok(json) = serde_json::to_string(&updated_cp) {
+            let _ = std::fs::write(&cp_path, json);
+        }
+        // 返回磁盘缓存（无需修改，mtime 已通过 USN 验证为最新）
+        if let Some(mut cached) = DiskCache::instance().get_stale(cache_key) {
+            // 重新写入内存缓存
+            SCAN_CACHE.insert(cache_key.to_string(), cached.clone());
+            let _ = DiskCache::instance().touch(cache_key, new_checkpoint.created_at);
+            cached.cache_usage = CacheUsage::hit(CacheSource::Disk, cached.items.len());
+            return Some(cached);
+        }
+        return None;
+    }
+
+    let create_count = changes.iter().filter(|c| c.reason & crate::fs::USN_REASON_FILE_CREATE != 0).count();
+    let delete_count = changes.iter().filter(|c| c.reason & crate::fs::USN_REASON_FILE_DELETE != 0).count();
+    let rename_count = changes.iter().filter(|c| c.reason & (crate::fs::USN_REASON_RENAME_OLD_NAME | crate::fs::USN_REASON_RENAME_NEW_NAME) != 0).count();
+    let data_count = changes.iter().filter(|c| c.reason & (crate::fs::USN_REASON_DATA_OVERWRITE | crate::fs::USN_REASON_DATA_EXTEND | crate::fs::USN_REASON_DATA_TRUNCATION) != 0).count();
+
+    eprintln!(
+        "[USN] 增量更新: {} 条变更 (CREATE={}, DELETE={}, RENAME={}, DATA={})",
+        changes.len(), create_count, delete_count, rename_count, data_count,
+    );
+
+    // ── 加载缓存的扫描结果 ──
+    // 使用 get_stale 获取过期缓存数据（忽略 mtime 检查），因为 USN 增量会将其更新到最新
+    // 无法读取的目录沿用上次完整扫描的记录（USN 不涉及目录读取权限）；
+    // 云文件与稀疏文件统计同样沿用（变更记录不含属性与分配大小，以下次完整扫描为准）
+    let (cached_items, cached_errors, cached_unreadable_bytes, cached_cloud, cached_sparse) = {
+        if let Some(cached) = DiskCache::instance().get_stale(cache_key) {
+            (
+                cached.items,
+                cached.errors,
+                cached.unreadable_bytes,
+                (cached.cloud_only_bytes, cached.cloud_local_bytes),
+                cached.sparse_files,
+            )
+        } else {
+            eprintln!("[USN] 磁盘缓存未命中，无法应用增量更新");
+            return None;
+        }
+    };
+
+    if cached_items.is_empty() {
+        eprintln!("[USN] 缓存为空，无法应用增量更新");
+        return None;
+    }
+
+    eprintln!(
+        "[USN] 加载缓存: {} 个项目，开始应用 USN 变更",
+        cached_items.len()
+    );
+
+    // ── 打开 MFT 扫描器用于 FRN → 路径解析 ──
+    let scanner = match crate::fs::MftScanner::open(drive) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("[USN] 无法打开 MFT 扫描器: {}", e);
+            return None;
+        }
+    };
+
+    // ── 路径格式检测（必须在移动 cached_items 之前） ──
+    // MFT 扫描产生 volume-relative 路径 (如 "Users/xxx/Documents/file.txt")
+    // v4 遍历产生 root-relative 路径 (如 "Documents/file.txt")
+    // USN FRN 解析总是产生 volume-relative 路径
+    // 需要根据缓存实际格式决定是否截去前缀
+    let volume_relative_prefix = {
+        // root_dir 格式: "C:/Users/xxx" (forward slashes)
+        let without_drive = if root_dir.len() >= 2 && root_dir.as_bytes().get(1) == Some(&b':') {
+            &root_dir[2..] // 去掉 "C:"
+        } else {
+            root_dir
+        };
+        let trimmed = without_drive.trim_start_matches('/');
+        if trimmed.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", trimmed)
+        }
+    };
+
+    let cached_is_mft_format = {
+        let first_cached = cached_items.first();
+        first_cached.map_or(false, |item| {
+            !volume_relative_prefix.is_empty()
+                && item.path.starts_with(volume_relative_prefix.as_str())
+        })
+    };
+
+    eprintln!(
+        "[USN] 缓存路径格式: {}, 卷相对前缀: \"{}\"",
+        if cached_is_mft_format { "MFT (volume-relative)" } else { "v4 (root-relative)" },
+        volume_relative_prefix,
+    );
+
+    let normalize_to_cache_format = |vol_relative_path: &str| -> CompactString {
+        if cached_is_mft_format {
+            // MFT 格式：直接使用 volume-relative 路径
+            CompactString::from(vol_relative_path)
+        } else if !volume_relative_prefix.is_empty()
+            && vol_relative_path.starts_with(&volume_relative_prefix)
+        {
+            // v4 格式：截去卷相对前缀，得到 root-relative 路径
+            CompactString::from(&vol_relative_path[volume_relative_prefix.len()..])
+        } else {
+            // 后备：路径不在预期前缀下，直接使用
+            CompactString::from(vol_relative_path)
+        }
+    };
+
+    // ── 构建 HashMap 索引 (path → Item) ──
+    use std::collections::HashMap;
+
+    let cached_count = cached_items.len();
+    // 被删除、替换或改动的缓存条目（同一路径只计一次）
+    let mut invalidated: std::collections::HashSet<CompactString> = std::collections::HashSet::new();
+    let mut items_map: HashMap<CompactString, Item> = HashMap::with_capacity(cached_items.len() + changes.len());
+    for item in cached_items {
+        items_map.insert(item.path.clone(), item);
+    }
+
+    // ── 路径解析缓存 (parent_ref → volume_relative_path) ──
+    let mut parent_path_cache: HashMap<u64, Option<String>> = HashMap::new();
+
+    let resolve_parent_path = |parent_ref: u64,
+                                scanner: &crate::fs::MftScanner,
+                                cache: &mut HashMap<u64, Option<String>>|
+     -> Option<String> {
+        if let Some(cached) = cache.get(&parent_ref) {
+            return cached.clone();
+        }
+        let result = scanner.resolve_frn_path(parent_ref).ok().flatten();
+        cache.insert(parent_ref, result.clone());
+        result
+    };
+
+    // ── 应用 USN 变更 ──
+    // Phase 1: 处理删除和旧名称（先移除）
+    for change in &changes {
+        let reason = change.reason;
+
+        let is_delete = reason & crate::fs::USN_REASON_FILE_DELETE != 0;
+        let is_rename_old = reason & crate::fs::USN_REASON_RENAME_OLD_NAME != 0;
+
+        if !is_delete && !is_rename_old {
+            continue;
+        }
+
+        if let Some(parent_path) = resolve_parent_path(change.parent_ref, &scanner, &mut parent_path_cache) {
+            let vol_path = if parent_path.is_empty() {
+                change.name.clone()
+            } else {
+                format!("{}/{}", parent_path, change.name)
+            };
+            let cache_key = normalize_to_cache_format(&vol_path);
+            if items_map.remove(&cache_key).is_some() {
+                invalidated.insert(cache_key.clone());
+                let action = if is_delete { "DEL" } else { "RN_OLD" };
+                eprintln!("  [USN-{}] 移除: {}", action, cache_key);
+            }
+
+            // 同步到全局索引与 SQLite
+            let abs_path = crate::global_search::normalize_abs_path(drive, &vol_path);
+            crate::global_search::instance().remove_by_path(&abs_path);
+            let _ = DiskCache::instance().remove_global_index_by_path(&abs_path);
+        }
+    }
+
+    // Phase 2: 处理创建、重命名新名称、数据变更
+    for change in &changes {
+        let reason = change.reason;
+
+        let is_create = reason & crate::fs::USN_REASON_FILE_CREATE != 0;
+        let is_rename_new = reason & crate::fs::USN_REASON_RENAME_NEW_NAME != 0;
+        let is_data_change = reason & (crate::fs::USN_REASON_DATA_OVERWRITE
+            | crate::fs::USN_REASON_DATA_EXTEND
+            | crate::fs::USN_REASON_DATA_TRUNCATION)
+            != 0;
+
+        if !is_create && !is_rename_new && !is_data_change {
+            continue;
+        }
+
+        if let Some(parent_path) = resolve_parent_path(change.parent_ref, &scanner, &mut parent_path_cache) {
+            let vol_path = if parent_path.is_empty() {
+                change.name.clone()
+            } else {
+                format!("{}/{}", parent_path, change.name)
+            };
+            let cache_key = normalize_to_cache_format(&vol_path);
+            let abs_path = crate::global_search::normalize_abs_path(drive, &vol_path);
+            let mtime = crate::fs::filetime_to_unix(change.timestamp);
+
+            if is_create || is_rename_new {
+                // 读取 MFT 获取文件大小和目录标志
+                let (file_size, is_dir, modified, created) = match scanner.read_single_record(change.file_ref) {
+                    Ok(Some(record)) => (record.real_size as i64, record.is_dir, record.modified, record.created),
+                    _ => {
+                        // 回退：用 USN attributes 判断目录标志，时间取 USN 记录时间
+                        let is_dir_attr = (change.attributes & 0x10) != 0; // FILE_ATTRIBUTE_DIRECTORY
+                        (0i64, is_dir_attr, mtime, if is_create { mtime } else { 0 })
+                    }
+                };
+
+                // USN 记录不含重解析标记，只能识别云占位符
+                let kind = crate::fs::classify_windows(change.attributes, 0, is_dir, || false);
+                let item = Item {
+                    path: cache_key.clone(),
+                    name: CompactString::from(change.name.as_str()),
+                    size: file_size,
+                    size_formatted: format_size(file_size),
+                    is_dir,
+                    modified,
+                    created,
+                    id: stable_item_id(volume_id, &abs_path),
+                    kind,
+                    file_count: 0,
+                    dir_count: 0,
+                    category: item_category(&change.name, kind),
+                    percent_of_parent: 0.0,
+                    percent_of_total: 0.0,
+                };
+
+                if items_map.insert(cache_key.clone(), item).is_some() {
+                    invalidated.insert(cache_key.clone());
+                }
+                let action = if is_create { "CREATE" } else { "RN_NEW" };
+                eprintln!(
+                    "  [USN-{}] 添加: {} ({} bytes, dir={})",
+                    action, cache_key, file_size, is_dir
+                );
+
+                // 同步到全局索引与 SQLite
+                let name = change.name.clone();
+                let entry = crate::global_search::IndexEntry {
+                    path: abs_path.clone(),
+                    name: name.clone(),
+                    name_lower: name.to_lowercase(),
+                    size: file_size,
+                    is_dir,
+                    mtime,
+                };
+                crate::global_search::instance().upsert(entry.clone());
+                let _ = DiskCache::instance().upsert_global_index_entry(&entry);
+            } else if is_data_change {
+                // 更新文件大小（从 MFT 读取最新值）
+                if let Some(item) = items_map.get_mut(&cache_key) {
+                    item.modified = mtime;
+                    if let Ok(Some(record)) = scanner.read_single_record(change.file_ref) {
+                        if !item.is_dir {
+                            let new_size = record.real_size as i64;
+                            if new_size != item.size {
+                                invalidated.insert(cache_key.clone());
+                                eprintln!(
+                                    "  [USN-DATA] 更新大小: {} {} -> {} bytes",
+                                    cache_key, item.size, new_size
+                                );
+                                item.size = new_size;
+                                item.size_formatted = format_size(new_size);
+
+                                // 同步到全局索引与 SQLite
+                                let name = item.name.to_string();
+                                let entry = crate::global_search::IndexEntry {
+                                    path: abs_path.clone(),
+                                    name: name.clone(),
+                                    name_lower: name.to_lowercase(),
+                                    size: new_size,
+                                    is_dir: item.is_dir,
+                                    mtime,
+                                };
+                                crate::global_search::instance().upsert(entry.clone());
+                                let _ = DiskCache::instance().upsert_global_index_entry(&entry);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // 释放 MFT 扫描器
+    drop(scanner);
+    drop(parent_path_cache);
+
+    // ── 重建目录大小 ──
+    // 文件大小可能已更新，需要重新聚合计入目录
+    let mut new_items: Vec<Item> = items_map.into_values().collect();
+
+    // 重新计算目录大小：为每个目录累计其子文件的字节数
+    {
+        use std::collections::HashMap as StdHashMap;
+
+        let mut dir_sizes: StdHashMap<CompactString, i64> = StdHashMap::new();
+        // 目录 path → (子树文件数, 子树目录数)
+        let mut child_counts: StdHashMap<CompactString, (u64, u64)> = StdHashMap::new();
+
+        for item in &new_items {
+            let file_path = item.path.as_str();
+            // 沿路径向上，累加到每个祖先目录
+            let mut pos = 0;
+            while let Some(slash_pos) = file_path[pos..].find('/') {
+                let abs_pos = pos + slash_pos;
+                let parent = &file_path[..abs_pos];
+                let counts = child_counts.entry(CompactString::from(parent)).or_insert((0, 0));
+                if item.is_dir {
+                    counts.1 += 1;
+                } else {
+                    counts.0 += 1;
+                    if item.size > 0 {
+                        *dir_sizes
+                            .entry(CompactString::from(parent))
+                            .or_insert(0) += item.size;
+                    }
+                }
+                pos = abs_pos + 1;
+            }
+            // 也计入根
+            if !item.is_dir && item.size > 0 {
+                *dir_sizes.entry(CompactString::new()).or_insert(0) += item.size;
+            }
+        }
+
+        for item in &mut new_items {
+            if item.is_dir {
+                item.size = dir_sizes.get(&item.path).copied().unwrap_or(0);
+                item.size_formatted = format_size(item.size);
+                (item.file_count, item.dir_count) = child_counts.get(&item.path).copied().unwrap_or((0, 0));
+            }
+        }
+    }
+
+    let actual_total_size: i64 = new_items
+        .iter()
+        .filter(|i| !i.is_dir)
+        .map(|i| i.size)
+        .sum();
+    fill_percentages(&mut new_items, actual_total_size);
+
+    // 按大小降序排序
+    new_items.sort_unstable_by_key(|i| std::cmp::Reverse(i.size));
+
+    let new_file_count = new_items.iter().filter(|i| !i.is_dir).count();
+    let new_dir_count = new_items.iter().filter(|i| i.is_dir).count();
+
+    eprintln!(
+        "[USN] 增量更新完成: {} 文件, {} 目录, {} ({} 变更已应用)",
+        new_file_count,
+        new_dir_count,
+        format_size(actual_total_size),
+        changes.len(),
+    );
+
+    // ── 更新检查点 ──
+    let updated_cp = crate::fs::UsnCheckpoint {
+        created_at: chrono::Utc::now().timestamp(),
+        ..new_checkpoint
+    };
+    if let Ok(json) = serde_json::to_string(&updated_cp) {
+        let _ = std::fs::write(&cp_path, json);
+    }
+
+    // ── 写回缓存 ──
+    let result = ScanResult {
+        items: new_items,
+        total_size: actual_total_size,
+        total_size_formatted: format_size(actual_total_size),
+        scan_time: 0.0, // USN 增量更新视为即时
+        path: CompactString::from(root_dir),
+        mft_available: false, // USN 增量更新路径不直接依赖 MFT 直读能力标志
+        timing: Some(TimingInfo {
+            scan_phase: 0.0,
+            compute_phase: 0.0,
+            format_phase: 0.0,
+            total: 0.0,
+        }),
+        perf_metrics: Some(ScanPerfMetrics {
+            io_phase_ms: 0,
+            compute_phase_ms: 0,
+            serialize_phase_ms: 0,
+            cache_read_time_ms: 0,
+            files_scanned: 0,
+            dirs_scanned: 0,
+            io_throughput_mbps: 0.0,
+            memory_peak_mb: 0.0,
+            threads_used: 0,
+            cache_hit: true,
+            cache_source: Some("usn".to_string()),
+            drive_type: None,
+        }),
+        extension_stats: None,
+        category_stats: Vec::new(),
+        owners: None,
+        unscanned: None,
+        errors: cached_errors,
+        unreadable_bytes: cached_unreadable_bytes,
+        cloud_only_bytes: cached_cloud.0,
+        cloud_local_bytes: cached_cloud.1,
+        sparse_savings_bytes: sparse_savings(&cached_sparse),
+        sparse_files: cached_sparse,
+        cache_usage: CacheUsage {
+            source: CacheSource::Partial,
+            reused_entries: cached_count.saturating_sub(invalidated.len()),
+            invalidated_entries: invalidated.len(),
+        },
+    };
+
+    // 写入两级缓存
+    SCAN_CACHE.insert(cache_key.to_string(), result.clone());
+    DiskCache::instance().insert_async(cache_key, &result, new_checkpoint.created_at);
+    record_size_history(root_dir, &result);
+
+    Some(result)
+}
+
+/// 按当前设置整理两级缓存（修改设置后调用）：内存中移除过期的结果，磁盘缓存删除
+/// 过期、属于 never_cache 目录与超出大小上限的条目；返回磁盘缓存删除的条数
+pub fn apply_cache_policy() -> anyhow::Result<usize> {
+    let policy = crate::settings::Settings::current().cache;
+    let evicted = SCAN_CACHE.set_limits(policy.memory_max_entries, policy.memory_max_mb as usize);
+    if !evicted.is_empty() {
+        eprintln!("[Cache] 内存缓存上限调整为 {} 条 / {} MB，关闭 {} 个会话", policy.memory_max_entries, policy.memory_max_mb, evicted.len());
+    }
+    SCAN_CACHE.apply_policy(&policy);
+    DiskCache::instance().apply_policy(&policy)
+}
+
+/// 查找 root_key 下仍然新鲜的已缓存子目录（缓存时的 mtime 不早于子目录当前的 mtime）。
+/// memory 为事先从内存缓存取出的候选，其余候选从磁盘缓存读取；嵌套的候选只保留最外层
+fn find_reusable_subtrees(root_key: &str, memory: Vec<(String, CacheEntry)>) -> ReusedSubtrees {
+    let disk_cache = DiskCache::instance();
+    let policy = crate::settings::Settings::current().cache;
+    let mut candidates: Vec<(String, i64, Option<ReusedSubtree>)> = memory
+        .into_iter()
+        .filter(|(key, entry)| {
+            !entry.stale
+                && !policy.never_caches(key)
+                && !policy.is_expired(entry.dir_mtime.timestamp())
+                && entry.digest.find_changed(key, policy.validation_samples).is_none()
+        })
+        .map(|(key, entry)| (key, entry.dir_mtime.timestamp(), Some(ReusedSubtree::from(&entry.result))))
+        .collect();
+    for (key, dir_mtime) in disk_cache.child_entries(root_key).unwrap_or_default() {
+        if !candidates.iter().any(|(k, _, _)| *k == key) {
+            candidates.push((key, dir_mtime, None));
+        }
+    }
+    // 外层目录的 key 更短，先于其子目录被选中
+    candidates.sort_unstable_by_key(|(key, _, _)| key.len());
+
+    let mut chosen: HashMap<String, ReusedSubtree> = HashMap::new();
+    for (key, cached_mtime, subtree) in candidates {
+        if chosen.keys().any(|outer| crate::fs::is_child_cache_key(outer, &key)) {
+            continue;
+        }
+        let Some(current_mtime) = std::fs::metadata(crate::fs::to_extended_length(&key))
+            .and_then(|m| m.modified())
+            .ok()
+            .map(|t| chrono::DateTime::<chrono::Local>::from(t).timestamp())
+        else {
+            continue;
+        };
+        if cached_mtime < current_mtime {
+            continue;
+        }
+        let subtree = match subtree {
+            Some(subtree) => subtree,
+            None => match disk_cache.get(&key, current_mtime) {
+                Some(result) => ReusedSubtree::from(result),
+                None => continue,
+            },
+        };
+        chosen.insert(key, subtree);
+    }
+
+    if !chosen.is_empty() {
+        let entries: usize = chosen.values().map(|s| s.items.len()).sum();
+        eprintln!("[Scan] 沿用 {} 个子目录的缓存（{} 项）: {}", chosen.len(), entries, root_key);
+    }
+    ReusedSubtrees(Arc::new(chosen))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn try_usn_incremental_update(
+    _root_dir: &str,
+    _cache_key: &str,
+    _canonical_path: &std::path::Path,
+    _mtime_timestamp: i64,
+    _perf_monitor: &Arc<PerformanceMonitor>,
+) -> Option<ScanResult> {
+    None
+}
+
+/// 优化的扫描实现 v4
+/// 集成：性能监控、内存优化、Windows 原生 I/O、渐进式流式传输
+/// read_dir 为单层目录读取实现（平台原生遍历器或标准库实现）
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn scan_directory_optimized_v4(
+    root_path: &Path,
+    perf_monitor: &Arc<PerformanceMonitor>,
+    events: Option<Events>,
+    deadline: Option<std::time::Instant>,
+    profile: ScanProfile,
+    size_hints: &HashMap<CompactString, i64>,
+    progress: &crate::progress::ProgressTracker,
+    read_dir: crate::backend::ReadDirFn,
+) -> Result<ScanOutput, anyhow::Error> {
+    use rayon::prelude::*;
+
+    let total_start = std::time::Instant::now();
+    let volume_id = crate::fs::volume_id(&normalize_path_separator(root_path.as_os_str()));
+
+    let dir_queue = DirQueue::new();
+    let (item_sender, item_receiver): (Sender<ItemInternal>, Receiver<ItemInternal>) = unbounded();
+
+    dir_queue.push(root_path.to_path_buf(), 0);
+    progress.record_dir_queued();
+
+    let num_threads = profile.threads;
+    perf_monitor.set_threads_used(num_threads);
+
+    let mut pool_builder = rayon::ThreadPoolBuilder::new().num_threads(num_threads);
+    if profile.is_background() {
+        // 线程池随本次扫描销毁，降低的优先级不会泄漏到其他任务
+        pool_builder = pool_builder.start_handler(|_| {
+            crate::fs::enter_background_priority();
+        });
+    }
+    let pool = pool_builder.build()?;
+
+    perf_monitor.start_io_phase();
+    let scan_start = std::time::Instant::now();
+
+    // 超出时间预算后出队的目录不再读取，只记录下来（其 item 已由父目录发出，大小为 0）
+    let unscanned: Mutex<Vec<CompactString>> = Mutex::new(Vec::new());
+    // 无法读取的目录（拒绝访问等），大小按上次快照估计
+    let errors: Mutex<Vec<ScanError>> = Mutex::new(Vec::new());
+    // APFS 克隆的共享数据块：同一克隆组只计一次
+    let shared_extents = crate::fs::SharedExtentTracker::default();
+    // 云同步文件：仅在线 / 已在本地的字节数
+    let cloud = crate::fs::CloudTally::default();
+    // 稀疏文件：逻辑 / 分配大小
+    let sparse_files: Mutex<Vec<SparseEntry>> = Mutex::new(Vec::new());
+    // 从缓存子树并入的条目数
+    let reused_entries = std::sync::atomic::AtomicUsize::new(0);
+
+    pool.scope(|s| {
+        for _ in 0..num_threads {
+            let reused_entries = &reused_entries;
+            let unscanned = &unscanned;
+            let errors = &errors;
+            let sparse_files = &sparse_files;
+            let shared_extents = &shared_extents;
+            let cloud = &cloud;
+            let dir_queue = &dir_queue;
+            let profile = &profile;
+            let item_sender = item_sender.clone();
+            let events_for_worker = events.clone();
+
+            s.spawn(move |_| {
+                // 共享队列已满时本 worker 自行处理的子目录（深度优先）及其优先级
+                let mut overflow: Vec<(PathBuf, i64)> = Vec::new();
+                // 流式传输缓冲区：每 profile.stream_batch 条或每 STREAM_FLUSH_INTERVAL emit 一次
+                let mut stream_batch: Vec<Item> = Vec::with_capacity(profile.stream_batch);
+                let mut last_flush = std::time::Instant::now();
+
+                // 队列暂空时阻塞等待，所有已发现的目录都处理完毕后 pop 返回 None
+                while let Some((dir_path, priority)) = overflow.pop().or_else(|| dir_queue.pop()) {
+                    // 本目录处理结束（含超时跳过、读取失败、panic）时计数减一
+                    let _done = dir_queue.completion();
+
+                    if deadline.is_some_and(|d| std::time::Instant::now() >= d) {
+                        unscanned.lock().push(normalize_path_separator_compact(dir_path.as_os_str()));
+                        continue;
+                    }
+
+                    // 使用平台优化的目录遍历器
+                    // Windows: FindFirstFileExW 直接读取 size/attrs，零额外 syscall
+                    // Linux: getdents64 + statx；macOS: getattrlistbulk
+                    match read_dir(&dir_path) {
+                        Ok(mut entries) => {
+                            if profile.deterministic {
+                                entries.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+                            }
+                            // 确定性模式不排优先级，保持入队顺序
+                            let child_priority = if profile.deterministic {
+                                0
+                            } else {
+                                let direct_bytes = entries.iter().filter(|e| !e.is_dir).map(|e| e.size as i64).sum();
+                                live_priority(priority, direct_bytes, entries.len())
+                            };
+                            for entry in entries {
+                                let abs_path = normalize_path_separator_compact(entry.path.as_os_str());
+                                if profile.exclusions.excludes(&abs_path, &entry.name, entry.is_dir)
+                                    || (!entry.is_dir && !profile.includes.includes_file(&abs_path, &entry.name))
+                                {
+                                    continue;
+                                }
+                                let size = (entry.size + shared_extents.charge(entry.shared)) as i64;
+                                cloud.record(entry.cloud, entry.size);
+                                if let Some(sparse) = entry.sparse {
+                                    sparse_files.lock().push(SparseEntry {
+                                        path: abs_path.clone(),
+                                        logical_size: sparse.logical as i64,
+                                        allocated_size: sparse.allocated as i64,
+                                    });
+                                }
+
+                                // 联接 / 符号链接 / 云占位符只作为条目保留，不进入
+                                if let Some(subtree) =
+                                    (entry.is_dir && entry.kind.descends()).then(|| profile.reuse.get(&abs_path)).flatten()
+                                {
+                                    // 沿用缓存的子树：不再进入，直接发出其中的条目，目录大小与计数由下方聚合统一重算
+                                    // （这些条目不进入流式批次，随最终结果一起出现）
+                                    for cached in subtree.items.iter() {
+                                        progress.record_entry(&cached.path);
+                                        let _ = item_sender.send(ItemInternal {
+                                            path: cached.path.clone(),
+                                            name: cached.name.clone(),
+                                            size: if cached.is_dir { 0 } else { cached.size },
+                                            is_dir: cached.is_dir,
+                                            modified: cached.modified,
+                                            created: cached.created,
+                                            kind: cached.kind,
+                                        });
+                                    }
+                                    errors.lock().extend(subtree.errors.iter().cloned());
+                                    sparse_files.lock().extend(subtree.sparse_files.iter().cloned());
+                                    cloud.add_totals(subtree.cloud_only_bytes, subtree.cloud_local_bytes);
+                                    reused_entries.fetch_add(subtree.items.len(), std::sync::atomic::Ordering::Relaxed);
+                                } else if entry.is_dir && entry.kind.descends() {
+                                    let hint = if profile.deterministic {
+                                        0
+                                    } else {
+                                        size_hints.get(&abs_path).copied().unwrap_or(child_priority)
+                                    };
+                                    if profile.queue_capacity.is_some_and(|cap| dir_queue.len() >= cap) {
+                                        dir_queue.track_local();
+                                        overflow.push((entry.path, hint));
+                                    } else {
+                                        dir_queue.push(entry.path, hint);
+                                    }
+                                    progress.record_dir_queued();
+                                }
+                                progress.record_entry(&abs_path);
+
+                                let _ = item_sender.send(ItemInternal {
+                                    path: abs_path.clone(),
+                                    name: CompactString::from(entry.name.as_str()),
+                                    size,
+                                    is_dir: entry.is_dir,
+                                    modified: entry.modified,
+                                    created: entry.created,
+                                    kind: entry.kind,
+                                });
+
+                                // 渐进式流式传输
+                                if let Some(sink) = events_for_worker.as_ref() {
+                                    stream_batch.push(Item {
+                                        id: stable_item_id(volume_id, &abs_path),
+                                        path: abs_path,
+                                        category: item_category(&entry.name, entry.kind),
+                                        percent_of_parent: 0.0,
+                                        percent_of_total: 0.0,
+                                        name: CompactString::from(entry.name),
+                                        size,
+                                        size_formatted: format_size(size),
+                                        is_dir: entry.is_dir,
+                                        modified: entry.modified,
+                                        created: entry.created,
+                                        kind: entry.kind,
+                                        // 流式批次先于聚合，目录的子树计数以最终结果为准
+                                        file_count: 0,
+                                        dir_count: 0,
+                                    });
+                                    if stream_batch.len() >= profile.stream_batch {
+                                        sink.emit("scan-batch", std::mem::take(&mut stream_batch));
+                                        last_flush = std::time::Instant::now();
+                                    }
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            let path = normalize_path_separator_compact(dir_path.as_os_str());
+                            let estimated_bytes = size_hints.get(&path).copied().unwrap_or(0);
+                            errors.lock().push(ScanError { path, kind: e.kind().into(), estimated_bytes });
+                        }
+                    }
+                    progress.record_dir_done();
+                    if let Some(pause) = profile.dispatch_pause {
+                        std::thread::sleep(pause);
+                    }
+
+                    if let Some(sink) = events_for_worker.as_ref() {
+                        if !stream_batch.is_empty() && last_flush.elapsed() >= STREAM_FLUSH_INTERVAL {
+                            sink.emit("scan-batch", std::mem::take(&mut stream_batch));
+                            last_flush = std::time::Instant::now();
+                        }
+                        if progress.should_emit() {
+                            sink.emit("scan-progress", progress.snapshot(false));
+                        }
+                    }
+                }
+
+                // 发送当前 worker 剩余的批次
+                if let Some(sink) = events_for_worker.as_ref() {
+                    if !stream_batch.is_empty() {
+                        sink.emit("scan-batch", std::mem::take(&mut stream_batch));
+                    }
+                }
+            });
+        }
+    });
+
+    drop(item_sender);
+    if let Some(sink) = events.as_ref() {
+        sink.emit("scan-progress", progress.snapshot(true));
+    }
+    let (cloud_only_bytes, cloud_local_bytes) = cloud.totals();
+    let mut sparse_files = sparse_files.into_inner();
+    sparse_files.sort_unstable_by_key(|e| std::cmp::Reverse(e.logical_size - e.allocated_size));
+
+    let scan_phase = scan_start.elapsed();
+    perf_monitor.end_io_phase();
+    
+    perf_monitor.start_compute_phase();
+    let compute_start = std::time::Instant::now();
+
+    let internal_items: Vec<ItemInternal> = item_receiver.try_iter().collect();
+    let file_count = internal_items.iter().filter(|i| !i.is_dir).count();
+    let dir_count = internal_items.len() - file_count;
+
+    let actual_total_size: i64 = internal_items
+        .iter()
+        .filter(|i| !i.is_dir)
+        .map(|i| i.size)
+        .sum();
+
+    // 计算 I/O 吞吐量
+    let throughput_mbps = if scan_phase.as_secs_f64() > 0.0 {
+        (actual_total_size as f64 / 1024.0 / 1024.0) / scan_phase.as_secs_f64()
+    } else {
+        0.0
+    };
+
+    // 目录大小聚合：建立"目录 path → 在 internal_items 中的下标"索引，
+    // 配合按下标对齐的原子累加数组，把每个文件大小沿路径向上累加到各祖先目录。
+    // 旧实现为每个祖先 new 一个 CompactString（O(文件数×深度) 堆分配），这里改为仅 index 写入，零字符串分配。
+    use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+
+    let dir_index: HashMap<&str, usize> = internal_items
+        .iter()
+        .enumerate()
+        .filter(|(_, it)| it.is_dir)
+        .map(|(i, it)| (it.path.as_str(), i))
+        .collect();
+
+    let dir_sizes: Vec<AtomicI64> = (0..internal_items.len())
+        .map(|_| AtomicI64::new(0))
+        .collect();
+    // 同一遍中统计每个目录子树的文件数 / 子目录数
+    let file_counts: Vec<AtomicU64> = (0..internal_items.len()).map(|_| AtomicU64::new(0)).collect();
+    let dir_counts: Vec<AtomicU64> = (0..internal_items.len()).map(|_| AtomicU64::new(0)).collect();
+
+    internal_items
+        .par_iter()
+        .for_each(|it| {
+            let file_path = it.path.as_str();
+            let mut pos = 0;
+            while let Some(slash_pos) = file_path[pos..].find('/') {
+                let abs_pos = pos + slash_pos;
+                let parent = &file_path[..abs_pos];
+                if let Some(&idx) = dir_index.get(parent) {
+                    if it.is_dir {
+                        dir_counts[idx].fetch_add(1, Ordering::Relaxed);
+                    } else {
+                        file_counts[idx].fetch_add(1, Ordering::Relaxed);
+                        dir_sizes[idx].fetch_add(it.size, Ordering::Relaxed);
+                    }
+                }
+                pos = abs_pos + 1;
+            }
+        });
+
+    // 释放对 internal_items 的借用，以便下方 into_par_iter 消费它
+    drop(dir_index);
+
+    let compute_phase = compute_start.elapsed();
+    let format_start = std::time::Instant::now();
+
+    // 复用 internal_items（原地转换），不再额外拷贝一份中间结构
+    let mut items_vec: Vec<Item> = internal_items
+        .into_par_iter()
+        .enumerate()
+        .map(|(i, internal)| {
+            let (size, file_count, dir_count) = if internal.is_dir {
+                (
+                    dir_sizes[i].load(Ordering::Relaxed),
+                    file_counts[i].load(Ordering::Relaxed),
+                    dir_counts[i].load(Ordering::Relaxed),
+                )
+            } else {
+                (internal.size, 0, 0)
+            };
+
+            Item {
+                id: stable_item_id(volume_id, &internal.path),
+                category: item_category(&internal.name, internal.kind),
+                percent_of_parent: 0.0,
+                percent_of_total: 0.0,
+                path: internal.path,
+                name: internal.name,
+                size,
+                size_formatted: format_size(size),
+                is_dir: internal.is_dir,
+                modified: internal.modified,
+                created: internal.created,
+                kind: internal.kind,
+                file_count,
+                dir_count,
+            }
+        })
+        .collect();
+
+    fill_percentages(&mut items_vec, actual_total_size);
+    items_vec.sort_unstable_by_key(|i| std::cmp::Reverse(i.size));
+
+    let format_phase = format_start.elapsed();
+    let total = total_start.elapsed();
+
+    perf_monitor.end_compute_phase();
+
+    // 估算内存使用（internal_items 已消费进 items_vec；dir_sizes 为紧凑原子数组）
+    let memory_peak_mb = (items_vec.capacity() * std::mem::size_of::<Item>()
+        + dir_sizes.len() * (std::mem::size_of::<AtomicI64>() + 2 * std::mem::size_of::<AtomicU64>())) as f64
+        / 1024.0
+        / 1024.0;
+
+    perf_monitor.update_memory_stats(memory_peak_mb, memory_peak_mb);
+    perf_monitor.update_io_stats(file_count, dir_count, actual_total_size as u64, file_count + dir_count);
+
+    Ok(ScanOutput {
+        items: items_vec,
+        total_size: actual_total_size,
+        timing: TimingInfo {
+            scan_phase: scan_phase.as_secs_f64(),
+            compute_phase: compute_phase.as_secs_f64(),
+            format_phase: format_phase.as_secs_f64(),
+            total: total.as_secs_f64(),
+        },
+        file_count,
+        dir_count,
+        throughput_mbps,
+        memory_peak_mb,
+        threads_used: num_threads,
+        mft_available: false,
+        unscanned: unscanned.into_inner(),
+        errors: errors.into_inner(),
+        cloud_only_bytes,
+        cloud_local_bytes,
+        sparse_files,
+        reused_entries: reused_entries.into_inner(),
+    })
+}
+
+/// 本次扫描中推算的子目录优先级：父目录直接包含的文件大小与条目数（每个条目折算
+/// PRIORITY_BYTES_PER_ENTRY 字节），加上父目录自身优先级的一半。
+/// 大文件多、条目多的目录下的子树先遍历，且优先级随深度衰减，不会一条路走到底
+fn live_priority(parent_priority: i64, direct_bytes: i64, entry_count: usize) -> i64 {
+    direct_bytes
+        .saturating_add((entry_count as i64).saturating_mul(PRIORITY_BYTES_PER_ENTRY))
+        .saturating_add(parent_priority / 2)
+}
+
+/// 目录优先级中一个条目折算的字节数
+const PRIORITY_BYTES_PER_ENTRY: i64 = 64 * 1024;
+
+/// 流式批次最长攒多久必须发出，先遍历到的大目录不必等到攒满一批
+const STREAM_FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// 目录工作队列：优先级高的目录先出队。优先级取上次快照中的目录大小（历史上最大的子树先遍历），
+/// 没有历史数据时取本次扫描中由父目录推算的 live_priority；大目录先被读取、先流式发出，
+/// 限时扫描也能更快覆盖大部分数据。优先级相同的目录按入队顺序
+struct DirQueue {
+    state: Mutex<DirQueueState>,
+    ready: parking_lot::Condvar,
+    seq: std::sync::atomic::AtomicU64,
+}
+
+struct DirQueueState {
+    heap: std::collections::BinaryHeap<QueuedDir>,
+    /// 已发现但尚未处理完的目录数（队列中 + 正在读取 + worker 本地暂存）。
+    /// 归零即遍历结束：不会再有新目录出现
+    pending: usize,
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct QueuedDir {
+    size_hint: i64,
+    /// 大小提示相同时先入队的先出队
+    order: std::cmp::Reverse<u64>,
+    path: PathBuf,
+}
+
+/// 一个目录处理完毕的凭证，drop 时计数减一
+struct DirCompletion<'a>(&'a DirQueue);
+
+impl Drop for DirCompletion<'_> {
+    fn drop(&mut self) {
+        let mut state = self.0.state.lock();
+        state.pending -= 1;
+        if state.pending == 0 {
+            drop(state);
+            // 唤醒所有等待中的 worker 退出
+            self.0.ready.notify_all();
+        }
+    }
+}
+
+impl DirQueue {
+    fn new() -> Self {
+        Self {
+            state: Mutex::new(DirQueueState {
+                heap: std::collections::BinaryHeap::new(),
+                pending: 0,
+            }),
+            ready: parking_lot::Condvar::new(),
+            seq: std::sync::atomic::AtomicU64::new(0),
+        }
+    }
+
+    fn push(&self, path: PathBuf, size_hint: i64) {
+        let order = std::cmp::Reverse(self.seq.fetch_add(1, Ordering::Relaxed));
+        {
+            let mut state = self.state.lock();
+            state.heap.push(QueuedDir { size_hint, order, path });
+            state.pending += 1;
+        }
+        self.ready.notify_one();
+    }
+
+    /// 登记一个不经共享队列、由发现它的 worker 自行处理的目录
+    fn track_local(&self) {
+        self.state.lock().pending += 1;
+    }
+
+    /// 取出下一个目录及其优先级：队列暂空但仍有目录在处理时等待（新子目录入队或全部完成），
+    /// 全部完成时返回 None
+    fn pop(&self) -> Option<(PathBuf, i64)> {
+        let mut state = self.state.lock();
+        loop {
+            if let Some(dir) = state.heap.pop() {
+                return Some((dir.path, dir.size_hint));
+            }
+            if state.pending == 0 {
+                return None;
+            }
+            self.ready.wait(&mut state);
+        }
+    }
+
+    /// 每个取出的目录处理结束时必须恰好完成一次
+    fn completion(&self) -> DirCompletion<'_> {
+        DirCompletion(self)
+    }
+
+    fn len(&self) -> usize {
+        self.state.lock().heap.len()
+    }
+}
+
+/// 上次快照中各目录的大小（遍历优先级提示）
+pub(crate) fn dir_size_hints(previous: &ScanResult) -> HashMap<CompactString, i64> {
+    previous
+        .items
+        .iter()
+        .filter(|i| i.is_dir)
+        .map(|i| (i.path.clone(), i.size))
+        .collect()
+}
+
+struct ItemInternal {
+    path: CompactString,
+    name: CompactString,
+    size: i64,
+    is_dir: bool,
+    modified: i64,
+    created: i64,
+    kind: ItemKind,
+}
+
+/// 去掉 Windows 扩展长度前缀（\\?\C:\、\\?\UNC\），并统一使用正斜杠。
+/// 遍历使用扩展长度形式，结果 / 缓存 key 中只出现可读形式
+fn normalize_path_separator(path: &std::ffi::OsStr) -> String {
+    crate::fs::display_path(&path.to_string_lossy())
+}
+
+#[inline]
+fn normalize_path_separator_compact(path: &std::ffi::OsStr) -> CompactString {
+    let s = path.to_string_lossy();
+    let stripped = crate::fs::display_path_str(&s);
+    let bytes = stripped.as_bytes();
+    if stripped.contains('\\') || (bytes.len() >= 2 && bytes[1] == b':' && bytes[0].is_ascii_lowercase()) {
+        CompactString::from(crate::fs::display_path(&stripped))
+    } else {
+        CompactString::from(stripped.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drive_and_vol_prefix() {
+        assert_eq!(drive_and_vol_prefix("C:/Users/xxx"), Some(('C', "users/xxx/".to_string())));
+        assert_eq!(drive_and_vol_prefix("C:/"), Some(('C', String::new())));
+        assert_eq!(drive_and_vol_prefix("C:\\Users\\xxx"), Some(('C', "users/xxx/".to_string())));
+        assert_eq!(drive_and_vol_prefix("//?/C:/Users/xxx"), Some(('C', "users/xxx/".to_string())));
+        assert_eq!(drive_and_vol_prefix("\\\\?\\C:\\Users\\xxx"), Some(('C', "users/xxx/".to_string())));
+        assert_eq!(drive_and_vol_prefix("/home/xxx"), None);
+    }
+
+    #[test]
+    fn test_compute_extension_stats() {
+        let item = |name: &str, size: i64, is_dir: bool| Item::test(&format!("d/{}", name), size, is_dir);
+        let items = vec![
+            item("a.MP4", 300, false),
+            item("b.mp4", 100, false),
+            item("c.txt", 60, false),
+            item("Makefile", 30, false),
+            item(".gitignore", 20, false),
+            item("sub.d", 480, true),
+        ];
+        let stats = compute_extension_stats(&items);
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats[0].extension.as_str(), "mp4");
+        assert_eq!(stats[0].total_size, 400);
+        assert_eq!(stats[0].file_count, 2);
+        assert_eq!(stats[2].extension.as_str(), NO_EXTENSION);
+        assert_eq!(stats[2].file_count, 2);
+    }
+
+    #[test]
+    fn test_fold_small_files() {
+        let item = |path: &str, size: i64, kind: ItemKind| Item {
+            is_dir: kind == ItemKind::Dir,
+            modified: size,
+            kind,
+            ..Item::test_file(path, size)
+        };
+        let mut items = vec![
+            item("/r/src", 1000, ItemKind::Dir),
+            item("/r/big.bin", 600, ItemKind::File),
+            item("/r/src/a.rs", 400, ItemKind::File),
+            item("/r/src/b.rs", 300, ItemKind::File),
+            item("/r/src/c.rs", 300, ItemKind::File),
+            item("/r/readme", 50, ItemKind::File),
+            item("/r/link", 0, ItemKind::Symlink),
+        ];
+        fold_small_files(&mut items, 500, 1650, 7);
+
+        // 每个目录一个合成条目，大小为合并的总和；目录与链接照常列出
+        let paths: Vec<&str> = items.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(paths, ["/r/src", "/r/src/<小文件>", "/r/big.bin", "/r/<小文件>", "/r/link"]);
+        let bucket = &items[1];
+        assert_eq!((bucket.kind, bucket.name.as_str(), bucket.size, bucket.modified), (ItemKind::SmallFiles, "3 个小文件", 1000, 400));
+        assert_eq!(bucket.file_count, 3);
+        assert_eq!(bucket.percent_of_parent, 100.0);
+        assert_eq!(items.iter().filter(|i| i.kind != ItemKind::Dir).map(|i| i.size).sum::<i64>(), 1650);
+        assert_eq!(bucket.id, stable_item_id(7, "/r/src/<小文件>"));
+    }
+
+    #[test]
+    fn test_stable_item_id() {
+        let id = stable_item_id(42, "C:/Users/a/file.txt");
+        assert_eq!(id, stable_item_id(42, "C:/Users/a/file.txt"));
+        // 同一卷换盘符挂载，ID 不变；不同卷或不同路径则不同
+        assert_eq!(id, stable_item_id(42, "D:/Users/a/file.txt"));
+        assert_ne!(id, stable_item_id(43, "C:/Users/a/file.txt"));
+        assert_ne!(id, stable_item_id(42, "C:/Users/a/other.txt"));
+        assert!(id <= ITEM_ID_MASK);
+    }
+
+    #[test]
+    fn test_projected_items_serialization() {
+        let item = Item {
+            size_formatted: format_size(42),
+            modified: 7,
+            created: 3,
+            id: 11,
+            ..Item::test_file("d/a.txt", 42)
+        };
+
+        let full = ProjectedItems { items: vec![item.clone()], mask: FieldMask::from_fields(None) };
+        assert_eq!(
+            serde_json::to_value(&full).unwrap(),
+            serde_json::to_value(vec![item.clone()]).unwrap()
+        );
+
+        let fields = [ItemField::Path, ItemField::Size];
+        let projected = ProjectedItems { items: vec![item], mask: FieldMask::from_fields(Some(&fields)) };
+        assert_eq!(
+            serde_json::to_value(&projected).unwrap(),
+            serde_json::json!([{ "path": "d/a.txt", "size": 42 }])
+        );
+    }
+
+    #[test]
+    fn test_scan_cache_evicts_least_recently_viewed_for_rss() {
+        let result = |path: &str| ScanResult {
+            items: Vec::new(),
+            total_size: 0,
+            total_size_formatted: CompactString::new(),
+            scan_time: 0.0,
+            path: CompactString::from(path),
+            mft_available: false,
+            timing: None,
+            perf_metrics: None,
+            extension_stats: None,
+            category_stats: Vec::new(),
+            owners: None,
+            unscanned: None,
+            errors: Vec::new(),
+            unreadable_bytes: 0,
+            cloud_only_bytes: 0,
+            cloud_local_bytes: 0,
+            sparse_files: Vec::new(),
+            sparse_savings_bytes: 0,
+            cache_usage: Default::default(),
+        };
+        let cache = ScanCache::new(10, 100);
+        for key in ["C:/", "D:/", "E:/"] {
+            cache.insert(key.to_string(), result(key));
+        }
+        // 查看 C:/ 后，最久未查看的是 D:/
+        assert!(cache.get("C:/").is_some());
+
+        assert!(cache.evict_for_rss(100, 200, 1).is_empty());
+        // 每个会话的估计大小远小于超出部分：回收到只剩 keep 个为止
+        assert_eq!(cache.evict_for_rss(u64::MAX, 200, 1), vec!["D:/", "E:/"]);
+        assert!(cache.get("C:/").is_some());
+
+        // 包含路径的会话：最近的祖先优先，只按完整路径段匹配
+        cache.insert("C:/Users".to_string(), result("C:/Users"));
+        assert_eq!(cache.containing_key("C:/Users/me/Documents").as_deref(), Some("C:/Users"));
+        assert_eq!(cache.containing_key("C:/Users").as_deref(), Some("C:/Users"));
+        assert_eq!(cache.containing_key("C:/UsersOld").as_deref(), Some("C:/"));
+        assert_eq!(cache.containing_key("D:/data"), None);
+
+        // 运行时缩小容量：移除最久未查看的会话，之后按新容量淘汰
+        assert_eq!(cache.set_limits(1, 100), vec!["C:/"]);
+        let stats = cache.stats();
+        assert_eq!((stats.max_entries, stats.max_size_mb, stats.current_entries), (1, 100, 1));
+        cache.insert("D:/".to_string(), result("D:/"));
+        assert!(cache.get("C:/Users").is_none());
+        assert!(cache.set_limits(5, 0).len() == 1 && cache.stats().current_entries == 0);
+    }
+
+    #[test]
+    fn test_dir_queue_waits_for_in_flight_dirs() {
+        // 单链深目录：队列大部分时间为空但仍有目录在读取，worker 不能提前退出
+        let queue = DirQueue::new();
+        queue.push(PathBuf::from("0"), 0);
+        let processed = std::sync::atomic::AtomicUsize::new(0);
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    while let Some((dir, _)) = queue.pop() {
+                        let _done = queue.completion();
+                        processed.fetch_add(1, Ordering::Relaxed);
+                        let depth: usize = dir.to_str().unwrap().parse().unwrap();
+                        std::thread::sleep(std::time::Duration::from_micros(200));
+                        if depth < 200 {
+                            queue.push(PathBuf::from((depth + 1).to_string()), 0);
+                        }
+                    }
+                });
+            }
+        });
+        assert_eq!(processed.load(Ordering::Relaxed), 201);
+    }
+
+    #[test]
+    fn test_scan_profile_resolve() {
+        assert!(ScanProfile::resolve("//nas/share", NetworkMode::Auto).is_network());
+        assert!(ScanProfile::resolve("\\\\?\\UNC\\nas\\share", NetworkMode::Auto).is_network());
+        assert!(!ScanProfile::resolve("//nas/share", NetworkMode::Off).is_network());
+        assert_eq!(ScanProfile::resolve("/tmp", NetworkMode::On), ScanProfile::network());
+        assert!(ScanProfile::network().threads < ScanProfile::local().threads);
+        assert_eq!(ScanProfile::resolve("//nas/share", NetworkMode::Off).drive(), crate::fs::DriveType::Network);
+        // 机械硬盘：少量线程、小容量共享队列；设置未指定队列容量时保留
+        let hdd = ScanProfile::hdd();
+        assert!(hdd.threads < ScanProfile::local().threads);
+        assert_eq!(hdd.with_settings(&Default::default()).queue_capacity, Some(64));
+        assert_eq!(ScanProfile::local().with_settings(&Default::default()).queue_capacity, None);
+        let background = ScanProfile::local().background();
+        assert!(background.is_background() && !ScanProfile::local().is_background());
+        assert!(background.threads <= 2);
+
+        let settings = crate::settings::ScanSettings { threads: Some(3), queue_capacity: Some(64), deterministic: false };
+        assert_eq!(ScanProfile::local().with_settings(&settings).threads, 3);
+        assert_eq!(ScanProfile::network().with_settings(&settings).threads, ScanProfile::network().threads);
+        let deterministic = crate::settings::ScanSettings { threads: Some(8), deterministic: true, ..Default::default() };
+        assert_eq!(ScanProfile::local().with_settings(&deterministic).threads, 1);
+    }
+
+    #[test]
+    fn test_dir_queue_prioritizes_historically_largest() {
+        let queue = DirQueue::new();
+        queue.push(PathBuf::from("a"), 0);
+        queue.push(PathBuf::from("b"), 0);
+        queue.push(PathBuf::from("big"), 1 << 30);
+        queue.push(PathBuf::from("mid"), 1 << 20);
+        // 每个取出的目录处理完毕后完成，最后一个完成后 pop 返回 None
+        let order: Vec<PathBuf> =
+            std::iter::from_fn(|| queue.pop().inspect(|_| drop(queue.completion()))).map(|(p, _)| p).collect();
+        assert_eq!(order, ["big", "mid", "a", "b"].map(PathBuf::from));
+        assert_eq!(queue.len(), 0);
+    }
+
+    #[test]
+    fn test_live_priority_favors_heavy_and_wide_parents() {
+        // 直接包含 1GB 文件的目录下的子目录，先于只有几个小文件的目录下的子目录
+        let heavy = live_priority(0, 1 << 30, 3);
+        let light = live_priority(0, 4096, 3);
+        let wide = live_priority(0, 0, 5_000);
+        assert!(heavy > wide && wide > light);
+        // 父目录的优先级逐层减半传递
+        assert_eq!(live_priority(1000, 0, 0), 500);
+        assert!(live_priority(heavy, 0, 1) > light);
+        assert_eq!(live_priority(i64::MAX, i64::MAX, 10), i64::MAX);
+    }
+
+    #[test]
+    fn test_mft_path_to_abs() {
+        assert_eq!(mft_path_to_abs('C', "Users/xxx/file.txt"), CompactString::from("C:/Users/xxx/file.txt"));
+        assert_eq!(mft_path_to_abs('C', "C:/Users/xxx/file.txt"), CompactString::from("C:/Users/xxx/file.txt"));
+        assert_eq!(mft_path_to_abs('C', ""), CompactString::from("C:/"));
+    }
+}
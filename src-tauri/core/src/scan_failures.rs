@@ -0,0 +1,107 @@
+// 最近扫描失败的路径（负缓存）
+//
+// 断开的网络驱动器、不可达的共享等路径每次访问都要阻塞数秒才报错。扫描根路径无法访问时记录失败原因，
+// FAILURE_TTL 内再次扫描同一路径直接返回记录的错误（RecentFailure，附剩余等待时间），不再访问文件系统；
+// 强制刷新（forceRefresh）清除记录并立即重试。只记录访问根路径本身的失败，遍历中个别目录的错误不在此列。
+// 记录只在本进程内，按 cache_key 归一（大小写、分隔符、末尾斜杠）。
+
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// 失败记录的有效期
+pub const FAILURE_TTL: Duration = Duration::from_secs(30);
+
+/// 记录数超过此值时先清理过期的记录
+const MAX_FAILURES: usize = 256;
+
+lazy_static! {
+    /// 路径 key → (错误描述, 失败时间)
+    static ref FAILURES: Mutex<HashMap<String, (String, Instant)>> = Mutex::new(HashMap::new());
+}
+
+/// 路径最近访问失败，在有效期内未重试
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentFailure {
+    pub path: String,
+    /// 上次失败的错误描述
+    pub error: String,
+    /// 距上次失败的秒数
+    pub failed_secs_ago: u64,
+    /// 再过多少秒后自动重试；强制刷新可立即重试
+    pub retry_after_secs: u64,
+}
+
+impl fmt::Display for RecentFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}（{} 秒前访问失败，{} 秒后自动重试；强制刷新可立即重试）",
+            self.error, self.failed_secs_ago, self.retry_after_secs
+        )
+    }
+}
+
+impl std::error::Error for RecentFailure {}
+
+/// 记录 path 访问失败
+pub fn record(path: &str, error: &str) {
+    let now = Instant::now();
+    let mut failures = FAILURES.lock();
+    if failures.len() >= MAX_FAILURES {
+        failures.retain(|_, (_, at)| now.duration_since(*at) < FAILURE_TTL);
+    }
+    failures.insert(crate::fs::cache_key(path), (error.to_string(), now));
+}
+
+/// path 在有效期内的失败记录；过期的记录顺带移除
+pub fn recent(path: &str) -> Option<RecentFailure> {
+    lookup(&mut FAILURES.lock(), path, Instant::now())
+}
+
+/// 清除 path 的失败记录（强制刷新或访问成功时）；返回之前是否有记录
+pub fn clear(path: &str) -> bool {
+    FAILURES.lock().remove(&crate::fs::cache_key(path)).is_some()
+}
+
+fn lookup(failures: &mut HashMap<String, (String, Instant)>, path: &str, now: Instant) -> Option<RecentFailure> {
+    let key = crate::fs::cache_key(path);
+    let (error, at) = failures.get(&key)?;
+    let elapsed = now.duration_since(*at);
+    if elapsed >= FAILURE_TTL {
+        failures.remove(&key);
+        return None;
+    }
+    Some(RecentFailure {
+        path: path.to_string(),
+        error: error.clone(),
+        failed_secs_ago: elapsed.as_secs(),
+        retry_after_secs: (FAILURE_TTL - elapsed).as_secs_f64().ceil() as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn returns_failures_until_they_expire() {
+        let start = Instant::now();
+        let mut failures = HashMap::new();
+        failures.insert(crate::fs::cache_key("/mnt/share"), ("无法访问路径: timed out".to_string(), start));
+
+        // 末尾斜杠按同一路径处理
+        let failure = lookup(&mut failures, "/mnt/share/", start + Duration::from_secs(10)).unwrap();
+        assert_eq!((failure.failed_secs_ago, failure.retry_after_secs), (10, 20));
+        assert!(failure.to_string().starts_with("无法访问路径: timed out"));
+        assert_eq!(lookup(&mut failures, "/mnt/other", start), None);
+
+        assert_eq!(lookup(&mut failures, "/mnt/share", start + FAILURE_TTL), None);
+        assert!(failures.is_empty());
+    }
+}
@@ -0,0 +1,201 @@
+// 扫描配置（命名的 ScanOptions 预设）
+//
+// 用户保存的配置在 ~/.flashdir/scan_profiles.json（与 history.json 同目录），
+// 另有三个内置配置："Quick overview"（快速概览）、"Full audit"（完整审计）、"Network share"（网络共享）。
+// 以内置配置的名称保存时覆盖内置配置，删除后恢复内置版本。
+// 名称不区分大小写比较，保存时保留用户输入的大小写。
+
+use crate::exclusions::ExclusionPreset;
+use crate::scan::{NetworkMode, ScanOptions};
+use crate::validate::{ValidationCode, ValidationError, Validated};
+use lazy_static::lazy_static;
+use parking_lot::RwLock;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const PROFILES_FILE: &str = "scan_profiles.json";
+
+/// 配置名称的最大字符数
+pub const MAX_NAME_CHARS: usize = 64;
+
+/// 内置配置名称
+pub const QUICK_OVERVIEW: &str = "Quick overview";
+pub const FULL_AUDIT: &str = "Full audit";
+pub const NETWORK_SHARE: &str = "Network share";
+
+lazy_static! {
+    static ref PROFILES: RwLock<Vec<SavedProfile>> = RwLock::new(profiles_path().map(|p| load_from(&p)).unwrap_or_default());
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedProfile {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub options: ScanOptions,
+    /// 内置配置（未被用户覆盖）
+    #[serde(default)]
+    pub builtin: bool,
+    /// 最后保存时间（Unix 秒），内置配置为 0
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+fn builtin(name: &str, description: &str, options: ScanOptions) -> SavedProfile {
+    SavedProfile { name: name.to_string(), description: description.to_string(), options, builtin: true, updated_at: 0 }
+}
+
+/// 内置配置
+pub fn builtins() -> Vec<SavedProfile> {
+    vec![
+        builtin(
+            QUICK_OVERVIEW,
+            "2 秒时间预算，跳过系统目录，适合先看大致分布",
+            ScanOptions {
+                time_budget_ms: Some(2000),
+                exclude_presets: ExclusionPreset::ALL.to_vec(),
+                ..Default::default()
+            },
+        ),
+        builtin(
+            FULL_AUDIT,
+            "完整遍历，附带扩展名统计与所有者",
+            ScanOptions { extension_stats: true, include_owner: true, ..Default::default() },
+        ),
+        builtin(
+            NETWORK_SHARE,
+            "网络模式低并发遍历，后台优先级",
+            ScanOptions { network_mode: NetworkMode::On, background: true, ..Default::default() },
+        ),
+    ]
+}
+
+/// 内置配置与用户配置合并：同名（不区分大小写）时用户配置优先，内置配置在前
+fn merged(saved: &[SavedProfile]) -> Vec<SavedProfile> {
+    let mut profiles: Vec<SavedProfile> = builtins()
+        .into_iter()
+        .map(|b| saved.iter().find(|s| same_name(&s.name, &b.name)).cloned().unwrap_or(b))
+        .collect();
+    let mut custom: Vec<SavedProfile> = saved
+        .iter()
+        .filter(|s| !profiles.iter().any(|p| same_name(&p.name, &s.name)))
+        .cloned()
+        .collect();
+    custom.sort_by_key(|p| p.name.to_lowercase());
+    profiles.extend(custom);
+    profiles
+}
+
+fn same_name(a: &str, b: &str) -> bool {
+    a.to_lowercase() == b.to_lowercase()
+}
+
+/// 校验并规范化配置名称（去掉首尾空白）
+pub fn validate_name(field: &str, name: &str) -> Validated<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ValidationError::new(field, ValidationCode::Required, "配置名称不能为空"));
+    }
+    if name.chars().count() > MAX_NAME_CHARS {
+        return Err(ValidationError::new(
+            field,
+            ValidationCode::OutOfRange,
+            format!("配置名称不能超过 {} 个字符", MAX_NAME_CHARS),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn profiles_path() -> Option<PathBuf> {
+    crate::migration::data_dir().ok().map(|dir| dir.join(PROFILES_FILE))
+}
+
+fn load_from(path: &Path) -> Vec<SavedProfile> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_all(profiles: &[SavedProfile]) -> anyhow::Result<()> {
+    let path = profiles_path().ok_or_else(|| anyhow::anyhow!("无法确定数据目录"))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(profiles)?)?;
+    Ok(())
+}
+
+/// 全部配置：内置配置在前，其余按名称排序
+pub fn list() -> Vec<SavedProfile> {
+    merged(&PROFILES.read())
+}
+
+/// 按名称（不区分大小写）取配置
+pub fn get(name: &str) -> Option<SavedProfile> {
+    list().into_iter().find(|p| same_name(&p.name, name.trim()))
+}
+
+/// 保存配置（同名覆盖）并写入文件，返回保存后的配置
+pub fn save(name: &str, description: &str, options: ScanOptions) -> anyhow::Result<SavedProfile> {
+    let profile = SavedProfile {
+        name: validate_name("name", name)?,
+        description: description.trim().to_string(),
+        options,
+        builtin: false,
+        updated_at: chrono::Utc::now().timestamp(),
+    };
+    let mut profiles = PROFILES.write();
+    profiles.retain(|p| !same_name(&p.name, &profile.name));
+    profiles.push(profile.clone());
+    save_all(&profiles)?;
+    Ok(profile)
+}
+
+/// 删除用户配置，不存在时返回 false；被覆盖的内置配置恢复原样，内置配置本身不能删除
+pub fn remove(name: &str) -> anyhow::Result<bool> {
+    let mut profiles = PROFILES.write();
+    let before = profiles.len();
+    profiles.retain(|p| !same_name(&p.name, name.trim()));
+    if profiles.len() == before {
+        return Ok(false);
+    }
+    save_all(&profiles)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved(name: &str, budget: u64) -> SavedProfile {
+        SavedProfile {
+            name: name.to_string(),
+            description: String::new(),
+            options: ScanOptions { time_budget_ms: Some(budget), ..Default::default() },
+            builtin: false,
+            updated_at: 1,
+        }
+    }
+
+    #[test]
+    fn user_profiles_shadow_builtins_and_sort_after_them() {
+        let profiles = merged(&[saved("zeta", 1), saved("quick OVERVIEW", 500), saved("Alpha", 2)]);
+        let names: Vec<_> = profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["quick OVERVIEW", FULL_AUDIT, NETWORK_SHARE, "Alpha", "zeta"]);
+        assert!(!profiles[0].builtin);
+        assert_eq!(profiles[0].options.time_budget_ms, Some(500));
+        assert!(profiles[1].builtin);
+        assert_eq!(profiles[2].options.network_mode, NetworkMode::On);
+    }
+
+    #[test]
+    fn names_are_trimmed_and_bounded() {
+        assert_eq!(validate_name("name", "  Nightly  ").unwrap(), "Nightly");
+        assert_eq!(validate_name("name", "   ").unwrap_err().code, ValidationCode::Required);
+        let long = "x".repeat(MAX_NAME_CHARS + 1);
+        assert_eq!(validate_name("name", &long).unwrap_err().code, ValidationCode::OutOfRange);
+    }
+}
@@ -0,0 +1,443 @@
+// 定时扫描
+//
+// 计划保存在 ~/.flashdir/schedules.json，每项为 路径 + 触发规则：
+//   间隔："30m"、"6h"、"1d"、"every 2h"（单位 s / m / h / d / w，最短 5 分钟）
+//   cron：5 段 "分 时 日 月 周"（支持 * , - /，周日为 0 或 7），或 @hourly / @daily / @weekly / @monthly
+// cron 按本地时区计算；日与周都受限时满足其一即可（与 Vixie cron 一致）。
+//
+// run_loop 每 CHECK_INTERVAL 检查一次到期的计划，逐个以后台模式（低 CPU / I/O 优先级）强制重扫，
+// 写入内存 / 磁盘缓存并保存快照，完成后发出 scheduled-scan-complete 事件。
+// 应用未运行期间错过的多次触发只补跑一次，下次时间从补跑时刻起算。
+
+use crate::events::Events;
+use crate::validate::{ValidationCode, ValidationError, Validated};
+use chrono::{Datelike, Duration, Local, NaiveDate, TimeZone, Timelike};
+use lazy_static::lazy_static;
+use parking_lot::RwLock;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const SCHEDULES_FILE: &str = "schedules.json";
+
+/// 定时扫描完成事件
+pub const SCHEDULE_EVENT: &str = "scheduled-scan-complete";
+
+/// 到期检查间隔
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// 最短扫描间隔（秒）
+pub const MIN_INTERVAL_SECS: u64 = 5 * 60;
+
+/// cron 求下一次时间时最多前进的步数（约束永不触发的表达式，如 2 月 30 日）
+const MAX_CRON_STEPS: usize = 100_000;
+
+lazy_static! {
+    static ref SCHEDULES: RwLock<Vec<Schedule>> = RwLock::new(schedules_path().map(|p| load_from(&p)).unwrap_or_default());
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Schedule {
+    pub id: String,
+    pub path: String,
+    /// 用户输入的触发规则（间隔或 cron）
+    pub spec: String,
+    pub created_at: i64,
+    /// 下一次运行时间（Unix 秒）
+    pub next_run: i64,
+    pub last_run: Option<i64>,
+    /// 上次运行失败的原因
+    pub last_error: Option<String>,
+}
+
+impl Schedule {
+    /// 校验触发规则并计算首次运行时间
+    pub fn new(path: &str, spec: &str, now: i64) -> Validated<Schedule> {
+        let trigger = Trigger::parse("cronOrInterval", spec)?;
+        let next_run = trigger.next_after(now).ok_or_else(|| {
+            ValidationError::new("cronOrInterval", ValidationCode::InvalidPattern, "该 cron 表达式永远不会触发")
+        })?;
+        Ok(Schedule {
+            id: uuid::Uuid::new_v4().to_string(),
+            path: path.to_string(),
+            spec: spec.trim().to_string(),
+            created_at: now,
+            next_run,
+            last_run: None,
+            last_error: None,
+        })
+    }
+}
+
+/// 定时扫描完成事件的负载
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledScanEvent {
+    pub schedule_id: String,
+    pub path: String,
+    pub started_at: i64,
+    pub duration_ms: u64,
+    pub success: bool,
+    pub total_size: i64,
+    pub file_count: usize,
+    pub dir_count: usize,
+    pub snapshot_id: Option<i64>,
+    pub error: Option<String>,
+    pub next_run: Option<i64>,
+}
+
+/// cron 的一段：允许值的位集合
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CronField {
+    bits: u64,
+    /// 以 "*" 开头（含 "*/n"），日与周的组合规则据此判断
+    any: bool,
+}
+
+impl CronField {
+    fn parse(field: &str, text: &str, min: u32, max: u32) -> Validated<CronField> {
+        let invalid = |msg: String| ValidationError::new(field, ValidationCode::InvalidPattern, msg);
+        let number = |s: &str| -> Validated<u32> {
+            s.parse::<u32>()
+                .ok()
+                .filter(|n| (min..=max).contains(n))
+                .ok_or_else(|| invalid(format!("“{}”不是 {}–{} 之间的数字", s, min, max)))
+        };
+
+        let mut bits = 0u64;
+        for part in text.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => (range, Some(step)),
+                None => (part, None),
+            };
+            let step = match step {
+                Some(s) => s.parse::<u32>().ok().filter(|&n| n > 0).ok_or_else(|| invalid(format!("步长“{}”无效", s)))?,
+                None => 1,
+            };
+            let (start, end) = if range == "*" {
+                (min, max)
+            } else if let Some((a, b)) = range.split_once('-') {
+                (number(a)?, number(b)?)
+            } else {
+                let n = number(range)?;
+                // "5/15" 表示从 5 起每 15
+                (n, if step > 1 { max } else { n })
+            };
+            if start > end {
+                return Err(invalid(format!("范围“{}”起点大于终点", range)));
+            }
+            for n in (start..=end).step_by(step as usize) {
+                bits |= 1 << n;
+            }
+        }
+        Ok(CronField { bits, any: text.starts_with('*') })
+    }
+
+    #[inline]
+    fn has(&self, n: u32) -> bool {
+        self.bits & (1 << n) != 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CronSpec {
+    minute: CronField,
+    hour: CronField,
+    day: CronField,
+    month: CronField,
+    weekday: CronField,
+}
+
+impl CronSpec {
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day = self.day.has(date.day());
+        let weekday = self.weekday.has(date.weekday().num_days_from_sunday());
+        if !self.day.any && !self.weekday.any {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+
+    /// after 之后（不含）的第一个匹配时刻
+    fn next_after(&self, after: i64) -> Option<i64> {
+        let start = Local.timestamp_opt(after, 0).single()?.naive_local();
+        let mut t = start.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        for _ in 0..MAX_CRON_STEPS {
+            if !self.month.has(t.month()) {
+                let (y, m) = if t.month() == 12 { (t.year() + 1, 1) } else { (t.year(), t.month() + 1) };
+                t = NaiveDate::from_ymd_opt(y, m, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.day_matches(t.date()) {
+                t = (t.date() + Duration::days(1)).and_hms_opt(0, 0, 0)?;
+            } else if !self.hour.has(t.hour()) {
+                t = t.with_minute(0)? + Duration::hours(1);
+            } else if !self.minute.has(t.minute()) {
+                t += Duration::minutes(1);
+            } else {
+                // 夏令时跳过的本地时刻不存在，顺延
+                match t.and_local_timezone(Local).earliest() {
+                    Some(time) => return Some(time.timestamp()),
+                    None => t += Duration::minutes(1),
+                }
+            }
+        }
+        None
+    }
+}
+
+/// 触发规则
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Trigger {
+    Interval(u64),
+    Cron(Box<CronSpec>),
+}
+
+impl Trigger {
+    fn parse(field: &str, spec: &str) -> Validated<Trigger> {
+        let spec = spec.trim();
+        let invalid = |msg: &str| ValidationError::new(field, ValidationCode::InvalidPattern, msg);
+        if spec.is_empty() {
+            return Err(ValidationError::new(field, ValidationCode::Required, "不能为空"));
+        }
+
+        let cron = match spec {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = cron.split_whitespace().collect();
+        if let [minute, hour, day, month, weekday] = fields[..] {
+            let mut weekday = CronField::parse(field, weekday, 0, 7)?;
+            // 7 与 0 都表示周日
+            if weekday.has(7) {
+                weekday.bits = (weekday.bits & !(1 << 7)) | 1;
+            }
+            return Ok(Trigger::Cron(Box::new(CronSpec {
+                minute: CronField::parse(field, minute, 0, 59)?,
+                hour: CronField::parse(field, hour, 0, 23)?,
+                day: CronField::parse(field, day, 1, 31)?,
+                month: CronField::parse(field, month, 1, 12)?,
+                weekday,
+            })));
+        }
+
+        let interval = spec.strip_prefix("every").map(str::trim_start).unwrap_or(spec);
+        let split = interval.find(|c: char| !c.is_ascii_digit()).unwrap_or(interval.len());
+        let (number, unit) = interval.split_at(split);
+        let unit_secs = match unit.trim() {
+            "s" => 1,
+            "m" => 60,
+            "h" => 3600,
+            "d" => 86_400,
+            "w" => 7 * 86_400,
+            _ => return Err(invalid("应为间隔（如 30m、6h、1d）或 5 段 cron 表达式")),
+        };
+        let secs = number
+            .parse::<u64>()
+            .ok()
+            .and_then(|n| n.checked_mul(unit_secs))
+            .ok_or_else(|| invalid("间隔数值无效"))?;
+        if secs < MIN_INTERVAL_SECS {
+            return Err(ValidationError::new(
+                field,
+                ValidationCode::OutOfRange,
+                format!("间隔不能短于 {} 分钟", MIN_INTERVAL_SECS / 60),
+            ));
+        }
+        Ok(Trigger::Interval(secs))
+    }
+
+    fn next_after(&self, after: i64) -> Option<i64> {
+        match self {
+            Trigger::Interval(secs) => after.checked_add(*secs as i64),
+            Trigger::Cron(cron) => cron.next_after(after),
+        }
+    }
+}
+
+fn schedules_path() -> Option<PathBuf> {
+    crate::migration::data_dir().ok().map(|dir| dir.join(SCHEDULES_FILE))
+}
+
+/// 读取计划；规则已无法解析的条目丢弃
+fn load_from(path: &Path) -> Vec<Schedule> {
+    let schedules: Vec<Schedule> = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+    schedules.into_iter().filter(|s| Trigger::parse("spec", &s.spec).is_ok()).collect()
+}
+
+fn save(schedules: &[Schedule]) -> anyhow::Result<()> {
+    let path = schedules_path().ok_or_else(|| anyhow::anyhow!("无法确定数据目录"))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(schedules)?)?;
+    Ok(())
+}
+
+/// 全部计划，按下一次运行时间排序
+pub fn list() -> Vec<Schedule> {
+    let mut schedules = SCHEDULES.read().clone();
+    schedules.sort_by_key(|s| s.next_run);
+    schedules
+}
+
+/// 添加计划并保存
+pub fn add(schedule: Schedule) -> anyhow::Result<()> {
+    let mut schedules = SCHEDULES.write();
+    schedules.push(schedule);
+    save(&schedules)
+}
+
+/// 删除计划，不存在时返回 false
+pub fn remove(id: &str) -> anyhow::Result<bool> {
+    let mut schedules = SCHEDULES.write();
+    let before = schedules.len();
+    schedules.retain(|s| s.id != id);
+    if schedules.len() == before {
+        return Ok(false);
+    }
+    save(&schedules)?;
+    Ok(true)
+}
+
+/// 记录一次运行并计算下一次时间（从 finished 起算），返回下一次时间；计划已被删除时返回 None
+fn record_run(id: &str, finished: i64, error: Option<String>) -> Option<i64> {
+    let mut schedules = SCHEDULES.write();
+    let schedule = schedules.iter_mut().find(|s| s.id == id)?;
+    schedule.last_run = Some(finished);
+    schedule.last_error = error;
+    // 规则在加载与添加时已校验；cron 意外无解时一年后再试
+    schedule.next_run = Trigger::parse("spec", &schedule.spec)
+        .ok()
+        .and_then(|t| t.next_after(finished))
+        .unwrap_or(finished + 365 * 86_400);
+    let next_run = schedule.next_run;
+    if let Err(e) = save(&schedules) {
+        eprintln!("[schedule] 保存计划失败: {}", e);
+    }
+    Some(next_run)
+}
+
+/// 以后台模式强制重扫并保存快照
+async fn run_one(schedule: &Schedule) -> anyhow::Result<(crate::scan::ScanResult, i64)> {
+    let options = crate::scan::ScanOptions { background: true, ..Default::default() };
+    let result = crate::scan::scan_directory_with_options(
+        &schedule.path,
+        true,
+        &options,
+        crate::perf::PerformanceMonitor::instance(),
+        None,
+    )
+    .await?;
+    let (path, snapshot) = (schedule.path.clone(), result.clone());
+    let snapshot_id = tokio::task::spawn_blocking(move || {
+        let file_count = snapshot.items.iter().filter(|i| !i.is_dir).count();
+        let dir_count = snapshot.items.len() - file_count;
+        crate::disk_cache::DiskCache::instance().insert_snapshot(&path, &snapshot, file_count, dir_count)
+    })
+    .await??;
+    Ok((result, snapshot_id))
+}
+
+/// 依次运行 now 时已到期的计划
+pub async fn run_due(now: i64, events: Option<&Events>) {
+    let due: Vec<Schedule> = SCHEDULES.read().iter().filter(|s| s.next_run <= now).cloned().collect();
+    for schedule in due {
+        let started_at = chrono::Utc::now().timestamp();
+        let start = std::time::Instant::now();
+        let outcome = run_one(&schedule).await;
+        let error = outcome.as_ref().err().map(|e| e.to_string());
+        let next_run = record_run(&schedule.id, chrono::Utc::now().timestamp(), error.clone());
+
+        let mut event = ScheduledScanEvent {
+            schedule_id: schedule.id.clone(),
+            path: schedule.path.clone(),
+            started_at,
+            duration_ms: start.elapsed().as_millis() as u64,
+            success: error.is_none(),
+            total_size: 0,
+            file_count: 0,
+            dir_count: 0,
+            snapshot_id: None,
+            error,
+            next_run,
+        };
+        if let Ok((result, snapshot_id)) = &outcome {
+            event.total_size = result.total_size;
+            event.file_count = result.items.iter().filter(|i| !i.is_dir).count();
+            event.dir_count = result.items.len() - event.file_count;
+            event.snapshot_id = Some(*snapshot_id);
+        }
+        if let Some(events) = events {
+            events.emit(SCHEDULE_EVENT, &event);
+        }
+    }
+}
+
+/// 定时检查并运行到期的计划（常驻，随进程退出）
+pub async fn run_loop(events: Option<Events>) {
+    let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        ticker.tick().await;
+        run_due(chrono::Utc::now().timestamp(), events.as_ref()).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    fn local(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> i64 {
+        NaiveDate::from_ymd_opt(y, mo, d)
+            .unwrap()
+            .and_hms_opt(h, mi, 0)
+            .unwrap()
+            .and_local_timezone(Local)
+            .unwrap()
+            .timestamp()
+    }
+
+    fn next(spec: &str, after: i64) -> Option<NaiveDateTime> {
+        let t = Trigger::parse("spec", spec).unwrap().next_after(after)?;
+        Some(Local.timestamp_opt(t, 0).unwrap().naive_local())
+    }
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> Option<NaiveDateTime> {
+        NaiveDate::from_ymd_opt(y, mo, d).unwrap().and_hms_opt(h, mi, 0)
+    }
+
+    #[test]
+    fn parses_intervals_and_computes_cron_times() {
+        assert_eq!(Trigger::parse("f", "6h"), Ok(Trigger::Interval(6 * 3600)));
+        assert_eq!(Trigger::parse("f", "every 30m"), Ok(Trigger::Interval(1800)));
+        assert_eq!(Trigger::parse("f", "1d").unwrap().next_after(100), Some(100 + 86_400));
+        assert_eq!(Trigger::parse("f", "60s").unwrap_err().code, ValidationCode::OutOfRange);
+        assert_eq!(Trigger::parse("f", "").unwrap_err().code, ValidationCode::Required);
+        for bad in ["6x", "0 24 * * *", "*/0 * * * *", "5-1 * * * *", "* * *"] {
+            assert_eq!(Trigger::parse("f", bad).unwrap_err().code, ValidationCode::InvalidPattern, "{}", bad);
+        }
+
+        // 2026-01-05 为周一
+        let monday = local(2026, 1, 5, 10, 30);
+        assert_eq!(next("0 3 * * *", monday), at(2026, 1, 6, 3, 0));
+        assert_eq!(next("*/15 * * * *", monday), at(2026, 1, 5, 10, 45));
+        assert_eq!(next("0 9-17/4 * * 1-5", monday), at(2026, 1, 5, 13, 0));
+        assert_eq!(next("30 2 * * 7", monday), at(2026, 1, 11, 2, 30));
+        assert_eq!(next("@monthly", monday), at(2026, 2, 1, 0, 0));
+        // 日与周都受限时满足其一：1 号或周三
+        assert_eq!(next("0 0 1 * 3", monday), at(2026, 1, 7, 0, 0));
+        assert_eq!(next("0 0 30 2 *", monday), None);
+        assert!(Schedule::new("/data", "0 0 30 2 *", monday).is_err());
+
+        let schedule = Schedule::new("/data", " @daily ", monday).unwrap();
+        assert_eq!((schedule.spec.as_str(), schedule.next_run), ("@daily", local(2026, 1, 6, 0, 0)));
+    }
+}
@@ -0,0 +1,354 @@
+// 前端类型定义生成：由 Rust 类型导出 JSON Schema 与 TypeScript 声明
+//
+// 命令返回值 / 参数 / 事件载荷的结构以本库的 serde 类型为准，schemars 从类型推导
+// JSON Schema，再由下面的转换器生成 .d.ts；二进制协议版本与 ItemKind 编码另生成 JS 常量，
+// 供 scanBinary.js 解码器直接引用。生成结果提交在 app/src/types/ 下，
+// 单元测试比对提交内容与当前类型，类型改动后未重新生成即失败：
+//
+//   cargo run -p flashdir-core --example gen_schema
+
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use serde_json::{json, Map, Value};
+use std::fmt::Write;
+
+/// 生成文件相对 src-tauri 的输出目录
+pub const OUTPUT_DIR: &str = "app/src/types";
+
+const HEADER: &str = "// 由 cargo run -p flashdir-core --example gen_schema 生成，请勿手动修改";
+
+/// 注册所有对外载荷类型（命令参数 / 返回值 / 事件）
+fn payload_generator() -> SchemaGenerator {
+    let mut gen = SchemaSettings::draft07().into_generator();
+
+    gen.subschema_for::<crate::scan::ScanResponse>();
+    gen.subschema_for::<crate::scan::ScanResult>();
+    gen.subschema_for::<crate::scan::ScanOptions>();
+    gen.subschema_for::<crate::scan::CountResult>();
+    gen.subschema_for::<crate::estimate::SizeEstimate>();
+    gen.subschema_for::<crate::level_scan::LevelResult>();
+    gen.subschema_for::<crate::dir_listing::DirListing>();
+    gen.subschema_for::<crate::scan::HistoryItem>();
+    gen.subschema_for::<crate::scan::HistoryItemSummary>();
+    gen.subschema_for::<crate::progress::ScanProgress>();
+    gen.subschema_for::<crate::perf::ScanMetrics>();
+    gen.subschema_for::<crate::perf::PerformanceSummary>();
+    gen.subschema_for::<crate::disk_cache::CacheStats>();
+    gen.subschema_for::<crate::disk_cache::CompactResult>();
+    gen.subschema_for::<crate::scan::MemoryCacheStats>();
+    gen.subschema_for::<crate::scan_failures::RecentFailure>();
+    gen.subschema_for::<crate::disk_cache::CacheEntryInfo>();
+    gen.subschema_for::<crate::disk_cache::EventRecord>();
+    gen.subschema_for::<crate::disk_cache::EventFilter>();
+    gen.subschema_for::<crate::disk_cache::SnapshotInfo>();
+    gen.subschema_for::<crate::migration::MigrationStatus>();
+    gen.subschema_for::<crate::fs::VolumeInfo>();
+    gen.subschema_for::<crate::dev_analyzer::DevAnalysisResult>();
+    gen.subschema_for::<crate::tiering::TieringReport>();
+    gen.subschema_for::<crate::file_age::FileAgeReport>();
+    gen.subschema_for::<crate::small_files::SmallFileReport>();
+    gen.subschema_for::<crate::git_repos::GitRepoReport>();
+    gen.subschema_for::<crate::app_data::AppDataReport>();
+    gen.subschema_for::<crate::pkg_caches::PackageCacheReport>();
+    gen.subschema_for::<crate::pkg_caches::CleanOutput>();
+    gen.subschema_for::<crate::schedule::Schedule>();
+    gen.subschema_for::<crate::schedule::ScheduledScanEvent>();
+    gen.subschema_for::<crate::idle_scan::IdleStatus>();
+    gen.subschema_for::<crate::idle_scan::IdleScanEvent>();
+    gen.subschema_for::<crate::prewarm::PrewarmEvent>();
+    gen.subschema_for::<crate::owner_report::OwnerStat>();
+    gen.subschema_for::<crate::hashing::HashAlgorithm>();
+    gen.subschema_for::<crate::hashing::FileHash>();
+    gen.subschema_for::<crate::hashing::HashProgress>();
+    gen.subschema_for::<crate::autocomplete::PathSuggestion>();
+    gen.subschema_for::<crate::frecency::FrecentPath>();
+    gen.subschema_for::<crate::selection::SelectionMode>();
+    gen.subschema_for::<crate::selection::SelectionTarget>();
+    gen.subschema_for::<crate::selection::SelectionSummary>();
+    gen.subschema_for::<crate::selection::BulkActionResult>();
+    gen.subschema_for::<crate::selection::ExportFormat>();
+    gen.subschema_for::<crate::diff_engine::SnapshotDiff>();
+    gen.subschema_for::<crate::diff_engine::ScanComparison>();
+    gen.subschema_for::<crate::size_history::SizePoint>();
+    gen.subschema_for::<crate::global_search::IndexState>();
+    gen.subschema_for::<crate::global_search::IndexEntry>();
+    gen.subschema_for::<crate::system_scan::SystemScanResult>();
+    gen.subschema_for::<crate::validate::ValidationError>();
+    gen.subschema_for::<crate::operation::OperationProgress>();
+    gen.subschema_for::<crate::settings::Settings>();
+    gen.subschema_for::<crate::report_style::ReportStyle>();
+    gen.subschema_for::<crate::treemap::TreemapLayout>();
+    gen.subschema_for::<crate::exclusions::ExclusionPresetInfo>();
+    gen.subschema_for::<crate::path_anomalies::PathAnomalyReport>();
+    gen.subschema_for::<crate::permission_report::PermissionReport>();
+    gen.subschema_for::<crate::scan_profiles::SavedProfile>();
+    gen.subschema_for::<crate::recycle_bin::RecycleBinReport>();
+    gen.subschema_for::<crate::file_ops::FileOpResult>();
+    gen.subschema_for::<crate::file_ops::DeleteConfirmation>();
+    gen.subschema_for::<crate::file_ops::TransferResult>();
+    gen.subschema_for::<crate::file_ops::ConflictPolicy>();
+    gen.subschema_for::<crate::file_ops::RenameResult>();
+    gen.subschema_for::<crate::jobs::Job>();
+    gen.subschema_for::<crate::archive::ArchiveEstimate>();
+    gen.subschema_for::<crate::archive::ArchiveResult>();
+    gen.subschema_for::<crate::item_details::ItemDetails>();
+    gen.subschema_for::<crate::preview::FilePreview>();
+    gen.subschema_for::<crate::export::ExportColumn>();
+    gen.subschema_for::<crate::export::ScanExportResult>();
+    gen.subschema_for::<crate::report::ReportOptions>();
+    gen.subschema_for::<crate::report::ReportResult>();
+    gen.subschema_for::<crate::clipboard::ClipboardResult>();
+    gen.subschema_for::<crate::cache_transfer::CacheManifest>();
+    gen.subschema_for::<crate::cache_transfer::CacheExportResult>();
+    gen.subschema_for::<crate::cache_transfer::CacheImportResult>();
+
+    gen
+}
+
+/// 所有载荷类型的 JSON Schema（draft-07，类型位于 definitions 下）
+pub fn json_schema() -> Value {
+    let gen = payload_generator();
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "FlashDir",
+        "definitions": serde_json::to_value(gen.definitions()).expect("schema 序列化失败"),
+    })
+}
+
+/// 由 JSON Schema 生成的 TypeScript 声明
+pub fn typescript() -> String {
+    let schema = json_schema();
+    let mut out = format!("{}\n", HEADER);
+    for (name, def) in schema["definitions"].as_object().into_iter().flatten() {
+        out.push('\n');
+        write_doc(&mut out, def, "");
+        if def.get("properties").is_some() {
+            let _ = writeln!(out, "export interface {} {}", name, ts_object(def, ""));
+        } else {
+            let _ = writeln!(out, "export type {} = {};", name, ts_type(def, ""));
+        }
+    }
+    out
+}
+
+/// 二进制协议常量（scanBinary.js 引用）
+pub fn protocol_js() -> String {
+    let schema = json_schema();
+    // ItemKind 按声明顺序即判别值顺序（判别值从 0 连续递增，只能追加）
+    let mut kinds = Vec::new();
+    enum_values(&schema["definitions"]["ItemKind"], &mut kinds);
+    let mut categories = Vec::new();
+    enum_values(&schema["definitions"]["FileCategory"], &mut categories);
+    format!(
+        "{}\n\n// 后端 scan::BINARY_PROTOCOL_VERSION，解码器支持的最高版本\nexport const BINARY_PROTOCOL_VERSION = {}\n\n// 与后端 ItemKind 判别值一一对应\nexport const ITEM_KINDS = {}\n\n// 与后端 FileCategory 判别值一一对应\nexport const FILE_CATEGORIES = {}\n",
+        HEADER,
+        crate::scan::BINARY_PROTOCOL_VERSION,
+        serde_json::to_string(&kinds).expect("序列化失败"),
+        serde_json::to_string(&categories).expect("序列化失败"),
+    )
+}
+
+/// 全部生成文件：(文件名, 内容)
+pub fn generated_files() -> Vec<(&'static str, String)> {
+    let mut schema = serde_json::to_string_pretty(&json_schema()).expect("schema 序列化失败");
+    schema.push('\n');
+    vec![
+        ("flashdir.schema.json", schema),
+        ("flashdir.d.ts", typescript()),
+        ("protocol.js", protocol_js()),
+    ]
+}
+
+/// 按出现顺序收集字符串枚举值（带文档的 variant 会被拆到 oneOf 的各分支里）
+fn enum_values<'a>(schema: &'a Value, out: &mut Vec<&'a str>) {
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        out.extend(values.iter().filter_map(Value::as_str));
+    }
+    for variant in schema.get("oneOf").and_then(Value::as_array).into_iter().flatten() {
+        enum_values(variant, out);
+    }
+}
+
+fn write_doc(out: &mut String, schema: &Value, indent: &str) {
+    let Some(desc) = schema.get("description").and_then(Value::as_str) else {
+        return;
+    };
+    let lines: Vec<&str> = desc.lines().collect();
+    if lines.len() == 1 {
+        let _ = writeln!(out, "{}/** {} */", indent, lines[0]);
+    } else {
+        let _ = writeln!(out, "{}/**", indent);
+        for line in lines {
+            let _ = writeln!(out, "{} * {}", indent, line);
+        }
+        let _ = writeln!(out, "{} */", indent);
+    }
+}
+
+/// 对象类型（interface 体或内联对象）
+fn ts_object(schema: &Value, indent: &str) -> String {
+    let empty = Map::new();
+    let props = schema.get("properties").and_then(Value::as_object).unwrap_or(&empty);
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+
+    let inner = format!("{}  ", indent);
+    let mut out = String::from("{\n");
+    for (name, prop) in props {
+        write_doc(&mut out, prop, &inner);
+        let optional = if required.contains(&name.as_str()) { "" } else { "?" };
+        let _ = writeln!(out, "{}{}{}: {};", inner, name, optional, ts_type(prop, &inner));
+    }
+    let _ = write!(out, "{}}}", indent);
+    out
+}
+
+/// JSON Schema 片段 → TypeScript 类型表达式
+fn ts_type(schema: &Value, indent: &str) -> String {
+    let Some(obj) = schema.as_object() else {
+        return "unknown".to_string();
+    };
+
+    if let Some(r) = obj.get("$ref").and_then(Value::as_str) {
+        return r.rsplit('/').next().unwrap_or(r).to_string();
+    }
+    if let Some(c) = obj.get("const") {
+        return c.to_string();
+    }
+    if let Some(values) = obj.get("enum").and_then(Value::as_array) {
+        return values.iter().map(Value::to_string).collect::<Vec<_>>().join(" | ");
+    }
+    for key in ["allOf", "anyOf", "oneOf"] {
+        if let Some(variants) = obj.get(key).and_then(Value::as_array) {
+            // allOf 只由 schemars 用于给 $ref 附加说明，单元素
+            let sep = if key == "allOf" { " & " } else { " | " };
+            return union(variants.iter().map(|v| ts_type(v, indent)), sep);
+        }
+    }
+
+    match obj.get("type") {
+        Some(Value::String(t)) => ts_primitive(t, obj, indent),
+        Some(Value::Array(types)) => union(
+            types.iter().filter_map(Value::as_str).map(|t| ts_primitive(t, obj, indent)),
+            " | ",
+        ),
+        _ if obj.contains_key("properties") => ts_object(schema, indent),
+        _ => "unknown".to_string(),
+    }
+}
+
+fn ts_primitive(t: &str, obj: &Map<String, Value>, indent: &str) -> String {
+    match t {
+        "string" => "string".to_string(),
+        "integer" | "number" => "number".to_string(),
+        "boolean" => "boolean".to_string(),
+        "null" => "null".to_string(),
+        "array" => match obj.get("items") {
+            // 定长元组
+            Some(Value::Array(items)) => format!(
+                "[{}]",
+                items.iter().map(|i| ts_type(i, indent)).collect::<Vec<_>>().join(", ")
+            ),
+            Some(items) => {
+                let inner = ts_type(items, indent);
+                if inner.contains(' ') && !inner.starts_with('{') {
+                    format!("({})[]", inner)
+                } else {
+                    format!("{}[]", inner)
+                }
+            }
+            None => "unknown[]".to_string(),
+        },
+        "object" => {
+            if obj.contains_key("properties") {
+                ts_object(&Value::Object(obj.clone()), indent)
+            } else {
+                match obj.get("additionalProperties") {
+                    Some(v @ Value::Object(_)) => format!("Record<string, {}>", ts_type(v, indent)),
+                    _ => "Record<string, unknown>".to_string(),
+                }
+            }
+        }
+        _ => "unknown".to_string(),
+    }
+}
+
+fn union(parts: impl Iterator<Item = String>, sep: &str) -> String {
+    let mut seen: Vec<String> = Vec::new();
+    for p in parts {
+        if !seen.contains(&p) {
+            seen.push(p);
+        }
+    }
+    seen.join(sep)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// 提交的生成文件须与当前 Rust 类型一致
+    #[test]
+    fn generated_files_up_to_date() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join(OUTPUT_DIR);
+        for (name, content) in generated_files() {
+            let committed = std::fs::read_to_string(dir.join(name)).unwrap_or_default();
+            assert!(
+                committed.replace("\r\n", "\n") == content,
+                "{} 与 Rust 类型不一致，请运行 cargo run -p flashdir-core --example gen_schema",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn item_kinds_follow_discriminants() {
+        let schema = json_schema();
+        let mut kinds = Vec::new();
+        enum_values(&schema["definitions"]["ItemKind"], &mut kinds);
+        for (i, kind) in kinds.iter().enumerate() {
+            let parsed: crate::scan::ItemKind = serde_json::from_value(json!(kind)).unwrap();
+            assert_eq!(parsed as usize, i);
+        }
+        assert_eq!(kinds.len(), crate::scan::ItemKind::SmallFiles as usize + 1);
+
+        let mut categories = Vec::new();
+        enum_values(&schema["definitions"]["FileCategory"], &mut categories);
+        for (i, category) in categories.iter().enumerate() {
+            let parsed: crate::categories::FileCategory = serde_json::from_value(json!(category)).unwrap();
+            assert_eq!(parsed as usize, i);
+        }
+        assert_eq!(categories.len(), crate::categories::FileCategory::ALL.len());
+    }
+
+    /// WASM 排序模块的 WasmItem 字段须是 Item 的子集（名称一致）
+    #[test]
+    fn wasm_item_matches_item() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../wasm-sort/src/lib.rs");
+        let Ok(src) = std::fs::read_to_string(path) else {
+            return;
+        };
+        let schema = json_schema();
+        let item_props = schema["definitions"]["Item"]["properties"].as_object().unwrap();
+
+        let body = src.split("pub struct WasmItem {").nth(1).unwrap().split('}').next().unwrap();
+        let fields: Vec<String> = body
+            .lines()
+            .filter_map(|l| l.trim().strip_prefix("pub ")?.split(':').next())
+            .map(|f| {
+                let mut parts = f.split('_');
+                let first = parts.next().unwrap_or_default().to_string();
+                parts.fold(first, |acc, p| acc + p[..1].to_uppercase().as_str() + &p[1..])
+            })
+            .collect();
+        assert!(!fields.is_empty());
+        for f in fields {
+            assert!(item_props.contains_key(&f), "WasmItem.{} 不在 Item 中", f);
+        }
+    }
+}
@@ -0,0 +1,371 @@
+// 选择集管理与批量操作
+//
+// 前端的选中状态保存在后端（按会话 = 扫描根路径），以稳定 item ID 为 key，
+// 全选 / 区间选择 / 批量删除 / 批量导出都只传 ID 或行号区间，
+// 百万级选择也不需要把路径列表在 IPC 上来回传输。
+//
+// 行号区间以后端扫描结果的顺序（按大小降序）为准。
+
+use lazy_static::lazy_static;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use std::collections::{HashMap, HashSet};
+use crate::scan::{Item, format_size};
+use crate::operation::Operation;
+
+/// 选择操作方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SelectionMode {
+    /// 替换为目标集合
+    Set,
+    Add,
+    Remove,
+    Toggle,
+}
+
+/// 选择目标
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum SelectionTarget {
+    Ids { ids: Vec<u64> },
+    /// 行号区间 [start, end)，按扫描结果顺序
+    Range { start: usize, end: usize },
+    All,
+}
+
+/// 选择集摘要
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SelectionSummary {
+    pub session: String,
+    pub count: usize,
+    /// 选中内容的总大小（已选目录下的子项不重复计算）
+    pub total_size: i64,
+    pub total_size_formatted: String,
+}
+
+/// 批量操作中单项失败
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkFailure {
+    pub path: String,
+    pub error: String,
+}
+
+/// 批量操作结果
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkActionResult {
+    pub succeeded: usize,
+    pub failed: Vec<BulkFailure>,
+    pub affected_bytes: i64,
+    pub affected_bytes_formatted: String,
+}
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    /// 每行一个路径
+    Txt,
+}
+
+pub struct SelectionStore {
+    sessions: RwLock<HashMap<String, HashSet<u64>>>,
+}
+
+lazy_static! {
+    static ref STORE: SelectionStore = SelectionStore {
+        sessions: RwLock::new(HashMap::new()),
+    };
+}
+
+impl SelectionStore {
+    pub fn instance() -> &'static SelectionStore {
+        &STORE
+    }
+
+    /// 应用一次选择操作；`items` 为会话对应的扫描结果（解析区间/全选时需要）
+    pub fn update(
+        &self,
+        session: &str,
+        mode: SelectionMode,
+        target: &SelectionTarget,
+        items: &[Item],
+    ) -> SelectionSummary {
+        let target_ids: Vec<u64> = match target {
+            SelectionTarget::Ids { ids } => ids.clone(),
+            SelectionTarget::Range { start, end } => {
+                let end = (*end).min(items.len());
+                let start = (*start).min(end);
+                items[start..end].iter().map(|i| i.id).collect()
+            }
+            SelectionTarget::All => items.iter().map(|i| i.id).collect(),
+        };
+
+        let mut sessions = self.sessions.write();
+        let selected = sessions.entry(session.to_string()).or_default();
+        match mode {
+            SelectionMode::Set => {
+                selected.clear();
+                selected.extend(target_ids);
+            }
+            SelectionMode::Add => selected.extend(target_ids),
+            SelectionMode::Remove => {
+                for id in target_ids {
+                    selected.remove(&id);
+                }
+            }
+            SelectionMode::Toggle => {
+                for id in target_ids {
+                    if !selected.remove(&id) {
+                        selected.insert(id);
+                    }
+                }
+            }
+        }
+        let selected = selected.clone();
+        drop(sessions);
+
+        summarize(session, &selected, items)
+    }
+
+    pub fn ids(&self, session: &str) -> HashSet<u64> {
+        self.sessions.read().get(session).cloned().unwrap_or_default()
+    }
+
+    pub fn clear(&self, session: &str) {
+        self.sessions.write().remove(session);
+    }
+
+    /// 从选择集中移除指定 ID（批量删除成功后调用）
+    pub fn remove_ids(&self, session: &str, ids: &[u64]) {
+        if let Some(selected) = self.sessions.write().get_mut(session) {
+            for id in ids {
+                selected.remove(id);
+            }
+        }
+    }
+
+    pub fn summary(&self, session: &str, items: &[Item]) -> SelectionSummary {
+        summarize(session, &self.ids(session), items)
+    }
+}
+
+fn summarize(session: &str, selected: &HashSet<u64>, items: &[Item]) -> SelectionSummary {
+    let total_size: i64 = top_level_selected(selected, items).iter().map(|i| i.size).sum();
+    SelectionSummary {
+        session: session.to_string(),
+        count: selected.len(),
+        total_size,
+        total_size_formatted: format_size(total_size).to_string(),
+    }
+}
+
+/// 选中的 items 中去掉"祖先目录也被选中"的项，避免重复计算/重复删除
+pub fn top_level_selected<'a>(selected: &HashSet<u64>, items: &'a [Item]) -> Vec<&'a Item> {
+    let chosen: Vec<&Item> = items.iter().filter(|i| selected.contains(&i.id)).collect();
+    let selected_dirs: HashSet<&str> = chosen
+        .iter()
+        .filter(|i| i.is_dir)
+        .map(|i| i.path.as_str())
+        .collect();
+
+    chosen
+        .into_iter()
+        .filter(|item| {
+            let path = item.path.as_str();
+            !path
+                .match_indices('/')
+                .any(|(pos, _)| pos > 0 && selected_dirs.contains(&path[..pos]))
+        })
+        .collect()
+}
+
+/// 永久删除选中内容（目录递归删除）；返回逐项结果，成功的 ID 会从选择集中移除
+pub fn delete_selected(session: &str, items: &[Item]) -> BulkActionResult {
+    let store = SelectionStore::instance();
+    let selected = store.ids(session);
+
+    let mut succeeded = 0usize;
+    let mut affected_bytes = 0i64;
+    let mut failed = Vec::new();
+    let mut deleted_paths: HashSet<&str> = HashSet::new();
+
+    for item in top_level_selected(&selected, items) {
+        let outcome = if item.is_dir {
+            std::fs::remove_dir_all(item.path.as_str())
+        } else {
+            std::fs::remove_file(item.path.as_str())
+        };
+        match outcome {
+            Ok(()) => {
+                succeeded += 1;
+                affected_bytes += item.size;
+                deleted_paths.insert(item.path.as_str());
+            }
+            Err(e) => failed.push(BulkFailure {
+                path: item.path.to_string(),
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    // 已删除项及其下被一并删除的已选子项都移出选择集
+    let removed_ids: Vec<u64> = items
+        .iter()
+        .filter(|i| selected.contains(&i.id))
+        .filter(|i| {
+            let path = i.path.as_str();
+            deleted_paths.contains(path)
+                || path
+                    .match_indices('/')
+                    .any(|(pos, _)| pos > 0 && deleted_paths.contains(&path[..pos]))
+        })
+        .map(|i| i.id)
+        .collect();
+    store.remove_ids(session, &removed_ids);
+
+    BulkActionResult {
+        succeeded,
+        failed,
+        affected_bytes,
+        affected_bytes_formatted: format_size(affected_bytes).to_string(),
+    }
+}
+
+/// 把选中项（全部选中项，不做祖先去重）导出到文件
+pub fn export_selected(
+    session: &str,
+    items: &[Item],
+    dest: &str,
+    format: ExportFormat,
+) -> anyhow::Result<BulkActionResult> {
+    export_selected_with(session, items, dest, format, &Operation::start("export_selected", None, None))
+}
+
+/// 导出并经 op 上报进度；取消或写入失败时删除未写完的文件
+pub fn export_selected_with(
+    session: &str,
+    items: &[Item],
+    dest: &str,
+    format: ExportFormat,
+    op: &Operation,
+) -> anyhow::Result<BulkActionResult> {
+    let selected = SelectionStore::instance().ids(session);
+    let chosen: Vec<&Item> = items.iter().filter(|i| selected.contains(&i.id)).collect();
+
+    if let Err(e) = write_export(&chosen, dest, format, op) {
+        std::fs::remove_file(dest).ok();
+        return Err(e);
+    }
+
+    let affected_bytes: i64 = chosen.iter().map(|i| i.size).sum();
+    Ok(BulkActionResult {
+        succeeded: chosen.len(),
+        failed: Vec::new(),
+        affected_bytes,
+        affected_bytes_formatted: format_size(affected_bytes).to_string(),
+    })
+}
+
+fn write_export(chosen: &[&Item], dest: &str, format: ExportFormat, op: &Operation) -> anyhow::Result<()> {
+    use serde::ser::{SerializeSeq, Serializer};
+    use std::io::Write;
+
+    // 每写出 CHUNK 行检查一次取消、上报一次进度
+    const CHUNK: usize = 10_000;
+    let total = chosen.len().max(1) as f64;
+    let tick = |written: usize| -> anyhow::Result<()> {
+        if written.is_multiple_of(CHUNK) {
+            op.check()?;
+            op.progress("write", written as f64 * 100.0 / total);
+        }
+        Ok(())
+    };
+
+    let mut out = std::io::BufWriter::new(std::fs::File::create(dest)?);
+    match format {
+        ExportFormat::Csv => {
+            writeln!(out, "path,name,size,is_dir,modified,created")?;
+            for (n, i) in chosen.iter().enumerate() {
+                tick(n)?;
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{}",
+                    csv_field(&i.path),
+                    csv_field(&i.name),
+                    i.size,
+                    i.is_dir,
+                    i.modified,
+                    i.created
+                )?;
+            }
+        }
+        ExportFormat::Json => {
+            // 逐项序列化（输出与 to_writer_pretty 相同），以便在项之间上报进度
+            let mut ser = serde_json::Serializer::pretty(&mut out);
+            let mut seq = ser.serialize_seq(Some(chosen.len()))?;
+            for (n, i) in chosen.iter().enumerate() {
+                tick(n)?;
+                seq.serialize_element(i)?;
+            }
+            seq.end()?;
+        }
+        ExportFormat::Txt => {
+            for (n, i) in chosen.iter().enumerate() {
+                tick(n)?;
+                writeln!(out, "{}", i.path)?;
+            }
+        }
+    }
+    op.progress("flush", 100.0);
+    out.flush()?;
+    Ok(())
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(path: &str, size: i64, is_dir: bool, id: u64) -> Item {
+        let item = Item::test(path, size, is_dir);
+        Item { id, ..item }
+    }
+
+    #[test]
+    fn range_toggle_and_nested_dedup() {
+        let items = vec![
+            item("r/d", 30, true, 1),
+            item("r/d/a", 20, false, 2),
+            item("r/d/b", 10, false, 3),
+            item("r/e", 5, false, 4),
+        ];
+        let store = SelectionStore::instance();
+        let session = "test-session-range";
+
+        let s = store.update(session, SelectionMode::Set, &SelectionTarget::Range { start: 0, end: 2 }, &items);
+        assert_eq!(s.count, 2);
+        // r/d/a 位于已选目录 r/d 下，不重复计算
+        assert_eq!(s.total_size, 30);
+
+        let s = store.update(session, SelectionMode::Toggle, &SelectionTarget::Ids { ids: vec![1, 4] }, &items);
+        assert_eq!(s.count, 2);
+        assert_eq!(s.total_size, 25);
+
+        store.clear(session);
+        assert_eq!(store.summary(session, &items).count, 0);
+    }
+}
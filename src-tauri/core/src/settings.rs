@@ -0,0 +1,482 @@
+// 用户设置
+//
+// 保存在 ~/.flashdir/settings.json，进程内缓存一份；桌面端经 get_settings / update_settings
+// 读写，CLI 可用命令行参数临时覆盖（只影响本进程，不写文件）。
+// 文件缺失、损坏或字段缺省时一律取默认值；遍历参数的默认值与引入设置之前的行为一致。
+
+use crate::validate::{ValidationCode, ValidationError, Validated};
+use lazy_static::lazy_static;
+use parking_lot::RwLock;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// 遍历线程数上限
+pub const MAX_SCAN_THREADS: usize = 256;
+
+/// 文件操作队列同时执行的作业数上限
+pub const MAX_PARALLEL_JOBS: usize = 8;
+
+lazy_static! {
+    static ref CURRENT: RwLock<Settings> = RwLock::new(settings_path().map(|p| load_from(&p)).unwrap_or_default());
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub scan: ScanSettings,
+    pub cache: CacheSettings,
+    pub appearance: AppearanceSettings,
+    pub idle: IdleScanSettings,
+    pub prewarm: PrewarmSettings,
+    pub file_ops: FileOpsSettings,
+}
+
+/// 目录遍历参数
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScanSettings {
+    /// 本地磁盘的遍历线程数；None 为按 CPU 自动（核数 × 2，限制在 8–32）。
+    /// 网络路径仍使用低并发参数
+    pub threads: Option<usize>,
+    /// 共享目录队列的容量上限；队列满时 worker 把新发现的子目录留在本线程内深度优先处理，
+    /// 超大目录树的待遍历队列不再无限增长。None 为按介质默认（机械硬盘 64，其他不限）
+    pub queue_capacity: Option<usize>,
+    /// 单线程确定性模式（调试用）：1 个 worker，按名称顺序读取条目、忽略上次快照的优先级提示，
+    /// 同一目录树每次扫描的遍历顺序、事件顺序与结果顺序都相同
+    pub deterministic: bool,
+}
+
+/// 扫描结果缓存的保留策略：内存中的会话与磁盘缓存。
+/// 修改后经 scan::apply_cache_policy 立即作用于已有的缓存
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CacheSettings {
+    /// 进程常驻内存（RSS）超过此值（MB）时，按最久未查看的顺序关闭内存中的会话，
+    /// 再次打开时透明地从磁盘缓存读回。None 为物理内存的 1/4
+    pub rss_limit_mb: Option<u64>,
+    /// 无论内存占用如何都保留的最近查看的会话数
+    pub keep_sessions: usize,
+    /// 缓存的有效期（天）：超过此时间未被访问的磁盘缓存条目被删除，
+    /// 超过此时间的内存缓存不再作为命中返回
+    pub ttl_days: u32,
+    /// 磁盘缓存中扫描结果的总大小上限（MB），超出时淘汰最久未访问的条目
+    pub max_disk_mb: u64,
+    /// 从不缓存的目录（含其子目录）：扫描结果不写入磁盘缓存，再次扫描总是完整遍历；
+    /// 本次打开的结果仍保留在内存中供浏览
+    pub never_cache: Vec<String>,
+    /// 命中缓存前抽样检查的子目录数（见 dir_digest）：任一子目录在扫描后有增删即按未命中处理；
+    /// 0 为只校验根目录 mtime
+    pub validation_samples: usize,
+    /// 内存缓存保留的扫描结果数上限，超出时关闭最久未查看的会话（低内存的机器可调小）
+    pub memory_max_entries: usize,
+    /// 内存缓存中扫描结果的估计大小上限（MB）
+    pub memory_max_mb: u64,
+    /// 空闲时自动压缩磁盘缓存数据库（见 DiskCache::compact），空闲条件同 idle，但不要求开启空闲扫描
+    pub compact_when_idle: bool,
+    /// 空闲页（删除缓存留下的空间）达到此值（MB）时才自动压缩
+    pub compact_min_free_mb: u64,
+}
+
+/// 内存缓存条数上限的最大值
+pub const MAX_MEMORY_CACHE_ENTRIES: usize = 1000;
+
+/// 内存缓存大小上限的最大值（MB）
+pub const MAX_MEMORY_CACHE_MB: u64 = 64 * 1024;
+
+/// 每次命中前抽样检查的子目录数上限
+pub const MAX_VALIDATION_SAMPLES: usize = 10_000;
+
+/// 缓存有效期上限（天）
+pub const MAX_CACHE_TTL_DAYS: u32 = 3650;
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self {
+            rss_limit_mb: None,
+            keep_sessions: 1,
+            ttl_days: 7,
+            max_disk_mb: 500,
+            never_cache: Vec::new(),
+            validation_samples: 64,
+            memory_max_entries: 30,
+            memory_max_mb: 200,
+            compact_when_idle: true,
+            compact_min_free_mb: 64,
+        }
+    }
+}
+
+impl CacheSettings {
+    /// 生效的 RSS 上限（字节）
+    pub fn rss_limit_bytes(&self, total_memory: u64) -> u64 {
+        match self.rss_limit_mb {
+            Some(mb) => mb * 1024 * 1024,
+            None => total_memory / 4,
+        }
+    }
+
+    /// 有效期（秒）
+    pub fn ttl_secs(&self) -> i64 {
+        i64::from(self.ttl_days) * 24 * 3600
+    }
+
+    /// 在 Unix 秒 cached_at 写入 / 访问的缓存此刻是否已过期
+    pub fn is_expired(&self, cached_at: i64) -> bool {
+        chrono::Utc::now().timestamp() - cached_at > self.ttl_secs()
+    }
+
+    /// 磁盘缓存上限（字节）
+    pub fn max_disk_bytes(&self) -> u64 {
+        self.max_disk_mb * 1024 * 1024
+    }
+
+    /// 缓存 key 是否落在 never_cache 规则内（规则目录本身或其子目录，含带排除 / 包含修饰的 key）
+    pub fn never_caches(&self, key: &str) -> bool {
+        let root = key.split('|').next().unwrap_or(key);
+        self.never_cache.iter().any(|rule| {
+            let rule = crate::fs::cache_key(rule);
+            root == rule || crate::fs::is_child_cache_key(&rule, root)
+        })
+    }
+}
+
+/// 主题偏好
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ThemePreference {
+    /// 跟随系统深色 / 浅色设置
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+/// 生成报告时使用的外观（见 report_style）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppearanceSettings {
+    pub theme: ThemePreference,
+    /// BCP 47 语言标记（如 "zh-CN"、"en-US"、"ar-EG"），决定日期 / 数字格式与书写方向；
+    /// None 为跟随系统
+    pub locale: Option<String>,
+}
+
+/// 空闲时自动刷新收藏路径的扫描结果（见 idle_scan），默认关闭
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IdleScanSettings {
+    pub enabled: bool,
+    /// 无键盘 / 鼠标输入超过此分钟数视为空闲
+    pub idle_minutes: u64,
+    /// 系统 CPU 使用率不高于此百分比（1–100）时才扫描
+    pub max_cpu_percent: u8,
+    /// 同一路径两次空闲刷新的最短间隔（分钟）
+    pub refresh_minutes: u64,
+    /// 静默时段：期间不做空闲扫描（如夜间避免风扇与磁盘噪音）
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl Default for IdleScanSettings {
+    fn default() -> Self {
+        Self { enabled: false, idle_minutes: 10, max_cpu_percent: 25, refresh_minutes: 60, quiet_hours: None }
+    }
+}
+
+/// 启动与空闲后把常用的扫描结果预先载入内存缓存（见 prewarm）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PrewarmSettings {
+    pub enabled: bool,
+    /// 每次预热最多载入的扫描结果数（按磁盘缓存命中次数从多到少）
+    pub max_entries: usize,
+    /// 无键盘 / 鼠标输入超过此分钟数后再预热一次（内存中的结果可能已按内存上限关闭）
+    pub idle_minutes: u64,
+    /// 预热时以后台模式依次重扫收藏的路径：未变化的目录只校验缓存，变化的目录更新缓存
+    pub refresh_pinned: bool,
+}
+
+/// 一次预热载入的扫描结果数上限（与内存缓存的默认容量一致；只占用空余容量，实际载入受当前容量限制）
+pub const MAX_PREWARM_ENTRIES: usize = 30;
+
+impl Default for PrewarmSettings {
+    fn default() -> Self {
+        Self { enabled: true, max_entries: 5, idle_minutes: 10, refresh_pinned: false }
+    }
+}
+
+/// 文件操作队列（见 jobs）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FileOpsSettings {
+    /// 同时执行的作业数；1 为按入队顺序逐个执行
+    pub max_parallel: usize,
+}
+
+impl Default for FileOpsSettings {
+    fn default() -> Self {
+        Self { max_parallel: 1 }
+    }
+}
+
+/// 本地时间段，"HH:MM"；结束早于开始时跨越午夜（如 22:00–07:00）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+impl QuietHours {
+    /// 一天中的第 minute 分钟（0–1439）是否在时段内；格式无效时视为不在
+    pub fn contains(&self, minute: u32) -> bool {
+        let (Some(start), Some(end)) = (parse_hhmm(&self.start), parse_hhmm(&self.end)) else {
+            return false;
+        };
+        if start <= end {
+            (start..end).contains(&minute)
+        } else {
+            minute >= start || minute < end
+        }
+    }
+}
+
+/// "HH:MM" → 一天中的分钟数
+fn parse_hhmm(value: &str) -> Option<u32> {
+    let (h, m) = value.split_once(':')?;
+    if !(1..=2).contains(&h.len()) || m.len() != 2 {
+        return None;
+    }
+    let (h, m): (u32, u32) = (h.parse().ok()?, m.parse().ok()?);
+    (h < 24 && m < 60).then_some(h * 60 + m)
+}
+
+impl Settings {
+    /// 当前生效的设置
+    pub fn current() -> Settings {
+        CURRENT.read().clone()
+    }
+
+    /// 只在本进程内生效（CLI 参数覆盖）
+    pub fn apply(self) -> Validated<()> {
+        self.validate()?;
+        *CURRENT.write() = self;
+        Ok(())
+    }
+
+    /// 校验、写入 settings.json 并立即生效
+    pub fn save(self) -> anyhow::Result<()> {
+        self.validate()?;
+        let path = settings_path().ok_or_else(|| anyhow::anyhow!("无法确定数据目录"))?;
+        save_to(&path, &self)?;
+        *CURRENT.write() = self;
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Validated<()> {
+        if let Some(threads) = self.scan.threads {
+            if threads == 0 || threads > MAX_SCAN_THREADS {
+                return Err(ValidationError::new(
+                    "scan.threads",
+                    ValidationCode::OutOfRange,
+                    format!("线程数须在 1–{} 之间（当前 {}）", MAX_SCAN_THREADS, threads),
+                ));
+            }
+        }
+        if self.scan.queue_capacity == Some(0) {
+            return Err(ValidationError::new("scan.queueCapacity", ValidationCode::OutOfRange, "必须大于 0"));
+        }
+        if self.cache.rss_limit_mb == Some(0) {
+            return Err(ValidationError::new("cache.rssLimitMb", ValidationCode::OutOfRange, "必须大于 0"));
+        }
+        if let Some(locale) = &self.appearance.locale {
+            crate::validate::language_tag("appearance.locale", locale)?;
+        }
+        if self.idle.idle_minutes == 0 {
+            return Err(ValidationError::new("idle.idleMinutes", ValidationCode::OutOfRange, "必须大于 0"));
+        }
+        if !(1..=100).contains(&self.idle.max_cpu_percent) {
+            return Err(ValidationError::new("idle.maxCpuPercent", ValidationCode::OutOfRange, "须在 1–100 之间"));
+        }
+        if self.idle.refresh_minutes == 0 {
+            return Err(ValidationError::new("idle.refreshMinutes", ValidationCode::OutOfRange, "必须大于 0"));
+        }
+        if let Some(quiet) = &self.idle.quiet_hours {
+            for (field, value) in [("idle.quietHours.start", &quiet.start), ("idle.quietHours.end", &quiet.end)] {
+                if parse_hhmm(value).is_none() {
+                    return Err(ValidationError::new(
+                        field,
+                        ValidationCode::InvalidPattern,
+                        format!("应为 HH:MM（当前 {}）", value),
+                    ));
+                }
+            }
+        }
+        if !(1..=MAX_PREWARM_ENTRIES).contains(&self.prewarm.max_entries) {
+            return Err(ValidationError::new(
+                "prewarm.maxEntries",
+                ValidationCode::OutOfRange,
+                format!("须在 1–{} 之间", MAX_PREWARM_ENTRIES),
+            ));
+        }
+        if self.prewarm.idle_minutes == 0 {
+            return Err(ValidationError::new("prewarm.idleMinutes", ValidationCode::OutOfRange, "必须大于 0"));
+        }
+        if !(1..=MAX_PARALLEL_JOBS).contains(&self.file_ops.max_parallel) {
+            return Err(ValidationError::new(
+                "fileOps.maxParallel",
+                ValidationCode::OutOfRange,
+                format!("须在 1–{} 之间", MAX_PARALLEL_JOBS),
+            ));
+        }
+        if !(1..=MAX_CACHE_TTL_DAYS).contains(&self.cache.ttl_days) {
+            return Err(ValidationError::new(
+                "cache.ttlDays",
+                ValidationCode::OutOfRange,
+                format!("须在 1–{} 之间", MAX_CACHE_TTL_DAYS),
+            ));
+        }
+        if self.cache.max_disk_mb == 0 {
+            return Err(ValidationError::new("cache.maxDiskMb", ValidationCode::OutOfRange, "必须大于 0"));
+        }
+        if self.cache.validation_samples > MAX_VALIDATION_SAMPLES {
+            return Err(ValidationError::new(
+                "cache.validationSamples",
+                ValidationCode::OutOfRange,
+                format!("须在 0–{} 之间", MAX_VALIDATION_SAMPLES),
+            ));
+        }
+        if !(1..=MAX_MEMORY_CACHE_ENTRIES).contains(&self.cache.memory_max_entries) {
+            return Err(ValidationError::new(
+                "cache.memoryMaxEntries",
+                ValidationCode::OutOfRange,
+                format!("须在 1–{} 之间", MAX_MEMORY_CACHE_ENTRIES),
+            ));
+        }
+        if !(1..=MAX_MEMORY_CACHE_MB).contains(&self.cache.memory_max_mb) {
+            return Err(ValidationError::new(
+                "cache.memoryMaxMb",
+                ValidationCode::OutOfRange,
+                format!("须在 1–{} 之间", MAX_MEMORY_CACHE_MB),
+            ));
+        }
+        for (i, rule) in self.cache.never_cache.iter().enumerate() {
+            crate::validate::absolute_path(&format!("cache.neverCache[{}]", i), rule)?;
+        }
+        if self.cache.keep_sessions == 0 {
+            return Err(ValidationError::new(
+                "cache.keepSessions",
+                ValidationCode::OutOfRange,
+                "至少保留当前查看的会话",
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn settings_path() -> Option<PathBuf> {
+    crate::migration::data_dir().ok().map(|dir| dir.join(SETTINGS_FILE))
+}
+
+fn load_from(path: &Path) -> Settings {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str::<Settings>(&s).ok())
+        .filter(|s| s.validate().is_ok())
+        .unwrap_or_default()
+}
+
+fn save_to(path: &Path, settings: &Settings) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(settings)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_rejects_invalid_values() {
+        let path = std::env::temp_dir().join(format!("flashdir-settings-{}.json", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        assert_eq!(load_from(&path), Settings::default());
+
+        let settings = Settings {
+            scan: ScanSettings { threads: Some(4), queue_capacity: Some(1000), deterministic: true },
+            cache: CacheSettings {
+                rss_limit_mb: Some(512),
+                keep_sessions: 2,
+                ttl_days: 30,
+                max_disk_mb: 2048,
+                never_cache: vec!["/mnt/scratch".into()],
+                validation_samples: 0,
+                memory_max_entries: 5,
+                memory_max_mb: 64,
+                compact_when_idle: false,
+                compact_min_free_mb: 16,
+            },
+            appearance: AppearanceSettings { theme: ThemePreference::Dark, locale: Some("de-DE".into()) },
+            idle: IdleScanSettings {
+                enabled: true,
+                quiet_hours: Some(QuietHours { start: "22:30".into(), end: "07:00".into() }),
+                ..Default::default()
+            },
+            prewarm: PrewarmSettings { max_entries: 10, refresh_pinned: true, ..Default::default() },
+            file_ops: FileOpsSettings { max_parallel: 3 },
+        };
+        save_to(&path, &settings).unwrap();
+        assert_eq!(load_from(&path), settings);
+
+        // 缺省字段取默认值，非法值整体回退为默认设置
+        std::fs::write(&path, r#"{"scan":{"threads":2}}"#).unwrap();
+        assert_eq!(load_from(&path).scan, ScanSettings { threads: Some(2), ..Default::default() });
+        std::fs::write(&path, r#"{"scan":{"threads":0}}"#).unwrap();
+        assert_eq!(load_from(&path), Settings::default());
+        std::fs::write(&path, r#"{"cache":{"keepSessions":0}}"#).unwrap();
+        assert_eq!(load_from(&path), Settings::default());
+        std::fs::write(&path, r#"{"cache":{"ttlDays":0}}"#).unwrap();
+        assert_eq!(load_from(&path), Settings::default());
+        std::fs::write(&path, r#"{"cache":{"neverCache":["relative/dir"]}}"#).unwrap();
+        assert_eq!(load_from(&path), Settings::default());
+        std::fs::write(&path, r#"{"cache":{"memoryMaxEntries":0}}"#).unwrap();
+        assert_eq!(load_from(&path), Settings::default());
+        std::fs::write(&path, r#"{"prewarm":{"maxEntries":31}}"#).unwrap();
+        assert_eq!(load_from(&path), Settings::default());
+        std::fs::write(&path, r#"{"fileOps":{"maxParallel":0}}"#).unwrap();
+        assert_eq!(load_from(&path), Settings::default());
+        std::fs::write(&path, r#"{"appearance":{"locale":"de_DE.UTF-8"}}"#).unwrap();
+        assert_eq!(load_from(&path), Settings::default());
+
+        let too_many = Settings {
+            scan: ScanSettings { threads: Some(MAX_SCAN_THREADS + 1), ..Default::default() },
+            ..Default::default()
+        };
+        assert_eq!(too_many.validate().unwrap_err().field, "scan.threads");
+
+        // 静默时段跨越午夜
+        let quiet = settings.idle.quiet_hours.clone().unwrap();
+        assert!(quiet.contains(23 * 60) && quiet.contains(6 * 60 + 59) && !quiet.contains(7 * 60));
+        assert!(!quiet.contains(12 * 60));
+        let bad_quiet = Settings {
+            idle: IdleScanSettings {
+                quiet_hours: Some(QuietHours { start: "25:00".into(), end: "07:00".into() }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(bad_quiet.validate().unwrap_err().field, "idle.quietHours.start");
+
+        // never_cache 覆盖规则目录本身、子目录与带修饰的 key，不误伤同名前缀的兄弟目录
+        let cache = &settings.cache;
+        assert!(cache.never_caches("/mnt/scratch") && cache.never_caches("/mnt/scratch/a|exclude=system"));
+        assert!(!cache.never_caches("/mnt/scratch2") && !cache.never_caches("/mnt"));
+        assert!(!cache.is_expired(chrono::Utc::now().timestamp() - 29 * 24 * 3600));
+        assert!(cache.is_expired(chrono::Utc::now().timestamp() - 31 * 24 * 3600));
+        std::fs::remove_file(&path).ok();
+    }
+}
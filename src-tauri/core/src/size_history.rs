@@ -0,0 +1,65 @@
+// 目录大小历史
+//
+// 每次完整扫描后记录扫描根及其下两层目录的大小（path, size, 时间），
+// 用于绘制目录在数周内的增长趋势。只记录目录且有数量上限，
+// 全盘扫描也只写入少量行。
+
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::scan::Item;
+
+/// 记录到扫描根下第几层目录
+const MAX_DEPTH: usize = 2;
+/// 单次扫描最多记录的目录数（按大小取最大的）
+const MAX_DIRS_PER_SCAN: usize = 1000;
+
+/// 时间序列中的一个点
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SizePoint {
+    /// 扫描时间（Unix 秒）
+    pub timestamp: i64,
+    pub size: i64,
+}
+
+/// 从扫描结果中选出要记录的 (路径, 大小)：扫描根本身 + 根下 MAX_DEPTH 层内最大的目录
+pub fn sample_dirs(root: &str, total_size: i64, items: &[Item]) -> Vec<(String, i64)> {
+    let root = root.trim_end_matches('/');
+    let mut dirs: Vec<(&str, i64)> = items
+        .iter()
+        .filter(|i| i.is_dir)
+        .filter_map(|i| {
+            let rel = i.path.strip_prefix(root)?.strip_prefix('/')?;
+            (rel.split('/').count() <= MAX_DEPTH).then_some((i.path.as_str(), i.size))
+        })
+        .collect();
+    dirs.sort_unstable_by_key(|d| std::cmp::Reverse(d.1));
+    dirs.truncate(MAX_DIRS_PER_SCAN);
+
+    std::iter::once((root.to_string(), total_size))
+        .chain(dirs.into_iter().map(|(p, s)| (p.to_string(), s)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dir(path: &str, size: i64) -> Item {
+        Item::test_dir(path, size)
+    }
+
+    #[test]
+    fn samples_root_and_two_levels() {
+        let items = vec![
+            dir("C:/data/a", 50),
+            dir("C:/data/a/b", 40),
+            dir("C:/data/a/b/c", 30),
+            dir("C:/other", 99),
+        ];
+        let samples = sample_dirs("C:/data/", 60, &items);
+        let paths: Vec<&str> = samples.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(paths, vec!["C:/data", "C:/data/a", "C:/data/a/b"]);
+        assert_eq!(samples[0].1, 60);
+    }
+}
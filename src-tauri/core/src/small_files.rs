@@ -0,0 +1,183 @@
+// 小文件空间开销分析
+//
+// 文件按簇（分配单元）分配空间：1 字节的文件在 4KB 簇的卷上也占 4KB，
+// 数百万个小文件（node_modules、缓存、邮件存储）浪费的空间不会出现在逻辑大小里。
+// 按卷的簇大小估算每个文件的分配大小与尾部空闲（slack），统计 0 字节文件、
+// 小于一个簇的文件，并列出小文件最集中的目录。
+//
+// 估算为上限：NTFS 把数百字节以内的文件内容直接存在 MFT 记录中（不占簇），
+// 压缩 / 稀疏文件与支持尾部打包的文件系统实际分配也更少。
+
+use crate::scan::{format_size, Item, ItemKind};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// 无法获取簇大小时按 4KB 估算（NTFS / ext4 / APFS 的默认值）；
+/// 能获取时使用卷的实际分配单元（fs::cluster_size，与 list_volumes 报告的一致）
+pub const DEFAULT_CLUSTER_SIZE: u64 = 4096;
+
+/// 小文件最集中的目录列表长度
+const TOP_DIRS: usize = 20;
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SmallFileDir {
+    pub path: String,
+    /// 直接位于该目录下、小于一个簇的文件数
+    pub small_files: usize,
+    pub slack_bytes: i64,
+    pub slack_bytes_formatted: String,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SmallFileReport {
+    pub path: String,
+    pub cluster_size: u64,
+    /// 簇大小无法获取，按 DEFAULT_CLUSTER_SIZE 估算
+    pub cluster_size_estimated: bool,
+    pub total_files: usize,
+    pub zero_byte_files: usize,
+    /// 大于 0 且小于一个簇的文件
+    pub sub_cluster_files: usize,
+    /// 这些小文件的逻辑大小之和
+    pub sub_cluster_bytes: i64,
+    /// 全部文件逻辑大小之和
+    pub logical_bytes: i64,
+    /// 按簇向上取整后的估计分配大小
+    pub allocated_bytes: i64,
+    pub allocated_bytes_formatted: String,
+    /// 分配大小与逻辑大小之差（所有文件的尾部空闲）
+    pub slack_bytes: i64,
+    pub slack_bytes_formatted: String,
+    /// 其中小于一个簇的文件贡献的部分
+    pub sub_cluster_slack_bytes: i64,
+    /// slack 占分配大小的百分比
+    pub slack_percent: f64,
+    pub top_dirs: Vec<SmallFileDir>,
+}
+
+/// 按簇向上取整的分配大小（0 字节文件不分配数据簇）
+#[inline]
+pub fn allocated_size(size: i64, cluster_size: i64) -> i64 {
+    if cluster_size <= 0 {
+        return size;
+    }
+    (size.max(0) + cluster_size - 1) / cluster_size * cluster_size
+}
+
+/// 估算 items 中普通文件的簇分配开销（链接、云占位符不占本地数据簇，跳过）
+pub fn analyze(path: &str, items: &[Item], cluster_size: Option<u64>) -> SmallFileReport {
+    let cluster = cluster_size.filter(|&c| c > 0).unwrap_or(DEFAULT_CLUSTER_SIZE) as i64;
+
+    let mut total_files = 0usize;
+    let mut zero_byte_files = 0usize;
+    let mut sub_cluster_files = 0usize;
+    let mut sub_cluster_bytes = 0i64;
+    let mut sub_cluster_slack_bytes = 0i64;
+    let mut logical_bytes = 0i64;
+    let mut allocated_bytes = 0i64;
+    let mut dirs: HashMap<&str, (usize, i64)> = HashMap::new();
+
+    for item in items.iter().filter(|i| i.kind == ItemKind::File) {
+        total_files += 1;
+        let size = item.size.max(0);
+        let allocated = allocated_size(size, cluster);
+        logical_bytes += size;
+        allocated_bytes += allocated;
+
+        if size == 0 {
+            zero_byte_files += 1;
+        } else if size < cluster {
+            sub_cluster_files += 1;
+            sub_cluster_bytes += size;
+            let slack = allocated - size;
+            sub_cluster_slack_bytes += slack;
+            let parent = item.path.rsplit_once('/').map_or("", |(p, _)| p);
+            let entry = dirs.entry(parent).or_default();
+            entry.0 += 1;
+            entry.1 += slack;
+        }
+    }
+
+    let mut top: Vec<(&str, (usize, i64))> = dirs.into_iter().collect();
+    top.sort_unstable_by(|a, b| b.1 .0.cmp(&a.1 .0).then_with(|| a.0.cmp(b.0)));
+    let top_dirs = top
+        .into_iter()
+        .take(TOP_DIRS)
+        .map(|(path, (small_files, slack))| SmallFileDir {
+            path: path.to_string(),
+            small_files,
+            slack_bytes: slack,
+            slack_bytes_formatted: format_size(slack).to_string(),
+        })
+        .collect();
+
+    let slack_bytes = allocated_bytes - logical_bytes;
+    SmallFileReport {
+        path: path.to_string(),
+        cluster_size: cluster as u64,
+        cluster_size_estimated: cluster_size.is_none_or(|c| c == 0),
+        total_files,
+        zero_byte_files,
+        sub_cluster_files,
+        sub_cluster_bytes,
+        logical_bytes,
+        allocated_bytes,
+        allocated_bytes_formatted: format_size(allocated_bytes).to_string(),
+        slack_bytes,
+        slack_bytes_formatted: format_size(slack_bytes).to_string(),
+        sub_cluster_slack_bytes,
+        slack_percent: if allocated_bytes > 0 {
+            slack_bytes as f64 / allocated_bytes as f64 * 100.0
+        } else {
+            0.0
+        },
+        top_dirs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(path: &str, size: i64, kind: ItemKind) -> Item {
+        Item { kind, ..Item::test(path, size, kind == ItemKind::Dir) }
+    }
+
+    #[test]
+    fn counts_slack_against_cluster_size() {
+        let items = vec![
+            item("r/a", 0, ItemKind::Dir),
+            item("r/a/empty", 0, ItemKind::File),
+            item("r/a/one", 1, ItemKind::File),
+            item("r/a/two", 100, ItemKind::File),
+            item("r/big", 4097, ItemKind::File),
+            item("r/exact", 4096, ItemKind::File),
+            item("r/link", 0, ItemKind::Symlink),
+            item("r/cloud", 0, ItemKind::CloudPlaceholder),
+        ];
+        let report = analyze("r", &items, Some(4096));
+
+        assert_eq!(report.total_files, 5);
+        assert_eq!(report.zero_byte_files, 1);
+        assert_eq!(report.sub_cluster_files, 2);
+        assert_eq!(report.sub_cluster_bytes, 101);
+        assert_eq!(report.allocated_bytes, 4096 * 2 + 8192 + 4096);
+        assert_eq!(report.slack_bytes, (4096 - 1) + (4096 - 100) + (8192 - 4097));
+        assert_eq!(report.sub_cluster_slack_bytes, (4096 - 1) + (4096 - 100));
+        assert_eq!(report.top_dirs[0].path, "r/a");
+        assert_eq!(report.top_dirs[0].small_files, 2);
+        assert!(!report.cluster_size_estimated);
+
+        // 64KB 簇（大容量 exFAT / 调大簇的 NTFS）
+        let large = analyze("r", &items, Some(65536));
+        assert_eq!(large.allocated_bytes, 65536 * 4);
+        assert_eq!(allocated_size(0, 65536), 0);
+
+        let estimated = analyze("r", &items, None);
+        assert!(estimated.cluster_size_estimated);
+        assert_eq!(estimated.cluster_size, DEFAULT_CLUSTER_SIZE);
+    }
+}
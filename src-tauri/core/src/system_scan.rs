@@ -0,0 +1,155 @@
+// 全系统扫描
+//
+// 枚举所有固定卷并行扫描，汇总为一个结果：逐盘明细 + 全局 Top 100 大目录。
+// 每个盘走完整的 scan::scan_directory（含 MFT 快速路径与两级缓存），
+// 因此扫描完成后前端可直接按盘符打开单盘结果而无需重扫。
+
+use serde::Serialize;
+use schemars::JsonSchema;
+use std::sync::Arc;
+use crate::events::Events;
+
+use crate::perf::PerformanceMonitor;
+use crate::scan::{self, format_size, Item};
+
+/// 全局大目录列表长度
+const TOP_DIRECTORIES: usize = 100;
+
+/// 单盘扫描摘要
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveScanSummary {
+    pub mount_point: String,
+    pub label: String,
+    pub file_system: String,
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+    /// 扫描到的文件总大小
+    pub scanned_size: i64,
+    pub scanned_size_formatted: String,
+    pub file_count: usize,
+    pub dir_count: usize,
+    pub scan_time: f64,
+    pub mft_available: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 全系统扫描结果（不含完整 items，单盘明细请按 mount_point 再次调用 scan_directory 命中缓存）
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemScanResult {
+    pub drives: Vec<DriveScanSummary>,
+    pub total_size: i64,
+    pub total_size_formatted: String,
+    pub file_count: usize,
+    pub dir_count: usize,
+    /// 总耗时（各盘并行，约等于最慢的一个盘）
+    pub scan_time: f64,
+    /// 跨所有盘的 Top 100 大目录（按 size 降序）
+    pub top_directories: Vec<Item>,
+}
+
+/// 并行扫描所有固定卷并汇总
+pub async fn scan_all_drives(force_refresh: bool, events: Option<Events>) -> SystemScanResult {
+    let start = std::time::Instant::now();
+
+    let volumes: Vec<crate::fs::VolumeInfo> = crate::fs::list_volumes()
+        .into_iter()
+        .filter(|v| v.kind == crate::fs::VolumeKind::Fixed && v.is_ready)
+        .collect();
+
+    // 每个盘独立的 PerformanceMonitor：全局单例只跟踪一个 session，并行扫描会相互覆盖
+    let handles: Vec<_> = volumes
+        .iter()
+        .map(|v| {
+            let root = v.mount_point.clone();
+            let events = events.clone();
+            tokio::spawn(async move {
+                if let Some(sink) = events.as_ref() {
+                    sink.emit(
+                        "scan-all-progress",
+                        serde_json::json!({ "drive": root, "phase": "scanning" }),
+                    );
+                }
+                let monitor = Arc::new(PerformanceMonitor::new(1));
+                let result = scan::scan_directory(&root, force_refresh, monitor, None).await;
+                if let Some(sink) = events.as_ref() {
+                    let phase = match &result {
+                        Ok(_) => "done".to_string(),
+                        Err(e) => format!("failed: {}", e),
+                    };
+                    sink.emit(
+                        "scan-all-progress",
+                        serde_json::json!({ "drive": root, "phase": phase }),
+                    );
+                }
+                result
+            })
+        })
+        .collect();
+
+    let mut drives = Vec::with_capacity(volumes.len());
+    let mut top_directories: Vec<Item> = Vec::new();
+
+    for (volume, handle) in volumes.into_iter().zip(handles) {
+        let outcome = match handle.await {
+            Ok(r) => r.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+
+        let mut summary = DriveScanSummary {
+            mount_point: volume.mount_point,
+            label: volume.label,
+            file_system: volume.file_system,
+            total_bytes: volume.total_bytes,
+            free_bytes: volume.free_bytes,
+            scanned_size: 0,
+            scanned_size_formatted: format_size(0).to_string(),
+            file_count: 0,
+            dir_count: 0,
+            scan_time: 0.0,
+            mft_available: false,
+            error: None,
+        };
+
+        match outcome {
+            Ok(result) => {
+                let dir_count = result.items.iter().filter(|i| i.is_dir).count();
+                summary.scanned_size = result.total_size;
+                summary.scanned_size_formatted = result.total_size_formatted.to_string();
+                summary.file_count = result.items.len() - dir_count;
+                summary.dir_count = dir_count;
+                summary.scan_time = result.scan_time;
+                summary.mft_available = result.mft_available;
+
+                // 每盘 items 已按 size 降序，取前 N 个目录即可参与全局合并
+                top_directories.extend(
+                    result
+                        .items
+                        .into_iter()
+                        .filter(|i| i.is_dir)
+                        .take(TOP_DIRECTORIES),
+                );
+            }
+            Err(e) => summary.error = Some(e),
+        }
+
+        drives.push(summary);
+    }
+
+    top_directories.sort_unstable_by_key(|i| std::cmp::Reverse(i.size));
+    top_directories.truncate(TOP_DIRECTORIES);
+
+    let total_size: i64 = drives.iter().map(|d| d.scanned_size).sum();
+
+    SystemScanResult {
+        total_size,
+        total_size_formatted: format_size(total_size).to_string(),
+        file_count: drives.iter().map(|d| d.file_count).sum(),
+        dir_count: drives.iter().map(|d| d.dir_count).sum(),
+        scan_time: start.elapsed().as_secs_f64(),
+        drives,
+        top_directories,
+    }
+}
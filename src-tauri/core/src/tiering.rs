@@ -0,0 +1,331 @@
+// 冷热数据分级模块
+//
+// 结合最后访问时间（可用时）与修改时间，把文件分为 hot / warm / cold 三级，
+// 汇总每级的空间占用，评估有多少数据可以迁移到更便宜的存储（NAS 分层、云归档）。
+//
+// 设计原则：
+// - 只读分析，不修改任何文件
+// - "最后使用时间" = max(atime, mtime)；atime 不可用时退化为 mtime
+// - 系统关闭了 atime 更新时在报告中给出警告，调用方可选择只按 mtime 分级
+// - 分级逻辑与文件系统 I/O 分离，便于测试
+
+use rayon::prelude::*;
+use serde::Serialize;
+use schemars::JsonSchema;
+use std::time::SystemTime;
+use crate::fs::LastAccessUpdates;
+use crate::scan::{Item, format_size};
+
+/// 默认阈值：30 天内使用过为 hot，180 天内为 warm，其余为 cold
+pub const DEFAULT_HOT_DAYS: u32 = 30;
+pub const DEFAULT_COLD_DAYS: u32 = 180;
+
+/// cold 文件 Top 列表长度
+const TOP_COLD_FILES: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum Tier {
+    Hot,
+    Warm,
+    Cold,
+}
+
+/// 分级依据的时间
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum TimeBasis {
+    /// max(atime, mtime)
+    #[default]
+    LastUsed,
+    /// 仅 mtime（atime 更新关闭时的回退）
+    ModifiedOnly,
+}
+
+/// 分级阈值（天）
+#[derive(Debug, Clone, Copy)]
+pub struct TierThresholds {
+    pub hot_days: u32,
+    pub cold_days: u32,
+}
+
+impl Default for TierThresholds {
+    fn default() -> Self {
+        Self {
+            hot_days: DEFAULT_HOT_DAYS,
+            cold_days: DEFAULT_COLD_DAYS,
+        }
+    }
+}
+
+/// 单个文件的时间信息（Unix 秒）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileTimes {
+    pub accessed: Option<i64>,
+    pub modified: Option<i64>,
+}
+
+impl FileTimes {
+    /// 最后使用时间：atime 与 mtime 取较新者
+    pub fn last_used(&self) -> Option<i64> {
+        match (self.accessed, self.modified) {
+            (Some(a), Some(m)) => Some(a.max(m)),
+            (a, m) => a.or(m),
+        }
+    }
+
+    /// 按分级依据取时间
+    pub fn for_basis(&self, basis: TimeBasis) -> Option<i64> {
+        match basis {
+            TimeBasis::LastUsed => self.last_used(),
+            TimeBasis::ModifiedOnly => self.modified,
+        }
+    }
+}
+
+/// 单级统计
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TierStats {
+    pub tier: Tier,
+    pub file_count: usize,
+    pub total_size: i64,
+    pub total_size_formatted: String,
+    pub percent_of_total: f64,
+}
+
+/// cold 文件 Top 项
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ColdFile {
+    pub path: String,
+    pub size: i64,
+    pub size_formatted: String,
+    /// 最后使用时间（Unix 秒）
+    pub last_used: i64,
+    pub days_since_use: i64,
+}
+
+/// 冷热分级报告
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TieringReport {
+    pub path: String,
+    pub hot_days: u32,
+    pub cold_days: u32,
+    pub time_basis: TimeBasis,
+    /// 系统关闭了 atime 更新等导致分级可能失真时的提示
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+    pub total_files: usize,
+    pub total_size: i64,
+    /// 按 hot / warm / cold 顺序
+    pub tiers: Vec<TierStats>,
+    /// 可迁移到廉价存储的数据量（cold 级合计）
+    pub movable_size: i64,
+    pub movable_size_formatted: String,
+    /// 拿到了 atime 的文件数（为 0 时分级仅基于 mtime）
+    pub access_time_available: usize,
+    /// 无法读取时间信息的文件数（未计入任何级别）
+    pub unknown_count: usize,
+    pub top_cold_files: Vec<ColdFile>,
+}
+
+/// 按最后使用时间分级
+pub fn classify(last_used: i64, now: i64, thresholds: &TierThresholds) -> Tier {
+    let days = (now - last_used).max(0) / 86_400;
+    if days <= thresholds.hot_days as i64 {
+        Tier::Hot
+    } else if days <= thresholds.cold_days as i64 {
+        Tier::Warm
+    } else {
+        Tier::Cold
+    }
+}
+
+/// atime 更新已关闭且仍按访问时间分级时的警告文案
+pub fn access_time_warning(updates: LastAccessUpdates, basis: TimeBasis) -> Option<String> {
+    if basis == TimeBasis::LastUsed && !updates.is_reliable() {
+        Some("系统已关闭最后访问时间更新，访问时间不可信，分级实际仅反映修改时间；可改用仅按修改时间分级".to_string())
+    } else {
+        None
+    }
+}
+
+/// 读取文件的访问/修改时间（并行 stat，只处理文件）
+pub fn collect_file_times(items: &[Item]) -> Vec<FileTimes> {
+    items
+        .par_iter()
+        .map(|item| {
+            if item.is_dir {
+                return FileTimes::default();
+            }
+            match std::fs::metadata(item.path.as_str()) {
+                Ok(m) => FileTimes {
+                    accessed: m.accessed().ok().and_then(to_unix),
+                    modified: m.modified().ok().and_then(to_unix),
+                },
+                Err(_) => FileTimes::default(),
+            }
+        })
+        .collect()
+}
+
+fn to_unix(t: SystemTime) -> Option<i64> {
+    t.duration_since(SystemTime::UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs() as i64)
+}
+
+/// 由 items 与对应的时间信息生成报告（`times` 与 `items` 按下标对齐）
+pub fn build_report(
+    path: &str,
+    items: &[Item],
+    times: &[FileTimes],
+    now: i64,
+    thresholds: TierThresholds,
+    basis: TimeBasis,
+) -> TieringReport {
+    let mut counts = [0usize; 3];
+    let mut sizes = [0i64; 3];
+    let mut access_time_available = 0usize;
+    let mut unknown_count = 0usize;
+    let mut cold_files: Vec<ColdFile> = Vec::new();
+
+    for (item, t) in items.iter().zip(times.iter()) {
+        if item.is_dir {
+            continue;
+        }
+        if t.accessed.is_some() {
+            access_time_available += 1;
+        }
+        let Some(last_used) = t.for_basis(basis) else {
+            unknown_count += 1;
+            continue;
+        };
+        let tier = classify(last_used, now, &thresholds);
+        let idx = tier as usize;
+        counts[idx] += 1;
+        sizes[idx] += item.size;
+
+        if tier == Tier::Cold {
+            cold_files.push(ColdFile {
+                path: item.path.to_string(),
+                size: item.size,
+                size_formatted: String::new(),
+                last_used,
+                days_since_use: (now - last_used).max(0) / 86_400,
+            });
+        }
+    }
+
+    cold_files.sort_unstable_by_key(|i| std::cmp::Reverse(i.size));
+    cold_files.truncate(TOP_COLD_FILES);
+    for f in &mut cold_files {
+        f.size_formatted = format_size(f.size).to_string();
+    }
+
+    let total_size: i64 = sizes.iter().sum();
+    let tiers = [Tier::Hot, Tier::Warm, Tier::Cold]
+        .iter()
+        .map(|&tier| {
+            let idx = tier as usize;
+            TierStats {
+                tier,
+                file_count: counts[idx],
+                total_size: sizes[idx],
+                total_size_formatted: format_size(sizes[idx]).to_string(),
+                percent_of_total: if total_size > 0 {
+                    (sizes[idx] as f64 / total_size as f64) * 100.0
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect();
+
+    let movable_size = sizes[Tier::Cold as usize];
+
+    TieringReport {
+        path: path.to_string(),
+        hot_days: thresholds.hot_days,
+        cold_days: thresholds.cold_days,
+        time_basis: basis,
+        warning: None,
+        total_files: counts.iter().sum::<usize>() + unknown_count,
+        total_size,
+        tiers,
+        movable_size,
+        movable_size_formatted: format_size(movable_size).to_string(),
+        access_time_available,
+        unknown_count,
+        top_cold_files: cold_files,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400;
+
+    fn file(path: &str, size: i64) -> Item {
+        Item::test_file(path, size)
+    }
+
+    #[test]
+    fn classify_by_threshold() {
+        let t = TierThresholds::default();
+        let now = 1000 * DAY;
+        assert_eq!(classify(now - 10 * DAY, now, &t), Tier::Hot);
+        assert_eq!(classify(now - 100 * DAY, now, &t), Tier::Warm);
+        assert_eq!(classify(now - 400 * DAY, now, &t), Tier::Cold);
+    }
+
+    #[test]
+    fn last_used_prefers_newer_of_atime_and_mtime() {
+        let t = FileTimes { accessed: Some(50), modified: Some(100) };
+        assert_eq!(t.last_used(), Some(100));
+        let t = FileTimes { accessed: None, modified: Some(100) };
+        assert_eq!(t.last_used(), Some(100));
+        assert_eq!(FileTimes::default().last_used(), None);
+    }
+
+    #[test]
+    fn report_sums_tiers_and_movable_size() {
+        let now = 1000 * DAY;
+        let items = vec![
+            file("d/hot.txt", 10),
+            file("d/warm.txt", 20),
+            file("d/cold.txt", 30),
+            file("d/unknown.txt", 40),
+        ];
+        let times = vec![
+            FileTimes { accessed: Some(now - DAY), modified: Some(now - 500 * DAY) },
+            FileTimes { accessed: None, modified: Some(now - 90 * DAY) },
+            FileTimes { accessed: None, modified: Some(now - 365 * DAY) },
+            FileTimes::default(),
+        ];
+        let report = build_report("d", &items, &times, now, TierThresholds::default(), TimeBasis::LastUsed);
+        assert_eq!(report.tiers[0].total_size, 10);
+        assert_eq!(report.tiers[1].total_size, 20);
+        assert_eq!(report.tiers[2].total_size, 30);
+        assert_eq!(report.movable_size, 30);
+        assert_eq!(report.unknown_count, 1);
+        assert_eq!(report.access_time_available, 1);
+        assert_eq!(report.top_cold_files.len(), 1);
+        assert_eq!(report.top_cold_files[0].days_since_use, 365);
+
+        // 仅按 mtime：hot.txt 的 atime 被忽略，落入 cold
+        let report = build_report("d", &items, &times, now, TierThresholds::default(), TimeBasis::ModifiedOnly);
+        assert_eq!(report.tiers[0].total_size, 0);
+        assert_eq!(report.movable_size, 40);
+    }
+
+    #[test]
+    fn warns_only_when_access_time_disabled() {
+        assert!(access_time_warning(LastAccessUpdates::Disabled, TimeBasis::LastUsed).is_some());
+        assert!(access_time_warning(LastAccessUpdates::Disabled, TimeBasis::ModifiedOnly).is_none());
+        assert!(access_time_warning(LastAccessUpdates::Enabled, TimeBasis::LastUsed).is_none());
+    }
+}
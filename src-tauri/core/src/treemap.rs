@@ -0,0 +1,234 @@
+// Squarified treemap 布局
+//
+// 前端在 JS 中为 10 万级节点计算矩形很慢，且只能用切片算法（长条形矩形难以辨认）。
+// 这里在后端基于内存缓存中的扫描结果运行 squarified 算法（Bruls 等，2000），
+// 按层（广度优先）展开到 max_depth，返回可直接绘制的矩形（视口像素坐标）。
+//
+// 面积不足 MIN_AREA 像素的矩形不返回（画不出来），但仍占据其面积，兄弟矩形的位置不受影响；
+// 目录内缩 DIR_PADDING 后再布局子项，内部空间过小时不再展开。
+// 矩形总数超过 MAX_RECTS 时停止展开更深的层并标记 truncated。
+
+use crate::categories::FileCategory;
+use crate::scan::{Item, ItemKind};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+
+/// 小于此面积（像素²）的矩形不返回
+const MIN_AREA: f64 = 1.0;
+
+/// 目录矩形四周留出的边距，子项在其内部布局
+const DIR_PADDING: f64 = 1.0;
+
+/// 目录内部宽或高小于此值时不再展开
+const MIN_NEST_SIDE: f64 = 4.0;
+
+/// 返回的矩形数上限
+const MAX_RECTS: usize = 200_000;
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TreemapRect {
+    pub id: u64,
+    pub path: String,
+    pub name: String,
+    pub size: i64,
+    pub is_dir: bool,
+    pub kind: ItemKind,
+    pub category: FileCategory,
+    /// 1 为扫描根的直接子项
+    pub depth: u32,
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TreemapLayout {
+    pub width: f64,
+    pub height: f64,
+    pub max_depth: u32,
+    /// 按层排列：父目录总在其子项之前，按顺序绘制即可
+    pub rects: Vec<TreemapRect>,
+    /// 超过矩形数上限，部分深层未展开
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rect {
+    x: f64,
+    y: f64,
+    w: f64,
+    h: f64,
+}
+
+/// 在 rect 内按 squarified 算法排列 sizes（须已降序、全部大于 0），返回与 sizes 对齐的矩形
+fn squarify(sizes: &[f64], rect: Rect) -> Vec<Rect> {
+    let mut out = Vec::with_capacity(sizes.len());
+    let total: f64 = sizes.iter().sum();
+    if total <= 0.0 || rect.w <= 0.0 || rect.h <= 0.0 {
+        return out;
+    }
+    // 换算为面积
+    let scale = rect.w * rect.h / total;
+    let areas: Vec<f64> = sizes.iter().map(|s| s * scale).collect();
+
+    // 一行的最差长宽比：行内最大 / 最小面积分别为 first / last（已降序）
+    let worst = |first: f64, last: f64, sum: f64, side: f64| -> f64 {
+        let side2 = side * side;
+        let sum2 = sum * sum;
+        (side2 * first / sum2).max(sum2 / (side2 * last))
+    };
+
+    let mut free = rect;
+    let mut i = 0;
+    while i < areas.len() {
+        let side = free.w.min(free.h);
+        let mut row_sum = areas[i];
+        let mut row_worst = worst(areas[i], areas[i], row_sum, side);
+        let mut end = i + 1;
+        while end < areas.len() {
+            let candidate = worst(areas[i], areas[end], row_sum + areas[end], side);
+            if candidate > row_worst {
+                break;
+            }
+            row_sum += areas[end];
+            row_worst = candidate;
+            end += 1;
+        }
+
+        // 行沿短边排列，厚度 = 行面积 / 短边
+        let thickness = if side > 0.0 { row_sum / side } else { 0.0 };
+        let mut offset = 0.0;
+        for &area in &areas[i..end] {
+            let length = if thickness > 0.0 { area / thickness } else { 0.0 };
+            if free.w >= free.h {
+                out.push(Rect { x: free.x, y: free.y + offset, w: thickness, h: length });
+            } else {
+                out.push(Rect { x: free.x + offset, y: free.y, w: length, h: thickness });
+            }
+            offset += length;
+        }
+        if free.w >= free.h {
+            free.x += thickness;
+            free.w = (free.w - thickness).max(0.0);
+        } else {
+            free.y += thickness;
+            free.h = (free.h - thickness).max(0.0);
+        }
+        i = end;
+    }
+    out
+}
+
+/// 由扫描结果的 items 计算布局。扫描根不在 items 中：父目录不在 items 里的条目即为第一层
+pub fn compute(items: &[Item], width: f64, height: f64, max_depth: u32) -> TreemapLayout {
+    let mut layout = TreemapLayout { width, height, max_depth, rects: Vec::new(), truncated: false };
+    if width <= 0.0 || height <= 0.0 || max_depth == 0 {
+        return layout;
+    }
+
+    let dirs: HashMap<&str, usize> = items
+        .iter()
+        .enumerate()
+        .filter(|(_, i)| i.is_dir)
+        .map(|(idx, i)| (i.path.as_str(), idx))
+        .collect();
+    // 父目录下标 → 子项下标；None 为第一层
+    let mut children: HashMap<Option<usize>, Vec<usize>> = HashMap::new();
+    for (idx, item) in items.iter().enumerate() {
+        if item.size <= 0 {
+            continue;
+        }
+        let parent = item.path.rsplit_once('/').and_then(|(p, _)| dirs.get(p).copied());
+        children.entry(parent).or_default().push(idx);
+    }
+    for list in children.values_mut() {
+        list.sort_unstable_by(|&a, &b| items[b].size.cmp(&items[a].size));
+    }
+
+    let mut queue: VecDeque<(Option<usize>, Rect, u32)> = VecDeque::new();
+    queue.push_back((None, Rect { x: 0.0, y: 0.0, w: width, h: height }, 1));
+
+    while let Some((parent, area, depth)) = queue.pop_front() {
+        let Some(list) = children.get(&parent) else {
+            continue;
+        };
+        let sizes: Vec<f64> = list.iter().map(|&idx| items[idx].size as f64).collect();
+        for (&idx, rect) in list.iter().zip(squarify(&sizes, area)) {
+            if rect.w * rect.h < MIN_AREA {
+                continue;
+            }
+            if layout.rects.len() >= MAX_RECTS {
+                layout.truncated = true;
+                return layout;
+            }
+            let item = &items[idx];
+            layout.rects.push(TreemapRect {
+                id: item.id,
+                path: item.path.to_string(),
+                name: item.name.to_string(),
+                size: item.size,
+                is_dir: item.is_dir,
+                kind: item.kind,
+                category: item.category,
+                depth,
+                x: rect.x as f32,
+                y: rect.y as f32,
+                w: rect.w as f32,
+                h: rect.h as f32,
+            });
+
+            let inner = Rect {
+                x: rect.x + DIR_PADDING,
+                y: rect.y + DIR_PADDING,
+                w: rect.w - 2.0 * DIR_PADDING,
+                h: rect.h - 2.0 * DIR_PADDING,
+            };
+            if item.is_dir && depth < max_depth && inner.w >= MIN_NEST_SIDE && inner.h >= MIN_NEST_SIDE {
+                queue.push_back((Some(idx), inner, depth + 1));
+            }
+        }
+    }
+    layout
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn squarifies_and_nests_by_depth() {
+        // Bruls 等论文中的示例：6×4 区域，面积 6 6 4 3 2 2 1
+        let rects = squarify(&[6.0, 6.0, 4.0, 3.0, 2.0, 2.0, 1.0], Rect { x: 0.0, y: 0.0, w: 6.0, h: 4.0 });
+        assert_eq!(rects.len(), 7);
+        let area: f64 = rects.iter().map(|r| r.w * r.h).sum();
+        assert!((area - 24.0).abs() < 1e-9);
+        assert_eq!(rects[0], Rect { x: 0.0, y: 0.0, w: 3.0, h: 2.0 });
+        assert!(rects.iter().all(|r| r.x >= 0.0 && r.y >= 0.0 && r.x + r.w <= 6.0 + 1e-9 && r.y + r.h <= 4.0 + 1e-9));
+
+        let items = vec![
+            Item::test("/r/a", 600, true),
+            Item::test("/r/a/x.bin", 400, false),
+            Item::test("/r/a/y.bin", 200, false),
+            Item::test("/r/b.bin", 400, false),
+            Item::test("/r/empty", 0, true),
+        ];
+        let shallow = compute(&items, 100.0, 50.0, 1);
+        let paths: Vec<_> = shallow.rects.iter().map(|r| (r.path.as_str(), r.depth)).collect();
+        assert_eq!(paths, vec![("/r/a", 1), ("/r/b.bin", 1)]);
+        assert!((shallow.rects[0].w * shallow.rects[0].h - 3000.0).abs() < 1.0);
+
+        let deep = compute(&items, 100.0, 50.0, 2);
+        assert_eq!(deep.rects.len(), 4);
+        let dir = &deep.rects[0];
+        for child in deep.rects.iter().filter(|r| r.depth == 2) {
+            assert!(child.x >= dir.x && child.y >= dir.y);
+            assert!(child.x + child.w <= dir.x + dir.w + 1e-3 && child.y + child.h <= dir.y + dir.h + 1e-3);
+        }
+        assert!(!deep.truncated);
+        assert!(compute(&items, 0.0, 50.0, 2).rects.is_empty());
+    }
+}
@@ -0,0 +1,216 @@
+// 命令参数校验
+//
+// 桌面端命令、FFI 在进入扫描 / 分析之前统一校验输入（路径存在且为绝对路径、数量 > 0、
+// 上限截断、搜索语法可解析），失败时返回带出错字段的 ValidationError，
+// 而不是各处零散的空串判断，或者把错误输入一路带进扫描深处才失败。
+
+use crate::path_anomalies::PathAnomalyKind;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt;
+use std::path::Path;
+
+/// 参数校验失败
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationError {
+    /// 出错的参数名（与前端 invoke 的参数名一致，camelCase；数组元素为 `paths[2]`）
+    pub field: String,
+    pub code: ValidationCode,
+    /// 面向用户的说明
+    pub message: String,
+}
+
+/// 校验失败类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ValidationCode {
+    Required,
+    NotAbsolute,
+    NotFound,
+    NotDirectory,
+    OutOfRange,
+    InvalidPattern,
+}
+
+impl ValidationError {
+    pub fn new(field: impl Into<String>, code: ValidationCode, message: impl Into<String>) -> Self {
+        Self { field: field.into(), code, message: message.into() }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+pub type Validated<T> = Result<T, ValidationError>;
+
+/// 非空绝对路径，返回去掉首尾空白后的路径
+pub fn absolute_path(field: &str, value: &str) -> Validated<String> {
+    let path = value.trim();
+    if path.is_empty() {
+        return Err(ValidationError::new(field, ValidationCode::Required, "请提供路径"));
+    }
+    if !Path::new(path).is_absolute() {
+        return Err(ValidationError::new(
+            field,
+            ValidationCode::NotAbsolute,
+            format!("需要绝对路径: {}", path),
+        ));
+    }
+    Ok(path.to_string())
+}
+
+/// 存在的绝对路径（文件或目录）
+pub fn existing_path(field: &str, value: &str) -> Validated<String> {
+    let path = absolute_path(field, value)?;
+    metadata(field, &path)?;
+    Ok(path)
+}
+
+/// 存在的目录
+pub fn existing_dir(field: &str, value: &str) -> Validated<String> {
+    let path = absolute_path(field, value)?;
+    if !metadata(field, &path)?.is_dir() {
+        return Err(ValidationError::new(field, ValidationCode::NotDirectory, format!("不是目录: {}", path)));
+    }
+    Ok(path)
+}
+
+/// 输出文件路径：绝对路径且所在目录存在
+pub fn output_file(field: &str, value: &str) -> Validated<String> {
+    let path = absolute_path(field, value)?;
+    match Path::new(&path).parent() {
+        Some(parent) if parent.as_os_str().is_empty() || parent.is_dir() => Ok(path),
+        _ => Err(ValidationError::new(field, ValidationCode::NotFound, format!("目标目录不存在: {}", path))),
+    }
+}
+
+/// 单个文件名（不含路径分隔符，不为 . / ..，不超过 255 个 UTF-16 单元）；
+/// Windows 上另拒绝非法字符、保留设备名与以空格或点结尾的名称
+pub fn file_name(field: &str, value: &str) -> Validated<String> {
+    if value.trim().is_empty() {
+        return Err(ValidationError::new(field, ValidationCode::Required, "请提供名称"));
+    }
+    if value == "." || value == ".." || value.contains(['/', '\0']) {
+        return Err(ValidationError::new(field, ValidationCode::InvalidPattern, format!("不是有效的名称: {}", value)));
+    }
+    if value.encode_utf16().count() > 255 {
+        return Err(ValidationError::new(field, ValidationCode::OutOfRange, "名称过长（最多 255 个字符）"));
+    }
+    // 只看名称本身的异常，路径长度由调用方所在目录决定
+    let invalid_on_windows = || {
+        crate::path_anomalies::check(value, value)
+            .iter()
+            .any(|kind| !matches!(kind, PathAnomalyKind::OverMaxPath | PathAnomalyKind::NearMaxPath))
+    };
+    if cfg!(target_os = "windows") && invalid_on_windows() {
+        return Err(ValidationError::new(
+            field,
+            ValidationCode::InvalidPattern,
+            format!("名称在 Windows 上无效: {}", value),
+        ));
+    }
+    Ok(value.to_string())
+}
+
+/// 逐项校验绝对路径列表（至少一项），出错字段为 `field[i]`
+pub fn absolute_paths(field: &str, values: &[String]) -> Validated<Vec<String>> {
+    if values.is_empty() {
+        return Err(ValidationError::new(field, ValidationCode::Required, "至少提供一个路径"));
+    }
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| absolute_path(&format!("{}[{}]", field, i), v))
+        .collect()
+}
+
+/// 数量上限：缺省取 default，0 报错，超过 max 截断为 max
+pub fn limit(field: &str, value: Option<usize>, default: usize, max: usize) -> Validated<usize> {
+    match value {
+        None => Ok(default.min(max)),
+        Some(0) => Err(ValidationError::new(field, ValidationCode::OutOfRange, "必须大于 0")),
+        Some(n) => Ok(n.min(max)),
+    }
+}
+
+/// 正整数
+pub fn positive<T: PartialOrd + Default + fmt::Display>(field: &str, value: T) -> Validated<T> {
+    if value > T::default() {
+        Ok(value)
+    } else {
+        Err(ValidationError::new(field, ValidationCode::OutOfRange, format!("必须大于 0（当前 {}）", value)))
+    }
+}
+
+/// 搜索语法（`size:` / `mtime:` 条件与引号配对）可解析
+/// 形如 "en"、"zh-CN"、"zh-Hant-TW" 的 BCP 47 语言标记：2–3 个字母的语言，后接字母数字子标记
+pub fn language_tag(field: &str, tag: &str) -> Validated<()> {
+    let mut parts = tag.split('-');
+    let language = parts.next().unwrap_or_default();
+    let valid = (2..=3).contains(&language.len())
+        && language.bytes().all(|b| b.is_ascii_alphabetic())
+        && parts.all(|p| (2..=8).contains(&p.len()) && p.bytes().all(|b| b.is_ascii_alphanumeric()));
+    if valid {
+        Ok(())
+    } else {
+        Err(ValidationError::new(field, ValidationCode::InvalidPattern, format!("不是有效的语言标记: {}", tag)))
+    }
+}
+
+pub fn search_query(field: &str, query: &str) -> Validated<()> {
+    crate::global_search::check_search_filter(query)
+        .map_err(|msg| ValidationError::new(field, ValidationCode::InvalidPattern, msg))
+}
+
+fn metadata(field: &str, path: &str) -> Validated<std::fs::Metadata> {
+    // 扩展长度形式，超过 MAX_PATH 的路径也能访问
+    std::fs::metadata(crate::fs::to_extended_length(path)).map_err(|e| {
+        ValidationError::new(field, ValidationCode::NotFound, format!("无法访问路径 {}: {}", path, e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_offending_field() {
+        let dir = std::env::temp_dir();
+        let dir_str = dir.to_string_lossy().to_string();
+
+        assert_eq!(absolute_path("path", "  ").unwrap_err().code, ValidationCode::Required);
+        assert_eq!(absolute_path("path", "relative/dir").unwrap_err().code, ValidationCode::NotAbsolute);
+        assert_eq!(existing_dir("path", &format!(" {} ", dir_str)).unwrap(), dir_str);
+
+        let missing = dir.join("flashdir-validate-missing").to_string_lossy().to_string();
+        let err = existing_dir("path", &missing).unwrap_err();
+        assert_eq!((err.field.as_str(), err.code), ("path", ValidationCode::NotFound));
+
+        let err = absolute_paths("paths", &[dir_str.clone(), "x".to_string()]).unwrap_err();
+        assert_eq!(err.field, "paths[1]");
+
+        assert_eq!(limit("limit", None, 20, 100), Ok(20));
+        assert_eq!(limit("limit", Some(5000), 20, 100), Ok(100));
+        assert_eq!(limit("limit", Some(0), 20, 100).unwrap_err().code, ValidationCode::OutOfRange);
+        assert!(positive("hotDays", 0u32).is_err());
+
+        assert!(search_query("query", "report ext:pdf size:>10MB").is_ok());
+        let err = search_query("query", "size:>lots").unwrap_err();
+        assert_eq!(err.code, ValidationCode::InvalidPattern);
+        assert!(search_query("query", "\"unterminated").is_err());
+
+        assert_eq!(file_name("newName", "report (2).pdf").unwrap(), "report (2).pdf");
+        assert_eq!(file_name("newName", " ").unwrap_err().code, ValidationCode::Required);
+        assert_eq!(file_name("newName", "a/b").unwrap_err().code, ValidationCode::InvalidPattern);
+        assert_eq!(file_name("newName", "..").unwrap_err().code, ValidationCode::InvalidPattern);
+
+        assert!(language_tag("locale", "zh-Hant-TW").is_ok());
+        assert_eq!(language_tag("locale", "en_US").unwrap_err().code, ValidationCode::InvalidPattern);
+    }
+}
@@ -0,0 +1,641 @@
+// 端到端集成测试：在生成的临时目录树上驱动前端依赖的 扫描 / 缓存 / 历史 / 导出 流程，
+// 校验返回给前端的 JSON 字段与二进制协议契约。
+//
+// 测试进程把 HOME / USERPROFILE 指向临时目录，磁盘缓存与数据目录不会污染真实用户数据。
+
+use flashdir_core::backend::BackendKind;
+use flashdir_core::categories::FileCategory;
+use flashdir_core::disk_cache::{DiskCache, EventFilter, EventKind};
+use flashdir_core::perf::PerformanceMonitor;
+use flashdir_core::scan::{self, CacheSource, ItemField, ScanOptions, ScanResponse, ScanResult};
+use flashdir_core::selection::{ExportFormat, SelectionMode, SelectionStore, SelectionTarget};
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+
+static SETUP: Once = Once::new();
+
+/// 隔离数据目录（必须在首次访问 DiskCache 之前调用）
+fn setup() {
+    SETUP.call_once(|| {
+        let home = std::env::temp_dir().join(format!("flashdir-it-home-{}", std::process::id()));
+        std::fs::create_dir_all(&home).unwrap();
+        std::env::set_var("HOME", &home);
+        std::env::set_var("USERPROFILE", &home);
+        std::env::remove_var("XDG_DATA_HOME");
+    });
+}
+
+/// 生成目录树：
+///   root/
+///     a.txt            100
+///     docs/b.md        200
+///     docs/deep/c.bin  300
+///     empty/
+fn make_tree(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("flashdir-it-{}-{}", name, std::process::id()));
+    std::fs::remove_dir_all(&root).ok();
+    std::fs::create_dir_all(root.join("docs/deep")).unwrap();
+    std::fs::create_dir_all(root.join("empty")).unwrap();
+    std::fs::write(root.join("a.txt"), vec![b'a'; 100]).unwrap();
+    std::fs::write(root.join("docs/b.md"), vec![b'b'; 200]).unwrap();
+    std::fs::write(root.join("docs/deep/c.bin"), vec![b'c'; 300]).unwrap();
+    root
+}
+
+async fn scan(root: &Path, force_refresh: bool, options: &ScanOptions) -> ScanResult {
+    scan::scan_directory_with_options(
+        &root.to_string_lossy(),
+        force_refresh,
+        options,
+        PerformanceMonitor::instance(),
+        None,
+    )
+    .await
+    .expect("scan failed")
+}
+
+fn find<'a>(result: &'a ScanResult, suffix: &str) -> &'a flashdir_core::scan::Item {
+    result
+        .items
+        .iter()
+        .find(|i| i.path.ends_with(suffix))
+        .unwrap_or_else(|| panic!("missing item {}", suffix))
+}
+
+#[tokio::test]
+async fn scan_aggregates_sizes_and_hits_cache() {
+    setup();
+    let root = make_tree("scan");
+
+    let result = scan(&root, true, &ScanOptions::default()).await;
+    assert_eq!(result.total_size, 600);
+    assert!(result.errors.is_empty());
+    assert_eq!(find(&result, "docs").size, 500);
+    assert_eq!(find(&result, "deep").size, 300);
+    assert_eq!(find(&result, "empty").size, 0);
+    assert!(find(&result, "a.txt").modified > 0);
+    // 子树计数：docs 下有 b.md、deep/c.bin 两个文件与 deep 一个子目录
+    let docs = find(&result, "docs");
+    assert_eq!((docs.file_count, docs.dir_count), (2, 1));
+    assert_eq!((find(&result, "empty").file_count, find(&result, "a.txt").file_count), (0, 0));
+    // 百分比：b.md 占 docs 的 40%、占总量的 1/3；根下直接子项相对总量
+    let b = find(&result, "b.md");
+    assert!((b.percent_of_parent - 40.0).abs() < 1e-3 && (b.percent_of_total - 100.0 / 3.0).abs() < 1e-3);
+    assert!((docs.percent_of_parent - docs.percent_of_total).abs() < 1e-6);
+    assert_eq!(find(&result, "c.bin").percent_of_parent, 100.0);
+    // 类别统计：a.txt / b.md 为文档，c.bin 未归类
+    let categories: Vec<_> = result.category_stats.iter().map(|s| (s.category, s.total_size, s.file_count)).collect();
+    assert_eq!(categories, vec![(FileCategory::Other, 300, 1), (FileCategory::Document, 300, 2)]);
+    // 按大小降序
+    assert!(result.items.windows(2).all(|w| w[0].size >= w[1].size));
+    // 稳定 ID：同一路径两次扫描 ID 不变
+    let id = find(&result, "b.md").id;
+    assert_ne!(id, 0);
+
+    let cached = scan(&root, false, &ScanOptions::default()).await;
+    assert!(cached.perf_metrics.as_ref().is_some_and(|m| m.cache_hit));
+    assert_eq!(result.cache_usage.source, CacheSource::Fresh);
+    assert_eq!(cached.cache_usage.source, CacheSource::Memory);
+    assert_eq!(cached.cache_usage.reused_entries, cached.items.len());
+    assert_eq!(cached.total_size, 600);
+    assert_eq!(find(&cached, "b.md").id, id);
+    assert!(scan::get_cached_items(&root.to_string_lossy()).is_some());
+
+    // 每次完整扫描记录目录大小历史
+    let history = flashdir_core::disk_cache::DiskCache::instance()
+        .get_size_history(&scan::normalize_cache_key(&root.to_string_lossy()))
+        .unwrap();
+    assert_eq!(history.last().map(|p| p.size), Some(600));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn parent_scan_reuses_fresh_child_cache() {
+    setup();
+    let root = make_tree("subtree");
+
+    let docs = scan(&root.join("docs"), true, &ScanOptions::default()).await;
+    assert_eq!(docs.items.len(), 3);
+
+    // 父目录没有缓存：完整扫描，但 docs 子树直接沿用缓存
+    let parent = scan(&root, false, &ScanOptions::default()).await;
+    assert_eq!(parent.cache_usage.source, CacheSource::Fresh);
+    assert_eq!(parent.cache_usage.reused_entries, 3);
+    assert_eq!(parent.total_size, 600);
+    let docs_item = find(&parent, "docs");
+    assert_eq!((docs_item.size, docs_item.file_count, docs_item.dir_count), (500, 2, 1));
+    assert_eq!(find(&parent, "deep").size, 300);
+
+    // 与完整遍历的结果一致
+    let fresh = scan(&root, true, &ScanOptions::default()).await;
+    assert_eq!(fresh.cache_usage.reused_entries, 0);
+    let summarize = |r: &ScanResult| {
+        let mut items: Vec<_> = r.items.iter().map(|i| (i.path.to_string(), i.size, i.file_count, i.dir_count)).collect();
+        items.sort();
+        items
+    };
+    assert_eq!(summarize(&parent), summarize(&fresh));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn sampled_validation_detects_nested_change() {
+    setup();
+    let root = make_tree("digest");
+    scan(&root, true, &ScanOptions::default()).await;
+    assert_eq!(scan(&root, false, &ScanOptions::default()).await.cache_usage.source, CacheSource::Memory);
+
+    // mtime 精度为秒：等到下一秒再修改，深层目录的 mtime 才会晚于记录值；根目录 mtime 不变
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    std::fs::write(root.join("docs/deep/new.bin"), vec![b'n'; 50]).unwrap();
+    let rescanned = scan(&root, false, &ScanOptions::default()).await;
+    assert_eq!(rescanned.cache_usage.source, CacheSource::Fresh);
+    assert_eq!(rescanned.total_size, 650);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn watcher_expires_cache_on_nested_change() {
+    setup();
+    let root = make_tree("watch");
+    let first = scan(&root, true, &ScanOptions::default()).await;
+    flashdir_core::cache_watch::watch(&first.path, &first.items);
+    assert_eq!(scan(&root, false, &ScanOptions::default()).await.cache_usage.source, CacheSource::Memory);
+
+    // 根目录 mtime 不变，只有监视能发现这次修改
+    std::fs::write(root.join("docs/deep/new.bin"), vec![b'n'; 50]).unwrap();
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    let rescanned = loop {
+        let result = scan(&root, false, &ScanOptions::default()).await;
+        if result.cache_usage.source != CacheSource::Memory || std::time::Instant::now() > deadline {
+            break result;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    };
+    assert_ne!(rescanned.cache_usage.source, CacheSource::Memory);
+    assert_ne!(rescanned.cache_usage.source, CacheSource::Disk);
+    assert_eq!(rescanned.total_size, 650);
+
+    assert!(flashdir_core::cache_watch::unwatch(&first.path));
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn failing_path_is_cached_until_force_refresh() {
+    use flashdir_core::scan_failures::RecentFailure;
+    setup();
+    let root = std::env::temp_dir().join(format!("flashdir-it-missing-{}", std::process::id()));
+    std::fs::remove_dir_all(&root).ok();
+    let path = root.to_string_lossy().to_string();
+    let attempt = |force_refresh: bool| {
+        let path = path.clone();
+        async move {
+            let options = ScanOptions::default();
+            scan::scan_directory_with_options(&path, force_refresh, &options, PerformanceMonitor::instance(), None).await
+        }
+    };
+
+    let first = attempt(false).await.unwrap_err();
+    assert!(first.downcast_ref::<RecentFailure>().is_none());
+    // 有效期内直接返回记录的错误，路径恢复后仍需强制刷新才重试
+    std::fs::create_dir_all(&root).unwrap();
+    let cached = attempt(false).await.unwrap_err();
+    let failure = cached.downcast_ref::<RecentFailure>().expect("cached failure");
+    assert_eq!(failure.error, first.to_string());
+    assert!(failure.retry_after_secs > 0);
+    // 末尾斜杠按同一路径处理，错误中保留调用方给出的写法
+    let slashed = format!("{}/", path);
+    let options = ScanOptions::default();
+    let again = scan::scan_directory_with_options(&slashed, false, &options, PerformanceMonitor::instance(), None)
+        .await
+        .unwrap_err();
+    assert_eq!(again.downcast_ref::<RecentFailure>().map(|f| f.path.as_str()), Some(slashed.as_str()));
+    // 直接返回记录的错误时同样写入事件日志
+    let errors = DiskCache::instance()
+        .query_events(&EventFilter {
+            kinds: Some(vec![EventKind::Error]),
+            path: Some(path.clone()),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(errors.len(), 3);
+    assert!(errors[0].detail.contains("秒后自动重试"));
+    assert!(attempt(true).await.is_ok());
+    assert!(attempt(false).await.is_ok());
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn backends_agree_on_totals() {
+    setup();
+    let root = make_tree("backend");
+
+    let mut results = Vec::new();
+    for backend in [BackendKind::Native, BackendKind::Std] {
+        let options = ScanOptions { backend, ..Default::default() };
+        let result = scan(&root, true, &options).await;
+        let mut items: Vec<_> = result.items.iter().map(|i| (i.path.to_string(), i.size, i.kind as u8)).collect();
+        items.sort();
+        results.push((result.total_size, items));
+    }
+    assert_eq!(results[0].0, 600);
+    assert_eq!(results[0], results[1]);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn count_only_mode_matches_full_scan_totals() {
+    setup();
+    let root = make_tree("count");
+
+    let counted = scan::count_directory(&root.to_string_lossy(), &ScanOptions::default()).unwrap();
+    assert_eq!((counted.file_count, counted.dir_count, counted.total_size), (3, 3, 600));
+    assert_eq!((counted.error_count, counted.unscanned_count), (0, 0));
+    // 不写缓存
+    assert!(scan::get_cached_items(&root.to_string_lossy()).is_none());
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn small_files_fold_before_owners_resolve() {
+    setup();
+    let root = make_tree("fold");
+    let options = ScanOptions { min_file_size: Some(250), include_owner: true, ..Default::default() };
+
+    let result = scan(&root, true, &options).await;
+    // a.txt、docs/b.md 各自合并进所在目录的合成条目，所有者仍与 items 按下标对齐
+    let owners = result.owners.as_ref().expect("owners");
+    assert_eq!(owners.len(), result.items.len());
+    let buckets: Vec<_> = result.items.iter().filter(|i| i.kind == flashdir_core::scan::ItemKind::SmallFiles).collect();
+    assert_eq!(buckets.len(), 2);
+    let volume_id = flashdir_core::fs::volume_id(&result.path);
+    assert!(buckets.iter().all(|b| b.id == scan::stable_item_id(volume_id, &b.path)));
+    assert_eq!(find(&result, "c.bin").size, 300);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn include_globs_count_only_matching_files_and_cache_separately() {
+    setup();
+    let root = make_tree("include");
+    let options = ScanOptions { include_globs: vec!["*.MD; *.bin".to_string()], ..Default::default() };
+
+    let filtered = scan(&root, true, &options).await;
+    assert_eq!(filtered.total_size, 500);
+    assert!(filtered.items.iter().all(|i| !i.path.ends_with("a.txt")));
+    // 目录照常列出，大小只含匹配的文件
+    assert_eq!(find(&filtered, "docs").size, 500);
+    assert_eq!(find(&filtered, "empty").size, 0);
+    let counted = scan::count_directory(&root.to_string_lossy(), &options).unwrap();
+    assert_eq!((counted.file_count, counted.total_size), (2, 500));
+
+    // 模式是缓存 key 的一部分：顺序 / 大小写不同的同一组模式命中缓存，完整扫描不命中过滤后的结果
+    let reordered = ScanOptions { include_globs: vec!["*.bin".to_string(), "*.md".to_string()], ..Default::default() };
+    let cached = scan(&root, false, &reordered).await;
+    assert_eq!((cached.total_size, cached.cache_usage.source), (500, CacheSource::Memory));
+    let full = scan(&root, false, &ScanOptions::default()).await;
+    assert_eq!((full.total_size, full.cache_usage.source), (600, CacheSource::Fresh));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn level_scan_returns_one_level_and_expands_from_cache() {
+    setup();
+    let root = make_tree("level");
+    let root_str = root.to_string_lossy().to_string();
+
+    let level = flashdir_core::level_scan::scan_level(&root_str, &ScanOptions::default(), None).await.unwrap();
+    assert_eq!(level.source, flashdir_core::level_scan::LevelSource::Traversal);
+    assert!(level.complete);
+    let children: Vec<_> = level.children.iter().map(|c| (c.name.as_str(), c.size)).collect();
+    assert_eq!(children, vec![("docs", 500), ("a.txt", 100), ("empty", 0)]);
+    assert_eq!((level.total_size, level.file_count, level.dir_count), (600, 3, 3));
+
+    // 下钻命中根目录扫描留下的缓存
+    let docs = flashdir_core::level_scan::expand_directory(&root.join("docs").to_string_lossy(), None).await.unwrap();
+    assert_eq!(docs.source, flashdir_core::level_scan::LevelSource::Cache);
+    let children: Vec<_> = docs.children.iter().map(|c| (c.name.as_str(), c.size)).collect();
+    assert_eq!(children, vec![("deep", 300), ("b.md", 200)]);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn response_json_matches_frontend_contract() {
+    setup();
+    let root = make_tree("json");
+
+    let options = ScanOptions {
+        extension_stats: true,
+        fields: Some(vec![ItemField::Path, ItemField::Size]),
+        ..Default::default()
+    };
+    let result = scan(&root, true, &options).await;
+    let json = serde_json::to_value(ScanResponse::new(result, options.fields.as_deref())).unwrap();
+
+    for key in ["items", "totalSize", "totalSizeFormatted", "scanTime", "path", "mftAvailable", "errors", "unreadableBytes"] {
+        assert!(json.get(key).is_some(), "missing key {}", key);
+    }
+    let item = json["items"][0].as_object().unwrap();
+    let mut keys: Vec<&str> = item.keys().map(|k| k.as_str()).collect();
+    keys.sort_unstable();
+    assert_eq!(keys, vec!["path", "size"]);
+    assert!(json["extensionStats"].as_array().is_some_and(|s| !s.is_empty()));
+    assert_eq!(json["cacheUsage"]["source"], "fresh");
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn binary_payload_header_and_item_count() {
+    setup();
+    let root = make_tree("binary");
+
+    let result = scan(&root, true, &ScanOptions::default()).await;
+    let bytes = scan::encode_scan_result(&result);
+
+    assert_eq!(u32::from_le_bytes(bytes[0..4].try_into().unwrap()), 0x4644);
+    assert_eq!(bytes[4], scan::BINARY_PROTOCOL_VERSION);
+    assert_eq!(i64::from_le_bytes(bytes[6..14].try_into().unwrap()), 600);
+    let item_count = u32::from_le_bytes(bytes[22..26].try_into().unwrap());
+    assert_eq!(item_count as usize, result.items.len());
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn rescan_reports_diff_against_previous() {
+    setup();
+    let root = make_tree("diff");
+    let root_str = root.to_string_lossy().to_string();
+
+    scan(&root, true, &ScanOptions::default()).await;
+    std::fs::write(root.join("docs/new.log"), vec![b'n'; 1000]).unwrap();
+    std::fs::remove_file(root.join("a.txt")).unwrap();
+    scan(&root, true, &ScanOptions::default()).await;
+
+    let comparison = scan::compare_with_previous_scan(&root_str).expect("no previous scan");
+    let diff = comparison.diff;
+    assert!(diff.added.iter().any(|i| i.path.ends_with("new.log")));
+    assert!(diff.removed.iter().any(|i| i.path.ends_with("a.txt")));
+    let docs = diff.modified.iter().find(|m| m.path.ends_with("docs")).unwrap();
+    assert_eq!(docs.delta, 1000);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn symlinks_are_listed_but_not_followed() {
+    setup();
+    let root = make_tree("symlink");
+    std::os::unix::fs::symlink(root.join("docs"), root.join("docs-link")).unwrap();
+
+    let result = scan(&root, true, &ScanOptions::default()).await;
+    let link = find(&result, "docs-link");
+    assert_eq!(link.kind, flashdir_core::scan::ItemKind::Symlink);
+    assert_eq!(link.size, 0);
+    // 链接目标不重复计算
+    assert_eq!(result.total_size, 600);
+    assert!(!result.items.iter().any(|i| i.path.contains("docs-link/")));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn sparse_files_count_allocated_size() {
+    setup();
+    let root = make_tree("sparse");
+    // 只扩展长度、不写数据：64 MB 全为空洞
+    std::fs::File::create(root.join("disk.img")).unwrap().set_len(64 << 20).unwrap();
+
+    let result = scan(&root, true, &ScanOptions::default()).await;
+    let image = find(&result, "disk.img");
+    assert!(image.size < 64 << 20);
+    assert_eq!(result.total_size, 600 + image.size);
+    assert_eq!(result.sparse_files.len(), 1);
+    let sparse = &result.sparse_files[0];
+    assert!(sparse.path.ends_with("disk.img"));
+    assert_eq!((sparse.logical_size, sparse.allocated_size), (64 << 20, image.size));
+    assert_eq!(result.sparse_savings_bytes, (64 << 20) - image.size);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn unreadable_directories_are_reported() {
+    use std::os::unix::fs::PermissionsExt;
+
+    setup();
+    let root = make_tree("denied");
+    let locked = root.join("docs/deep");
+    std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+    // root 用户不受权限位限制，无法构造拒绝访问
+    if std::fs::read_dir(&locked).is_ok() {
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::fs::remove_dir_all(&root).ok();
+        return;
+    }
+
+    let result = scan(&root, true, &ScanOptions::default()).await;
+    std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    assert_eq!(result.errors.len(), 1);
+    assert!(result.errors[0].path.ends_with("docs/deep"));
+    assert_eq!(result.errors[0].kind, scan::ScanErrorKind::AccessDenied);
+    assert_eq!(result.total_size, 300);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn time_budget_returns_partial_result_without_caching() {
+    setup();
+    let root = make_tree("budget");
+
+    let options = ScanOptions {
+        time_budget_ms: Some(0),
+        ..Default::default()
+    };
+    let partial = scan(&root, true, &options).await;
+    let unscanned = partial.unscanned.expect("budget exceeded but no unscanned dirs");
+    assert!(!unscanned.is_empty());
+    assert!(partial.total_size < 600);
+
+    // 只记录一条扫描完成事件，未遍历的目录数附在其中
+    let finished = DiskCache::instance()
+        .query_events(&EventFilter {
+            kinds: Some(vec![EventKind::ScanFinish]),
+            path: Some(partial.path.to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(finished.len(), 1);
+    assert!(finished[0].detail.contains(&format!("{} 个目录未遍历", unscanned.len())));
+
+    // 部分结果不进缓存：普通扫描得到完整结果
+    let full = scan(&root, false, &ScanOptions::default()).await;
+    assert!(!full.perf_metrics.as_ref().is_some_and(|m| m.cache_hit));
+    assert_eq!(full.total_size, 600);
+    assert!(full.unscanned.is_none());
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn selection_export_and_delete() {
+    setup();
+    let root = make_tree("selection");
+    let session = root.to_string_lossy().to_string();
+
+    let result = scan(&root, true, &ScanOptions::default()).await;
+    let store = SelectionStore::instance();
+    let ids = vec![find(&result, "docs").id, find(&result, "c.bin").id];
+    let summary = store.update(&session, SelectionMode::Set, &SelectionTarget::Ids { ids }, &result.items);
+    assert_eq!(summary.count, 2);
+    // c.bin 在已选目录 docs 下，不重复计算
+    assert_eq!(summary.total_size, 500);
+
+    let csv = root.with_extension("csv");
+    let exported = flashdir_core::selection::export_selected(
+        &session,
+        &result.items,
+        &csv.to_string_lossy(),
+        ExportFormat::Csv,
+    )
+    .unwrap();
+    assert_eq!(exported.succeeded, 2);
+    let content = std::fs::read_to_string(&csv).unwrap();
+    assert!(content.starts_with("path,name,size,is_dir,modified,created"));
+    assert_eq!(content.lines().count(), 3);
+
+    let deleted = flashdir_core::selection::delete_selected(&session, &result.items);
+    assert_eq!(deleted.succeeded, 1);
+    assert_eq!(deleted.affected_bytes, 500);
+    assert!(!root.join("docs").exists());
+    assert!(store.ids(&session).is_empty());
+
+    scan::invalidate_caches_for(&session);
+    let rescanned = scan(&root, false, &ScanOptions::default()).await;
+    assert_eq!(rescanned.total_size, 100);
+
+    std::fs::remove_file(&csv).ok();
+    std::fs::remove_dir_all(&root).ok();
+}
+
+// Windows 上会移入真实回收站，只在其他平台运行（废纸篓位于隔离的 HOME 下）
+#[cfg(not(windows))]
+#[tokio::test]
+async fn delete_to_trash_updates_parent_sizes_from_cache() {
+    setup();
+    let root = make_tree("trash");
+    let docs = root.join("docs");
+
+    scan(&root, true, &ScanOptions::default()).await;
+    let paths = vec![docs.join("b.md").to_string_lossy().to_string(), root.join("a.txt").to_string_lossy().to_string()];
+    let result = flashdir_core::file_ops::delete_to_trash(&paths);
+    assert!(result.failed.is_empty(), "{:?}", result.failed);
+    assert_eq!(result.affected_bytes, 300);
+    assert!(!docs.join("b.md").exists() && !root.join("a.txt").exists());
+
+    let sizes: Vec<_> = result.parents.iter().map(|p| (p.path.rsplit('/').next().unwrap().to_string(), p.size)).collect();
+    assert_eq!(sizes, vec![("docs".to_string(), Some(300)), (format!("flashdir-it-trash-{}", std::process::id()), Some(300))]);
+
+    // 缓存已失效：重新扫描反映删除后的大小
+    let rescanned = scan(&root, false, &ScanOptions::default()).await;
+    assert_eq!(rescanned.total_size, 300);
+    assert_eq!(flashdir_core::recycle_bin::analyze().item_count, 2);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn rename_patches_cached_scan_result() {
+    setup();
+    let root = make_tree("rename");
+    let scanned = scan(&root, true, &ScanOptions::default()).await;
+    let old_id = scanned.items.iter().find(|i| i.name == "docs").unwrap().id;
+
+    let result = flashdir_core::file_ops::rename_item(&root.join("docs").to_string_lossy(), "notes.txt").unwrap();
+    assert!(root.join("notes.txt/deep/c.bin").exists());
+    let item = result.item.expect("条目在缓存会话中");
+    assert_eq!((item.name.as_str(), item.size, item.file_count), ("notes.txt", 500, 2));
+    assert_ne!(item.id, old_id);
+    // 目录不按扩展名归类
+    assert_eq!(item.category, flashdir_core::categories::FileCategory::Other);
+
+    // 缓存中的子树路径已改写，不需要重新扫描
+    let cached = scan(&root, false, &ScanOptions::default()).await;
+    assert_eq!(cached.cache_usage.source, flashdir_core::scan::CacheSource::Memory);
+    let paths: Vec<&str> = cached.items.iter().map(|i| i.path.as_str()).collect();
+    assert!(paths.iter().any(|p| p.ends_with("/notes.txt/deep/c.bin")));
+    assert!(!paths.iter().any(|p| p.contains("/docs")));
+
+    let err = flashdir_core::file_ops::rename_item(&root.join("a.txt").to_string_lossy(), "empty").unwrap_err();
+    assert!(err.to_string().contains("已存在同名条目"), "{}", err);
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn queued_copy_job_runs_and_reports_result() {
+    use flashdir_core::jobs::{self, JobSpec, JobState};
+
+    setup();
+    let root = make_tree("jobs");
+    let dest = root.join("empty");
+    let spec = JobSpec::Copy {
+        paths: vec![root.join("docs").to_string_lossy().to_string()],
+        dest: dest.to_string_lossy().to_string(),
+        conflict: Default::default(),
+    };
+    let job = jobs::enqueue(spec, None).unwrap();
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    let finished = loop {
+        let current = jobs::get(&job.id).unwrap();
+        if current.state.is_finished() {
+            break current;
+        }
+        assert!(std::time::Instant::now() < deadline, "job did not finish");
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    };
+    assert_eq!(finished.state, JobState::Completed);
+    assert!(dest.join("docs/deep/c.bin").exists());
+    assert!(!jobs::cancel(&job.id));
+    assert!(jobs::list().iter().any(|j| j.id == job.id));
+
+    // 缺少确认令牌的永久删除不入队
+    let delete = JobSpec::Delete { paths: vec![root.to_string_lossy().to_string()], token: String::new() };
+    assert_eq!(jobs::enqueue(delete, None).unwrap_err().field, "token");
+    assert!(root.exists());
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn history_file_format_round_trips() {
+    let item = scan::HistoryItem {
+        path: "/data".into(),
+        scan_time: chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+        total_size: 42,
+        size_format: "42 B".into(),
+        item_count: 3,
+    };
+    let json = serde_json::to_value(&item).unwrap();
+    assert_eq!(json["scanTime"], 1_700_000_000);
+    assert_eq!(json["sizeFormat"], "42 B");
+
+    let back: scan::HistoryItem = serde_json::from_value(json).unwrap();
+    assert_eq!(back.total_size, 42);
+}
@@ -0,0 +1,44 @@
+/*
+ * FlashDir 扫描引擎 C ABI（flashdir-ffi）
+ *
+ * 字符串均为 UTF-8、以 NUL 结尾。返回 char* 的函数结果为 JSON 信封：
+ *   成功 {"ok":true,"data":...}   失败 {"ok":false,"error":"..."}
+ * 用完后必须调用 flashdir_string_free 释放。所有函数线程安全；扫描为阻塞调用。
+ *
+ * release 构建使用 panic = "abort"：库内部 panic 会直接终止宿主进程，而不是返回
+ * {"ok":false}；需要隔离故障的宿主应在子进程中调用。
+ */
+#ifndef FLASHDIR_H
+#define FLASHDIR_H
+
+#include <stdbool.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* 扫描目录；options_json 为 ScanOptions 的 JSON（可为 NULL），data 为 ScanResponse */
+char *flashdir_scan(const char *path, const char *options_json, bool force_refresh);
+
+/* 以下分析函数基于本进程最近一次扫描结果，须先调用 flashdir_scan */
+
+/* 按扩展名聚合，data 为 ExtensionStat 数组 */
+char *flashdir_extension_stats(const char *path);
+
+/* 开发者目录分析，data 为 DevAnalysisResult */
+char *flashdir_analyze_dev(const char *path);
+
+/* 与上一次扫描对比，data 为 ScanComparison（没有上一次扫描时为 null） */
+char *flashdir_compare_scans(const char *path);
+
+/* 释放本库返回的字符串；NULL 无操作 */
+void flashdir_string_free(char *s);
+
+/* 库版本（静态字符串，无需释放） */
+const char *flashdir_version(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* FLASHDIR_H */
@@ -0,0 +1,141 @@
+//! flashdir-core 的 C ABI 绑定：Electron（koffi / ffi-napi）、Python（ctypes）、C# 等
+//! 无需桌面外壳即可嵌入 FlashDir 扫描引擎。函数声明见 `include/flashdir.h`。
+//!
+//! 约定：
+//! - 字符串参数为 UTF-8、以 NUL 结尾；可选参数允许传 NULL
+//! - 返回的字符串由本库分配，调用方用完后必须交给 [`flashdir_string_free`] 释放
+//! - 返回值统一为 JSON 信封：成功 `{"ok":true,"data":…}`，失败 `{"ok":false,"error":"…"}`；
+//!   data 的结构与桌面端对应命令的返回值一致（camelCase）
+//! - 所有函数线程安全。扫描是阻塞调用，调用方自行放到后台线程
+//! - 分析类函数基于本进程最近一次扫描的结果（内存缓存，其次磁盘缓存），须先扫描
+//! - release 构建 panic = "abort"：内部 panic 终止宿主进程，不会返回错误信封
+
+use flashdir_core::perf::PerformanceMonitor;
+use flashdir_core::scan::{self, Item, ScanOptions, ScanResponse};
+use flashdir_core::validate;
+use serde::Serialize;
+use std::ffi::{c_char, CStr, CString};
+use std::sync::Arc;
+
+lazy_static::lazy_static! {
+    /// 扫描引擎是异步的，而 C 调用方没有运行时，由本库持有一个
+    static ref RUNTIME: tokio::runtime::Runtime =
+        tokio::runtime::Runtime::new().expect("创建 tokio 运行时失败");
+}
+
+/// 扫描目录，data 为 ScanResponse（与桌面端 scan_directory 命令相同）。
+/// `options_json` 为 ScanOptions 的 JSON，NULL 表示默认选项
+///
+/// # Safety
+/// `path` 必须是有效的 NUL 结尾字符串；`options_json` 为 NULL 或有效的 NUL 结尾字符串
+#[no_mangle]
+pub unsafe extern "C" fn flashdir_scan(
+    path: *const c_char,
+    options_json: *const c_char,
+    force_refresh: bool,
+) -> *mut c_char {
+    respond(|| {
+        let path = validate::existing_dir("path", str_arg(path, "path")?).map_err(|e| e.to_string())?;
+        let options: ScanOptions = match opt_str_arg(options_json, "options_json")? {
+            Some(json) => serde_json::from_str(json).map_err(|e| format!("options_json 解析失败: {}", e))?,
+            None => ScanOptions::default(),
+        };
+        let result = RUNTIME
+            .block_on(scan::scan_directory_with_options(
+                &path,
+                force_refresh,
+                &options,
+                PerformanceMonitor::instance(),
+                None,
+            ))
+            .map_err(|e| format!("扫描失败: {}", e))?;
+        Ok(ScanResponse::new(result, options.fields.as_deref()))
+    })
+}
+
+/// 按扩展名聚合最近一次扫描的文件，data 为 ExtensionStat 数组（按大小降序）
+///
+/// # Safety
+/// `path` 必须是有效的 NUL 结尾字符串
+#[no_mangle]
+pub unsafe extern "C" fn flashdir_extension_stats(path: *const c_char) -> *mut c_char {
+    respond(|| {
+        let items = scanned_items(str_arg(path, "path")?)?;
+        Ok(scan::compute_extension_stats(&items))
+    })
+}
+
+/// 开发者目录分析（node_modules / target / 各类缓存），data 为 DevAnalysisResult
+///
+/// # Safety
+/// `path` 必须是有效的 NUL 结尾字符串
+#[no_mangle]
+pub unsafe extern "C" fn flashdir_analyze_dev(path: *const c_char) -> *mut c_char {
+    respond(|| {
+        let items = scanned_items(str_arg(path, "path")?)?;
+        let total_size: i64 = items.iter().filter(|i| !i.is_dir).map(|i| i.size).sum();
+        Ok(flashdir_core::dev_analyzer::analyze(&items, total_size, items.len()))
+    })
+}
+
+/// 与同一路径上一次扫描对比，data 为 ScanComparison；没有上一次扫描时为 null
+///
+/// # Safety
+/// `path` 必须是有效的 NUL 结尾字符串
+#[no_mangle]
+pub unsafe extern "C" fn flashdir_compare_scans(path: *const c_char) -> *mut c_char {
+    respond(|| Ok(scan::compare_with_previous_scan(str_arg(path, "path")?)))
+}
+
+/// 释放本库返回的字符串；传 NULL 无操作
+///
+/// # Safety
+/// `s` 必须是本库返回且尚未释放的指针
+#[no_mangle]
+pub unsafe extern "C" fn flashdir_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// 库版本（静态字符串，无需释放）
+#[no_mangle]
+pub extern "C" fn flashdir_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char
+}
+
+/// 最近一次扫描的 items（内存缓存优先，其次磁盘缓存）
+fn scanned_items(path: &str) -> Result<Arc<Vec<Item>>, String> {
+    scan::get_cached_items(path)
+        .or_else(|| scan::current_scan_result(path).map(|(_, result)| Arc::new(result.items)))
+        .ok_or_else(|| format!("没有扫描结果，请先调用 flashdir_scan: {}", path))
+}
+
+/// 执行调用并包装为 JSON 信封；可展开的 panic（dev / test 构建）转为错误返回。
+/// release 配置为 panic = "abort"（workspace 级，无法按包覆盖），panic 时直接终止进程，不会跨越 FFI 边界
+fn respond<T: Serialize>(f: impl FnOnce() -> Result<T, String>) -> *mut c_char {
+    let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f))
+        .unwrap_or_else(|_| Err("内部错误（panic）".to_string()));
+    let envelope = match outcome.and_then(|data| serde_json::to_value(data).map_err(|e| e.to_string())) {
+        Ok(data) => serde_json::json!({ "ok": true, "data": data }),
+        Err(error) => serde_json::json!({ "ok": false, "error": error }),
+    };
+    // JSON 中的控制字符均已转义，不会出现内部 NUL
+    CString::new(envelope.to_string())
+        .map(CString::into_raw)
+        .unwrap_or(std::ptr::null_mut())
+}
+
+unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, String> {
+    opt_str_arg(ptr, name)?.ok_or_else(|| format!("{} 不能为 NULL", name))
+}
+
+unsafe fn opt_str_arg<'a>(ptr: *const c_char, name: &str) -> Result<Option<&'a str>, String> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map(Some)
+        .map_err(|_| format!("{} 不是有效的 UTF-8", name))
+}
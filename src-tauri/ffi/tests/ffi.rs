@@ -0,0 +1,52 @@
+// 经 C ABI 驱动 扫描 → 聚合 流程，校验 JSON 信封与字符串所有权约定。
+// 与 core 的集成测试一样把 HOME / USERPROFILE 指向临时目录，不污染真实缓存。
+
+use flashdir_ffi::{flashdir_extension_stats, flashdir_scan, flashdir_string_free, flashdir_version};
+use std::ffi::{c_char, CStr, CString};
+
+/// 取出信封并释放字符串
+unsafe fn take(ptr: *mut c_char) -> serde_json::Value {
+    assert!(!ptr.is_null());
+    let json = CStr::from_ptr(ptr).to_str().unwrap().to_owned();
+    flashdir_string_free(ptr);
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn scan_and_aggregate_through_c_abi() {
+    let home = std::env::temp_dir().join(format!("flashdir-ffi-home-{}", std::process::id()));
+    std::fs::create_dir_all(&home).unwrap();
+    std::env::set_var("HOME", &home);
+    std::env::set_var("USERPROFILE", &home);
+
+    let root = std::env::temp_dir().join(format!("flashdir-ffi-{}", std::process::id()));
+    std::fs::remove_dir_all(&root).ok();
+    std::fs::create_dir_all(root.join("sub")).unwrap();
+    std::fs::write(root.join("a.txt"), vec![b'a'; 100]).unwrap();
+    std::fs::write(root.join("sub/b.log"), vec![b'b'; 300]).unwrap();
+    let path = CString::new(root.to_string_lossy().as_ref()).unwrap();
+    let options = CString::new(r#"{"fields":["path","size"]}"#).unwrap();
+
+    unsafe {
+        let scan = take(flashdir_scan(path.as_ptr(), options.as_ptr(), true));
+        assert_eq!(scan["ok"], true, "{}", scan);
+        assert_eq!(scan["data"]["totalSize"], 400);
+
+        let stats = take(flashdir_extension_stats(path.as_ptr()));
+        assert_eq!(stats["data"][0]["extension"], "log");
+        assert_eq!(stats["data"][1]["totalSize"], 100);
+
+        // 错误以信封返回而不是崩溃
+        let missing = CString::new(root.join("missing").to_string_lossy().as_ref()).unwrap();
+        let err = take(flashdir_scan(missing.as_ptr(), std::ptr::null(), false));
+        assert_eq!(err["ok"], false);
+        let err = take(flashdir_scan(std::ptr::null(), std::ptr::null(), false));
+        assert!(err["error"].as_str().unwrap().contains("path"));
+
+        let version = CStr::from_ptr(flashdir_version()).to_str().unwrap();
+        assert_eq!(version, env!("CARGO_PKG_VERSION"));
+        flashdir_string_free(std::ptr::null_mut());
+    }
+
+    std::fs::remove_dir_all(&root).ok();
+}
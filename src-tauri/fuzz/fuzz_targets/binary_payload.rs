@@ -0,0 +1,15 @@
+// BinaryPayload 解码与解压
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use flashdir_core::binary_protocol::BinaryPayload;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(payload) = BinaryPayload::decode(data) {
+        let compressed = payload.compressed;
+        let original_size = payload.original_size;
+        if let Ok(bytes) = payload.into_data() {
+            assert!(compressed || bytes.len() == original_size);
+        }
+    }
+});
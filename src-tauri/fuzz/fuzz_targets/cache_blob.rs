@@ -0,0 +1,12 @@
+// 磁盘缓存 / 快照中的 bincode 扫描结果：数据库文件可能被截断或被其他版本写入
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(result) = flashdir_core::disk_cache::decode_scan_result(data) {
+        // 解码成功的结果必须能重新编码（缓存回写、二进制协议下发）
+        let _ = flashdir_core::scan::encode_scan_result(&result);
+        let _ = flashdir_core::scan::ScanResponse::new(result, None);
+    }
+});
@@ -0,0 +1,26 @@
+// WASM 排序模块的输入：前端传入的 items 与列名 / 方向 / 关键字。
+// serde_wasm_bindgen 只能在浏览器中运行，这里用 JSON 构造同结构的输入驱动纯 Rust 逻辑。
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use flashdir_sort::{extension_stats_vec, filter_item_vec, sort_item_vec, WasmItem};
+
+fuzz_target!(|data: &[u8]| {
+    // 首字节选择列与方向，其后第一行为关键字，剩余部分为 items JSON
+    let Some((&selector, rest)) = data.split_first() else {
+        return;
+    };
+    let text = String::from_utf8_lossy(rest);
+    let (keyword, json) = text.split_once('\n').unwrap_or(("", &text));
+    let Ok(items) = serde_json::from_str::<Vec<WasmItem>>(json) else {
+        return;
+    };
+
+    let columns = ["name", "size", "type", "modified", "created", "bogus"];
+    let column = columns[(selector & 0x0f) as usize % columns.len()];
+    let direction = if selector & 0x10 != 0 { "asc" } else { "desc" };
+
+    let _ = extension_stats_vec(&items);
+    let mut filtered = filter_item_vec(items, keyword);
+    sort_item_vec(&mut filtered, column, direction);
+});
@@ -9,18 +9,29 @@
 //   --json          以 JSON 格式输出
 //   --no-cache      跳过缓存，强制重新扫描
 //   --no-mft        禁用 MFT 直接读取（回退到目录遍历）
+//   --background    后台模式：低 CPU / I/O 优先级、限速遍历（定时任务使用）
+//   --threads <N>   遍历线程数（覆盖设置，仅本次生效）
+//   --deterministic 单线程确定性遍历（调试用，结果与顺序可复现）
+//   --count-only    只统计文件数、目录数与总大小，不收集条目（更快、更省内存）
+//   --exclude <ID>  启用内置排除预设（可重复）：winSxs | systemVolumeInformation |
+//                   pageFile | recycleBin | proc | sys
+//   --include <GLOB> 只统计匹配的文件（可重复，或用 ; 分隔）：*.mp4;*.mkv
 //   --help          显示帮助
 //
 // 示例:
 //   flashdir-cli C:\Users\Downloads
 //   flashdir-cli C:\Windows --top 10 --sort name
 //   flashdir-cli /home/user --json --no-cache
+//   flashdir-cli / --exclude proc --exclude sys
+//   flashdir-cli D:\ --include "*.mp4;*.mkv"
 
 use std::io::{self, Write};
 use std::time::Instant;
 
-use flashdir::perf::PerformanceMonitor;
-use flashdir::scan;
+use flashdir_core::exclusions::ExclusionPreset;
+use flashdir_core::perf::PerformanceMonitor;
+use flashdir_core::scan;
+use flashdir_core::settings::Settings;
 
 // ─── 命令行参数解析 ────────────────────────────────────────
 
@@ -31,6 +42,12 @@ struct Args {
     json: bool,
     no_cache: bool,
     no_mft: bool,
+    background: bool,
+    threads: Option<usize>,
+    deterministic: bool,
+    count_only: bool,
+    exclude: Vec<ExclusionPreset>,
+    include: Vec<String>,
 }
 
 #[derive(Clone, Copy)]
@@ -53,6 +70,12 @@ fn parse_args() -> Result<Args, String> {
     let mut json = false;
     let mut no_cache = false;
     let mut no_mft = false;
+    let mut background = false;
+    let mut threads: Option<usize> = None;
+    let mut deterministic = false;
+    let mut count_only = false;
+    let mut exclude = Vec::new();
+    let mut include = Vec::new();
 
     let mut i = 1;
     while i < raw.len() {
@@ -79,6 +102,31 @@ fn parse_args() -> Result<Args, String> {
             "--json" => json = true,
             "--no-cache" => no_cache = true,
             "--no-mft" => no_mft = true,
+            "--background" => background = true,
+            "--deterministic" => deterministic = true,
+            "--count-only" => count_only = true,
+            "--threads" => {
+                i += 1;
+                threads = Some(
+                    raw.get(i)
+                        .ok_or("--threads 需要一个数字参数")?
+                        .parse()
+                        .map_err(|_| "--threads 参数必须是数字")?,
+                );
+            }
+            "--exclude" => {
+                i += 1;
+                let id = raw.get(i).ok_or("--exclude 需要一个预设 ID")?;
+                let preset = ExclusionPreset::ALL
+                    .into_iter()
+                    .find(|p| p.id().eq_ignore_ascii_case(id))
+                    .ok_or_else(|| format!("未知的排除预设: {}", id))?;
+                exclude.push(preset);
+            }
+            "--include" => {
+                i += 1;
+                include.push(raw.get(i).ok_or("--include 需要一个 glob 模式")?.clone());
+            }
             arg if !arg.starts_with('-') && path.is_none() => {
                 path = Some(arg.to_string());
             }
@@ -101,6 +149,12 @@ fn parse_args() -> Result<Args, String> {
         json,
         no_cache,
         no_mft,
+        background,
+        threads,
+        deterministic,
+        count_only,
+        exclude,
+        include,
     })
 }
 
@@ -116,12 +170,22 @@ fn print_help() {
   --json          以 JSON 格式输出
   --no-cache      跳过缓存，强制重新扫描
   --no-mft        禁用 MFT 直接读取
+  --background    后台模式：低优先级、限速遍历，不影响前台程序
+  --threads <N>   遍历线程数（仅本次生效，不修改设置）
+  --deterministic 单线程确定性遍历（调试用）
+  --count-only    只统计文件数、目录数与总大小，不列出条目
+  --exclude <ID>  跳过系统目录（可重复）: winSxs | systemVolumeInformation |
+                  pageFile | recycleBin | proc | sys
+  --include <GLOB> 只统计匹配的文件（可重复，或用 ; 分隔）
   --help, -h      显示此帮助
 
 示例:
   flashdir-cli C:\Users\Downloads
   flashdir-cli C:\ --top 10
   flashdir-cli /home/user/Documents --sort name --json
+  flashdir-cli / --exclude proc --exclude sys
+  flashdir-cli D:\ --count-only
+  flashdir-cli D:\ --include "*.mp4;*.mkv"
 "#,
         env!("CARGO_PKG_VERSION")
     );
@@ -208,6 +272,8 @@ fn print_json(items: &[scan::Item], total_size: i64, scan_time: f64, file_count:
         size: i64,
         size_formatted: String,
         is_dir: bool,
+        modified: i64,
+        created: i64,
     }
 
     let output = Output {
@@ -223,6 +289,8 @@ fn print_json(items: &[scan::Item], total_size: i64, scan_time: f64, file_count:
                 size: i.size,
                 size_formatted: i.size_formatted.to_string(),
                 is_dir: i.is_dir,
+                modified: i.modified,
+                created: i.created,
             })
             .collect(),
     };
@@ -232,6 +300,35 @@ fn print_json(items: &[scan::Item], total_size: i64, scan_time: f64, file_count:
 
 // ─── 扫描 ──────────────────────────────────────────────────
 
+/// 只计数模式：不收集条目、不读写缓存
+fn run_count_only(args: &Args, options: &scan::ScanOptions, start: Instant) {
+    let result = match scan::count_directory(&args.path, options) {
+        Ok(r) => r,
+        Err(e) => {
+            if !args.json {
+                eprintln!("\n扫描失败: {}", e);
+            } else {
+                eprintln!("{{\"error\": \"{}\"}}", e);
+            }
+            std::process::exit(1);
+        }
+    };
+
+    let elapsed = start.elapsed().as_secs_f64();
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&result).unwrap_or_default());
+    } else {
+        eprintln!("完成 ({:.2}s)", elapsed);
+        println!(
+            "{} 个文件 | {} 个目录 | 总计: {} | 扫描耗时: {:.2}s",
+            result.file_count, result.dir_count, result.total_size_formatted, elapsed
+        );
+        if result.error_count > 0 || result.unscanned_count > 0 {
+            println!("{} 个目录无法读取，{} 个目录超出时间预算未扫描", result.error_count, result.unscanned_count);
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() {
     let args = match parse_args() {
@@ -258,9 +355,32 @@ async fn main() {
     }
 
     // 调用扫描引擎（不使用 app_handle = 无流式事件）
-    let result = match scan::scan_directory(
+    // 命令行参数只覆盖本进程的设置，不写入 settings.json
+    if args.threads.is_some() || args.deterministic {
+        let mut settings = Settings::current();
+        settings.scan.threads = args.threads.or(settings.scan.threads);
+        settings.scan.deterministic |= args.deterministic;
+        if let Err(e) = settings.apply() {
+            eprintln!("错误: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    let options = scan::ScanOptions {
+        background: args.background,
+        exclude_presets: args.exclude.clone(),
+        include_globs: args.include.clone(),
+        ..Default::default()
+    };
+    if args.count_only {
+        run_count_only(&args, &options, total_start);
+        return;
+    }
+
+    let result = match scan::scan_directory_with_options(
         &args.path,
         args.no_cache || args.no_mft, // no_mft 同时会强制刷新缓存
+        &options,
         perf_monitor,
         None, // CLI 不需要流式事件
     )
@@ -315,4 +435,7 @@ async fn main() {
             );
         }
     }
+
+    // 磁盘缓存在后台写入，退出前等待写完
+    flashdir_core::disk_cache::DiskCache::instance().flush();
 }
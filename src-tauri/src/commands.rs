@@ -1,9 +1,14 @@
 // 命令处理器 - 优化版
 // 集成性能监控、磁盘缓存、二进制协议
 
-use flashdir::scan::{self, HistoryItem, HistoryItemSummary, ScanResult};
-use flashdir::perf::{PerformanceMonitor, ScanMetrics};
-use flashdir::disk_cache::DiskCache;
+use flashdir_core::scan::{self, HistoryItem, HistoryItemSummary, ScanOptions, ScanResponse, ScanResult};
+use flashdir_core::perf::{PerformanceMonitor, ScanMetrics};
+use flashdir_core::disk_cache::DiskCache;
+use flashdir_core::events::{EventSink, Events};
+use flashdir_core::validate::{self, ValidationError};
+use flashdir_core::guard::{CommandGuard, Exclusive};
+use flashdir_core::operation::{Cancelled, Operation};
+use flashdir_core::scan_failures::{self, RecentFailure};
 use crate::AppState;
 use chrono::Utc;
 use std::collections::VecDeque;
@@ -11,6 +16,74 @@ use tauri::{command, State, Emitter};
 use std::path::PathBuf;
 use tokio::{fs, io::AsyncWriteExt};
 
+/// 把核心库的扫描事件（scan-batch / scan-progress 等）转发给前端
+struct TauriEvents(tauri::AppHandle);
+
+impl EventSink for TauriEvents {
+    fn emit_json(&self, event: &str, payload: serde_json::Value) {
+        let _ = self.0.emit(event, payload);
+    }
+}
+
+pub(crate) fn events_for(app: &tauri::AppHandle) -> Events {
+    std::sync::Arc::new(TauriEvents(app.clone()))
+}
+
+/// 命令错误：参数校验失败时为带出错字段的 ValidationError 对象，路径最近无法访问（未重试）时为
+/// 带 retryAfterSecs 的 RecentFailure 对象，其余为错误描述字符串
+#[derive(Debug, serde::Serialize)]
+#[serde(untagged)]
+pub enum CommandError {
+    Invalid(ValidationError),
+    Retry(RecentFailure),
+    Failed(String),
+}
+
+impl From<RecentFailure> for CommandError {
+    fn from(e: RecentFailure) -> Self {
+        CommandError::Retry(e)
+    }
+}
+
+impl From<ValidationError> for CommandError {
+    fn from(e: ValidationError) -> Self {
+        CommandError::Invalid(e)
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(e: String) -> Self {
+        CommandError::Failed(e)
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandError::Invalid(e) => e.fmt(f),
+            CommandError::Retry(e) => e.fmt(f),
+            CommandError::Failed(e) => f.write_str(e),
+        }
+    }
+}
+
+/// 轮询类命令（性能指标 / 统计）的最小采集间隔，间隔内重复调用返回上次结果
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// 独占执行昂贵 / 破坏性命令：同名命令尚未结束时直接报错，不重复执行
+fn exclusive(command: &'static str) -> Result<Exclusive<'static>, String> {
+    CommandGuard::instance().exclusive(command).map_err(|e| e.to_string())
+}
+
+/// 维护操作失败：取消时原样返回"操作已取消"，其余错误加上操作说明
+fn operation_error(context: &str, e: anyhow::Error) -> String {
+    if e.is::<Cancelled>() {
+        e.to_string()
+    } else {
+        format!("{}: {}", context, e)
+    }
+}
+
 fn get_history_file_path() -> Result<PathBuf, String> {
     let home_dir = std::env::var("USERPROFILE")
         .or_else(|_| std::env::var("HOME"))
@@ -45,10 +118,10 @@ pub fn load_history_from_file_sync() -> VecDeque<HistoryItem> {
                                     serde_json::from_str(&content).unwrap_or_default();
 
                                 old_history.into_iter().map(|old| HistoryItem {
-                                    path: smartstring::SmartString::from(old.path),
+                                    path: scan::CompactString::from(old.path),
                                     scan_time: old.scan_time,
                                     total_size: old.total_size,
-                                    size_format: smartstring::SmartString::from(old.size_format),
+                                    size_format: scan::CompactString::from(old.size_format),
                                     item_count: old.items.len(),
                                 }).collect()
                             }
@@ -94,29 +167,75 @@ async fn save_history_to_file_async(history: &VecDeque<HistoryItem>) -> Result<(
     Ok(())
 }
 
-/// 扫描目录 - 优化版（支持渐进式流式传输）
+/// 扫描目录 - 优化版（支持渐进式流式传输）；`options.fields` 可只返回部分 item 字段
 #[command]
 pub async fn scan_directory(
     path: String,
     force_refresh: bool,
+    options: Option<ScanOptions>,
     app: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<ScanResult, String> {
-    let path = path.trim().to_string();
+) -> Result<ScanResponse, CommandError> {
+    let options = options.unwrap_or_default();
+    let elevation = probe_elevation(&path).await;
+    let result = run_scan(path, force_refresh, &options, app, &state).await?;
+    let mut response = ScanResponse::new(result, options.fields.as_deref());
+    response.elevation = elevation;
+    Ok(response)
+}
 
-    if path.is_empty() {
-        return Err("请提供有效的目录路径".to_string());
+/// 扫描受保护根目录前的浅层权限探测（只打开一级子目录），非受保护根目录返回 None
+async fn probe_elevation(path: &str) -> Option<flashdir_core::elevated::ElevationAdvice> {
+    // 最近无法访问的路径不再探测（扫描随即返回记录的错误）
+    if scan_failures::recent(path).is_some() {
+        return None;
     }
+    let path = path.to_string();
+    tokio::task::spawn_blocking(move || flashdir_core::elevated::probe(&path))
+        .await
+        .ok()
+        .flatten()
+}
+
+/// 执行扫描并记录历史（各扫描命令共用）
+async fn run_scan(
+    path: String,
+    force_refresh: bool,
+    options: &ScanOptions,
+    app: tauri::AppHandle,
+    state: &State<'_, AppState>,
+) -> Result<ScanResult, CommandError> {
+    // 最近无法访问的路径不再校验（校验本身也要访问文件系统），直接返回上次的错误
+    if force_refresh {
+        scan_failures::clear(&path);
+    } else if let Some(failure) = scan_failures::recent(&path) {
+        return Err(failure.into());
+    }
+    let path = validate::existing_dir("path", &path).inspect_err(|e| {
+        if e.code == validate::ValidationCode::NotFound {
+            scan_failures::record(&path, &e.message);
+        }
+    })?;
 
     let perf_monitor = PerformanceMonitor::instance();
 
-    match scan::scan_directory(&path, force_refresh, perf_monitor, Some(app)).await {
+    match scan::scan_directory_with_options(&path, force_refresh, options, perf_monitor, Some(events_for(&app)))
+        .await
+    {
         Ok(result) => {
+            // 历史与访问记录统一使用规范化后的展示路径（与缓存 key 一致），
+            // 不记录用户输入的原样路径或 \\?\ 前缀形式
+            DiskCache::instance()
+                .record_visit(&result.path, flashdir_core::frecency::VisitKind::Scan)
+                .ok();
+            // 之后树内的变更立即让缓存过期，而不是等根目录 mtime 变化
+            flashdir_core::cache_watch::watch(&result.path, &result.items);
+
             let history_item = HistoryItem {
-                path: smartstring::SmartString::from(path.clone()),
+                path: scan::CompactString::from(result.path.as_str()),
                 scan_time: Utc::now(),
                 total_size: result.total_size,
-                size_format: smartstring::SmartString::from(result.total_size_formatted.as_str()),
+                size_format: scan::CompactString::from(result.total_size_formatted.as_str()),
                 item_count: result.items.len(),
             };
 
@@ -138,7 +257,10 @@ pub async fn scan_directory(
 
             Ok(result)
         }
-        Err(e) => Err(e.to_string()),
+        Err(e) => match e.downcast::<RecentFailure>() {
+            Ok(failure) => Err(failure.into()),
+            Err(e) => Err(CommandError::Failed(e.to_string())),
+        },
     }
 }
 
@@ -149,8 +271,8 @@ pub async fn scan_directory_binary(
     force_refresh: bool,
     app: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<tauri::ipc::Response, String> {
-    let result = scan_directory(path, force_refresh, app, state).await?;
+) -> Result<tauri::ipc::Response, CommandError> {
+    let result = run_scan(path, force_refresh, &ScanOptions::default(), app, &state).await?;
     Ok(tauri::ipc::Response::new(scan::encode_scan_result(&result)))
 }
 
@@ -159,14 +281,24 @@ pub async fn scan_directory_binary(
 pub async fn scan_directories_batch(
     paths: Vec<String>,
     force_refresh: bool,
+    options: Option<ScanOptions>,
     app: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<Vec<ScanResult>, String> {
+) -> Result<Vec<ScanResponse>, CommandError> {
+    if paths.is_empty() {
+        return Err(ValidationError::new("paths", validate::ValidationCode::Required, "至少提供一个路径").into());
+    }
+    let options = options.unwrap_or_default();
     let mut results = Vec::with_capacity(paths.len());
 
     for path in paths {
-        match scan_directory(path, force_refresh, app.clone(), state.clone()).await {
-            Ok(result) => results.push(result),
+        let elevation = probe_elevation(&path).await;
+        match run_scan(path, force_refresh, &options, app.clone(), &state).await {
+            Ok(result) => {
+                let mut response = ScanResponse::new(result, options.fields.as_deref());
+                response.elevation = elevation;
+                results.push(response);
+            }
             Err(e) => eprintln!("扫描失败: {}", e),
         }
     }
@@ -174,6 +306,83 @@ pub async fn scan_directories_batch(
     Ok(results)
 }
 
+/// 只计数扫描：返回文件数、目录数与总大小，不收集条目、不写缓存（驱动器概览、进度估计）
+#[command]
+pub async fn count_directory(
+    path: String,
+    options: Option<ScanOptions>,
+) -> Result<flashdir_core::scan::CountResult, CommandError> {
+    let path = validate::existing_dir("path", &path)?;
+    let options = options.unwrap_or_default();
+    tokio::task::spawn_blocking(move || flashdir_core::scan::count_directory(&path, &options))
+        .await
+        .map_err(|e| CommandError::Failed(format!("计数失败: {}", e)))?
+        .map_err(|e| CommandError::Failed(e.to_string()))
+}
+
+/// 抽样估算目录总大小：1 秒内返回带置信区间的近似值，estimated 为 true 时界面标注“估算”
+#[command]
+pub async fn estimate_directory_size(
+    path: String,
+    options: Option<ScanOptions>,
+) -> Result<flashdir_core::estimate::SizeEstimate, CommandError> {
+    let path = validate::existing_dir("path", &path)?;
+    let options = options.unwrap_or_default();
+    tokio::task::spawn_blocking(move || flashdir_core::estimate::estimate_directory(&path, &options))
+        .await
+        .map_err(|e| CommandError::Failed(format!("估算失败: {}", e)))?
+        .map_err(|e| CommandError::Failed(e.to_string()))
+}
+
+/// 逐层浏览：只返回 path 的直接子项（子目录带递归大小），完整结果留在后端缓存
+#[command]
+pub async fn scan_level(
+    path: String,
+    options: Option<ScanOptions>,
+    app: tauri::AppHandle,
+) -> Result<flashdir_core::level_scan::LevelResult, CommandError> {
+    let path = validate::existing_dir("path", &path)?;
+    let options = options.unwrap_or_default();
+    flashdir_core::level_scan::scan_level(&path, &options, Some(events_for(&app)))
+        .await
+        .map_err(|e| CommandError::Failed(e.to_string()))
+}
+
+/// 按需展开子目录：通常命中上一层扫描的缓存
+#[command]
+pub async fn expand_directory(
+    path: String,
+    app: tauri::AppHandle,
+) -> Result<flashdir_core::level_scan::LevelResult, CommandError> {
+    let path = validate::existing_dir("path", &path)?;
+    flashdir_core::level_scan::expand_directory(&path, Some(events_for(&app)))
+        .await
+        .map_err(|e| CommandError::Failed(e.to_string()))
+}
+
+/// 列出目录的直接子项（不递归、不读写缓存），供文件浏览面板与路径补全
+#[command]
+pub async fn list_directory(
+    path: String,
+    sort: Option<flashdir_core::dir_listing::ListSort>,
+) -> Result<flashdir_core::dir_listing::DirListing, CommandError> {
+    let path = validate::existing_dir("path", &path)?;
+    tokio::task::spawn_blocking(move || flashdir_core::dir_listing::list_directory(&path, sort.unwrap_or_default()))
+        .await
+        .map_err(|e| CommandError::Failed(format!("列举目录失败: {}", e)))?
+        .map_err(|e| CommandError::Failed(e.to_string()))
+}
+
+/// 全系统扫描：并行扫描所有固定卷，返回逐盘明细与全局 Top 100 大目录
+#[command]
+pub async fn scan_all_drives(
+    force_refresh: bool,
+    app: tauri::AppHandle,
+) -> Result<flashdir_core::system_scan::SystemScanResult, String> {
+    let _running = exclusive("scan_all_drives")?;
+    Ok(flashdir_core::system_scan::scan_all_drives(force_refresh, Some(events_for(&app))).await)
+}
+
 #[command]
 pub fn get_history_summary(state: State<'_, AppState>) -> Vec<HistoryItemSummary> {
     let history = state.history.lock();
@@ -191,6 +400,7 @@ pub fn get_history(state: State<'_, AppState>) -> Vec<HistoryItem> {
 
 #[command]
 pub async fn clear_history(state: State<'_, AppState>) -> Result<(), String> {
+    let _running = exclusive("clear_history")?;
     {
         let mut history = state.history.lock();
         history.clear();
@@ -202,7 +412,9 @@ pub async fn clear_history(state: State<'_, AppState>) -> Result<(), String> {
 /// 获取性能指标
 #[command]
 pub fn get_performance_metrics() -> Option<ScanMetrics> {
-    PerformanceMonitor::instance().get_current_metrics()
+    CommandGuard::instance().throttle("get_performance_metrics", POLL_INTERVAL, || {
+        PerformanceMonitor::instance().get_current_metrics()
+    })
 }
 
 /// 获取性能历史
@@ -219,47 +431,346 @@ pub fn clear_performance_history() {
 
 /// 获取性能摘要
 #[command]
-pub fn get_performance_summary() -> flashdir::perf::PerformanceSummary {
-    PerformanceMonitor::instance().get_summary()
+pub fn get_performance_summary() -> flashdir_core::perf::PerformanceSummary {
+    CommandGuard::instance().throttle("get_performance_summary", POLL_INTERVAL, || {
+        PerformanceMonitor::instance().get_summary()
+    })
 }
 
 /// 获取磁盘缓存统计
 #[command]
-pub fn get_disk_cache_stats() -> flashdir::disk_cache::CacheStats {
-    DiskCache::instance().get_stats()
+pub fn get_disk_cache_stats() -> flashdir_core::disk_cache::CacheStats {
+    CommandGuard::instance().throttle("get_disk_cache_stats", POLL_INTERVAL, || DiskCache::instance().get_stats())
+}
+
+/// 列出磁盘缓存中的扫描结果：路径、大小、条目数、缓存时长与命中次数
+#[command]
+pub async fn list_cache_entries() -> Result<Vec<flashdir_core::disk_cache::CacheEntryInfo>, String> {
+    tokio::task::spawn_blocking(|| DiskCache::instance().list_entries())
+        .await
+        .map_err(|e| format!("读取缓存列表失败: {}", e))?
+        .map_err(|e| format!("读取缓存列表失败: {}", e))
+}
+
+/// 删除一条扫描缓存（内存与磁盘），返回是否存在
+#[command]
+pub fn delete_cache_entry(path: String) -> Result<bool, CommandError> {
+    validate::absolute_path("path", &path)?;
+    flashdir_core::scan::delete_cache_entry(&path)
+        .map_err(|e| CommandError::Failed(format!("删除缓存失败: {}", e)))
+}
+
+/// 把缓存的扫描结果导出为可移植文件（paths 为空时导出全部完整结果），
+/// 发出 operation-progress 事件，可用 operationId 取消
+#[command]
+pub async fn export_cache(
+    dest: String,
+    paths: Option<Vec<String>>,
+    operation_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<flashdir_core::cache_transfer::CacheExportResult, CommandError> {
+    let dest = validate::output_file("dest", &dest)?;
+    let paths = paths.map(|p| validate::absolute_paths("paths", &p)).transpose()?;
+    let op = Operation::start("export_cache", operation_id, Some(events_for(&app)));
+
+    tokio::task::spawn_blocking(move || {
+        flashdir_core::cache_transfer::export_cache(&DiskCache::instance(), paths.as_deref(), &dest, &op)
+    })
+    .await
+    .map_err(|e| format!("导出缓存失败: {}", e))?
+    .map_err(|e| CommandError::Failed(operation_error("导出缓存失败", e)))
+}
+
+/// 导入其他机器导出的缓存文件；本机已有同一目录的缓存时跳过，overwrite 为 true 时替换。
+/// 导入的结果用 open_cached_scan 按路径打开
+#[command]
+pub async fn import_cache(
+    src: String,
+    overwrite: Option<bool>,
+    operation_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<flashdir_core::cache_transfer::CacheImportResult, CommandError> {
+    let src = validate::existing_path("src", &src)?;
+    let _running = exclusive("import_cache")?;
+    let op = Operation::start("import_cache", operation_id, Some(events_for(&app)));
+
+    tokio::task::spawn_blocking(move || {
+        flashdir_core::cache_transfer::import_cache(&DiskCache::instance(), &src, overwrite.unwrap_or(false), &op)
+    })
+    .await
+    .map_err(|e| format!("导入缓存失败: {}", e))?
+    .map_err(|e| CommandError::Failed(operation_error("导入缓存失败", e)))
+}
+
+/// 打开缓存中的扫描结果而不扫描（导入的结果或本机已不存在的目录），之后可按 path 使用会话类命令
+#[command]
+pub async fn open_cached_scan(path: String, options: Option<ScanOptions>) -> Result<ScanResponse, CommandError> {
+    let path = validate::absolute_path("path", &path)?;
+    let options = options.unwrap_or_default();
+    let result = tokio::task::spawn_blocking(move || scan::open_cached_result(&path))
+        .await
+        .map_err(|e| format!("打开缓存失败: {}", e))?
+        .ok_or_else(|| CommandError::Failed("没有该目录的扫描缓存".to_string()))?;
+    Ok(ScanResponse::new(result, options.fields.as_deref()))
+}
+
+/// 清除磁盘缓存（分批删除，发出 operation-progress 事件，可用 operationId 取消）
+#[command]
+pub async fn clear_disk_cache(operation_id: Option<String>, app: tauri::AppHandle) -> Result<(), String> {
+    let _running = exclusive("clear_disk_cache")?;
+    let op = Operation::start("clear_disk_cache", operation_id, Some(events_for(&app)));
+    tokio::task::spawn_blocking(move || DiskCache::instance().clear_with(&op))
+        .await
+        .map_err(|e| format!("清除缓存失败: {}", e))?
+        .map_err(|e| operation_error("清除缓存失败", e))
+}
+
+/// 压缩磁盘缓存数据库，把删除缓存留下的空间归还文件系统（分批回收，发出 operation-progress 事件，可用 operationId 取消）
+#[command]
+pub async fn compact_disk_cache(
+    operation_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<flashdir_core::disk_cache::CompactResult, String> {
+    let _running = exclusive("compact_disk_cache")?;
+    let op = Operation::start("compact_disk_cache", operation_id, Some(events_for(&app)));
+    tokio::task::spawn_blocking(move || DiskCache::instance().compact(&op))
+        .await
+        .map_err(|e| format!("压缩缓存失败: {}", e))?
+        .map_err(|e| operation_error("压缩缓存失败", e))
+}
+
+/// 内置排除预设列表（扫描选项 excludePresets 的可选值）
+#[command]
+pub fn get_exclusion_presets() -> Vec<flashdir_core::exclusions::ExclusionPresetInfo> {
+    flashdir_core::exclusions::presets()
+}
+
+/// 读取用户设置
+#[command]
+pub fn get_settings() -> flashdir_core::settings::Settings {
+    flashdir_core::settings::Settings::current()
+}
+
+/// 保存用户设置（下次扫描起生效），返回保存后的设置
+#[command]
+pub fn update_settings(
+    settings: flashdir_core::settings::Settings,
+) -> Result<flashdir_core::settings::Settings, CommandError> {
+    settings.validate()?;
+    settings
+        .clone()
+        .save()
+        .map_err(|e| CommandError::Failed(format!("保存设置失败: {}", e)))?;
+    // 缓存有效期 / 大小上限 / 不缓存目录立即作用于已有的缓存
+    if let Err(e) = flashdir_core::scan::apply_cache_policy() {
+        eprintln!("[Settings] 应用缓存策略失败: {}", e);
+    }
+    Ok(settings)
+}
+
+/// 生成报告使用的主题与区域格式：参数优先，其次取设置，设置为跟随系统时读取系统偏好
+#[command]
+pub fn get_report_style(
+    theme: Option<flashdir_core::settings::ThemePreference>,
+    locale: Option<String>,
+) -> Result<flashdir_core::report_style::ReportStyle, CommandError> {
+    if let Some(locale) = &locale {
+        validate::language_tag("locale", locale)?;
+    }
+    Ok(flashdir_core::report_style::ReportStyle::resolve(theme, locale.as_deref()))
+}
+
+/// treemap 最多展开的层数
+const MAX_TREEMAP_DEPTH: usize = 32;
+
+/// 在后端计算矩形树图（squarified 布局），返回视口坐标下可直接绘制的矩形；
+/// scan_id 为扫描根路径，max_depth 缺省为 3
+#[command]
+pub async fn compute_treemap(
+    scan_id: String,
+    viewport_w: f64,
+    viewport_h: f64,
+    max_depth: Option<usize>,
+) -> Result<flashdir_core::treemap::TreemapLayout, CommandError> {
+    let width = validate::positive("viewportW", viewport_w)?;
+    let height = validate::positive("viewportH", viewport_h)?;
+    let max_depth = validate::limit("maxDepth", max_depth, 3, MAX_TREEMAP_DEPTH)? as u32;
+    let items = session_items(&scan_id)?;
+    tokio::task::spawn_blocking(move || flashdir_core::treemap::compute(&items, width, height, max_depth))
+        .await
+        .map_err(|e| CommandError::Failed(format!("计算 treemap 失败: {}", e)))
+}
+
+// ─── 定时扫描 ────────────────────────────────────────────
+
+/// 添加定时扫描：cron_or_interval 为间隔（30m / 6h / 1d）或 5 段 cron 表达式
+#[command]
+pub fn add_schedule(path: String, cron_or_interval: String) -> Result<flashdir_core::schedule::Schedule, CommandError> {
+    let path = validate::existing_dir("path", &path)?;
+    let schedule = flashdir_core::schedule::Schedule::new(&path, &cron_or_interval, Utc::now().timestamp())?;
+    flashdir_core::schedule::add(schedule.clone()).map_err(|e| format!("保存定时扫描失败: {}", e))?;
+    Ok(schedule)
+}
+
+/// 列出定时扫描，按下一次运行时间排序
+#[command]
+pub fn list_schedules() -> Vec<flashdir_core::schedule::Schedule> {
+    flashdir_core::schedule::list()
+}
+
+/// 删除定时扫描，不存在时返回 false
+#[command]
+pub fn remove_schedule(id: String) -> Result<bool, String> {
+    flashdir_core::schedule::remove(&id).map_err(|e| format!("删除定时扫描失败: {}", e))
+}
+
+// ─── 扫描配置 ────────────────────────────────────────────
+
+/// 列出扫描配置：内置配置（快速概览 / 完整审计 / 网络共享）在前，其余按名称排序
+#[command]
+pub fn list_scan_profiles() -> Vec<flashdir_core::scan_profiles::SavedProfile> {
+    flashdir_core::scan_profiles::list()
+}
+
+/// 按名称读取扫描配置（不区分大小写）
+#[command]
+pub fn load_scan_profile(name: String) -> Result<flashdir_core::scan_profiles::SavedProfile, CommandError> {
+    flashdir_core::scan_profiles::get(&name).ok_or_else(|| {
+        ValidationError::new("name", validate::ValidationCode::NotFound, format!("扫描配置不存在: {}", name)).into()
+    })
+}
+
+/// 保存扫描配置，同名配置（含内置配置）被覆盖
+#[command]
+pub fn save_scan_profile(
+    name: String,
+    options: ScanOptions,
+    description: Option<String>,
+) -> Result<flashdir_core::scan_profiles::SavedProfile, CommandError> {
+    let name = flashdir_core::scan_profiles::validate_name("name", &name)?;
+    Ok(flashdir_core::scan_profiles::save(&name, description.as_deref().unwrap_or(""), options)
+        .map_err(|e| format!("保存扫描配置失败: {}", e))?)
+}
+
+/// 删除用户保存的扫描配置，不存在时返回 false；被覆盖的内置配置恢复原样
+#[command]
+pub fn delete_scan_profile(name: String) -> Result<bool, String> {
+    flashdir_core::scan_profiles::remove(&name).map_err(|e| format!("删除扫描配置失败: {}", e))
+}
+
+/// 按命名配置的选项扫描目录（同 scan_directory）
+#[command]
+pub async fn scan_with_profile(
+    path: String,
+    profile: String,
+    force_refresh: bool,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ScanResponse, CommandError> {
+    let options = load_scan_profile(profile)?.options;
+    scan_directory(path, force_refresh, Some(options), app, state).await
+}
+
+// ─── 空闲扫描 ────────────────────────────────────────────
+
+/// 开关空闲扫描并设置静默时段（"HH:MM"，可跨午夜；None 表示不限），写入设置并立即生效
+#[command]
+pub fn set_idle_scan(
+    enabled: bool,
+    quiet_hours: Option<flashdir_core::settings::QuietHours>,
+) -> Result<flashdir_core::settings::IdleScanSettings, CommandError> {
+    let mut settings = flashdir_core::settings::Settings::current();
+    settings.idle.enabled = enabled;
+    settings.idle.quiet_hours = quiet_hours;
+    settings.validate()?;
+    let idle = settings.idle.clone();
+    settings
+        .save()
+        .map_err(|e| CommandError::Failed(format!("保存设置失败: {}", e)))?;
+    Ok(idle)
+}
+
+/// 空闲扫描状态：输入空闲时长、系统 CPU、是否处于静默时段、下一个待刷新的收藏路径
+#[command]
+pub async fn get_idle_scan_status() -> Result<flashdir_core::idle_scan::IdleStatus, String> {
+    tokio::task::spawn_blocking(flashdir_core::idle_scan::status)
+        .await
+        .map_err(|e| format!("读取空闲状态失败: {}", e))
+}
+
+/// 取消执行中的维护操作（清除缓存 / 导出 / 保存快照）或文件操作作业（排队中的直接取消），
+/// 操作不存在时返回 false
+#[command]
+pub fn cancel_operation(operation_id: String) -> bool {
+    flashdir_core::jobs::cancel(&operation_id) || flashdir_core::operation::cancel(&operation_id)
 }
 
-/// 清除磁盘缓存
+/// 把文件操作（移入回收站 / 永久删除 / 移动 / 复制）加入作业队列，立即返回排队中的作业；
+/// 进度经 operation-progress（id 为作业 ID）发出，状态变化发出 job-updated
 #[command]
-pub fn clear_disk_cache() -> Result<(), String> {
+pub fn enqueue_operation(
+    spec: flashdir_core::jobs::JobSpec,
+    app: tauri::AppHandle,
+) -> Result<flashdir_core::jobs::Job, CommandError> {
+    Ok(flashdir_core::jobs::enqueue(spec, Some(events_for(&app)))?)
+}
+
+/// 查询作业状态与结果，作业不存在（或已被清理）时返回 None
+#[command]
+pub fn get_operation_status(operation_id: String) -> Option<flashdir_core::jobs::Job> {
+    flashdir_core::jobs::get(&operation_id)
+}
+
+/// 作业队列中的全部作业（入队顺序，含最近结束的作业）
+#[command]
+pub fn list_operations() -> Vec<flashdir_core::jobs::Job> {
+    flashdir_core::jobs::list()
+}
+
+/// 查询事件日志（扫描开始/结束、缓存命中/未命中、失效、错误），最新在前
+#[command]
+pub fn get_event_log(
+    filter: Option<flashdir_core::disk_cache::EventFilter>,
+) -> Result<Vec<flashdir_core::disk_cache::EventRecord>, String> {
     DiskCache::instance()
-        .clear()
-        .map_err(|e| format!("清除缓存失败: {}", e))
+        .query_events(&filter.unwrap_or_default())
+        .map_err(|e| format!("查询事件日志失败: {}", e))
 }
 
 /// 获取内存缓存统计
 #[command]
-pub fn get_memory_cache_stats() -> MemoryCacheStats {
-    // 返回内存缓存统计
-    MemoryCacheStats {
-        max_entries: 30,
-        max_size_mb: 200,
-        current_entries: 0, // 需要实现获取逻辑
-        current_size_mb: 0.0,
-    }
+pub fn get_memory_cache_stats() -> flashdir_core::scan::MemoryCacheStats {
+    flashdir_core::scan::memory_cache_stats()
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
-pub struct MemoryCacheStats {
-    pub max_entries: usize,
-    pub max_size_mb: usize,
-    pub current_entries: usize,
-    pub current_size_mb: f64,
+/// 调整内存缓存的容量上限（条数、估计大小 MB）并保存到设置，超出新上限的会话立即关闭
+/// （可从磁盘缓存读回），返回调整后的统计
+#[command]
+pub fn set_memory_cache_limits(
+    max_entries: usize,
+    max_mb: u64,
+) -> Result<flashdir_core::scan::MemoryCacheStats, CommandError> {
+    let mut settings = flashdir_core::settings::Settings::current();
+    settings.cache.memory_max_entries = max_entries;
+    settings.cache.memory_max_mb = max_mb;
+    settings.validate()?;
+    settings
+        .save()
+        .map_err(|e| CommandError::Failed(format!("保存设置失败: {}", e)))?;
+    if let Err(e) = flashdir_core::scan::apply_cache_policy() {
+        eprintln!("[Settings] 应用缓存策略失败: {}", e);
+    }
+    Ok(flashdir_core::scan::memory_cache_stats())
 }
 
 /// 获取系统信息
 #[command]
 pub fn get_system_info() -> SystemInfo {
+    // refresh_all 会枚举全部进程，开销较大
+    CommandGuard::instance().throttle("get_system_info", std::time::Duration::from_secs(1), collect_system_info)
+}
+
+fn collect_system_info() -> SystemInfo {
     use sysinfo::{System, RefreshKind, CpuRefreshKind};
 
     let mut system = System::new_with_specifics(
@@ -289,56 +800,97 @@ pub struct SystemInfo {
     pub os_version: String,
 }
 
+/// 本次启动的数据迁移结果（版本、已执行的迁移、备份位置、错误）
+#[command]
+pub fn get_migration_status() -> Option<flashdir_core::migration::MigrationStatus> {
+    flashdir_core::migration::status()
+}
+
 /// 使用系统默认程序打开文件或目录
 #[command]
-pub async fn open_path(app: tauri::AppHandle, path: String) -> Result<(), String> {
-    use tauri_plugin_shell::ShellExt;
+pub async fn open_path(path: String) -> Result<(), CommandError> {
+    let path = validate::existing_path("path", &path)?;
 
-    let target = if path.starts_with("//?/") {
-        // 将 canonicalize 风格路径转换回普通 Windows 路径
-        path[4..].replace('/', "\\")
-    } else {
-        path.replace('/', "\\")
-    };
+    DiskCache::instance()
+        .record_visit(&flashdir_core::fs::display_path(&path), flashdir_core::frecency::VisitKind::Open)
+        .ok();
+
+    tokio::task::spawn_blocking(move || flashdir_core::fs::open_with_default_app(&path))
+        .await
+        .map_err(|e| format!("无法打开路径: {}", e))?
+        .map_err(|e| CommandError::Failed(format!("无法打开路径: {}", e)))
+}
 
-    app.shell()
-        .open(&target, None)
-        .map_err(|e| format!("无法打开路径: {}", e))
+/// 在系统文件管理器中显示条目（打开所在目录并选中）
+#[command]
+pub async fn reveal_in_file_manager(path: String) -> Result<(), CommandError> {
+    let path = validate::existing_path("path", &path)?;
+    tokio::task::spawn_blocking(move || flashdir_core::fs::reveal_in_file_manager(&path))
+        .await
+        .map_err(|e| format!("无法在文件管理器中显示: {}", e))?
+        .map_err(|e| CommandError::Failed(format!("无法在文件管理器中显示: {}", e)))
+}
+
+/// 预览文件内容：文本读取前 maxBytes 字节（缺省 64 KB，上限 1 MB）并检测编码，
+/// 图片返回缩略图（data URL），其他文件返回开头的十六进制转储
+#[command]
+pub async fn preview_file(
+    path: String,
+    max_bytes: Option<usize>,
+) -> Result<flashdir_core::preview::FilePreview, CommandError> {
+    use flashdir_core::preview::{DEFAULT_PREVIEW_BYTES, MAX_PREVIEW_BYTES};
+
+    let path = validate::existing_path("path", &path)?;
+    let max_bytes = validate::limit("maxBytes", max_bytes, DEFAULT_PREVIEW_BYTES, MAX_PREVIEW_BYTES)?;
+    tokio::task::spawn_blocking(move || flashdir_core::preview::preview_file(&path, max_bytes))
+        .await
+        .map_err(|e| format!("预览失败: {}", e))?
+        .map_err(|e| CommandError::Failed(e.to_string()))
+}
+
+/// 单个条目的详细属性（属性对话框）：时间戳、属性、所有者、链接目标、数据流、
+/// 分配 / 压缩后大小与子项统计；目录大小与递归计数取自扫描缓存，不重新扫描
+#[command]
+pub async fn get_item_details(path: String) -> Result<flashdir_core::item_details::ItemDetails, CommandError> {
+    // 不要求路径可跟随：断开的符号链接也能查看属性
+    let path = validate::absolute_path("path", &path)?;
+    tokio::task::spawn_blocking(move || flashdir_core::item_details::get_item_details(&path))
+        .await
+        .map_err(|e| format!("读取属性失败: {}", e))?
+        .map_err(|e| CommandError::Failed(e.to_string()))
 }
 
 /// 判断路径是否为目录
 #[command]
-pub async fn is_directory(path: String) -> Result<bool, String> {
-    let p = if path.starts_with("//?/") {
-        PathBuf::from(&path[4..].replace('/', "\\"))
-    } else {
-        PathBuf::from(&path.replace('/', "\\"))
-    };
+pub async fn is_directory(path: String) -> Result<bool, CommandError> {
+    let path = validate::absolute_path("path", &path)?;
+    // 扩展长度形式，超过 MAX_PATH 的目录也能访问
+    let p = flashdir_core::fs::to_extended_length(&path);
 
     match fs::metadata(&p).await {
         Ok(m) => Ok(m.is_dir()),
-        Err(e) => Err(format!("无法访问路径: {}", e)),
+        Err(e) => Err(CommandError::Failed(format!("无法访问路径: {}", e))),
     }
 }
 
 /// 检测当前进程是否以管理员/提升权限运行
 #[command]
 pub fn is_admin() -> bool {
-    flashdir::fs::is_admin()
+    flashdir_core::fs::is_admin()
 }
 
 /// 检测 MFT 直接扫描是否可用（Windows 管理员权限）
 #[command]
 pub fn check_mft_available(path: String) -> bool {
-    flashdir::fs::check_mft_available(&path)
+    flashdir_core::fs::check_mft_available(&path)
 }
 
 /// 获取当前扫描环境状态（管理员 + 指定路径 MFT 可用性）
 #[command]
 pub fn get_scan_status(path: String) -> ScanStatus {
     ScanStatus {
-        is_admin: flashdir::fs::is_admin(),
-        mft_available: flashdir::fs::check_mft_available(&path),
+        is_admin: flashdir_core::fs::is_admin(),
+        mft_available: flashdir_core::fs::check_mft_available(&path),
     }
 }
 
@@ -348,55 +900,684 @@ pub struct ScanStatus {
     pub mft_available: bool,
 }
 
+/// 获取当前平台/环境能力（管理员权限、最后访问时间是否可信等），
+/// 前端据此决定是否提示"访问时间分析不可用"
+#[command]
+pub fn get_capabilities() -> Capabilities {
+    let last_access_updates = flashdir_core::fs::last_access_updates();
+    Capabilities {
+        platform: std::env::consts::OS.to_string(),
+        is_admin: flashdir_core::fs::is_admin(),
+        last_access_updates,
+        access_time_reliable: last_access_updates.is_reliable(),
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Capabilities {
+    pub platform: String,
+    pub is_admin: bool,
+    pub last_access_updates: flashdir_core::fs::LastAccessUpdates,
+    pub access_time_reliable: bool,
+}
+
+/// 列出所有已挂载的卷（卷标、文件系统、总/可用空间、可移动/网络），无需扫描
+#[command]
+pub fn list_volumes() -> Vec<flashdir_core::fs::VolumeInfo> {
+    flashdir_core::fs::list_volumes()
+}
+
 /// 以管理员权限重启应用
 #[command]
 pub fn restart_as_admin() -> bool {
-    flashdir::fs::restart_as_admin()
+    flashdir_core::fs::restart_as_admin()
+}
+
+/// 扫描覆盖情况：一级子目录完全可读 / 部分可读 / 拒绝访问，以及提权补扫是否可能有帮助
+#[command]
+pub fn get_permission_report(path: String) -> Option<flashdir_core::permission_report::PermissionReport> {
+    flashdir_core::permission_report::permission_report(&path)
+}
+
+/// 以管理员权限补扫上次扫描中拒绝访问的目录（`paths` 取自 ScanResult.errors），
+/// 合并进 `path` 的扫描结果；未提权时会弹出一次 UAC 确认
+#[command]
+pub async fn rescan_elevated(
+    path: String,
+    paths: Vec<String>,
+    options: Option<ScanOptions>,
+) -> Result<ScanResponse, CommandError> {
+    let path = validate::existing_dir("path", &path)?;
+    let paths = validate::absolute_paths("paths", &paths)?;
+    let options = options.unwrap_or_default();
+    // 每次调用都会弹出 UAC 确认
+    let _running = exclusive("rescan_elevated")?;
+    let result = tokio::task::spawn_blocking(move || flashdir_core::elevated::rescan_elevated(&path, &paths))
+        .await
+        .map_err(|e| format!("提权补扫失败: {}", e))?
+        .map_err(|e| format!("提权补扫失败: {}", e))?;
+    Ok(ScanResponse::new(result, options.fields.as_deref()))
 }
 
 /// 开发者磁盘分析：从内存缓存读取当前路径的扫描结果（避免百万级 items 跨 IPC 传输），
 /// 识别并分类常见开发工具/缓存目录的空间占用（已按"匹配边界顶层"去重，杜绝重复累加）
 #[command]
-pub fn analyze_dev_disk(path: String) -> Option<flashdir::dev_analyzer::DevAnalysisResult> {
-    let items = flashdir::scan::get_cached_items(&path)?;
+pub fn analyze_dev_disk(path: String) -> Option<flashdir_core::dev_analyzer::DevAnalysisResult> {
+    let items = flashdir_core::scan::get_cached_items(&path)?;
     let total_size: i64 = items.iter().filter(|i| !i.is_dir).map(|i| i.size).sum();
     let total_items = items.len();
-    Some(flashdir::dev_analyzer::analyze(&items, total_size, total_items))
+    Some(flashdir_core::dev_analyzer::analyze(&items, total_size, total_items))
+}
+
+/// 冷热数据分级报告：基于内存缓存中的扫描结果，按最后使用时间（atime/mtime）
+/// 把文件分为 hot / warm / cold，并统计可迁移到廉价存储的数据量。
+/// `mtime_only` 为 true 时忽略 atime（系统关闭了访问时间更新时的回退）
+#[command]
+pub async fn get_tiering_report(
+    path: String,
+    hot_days: Option<u32>,
+    cold_days: Option<u32>,
+    mtime_only: Option<bool>,
+) -> Result<Option<flashdir_core::tiering::TieringReport>, CommandError> {
+    let hot_days = validate::positive("hotDays", hot_days.unwrap_or(flashdir_core::tiering::DEFAULT_HOT_DAYS))?;
+    let cold_days = validate::positive("coldDays", cold_days.unwrap_or(flashdir_core::tiering::DEFAULT_COLD_DAYS))?;
+    if hot_days >= cold_days {
+        return Err(ValidationError::new(
+            "coldDays",
+            validate::ValidationCode::OutOfRange,
+            format!("必须大于 hotDays（{}）", hot_days),
+        )
+        .into());
+    }
+
+    let Some(items) = flashdir_core::scan::get_cached_items(&path) else {
+        return Ok(None);
+    };
+
+    let thresholds = flashdir_core::tiering::TierThresholds { hot_days, cold_days };
+
+    let basis = if mtime_only.unwrap_or(false) {
+        flashdir_core::tiering::TimeBasis::ModifiedOnly
+    } else {
+        flashdir_core::tiering::TimeBasis::LastUsed
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let times = flashdir_core::tiering::collect_file_times(&items);
+        let now = Utc::now().timestamp();
+        let mut report = flashdir_core::tiering::build_report(&path, &items, &times, now, thresholds, basis);
+        report.warning =
+            flashdir_core::tiering::access_time_warning(flashdir_core::fs::last_access_updates(), basis);
+        Some(report)
+    })
+    .await
+    .map_err(|e| CommandError::Failed(format!("分级分析失败: {}", e)))
+}
+
+/// 文件年龄报告：基于内存缓存中的扫描结果，按修改时间分段（<30d / 30-180d / 180d-1y / >1y）
+/// 统计数量与大小，并列出最大的陈旧文件
+#[command]
+pub fn analyze_file_age(path: String) -> Option<flashdir_core::file_age::FileAgeReport> {
+    let items = flashdir_core::scan::get_cached_items(&path)?;
+    Some(flashdir_core::file_age::analyze(&path, &items, Utc::now().timestamp()))
+}
+
+/// 小文件空间开销：0 字节文件、小于一个簇的文件数量与按卷簇大小估算的分配浪费
+#[command]
+pub fn get_small_file_report(path: String) -> Option<flashdir_core::small_files::SmallFileReport> {
+    let items = flashdir_core::scan::get_cached_items(&path)?;
+    let cluster_size = flashdir_core::fs::cluster_size(&path);
+    Some(flashdir_core::small_files::analyze(&path, &items, cluster_size))
+}
+
+/// Git 仓库报告：按总大小列出扫描结果中的仓库，拆分 .git 与工作区大小并给出清理建议
+#[command]
+pub fn get_git_repo_report(path: String) -> Option<flashdir_core::git_repos::GitRepoReport> {
+    let items = flashdir_core::scan::get_cached_items(&path)?;
+    Some(flashdir_core::git_repos::analyze(&path, &items))
+}
+
+/// 邮件存档与应用数据库：按应用分组的 PST / OST、mbox、SQLite、LevelDB，附压缩 / 归档建议
+#[command]
+pub fn get_app_data_report(path: String) -> Option<flashdir_core::app_data::AppDataReport> {
+    let items = flashdir_core::scan::get_cached_items(&path)?;
+    Some(flashdir_core::app_data::analyze(&path, &items))
+}
+
+/// 包管理器缓存：cargo / npm / pnpm / pip / Gradle 的缓存大小与可回收量；
+/// 传入 path 时从其扫描结果中查找 Rust 项目的 target 目录
+#[command]
+pub async fn get_package_caches(
+    path: Option<String>,
+) -> Result<Vec<flashdir_core::pkg_caches::PackageCacheReport>, String> {
+    let items = path.as_deref().and_then(flashdir_core::scan::get_cached_items).unwrap_or_default();
+    tokio::task::spawn_blocking(move || flashdir_core::pkg_caches::analyze(&items))
+        .await
+        .map_err(|e| format!("统计包管理器缓存失败: {}", e))
+}
+
+/// 执行工具自带的清理命令（cargo clean / npm cache clean --force / pnpm store prune / pip cache purge）
+/// 并返回输出；cargo 需要 project（Rust 项目目录）
+#[command]
+pub async fn run_safe_clean(
+    tool: flashdir_core::pkg_caches::PackageTool,
+    project: Option<String>,
+) -> Result<flashdir_core::pkg_caches::CleanOutput, CommandError> {
+    let project = project.map(|p| validate::existing_dir("project", &p)).transpose()?;
+    let _running = exclusive("run_safe_clean")?;
+    tokio::task::spawn_blocking(move || flashdir_core::pkg_caches::run_safe_clean(tool, project.as_deref()))
+        .await
+        .map_err(|e| CommandError::Failed(format!("清理失败: {}", e)))?
+        .map_err(|e| CommandError::Failed(e.to_string()))
+}
+
+/// 回收站分析：各卷回收站中的条目（原路径、大小、删除时间），按原所在目录与回收站位置汇总
+#[command]
+pub async fn analyze_recycle_bin() -> Result<flashdir_core::recycle_bin::RecycleBinReport, String> {
+    tokio::task::spawn_blocking(flashdir_core::recycle_bin::analyze)
+        .await
+        .map_err(|e| format!("分析回收站失败: {}", e))
+}
+
+/// 清空回收站（所有卷，不再确认），返回释放的字节数
+#[command]
+pub async fn empty_recycle_bin() -> Result<i64, CommandError> {
+    let _running = exclusive("empty_recycle_bin")?;
+    tokio::task::spawn_blocking(flashdir_core::recycle_bin::empty)
+        .await
+        .map_err(|e| CommandError::Failed(format!("清空回收站失败: {}", e)))?
+        .map_err(|e| CommandError::Failed(e.to_string()))
+}
+
+/// 移入回收站（可还原）：逐项报告失败，失效相关缓存，返回父目录的新大小
+#[command]
+pub async fn delete_to_trash(paths: Vec<String>) -> Result<flashdir_core::file_ops::FileOpResult, CommandError> {
+    let paths = validate::absolute_paths("paths", &paths)?;
+    let _running = exclusive("delete_to_trash")?;
+    tokio::task::spawn_blocking(move || flashdir_core::file_ops::delete_to_trash(&paths))
+        .await
+        .map_err(|e| CommandError::Failed(format!("移入回收站失败: {}", e)))
+}
+
+/// 永久删除前的确认：统计待删除的条目与大小，签发一次性确认令牌
+#[command]
+pub async fn request_delete_token(
+    paths: Vec<String>,
+) -> Result<flashdir_core::file_ops::DeleteConfirmation, CommandError> {
+    let paths = validate::absolute_paths("paths", &paths)?;
+    tokio::task::spawn_blocking(move || flashdir_core::file_ops::issue_delete_token(&paths))
+        .await
+        .map_err(|e| CommandError::Failed(format!("统计待删除条目失败: {}", e)))
+}
+
+/// 永久删除（不经过回收站），须带 request_delete_token 签发的令牌；
+/// 发出 operation-progress 事件，可用 operationId 取消，逐项报告失败，返回父目录的新大小
+#[command]
+pub async fn delete_permanently(
+    paths: Vec<String>,
+    token: String,
+    operation_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<flashdir_core::file_ops::FileOpResult, CommandError> {
+    let paths = validate::absolute_paths("paths", &paths)?;
+    let _running = exclusive("delete_permanently")?;
+    let op = Operation::start("delete_permanently", operation_id, Some(events_for(&app)));
+    Ok(tokio::task::spawn_blocking(move || flashdir_core::file_ops::delete_permanently(&paths, &token, &op))
+        .await
+        .map_err(|e| CommandError::Failed(format!("删除失败: {}", e)))??)
+}
+
+/// 把条目移动到 dest 目录下（同名冲突默认跳过）；发出 operation-progress 事件，可用 operationId 取消，
+/// 源与目标的缓存都会失效
+#[command]
+pub async fn move_items(
+    src_paths: Vec<String>,
+    dest: String,
+    conflict: Option<flashdir_core::file_ops::ConflictPolicy>,
+    operation_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<flashdir_core::file_ops::TransferResult, CommandError> {
+    let src_paths = validate::absolute_paths("srcPaths", &src_paths)?;
+    let dest = validate::existing_dir("dest", &dest)?;
+    let _running = exclusive("move_items")?;
+    let op = Operation::start("move_items", operation_id, Some(events_for(&app)));
+    tokio::task::spawn_blocking(move || {
+        flashdir_core::file_ops::move_items(&src_paths, &dest, conflict.unwrap_or_default(), &op)
+    })
+    .await
+    .map_err(|e| CommandError::Failed(format!("移动失败: {}", e)))
+}
+
+/// 把条目复制到 dest 目录下（分块复制，同名冲突默认跳过）；发出 operation-progress 事件，可用 operationId 取消
+#[command]
+pub async fn copy_items(
+    src_paths: Vec<String>,
+    dest: String,
+    conflict: Option<flashdir_core::file_ops::ConflictPolicy>,
+    operation_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<flashdir_core::file_ops::TransferResult, CommandError> {
+    let src_paths = validate::absolute_paths("srcPaths", &src_paths)?;
+    let dest = validate::existing_dir("dest", &dest)?;
+    let _running = exclusive("copy_items")?;
+    let op = Operation::start("copy_items", operation_id, Some(events_for(&app)));
+    tokio::task::spawn_blocking(move || {
+        flashdir_core::file_ops::copy_items(&src_paths, &dest, conflict.unwrap_or_default(), &op)
+    })
+    .await
+    .map_err(|e| CommandError::Failed(format!("复制失败: {}", e)))
+}
+
+/// 在原目录内重命名条目，并就地修正扫描缓存（内存 + 磁盘），返回修正后的条目，前端无需重新扫描
+#[command]
+pub async fn rename_item(
+    path: String,
+    new_name: String,
+) -> Result<flashdir_core::file_ops::RenameResult, CommandError> {
+    let path = validate::absolute_path("path", &path)?;
+    let new_name = validate::file_name("newName", &new_name)?;
+    tokio::task::spawn_blocking(move || flashdir_core::file_ops::rename_item(&path, &new_name))
+        .await
+        .map_err(|e| format!("重命名失败: {}", e))?
+        .map_err(|e| CommandError::Failed(e.to_string()))
+}
+
+/// 压缩前估计：按扩展名类别估计压缩后大小（不读取文件内容）
+#[command]
+pub async fn estimate_archive(
+    paths: Vec<String>,
+    format: Option<flashdir_core::archive::ArchiveFormat>,
+) -> Result<flashdir_core::archive::ArchiveEstimate, CommandError> {
+    let paths = validate::absolute_paths("paths", &paths)?;
+    tokio::task::spawn_blocking(move || flashdir_core::archive::estimate(&paths, format.unwrap_or_default()))
+        .await
+        .map_err(|e| CommandError::Failed(format!("估计失败: {}", e)))
+}
+
+/// 把条目压缩为 zip 或 tar.zst（流式写入）；发出 operation-progress 事件，可用 operationId 取消
+#[command]
+pub async fn compress_to_archive(
+    paths: Vec<String>,
+    dest: String,
+    format: Option<flashdir_core::archive::ArchiveFormat>,
+    operation_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<flashdir_core::archive::ArchiveResult, CommandError> {
+    let paths = validate::absolute_paths("paths", &paths)?;
+    let dest = validate::output_file("dest", &dest)?;
+    let _running = exclusive("compress_to_archive")?;
+    let op = Operation::start("compress_to_archive", operation_id, Some(events_for(&app)));
+    tokio::task::spawn_blocking(move || {
+        flashdir_core::archive::compress(&paths, &dest, format.unwrap_or_default(), &op)
+    })
+    .await
+    .map_err(|e| format!("压缩失败: {}", e))?
+    .map_err(|e| CommandError::Failed(operation_error("压缩失败", e)))
+}
+
+/// 路径长度与名称异常：接近 / 超过 MAX_PATH、以空格或点结尾、保留设备名、Windows 非法字符
+#[command]
+pub fn get_path_anomalies(path: String) -> Option<flashdir_core::path_anomalies::PathAnomalyReport> {
+    let items = flashdir_core::scan::get_cached_items(&path)?;
+    Some(flashdir_core::path_anomalies::analyze(&path, &items))
+}
+
+/// 按所有者聚合空间占用（共享文件服务器场景）：基于内存缓存中的扫描结果，
+/// 逐文件解析所有者，较慢，在后台线程执行
+#[command]
+pub async fn analyze_owners(path: String) -> Result<Option<Vec<flashdir_core::owner_report::OwnerStat>>, String> {
+    let Some(items) = flashdir_core::scan::get_cached_items(&path) else {
+        return Ok(None);
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let owners = flashdir_core::owner_report::resolve_owners(&items);
+        Some(flashdir_core::owner_report::aggregate(&items, &owners))
+    })
+    .await
+    .map_err(|e| format!("所有者分析失败: {}", e))
+}
+
+// ─── 内容哈希 ────────────────────────────────────────────
+
+/// 计算文件内容哈希（blake3 / sha256），进度通过 hash-progress 事件推送
+#[command]
+pub async fn hash_files(
+    paths: Vec<String>,
+    algorithm: flashdir_core::hashing::HashAlgorithm,
+    app: tauri::AppHandle,
+) -> Result<Vec<flashdir_core::hashing::FileHash>, CommandError> {
+    let paths = validate::absolute_paths("paths", &paths)?;
+    tokio::task::spawn_blocking(move || {
+        flashdir_core::hashing::hash_files(&paths, algorithm, |progress| {
+            let _ = app.emit("hash-progress", progress);
+        })
+    })
+    .await
+    .map_err(|e| CommandError::Failed(format!("计算哈希失败: {}", e)))
+}
+
+// ─── 路径补全与收藏夹 ────────────────────────────────────
+
+/// 路径输入框补全：合并收藏夹、扫描历史、缓存索引与文件系统列举并排序
+#[command]
+pub fn autocomplete_path(
+    prefix: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<flashdir_core::autocomplete::PathSuggestion>, CommandError> {
+    use flashdir_core::autocomplete::{self, Candidate, SuggestionSource};
+
+    let limit = validate::limit("limit", limit, 20, 200)?;
+    let cache = DiskCache::instance();
+    let mut candidates = Vec::new();
+
+    let candidate = |path: String, source| Candidate { path, source };
+    candidates.extend(
+        cache
+            .list_favorites()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|p| candidate(p, SuggestionSource::Favorite)),
+    );
+    // 常用位置按 frecency 排在扫描历史之前
+    candidates.extend(
+        flashdir_core::frecency::rank(
+            cache.list_path_visits().unwrap_or_default(),
+            Utc::now().timestamp(),
+            usize::MAX,
+        )
+        .into_iter()
+        .map(|f| candidate(f.path, SuggestionSource::History)),
+    );
+    // 历史按时间升序存储，倒序使最近的排在前面
+    candidates.extend(
+        state
+            .history
+            .lock()
+            .iter()
+            .rev()
+            .map(|h| candidate(h.path.to_string(), SuggestionSource::History)),
+    );
+    if !prefix.is_empty() {
+        let cache_prefix = prefix.replace('\\', "/");
+        candidates.extend(
+            cache
+                .cached_paths_with_prefix(&cache_prefix, limit)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|p| candidate(p, SuggestionSource::CacheIndex)),
+        );
+        candidates.extend(
+            autocomplete::list_dir_completions(&prefix, limit)
+                .into_iter()
+                .map(|p| candidate(p, SuggestionSource::Filesystem)),
+        );
+    }
+
+    Ok(autocomplete::rank(&prefix, candidates, limit))
+}
+
+#[command]
+pub fn add_favorite(path: String) -> Result<(), CommandError> {
+    let path = validate::existing_dir("path", &path)?;
+    DiskCache::instance()
+        .add_favorite(&path)
+        .map_err(|e| CommandError::Failed(format!("添加收藏失败: {}", e)))
+}
+
+#[command]
+pub fn remove_favorite(path: String) -> Result<(), String> {
+    DiskCache::instance()
+        .remove_favorite(&path)
+        .map_err(|e| format!("移除收藏失败: {}", e))
+}
+
+#[command]
+pub fn list_favorites() -> Result<Vec<String>, String> {
+    DiskCache::instance()
+        .list_favorites()
+        .map_err(|e| format!("获取收藏失败: {}", e))
+}
+
+/// 按访问频率与新近度排序的常用路径（首页"最近位置"）
+#[command]
+pub fn get_frecent_paths(limit: Option<usize>) -> Result<Vec<flashdir_core::frecency::FrecentPath>, CommandError> {
+    let limit = validate::limit("limit", limit, 10, 200)?;
+    let visits = DiskCache::instance()
+        .list_path_visits()
+        .map_err(|e| format!("获取常用路径失败: {}", e))?;
+    Ok(flashdir_core::frecency::rank(visits, Utc::now().timestamp(), limit))
+}
+
+#[command]
+pub fn clear_frecent_paths() -> Result<(), String> {
+    DiskCache::instance()
+        .clear_path_visits()
+        .map_err(|e| format!("清除常用路径失败: {}", e))
+}
+
+// ─── 选择集与批量操作 ────────────────────────────────────
+
+fn session_items(session: &str) -> Result<std::sync::Arc<Vec<flashdir_core::scan::Item>>, String> {
+    flashdir_core::scan::get_cached_items(session)
+        .ok_or_else(|| format!("会话 {} 没有可用的扫描结果", session))
+}
+
+/// 更新会话（扫描根路径）的选择集：按 ID 列表、行号区间或全选，
+/// 只返回摘要，不回传路径列表
+#[command]
+pub fn update_selection(
+    session: String,
+    mode: flashdir_core::selection::SelectionMode,
+    target: flashdir_core::selection::SelectionTarget,
+) -> Result<flashdir_core::selection::SelectionSummary, String> {
+    let items = session_items(&session)?;
+    Ok(flashdir_core::selection::SelectionStore::instance().update(&session, mode, &target, &items))
+}
+
+/// 获取选择集摘要
+#[command]
+pub fn get_selection_summary(session: String) -> Result<flashdir_core::selection::SelectionSummary, String> {
+    let items = session_items(&session)?;
+    Ok(flashdir_core::selection::SelectionStore::instance().summary(&session, &items))
+}
+
+/// 获取选中项 ID 列表
+#[command]
+pub fn get_selected_ids(session: String) -> Vec<u64> {
+    flashdir_core::selection::SelectionStore::instance()
+        .ids(&session)
+        .into_iter()
+        .collect()
+}
+
+/// 清空选择集
+#[command]
+pub fn clear_selection(session: String) {
+    flashdir_core::selection::SelectionStore::instance().clear(&session);
+}
+
+/// 永久删除选中项，删除后失效该会话相关的扫描缓存
+#[command]
+pub async fn delete_selected(session: String) -> Result<flashdir_core::selection::BulkActionResult, String> {
+    let _running = exclusive("delete_selected")?;
+    let items = session_items(&session)?;
+
+    tokio::task::spawn_blocking(move || {
+        let result = flashdir_core::selection::delete_selected(&session, &items);
+        if result.succeeded > 0 {
+            scan::invalidate_caches_for(&session);
+        }
+        result
+    })
+    .await
+    .map_err(|e| format!("批量删除失败: {}", e))
+}
+
+/// 把选中项导出为 CSV / JSON / 纯文本路径列表
+#[command]
+pub async fn export_selected(
+    session: String,
+    dest: String,
+    format: flashdir_core::selection::ExportFormat,
+    operation_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<flashdir_core::selection::BulkActionResult, CommandError> {
+    let dest = validate::output_file("dest", &dest)?;
+    let items = session_items(&session)?;
+    let op = Operation::start("export_selected", operation_id, Some(events_for(&app)));
+
+    tokio::task::spawn_blocking(move || {
+        flashdir_core::selection::export_selected_with(&session, &items, &dest, format, &op)
+    })
+    .await
+    .map_err(|e| format!("导出选择失败: {}", e))?
+    .map_err(|e| CommandError::Failed(operation_error("导出选择失败", e)))
+}
+
+/// 把扫描结果的完整条目列表导出为 CSV / NDJSON / XLSX（在后端逐行写出，不经过 IPC）；
+/// scan_id 为扫描根路径，columns 缺省为常用列；发出 operation-progress 事件，可用 operationId 取消
+#[command]
+pub async fn export_scan(
+    scan_id: String,
+    format: Option<flashdir_core::export::ScanExportFormat>,
+    dest: String,
+    columns: Option<Vec<flashdir_core::export::ExportColumn>>,
+    operation_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<flashdir_core::export::ScanExportResult, CommandError> {
+    let dest = validate::output_file("dest", &dest)?;
+    let items = session_items(&scan_id)?;
+    let op = Operation::start("export_scan", operation_id, Some(events_for(&app)));
+
+    tokio::task::spawn_blocking(move || {
+        flashdir_core::export::export_scan(
+            &items,
+            &dest,
+            format.unwrap_or_default(),
+            &columns.unwrap_or_default(),
+            &op,
+        )
+    })
+    .await
+    .map_err(|e| format!("导出失败: {}", e))?
+    .map_err(|e| CommandError::Failed(operation_error("导出失败", e)))
+}
+
+/// 生成扫描报告（独立 HTML，options.format 为 pdf 时由本机浏览器打印为 PDF）：
+/// 总计、最大目录、扩展名分布与重复文件摘要；发出 operation-progress 事件，可用 operationId 取消
+#[command]
+pub async fn generate_report(
+    scan_id: String,
+    dest: String,
+    options: Option<flashdir_core::report::ReportOptions>,
+    operation_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<flashdir_core::report::ReportResult, CommandError> {
+    use flashdir_core::report::{DEFAULT_TOP_N, MAX_TOP_N};
+
+    let dest = validate::output_file("dest", &dest)?;
+    let mut options = options.unwrap_or_default();
+    options.top_n = Some(validate::limit("options.topN", options.top_n, DEFAULT_TOP_N, MAX_TOP_N)?);
+    if let Some(locale) = options.locale.as_deref() {
+        validate::language_tag("options.locale", locale)?;
+    }
+    let items = session_items(&scan_id)?;
+    let op = Operation::start("generate_report", operation_id, Some(events_for(&app)));
+
+    tokio::task::spawn_blocking(move || flashdir_core::report::generate_report(&scan_id, &items, &dest, &options, &op))
+        .await
+        .map_err(|e| format!("生成报告失败: {}", e))?
+        .map_err(|e| CommandError::Failed(operation_error("生成报告失败", e)))
+}
+
+/// 把扫描摘要或会话的选择集格式化为文本 / Markdown / TSV 并写入系统剪贴板（不经前端回传）；
+/// 摘要的列表条数为 topN，格式跟随外观设置中的区域
+#[command]
+pub async fn copy_to_clipboard(
+    scan_id: String,
+    content: flashdir_core::clipboard::ClipboardContent,
+    format: Option<flashdir_core::clipboard::ClipboardFormat>,
+    top_n: Option<usize>,
+) -> Result<flashdir_core::clipboard::ClipboardResult, CommandError> {
+    use flashdir_core::clipboard::{self, ClipboardContent};
+    use flashdir_core::report::{DEFAULT_TOP_N, MAX_TOP_N};
+
+    let top_n = validate::limit("topN", top_n, DEFAULT_TOP_N, MAX_TOP_N)?;
+    let format = format.unwrap_or_default();
+    let items = session_items(&scan_id)?;
+
+    tokio::task::spawn_blocking(move || {
+        let style = flashdir_core::report_style::ReportStyle::resolve(None, None);
+        match content {
+            ClipboardContent::Summary => {
+                let options = flashdir_core::report::ReportOptions { top_n: Some(top_n), ..Default::default() };
+                let op = Operation::start("copy_to_clipboard", None, None);
+                let summary = flashdir_core::report::build_summary(&scan_id, &items, &options, &op)?;
+                clipboard::copy_summary(&summary, &style, format)
+            }
+            ClipboardContent::Selection => clipboard::copy_selection(&scan_id, &items, &style, format),
+        }
+    })
+    .await
+    .map_err(|e| format!("复制失败: {}", e))?
+    .map_err(|e| CommandError::Failed(format!("复制失败: {}", e)))
 }
 
 // ─── 快照管理 ────────────────────────────────────────────
 
-/// 保存当前扫描结果为快照
+/// 保存当前扫描结果为快照（发出 operation-progress 事件，写入前可用 operationId 取消）
 #[command]
-pub fn save_snapshot(
+pub async fn save_snapshot(
     path: String,
-    items: Vec<flashdir::scan::Item>,
+    items: Vec<flashdir_core::scan::Item>,
     total_size: i64,
     total_size_formatted: String,
-) -> Result<i64, String> {
-    let result = flashdir::scan::ScanResult {
+    operation_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<i64, CommandError> {
+    let path = validate::absolute_path("path", &path)?;
+    let result = flashdir_core::scan::ScanResult {
         items,
         total_size,
-        total_size_formatted: flashdir::scan::CompactString::from(total_size_formatted.as_str()),
+        total_size_formatted: flashdir_core::scan::CompactString::from(total_size_formatted.as_str()),
         scan_time: 0.0,
-        path: flashdir::scan::CompactString::from(path.as_str()),
+        path: flashdir_core::scan::CompactString::from(path.as_str()),
         mft_available: false,
         timing: None,
         perf_metrics: None,
+        extension_stats: None,
+        category_stats: Vec::new(),
+        owners: None,
+        unscanned: None,
+        errors: Vec::new(),
+        unreadable_bytes: 0,
+        cloud_only_bytes: 0,
+        cloud_local_bytes: 0,
+        sparse_files: Vec::new(),
+        sparse_savings_bytes: 0,
+        cache_usage: Default::default(),
     };
 
     let file_count = result.items.iter().filter(|i| !i.is_dir).count();
     let dir_count = result.items.iter().filter(|i| i.is_dir).count();
 
-    flashdir::disk_cache::DiskCache::instance()
-        .insert_snapshot(&path, &result, file_count, dir_count)
-        .map_err(|e| format!("保存快照失败: {}", e))
+    let op = Operation::start("save_snapshot", operation_id, Some(events_for(&app)));
+
+    tokio::task::spawn_blocking(move || {
+        flashdir_core::disk_cache::DiskCache::instance().insert_snapshot_with(&path, &result, file_count, dir_count, &op)
+    })
+    .await
+    .map_err(|e| format!("保存快照失败: {}", e))?
+    .map_err(|e| CommandError::Failed(operation_error("保存快照失败", e)))
 }
 
 /// 列出指定路径的所有快照
 #[command]
-pub fn list_snapshots(path: String) -> Result<Vec<flashdir::disk_cache::SnapshotInfo>, String> {
-    flashdir::disk_cache::DiskCache::instance()
+pub fn list_snapshots(path: String) -> Result<Vec<flashdir_core::disk_cache::SnapshotInfo>, String> {
+    flashdir_core::disk_cache::DiskCache::instance()
         .list_snapshots(&path)
         .map_err(|e| format!("获取快照列表失败: {}", e))
 }
@@ -406,8 +1587,10 @@ pub fn list_snapshots(path: String) -> Result<Vec<flashdir::disk_cache::Snapshot
 pub fn compare_snapshots(
     old_id: i64,
     new_id: i64,
-) -> Result<flashdir::diff_engine::SnapshotDiff, String> {
-    let disk_cache = flashdir::disk_cache::DiskCache::instance();
+) -> Result<flashdir_core::diff_engine::SnapshotDiff, CommandError> {
+    validate::positive("oldId", old_id)?;
+    validate::positive("newId", new_id)?;
+    let disk_cache = flashdir_core::disk_cache::DiskCache::instance();
 
     let old_result = disk_cache
         .get_snapshot(old_id)
@@ -417,17 +1600,31 @@ pub fn compare_snapshots(
         .get_snapshot(new_id)
         .ok_or_else(|| format!("快照 {} 不存在", new_id))?;
 
-    Ok(flashdir::diff_engine::diff(
+    Ok(flashdir_core::diff_engine::diff(
         &old_result.items,
         &new_result.items,
         old_result.total_size,
     ))
 }
 
+/// 比较该路径上一次扫描与最近一次扫描：新增 / 删除 / 增长的项按变化量排序
+#[command]
+pub fn compare_scans(path: String) -> Option<flashdir_core::diff_engine::ScanComparison> {
+    scan::compare_with_previous_scan(&path)
+}
+
+/// 目录大小的历史时间序列（每次扫描记录扫描根及其下两层目录）
+#[command]
+pub fn get_size_history(path: String) -> Result<Vec<flashdir_core::size_history::SizePoint>, String> {
+    DiskCache::instance()
+        .get_size_history(&scan::normalize_cache_key(&path))
+        .map_err(|e| format!("获取大小历史失败: {}", e))
+}
+
 /// 删除指定快照
 #[command]
 pub fn delete_snapshot(id: i64) -> Result<(), String> {
-    flashdir::disk_cache::DiskCache::instance()
+    flashdir_core::disk_cache::DiskCache::instance()
         .delete_snapshot(id)
         .map_err(|e| format!("删除快照失败: {}", e))
 }
@@ -436,10 +1633,10 @@ pub fn delete_snapshot(id: i64) -> Result<(), String> {
 #[command]
 pub fn compare_with_latest_snapshot(
     path: String,
-    current_items: Vec<flashdir::scan::Item>,
+    current_items: Vec<flashdir_core::scan::Item>,
     _current_total_size: i64,
-) -> Result<Option<flashdir::diff_engine::SnapshotDiff>, String> {
-    let disk_cache = flashdir::disk_cache::DiskCache::instance();
+) -> Result<Option<flashdir_core::diff_engine::SnapshotDiff>, String> {
+    let disk_cache = flashdir_core::disk_cache::DiskCache::instance();
     let snapshots = disk_cache
         .list_snapshots(&path)
         .map_err(|e| format!("获取快照列表失败: {}", e))?;
@@ -454,7 +1651,7 @@ pub fn compare_with_latest_snapshot(
         .get_snapshot(latest.id)
         .ok_or_else(|| format!("快照 {} 不存在", latest.id))?;
 
-    Ok(Some(flashdir::diff_engine::diff(
+    Ok(Some(flashdir_core::diff_engine::diff(
         &old_result.items,
         &current_items,
         old_result.total_size,
@@ -467,8 +1664,8 @@ pub fn compare_with_latest_snapshot(
 #[serde(rename_all = "camelCase")]
 pub struct GlobalSearchResponse {
     pub ready: bool,
-    pub state: flashdir::global_search::IndexState,
-    pub results: Vec<flashdir::global_search::IndexEntry>,
+    pub state: flashdir_core::global_search::IndexState,
+    pub results: Vec<flashdir_core::global_search::IndexEntry>,
     /// 诊断：搜索无结果时返回索引实际条目数
     #[serde(skip_serializing_if = "Option::is_none")]
     pub index_size: Option<usize>,
@@ -479,32 +1676,34 @@ pub struct GlobalSearchResponse {
 
 /// 查询全局索引状态
 #[command]
-pub fn global_search_status() -> flashdir::global_search::IndexState {
-    flashdir::global_search::instance().state()
+pub fn global_search_status() -> flashdir_core::global_search::IndexState {
+    flashdir_core::global_search::instance().state()
 }
 
 /// 构建全盘索引：逐盘调 scan_directory（与主界面相同的已验证路径，确保文件名正确）
 #[command]
 pub async fn global_search_ensure_index(app: tauri::AppHandle) -> Result<(), String> {
+    // 与 global_search_refresh 共用一把锁：同一时间只有一次索引构建
+    let _running = exclusive("global_search_index")?;
     {
-        let idx = flashdir::global_search::instance();
+        let idx = flashdir_core::global_search::instance();
         match idx.state() {
-            flashdir::global_search::IndexState::Ready(..)
-            | flashdir::global_search::IndexState::Loading { .. } => return Ok(()),
+            flashdir_core::global_search::IndexState::Ready(..)
+            | flashdir_core::global_search::IndexState::Loading { .. } => return Ok(()),
             _ => {}
         }
     }
 
-    let idx = flashdir::global_search::instance();
+    let idx = flashdir_core::global_search::instance();
     idx.set_loading();
 
-    let drives = flashdir::global_search::list_ntfs_drives();
+    let drives = flashdir_core::global_search::list_ntfs_drives();
     if drives.is_empty() {
         idx.finish_building(&[]);
         return Err("未检测到可扫描的 NTFS 卷（需要管理员权限读取 MFT）".to_string());
     }
 
-    let perf = flashdir::perf::PerformanceMonitor::instance();
+    let perf = flashdir_core::perf::PerformanceMonitor::instance();
     let mut ok_drives: Vec<char> = Vec::new();
 
     for &drive in &drives {
@@ -515,7 +1714,7 @@ pub async fn global_search_ensure_index(app: tauri::AppHandle) -> Result<(), Str
         );
 
         // 1) 内存缓存命中：毫秒级（之前扫过该盘）
-        if let Some(cached) = flashdir::scan::get_cached_items(&root) {
+        if let Some(cached) = flashdir_core::scan::get_cached_items(&root) {
             idx.append_scan(drive, &cached);
             ok_drives.push(drive);
             let _ = app.emit(
@@ -526,7 +1725,7 @@ pub async fn global_search_ensure_index(app: tauri::AppHandle) -> Result<(), Str
         }
 
         // 2) 轻量 MFT 扫描：仅取文件名/路径/大小，跳过聚合/format/排序（3-5s）
-        if let Some(lite_items) = flashdir::scan::scan_lite(&root) {
+        if let Some(lite_items) = flashdir_core::scan::scan_lite(&root) {
             idx.append_scan(drive, &lite_items);
             ok_drives.push(drive);
             let _ = app.emit(
@@ -537,7 +1736,7 @@ pub async fn global_search_ensure_index(app: tauri::AppHandle) -> Result<(), Str
         }
 
         // 3) 完整 scan_directory（回退，同时写缓存供后续命中）
-        match flashdir::scan::scan_directory(&root, false, std::sync::Arc::clone(&perf), Some(app.clone()))
+        match flashdir_core::scan::scan_directory(&root, false, std::sync::Arc::clone(&perf), Some(events_for(&app)))
             .await
         {
             Ok(result) => {
@@ -567,12 +1766,14 @@ pub async fn global_search_ensure_index(app: tauri::AppHandle) -> Result<(), Str
 
 /// 全局搜索：按文件名匹配，返回结果（索引未就绪时 ready=false）
 #[command]
-pub fn global_search(query: String, limit: Option<usize>) -> GlobalSearchResponse {
-    let idx = flashdir::global_search::instance();
+pub fn global_search(query: String, limit: Option<usize>) -> Result<GlobalSearchResponse, CommandError> {
+    validate::search_query("query", &query)?;
+    let limit = validate::limit("limit", limit, 500, 10_000)?;
+    let idx = flashdir_core::global_search::instance();
     let state = idx.state();
-    let ready = matches!(state, flashdir::global_search::IndexState::Ready(..));
+    let ready = matches!(state, flashdir_core::global_search::IndexState::Ready(..));
     let (results, index_size, sample_names) = if ready {
-        let r = idx.search_with_filter(&query, limit.unwrap_or(500));
+        let r = idx.search_with_filter(&query, limit);
         let empty = r.is_empty() && !query.trim().is_empty();
         let n = if empty { Some(idx.entries_len()) } else { None };
         let sn = if empty { Some(idx.sample_names(5)) } else { None };
@@ -580,7 +1781,7 @@ pub fn global_search(query: String, limit: Option<usize>) -> GlobalSearchRespons
     } else {
         (vec![], None, None)
     };
-    GlobalSearchResponse { ready, state, results, index_size, sample_names }
+    Ok(GlobalSearchResponse { ready, state, results, index_size, sample_names })
 }
 
 /// 将主界面扫描结果追加到全局索引（复用已验证的 scan_dir 结果，
@@ -588,36 +1789,37 @@ pub fn global_search(query: String, limit: Option<usize>) -> GlobalSearchRespons
 #[command]
 pub fn global_search_add_scan(
     path: String,
-    items: Vec<flashdir::scan::Item>,
+    items: Vec<flashdir_core::scan::Item>,
 ) -> Result<(), String> {
-    flashdir::global_search::instance().add_items(&path, &items);
+    flashdir_core::global_search::instance().add_items(&path, &items);
     Ok(())
 }
 
 /// 刷新索引（全量重建，走 scan_directory 保证文件名正确）
 #[command]
 pub async fn global_search_refresh(app: tauri::AppHandle) -> Result<(), String> {
-    let idx = flashdir::global_search::instance();
+    let _running = exclusive("global_search_index")?;
+    let idx = flashdir_core::global_search::instance();
     idx.set_loading();
 
-    let drives = flashdir::global_search::list_ntfs_drives();
-    let perf = flashdir::perf::PerformanceMonitor::instance();
+    let drives = flashdir_core::global_search::list_ntfs_drives();
+    let perf = flashdir_core::perf::PerformanceMonitor::instance();
     let mut ok_drives: Vec<char> = Vec::new();
 
     for &drive in &drives {
         let root = format!("{}:\\", drive);
-        if let Some(cached) = flashdir::scan::get_cached_items(&root) {
+        if let Some(cached) = flashdir_core::scan::get_cached_items(&root) {
             idx.append_scan(drive, &cached);
             ok_drives.push(drive);
             continue;
         }
-        if let Some(lite_items) = flashdir::scan::scan_lite(&root) {
+        if let Some(lite_items) = flashdir_core::scan::scan_lite(&root) {
             idx.append_scan(drive, &lite_items);
             ok_drives.push(drive);
             continue;
         }
-        if let Ok(result) = flashdir::scan::scan_directory(
-            &root, false, std::sync::Arc::clone(&perf), Some(app.clone()),
+        if let Ok(result) = flashdir_core::scan::scan_directory(
+            &root, false, std::sync::Arc::clone(&perf), Some(events_for(&app)),
         )
         .await
         {
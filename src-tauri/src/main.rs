@@ -11,8 +11,8 @@ use parking_lot::Mutex;
 
 mod commands;
 
-use flashdir::scan;
-use flashdir::global_search;
+use flashdir_core::scan;
+use flashdir_core::global_search;
 use tauri::Emitter;
 
 struct AppState {
@@ -21,7 +21,17 @@ struct AppState {
 
 #[tokio::main]
 async fn main() {
-    let _ = flashdir::disk_cache::DiskCache::instance();
+    // 提权补扫助手模式：由 rescan_elevated 以管理员身份启动，补扫完成后直接退出
+    if let Some(code) = flashdir_core::elevated::run_helper_from_args() {
+        std::process::exit(code);
+    }
+
+    // 数据迁移必须在打开磁盘缓存数据库之前完成
+    let migration = flashdir_core::migration::run_startup_migrations();
+    if let Some(err) = &migration.error {
+        eprintln!("数据迁移失败: {}", err);
+    }
+    let _ = flashdir_core::disk_cache::DiskCache::instance();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
@@ -31,6 +41,15 @@ async fn main() {
             history: Mutex::new(commands::load_history_from_file_sync()),
         })
         .setup(|app| {
+            // 定时扫描：到期的计划以后台模式重扫，完成后发出 scheduled-scan-complete
+            tauri::async_runtime::spawn(flashdir_core::schedule::run_loop(Some(commands::events_for(app.handle()))));
+            // 空闲扫描：用户离开且 CPU 空闲时刷新收藏路径，完成后发出 idle-scan-complete
+            tauri::async_runtime::spawn(flashdir_core::idle_scan::run_loop(Some(commands::events_for(app.handle()))));
+            // 缓存预热：启动时与空闲后把常用的扫描结果载入内存，完成后发出 cache-prewarm-complete
+            tauri::async_runtime::spawn(flashdir_core::prewarm::run_loop(Some(commands::events_for(app.handle()))));
+            // 文件操作队列：继续上次退出时未完成的作业
+            flashdir_core::jobs::resume(Some(commands::events_for(app.handle())));
+
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 let idx = global_search::instance();
@@ -106,6 +125,12 @@ async fn main() {
             commands::scan_directory,
             commands::scan_directory_binary,
             commands::scan_directories_batch,
+            commands::scan_all_drives,
+            commands::count_directory,
+            commands::estimate_directory_size,
+            commands::scan_level,
+            commands::expand_directory,
+            commands::list_directory,
             commands::get_history_summary,
             commands::get_history,
             commands::clear_history,
@@ -115,19 +140,91 @@ async fn main() {
             commands::get_performance_summary,
             commands::get_disk_cache_stats,
             commands::clear_disk_cache,
+            commands::compact_disk_cache,
+            commands::list_cache_entries,
+            commands::delete_cache_entry,
+            commands::export_cache,
+            commands::import_cache,
+            commands::open_cached_scan,
+            commands::cancel_operation,
+            commands::enqueue_operation,
+            commands::get_operation_status,
+            commands::list_operations,
+            commands::get_exclusion_presets,
+            commands::get_settings,
+            commands::update_settings,
+            commands::get_report_style,
+            commands::compute_treemap,
+            commands::get_event_log,
             commands::get_memory_cache_stats,
+            commands::set_memory_cache_limits,
             commands::get_system_info,
             commands::is_admin,
             commands::check_mft_available,
             commands::get_scan_status,
+            commands::get_capabilities,
+            commands::get_migration_status,
+            commands::list_volumes,
             commands::open_path,
+            commands::reveal_in_file_manager,
+            commands::get_item_details,
+            commands::preview_file,
             commands::is_directory,
             commands::restart_as_admin,
+            commands::rescan_elevated,
             commands::analyze_dev_disk,
+            commands::get_tiering_report,
+            commands::analyze_file_age,
+            commands::get_small_file_report,
+            commands::get_git_repo_report,
+            commands::get_app_data_report,
+            commands::get_package_caches,
+            commands::run_safe_clean,
+            commands::analyze_recycle_bin,
+            commands::empty_recycle_bin,
+            commands::delete_to_trash,
+            commands::request_delete_token,
+            commands::delete_permanently,
+            commands::move_items,
+            commands::copy_items,
+            commands::rename_item,
+            commands::estimate_archive,
+            commands::compress_to_archive,
+            commands::add_schedule,
+            commands::list_schedules,
+            commands::remove_schedule,
+            commands::list_scan_profiles,
+            commands::load_scan_profile,
+            commands::save_scan_profile,
+            commands::delete_scan_profile,
+            commands::scan_with_profile,
+            commands::set_idle_scan,
+            commands::get_idle_scan_status,
+            commands::get_path_anomalies,
+            commands::get_permission_report,
+            commands::analyze_owners,
+            commands::hash_files,
+            commands::autocomplete_path,
+            commands::add_favorite,
+            commands::remove_favorite,
+            commands::list_favorites,
+            commands::get_frecent_paths,
+            commands::clear_frecent_paths,
+            commands::update_selection,
+            commands::get_selection_summary,
+            commands::get_selected_ids,
+            commands::clear_selection,
+            commands::delete_selected,
+            commands::export_selected,
+            commands::export_scan,
+            commands::generate_report,
+            commands::copy_to_clipboard,
             commands::save_snapshot,
             commands::list_snapshots,
             commands::compare_snapshots,
             commands::delete_snapshot,
+            commands::compare_scans,
+            commands::get_size_history,
             commands::compare_with_latest_snapshot,
             commands::global_search_status,
             commands::global_search_ensure_index,
@@ -135,6 +232,12 @@ async fn main() {
             commands::global_search_refresh,
             commands::global_search_add_scan,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_app, event| {
+            // 退出前写完排队中的磁盘缓存
+            if let tauri::RunEvent::Exit = event {
+                flashdir_core::disk_cache::DiskCache::instance().flush();
+            }
+        });
 }
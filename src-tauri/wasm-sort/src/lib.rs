@@ -13,6 +13,18 @@ pub struct WasmItem {
     pub size: i64,
     pub size_formatted: String,
     pub is_dir: bool,
+    /// 最后修改时间（Unix 秒，0 表示未知）
+    #[serde(default)]
+    pub modified: i64,
+    /// 创建时间（Unix 秒，0 表示未知）
+    #[serde(default)]
+    pub created: i64,
+    /// 目录子树中的文件数（文件为 0）
+    #[serde(default)]
+    pub file_count: u64,
+    /// 目录子树中的子目录数（文件为 0）
+    #[serde(default)]
+    pub dir_count: u64,
 }
 
 /// 排序配置
@@ -22,6 +34,10 @@ pub enum SortColumn {
     Name,
     Size,
     Type,
+    Modified,
+    Created,
+    /// 子树条目数（文件数 + 子目录数）
+    Items,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -44,10 +60,20 @@ pub fn sort_items(items_js: JsValue, column: &str, direction: &str) -> JsValue {
     let mut items: Vec<WasmItem> = serde_wasm_bindgen::from_value(items_js)
         .unwrap_or_default();
 
+    sort_item_vec(&mut items, column, direction);
+
+    serde_wasm_bindgen::to_value(&items).unwrap_or(JsValue::NULL)
+}
+
+/// 排序（纯 Rust 部分，供 JS 绑定与 fuzz 目标共用）
+pub fn sort_item_vec(items: &mut [WasmItem], column: &str, direction: &str) {
     let column = match column {
         "name" => SortColumn::Name,
         "size" => SortColumn::Size,
         "type" => SortColumn::Type,
+        "modified" => SortColumn::Modified,
+        "created" => SortColumn::Created,
+        "items" => SortColumn::Items,
         _ => SortColumn::Size,
     };
 
@@ -58,8 +84,6 @@ pub fn sort_items(items_js: JsValue, column: &str, direction: &str) -> JsValue {
     };
 
     items.sort_unstable_by(|a, b| compare_items(a, b, column, direction));
-
-    serde_wasm_bindgen::to_value(&items).unwrap_or(JsValue::NULL)
 }
 
 /// 过滤项目列表
@@ -72,17 +96,22 @@ pub fn filter_items(items_js: JsValue, keyword: &str) -> JsValue {
         return items_js;
     }
 
+    let filtered = filter_item_vec(items, keyword);
+
+    serde_wasm_bindgen::to_value(&filtered).unwrap_or(JsValue::NULL)
+}
+
+/// 按名称 / 路径过滤（大小写不敏感）
+pub fn filter_item_vec(items: Vec<WasmItem>, keyword: &str) -> Vec<WasmItem> {
     let lower_keyword = keyword.to_lowercase();
 
-    let filtered: Vec<WasmItem> = items
+    items
         .into_iter()
         .filter(|item| {
             item.name.to_lowercase().contains(&lower_keyword) ||
             item.path.to_lowercase().contains(&lower_keyword)
         })
-        .collect();
-
-    serde_wasm_bindgen::to_value(&filtered).unwrap_or(JsValue::NULL)
+        .collect()
 }
 
 /// 排序并过滤
@@ -103,6 +132,11 @@ pub fn get_extension_stats(items_js: JsValue) -> JsValue {
     let items: Vec<WasmItem> = serde_wasm_bindgen::from_value(items_js)
         .unwrap_or_default();
 
+    serde_wasm_bindgen::to_value(&extension_stats_vec(&items)).unwrap_or(JsValue::NULL)
+}
+
+/// 扩展名统计：(扩展名, (总大小, 文件数))，按大小降序
+pub fn extension_stats_vec(items: &[WasmItem]) -> Vec<(String, (i64, usize))> {
     use std::collections::HashMap;
 
     let mut stats: HashMap<String, (i64, usize)> = HashMap::new();
@@ -117,7 +151,7 @@ pub fn get_extension_stats(items_js: JsValue) -> JsValue {
 
             stats.entry(ext)
                 .and_modify(|(size, count)| {
-                    *size += item.size;
+                    *size = size.saturating_add(item.size);
                     *count += 1;
                 })
                 .or_insert((item.size, 1));
@@ -127,8 +161,7 @@ pub fn get_extension_stats(items_js: JsValue) -> JsValue {
     // 按大小排序
     let mut sorted_stats: Vec<_> = stats.into_iter().collect();
     sorted_stats.sort_by(|a, b| b.1 .0.cmp(&a.1 .0));
-
-    serde_wasm_bindgen::to_value(&sorted_stats).unwrap_or(JsValue::NULL)
+    sorted_stats
 }
 
 /// 获取 Top N 大文件
@@ -171,6 +204,15 @@ fn compare_items(
                 type_ord
             }
         }
+        SortColumn::Modified => {
+            a.modified.cmp(&b.modified)
+        }
+        SortColumn::Created => {
+            a.created.cmp(&b.created)
+        }
+        SortColumn::Items => {
+            (a.file_count + a.dir_count).cmp(&(b.file_count + b.dir_count))
+        }
     };
 
     match direction {
@@ -197,6 +239,10 @@ pub fn benchmark_sort(count: usize) -> f64 {
             size: (i * 1024) as i64,
             size_formatted: format!("{} KB", i),
             is_dir: false,
+            modified: 0,
+            created: 0,
+            file_count: 0,
+            dir_count: 0,
         })
         .collect();
 